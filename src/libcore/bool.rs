@@ -15,6 +15,7 @@ use bool;
 use cmp;
 use cmp::Eq;
 use option::{None, Option, Some};
+use str;
 
 /// Negation / inverse
 pub pure fn not(v: bool) -> bool { !v }
@@ -58,6 +59,25 @@ pub pure fn from_str(s: &str) -> Option<bool> {
     }
 }
 
+/**
+ * Parse a logic value from `s` as a person might type it
+ *
+ * Ignores case and surrounding whitespace, and accepts `yes`/`no`,
+ * `y`/`n`, `on`/`off` and `1`/`0` as well as `true`/`false`.
+ */
+pub pure fn from_str_loose(s: &str) -> Option<bool> {
+    let s = str::to_lower(str::trim(s));
+    if s == ~"true" || s == ~"yes" || s == ~"y" || s == ~"on" ||
+            s == ~"1" {
+        Some(true)
+    } else if s == ~"false" || s == ~"no" || s == ~"n" || s == ~"off" ||
+            s == ~"0" {
+        Some(false)
+    } else {
+        None
+    }
+}
+
 /// Convert `v` into a string
 pub pure fn to_str(v: bool) -> ~str { if v { ~"true" } else { ~"false" } }
 
@@ -86,6 +106,19 @@ pub fn test_bool_from_str() {
     }
 }
 
+#[test]
+pub fn test_bool_from_str_loose() {
+    do all_values |v| {
+        assert Some(v) == from_str_loose(bool::to_str(v))
+    }
+    assert from_str_loose(" Yes\n") == Some(true);
+    assert from_str_loose("N") == Some(false);
+    assert from_str_loose("off") == Some(false);
+    assert from_str_loose("1") == Some(true);
+    assert from_str_loose("") == None;
+    assert from_str_loose("yep") == None;
+}
+
 #[test]
 pub fn test_bool_to_str() {
     assert to_str(false) == ~"false";
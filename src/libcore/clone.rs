@@ -19,3 +19,43 @@ impl (): Clone {
     #[inline(always)]
     fn clone(&self) -> () { () }
 }
+
+macro_rules! clone_impl(
+    ($t:ty) => {
+        impl $t: Clone {
+            #[inline(always)]
+            fn clone(&self) -> $t { *self }
+        }
+    }
+)
+
+clone_impl!(int)
+clone_impl!(i8)
+clone_impl!(i16)
+clone_impl!(i32)
+clone_impl!(i64)
+
+clone_impl!(uint)
+clone_impl!(u8)
+clone_impl!(u16)
+clone_impl!(u32)
+clone_impl!(u64)
+
+clone_impl!(float)
+clone_impl!(f32)
+clone_impl!(f64)
+
+clone_impl!(bool)
+clone_impl!(char)
+
+impl<T: Clone> ~T: Clone {
+    /// Return a new owned box holding a clone of the contents
+    #[inline(always)]
+    fn clone(&self) -> ~T { ~(**self).clone() }
+}
+
+impl<T> @T: Clone {
+    /// Return a shallow copy of the managed box, sharing its contents
+    #[inline(always)]
+    fn clone(&self) -> @T { *self }
+}
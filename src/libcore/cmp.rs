@@ -55,6 +55,17 @@ pub trait Ord {
     pure fn gt(&self, other: &Self) -> bool;
 }
 
+/// The result of a three-way comparison
+#[deriving_eq]
+pub enum Ordering { Less, Equal, Greater }
+
+/// Compares two values, returning whether `v1` is less than, equal to or
+/// greater than `v2`
+#[inline(always)]
+pub pure fn cmp<T: Ord>(v1: &T, v2: &T) -> Ordering {
+    if (*v1).lt(v2) { Less } else if (*v2).lt(v1) { Greater } else { Equal }
+}
+
 #[inline(always)]
 pub pure fn lt<T: Ord>(v1: &T, v2: &T) -> bool {
     (*v1).lt(v2)
@@ -113,6 +113,43 @@ pub pure fn to_result<T, U>(eith: Either<T, U>)
     }
 }
 
+#[inline(always)]
+pub pure fn from_result<T, U>(res: Result<U, T>) -> Either<T, U> {
+    /*!
+     * Converts a result::t to either::t
+     *
+     * The inverse of `to_result`: an ok result becomes the "right" choice
+     * and a fail becomes the "left" choice.
+     */
+
+    match move res {
+      result::Ok(move r) => Right(move r),
+      result::Err(move l) => Left(move l)
+    }
+}
+
+#[inline(always)]
+pub pure fn map_left<T, U, V>(eith: Either<T, U>, f: fn(T) -> V)
+    -> Either<V, U> {
+    //! Applies a function to the left value, leaving a right untouched
+
+    match move eith {
+      Left(move l) => Left(f(move l)),
+      Right(move r) => Right(move r)
+    }
+}
+
+#[inline(always)]
+pub pure fn map_right<T, U, V>(eith: Either<T, U>, f: fn(U) -> V)
+    -> Either<T, V> {
+    //! Applies a function to the right value, leaving a left untouched
+
+    match move eith {
+      Left(move l) => Left(move l),
+      Right(move r) => Right(f(move r))
+    }
+}
+
 #[inline(always)]
 pub pure fn is_left<T, U>(eith: &Either<T, U>) -> bool {
     //! Checks whether the given value is a left
@@ -157,6 +194,14 @@ impl<T, U> Either<T, U> {
     #[inline(always)]
     fn to_result(self) -> Result<U, T> { to_result(self) }
 
+    #[inline(always)]
+    fn map_left<V>(self, f: fn(T) -> V) -> Either<V, U> { map_left(self, f) }
+
+    #[inline(always)]
+    fn map_right<V>(self, f: fn(U) -> V) -> Either<T, V> {
+        map_right(self, f)
+    }
+
     #[inline(always)]
     fn is_left(&self) -> bool { is_left(self) }
 
@@ -263,6 +308,34 @@ fn test_partition_empty() {
     assert (vec::len(rights) == 0u);
 }
 
+#[test]
+fn test_result_round_trip() {
+    let l: Either<~str, int> = Left(~"err");
+    let r: Either<~str, int> = Right(10);
+    assert l.to_result() == result::Err(~"err");
+    assert r.to_result() == result::Ok(10);
+    assert from_result(Left::<~str, int>(~"err").to_result()) ==
+        Left(~"err");
+    assert from_result(Right::<~str, int>(10).to_result()) == Right(10);
+}
+
+#[test]
+fn test_map_left_right() {
+    let l: Either<~str, int> = Left(~"a");
+    let r: Either<~str, int> = Right(10);
+    assert l.map_left(|s| ~[move s]) == Left(~[~"a"]);
+    assert r.map_left(|s| ~[move s]) == Right(10);
+
+    let l: Either<~str, int> = Left(~"a");
+    let r: Either<~str, int> = Right(10);
+    assert l.map_right(|i| i + 1) == Left(~"a");
+    assert r.map_right(|i| i + 1) == Right(11);
+
+    let len = Right::<~[int], ~[int]>(~[1, 2, 3]).map_left(|v| v.len())
+        .either(|n| *n, |v| v.len() * 2);
+    assert len == 6;
+}
+
 //
 // Local Variables:
 // mode: rust
@@ -479,6 +479,7 @@ pub mod ct {
 #[doc(hidden)]
 pub mod rt {
     use float;
+    use io::{Writer, WriterUtil};
     use str;
     use sys;
     use uint;
@@ -658,6 +659,9 @@ pub mod rt {
     pub pure fn have_flag(flags: u32, f: u32) -> bool {
         flags & f != 0
     }
+
+    // Called by write_fmt! once for each piece of the output
+    pub fn write_str(wr: Writer, s: &str) { wr.write_str(s) }
 }
 
 // Bulk of the tests are in src/test/run-pass/syntax-extension-fmt.rs
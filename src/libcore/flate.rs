@@ -84,7 +84,7 @@ fn test_flate_round_trip() {
     for 20.times {
         let mut in = ~[];
         for 2000.times {
-            in.push_all(r.choose(words));
+            in.push_all(*r.choose(words));
         }
         debug!("de/inflate of %u bytes of random word-sequences",
                in.len());
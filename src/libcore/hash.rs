@@ -74,14 +74,25 @@ pub trait Streaming {
     fn reset();
 }
 
+/**
+ * Feed the bytes of a value into a streaming hash function
+ *
+ * The bytes are passed along as `IterBytes` produces them, so the value
+ * never has to be flattened into a byte vector first.
+ */
+#[inline(always)]
+pub fn input_iter_bytes<A: IterBytes, S: Streaming>(s: S, a: &A) {
+    for a.iter_bytes(true) |bytes| {
+        s.input(bytes);
+    }
+}
+
 impl <A: IterBytes> A: Hash {
     #[inline(always)]
     pure fn hash_keyed(k0: u64, k1: u64) -> u64 {
         unsafe {
             let s = &State(k0, k1);
-            for self.iter_bytes(true) |bytes| {
-                s.input(bytes);
-            }
+            input_iter_bytes(s, &self);
             s.result_u64()
         }
     }
@@ -157,7 +168,14 @@ pub fn default_state() -> State {
     State(0,0)
 }
 
-struct SipState {
+/**
+ * The state of a SipHash 2-4 computation
+ *
+ * Bytes are fed in with `input` (or through `io::Writer`) in as many
+ * pieces as convenient; `result_u64` then gives the same hash as hashing
+ * all of the bytes at once, and does not disturb the state.
+ */
+pub struct SipState {
     k0: u64,
     k1: u64,
     mut length: uint, // how many bytes we've processed
@@ -169,8 +187,9 @@ struct SipState {
     mut ntail: uint,  // how many bytes in tail are valid
 }
 
+/// Create a SipHash state keyed with `key0` and `key1`
 #[inline(always)]
-fn SipState(key0: u64, key1: u64) -> SipState {
+pub fn SipState(key0: u64, key1: u64) -> SipState {
     let state = SipState {
         k0 : key0,
         k1 : key1,
@@ -473,6 +492,34 @@ pub fn test_siphash() {
     }
 }
 
+#[test]
+pub fn test_siphash_keys() {
+    let buf = ~[1u8, 2, 3, 4, 5, 6, 7, 8, 9];
+    let h = buf.hash_keyed(1, 2);
+    assert h == buf.hash_keyed(1, 2);
+    assert h != buf.hash_keyed(2, 1);
+    assert h != buf.hash_keyed(1, 3);
+    assert h != buf.hash();
+}
+
+#[test]
+pub fn test_input_iter_bytes() {
+    let val = (~"a string", 0xdeadbeef_u32, ~[1u16, 2, 3]);
+
+    let s = &SipState(5, 6);
+    input_iter_bytes(s, &val);
+    assert s.result_u64() == val.hash_keyed(5, 6);
+
+    // Feeding the same bytes piecewise gives the same result
+    let s2 = &SipState(5, 6);
+    for val.iter_bytes(true) |bytes| {
+        for vec::each(bytes) |b| {
+            s2.input([*b]);
+        }
+    }
+    assert s2.result_u64() == s.result_u64();
+}
+
 #[test] #[cfg(target_arch = "arm")]
 pub fn test_hash_uint() {
     let val = 0xdeadbeef_deadbeef_u64;
@@ -18,6 +18,7 @@ use to_bytes::IterBytes;
 /// Open addressing with linear probing.
 pub mod linear {
     use super::*;
+    use cast;
     use iter::BaseIter;
     use hash::Hash;
     use iter;
@@ -229,6 +230,23 @@ pub mod linear {
             value
         }
 
+        /// Makes room for one more entry, then hashes `k` and finds the
+        /// bucket it occupies or should be inserted into.
+        fn search_for_insert(&mut self, k: &K) -> (uint, SearchResult) {
+            if self.size >= self.resize_at {
+                self.expand();
+            }
+            let hash = k.hash_keyed(self.k0, self.k1) as uint;
+            (hash, self.bucket_for_key_with_hash(self.buckets, hash, k))
+        }
+
+        fn mut_value_for_bucket(&mut self, idx: uint) -> &self/mut V {
+            match self.buckets[idx] {
+                Some(ref mut bkt) => &mut bkt.value,
+                None => die!(~"LinearMap: internal logic error")
+            }
+        }
+
         fn search(&self, hash: uint,
                   op: fn(x: &Option<Bucket<K, V>>) -> bool) {
             let _ = self.bucket_sequence(hash, |i| op(&self.buckets[i]));
@@ -360,10 +378,14 @@ pub mod linear {
             old_value
         }
 
-        fn consume(&mut self, f: fn(K, V)) {
+        /// Consume the map, moving each key-value pair into `f`. Every
+        /// entry is moved out exactly once: if `f` fails part way through,
+        /// only the entries it has not been given yet are dropped.
+        fn consume(self, f: fn(K, V)) {
+            let mut map = self;
             let mut buckets = ~[];
-            self.buckets <-> buckets;
-            self.size = 0;
+            map.buckets <-> buckets;
+            map.size = 0;
 
             do vec::consume(buckets) |_, bucket| {
                 match bucket {
@@ -376,12 +398,99 @@ pub mod linear {
             }
         }
 
+        /// Visit all key-value pairs, allowing the values to be modified
+        /// in place
+        fn mutate_values(&mut self, f: fn(&K, &mut V) -> bool) {
+            for uint::range(0, self.buckets.len()) |i| {
+                match self.buckets[i] {
+                    Some(Bucket{key: ref key, value: ref mut value, _}) => {
+                        if !f(key, value) { return; }
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        /// Visit all values, allowing them to be modified in place
+        fn each_value_mut(&mut self, f: fn(&mut V) -> bool) {
+            self.mutate_values(|_, v| f(v))
+        }
+
         pure fn get(&self, k: &K) -> &self/V {
             match self.find(k) {
                 Some(v) => v,
                 None => die!(fmt!("No entry found for key: %?", k)),
             }
         }
+
+        /// Return the value for the key, first inserting `v` if the key
+        /// is not already in the map
+        fn find_or_insert(&mut self, k: K, v: V) -> &self/mut V {
+            let (hash, search) = self.search_for_insert(&k);
+            let idx = match search {
+                TableFull => die!(~"Internal logic error"),
+                FoundEntry(idx) => idx,
+                FoundHole(idx) => {
+                    self.buckets[idx] = Some(Bucket{hash: hash, key: k,
+                                                    value: v});
+                    self.size += 1;
+                    idx
+                }
+            };
+            unsafe {
+                // FIXME(#4903) requires a flow-sensitive borrow checker
+                cast::transmute_mut_region(self.mut_value_for_bucket(idx))
+            }
+        }
+
+        /// Return the value for the key, first inserting the result of
+        /// `f` if the key is not already in the map. `f` is only called
+        /// when the key is absent.
+        fn find_or_insert_with(&mut self, k: K,
+                               f: fn(&K) -> V) -> &self/mut V {
+            let (hash, search) = self.search_for_insert(&k);
+            let idx = match search {
+                TableFull => die!(~"Internal logic error"),
+                FoundEntry(idx) => idx,
+                FoundHole(idx) => {
+                    let v = f(&k);
+                    self.buckets[idx] = Some(Bucket{hash: hash, key: k,
+                                                    value: v});
+                    self.size += 1;
+                    idx
+                }
+            };
+            unsafe {
+                // FIXME(#4903) requires a flow-sensitive borrow checker
+                cast::transmute_mut_region(self.mut_value_for_bucket(idx))
+            }
+        }
+
+        /// Insert a key-value pair into the map. If the key is already
+        /// present, its value is replaced by `combine(key, old, v)`
+        /// instead. Return true if the key did not already exist in the
+        /// map.
+        fn update_with_key(&mut self, k: K, v: V,
+                           combine: fn(&K, V, V) -> V) -> bool {
+            let (hash, search) = self.search_for_insert(&k);
+            match search {
+                TableFull => die!(~"Internal logic error"),
+                FoundHole(idx) => {
+                    self.buckets[idx] = Some(Bucket{hash: hash, key: k,
+                                                    value: v});
+                    self.size += 1;
+                    true
+                }
+                FoundEntry(idx) => {
+                    let Bucket{hash: hash, key: key, value: old} =
+                        self.buckets[idx].swap_unwrap();
+                    let value = combine(&key, old, v);
+                    self.buckets[idx] = Some(Bucket{hash: hash, key: key,
+                                                    value: value});
+                    false
+                }
+            }
+        }
     }
 
     impl<K: Hash IterBytes Eq, V: Eq> LinearMap<K, V>: Eq {
@@ -450,12 +559,14 @@ pub mod linear {
         /// Return true if the set has no elements in common with `other`.
         /// This is equivalent to checking for an empty intersection.
         pure fn is_disjoint(&self, other: &LinearSet<T>) -> bool {
-            iter::all(self, |v| !other.contains(v))
+            let (small, large) = smaller_first(self, other);
+            iter::all(small, |v| !large.contains(v))
         }
 
         /// Return true if the set is a subset of another
         pure fn is_subset(&self, other: &LinearSet<T>) -> bool {
-            iter::all(self, |v| other.contains(v))
+            self.len() <= other.len() &&
+                iter::all(self, |v| other.contains(v))
         }
 
         /// Return true if the set is a superset of another
@@ -475,14 +586,19 @@ pub mod linear {
         /// Visit the values representing the symmetric difference
         pure fn symmetric_difference(&self, other: &LinearSet<T>,
                                      f: fn(&T) -> bool) {
-            self.difference(other, f);
+            let mut done = false;
+            for self.difference(other) |v| {
+                if !f(v) { done = true; break; }
+            }
+            if done { return; }
             other.difference(self, f);
         }
 
         /// Visit the values representing the intersection
         pure fn intersection(&self, other: &LinearSet<T>, f: fn(&T) -> bool) {
-            for self.each |v| {
-                if other.contains(v) {
+            let (small, large) = smaller_first(self, other);
+            for small.each |v| {
+                if large.contains(v) {
                     if !f(v) { return }
                 }
             }
@@ -506,6 +622,14 @@ pub mod linear {
         /// Create an empty LinearSet
         static fn new() -> LinearSet<T> { LinearSet{map: LinearMap::new()} }
     }
+
+    /// Orders two sets so that the smaller one comes first, for the
+    /// operations that only need to walk one of them
+    pure fn smaller_first<T: Hash IterBytes Eq>(
+        a: &r/LinearSet<T>,
+        b: &r/LinearSet<T>) -> (&r/LinearSet<T>, &r/LinearSet<T>) {
+        if a.len() <= b.len() { (a, b) } else { (b, a) }
+    }
 }
 
 #[test]
@@ -514,7 +638,10 @@ mod test_map {
     use option::{None, Some};
     use hashmap::linear::LinearMap;
     use hashmap::linear;
+    use task;
     use uint;
+    use util::{DropCount, DropCounter};
+    use vec;
 
     #[test]
     pub fn inserts() {
@@ -590,12 +717,80 @@ mod test_map {
         do m.consume |k, v| {
             m2.insert(k, v);
         }
-        assert m.len() == 0;
         assert m2.len() == 2;
         assert m2.get(&1) == &2;
         assert m2.get(&2) == &3;
     }
 
+    fn drop_counter_map(count: &DropCount,
+                        n: uint) -> LinearMap<uint, DropCounter> {
+        let mut m = LinearMap::new();
+        for uint::range(0, n) |i| {
+            m.insert(i, count.counter(i));
+        }
+        m
+    }
+
+    #[test]
+    pub fn consume_drops_once() {
+        let count = DropCount();
+        let m = drop_counter_map(&count, 100);
+
+        let mut seen = vec::from_elem(100, false);
+        do m.consume |k, _v| {
+            assert !seen[k];
+            seen[k] = true;
+        }
+        assert count.get() == 100;
+        assert vec::all(seen, |b| *b);
+    }
+
+    #[test]
+    #[ignore(cfg(windows))]
+    pub fn consume_fail_drops_once() {
+        let count = DropCount();
+        let count2 = count.clone();
+        let result = do task::try |move count2| {
+            let m = drop_counter_map(&count2, 100);
+            let mut seen = 0u;
+            do m.consume |_k, _v| {
+                seen += 1;
+                if seen == 10 { die!(); }
+            }
+        };
+        assert result.is_err();
+        assert count.get() == 100;
+    }
+
+    #[test]
+    pub fn mutate_values() {
+        let mut m = LinearMap::new();
+        for uint::range(0, 64) |i| {
+            m.insert(i, i);
+        }
+        for m.mutate_values |k, v| {
+            *v += *k;
+        }
+        for m.each_value_mut |v| {
+            *v += 1;
+        }
+        for uint::range(0, 64) |i| {
+            assert *m.get(&i) == i * 2 + 1;
+        }
+
+        let mut visited = 0;
+        for m.each_value_mut |v| {
+            *v = 0;
+            visited += 1;
+            if visited == 10 { break; }
+        }
+        let mut zeroes = 0;
+        for m.each_value |v| {
+            if *v == 0 { zeroes += 1; }
+        }
+        assert zeroes == 10;
+    }
+
     #[test]
     pub fn iterate() {
         let mut m = linear::linear_map_with_capacity(4);
@@ -639,6 +834,15 @@ mod test_map {
         assert m1 == m2;
     }
 
+    #[test]
+    pub fn test_random_keys() {
+        // Every map is keyed separately, so that the layout of one map
+        // tells an attacker nothing about another
+        let m1 = LinearMap::new::<uint, uint>();
+        let m2 = LinearMap::new::<uint, uint>();
+        assert (m1.k0, m1.k1) != (m2.k0, m2.k1);
+    }
+
     #[test]
     pub fn test_expand() {
         let mut m = LinearMap::new();
@@ -656,11 +860,79 @@ mod test_map {
         assert m.len() == i;
         assert !m.is_empty();
     }
+
+    #[test]
+    pub fn test_find_or_insert() {
+        let mut m = LinearMap::new();
+        assert *m.find_or_insert(1, 2) == 2;
+        assert *m.find_or_insert(1, 3) == 2;
+        *m.find_or_insert(1, 0) += 5;
+        assert *m.get(&1) == 7;
+        assert m.len() == 1;
+
+        let mut called = false;
+        assert *m.find_or_insert_with(1, |_| { called = true; 0 }) == 7;
+        assert !called;
+        assert *m.find_or_insert_with(2, |k| *k * 10) == 20;
+        assert m.len() == 2;
+    }
+
+    #[test]
+    pub fn test_find_or_insert_resize() {
+        let mut m = linear::linear_map_with_capacity(4);
+        let mut i = 0u;
+        while m.size < m.resize_at {
+            m.insert(i, i);
+            i += 1;
+        }
+        let old_capacity = m.buckets.len();
+        {
+            let v = m.find_or_insert(i, 0);
+            *v = 100;
+        }
+        assert m.buckets.len() > old_capacity;
+        assert *m.get(&i) == 100;
+        for uint::range(0, i) |j| {
+            assert *m.get(&j) == j;
+        }
+    }
+
+    #[test]
+    pub fn test_update_with_key() {
+        let mut m = LinearMap::new();
+        assert m.update_with_key(1, 2, |_, old, new| old + new);
+        assert !m.update_with_key(1, 3, |k, old, new| *k + old * new);
+        assert *m.get(&1) == 7;
+        assert m.len() == 1;
+    }
+
+    #[test]
+    pub fn test_word_count() {
+        let words = 1000;
+        let rounds = 5;
+
+        let mut counts = LinearMap::new();
+        let mut totals = LinearMap::new();
+        for uint::range(0, words * rounds) |i| {
+            let word = fmt!("word%u", i % words);
+            *counts.find_or_insert(copy word, 0u) += 1;
+            totals.update_with_key(word, 1u, |_, old, new| old + new);
+        }
+
+        assert counts.len() == words;
+        assert totals.len() == words;
+        for uint::range(0, words) |i| {
+            let word = fmt!("word%u", i);
+            assert *counts.get(&word) == rounds;
+            assert *totals.get(&word) == rounds;
+        }
+    }
 }
 
 #[test]
 mod test_set {
     use super::*;
+    use str;
     use vec;
 
     #[test]
@@ -822,4 +1094,92 @@ mod test_set {
         }
         assert i == expected.len();
     }
+
+    fn str_set(words: &[&str]) -> linear::LinearSet<~str> {
+        let mut set = linear::LinearSet::new();
+        for words.each |w| {
+            set.insert(str::from_slice(*w));
+        }
+        set
+    }
+
+    fn collect(f: fn(fn(&~str) -> bool)) -> ~[~str] {
+        let mut v = ~[];
+        for f |x| {
+            v.push(copy *x);
+        }
+        v
+    }
+
+    fn same_elements(actual: ~[~str], expected: &[&str]) -> bool {
+        actual.len() == expected.len() &&
+            vec::all(expected, |e| {
+                vec::contains(actual, &str::from_slice(*e))
+            })
+    }
+
+    #[test]
+    fn test_string_sets() {
+        let empty = str_set([]);
+        let fruit = str_set(["apple", "banana", "cherry", "date"]);
+        let red = str_set(["apple", "cherry", "brick"]);
+        let stone = str_set(["cherry", "date"]);
+        let metal = str_set(["iron", "tin"]);
+
+        // Overlapping sets
+        assert same_elements(collect(|f| fruit.union(&red, f)),
+                             ["apple", "banana", "cherry", "date", "brick"]);
+        assert same_elements(collect(|f| fruit.intersection(&red, f)),
+                             ["apple", "cherry"]);
+        assert same_elements(collect(|f| red.intersection(&fruit, f)),
+                             ["apple", "cherry"]);
+        assert same_elements(collect(|f| fruit.difference(&red, f)),
+                             ["banana", "date"]);
+        assert same_elements(
+            collect(|f| fruit.symmetric_difference(&red, f)),
+            ["banana", "date", "brick"]);
+        assert !fruit.is_disjoint(&red);
+        assert !fruit.is_subset(&red) && !fruit.is_superset(&red);
+
+        // Disjoint sets
+        assert fruit.is_disjoint(&metal) && metal.is_disjoint(&fruit);
+        assert vec::is_empty(collect(|f| fruit.intersection(&metal, f)));
+        assert same_elements(collect(|f| metal.difference(&fruit, f)),
+                             ["iron", "tin"]);
+
+        // Nested sets
+        assert stone.is_subset(&fruit) && fruit.is_superset(&stone);
+        assert !fruit.is_subset(&stone) && !stone.is_superset(&fruit);
+        assert vec::is_empty(collect(|f| stone.difference(&fruit, f)));
+        assert same_elements(collect(|f| fruit.intersection(&stone, f)),
+                             ["cherry", "date"]);
+        assert fruit.is_subset(&fruit) && fruit.is_superset(&fruit);
+
+        // The empty set
+        assert empty.is_subset(&fruit) && empty.is_subset(&empty);
+        assert fruit.is_superset(&empty);
+        assert empty.is_disjoint(&fruit) && fruit.is_disjoint(&empty);
+        assert empty.is_disjoint(&empty);
+        assert vec::is_empty(collect(|f| empty.intersection(&fruit, f)));
+        assert vec::is_empty(collect(|f| empty.difference(&fruit, f)));
+        assert same_elements(collect(|f| fruit.difference(&empty, f)),
+                             ["apple", "banana", "cherry", "date"]);
+        assert same_elements(collect(|f| empty.union(&metal, f)),
+                             ["iron", "tin"]);
+        assert same_elements(
+            collect(|f| empty.symmetric_difference(&metal, f)),
+            ["iron", "tin"]);
+    }
+
+    #[test]
+    fn test_symmetric_difference_stops() {
+        let a = str_set(["a", "b", "c"]);
+        let b = str_set(["x", "y", "z"]);
+        let mut n = 0;
+        for a.symmetric_difference(&b) |_| {
+            n += 1;
+            if n == 2 { break; }
+        }
+        assert n == 2;
+    }
 }
@@ -16,6 +16,7 @@ Basic input/output
 
 use result::Result;
 
+use bool;
 use cmp::Eq;
 use dvec::DVec;
 use int;
@@ -40,6 +41,8 @@ extern mod rustrt {
     unsafe fn rust_get_stdin() -> *libc::FILE;
     unsafe fn rust_get_stdout() -> *libc::FILE;
     unsafe fn rust_get_stderr() -> *libc::FILE;
+    unsafe fn rust_get_echo(fd: c_int) -> c_int;
+    unsafe fn rust_set_echo(fd: c_int, on: c_int) -> c_int;
 }
 
 // Reading
@@ -81,6 +84,10 @@ pub trait ReaderUtil {
     /// Read up until the first '\n' char (which is not returned), or EOF.
     fn read_line(&self) -> ~str;
 
+    /// Read up until the first occurrence of the byte `c`, or EOF. The
+    /// byte itself is consumed, and is only returned if `include` is true.
+    fn read_until(&self, c: u8, include: bool) -> ~str;
+
     /// Read n utf-8 encoded chars.
     fn read_chars(&self, n: uint) -> ~[char];
 
@@ -102,6 +109,10 @@ pub trait ReaderUtil {
     /// Iterate over every line until the iterator breaks or EOF.
     fn each_line(&self, it: fn(&str) -> bool);
 
+    // The integer readers below fail if the stream ends before all of the
+    // bytes have been read. Use the `try_read_*_n` methods to detect a
+    // short read instead.
+
     /// Read n (between 1 and 8) little-endian unsigned integer bytes.
     fn read_le_uint_n(&self, nbytes: uint) -> u64;
 
@@ -114,6 +125,22 @@ pub trait ReaderUtil {
     /// Read n (between 1 and 8) big-endian signed integer bytes.
     fn read_be_int_n(&self, nbytes: uint) -> i64;
 
+    /// Read n (between 1 and 8) little-endian unsigned integer bytes, or
+    /// return None if the stream ends first.
+    fn try_read_le_uint_n(&self, nbytes: uint) -> Option<u64>;
+
+    /// Read n (between 1 and 8) little-endian signed integer bytes, or
+    /// return None if the stream ends first.
+    fn try_read_le_int_n(&self, nbytes: uint) -> Option<i64>;
+
+    /// Read n (between 1 and 8) big-endian unsigned integer bytes, or
+    /// return None if the stream ends first.
+    fn try_read_be_uint_n(&self, nbytes: uint) -> Option<u64>;
+
+    /// Read n (between 1 and 8) big-endian signed integer bytes, or
+    /// return None if the stream ends first.
+    fn try_read_be_int_n(&self, nbytes: uint) -> Option<i64>;
+
     /// Read a little-endian uint (number of bytes depends on system).
     fn read_le_uint(&self) -> uint;
 
@@ -182,10 +209,18 @@ impl<T: Reader> T : ReaderUtil {
     }
 
     fn read_line(&self) -> ~str {
+        self.read_until(10, false)
+    }
+
+    fn read_until(&self, c: u8, include: bool) -> ~str {
         let mut bytes = ~[];
         loop {
             let ch = self.read_byte();
-            if ch == -1 || ch == 10 { break; }
+            if ch == -1 { break; }
+            if ch == c as int {
+                if include { bytes.push(c); }
+                break;
+            }
             bytes.push(ch as u8);
         }
         str::from_bytes(bytes)
@@ -294,37 +329,57 @@ impl<T: Reader> T : ReaderUtil {
         }
     }
 
-    // FIXME int reading methods need to deal with eof - issue #2004
+    // The int reading methods fail at eof; the try_ ones return None
 
     fn read_le_uint_n(&self, nbytes: uint) -> u64 {
+        expect_int(self.try_read_le_uint_n(nbytes))
+    }
+
+    fn read_le_int_n(&self, nbytes: uint) -> i64 {
+        extend_sign(self.read_le_uint_n(nbytes), nbytes)
+    }
+
+    fn read_be_uint_n(&self, nbytes: uint) -> u64 {
+        expect_int(self.try_read_be_uint_n(nbytes))
+    }
+
+    fn read_be_int_n(&self, nbytes: uint) -> i64 {
+        extend_sign(self.read_be_uint_n(nbytes), nbytes)
+    }
+
+    fn try_read_le_uint_n(&self, nbytes: uint) -> Option<u64> {
         assert nbytes > 0 && nbytes <= 8;
 
         let mut val = 0u64, pos = 0, i = nbytes;
         while i > 0 {
-            val += (self.read_u8() as u64) << pos;
+            let b = self.read_byte();
+            if b < 0 { return None; }
+            val += (b as u64) << pos;
             pos += 8;
             i -= 1;
         }
-        val
+        Some(val)
     }
 
-    fn read_le_int_n(&self, nbytes: uint) -> i64 {
-        extend_sign(self.read_le_uint_n(nbytes), nbytes)
+    fn try_read_le_int_n(&self, nbytes: uint) -> Option<i64> {
+        self.try_read_le_uint_n(nbytes).map(|v| extend_sign(*v, nbytes))
     }
 
-    fn read_be_uint_n(&self, nbytes: uint) -> u64 {
+    fn try_read_be_uint_n(&self, nbytes: uint) -> Option<u64> {
         assert nbytes > 0 && nbytes <= 8;
 
         let mut val = 0u64, i = nbytes;
         while i > 0 {
             i -= 1;
-            val += (self.read_u8() as u64) << i * 8;
+            let b = self.read_byte();
+            if b < 0 { return None; }
+            val += (b as u64) << i * 8;
         }
-        val
+        Some(val)
     }
 
-    fn read_be_int_n(&self, nbytes: uint) -> i64 {
-        extend_sign(self.read_be_uint_n(nbytes), nbytes)
+    fn try_read_be_int_n(&self, nbytes: uint) -> Option<i64> {
+        self.try_read_be_uint_n(nbytes).map(|v| extend_sign(*v, nbytes))
     }
 
     fn read_le_uint(&self) -> uint {
@@ -392,11 +447,11 @@ impl<T: Reader> T : ReaderUtil {
     }
 
     fn read_u8(&self) -> u8 {
-        self.read_byte() as u8
+        self.read_le_uint_n(1) as u8
     }
 
     fn read_i8(&self) -> i8 {
-        self.read_byte() as i8
+        self.read_le_uint_n(1) as i8
     }
 }
 
@@ -405,6 +460,13 @@ fn extend_sign(val: u64, nbytes: uint) -> i64 {
     (val << shift) as i64 >> shift
 }
 
+fn expect_int(val: Option<u64>) -> u64 {
+    match val {
+        Some(val) => val,
+        None => die!(~"unexpected end of stream while reading an integer")
+    }
+}
+
 // Reader implementations
 
 fn convert_whence(whence: SeekStyle) -> i32 {
@@ -521,6 +583,125 @@ pub fn file_reader(path: &Path) -> Result<@Reader, ~str> {
 }
 
 
+// Buffered readers
+
+const buffered_reader_size: uint = 65536;
+
+/**
+ * A reader that reads from an underlying reader in large chunks
+ *
+ * Reading a byte or a line at a time from a `BufferedReader` only goes to
+ * the underlying reader once every 64KB. Its own `read_until`,
+ * `read_line` and `each_line` scan the buffer directly, so they are only
+ * used when the reader is not hidden behind an `@Reader`.
+ */
+pub struct BufferedReader<R> {
+    priv rdr: R,
+    priv buf: ~[mut u8],
+    priv mut pos: uint,
+    priv mut len: uint,
+}
+
+/// Wrap a reader in a `BufferedReader` with a 64KB buffer
+pub fn BufferedReader<R: Reader>(rdr: R) -> BufferedReader<R> {
+    BufferedReader {
+        rdr: move rdr,
+        buf: vec::cast_to_mut(vec::from_elem(buffered_reader_size, 0u8)),
+        pos: 0,
+        len: 0,
+    }
+}
+
+impl<R: Reader> BufferedReader<R>: Reader {
+    fn read(&self, bytes: &[mut u8], len: uint) -> uint {
+        let mut count = 0;
+        while count < len && self.fill() {
+            let n = uint::min(len - count, self.len - self.pos);
+            vec::bytes::copy_memory(
+                vec::mut_view(bytes, count, len),
+                vec::const_view(self.buf, self.pos, self.pos + n), n);
+            self.pos += n;
+            count += n;
+        }
+        count
+    }
+    fn read_byte(&self) -> int {
+        if !self.fill() { return -1; }
+        let b = self.buf[self.pos];
+        self.pos += 1;
+        b as int
+    }
+    fn eof(&self) -> bool { !self.fill() }
+    fn seek(&self, offset: int, whence: SeekStyle) {
+        // The underlying reader is ahead by whatever is still buffered
+        let offset = match whence {
+            SeekCur => offset - (self.len - self.pos) as int,
+            SeekSet | SeekEnd => offset
+        };
+        self.pos = 0;
+        self.len = 0;
+        self.rdr.seek(offset, whence);
+    }
+    fn tell(&self) -> uint { self.rdr.tell() - (self.len - self.pos) }
+}
+
+pub impl<R: Reader> BufferedReader<R> {
+    /// Read up until the first occurrence of the byte `c`, or EOF. The
+    /// byte itself is consumed, and is only returned if `include` is true.
+    fn read_until(&self, c: u8, include: bool) -> ~str {
+        let mut bytes = ~[];
+        while self.fill() {
+            let start = self.pos;
+            let mut end = start;
+            while end < self.len && self.buf[end] != c { end += 1; }
+            if end == self.len {
+                bytes.push_all(vec::const_view(self.buf, start, end));
+                self.pos = end;
+            } else {
+                let stop = if include { end + 1 } else { end };
+                bytes.push_all(vec::const_view(self.buf, start, stop));
+                self.pos = end + 1;
+                break;
+            }
+        }
+        str::from_bytes(bytes)
+    }
+
+    /// Read up until the first '\n' char (which is not returned), or EOF.
+    fn read_line(&self) -> ~str { self.read_until(10, false) }
+
+    /**
+     * Iterate over every line until the iterator breaks or EOF
+     *
+     * A last line without a trailing '\n' is still visited, but a
+     * trailing '\n' does not produce an extra empty line.
+     */
+    fn each_line(&self, it: fn(&str) -> bool) {
+        while !self.eof() {
+            if !it(self.read_line()) { break; }
+        }
+    }
+}
+
+priv impl<R: Reader> BufferedReader<R> {
+    // Refill the buffer if it has been used up. Return false at EOF.
+    fn fill(&self) -> bool {
+        if self.pos < self.len { return true; }
+        self.pos = 0;
+        self.len = self.rdr.read(self.buf, self.buf.len());
+        self.len > 0
+    }
+}
+
+/// Open a file for reading through a `BufferedReader`
+pub fn buffered_file_reader(path: &Path)
+    -> Result<BufferedReader<@Reader>, ~str> {
+    match file_reader(path) {
+        result::Ok(rdr) => result::Ok(BufferedReader(rdr)),
+        result::Err(move e) => result::Err(move e)
+    }
+}
+
 // Byte readers
 pub struct BytesReader {
     bytes: &[u8],
@@ -991,6 +1172,123 @@ pub fn stderr() -> Writer { fd_writer(libc::STDERR_FILENO as c_int, false) }
 pub fn print(s: &str) { stdout().write_str(s); }
 pub fn println(s: &str) { stdout().write_line(s); }
 
+// Interactive input
+
+/**
+ * Prints `msg` to stderr and reads a line from stdin
+ *
+ * The line is returned without its line terminator. Returns `None` if
+ * stdin is at end of file.
+ */
+pub fn prompt(msg: &str) -> Option<~str> {
+    prompt_from(stdin(), stderr(), msg)
+}
+
+/**
+ * Like `prompt`, but doesn't echo what is typed
+ *
+ * If stdin is a terminal then echoing is turned off while the line is
+ * read, and restored afterwards even if reading fails. If stdin isn't a
+ * terminal the line is read as usual.
+ */
+pub fn prompt_password(msg: &str) -> Option<~str> {
+    let stdin_fd = libc::STDIN_FILENO as fd_t;
+    prompt_password_from(stdin(), stderr(), stdin_fd as EchoControl, msg)
+}
+
+/**
+ * Asks a yes or no question on stderr, returning the answer
+ *
+ * Answers are parsed with `bool::from_str_loose`, and the question is
+ * asked again if the answer can't be understood. An empty answer or end
+ * of file gives `default`.
+ */
+pub fn confirm(msg: &str, default: bool) -> bool {
+    confirm_from(stdin(), stderr(), msg, default)
+}
+
+// Turns terminal echo on and off. Abstracted so that the logic for
+// restoring the terminal can be tested without one.
+trait EchoControl {
+    // Returns whether input is echoed, or None if this isn't a terminal
+    fn get_echo(&self) -> Option<bool>;
+    fn set_echo(&self, on: bool);
+}
+
+impl fd_t: EchoControl {
+    fn get_echo(&self) -> Option<bool> {
+        match unsafe { rustrt::rust_get_echo(*self) } {
+            -1 => None,
+            on => Some(on != 0)
+        }
+    }
+    fn set_echo(&self, on: bool) {
+        unsafe {
+            rustrt::rust_set_echo(*self, if on { 1 } else { 0 });
+        }
+    }
+}
+
+// Turns echo back on when dropped, including during unwinding
+struct EchoRestorer {
+    term: EchoControl,
+    drop { self.term.set_echo(true); }
+}
+
+// Like read_line, but distinguishes end of file from an empty line
+fn read_line_or_eof(rdr: Reader) -> Option<~str> {
+    let mut bytes = ~[];
+    loop {
+        let b = rdr.read_byte();
+        if b == -1 {
+            if bytes.is_empty() { return None; }
+            break;
+        }
+        if b == '\n' as int { break; }
+        bytes.push(b as u8);
+    }
+    if !bytes.is_empty() && bytes[bytes.len() - 1] == '\r' as u8 {
+        bytes.pop();
+    }
+    Some(str::from_bytes(bytes))
+}
+
+fn prompt_from(rdr: Reader, wr: Writer, msg: &str) -> Option<~str> {
+    wr.write_str(msg);
+    wr.flush();
+    read_line_or_eof(rdr)
+}
+
+fn prompt_password_from(rdr: Reader, wr: Writer, term: EchoControl,
+                        msg: &str) -> Option<~str> {
+    match term.get_echo() {
+        Some(true) => {
+            term.set_echo(false);
+            let _restore = EchoRestorer { term: term };
+            let line = prompt_from(rdr, wr, msg);
+            // The user's newline wasn't echoed either
+            wr.write_char('\n');
+            line
+        }
+        _ => prompt_from(rdr, wr, msg)
+    }
+}
+
+fn confirm_from(rdr: Reader, wr: Writer, msg: &str, default: bool) -> bool {
+    let hint = if default { " [Y/n] " } else { " [y/N] " };
+    let msg = str::from_slice(msg) + hint;
+    loop {
+        match prompt_from(rdr, wr, msg) {
+            None => return default,
+            Some(ref line) if str::is_whitespace(*line) => return default,
+            Some(ref line) => match bool::from_str_loose(*line) {
+                Some(answer) => return answer,
+                None => wr.write_line("Please answer y or n.")
+            }
+        }
+    }
+}
+
 pub struct BytesWriter {
     bytes: DVec<u8>,
     mut pos: uint,
@@ -1007,6 +1305,9 @@ impl BytesWriter: Writer {
             vec::reserve(&mut bytes, count);
             unsafe { vec::raw::set_len(&mut bytes, count); }
 
+            // Fill the gap left by seeking past the end
+            for uint::range(bytes_len, self.pos) |i| { bytes[i] = 0; }
+
             {
                 let view = vec::mut_view(bytes, self.pos, count);
                 vec::bytes::copy_memory(view, v, v_len);
@@ -1018,19 +1319,41 @@ impl BytesWriter: Writer {
         }
     }
     fn seek(&self, offset: int, whence: SeekStyle) {
-        let pos = self.pos;
-        let len = self.bytes.len();
-        self.pos = seek_in_buf(offset, pos, len, whence);
+        // Unlike seek_in_buf, this allows seeking past the end
+        let pos = match whence {
+            SeekSet => offset,
+            SeekCur => self.pos as int + offset,
+            SeekEnd => self.bytes.len() as int + offset
+        };
+        self.pos = if pos < 0 { 0 } else { pos as uint };
     }
     fn tell(&self) -> uint { self.pos }
     fn flush(&self) -> int { 0 }
     fn get_type(&self) -> WriterType { File }
 }
 
+/**
+ * A writer into a growable byte vector
+ *
+ * Writing inside the bytes written so far overwrites them in place.
+ * Seeking past the end is allowed, and the next write zero-fills the gap.
+ */
 pub pure fn BytesWriter() -> BytesWriter {
     BytesWriter { bytes: DVec(), mut pos: 0u }
 }
 
+pub impl BytesWriter {
+    /// Return the number of bytes written so far, which is the position
+    /// of the end rather than the current position
+    fn bytes_len(&self) -> uint { self.bytes.len() }
+
+    /// Take the bytes written so far without copying them
+    fn unwrap(self) -> ~[u8] {
+        let BytesWriter { bytes: move bytes, _ } = move self;
+        bytes.unwrap()
+    }
+}
+
 pub pure fn with_bytes_writer(f: fn(Writer)) -> ~[u8] {
     let wr = @BytesWriter();
     f(wr as Writer);
@@ -1182,13 +1505,18 @@ pub mod fsync {
 #[cfg(test)]
 mod tests {
     use debug;
+    use i16;
     use i32;
+    use i64;
     use io::{BytesWriter, SeekCur, SeekEnd, SeekSet};
     use io;
     use path::Path;
+    use private;
     use result;
     use str;
+    use task;
     use u64;
+    use uint;
     use vec;
 
     #[test]
@@ -1304,6 +1632,88 @@ mod tests {
         file.read(buf, 4); // this should succeed because buf is big enough
     }
 
+    fn buffered_line(i: uint) -> ~str {
+        fmt!("line %u: %s", i, str::repeat("x", i % 100))
+    }
+
+    #[test]
+    fn test_buffered_each_line_large_file() {
+        let path = &Path("tmp/lib-io-test-buffered-each-line.tmp");
+        let nlines = 60000u;
+        {
+            let out = io::file_writer(path, [io::Create, io::Truncate]).get();
+            for uint::range(0, nlines) |i| {
+                out.write_str(buffered_line(i));
+                // The last line is left unterminated
+                if i + 1 < nlines { out.write_char('\n'); }
+            }
+        }
+
+        let rdr = io::buffered_file_reader(path).get();
+        let mut count = 0u;
+        for rdr.each_line |line| {
+            assert line == buffered_line(count);
+            count += 1;
+        }
+        assert count == nlines;
+        assert rdr.eof();
+        assert rdr.read_byte() == -1;
+
+        // Both paths agree, except that the unbuffered reader can't tell
+        // it is at EOF until it has read past the end
+        let unbuffered = io::file_reader(path).get();
+        let rdr = io::buffered_file_reader(path).get();
+        while !rdr.eof() {
+            assert rdr.read_line() == unbuffered.read_line();
+        }
+    }
+
+    #[test]
+    fn test_buffered_read_until() {
+        do io::with_str_reader("a,bc,,d") |inp| {
+            let rdr = io::BufferedReader(inp);
+            assert rdr.read_until(',' as u8, true) == ~"a,";
+            assert rdr.read_until(',' as u8, false) == ~"bc";
+            assert rdr.read_until(',' as u8, false) == ~"";
+            assert !rdr.eof();
+            assert rdr.read_until(',' as u8, true) == ~"d";
+            assert rdr.eof();
+            assert rdr.read_until(',' as u8, true) == ~"";
+        }
+        do io::with_str_reader("one\ntwo\n\nthree\n") |inp| {
+            let rdr = io::BufferedReader(inp);
+            let mut lines = ~[];
+            for rdr.each_line |line| { lines.push(str::from_slice(line)); }
+            assert lines == ~[~"one", ~"two", ~"", ~"three"];
+        }
+    }
+
+    #[test]
+    fn test_buffered_read_seek_tell() {
+        let bytes = vec::from_fn(200000, |i| (i % 251) as u8);
+        do io::with_bytes_reader(bytes) |inp| {
+            let rdr = io::BufferedReader(inp);
+            assert rdr.read_byte() == 0;
+            assert rdr.tell() == 1;
+
+            // A read spanning several refills of the buffer
+            let buf = rdr.read_bytes(150000);
+            assert buf.len() == 150000;
+            assert buf == vec::slice(bytes, 1, 150001);
+            assert rdr.tell() == 150001;
+
+            rdr.seek(-1, SeekCur);
+            assert rdr.tell() == 150000;
+            assert rdr.read_byte() == (150000 % 251) as int;
+            rdr.seek(10, SeekSet);
+            assert rdr.read_byte() == 10;
+            rdr.seek(-2, SeekEnd);
+            assert rdr.read_bytes(10) == ~[(199998 % 251) as u8,
+                                           (199999 % 251) as u8];
+            assert rdr.eof();
+        }
+    }
+
     #[test]
     fn test_write_empty() {
         let file = io::file_writer(&Path("tmp/lib-io-test-write-empty.tmp"),
@@ -1348,6 +1758,63 @@ mod tests {
             ~[0u8, 9u8, 4u8, 5u8, 8u8, 7u8]);
     }
 
+    #[test]
+    fn bytes_writer_backpatch() {
+        let wr = BytesWriter();
+        wr.write_str("hdr");
+        let len_pos = wr.tell();
+        wr.write_be_u32(0);
+        wr.write_str("payload");
+        let len = wr.tell() - len_pos - 4;
+
+        wr.seek(len_pos as int, SeekSet);
+        wr.write_be_u32(len as u32);
+        assert wr.tell() == len_pos + 4;
+        wr.seek(0, SeekEnd);
+        wr.write_u8(0xff);
+
+        assert wr.bytes_len() == 15;
+        assert wr.unwrap() ==
+            ~[104, 100, 114, 0, 0, 0, 7,
+              112, 97, 121, 108, 111, 97, 100, 0xff];
+    }
+
+    #[test]
+    fn bytes_writer_seek_past_end() {
+        let wr = BytesWriter();
+        wr.write([1, 2]);
+        wr.seek(3, SeekEnd);
+        assert wr.tell() == 5;
+        assert wr.bytes_len() == 2;
+        wr.write([3]);
+        wr.seek(-20, SeekCur);
+        assert wr.tell() == 0;
+        wr.seek(8, SeekSet);
+        wr.write([4]);
+        assert wr.unwrap() == ~[1, 2, 0, 0, 0, 3, 0, 0, 4];
+    }
+
+    #[test]
+    fn bytes_reader_seek() {
+        do io::with_bytes_reader([0, 1, 2, 3, 4, 5]) |rdr| {
+            rdr.seek(4, SeekSet);
+            assert rdr.read_byte() == 4;
+            rdr.seek(-3, SeekCur);
+            assert rdr.tell() == 2;
+            assert rdr.read_bytes(2) == ~[2, 3];
+            rdr.seek(-1, SeekEnd);
+            assert rdr.read_byte() == 5;
+            assert rdr.eof();
+
+            // Seeks are clamped to the bytes available
+            rdr.seek(10, SeekCur);
+            assert rdr.tell() == 6;
+            assert rdr.read_byte() == -1;
+            rdr.seek(-10, SeekEnd);
+            assert rdr.tell() == 0;
+        }
+    }
+
     #[test]
     fn test_read_write_le() {
         let path = Path("tmp/lib-io-test-read-write-le.tmp");
@@ -1416,6 +1883,196 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_read_write_all_widths() {
+        let bytes = do io::with_bytes_writer |wr| {
+            wr.write_u8(0xfe);
+            wr.write_i8(-2);
+            wr.write_le_u16(0xfedc_u16);
+            wr.write_be_u16(0xfedc_u16);
+            wr.write_le_i16(i16::min_value);
+            wr.write_be_i16(-2);
+            wr.write_le_u32(0xfedcba98_u32);
+            wr.write_be_u32(0xfedcba98_u32);
+            wr.write_le_i32(i32::min_value);
+            wr.write_be_i32(-2);
+            wr.write_le_u64(0xfedcba9876543210_u64);
+            wr.write_be_u64(0xfedcba9876543210_u64);
+            wr.write_le_i64(i64::min_value);
+            wr.write_be_i64(-2);
+        };
+        assert bytes.len() == 2 + 4 * 2 + 4 * 4 + 4 * 8;
+        assert vec::slice(bytes, 0, 6) ==
+            ~[0xfe, 0xfe, 0xdc, 0xfe, 0xfe, 0xdc];
+
+        do io::with_bytes_reader(bytes) |rdr| {
+            assert rdr.read_u8() == 0xfe;
+            assert rdr.read_i8() == -2;
+            assert rdr.read_le_u16() == 0xfedc_u16;
+            assert rdr.read_be_u16() == 0xfedc_u16;
+            assert rdr.read_le_i16() == i16::min_value;
+            assert rdr.read_be_i16() == -2;
+            assert rdr.read_le_u32() == 0xfedcba98_u32;
+            assert rdr.read_be_u32() == 0xfedcba98_u32;
+            assert rdr.read_le_i32() == i32::min_value;
+            assert rdr.read_be_i32() == -2;
+            assert rdr.read_le_u64() == 0xfedcba9876543210_u64;
+            assert rdr.read_be_u64() == 0xfedcba9876543210_u64;
+            assert rdr.read_le_i64() == i64::min_value;
+            assert rdr.read_be_i64() == -2;
+            assert rdr.eof();
+        }
+    }
+
+    #[test]
+    fn test_try_read_short() {
+        do io::with_bytes_reader([0x80, 0, 0, 0, 1, 2]) |rdr| {
+            assert rdr.try_read_be_int_n(4) == Some(i32::min_value as i64);
+            assert rdr.try_read_le_uint_n(1) == Some(1);
+            assert rdr.try_read_le_int_n(2) == None;
+            assert rdr.eof();
+            assert rdr.try_read_be_uint_n(1) == None;
+        }
+    }
+
+    #[test]
+    #[should_fail]
+    #[ignore(cfg(windows))]
+    fn test_read_short_fails() {
+        do io::with_bytes_reader([1, 2, 3]) |rdr| {
+            rdr.read_le_u32();
+        }
+    }
+
+    #[test]
+    fn test_prompt_from() {
+        let out = do io::with_str_writer |wr| {
+            do io::with_str_reader("first\nsecond\r\n\nlast") |rdr| {
+                assert io::prompt_from(rdr, wr, "> ") == Some(~"first");
+                assert io::prompt_from(rdr, wr, "> ") == Some(~"second");
+                assert io::prompt_from(rdr, wr, "> ") == Some(~"");
+                assert io::prompt_from(rdr, wr, "> ") == Some(~"last");
+                assert io::prompt_from(rdr, wr, "> ") == None;
+            }
+        };
+        assert out == ~"> > > > > ";
+    }
+
+    fn confirm_str(input: &str, default: bool) -> (bool, ~str) {
+        let mut answer = !default;
+        let out = do io::with_str_writer |wr| {
+            do io::with_str_reader(input) |rdr| {
+                answer = io::confirm_from(rdr, wr, "Go?", default);
+            }
+        };
+        (answer, out)
+    }
+
+    #[test]
+    fn test_confirm_from() {
+        assert confirm_str("y\n", false) == (true, ~"Go? [y/N] ");
+        assert confirm_str("No\n", true) == (false, ~"Go? [Y/n] ");
+        // Empty answers and EOF give the default
+        assert confirm_str("\n", true) == (true, ~"Go? [Y/n] ");
+        assert confirm_str("", false) == (false, ~"Go? [y/N] ");
+        // Unrecognized answers are asked again
+        assert confirm_str("maybe\nyes\n", false) ==
+            (true, ~"Go? [y/N] Please answer y or n.\nGo? [y/N] ");
+        assert confirm_str("maybe\n", true) ==
+            (true, ~"Go? [Y/n] Please answer y or n.\nGo? [Y/n] ");
+    }
+
+    // Records changes to its echo setting instead of touching a terminal
+    struct MockTerminal {
+        tty: bool,
+        // Whether echo is on, and how many times it has been set
+        state: private::Exclusive<(bool, uint)>,
+    }
+
+    impl MockTerminal: io::EchoControl {
+        fn get_echo(&self) -> Option<bool> {
+            if !self.tty { return None; }
+            unsafe {
+                do self.state.with |s| {
+                    let (on, _) = *s;
+                    Some(on)
+                }
+            }
+        }
+        fn set_echo(&self, on: bool) {
+            assert self.tty;
+            unsafe {
+                do self.state.with |s| {
+                    let (_, changes) = *s;
+                    *s = (on, changes + 1);
+                }
+            }
+        }
+    }
+
+    fn mock_terminal(tty: bool) -> (io::EchoControl,
+                                    private::Exclusive<(bool, uint)>) {
+        let state = private::exclusive((true, 0u));
+        let term = @MockTerminal { tty: tty, state: state.clone() };
+        (term as io::EchoControl, move state)
+    }
+
+    #[test]
+    fn test_prompt_password_restores_echo() {
+        let (term, state) = mock_terminal(true);
+        let out = do io::with_str_writer |wr| {
+            do io::with_str_reader("secret\n") |rdr| {
+                let line = io::prompt_password_from(rdr, wr, term, "pw: ");
+                assert line == Some(~"secret");
+            }
+        };
+        assert out == ~"pw: \n";
+        unsafe {
+            assert state.with(|s| *s) == (true, 2u);
+        }
+    }
+
+    #[test]
+    fn test_prompt_password_not_a_tty() {
+        let (term, state) = mock_terminal(false);
+        do io::with_str_writer |wr| {
+            do io::with_str_reader("") |rdr| {
+                assert io::prompt_password_from(rdr, wr, term, "") == None;
+            }
+        };
+        unsafe {
+            assert state.with(|s| *s) == (true, 0u);
+        }
+    }
+
+    struct FailingReader { dummy: () }
+
+    impl FailingReader: io::Reader {
+        fn read(&self, _bytes: &[mut u8], _len: uint) -> uint { die!() }
+        fn read_byte(&self) -> int { die!() }
+        fn eof(&self) -> bool { false }
+        fn seek(&self, _position: int, _style: io::SeekStyle) { die!() }
+        fn tell(&self) -> uint { die!() }
+    }
+
+    #[test]
+    fn test_prompt_password_restores_echo_on_failure() {
+        let state = private::exclusive((true, 0u));
+        let child_state = state.clone();
+        let result = do task::try |move child_state| {
+            let term = @MockTerminal { tty: true, state: move child_state };
+            let rdr = @FailingReader { dummy: () } as io::Reader;
+            do io::with_str_writer |wr| {
+                io::prompt_password_from(rdr, wr, term as io::EchoControl,
+                                         "pw: ");
+            };
+        };
+        assert result.is_err();
+        unsafe {
+            assert state.with(|s| *s) == (true, 2u);
+        }
+    }
+
 }
 
 //
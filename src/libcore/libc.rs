@@ -791,6 +791,7 @@ pub mod consts {
 
             pub const ERROR_SUCCESS : int = 0;
             pub const ERROR_INSUFFICIENT_BUFFER : int = 122;
+            pub const ERROR_ENVVAR_NOT_FOUND : int = 203;
         }
     }
 
@@ -830,6 +831,7 @@ pub mod consts {
             pub const S_IFBLK : int = 24576;
             pub const S_IFDIR : int = 16384;
             pub const S_IFREG : int = 32768;
+            pub const S_IFLNK : int = 40960;
             pub const S_IFMT : int = 61440;
             pub const S_IEXEC : int = 64;
             pub const S_IWRITE : int = 128;
@@ -897,6 +899,7 @@ pub mod consts {
             pub const S_IFBLK : int = 24576;
             pub const S_IFDIR : int = 16384;
             pub const S_IFREG : int = 32768;
+            pub const S_IFLNK : int = 40960;
             pub const S_IFMT : int = 61440;
             pub const S_IEXEC : int = 64;
             pub const S_IWRITE : int = 128;
@@ -965,6 +968,7 @@ pub mod consts {
             pub const S_IFBLK : int = 24576;
             pub const S_IFDIR : int = 16384;
             pub const S_IFREG : int = 32768;
+            pub const S_IFLNK : int = 40960;
             pub const S_IFMT : int = 61440;
             pub const S_IEXEC : int = 64;
             pub const S_IWRITE : int = 128;
@@ -1545,6 +1549,7 @@ pub mod funcs {
             unsafe fn SetCurrentDirectoryW(lpPathName: LPCWSTR) -> BOOL;
 
             unsafe fn GetLastError() -> DWORD;
+            unsafe fn SetLastError(dwErrCode: DWORD);
         }
 
         #[abi = "cdecl"]
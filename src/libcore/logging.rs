@@ -13,8 +13,12 @@
 use cast::transmute;
 use io;
 use libc;
+use option::{None, Some};
+use option;
+use private::{Exclusive, exclusive};
 use repr;
-use vec;
+use str;
+use task;
 
 #[nolink]
 extern mod rustrt {
@@ -45,15 +49,144 @@ pub fn console_off() {
     }
 }
 
-#[cfg(notest)]
-#[lang="log_type"]
-pub fn log_type<T>(level: u32, object: &T) {
-    let bytes = do io::with_bytes_writer |writer| {
-        repr::write_repr(writer, object);
-    };
+/**
+ * A destination for log messages. It is given the level of the message,
+ * the path of the module that logged it and the message itself: a logged
+ * string as it is, and any other value as `%?` would write it.
+ */
+pub type Logger = ~fn(level: u32, module: &str, msg: &str);
+
+// A task's logger is shared with the tasks it spawns, which may call it
+// at the same time
+struct SharedLogger {
+    logger: Exclusive<Logger>
+}
+
+fn logger_key(_v: @SharedLogger) {}
+
+/**
+ * Send the log messages of this task, and of the tasks it spawns from now
+ * on, to `logger` instead of the console.
+ *
+ * Calls to the logger are serialized. Anything logged by the logger
+ * itself goes to the console.
+ */
+pub fn set_logger(logger: Logger) {
+    unsafe {
+        let shared = @SharedLogger { logger: exclusive(move logger) };
+        task::local_data::local_data_set(logger_key, shared);
+    }
+}
+
+/// Send the log messages of this task, and of the tasks it spawns from now
+/// on, back to the console
+pub fn clear_logger() {
     unsafe {
-        let len = bytes.len() as libc::size_t;
-        rustrt::rust_log_str(level, transmute(vec::raw::to_ptr(bytes)), len);
+        task::local_data::local_data_pop(logger_key);
     }
 }
 
+/**
+ * Wrap the body of a new task so that it starts with the logger of the
+ * task spawning it. Used by `task::spawn`.
+ */
+#[doc(hidden)]
+pub fn inherit_logger(f: fn~()) -> fn~() {
+    let inherited = unsafe {
+        match task::local_data::local_data_get(logger_key) {
+            Some(shared) => Some(shared.logger.clone()),
+            None => None
+        }
+    };
+    match move inherited {
+        None => move f,
+        Some(move logger) => {
+            let logger = ~mut Some(move logger);
+            fn~(move logger, move f) {
+                let logger = option::swap_unwrap(logger);
+                unsafe {
+                    task::local_data::local_data_set(
+                        logger_key, @SharedLogger { logger: move logger });
+                }
+                f();
+            }
+        }
+    }
+}
+
+// What kind of string a logged value is, as the compiler tells `log_type`
+const NOT_STR: uint = 0;
+const UNIQ_STR: uint = 1;
+const BOX_STR: uint = 2;
+const SLICE_STR: uint = 3;
+
+fn log_repr<T>(level: u32, module: &str, str_kind: uint, object: &T) {
+    // A logged string is written as it is, not quoted
+    let msg = match unsafe { as_str(str_kind, object) } {
+        Some(s) => s.to_owned(),
+        None => repr_str(object)
+    };
+
+    // Taken out of the task while it runs, so that logging from inside
+    // the logger goes to the console instead of deadlocking
+    match unsafe { task::local_data::local_data_pop(logger_key) } {
+        Some(shared) => unsafe {
+            do shared.logger.with |logger| {
+                (*logger)(level, module, msg)
+            }
+            task::local_data::local_data_set(logger_key, shared);
+        },
+        None => unsafe {
+            let len = msg.len() as libc::size_t;
+            do str::as_buf(msg) |buf, _len| {
+                rustrt::rust_log_str(level, transmute(buf), len);
+            }
+        }
+    }
+}
+
+fn repr_str<T>(object: &T) -> ~str {
+    do io::with_str_writer |writer| {
+        repr::write_repr(writer, object);
+    }
+}
+
+// The string `object` is, if `str_kind` says it is one
+unsafe fn as_str<T>(str_kind: uint, object: &r/T) -> Option<&r/str> {
+    if str_kind == UNIQ_STR {
+        let s: &r/~str = transmute(object);
+        Some(str::view(*s, 0, s.len()))
+    } else if str_kind == BOX_STR {
+        let s: &r/@str = transmute(object);
+        Some(str::view(*s, 0, s.len()))
+    } else if str_kind == SLICE_STR {
+        let s: &r/&r/str = transmute(object);
+        Some(*s)
+    } else {
+        None
+    }
+}
+
+// The entry point for log statements. Compilers before the last snapshot
+// don't pass the name of the module, or say whether the value is a string.
+#[cfg(notest)]
+mod lang {
+    use libc::c_char;
+    use str;
+
+    #[cfg(stage0)]
+    #[lang="log_type"]
+    pub fn log_type<T>(level: u32, object: &T) {
+        super::log_repr(level, "", super::NOT_STR, object)
+    }
+
+    #[cfg(stage1)]
+    #[cfg(stage2)]
+    #[cfg(stage3)]
+    #[lang="log_type"]
+    pub fn log_type<T>(level: u32, module: *c_char, str_kind: uint,
+                       object: &T) {
+        let module = unsafe { str::raw::from_c_str(module) };
+        super::log_repr(level, module, str_kind, object)
+    }
+}
@@ -12,6 +12,7 @@
 
 use cmath;
 use cmp;
+use f64;
 use libc::{c_float, c_int};
 use num;
 use option::Option;
@@ -402,35 +403,49 @@ pub pure fn to_str_radix_special(num: f32, rdx: uint) -> (~str, bool) {
 }
 
 /**
- * Converts a float to a string with exactly the number of
- * provided significant digits
+ * Converts a float to a string with exactly the given number of digits
+ * after the decimal point
+ *
+ * See `f64::to_str_exact()` for the rounding and the special values.
  *
  * # Arguments
  *
  * * num - The float value
- * * digits - The number of significant digits
+ * * digits - The number of digits after the decimal point
  */
 #[inline(always)]
 pub pure fn to_str_exact(num: f32, dig: uint) -> ~str {
-    let (r, _) = num::to_str_common(
-        &num, 10u, true, true, num::SignNeg, num::DigExact(dig));
-    r
+    f64::to_str_exact(num as f64, dig)
 }
 
 /**
- * Converts a float to a string with a maximum number of
- * significant digits
+ * Converts a float to a string with at most the given number of digits
+ * after the decimal point
  *
  * # Arguments
  *
  * * num - The float value
- * * digits - The number of significant digits
+ * * digits - The maximum number of digits after the decimal point
  */
 #[inline(always)]
 pub pure fn to_str_digits(num: f32, dig: uint) -> ~str {
-    let (r, _) = num::to_str_common(
-        &num, 10u, true, true, num::SignNeg, num::DigMax(dig));
-    r
+    f64::to_str_digits(num as f64, dig)
+}
+
+/**
+ * Converts a float to a string in scientific notation
+ *
+ * See `f64::to_str_exp()` for the format.
+ *
+ * # Arguments
+ *
+ * * num - The float value
+ * * digits - The number of digits after the decimal point
+ * * upper - Whether to write the exponent marker as `E` instead of `e`
+ */
+#[inline(always)]
+pub pure fn to_str_exp(num: f32, dig: uint, upper: bool) -> ~str {
+    f64::to_str_exp(num as f64, dig, upper)
 }
 
 impl f32: to_str::ToStr {
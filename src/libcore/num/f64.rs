@@ -12,15 +12,23 @@
 
 use cmath;
 use cmp;
-use libc::{c_double, c_int};
+use libc::{c_char, c_double, c_int, size_t};
 use libc;
 use num;
-use option::Option;
+use option::{None, Option, Some};
+use ptr;
+use str;
 use to_str;
 use from_str;
 
 pub use cmath::c_double_targ_consts::*;
 
+#[abi = "cdecl"]
+extern mod rustrt {
+    unsafe fn rust_format_double(n: c_double, conv: c_char,
+                                 precision: size_t) -> ~str;
+}
+
 macro_rules! delegate(
     (
         fn $name:ident(
@@ -425,35 +433,96 @@ pub pure fn to_str_radix_special(num: f64, rdx: uint) -> (~str, bool) {
     num::to_str_common(&num, rdx, true, true, num::SignNeg, num::DigAll)
 }
 
+// Formats with printf's `%.*f`, `%.*e` or `%.*E`, whose rounding works
+// from the exact binary value, rather than with `num::to_str_common()`,
+// whose rounding works from the approximate digits it has produced.
+pure fn to_str_printf(num: f64, conv: char, precision: uint) -> ~str {
+    if is_NaN(num) { return ~"NaN"; }
+    if num == infinity { return ~"inf"; }
+    if num == neg_infinity { return ~"-inf"; }
+    unsafe {
+        rustrt::rust_format_double(num, conv as c_char, precision as size_t)
+    }
+}
+
 /**
- * Converts a float to a string with exactly the number of
- * provided significant digits
+ * Converts a float to a string with exactly the given number of digits
+ * after the decimal point
+ *
+ * The last digit is rounded to nearest, ties to even, from the exact
+ * binary value of `num`, so `to_str_exact(0.125, 2)` is "0.12" and
+ * `to_str_exact(0.1 + 0.2, 2)` is "0.30". A negative `num` keeps its
+ * sign even if it rounds to zero, as does -0.0. The special values are
+ * written as "NaN", "inf" and "-inf".
  *
  * # Arguments
  *
  * * num - The float value
- * * digits - The number of significant digits
+ * * digits - The number of digits after the decimal point. If 0, no
+ *            decimal point is written.
  */
 #[inline(always)]
 pub pure fn to_str_exact(num: f64, dig: uint) -> ~str {
-    let (r, _) = num::to_str_common(
-        &num, 10u, true, true, num::SignNeg, num::DigExact(dig));
-    r
+    to_str_printf(num, 'f', dig)
 }
 
 /**
- * Converts a float to a string with a maximum number of
- * significant digits
+ * Converts a float to a string with at most the given number of digits
+ * after the decimal point
+ *
+ * This is `to_str_exact()` with trailing zeros, and then a trailing
+ * decimal point, removed.
  *
  * # Arguments
  *
  * * num - The float value
- * * digits - The number of significant digits
+ * * digits - The maximum number of digits after the decimal point
  */
 #[inline(always)]
 pub pure fn to_str_digits(num: f64, dig: uint) -> ~str {
-    let (r, _) = num::to_str_common(
-        &num, 10u, true, true, num::SignNeg, num::DigMax(dig));
+    let mut s = to_str_printf(num, 'f', dig);
+    if dig > 0 && is_finite(num) {
+        let mut end = str::len(s);
+        while s[end - 1] == '0' as u8 { end -= 1; }
+        if s[end - 1] == '.' as u8 { end -= 1; }
+        unsafe { str::raw::set_len(&mut s, end); }
+    }
+    s
+}
+
+/**
+ * Converts a float to a string in scientific notation
+ *
+ * The mantissa has one digit before the decimal point and exactly
+ * `digits` after it, rounded as in `to_str_exact()`. The exponent is
+ * written without a `+` sign or leading zeros, so 1500.0 with 2 digits
+ * is "1.50e3" and 0.00015 is "1.50e-4". Rounding 16 digits, 17
+ * significant digits in all, keeps enough to read back the same `f64`
+ * with `from_str()`.
+ *
+ * # Arguments
+ *
+ * * num - The float value
+ * * digits - The number of digits after the decimal point
+ * * upper - Whether to write the exponent marker as `E` instead of `e`
+ */
+pub pure fn to_str_exp(num: f64, dig: uint, upper: bool) -> ~str {
+    let s = to_str_printf(num, if upper { 'E' } else { 'e' }, dig);
+    let e = match str::find_char(s, if upper { 'E' } else { 'e' }) {
+        Some(e) => e,
+        None => return s // a special value
+    };
+
+    // printf always writes a sign and at least two exponent digits
+    let len = str::len(s);
+    let mut start = e + 2;
+    while start < len - 1 && s[start] == '0' as u8 { start += 1; }
+
+    let mut r = str::slice(s, 0, e + 1);
+    unsafe {
+        if s[e + 1] == '-' as u8 { str::push_char(&mut r, '-'); }
+        str::push_str(&mut r, str::view(s, start, len));
+    }
     r
 }
 
@@ -498,7 +567,29 @@ impl f64: num::ToStrRadix {
  */
 #[inline(always)]
 pub pure fn from_str(num: &str) -> Option<f64> {
-    num::from_str_common(num, 10u, true, true, true, num::ExpDec, false)
+    match num::from_str_common(num, 10u, true, true, true, num::ExpDec,
+                               false) {
+        Some(approx) => match strtod_exact(num) {
+            Some(n) => Some(n),
+            None => Some(approx)
+        },
+        None => None
+    }
+}
+
+// `num::from_str_common()` accumulates rounding error over long inputs, so
+// once it has accepted a number, strtod (run in the C locale) is asked
+// for the correctly rounded value. Returns `None` for forms that strtod
+// doesn't read in full, such as ".".
+pure fn strtod_exact(num: &str) -> Option<f64> {
+    unsafe {
+        do str::as_c_str(num) |buf| {
+            let mut end: *c_char = ptr::null();
+            let n = libc::strtod(buf, ptr::addr_of(&end));
+            let whole = end == ptr::offset(buf, str::len(num));
+            if whole { Some(n) } else { None }
+        }
+    }
 }
 
 /**
@@ -163,19 +163,19 @@ pub pure fn to_str_radix_special(num: float, radix: uint) -> (~str, bool) {
 }
 
 /**
- * Converts a float to a string with exactly the number of
- * provided significant digits
+ * Converts a float to a string with exactly the given number of digits
+ * after the decimal point
+ *
+ * See `f64::to_str_exact()` for the rounding and the special values.
  *
  * # Arguments
  *
  * * num - The float value
- * * digits - The number of significant digits
+ * * digits - The number of digits after the decimal point
  */
 #[inline(always)]
 pub pure fn to_str_exact(num: float, digits: uint) -> ~str {
-    let (r, _) = num::to_str_common(
-        &num, 10u, true, true, num::SignNeg, num::DigExact(digits));
-    r
+    f64::to_str_exact(num as f64, digits)
 }
 
 #[test]
@@ -185,19 +185,33 @@ pub fn test_to_str_exact_do_decimal() {
 }
 
 /**
- * Converts a float to a string with a maximum number of
- * significant digits
+ * Converts a float to a string with at most the given number of digits
+ * after the decimal point
  *
  * # Arguments
  *
  * * num - The float value
- * * digits - The number of significant digits
+ * * digits - The maximum number of digits after the decimal point
  */
 #[inline(always)]
 pub pure fn to_str_digits(num: float, digits: uint) -> ~str {
-    let (r, _) = num::to_str_common(
-        &num, 10u, true, true, num::SignNeg, num::DigMax(digits));
-    r
+    f64::to_str_digits(num as f64, digits)
+}
+
+/**
+ * Converts a float to a string in scientific notation
+ *
+ * See `f64::to_str_exp()` for the format.
+ *
+ * # Arguments
+ *
+ * * num - The float value
+ * * digits - The number of digits after the decimal point
+ * * upper - Whether to write the exponent marker as `E` instead of `e`
+ */
+#[inline(always)]
+pub pure fn to_str_exp(num: float, digits: uint, upper: bool) -> ~str {
+    f64::to_str_exp(num as f64, digits, upper)
 }
 
 impl float: to_str::ToStr {
@@ -241,7 +255,10 @@ impl float: num::ToStrRadix {
  */
 #[inline(always)]
 pub pure fn from_str(num: &str) -> Option<float> {
-    num::from_str_common(num, 10u, true, true, true, num::ExpDec, false)
+    match f64::from_str(num) {
+        Some(n) => Some(n as float),
+        None => None
+    }
 }
 
 /**
@@ -514,6 +531,93 @@ pub fn test_from_str() {
    assert from_str(~"1e1-1").is_none();
 }
 
+#[test]
+pub fn test_to_str_exact_rounding() {
+    // Ties round to even, but only when the binary value is really a tie
+    assert to_str_exact(0.125, 2u) == ~"0.12";
+    assert to_str_exact(0.375, 2u) == ~"0.38";
+    assert to_str_exact(2.5, 0u) == ~"2";
+    assert to_str_exact(3.5, 0u) == ~"4";
+    assert to_str_exact(0.15, 1u) == ~"0.1";  // really 0.1499999...
+    assert to_str_exact(0.1 + 0.2, 2u) == ~"0.30";
+    assert to_str_exact(0.1 + 0.2, 17u) == ~"0.30000000000000004";
+    assert to_str_exact(9.995, 2u) == ~"9.99";  // really 9.99499999...
+    assert to_str_exact(9.9999, 3u) == ~"10.000";
+    assert to_str_exact(-1.25, 1u) == ~"-1.2";
+    assert to_str_exact(1e21, 0u) == ~"1000000000000000000000";
+    assert to_str_exact(1e-10, 5u) == ~"0.00000";
+}
+
+#[test]
+pub fn test_to_str_digits() {
+    use f32;
+
+    assert to_str_digits(0.1 + 0.2, 3u) == ~"0.3";
+    assert to_str_digits(0.1 + 0.2, 16u) == ~"0.3";
+    assert to_str_digits(0.1 + 0.2, 17u) == ~"0.30000000000000004";
+    assert to_str_digits(5.82, 6u) == ~"5.82";
+    assert to_str_digits(100.0, 6u) == ~"100";
+    assert to_str_digits(100.0, 0u) == ~"100";
+    assert to_str_digits(0.0004, 3u) == ~"0";
+    assert to_str_digits(1e-300, 6u) == ~"0";
+    assert f32::to_str_digits(0.1f32, 8u) == ~"0.1";
+    assert f32::to_str_digits(0.1f32, 10u) == ~"0.1000000015";
+}
+
+#[test]
+pub fn test_to_str_exp() {
+    use f32;
+
+    assert to_str_exp(1500.0, 2u, false) == ~"1.50e3";
+    assert to_str_exp(1500.0, 2u, true) == ~"1.50E3";
+    assert to_str_exp(0.00015, 2u, false) == ~"1.50e-4";
+    assert to_str_exp(-0.0002, 0u, false) == ~"-2e-4";
+    assert to_str_exp(0.0, 1u, false) == ~"0.0e0";
+    assert to_str_exp(1.0, 0u, false) == ~"1e0";
+    assert to_str_exp(9.96, 1u, false) == ~"1.0e1";
+    assert to_str_exp(1e300, 3u, false) == ~"1.000e300";
+    assert to_str_exp(5e-324, 0u, false) == ~"5e-324";
+    assert f64::to_str_exp(f64::max_value, 3u, true) == ~"1.798E308";
+    assert f32::to_str_exp(1.5f32, 2u, false) == ~"1.50e0";
+}
+
+#[test]
+pub fn test_to_str_special() {
+    for ~[to_str_exact(infinity, 3u), to_str_digits(infinity, 3u),
+         to_str_exp(infinity, 3u, false)].each |s| {
+        assert *s == ~"inf";
+    }
+    for ~[to_str_exact(neg_infinity, 3u), to_str_digits(neg_infinity, 3u),
+         to_str_exp(neg_infinity, 3u, true)].each |s| {
+        assert *s == ~"-inf";
+    }
+    for ~[to_str_exact(NaN, 3u), to_str_digits(NaN, 3u),
+         to_str_exp(NaN, 3u, false)].each |s| {
+        assert *s == ~"NaN";
+    }
+
+    // Negative zero, and negative numbers that round to zero, keep the sign
+    assert to_str_exact(-0.0, 2u) == ~"-0.00";
+    assert to_str_digits(-0.0, 2u) == ~"-0";
+    assert to_str_exp(-0.0, 1u, false) == ~"-0.0e0";
+    assert to_str_exact(-0.001, 2u) == ~"-0.00";
+    assert to_str_exact(0.0, 2u) == ~"0.00";
+}
+
+#[test]
+pub fn test_to_str_exp_round_trip() {
+    // 17 significant digits always read back as the same f64
+    let xs = ~[0.1, 0.1 + 0.2, 1.0 / 3.0, 2.0 / 3.0, f64::consts::pi as float,
+              123456789.123456789, 1e-300, 4.9406564584124654e-324,
+              2.2250738585072014e-308, 1.7976931348623157e308,
+              -9007199254740993.0, 0.000123456789012345678];
+    for xs.each |x| {
+        let s = to_str_exp(*x, 16u, false);
+        assert from_str(s) == Some(*x);
+        assert f64::from_str(s) == Some(*x as f64);
+    }
+}
+
 #[test]
 pub fn test_from_str_hex() {
    assert from_str_hex(~"a4") == Some(164.);
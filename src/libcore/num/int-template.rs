@@ -16,6 +16,7 @@ use cmp;
 use to_str::ToStr;
 use from_str::FromStr;
 use num::{ToStrRadix, FromStrRadix};
+use num::ParseIntErr;
 use num;
 use num::Num::from_int;
 use prelude::*;
@@ -109,7 +110,17 @@ pub pure fn is_nonnegative(x: T) -> bool { x >= 0 as T }
  * ~~~
  */
 #[inline(always)]
-/// Iterate over the range [`start`,`start`+`step`..`stop`)
+/**
+ * Iterate over the range [`start`,`start`+`step`..`stop`)
+ *
+ * A negative `step` counts down from `start` to just above `stop`. The
+ * last value is the last one short of `stop`, even if the step after it
+ * would go past `max_value` or `min_value`.
+ *
+ * # Failure
+ *
+ * Fails if `step` is 0.
+ */
 pub pure fn range_step(start: T, stop: T, step: T, it: fn(T) -> bool) {
     let mut i = start;
     if step == 0 {
@@ -117,12 +128,18 @@ pub pure fn range_step(start: T, stop: T, step: T, it: fn(T) -> bool) {
     } else if step > 0 { // ascending
         while i < stop {
             if !it(i) { break }
-            i += step;
+            match checked_add(i, step) {
+                Some(next) => i = next,
+                None => break
+            }
         }
     } else { // descending
         while i > stop {
             if !it(i) { break }
-            i += step;
+            match checked_add(i, step) {
+                Some(next) => i = next,
+                None => break
+            }
         }
     }
 }
@@ -151,6 +168,155 @@ pub pure fn abs(i: T) -> T {
     if is_negative(i) { -i } else { i }
 }
 
+/// Adds `x` and `y`, returning `None` if the sum lies outside
+/// [`min_value`, `max_value`]
+#[inline(always)]
+pub pure fn checked_add(x: T, y: T) -> Option<T> {
+    if (y > 0 && x > max_value - y) || (y < 0 && x < min_value - y) {
+        None
+    } else {
+        Some(x + y)
+    }
+}
+
+/// Subtracts `y` from `x`, returning `None` if the difference lies outside
+/// [`min_value`, `max_value`]
+#[inline(always)]
+pub pure fn checked_sub(x: T, y: T) -> Option<T> {
+    if (y < 0 && x > max_value + y) || (y > 0 && x < min_value + y) {
+        None
+    } else {
+        Some(x - y)
+    }
+}
+
+/// Multiplies `x` and `y`, returning `None` if the product lies outside
+/// [`min_value`, `max_value`]
+#[inline(always)]
+pub pure fn checked_mul(x: T, y: T) -> Option<T> {
+    // Division truncates towards zero, so each bound divided by one
+    // factor is the furthest the other factor may go in that direction
+    let overflow = if x > 0 {
+        if y > 0 { x > max_value / y } else { y < min_value / x }
+    } else if x < 0 {
+        if y > 0 { x < min_value / y } else { y < max_value / x }
+    } else {
+        false
+    };
+    if overflow { None } else { Some(x * y) }
+}
+
+/// Adds `x` and `y`, clamping the sum to [`min_value`, `max_value`]
+#[inline(always)]
+pub pure fn saturating_add(x: T, y: T) -> T {
+    match checked_add(x, y) {
+        Some(n) => n,
+        None => if y > 0 { max_value } else { min_value }
+    }
+}
+
+/// Subtracts `y` from `x`, clamping the difference to
+/// [`min_value`, `max_value`]
+#[inline(always)]
+pub pure fn saturating_sub(x: T, y: T) -> T {
+    match checked_sub(x, y) {
+        Some(n) => n,
+        None => if y < 0 { max_value } else { min_value }
+    }
+}
+
+/**
+ * Raises `base` to the power of `exp`, returning `None` if the result
+ * does not fit in the type
+ *
+ * `x` to the power of 0 is 1 for every `x`, including 0. A negative
+ * `base` gives a negative result for odd `exp`.
+ */
+pub pure fn checked_pow(base: T, exp: uint) -> Option<T> {
+    let mut acc: T = 1, base = base, exp = exp;
+    // Binary exponentiation. Squaring only when a higher bit of `exp`
+    // remains means that an overflowing square is always an overflow of
+    // the result.
+    while exp > 0 {
+        if exp & 1 == 1 {
+            match checked_mul(acc, base) {
+                Some(n) => acc = n,
+                None => return None
+            }
+        }
+        exp >>= 1;
+        if exp > 0 {
+            match checked_mul(base, base) {
+                Some(n) => base = n,
+                None => return None
+            }
+        }
+    }
+    Some(acc)
+}
+
+/**
+ * Raises `base` to the power of `exp`
+ *
+ * `x` to the power of 0 is 1 for every `x`, including 0. A negative
+ * `base` gives a negative result for odd `exp`.
+ *
+ * # Failure
+ *
+ * Fails if the result does not fit in the type.
+ */
+pub pure fn pow(base: T, exp: uint) -> T {
+    match checked_pow(base, exp) {
+        Some(n) => n,
+        None => die!(fmt!("pow: %s to the power of %u overflows",
+                          to_str(base), exp))
+    }
+}
+
+/**
+ * Computes the greatest common divisor, which is never negative, with
+ * `gcd(0, 0) == 0`
+ *
+ * # Failure
+ *
+ * Fails if the result is `-min_value`, which does not fit in the type.
+ * That only happens when both arguments are 0 or `min_value`.
+ */
+pub pure fn gcd(a: T, b: T) -> T {
+    // Work with the non-positive values, since `min_value` has no
+    // positive counterpart but every other value has a negative one
+    let mut a = if a > 0 { -a } else { a };
+    let mut b = if b > 0 { -b } else { b };
+    while b != 0 {
+        // `min_value % -1` overflows
+        if b == -1 { return 1; }
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    if a == min_value {
+        die!(~"gcd: the result does not fit in the type");
+    }
+    -a
+}
+
+/**
+ * Computes the least common multiple, which is never negative, and 0 if
+ * either argument is
+ *
+ * # Failure
+ *
+ * Fails if the result does not fit in the type.
+ */
+pub pure fn lcm(a: T, b: T) -> T {
+    if a == 0 || b == 0 { return 0; }
+    match checked_mul(a / gcd(a, b), b) {
+        Some(n) if n > 0 => n,
+        Some(n) if n != min_value => -n,
+        _ => die!(fmt!("lcm: lcm(%s, %s) overflows", to_str(a), to_str(b)))
+    }
+}
+
 #[cfg(notest)]
 impl T : Ord {
     #[inline(always)]
@@ -201,6 +367,42 @@ impl T: num::One {
     static pure fn one() -> T { 1 }
 }
 
+impl T: num::Integer {
+    #[inline(always)]
+    pure fn pow(&self, exp: uint) -> T { pow(*self, exp) }
+    #[inline(always)]
+    pure fn checked_pow(&self, exp: uint) -> Option<T> {
+        checked_pow(*self, exp)
+    }
+    #[inline(always)]
+    pure fn gcd(&self, other: &T) -> T { gcd(*self, *other) }
+    #[inline(always)]
+    pure fn lcm(&self, other: &T) -> T { lcm(*self, *other) }
+}
+
+impl T: num::CheckedNum {
+    #[inline(always)]
+    pure fn checked_add(&self, other: &T) -> Option<T> {
+        checked_add(*self, *other)
+    }
+    #[inline(always)]
+    pure fn checked_sub(&self, other: &T) -> Option<T> {
+        checked_sub(*self, *other)
+    }
+    #[inline(always)]
+    pure fn checked_mul(&self, other: &T) -> Option<T> {
+        checked_mul(*self, *other)
+    }
+    #[inline(always)]
+    pure fn saturating_add(&self, other: &T) -> T {
+        saturating_add(*self, *other)
+    }
+    #[inline(always)]
+    pure fn saturating_sub(&self, other: &T) -> T {
+        saturating_sub(*self, *other)
+    }
+}
+
 impl T: num::Round {
     #[inline(always)]
     pure fn round(&self, _: num::RoundMode) -> T { *self }
@@ -215,18 +417,49 @@ impl T: num::Round {
 
 // String conversion functions and impl str -> num
 
+/**
+ * Parse a string as a number in base 10, reporting why it could not be
+ * parsed.
+ *
+ * Accepts an optional leading `+` or `-`.
+ */
+#[inline(always)]
+pub pure fn from_str_result(s: &str) -> Result<T, ParseIntErr> {
+    num::from_str_int_common(s, 10u, true)
+}
+
+/**
+ * Parse a string as a number in the given base, reporting why it could
+ * not be parsed.
+ *
+ * Accepts an optional leading `+` or `-`.
+ *
+ * # Failure
+ *
+ * Fails if `radix` < 2 or `radix` > 36.
+ */
+#[inline(always)]
+pub pure fn from_str_radix_result(s: &str, radix: uint)
+    -> Result<T, ParseIntErr> {
+    num::from_str_int_common(s, radix, true)
+}
+
 /// Parse a string as a number in base 10.
 #[inline(always)]
 pub pure fn from_str(s: &str) -> Option<T> {
-    num::from_str_common(s, 10u, true, false, false,
-                         num::ExpNone, false)
+    match from_str_result(s) {
+        Ok(n) => Some(n),
+        Err(_) => None
+    }
 }
 
 /// Parse a string as a number in the given base.
 #[inline(always)]
 pub pure fn from_str_radix(s: &str, radix: uint) -> Option<T> {
-    num::from_str_common(s, radix, true, false, false,
-                         num::ExpNone, false)
+    match from_str_radix_result(s, radix) {
+        Ok(n) => Some(n),
+        Err(_) => None
+    }
 }
 
 /// Parse a byte slice as a number in the given base.
@@ -338,6 +571,221 @@ fn test_parse_bytes() {
     assert parse_bytes(to_bytes(~"-9"), 2u).is_none();
 }
 
+#[test]
+fn test_from_str_result() {
+    use num::{Empty, InvalidDigit, Overflow};
+
+    assert from_str_result(~"0") == Ok(0 as T);
+    assert from_str_result(~"+42") == Ok(42 as T);
+    assert from_str_result(~"-42") == Ok(-42 as T);
+    assert from_str_result(~"-0") == Ok(0 as T);
+    assert from_str_result(~"") == Err(Empty);
+    assert from_str_result(~"-") == Err(Empty);
+    assert from_str_result(~"+") == Err(Empty);
+    assert from_str_result(~"+-1") == Err(InvalidDigit('-', 1u));
+    assert from_str_result(~"--1") == Err(InvalidDigit('-', 1u));
+    assert from_str_result(~"-12x") == Err(InvalidDigit('x', 3u));
+    assert from_str_result(~"1é2") == Err(InvalidDigit('é', 1u));
+
+    assert from_str_radix_result(~"-1001", 2u) == Ok(-9 as T);
+    assert from_str_radix_result(~"-7f", 16u) == Ok(-127 as T);
+    assert from_str_radix_result(~"-z", 36u) == Ok(-35 as T);
+    assert from_str_radix_result(~"z", 35u) == Err(InvalidDigit('z', 0u));
+
+    // Both bounds parse in every radix, and one more digit overflows
+    for uint::range(2u, 37u) |radix| {
+        let max = to_str_radix(max_value, radix);
+        let min = to_str_radix(min_value, radix);
+        assert from_str_radix_result(max, radix) == Ok(max_value);
+        assert from_str_radix_result(min, radix) == Ok(min_value);
+        assert from_str_radix_result(max + ~"0", radix) == Err(Overflow);
+        assert from_str_radix_result(min + ~"0", radix) == Err(Overflow);
+    }
+
+    // Exactly one past each bound
+    let past_max = to_str(max_value / 10) + to_str(max_value % 10 + 1);
+    let past_min = to_str(min_value / 10) + to_str(-(min_value % 10) + 1);
+    assert from_str_result(past_max) == Err(Overflow);
+    assert from_str_result(past_min) == Err(Overflow);
+    assert from_str(past_max).is_none();
+    assert from_str(past_min).is_none();
+}
+
+#[test]
+fn test_checked_arith() {
+    assert checked_add(max_value - 1, 1) == Some(max_value);
+    assert checked_add(max_value, 1).is_none();
+    assert checked_add(1, max_value).is_none();
+    assert checked_add(min_value + 1, -1) == Some(min_value);
+    assert checked_add(min_value, -1).is_none();
+    assert checked_add(-1, min_value).is_none();
+    assert checked_add(max_value, min_value) == Some(-1);
+    assert checked_add(min_value, min_value).is_none();
+
+    assert checked_sub(min_value + 1, 1) == Some(min_value);
+    assert checked_sub(min_value, 1).is_none();
+    assert checked_sub(max_value - 1, -1) == Some(max_value);
+    assert checked_sub(max_value, -1).is_none();
+    assert checked_sub(0, max_value) == Some(min_value + 1);
+    assert checked_sub(-1, max_value) == Some(min_value);
+    assert checked_sub(0, min_value).is_none();
+    assert checked_sub(-1, min_value) == Some(max_value);
+    assert checked_sub(min_value, min_value) == Some(0);
+
+    let half = max_value / 2 + 1;
+    assert checked_mul(max_value, 1) == Some(max_value);
+    assert checked_mul(max_value, -1) == Some(min_value + 1);
+    assert checked_mul(min_value, 1) == Some(min_value);
+    assert checked_mul(min_value, -1).is_none();
+    assert checked_mul(-1, min_value).is_none();
+    assert checked_mul(min_value, 0) == Some(0);
+    assert checked_mul(0, min_value) == Some(0);
+    assert checked_mul(half - 1, 2) == Some(max_value - 1);
+    assert checked_mul(half, 2).is_none();
+    assert checked_mul(-half, 2) == Some(min_value);
+    assert checked_mul(2, -half) == Some(min_value);
+    assert checked_mul(-half - 1, 2).is_none();
+    assert checked_mul(-half, -2).is_none();
+    assert checked_mul(-(half - 1), -2) == Some(max_value - 1);
+    assert checked_mul(3, min_value / 3) == Some(min_value / 3 * 3);
+    assert checked_mul(3, min_value / 3 - 1).is_none();
+    assert checked_mul(max_value, max_value).is_none();
+    assert checked_mul(min_value, min_value).is_none();
+
+    assert saturating_add(max_value, 1) == max_value;
+    assert saturating_add(max_value, max_value) == max_value;
+    assert saturating_add(min_value, -1) == min_value;
+    assert saturating_add(min_value, min_value) == min_value;
+    assert saturating_add(max_value, min_value) == -1;
+    assert saturating_sub(min_value, 1) == min_value;
+    assert saturating_sub(max_value, -1) == max_value;
+    assert saturating_sub(0, min_value) == max_value;
+    assert saturating_sub(min_value, max_value) == min_value;
+    assert saturating_sub(-1, min_value) == max_value;
+}
+
+#[test]
+fn test_pow() {
+    assert pow(0, 0) == 1;
+    assert pow(0, 1) == 0;
+    assert pow(0, 7) == 0;
+    assert pow(7, 0) == 1;
+    assert pow(-7, 0) == 1;
+    assert pow(-1, 1000) == 1;
+    assert pow(-1, 1001) == -1;
+    assert pow(-2, 3) == -8;
+    assert pow(-3, 4) == 81;
+    assert pow(2, 6) == 64;
+
+    // The most negative value is a power of -2, but its negation is not a
+    // power of 2
+    assert pow(-2, bits - 1) == min_value;
+    assert checked_pow(-2, bits - 1) == Some(min_value);
+    assert checked_pow(2, bits - 1).is_none();
+    assert checked_pow(2, bits - 2) == Some(max_value / 2 + 1);
+    assert checked_pow(-2, bits).is_none();
+    assert checked_pow(min_value, 1) == Some(min_value);
+    assert checked_pow(min_value, 2).is_none();
+    assert checked_pow(max_value, 2).is_none();
+}
+
+#[test]
+#[should_fail]
+#[ignore(cfg(windows))]
+fn test_pow_overflow() {
+    pow(2, bits - 1);
+}
+
+#[test]
+fn test_gcd_lcm() {
+    assert gcd(0, 0) == 0;
+    assert gcd(0, -9) == 9;
+    assert gcd(-9, 0) == 9;
+    assert gcd(12, -18) == 6;
+    assert gcd(-12, -18) == 6;
+    assert gcd(-17, 5) == 1;
+    assert gcd(min_value, 1) == 1;
+    assert gcd(min_value, -1) == 1;
+    assert gcd(min_value, max_value) == 1;
+    assert gcd(min_value, 2) == 2;
+    assert gcd(min_value, min_value / 2) == max_value / 2 + 1;
+    assert gcd(max_value, max_value) == max_value;
+
+    assert lcm(0, -9) == 0;
+    assert lcm(-4, 6) == 12;
+    assert lcm(-4, -6) == 12;
+    assert lcm(max_value, -1) == max_value;
+    assert lcm(min_value + 1, -1) == max_value;
+
+    for range(-30, 31) |a| {
+        for range(-30, 31) |b| {
+            let g = gcd(a, b);
+            assert g >= 0;
+            assert g == gcd(b, a) && g == gcd(-a, b);
+            if g != 0 {
+                assert a % g == 0 && b % g == 0;
+                assert gcd(a / g, b / g) == 1;
+            }
+            match checked_mul(a, b) {
+                Some(p) => assert lcm(a, b) * g == abs(p),
+                None => ()
+            }
+        }
+    }
+}
+
+#[test]
+#[should_fail]
+#[ignore(cfg(windows))]
+fn test_gcd_min_value() {
+    gcd(min_value, 0);
+}
+
+#[test]
+#[should_fail]
+#[ignore(cfg(windows))]
+fn test_lcm_min_value() {
+    lcm(min_value, 1);
+}
+
+#[test]
+fn test_integer() {
+    use num::Integer;
+
+    fn reduce<N: Integer num::Num Copy>(n: N, d: N) -> (N, N) {
+        let g = n.gcd(&d);
+        (n.div(&g), d.div(&g))
+    }
+
+    assert reduce(-12 as T, 18) == (-2, 3);
+    assert (-3 as T).pow(3) == -27;
+    assert (2 as T).checked_pow(bits - 1).is_none();
+    assert (-4 as T).lcm(&6) == 12;
+}
+
+#[test]
+fn test_checked_num() {
+    use num::CheckedNum;
+
+    fn product<N: CheckedNum Copy>(xs: &[N], one: N) -> Option<N> {
+        let mut acc = one;
+        for xs.each |x| {
+            match acc.checked_mul(x) {
+                Some(n) => acc = n,
+                None => return None
+            }
+        }
+        Some(acc)
+    }
+
+    assert product(~[-2 as T, 3, -4], 1) == Some(24);
+    assert product(~[min_value, -1], 1).is_none();
+    assert (max_value).checked_add(&1).is_none();
+    assert (min_value).checked_sub(&1).is_none();
+    assert (max_value).saturating_add(&1) == max_value;
+    assert (min_value).saturating_sub(&1) == min_value;
+}
+
 #[test]
 fn test_to_str() {
     assert (to_str_radix(0 as T, 10u) == ~"0");
@@ -465,6 +913,61 @@ pub fn test_ranges() {
     }
 }
 
+#[test]
+pub fn test_range_step_uneven() {
+    let mut l = ~[];
+    for range_step(-5,5,3) |i| { l.push(i); }
+    assert l == ~[-5,-2,1,4];
+
+    let mut l = ~[];
+    for range_step(-6,3,3) |i| { l.push(i); }
+    assert l == ~[-6,-3,0];
+
+    let mut l = ~[];
+    for range_step(5,-5,-3) |i| { l.push(i); }
+    assert l == ~[5,2,-1,-4];
+
+    let mut l = ~[];
+    for range_step(0,50,100) |i| { l.push(i); }
+    assert l == ~[0];
+}
+
+#[test]
+pub fn test_range_step_bounds() {
+    // Steps that would carry past either bound must stop instead of
+    // wrapping around
+    let mut l = ~[];
+    for range_step(max_value - 5, max_value, 2) |i| { l.push(i); }
+    assert l == ~[max_value - 5, max_value - 3, max_value - 1];
+
+    let mut l = ~[];
+    for range_step(max_value - 5, max_value, max_value) |i| { l.push(i); }
+    assert l == ~[max_value - 5];
+
+    let mut l = ~[];
+    for range_step(min_value + 5, min_value, -2) |i| { l.push(i); }
+    assert l == ~[min_value + 5, min_value + 3, min_value + 1];
+
+    let mut l = ~[];
+    for range_step(min_value + 5, min_value, min_value) |i| { l.push(i); }
+    assert l == ~[min_value + 5];
+
+    // Across the whole range in the largest steps
+    let mut l = ~[];
+    for range_step(min_value, max_value, max_value) |i| { l.push(i); }
+    assert l == ~[min_value, -1, max_value - 1];
+
+    let mut l = ~[];
+    for range_step(max_value, min_value, min_value) |i| { l.push(i); }
+    assert l == ~[max_value, -1];
+
+    let mut n = 0;
+    for range(max_value - 3, max_value) |_i| { n += 1; }
+    assert n == 3;
+    for range_rev(min_value + 3, min_value) |_i| { n += 1; }
+    assert n == 6;
+}
+
 #[test]
 #[should_fail]
 #[ignore(cfg(windows))]
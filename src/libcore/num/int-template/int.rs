@@ -10,42 +10,20 @@
 
 //! Operations and constants for `int`
 
-pub use self::inst::pow;
-
 mod inst {
     pub type T = int;
     pub const bits: uint = ::uint::bits;
 
-    /// Returns `base` raised to the power of `exponent`
-    pub pure fn pow(base: int, exponent: uint) -> int {
-        if exponent == 0u {
-            //Not mathemtically true if ~[base == 0]
-            return 1;
-        }
-        if base == 0 { return 0; }
-        let mut my_pow  = exponent;
-        let mut acc     = 1;
-        let mut multiplier = base;
-        while(my_pow > 0u) {
-            if my_pow % 2u == 1u {
-                acc *= multiplier;
-            }
-            my_pow     /= 2u;
-            multiplier *= multiplier;
-        }
-        return acc;
-    }
-
     #[test]
     fn test_pow() {
-        assert (pow(0, 0u) == 1);
-        assert (pow(0, 1u) == 0);
-        assert (pow(0, 2u) == 0);
-        assert (pow(-1, 0u) == 1);
-        assert (pow(1, 0u) == 1);
-        assert (pow(-3, 2u) == 9);
-        assert (pow(-3, 3u) == -27);
-        assert (pow(4, 9u) == 262144);
+        assert (::int::pow(0, 0u) == 1);
+        assert (::int::pow(0, 1u) == 0);
+        assert (::int::pow(0, 2u) == 0);
+        assert (::int::pow(-1, 0u) == 1);
+        assert (::int::pow(1, 0u) == 1);
+        assert (::int::pow(-3, 2u) == 9);
+        assert (::int::pow(-3, 3u) == -27);
+        assert (::int::pow(4, 9u) == 262144);
     }
 
     #[test]
@@ -11,9 +11,11 @@
 //! An interface for numeric types
 use core::cmp::{Ord, Eq};
 use option::{None, Option, Some};
+use result::{Result, Ok, Err};
 use char;
 use str;
 use kinds::Copy;
+use to_str::ToStr;
 use vec;
 
 pub trait Num {
@@ -50,6 +52,33 @@ pub trait Round {
     pure fn fract(&self) -> Self;
 }
 
+/// Arithmetic that detects overflow instead of wrapping around
+pub trait CheckedNum {
+    /// Adds, returning `None` if the result would overflow
+    pure fn checked_add(&self, other: &Self) -> Option<Self>;
+    /// Subtracts, returning `None` if the result would overflow
+    pure fn checked_sub(&self, other: &Self) -> Option<Self>;
+    /// Multiplies, returning `None` if the result would overflow
+    pure fn checked_mul(&self, other: &Self) -> Option<Self>;
+
+    /// Adds, clamping the result to the bounds of the type
+    pure fn saturating_add(&self, other: &Self) -> Self;
+    /// Subtracts, clamping the result to the bounds of the type
+    pure fn saturating_sub(&self, other: &Self) -> Self;
+}
+
+/// Powers and divisibility for the integer types
+pub trait Integer {
+    /// Raises to the power of `exp`, failing on overflow
+    pure fn pow(&self, exp: uint) -> Self;
+    /// Raises to the power of `exp`, returning `None` on overflow
+    pure fn checked_pow(&self, exp: uint) -> Option<Self>;
+    /// The greatest common divisor, which is never negative
+    pure fn gcd(&self, other: &Self) -> Self;
+    /// The least common multiple, which is never negative
+    pure fn lcm(&self, other: &Self) -> Self;
+}
+
 pub enum RoundMode {
     RoundDown,
     RoundUp,
@@ -686,3 +715,96 @@ pub pure fn from_str_common<T: Num Zero One Ord Copy>(
     from_str_bytes_common(str::to_bytes(buf), radix, negative,
                             fractional, special, exponent, empty_zero)
 }
+
+/// The reason an integer could not be parsed by `from_str_int_common()`
+#[deriving_eq]
+pub enum ParseIntErr {
+    /// There were no digits, only possibly a sign
+    Empty,
+    /// The char at the given byte position is not a digit in the radix
+    InvalidDigit(char, uint),
+    /// The number does not fit in the integer type
+    Overflow
+}
+
+impl ParseIntErr: ToStr {
+    pure fn to_str(&self) -> ~str {
+        match *self {
+            Empty => ~"no digits to parse",
+            InvalidDigit(c, pos) => {
+                fmt!("invalid digit '%s' at position %u",
+                     str::from_char(c), pos)
+            }
+            Overflow => ~"number too large to fit in the target type"
+        }
+    }
+}
+
+/**
+ * Parses a string as an integer, reporting why it could not be parsed.
+ * This is meant to be a common base implementation for the integer
+ * `from_str_result()` and `from_str_radix_result()` functions.
+ *
+ * # Arguments
+ * - `buf`      - The string to parse.
+ * - `radix`    - Which base to parse the number as. Accepts 2-36.
+ * - `negative` - Whether to accept a leading `-`. A leading `+` is always
+ *                accepted.
+ *
+ * # Return value
+ * Returns `Ok(n)` if `buf` parses to the integer n, `Err(Overflow)` if n
+ * lies outside the range of `T`, and `Err(Empty)` or `Err(InvalidDigit)`
+ * if `buf` is not a number at all.
+ *
+ * # Failure
+ * - Fails if `radix` < 2 or `radix` > 36.
+ */
+pub pure fn from_str_int_common<T: Num Zero One Eq Ord Copy>(
+        buf: &str, radix: uint, negative: bool) -> Result<T, ParseIntErr> {
+    if radix < 2u || radix > 36u {
+        die!(fmt!("from_str_int_common: radix %? out of range, \
+                   must lie in the range [2, 36]", radix));
+    }
+
+    let _0: T = Zero::zero();
+    let radix_gen: T = Num::from_int(radix as int);
+
+    let len = buf.len();
+    let (start, positive) = if len == 0u {
+        (0u, true)
+    } else {
+        match buf[0] {
+            '-' as u8 if negative => (1u, false),
+            '+' as u8 => (1u, true),
+            _ => (0u, true)
+        }
+    };
+    if start == len { return Err(Empty); }
+
+    // Negative numbers are accumulated downwards so that the minimum
+    // value of a signed type, which has no positive counterpart, parses.
+    let mut accum = _0;
+    let mut i = start;
+    while i < len {
+        let str::CharRange {ch, next} = str::char_range_at(buf, i);
+        let digit: T = match char::to_digit(ch, radix) {
+            Some(d) => Num::from_int(d as int),
+            None => return Err(InvalidDigit(ch, i))
+        };
+
+        // The product wrapped around iff dividing it back out does not
+        // give the old value
+        let shifted = accum * radix_gen;
+        if shifted / radix_gen != accum { return Err(Overflow); }
+
+        if positive {
+            accum = shifted + digit;
+            if accum < shifted { return Err(Overflow); }
+        } else {
+            accum = shifted - digit;
+            if accum > shifted { return Err(Overflow); }
+        }
+        i = next;
+    }
+    Ok(accum)
+}
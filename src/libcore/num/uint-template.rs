@@ -17,6 +17,7 @@ use cmp;
 use to_str::ToStr;
 use from_str::FromStr;
 use num::{ToStrRadix, FromStrRadix};
+use num::ParseIntErr;
 use num;
 use option::{None, Option, Some};
 use prelude::*;
@@ -75,6 +76,13 @@ pub pure fn is_nonnegative(x: T) -> bool { x >= 0 as T }
 /**
  * Iterate over the range [`start`,`start`+`step`..`stop`)
  *
+ * A negative `step` counts down from `start` to just above `stop`. The
+ * last value is the last one short of `stop`, even if the step after it
+ * would go past `max_value` or below 0.
+ *
+ * # Failure
+ *
+ * Fails if `step` is 0.
  */
 pub pure fn range_step(start: T, stop: T, step: T_SIGNED, it: fn(T) -> bool) {
     let mut i = start;
@@ -82,15 +90,22 @@ pub pure fn range_step(start: T, stop: T, step: T_SIGNED, it: fn(T) -> bool) {
         die!(~"range_step called with step == 0");
     }
     if step >= 0 {
+        let step = step as T;
         while i < stop {
             if !it(i) { break }
-            i += step as T;
+            // Checking the distance left, rather than the next value,
+            // cannot wrap around
+            if stop - i <= step { break }
+            i += step;
         }
     }
     else {
+        // For the most negative step this wraps to its magnitude
+        let step = -step as T;
         while i > stop {
             if !it(i) { break }
-            i -= -step as T;
+            if i - stop <= step { break }
+            i -= step;
         }
     }
 }
@@ -113,6 +128,110 @@ pub pure fn compl(i: T) -> T {
     max_value ^ i
 }
 
+/// Adds `x` and `y`, returning `None` if the sum exceeds `max_value`
+#[inline(always)]
+pub pure fn checked_add(x: T, y: T) -> Option<T> {
+    if x > max_value - y { None } else { Some(x + y) }
+}
+
+/// Subtracts `y` from `x`, returning `None` if `y` is the larger
+#[inline(always)]
+pub pure fn checked_sub(x: T, y: T) -> Option<T> {
+    if x < y { None } else { Some(x - y) }
+}
+
+/// Multiplies `x` and `y`, returning `None` if the product exceeds
+/// `max_value`
+#[inline(always)]
+pub pure fn checked_mul(x: T, y: T) -> Option<T> {
+    if y != 0 && x > max_value / y { None } else { Some(x * y) }
+}
+
+/// Adds `x` and `y`, giving `max_value` if the sum would exceed it
+#[inline(always)]
+pub pure fn saturating_add(x: T, y: T) -> T {
+    if x > max_value - y { max_value } else { x + y }
+}
+
+/// Subtracts `y` from `x`, giving 0 if `y` is the larger
+#[inline(always)]
+pub pure fn saturating_sub(x: T, y: T) -> T {
+    if x < y { 0 } else { x - y }
+}
+
+/**
+ * Raises `base` to the power of `exp`, returning `None` if the result
+ * does not fit in the type
+ *
+ * `x` to the power of 0 is 1 for every `x`, including 0.
+ */
+pub pure fn checked_pow(base: T, exp: uint) -> Option<T> {
+    let mut acc: T = 1, base = base, exp = exp;
+    // Binary exponentiation. Squaring only when a higher bit of `exp`
+    // remains means that an overflowing square is always an overflow of
+    // the result.
+    while exp > 0 {
+        if exp & 1 == 1 {
+            match checked_mul(acc, base) {
+                Some(n) => acc = n,
+                None => return None
+            }
+        }
+        exp >>= 1;
+        if exp > 0 {
+            match checked_mul(base, base) {
+                Some(n) => base = n,
+                None => return None
+            }
+        }
+    }
+    Some(acc)
+}
+
+/**
+ * Raises `base` to the power of `exp`
+ *
+ * `x` to the power of 0 is 1 for every `x`, including 0.
+ *
+ * # Failure
+ *
+ * Fails if the result does not fit in the type.
+ */
+pub pure fn pow(base: T, exp: uint) -> T {
+    match checked_pow(base, exp) {
+        Some(n) => n,
+        None => die!(fmt!("pow: %s to the power of %u overflows",
+                          to_str(base), exp))
+    }
+}
+
+/// Computes the greatest common divisor, with `gcd(0, 0) == 0`
+pub pure fn gcd(a: T, b: T) -> T {
+    let mut a = a, b = b;
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/**
+ * Computes the least common multiple, which is 0 if either argument is
+ *
+ * # Failure
+ *
+ * Fails if the result does not fit in the type.
+ */
+pub pure fn lcm(a: T, b: T) -> T {
+    if a == 0 || b == 0 { return 0; }
+    match checked_mul(a / gcd(a, b), b) {
+        Some(n) => n,
+        None => die!(fmt!("lcm: lcm(%s, %s) overflows",
+                          to_str(a), to_str(b)))
+    }
+}
+
 #[cfg(notest)]
 impl T : Ord {
     #[inline(always)]
@@ -163,6 +282,42 @@ impl T: num::One {
     static pure fn one() -> T { 1 }
 }
 
+impl T: num::Integer {
+    #[inline(always)]
+    pure fn pow(&self, exp: uint) -> T { pow(*self, exp) }
+    #[inline(always)]
+    pure fn checked_pow(&self, exp: uint) -> Option<T> {
+        checked_pow(*self, exp)
+    }
+    #[inline(always)]
+    pure fn gcd(&self, other: &T) -> T { gcd(*self, *other) }
+    #[inline(always)]
+    pure fn lcm(&self, other: &T) -> T { lcm(*self, *other) }
+}
+
+impl T: num::CheckedNum {
+    #[inline(always)]
+    pure fn checked_add(&self, other: &T) -> Option<T> {
+        checked_add(*self, *other)
+    }
+    #[inline(always)]
+    pure fn checked_sub(&self, other: &T) -> Option<T> {
+        checked_sub(*self, *other)
+    }
+    #[inline(always)]
+    pure fn checked_mul(&self, other: &T) -> Option<T> {
+        checked_mul(*self, *other)
+    }
+    #[inline(always)]
+    pure fn saturating_add(&self, other: &T) -> T {
+        saturating_add(*self, *other)
+    }
+    #[inline(always)]
+    pure fn saturating_sub(&self, other: &T) -> T {
+        saturating_sub(*self, *other)
+    }
+}
+
 impl T: num::Round {
     #[inline(always)]
     pure fn round(&self, _: num::RoundMode) -> T { *self }
@@ -177,18 +332,49 @@ impl T: num::Round {
 
 // String conversion functions and impl str -> num
 
+/**
+ * Parse a string as a number in base 10, reporting why it could not be
+ * parsed.
+ *
+ * Accepts an optional leading `+`.
+ */
+#[inline(always)]
+pub pure fn from_str_result(s: &str) -> Result<T, ParseIntErr> {
+    num::from_str_int_common(s, 10u, false)
+}
+
+/**
+ * Parse a string as a number in the given base, reporting why it could
+ * not be parsed.
+ *
+ * Accepts an optional leading `+`.
+ *
+ * # Failure
+ *
+ * Fails if `radix` < 2 or `radix` > 36.
+ */
+#[inline(always)]
+pub pure fn from_str_radix_result(s: &str, radix: uint)
+    -> Result<T, ParseIntErr> {
+    num::from_str_int_common(s, radix, false)
+}
+
 /// Parse a string as a number in base 10.
 #[inline(always)]
 pub pure fn from_str(s: &str) -> Option<T> {
-    num::from_str_common(s, 10u, false, false, false,
-                         num::ExpNone, false)
+    match from_str_result(s) {
+        Ok(n) => Some(n),
+        Err(_) => None
+    }
 }
 
 /// Parse a string as a number in the given base.
 #[inline(always)]
 pub pure fn from_str_radix(s: &str, radix: uint) -> Option<T> {
-    num::from_str_common(s, radix, false, false, false,
-                         num::ExpNone, false)
+    match from_str_radix_result(s, radix) {
+        Ok(n) => Some(n),
+        Err(_) => None
+    }
 }
 
 /// Parse a byte slice as a number in the given base.
@@ -257,6 +443,137 @@ impl T : ToStrRadix {
     }
 }
 
+#[test]
+pub fn test_checked_arith() {
+    assert checked_add(max_value - 1, 1) == Some(max_value);
+    assert checked_add(max_value, 0) == Some(max_value);
+    assert checked_add(max_value, 1).is_none();
+    assert checked_add(1, max_value).is_none();
+    assert checked_add(max_value, max_value).is_none();
+
+    assert checked_sub(1, 1) == Some(0);
+    assert checked_sub(max_value, max_value) == Some(0);
+    assert checked_sub(0, 1).is_none();
+    assert checked_sub(min_value, max_value).is_none();
+
+    let half = max_value / 2 + 1;
+    assert checked_mul(max_value, 1) == Some(max_value);
+    assert checked_mul(max_value, 0) == Some(0);
+    assert checked_mul(0, max_value) == Some(0);
+    assert checked_mul(half - 1, 2) == Some(max_value - 1);
+    assert checked_mul(half, 2).is_none();
+    assert checked_mul(2, half).is_none();
+    assert checked_mul(max_value, max_value).is_none();
+
+    assert saturating_add(max_value - 1, 1) == max_value;
+    assert saturating_add(max_value, 1) == max_value;
+    assert saturating_add(max_value, max_value) == max_value;
+    assert saturating_sub(1, 1) == 0;
+    assert saturating_sub(0, 1) == 0;
+    assert saturating_sub(0, max_value) == 0;
+}
+
+#[test]
+pub fn test_pow() {
+    assert pow(0, 0) == 1;
+    assert pow(0, 1) == 0;
+    assert pow(0, 7) == 0;
+    assert pow(1, 1000) == 1;
+    assert pow(7, 0) == 1;
+    assert pow(2, 7) == 128;
+    assert pow(3, 4) == 81;
+    assert pow(2, bits - 1) == max_value / 2 + 1;
+    assert checked_pow(2, bits - 1) == Some(max_value / 2 + 1);
+    assert checked_pow(2, bits).is_none();
+    assert checked_pow(max_value, 1) == Some(max_value);
+    assert checked_pow(max_value, 2).is_none();
+    assert checked_pow(15, 2) == Some(225);
+}
+
+#[test]
+#[should_fail]
+#[ignore(cfg(windows))]
+pub fn test_pow_overflow() {
+    pow(2, bits);
+}
+
+#[test]
+pub fn test_gcd_lcm() {
+    assert gcd(0, 0) == 0;
+    assert gcd(0, 9) == 9;
+    assert gcd(9, 0) == 9;
+    assert gcd(12, 18) == 6;
+    assert gcd(17, 5) == 1;
+    assert gcd(max_value, max_value) == max_value;
+    assert gcd(max_value, 1) == 1;
+
+    assert lcm(0, 9) == 0;
+    assert lcm(9, 0) == 0;
+    assert lcm(4, 6) == 12;
+    assert lcm(max_value, 1) == max_value;
+    assert lcm(max_value, max_value) == max_value;
+
+    for range(0, 60) |a| {
+        for range(0, 60) |b| {
+            let g = gcd(a, b);
+            assert g == gcd(b, a);
+            if g != 0 {
+                assert a % g == 0 && b % g == 0;
+                assert gcd(a / g, b / g) == 1;
+            }
+            match checked_mul(a, b) {
+                Some(p) => assert lcm(a, b) * g == p,
+                None => ()
+            }
+        }
+    }
+}
+
+#[test]
+#[should_fail]
+#[ignore(cfg(windows))]
+pub fn test_lcm_overflow() {
+    lcm(max_value, max_value - 1);
+}
+
+#[test]
+pub fn test_integer() {
+    use num::Integer;
+
+    fn reduce<N: Integer num::Num Copy>(n: N, d: N) -> (N, N) {
+        let g = n.gcd(&d);
+        (n.div(&g), d.div(&g))
+    }
+
+    assert reduce(12 as T, 18) == (2, 3);
+    assert (3 as T).pow(2) == 9;
+    assert (2 as T).checked_pow(bits).is_none();
+    assert (4 as T).lcm(&6) == 12;
+}
+
+#[test]
+pub fn test_checked_num() {
+    use num::CheckedNum;
+
+    fn sum<N: CheckedNum Copy>(xs: &[N], zero: N) -> Option<N> {
+        let mut acc = zero;
+        for xs.each |x| {
+            match acc.checked_add(x) {
+                Some(n) => acc = n,
+                None => return None
+            }
+        }
+        Some(acc)
+    }
+
+    assert sum(~[1 as T, 2, 3], 0) == Some(6);
+    assert sum(~[max_value, 1], 0).is_none();
+    assert (max_value).checked_mul(&2).is_none();
+    assert (0 as T).checked_sub(&1).is_none();
+    assert (max_value).saturating_add(&1) == max_value;
+    assert (0 as T).saturating_sub(&1) == 0;
+}
+
 #[test]
 pub fn test_to_str() {
     assert to_str_radix(0 as T, 10u) == ~"0";
@@ -295,6 +612,47 @@ pub fn test_parse_bytes() {
     assert parse_bytes(to_bytes(~"_"), 2u).is_none();
 }
 
+#[test]
+pub fn test_from_str_result() {
+    use num::{Empty, InvalidDigit, Overflow};
+
+    assert from_str_result(~"0") == Ok(0u as T);
+    assert from_str_result(~"+42") == Ok(42u as T);
+    assert from_str_result(~"") == Err(Empty);
+    assert from_str_result(~"+") == Err(Empty);
+    assert from_str_result(~"-1") == Err(InvalidDigit('-', 0u));
+    assert from_str_result(~" 1") == Err(InvalidDigit(' ', 0u));
+    assert from_str_result(~"12x") == Err(InvalidDigit('x', 2u));
+    assert from_str_result(~"1é2") == Err(InvalidDigit('é', 1u));
+
+    assert from_str_radix_result(~"1001", 2u) == Ok(9u as T);
+    assert from_str_radix_result(~"1002", 2u) == Err(InvalidDigit('2', 3u));
+    assert from_str_radix_result(~"7F", 16u) == Ok(127u as T);
+    assert from_str_radix_result(~"z", 36u) == Ok(35u as T);
+    assert from_str_radix_result(~"Z", 36u) == Ok(35u as T);
+
+    // The bound parses in every radix, and one more digit overflows
+    for uint::range(2u, 37u) |radix| {
+        let max = to_str_radix(max_value, radix);
+        assert from_str_radix_result(max, radix) == Ok(max_value);
+        assert from_str_radix_result(max + ~"0", radix) == Err(Overflow);
+    }
+
+    // Exactly one past the bound
+    let max = to_str(max_value);
+    assert from_str_result(max) == Ok(max_value);
+    let past = to_str(max_value / 10) + to_str(max_value % 10 + 1);
+    assert from_str_result(past) == Err(Overflow);
+    assert from_str(past).is_none();
+}
+
+#[test]
+#[should_fail]
+#[ignore(cfg(windows))]
+pub fn test_from_str_radix_result_bad_radix() {
+    from_str_radix_result(~"1", 37u);
+}
+
 #[test]
 fn test_uint_to_str_overflow() {
     let mut u8_val: u8 = 255_u8;
@@ -409,6 +767,66 @@ pub fn test_ranges() {
     }
 }
 
+#[test]
+pub fn test_range_step_uneven() {
+    let mut l = ~[];
+    for range_step(0,10,3) |i| { l.push(i); }
+    assert l == ~[0,3,6,9];
+
+    let mut l = ~[];
+    for range_step(0,9,3) |i| { l.push(i); }
+    assert l == ~[0,3,6];
+
+    let mut l = ~[];
+    for range_step(10,0,-3) |i| { l.push(i); }
+    assert l == ~[10,7,4,1];
+
+    let mut l = ~[];
+    for range_step(9,0,-3) |i| { l.push(i); }
+    assert l == ~[9,6,3];
+
+    let mut l = ~[];
+    for range_step(0,50,100) |i| { l.push(i); }
+    assert l == ~[0];
+}
+
+#[test]
+pub fn test_range_step_bounds() {
+    // Steps that would carry past max_value or below 0 must stop instead
+    // of wrapping around
+    let mut l = ~[];
+    for range_step(max_value - 5, max_value, 2) |i| { l.push(i); }
+    assert l == ~[max_value - 5, max_value - 3, max_value - 1];
+
+    let mut l = ~[];
+    for range_step(max_value - 5, max_value, 10) |i| { l.push(i); }
+    assert l == ~[max_value - 5];
+
+    let mut l = ~[];
+    for range_step(5, 0, -2) |i| { l.push(i); }
+    assert l == ~[5, 3, 1];
+
+    let mut l = ~[];
+    for range_step(5, 0, -10) |i| { l.push(i); }
+    assert l == ~[5];
+
+    // The largest steps in both directions
+    let big = (max_value / 2) as T_SIGNED;
+    let mut l = ~[];
+    for range_step(0, max_value, big) |i| { l.push(i); }
+    assert l == ~[0, big as T, (big as T) * 2];
+
+    let mut l = ~[];
+    for range_step(max_value, 0, -big - 1) |i| { l.push(i); }
+    assert l == ~[max_value, max_value / 2];
+
+    let mut n = 0;
+    for range(max_value - 3, max_value) |_i| { n += 1; }
+    assert n == 3;
+    for range_rev(3, 0) |_i| { n += 1; }
+    assert n == 6;
+}
+
 #[test]
 #[should_fail]
 #[ignore(cfg(windows))]
@@ -41,7 +41,8 @@ let unwrapped_msg = match move msg {
 
 */
 
-use cmp::Eq;
+use clone::Clone;
+use cmp::{Eq, Ord};
 use kinds::Copy;
 use option;
 use ptr;
@@ -56,6 +57,36 @@ pub enum Option<T> {
     Some(T),
 }
 
+impl<T: Clone> Option<T>: Clone {
+    #[inline(always)]
+    fn clone(&self) -> Option<T> {
+        match *self {
+            Some(ref x) => Some(x.clone()),
+            None => None
+        }
+    }
+}
+
+// `None` orders before any `Some`
+impl<T: Ord> Option<T> : Ord {
+    #[inline(always)]
+    pure fn lt(&self, other: &Option<T>) -> bool {
+        match *self {
+            None => is_some(other),
+            Some(ref a) => match *other {
+                None => false,
+                Some(ref b) => (*a).lt(b)
+            }
+        }
+    }
+    #[inline(always)]
+    pure fn le(&self, other: &Option<T>) -> bool { !(*other).lt(self) }
+    #[inline(always)]
+    pure fn ge(&self, other: &Option<T>) -> bool { !(*self).lt(other) }
+    #[inline(always)]
+    pure fn gt(&self, other: &Option<T>) -> bool { (*other).lt(self) }
+}
+
 #[inline(always)]
 pub pure fn get<T: Copy>(opt: Option<T>) -> T {
     /*!
@@ -186,10 +217,88 @@ pub pure fn get_or_zero<T: Copy Zero>(opt: Option<T>) -> T {
 }
 
 #[inline(always)]
-pub pure fn get_or_default<T: Copy>(opt: Option<T>, def: T) -> T {
-    //! Returns the contained value or a default
+pub pure fn get_or_default<T>(opt: Option<T>, def: T) -> T {
+    /*!
+     * Returns the contained value or a default
+     *
+     * # Example
+     *
+     * ~~~
+     * assert Some(~"a").get_or_default(~"b") == ~"a";
+     * assert None.get_or_default(~"b") == ~"b";
+     * ~~~
+     */
+
+    match move opt { Some(move x) => move x, None => move def }
+}
+
+#[inline(always)]
+pub pure fn get_or_else<T>(opt: Option<T>, f: fn() -> T) -> T {
+    /*!
+     * Returns the contained value or computes a default
+     *
+     * Unlike `get_or_default`, `f` is only called if the option is `None`.
+     *
+     * # Example
+     *
+     * ~~~
+     * let port = opt_port.get_or_else(|| lookup_default_port());
+     * ~~~
+     */
 
-    match opt { Some(copy x) => x, None => def }
+    match move opt { Some(move x) => move x, None => f() }
+}
+
+#[inline(always)]
+pub pure fn filtered<T>(opt: Option<T>,
+                        pred: fn(x: &T) -> bool) -> Option<T> {
+    /*!
+     * Returns the option if it contains a value satisfying `pred`,
+     * otherwise `None`
+     *
+     * # Example
+     *
+     * ~~~
+     * assert Some(4).filtered(|x| *x % 2 == 0) == Some(4);
+     * assert Some(3).filtered(|x| *x % 2 == 0) == None;
+     * ~~~
+     */
+
+    match move opt {
+        Some(move x) => if pred(&x) { Some(move x) } else { None },
+        None => None
+    }
+}
+
+#[inline(always)]
+pub pure fn filtered_ref<T>(opt: &r/Option<T>,
+                            pred: fn(x: &T) -> bool) -> Option<&r/T> {
+    //! As `filtered`, but borrows the contained value instead of moving it
+
+    match *opt {
+        Some(ref x) => if pred(x) { Some(x) } else { None },
+        None => None
+    }
+}
+
+#[inline(always)]
+pub pure fn zip<T, U>(opta: Option<T>, optb: Option<U>) -> Option<(T, U)> {
+    /*!
+     * Pairs the values of two options, or returns `None` if either is
+     * `None`
+     *
+     * # Example
+     *
+     * ~~~
+     * assert Some(1).zip(Some(~"one")) == Some((1, ~"one"));
+     * assert Some(1).zip(None::<~str>) == None;
+     * ~~~
+     */
+
+    match (move opta, move optb) {
+        (Some(move a), Some(move b)) => Some((move a, move b)),
+        _ => None
+    }
 }
 
 #[inline(always)]
@@ -253,6 +362,15 @@ pub pure fn expect<T>(opt: Option<T>, reason: &str) -> T {
     }
 }
 
+#[inline(always)]
+pub pure fn expect_none<T>(opt: Option<T>, reason: &str) {
+    //! Fails with a specified message if the option holds a value.
+    match move opt {
+        Some(_) => die!(reason.to_owned()),
+        None => (),
+    }
+}
+
 impl<T> Option<T> {
     /// Returns true if the option equals `none`
     #[inline(always)]
@@ -295,6 +413,33 @@ impl<T> Option<T> {
         match self { None => def, Some(v) => f(v) }
     }
 
+    /// Returns the contained value or a default
+    #[inline(always)]
+    pure fn get_or_default(self, def: T) -> T { get_or_default(self, def) }
+
+    /// Returns the contained value or computes a default
+    #[inline(always)]
+    pure fn get_or_else(self, f: fn() -> T) -> T { get_or_else(self, f) }
+
+    /// Returns the option if its value satisfies `pred`, otherwise `None`
+    #[inline(always)]
+    pure fn filtered(self, pred: fn(x: &T) -> bool) -> Option<T> {
+        filtered(self, pred)
+    }
+
+    /// As `filtered`, but borrows the contained value
+    #[inline(always)]
+    pure fn filtered_ref(&self, pred: fn(x: &T) -> bool) -> Option<&self/T> {
+        filtered_ref(self, pred)
+    }
+
+    /// Pairs the values of two options, or returns `None` if either is
+    /// `None`
+    #[inline(always)]
+    pure fn zip<U>(self, other: Option<U>) -> Option<(T, U)> {
+        zip(self, other)
+    }
+
     /// Apply a function to the contained value or do nothing
     fn mutate(&mut self, f: fn(T) -> T) {
         if self.is_some() {
@@ -363,6 +508,17 @@ impl<T> Option<T> {
      */
     #[inline(always)]
     pure fn expect(self, reason: &str) -> T { expect(self, reason) }
+
+    /**
+     * Asserts that the option is empty, printing a specified message on
+     * failure
+     *
+     * # Failure
+     *
+     * Fails if the value equals `Some`
+     */
+    #[inline(always)]
+    pure fn expect_none(self, reason: &str) { expect_none(self, reason) }
 }
 
 impl<T: Copy> Option<T> {
@@ -383,9 +539,6 @@ impl<T: Copy> Option<T> {
     #[inline(always)]
     pure fn get(self) -> T { get(self) }
 
-    #[inline(always)]
-    pure fn get_or_default(self, def: T) -> T { get_or_default(self, def) }
-
     /// Applies a function zero or more times until the result is none.
     #[inline(always)]
     pure fn while_some(self, blk: fn(v: T) -> Option<T>) {
@@ -458,6 +611,22 @@ fn test_option_too_much_dance() {
     let _y3 = swap_unwrap(&mut y);
 }
 
+#[test]
+fn test_expect() {
+    assert Some(~"ok").expect("no value") == ~"ok";
+    None::<int>.expect_none("a value");
+}
+
+#[test] #[should_fail] #[ignore(cfg(windows))]
+fn test_expect_fail() {
+    None::<int>.expect("no value");
+}
+
+#[test] #[should_fail] #[ignore(cfg(windows))]
+fn test_expect_none_fail() {
+    Some(1).expect_none("a value");
+}
+
 #[test]
 fn test_option_while_some() {
     let mut i = 0;
@@ -480,6 +649,71 @@ fn test_get_or_zero() {
     assert no_stuff.get_or_zero() == 0;
 }
 
+#[test]
+fn test_get_or_default_noncopyable() {
+    let x = ~"some";
+    let addr_x = str::as_buf(x, |buf, _len| buf);
+    let y = Some(move x).get_or_default(~"default");
+    assert str::as_buf(y, |buf, _len| buf) == addr_x;
+
+    let none: Option<~str> = None;
+    assert none.get_or_default(~"default") == ~"default";
+}
+
+#[test]
+fn test_get_or_else() {
+    let mut calls = 0;
+    assert Some(~"some").get_or_else(|| { calls += 1; ~"else" }) == ~"some";
+    assert calls == 0;
+
+    let none: Option<~str> = None;
+    assert none.get_or_else(|| { calls += 1; ~"else" }) == ~"else";
+    assert calls == 1;
+}
+
+#[test]
+fn test_filtered() {
+    assert Some(~4).filtered(|x| **x % 2 == 0) == Some(~4);
+    assert Some(~3).filtered(|x| **x % 2 == 0) == None;
+
+    let mut called = false;
+    let none: Option<~int> = None;
+    assert none.filtered(|_x| { called = true; true }) == None;
+    assert !called;
+}
+
+#[test]
+fn test_filtered_ref() {
+    let x = Some(~"x");
+    match x.filtered_ref(|s| s.len() == 1) {
+        Some(s) => assert *s == ~"x",
+        None => die!()
+    }
+    assert x.filtered_ref(|s| s.len() == 2).is_none();
+    assert x.is_some();
+
+    let none: Option<~str> = None;
+    assert none.filtered_ref(|_s| true).is_none();
+}
+
+#[test]
+fn test_zip() {
+    assert Some(1).zip(Some(~"one")) == Some((1, ~"one"));
+    assert Some(1).zip(None::<~str>) == None;
+    assert None::<int>.zip(Some(~"one")) == None;
+    assert None::<int>.zip(None::<~str>) == None;
+}
+
+#[test]
+fn test_ord() {
+    assert None < Some(0);
+    assert Some(1) < Some(2);
+    assert !(Some(2) < Some(2));
+    assert Some(~[1, 2]) > Some(~[1]);
+    assert None::<int> >= None;
+    assert Some(Some(0)) > Some(None);
+}
+
 // Local Variables:
 // mode: rust;
 // fill-column: 78;
@@ -66,6 +66,7 @@ extern mod rustrt {
     unsafe fn rust_process_wait(handle: c_int) -> c_int;
     unsafe fn last_os_error() -> ~str;
     unsafe fn rust_set_exit_status(code: libc::intptr_t);
+    unsafe fn rust_num_cpus() -> libc::uintptr_t;
 }
 
 pub const tmpbuf_sz : uint = 1000u;
@@ -139,12 +140,19 @@ pub mod win32 {
     }
 }
 
-/*
-Accessing environment variables is not generally threadsafe.
-This uses a per-runtime lock to serialize access.
-FIXME #4726: It would probably be appropriate to make this a real global
-*/
-fn with_env_lock<T>(f: &fn() -> T) -> T {
+/**
+ * Run `f` while holding the lock that serializes access to the process
+ * environment
+ *
+ * Accessing environment variables is not generally threadsafe, so
+ * `getenv`, `setenv`, `unsetenv` and `env` all take this lock. Code that
+ * reads or changes the environment through foreign functions should do
+ * so inside `with_env_lock` as well. The lock is not reentrant: `f` must
+ * not call any of the functions above.
+ */
+// FIXME #4726: This is a per-runtime lock. It would probably be
+// appropriate to make this a real global.
+pub fn with_env_lock<T>(f: &fn() -> T) -> T {
     use private::global::global_data_clone_create;
     use private::{Exclusive, exclusive};
 
@@ -198,10 +206,20 @@ pub fn getenv(n: &str) -> Option<~str> {
 pub fn getenv(n: &str) -> Option<~str> {
     unsafe {
         do with_env_lock {
+            use libc::types::os::arch::extra::DWORD;
             use os::win32::{as_utf16_p, fill_utf16_buf_and_decode};
             do as_utf16_p(n) |u| {
-                do fill_utf16_buf_and_decode() |buf, sz| {
+                // A variable set to the empty string also reads back as
+                // zero characters, but without setting an error
+                libc::SetLastError(0);
+                let v = do fill_utf16_buf_and_decode() |buf, sz| {
                     libc::GetEnvironmentVariableW(u, buf, sz)
+                };
+                match v {
+                    Some(move v) => Some(move v),
+                    None if libc::GetLastError() ==
+                        libc::ERROR_ENVVAR_NOT_FOUND as DWORD => None,
+                    None => Some(~"")
                 }
             }
         }
@@ -237,6 +255,31 @@ pub fn setenv(n: &str, v: &str) {
     }
 }
 
+/// Remove a variable from the environment. Does nothing if it is not set.
+#[cfg(unix)]
+pub fn unsetenv(n: &str) {
+    unsafe {
+        do with_env_lock {
+            do str::as_c_str(n) |nbuf| {
+                libc::funcs::posix01::unistd::unsetenv(nbuf);
+            }
+        }
+    }
+}
+
+/// Remove a variable from the environment. Does nothing if it is not set.
+#[cfg(windows)]
+pub fn unsetenv(n: &str) {
+    unsafe {
+        do with_env_lock {
+            use os::win32::as_utf16_p;
+            do as_utf16_p(n) |nbuf| {
+                libc::SetEnvironmentVariableW(nbuf, ptr::null());
+            }
+        }
+    }
+}
+
 pub fn fdopen(fd: c_int) -> *FILE {
     unsafe {
         return do as_c_charp("r") |modebuf| {
@@ -559,6 +602,102 @@ pub fn path_exists(p: &Path) -> bool {
     }
 }
 
+/// The kind of file a path refers to
+#[deriving_eq]
+pub enum FileKind {
+    File,
+    Dir,
+    Symlink,
+    Other,
+}
+
+/**
+ * Metadata about a file, as returned by `stat` and `lstat`
+ *
+ * Times are pairs of seconds and nanoseconds since the epoch, in the same
+ * form as the fields of `std::time::Timespec`.
+ */
+pub struct FileInfo {
+    size: u64,
+    kind: FileKind,
+    accessed: (i64, int),
+    modified: (i64, int),
+    /// The creation time, on platforms whose filesystems record it
+    created: Option<(i64, int)>,
+}
+
+/// Return metadata about the file a path refers to, following symlinks,
+/// or None if it can't be read
+pub fn stat(p: &Path) -> Option<FileInfo> {
+    p.stat().map(|st| file_info(st))
+}
+
+/// Return metadata about a path without following a symlink at the end
+/// of it, or None if it can't be read
+#[cfg(unix)]
+pub fn lstat(p: &Path) -> Option<FileInfo> {
+    p.lstat().map(|st| file_info(st))
+}
+
+#[cfg(unix)]
+fn file_kind(mode: uint) -> FileKind {
+    use libc::consts::os::posix88::{S_IFDIR, S_IFLNK, S_IFMT, S_IFREG};
+
+    let fmt = mode & (S_IFMT as uint);
+    if fmt == S_IFREG as uint { File }
+    else if fmt == S_IFDIR as uint { Dir }
+    else if fmt == S_IFLNK as uint { Symlink }
+    else { Other }
+}
+
+#[cfg(windows)]
+fn file_kind(mode: uint) -> FileKind {
+    use libc::consts::os::posix88::{S_IFDIR, S_IFMT, S_IFREG};
+
+    let fmt = mode & (S_IFMT as uint);
+    if fmt == S_IFREG as uint { File }
+    else if fmt == S_IFDIR as uint { Dir }
+    else { Other }
+}
+
+#[cfg(target_os = "linux")]
+#[cfg(target_os = "android")]
+fn file_info(st: &libc::stat) -> FileInfo {
+    FileInfo {
+        size: st.st_size as u64,
+        kind: file_kind(st.st_mode as uint),
+        accessed: (st.st_atime as i64, st.st_atime_nsec as int),
+        modified: (st.st_mtime as i64, st.st_mtime_nsec as int),
+        // st_ctime is the time of the last status change instead
+        created: None,
+    }
+}
+
+#[cfg(target_os = "freebsd")]
+#[cfg(target_os = "macos")]
+fn file_info(st: &libc::stat) -> FileInfo {
+    FileInfo {
+        size: st.st_size as u64,
+        kind: file_kind(st.st_mode as uint),
+        accessed: (st.st_atime as i64, st.st_atime_nsec as int),
+        modified: (st.st_mtime as i64, st.st_mtime_nsec as int),
+        created: Some((st.st_birthtime as i64,
+                       st.st_birthtime_nsec as int)),
+    }
+}
+
+#[cfg(target_os = "win32")]
+fn file_info(st: &libc::stat) -> FileInfo {
+    FileInfo {
+        size: st.st_size as u64,
+        kind: file_kind(st.st_mode as uint),
+        accessed: (st.st_atime as i64, 0),
+        modified: (st.st_mtime as i64, 0),
+        // Windows keeps the creation time in st_ctime
+        created: Some((st.st_ctime as i64, 0)),
+    }
+}
+
 /**
  * Convert a relative path to an absolute path
  *
@@ -655,6 +794,37 @@ pub fn remove_dir(p: &Path) -> bool {
     }
 }
 
+/**
+ * Deletes a directory and everything in it
+ *
+ * Symlinks inside the directory are removed rather than followed. Keeps
+ * going when something can't be removed, and returns false if anything
+ * was left behind.
+ */
+pub fn remove_dir_recursive(p: &Path) -> bool {
+    let mut ok = true;
+    for list_dir_path(p).each |child| {
+        let removed = if is_real_dir(*child) {
+            remove_dir_recursive(*child)
+        } else {
+            remove_file(*child)
+        };
+        ok = removed && ok;
+    }
+    return remove_dir(p) && ok;
+
+    #[cfg(unix)]
+    fn is_real_dir(p: &Path) -> bool {
+        match lstat(p) {
+            Some(ref info) => info.kind == Dir,
+            None => false
+        }
+    }
+
+    #[cfg(windows)]
+    fn is_real_dir(p: &Path) -> bool { path_is_dir(p) }
+}
+
 pub fn change_dir(p: &Path) -> bool {
     return chdir(p);
 
@@ -787,6 +957,19 @@ pub fn set_exit_status(code: int) {
     }
 }
 
+/**
+ * Returns the number of CPUs available to the process
+ *
+ * This is the number of online processors as reported by the OS when the
+ * runtime started. Unlike the default number
+ * of scheduler threads it is not affected by `RUST_THREADS`.
+ */
+pub fn num_cpus() -> uint {
+    unsafe {
+        rustrt::rust_num_cpus() as uint
+    }
+}
+
 unsafe fn load_argc_and_argv(argc: c_int, argv: **c_char) -> ~[~str] {
     let mut args = ~[];
     for uint::range(0, argc as uint) |i| {
@@ -986,12 +1169,13 @@ pub mod consts {
 #[allow(non_implicitly_copyable_typarams)]
 mod tests {
     use debug;
+    use io;
     use libc::{c_int, c_void, size_t};
     use libc;
     use option::{None, Option, Some};
     use option;
     use os::{as_c_charp, env, getcwd, getenv, make_absolute, real_args};
-    use os::{remove_file, setenv};
+    use os::{remove_file, setenv, unsetenv};
     use os;
     use path::Path;
     use rand;
@@ -1004,6 +1188,11 @@ mod tests {
         log(debug, os::last_os_error());
     }
 
+    #[test]
+    pub fn test_num_cpus() {
+        assert os::num_cpus() >= 1;
+    }
+
     #[test]
     pub fn test_args() {
         let a = real_args();
@@ -1090,6 +1279,26 @@ mod tests {
         assert vec::contains(e, &(move n, ~"VALUE"));
     }
 
+    #[test]
+    fn test_unsetenv() {
+        let n = make_rand_name();
+        setenv(n, ~"a=b=c");
+        assert getenv(n) == Some(~"a=b=c");
+        assert vec::contains(env(), &(copy n, ~"a=b=c"));
+
+        unsetenv(n);
+        assert getenv(n).is_none();
+        assert !vec::any(env(), |p| match *p { (ref k, _) => *k == n });
+
+        // Unsetting again is harmless, and the name can be reused
+        unsetenv(n);
+        setenv(n, ~"");
+        assert getenv(n) == Some(~"");
+        assert vec::contains(env(), &(copy n, ~""));
+        unsetenv(n);
+        assert getenv(n).is_none();
+    }
+
     #[test]
     fn test() {
         assert (!Path("test-path").is_absolute);
@@ -1177,6 +1386,77 @@ mod tests {
         assert (!os::path_exists(&Path("test/nonexistent-bogus-path")));
     }
 
+    #[test]
+    fn stat_dir_and_missing() {
+        match os::stat(&Path(".")) {
+            Some(info) => assert info.kind == os::Dir,
+            None => die!()
+        }
+        assert os::stat(&Path("test/nonexistent-bogus-path")).is_none();
+    }
+
+    fn write_bytes(p: &Path, n: uint) {
+        let wr = io::file_writer(p, [io::Create, io::Truncate]).get();
+        wr.write(vec::from_elem(n, 'x' as u8));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn stat_size_and_mtime() {
+        let dir = &os::tmpdir().push(make_rand_name());
+        assert os::make_dir(dir, 0x1c0);
+        let p = &dir.push("stat-test.tmp");
+        write_bytes(p, 12345);
+        let before = os::stat(p).get();
+        assert before.size == 12345;
+        assert before.kind == os::File;
+
+        // Make sure the clock ticks over even on coarse filesystems
+        unsafe { libc::sleep(1); }
+        write_bytes(p, 10);
+        let after = os::stat(p).get();
+        assert after.size == 10;
+        assert after.modified > before.modified;
+
+        let link = &dir.push("stat-test-link.tmp");
+        unsafe {
+            do as_c_charp(p.to_str()) |target| {
+                do as_c_charp(link.to_str()) |name| {
+                    assert libc::symlink(target, name) == 0;
+                }
+            }
+        }
+        assert os::lstat(link).get().kind == os::Symlink;
+        assert os::stat(link).get().size == 10;
+
+        assert os::remove_dir_recursive(dir);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn remove_dir_recursive_keeps_symlink_targets() {
+        let root = &getcwd().push("remove-dir-recursive-test.tmp");
+        let outside = &getcwd().push("remove-dir-recursive-outside.tmp");
+        assert os::make_dir(root, 0x1c0);
+        assert os::make_dir(&root.push("sub"), 0x1c0);
+        assert os::make_dir(outside, 0x1c0);
+        write_bytes(&root.push_many([~"sub", ~"f.txt"]), 1);
+        write_bytes(&outside.push("keep.txt"), 1);
+        unsafe {
+            do as_c_charp(outside.to_str()) |target| {
+                do as_c_charp(root.push("link").to_str()) |name| {
+                    assert libc::symlink(target, name) == 0;
+                }
+            }
+        }
+
+        assert os::remove_dir_recursive(root);
+        assert !os::path_exists(root);
+        assert os::path_exists(&outside.push("keep.txt"));
+        assert remove_file(&outside.push("keep.txt"));
+        assert os::remove_dir(outside);
+    }
+
     #[test]
     fn copy_file_does_not_exist() {
       assert !os::copy_file(&Path("test/nonexistent-bogus-path"),
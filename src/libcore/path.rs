@@ -14,14 +14,23 @@ Cross-platform file path handling
 
 */
 
-use cmp::Eq;
+use cmp::{Eq, Ord};
 use libc;
 use option::{None, Option, Some};
 use ptr;
 use str;
+use to_bytes::{Cb, IterBytes, iter_bytes_2};
 use to_str::ToStr;
-
-#[deriving_eq]
+use uint;
+use vec;
+
+/**
+ * A Windows path
+ *
+ * Comparison ignores ASCII case, as Windows filesystems usually do, and
+ * doesn't distinguish `/` from `\` since both separate components. The
+ * host of a UNC path and the drive letter are part of the comparison.
+ */
 pub struct WindowsPath {
     host: Option<~str>,
     device: Option<~str>,
@@ -33,6 +42,7 @@ pub pure fn WindowsPath(s: &str) -> WindowsPath {
     GenericPath::from_str(s)
 }
 
+/// A POSIX path. Comparison is case-sensitive.
 #[deriving_eq]
 pub struct PosixPath {
     is_absolute: bool,
@@ -64,7 +74,24 @@ pub trait GenericPath {
     pure fn push_many((&[~str])) -> Self;
     pure fn pop() -> Self;
 
+    /// Resolves `.` and `..` components lexically, without looking at the
+    /// filesystem. A `..` that would climb above the start of the path,
+    /// or above the root, is kept.
     pure fn normalize() -> Self;
+
+    /// Returns the relative path that leads from the directory `base` to
+    /// this path, so that `base.push_rel(p).normalize()` is this path
+    /// normalized. Returns None if the two paths aren't both absolute or
+    /// both relative, are on different drives or hosts, or if `base`
+    /// climbs out through a `..` that can't be retraced.
+    pure fn path_relative_from(&self, base: &Self) -> Option<Self>;
+
+    /// Calls `f` on each component in order, stopping if it returns false.
+    /// Drive letters and UNC hosts are not components.
+    pure fn each_component(&self, f: fn(&str) -> bool);
+    /// Returns true if the last components of the path are those of
+    /// `suffix`, which must be a relative path or else equal to the path
+    pure fn ends_with_path(&self, suffix: &Self) -> bool;
 }
 
 #[cfg(windows)]
@@ -373,6 +400,28 @@ impl PosixPath : ToStr {
     }
 }
 
+// Relative paths sort before absolute ones, then by component
+pure fn posix_key(p: &PosixPath) -> ~[~str] {
+    let mut key = ~[if p.is_absolute { ~"/" } else { ~"" }];
+    unsafe { key.push_all(p.components); }
+    move key
+}
+
+impl PosixPath : Ord {
+    pure fn lt(&self, other: &PosixPath) -> bool {
+        posix_key(self) < posix_key(other)
+    }
+    pure fn le(&self, other: &PosixPath) -> bool { !(*other).lt(self) }
+    pure fn ge(&self, other: &PosixPath) -> bool { !(*self).lt(other) }
+    pure fn gt(&self, other: &PosixPath) -> bool { (*other).lt(self) }
+}
+
+impl PosixPath : IterBytes {
+    pure fn iter_bytes(&self, lsb0: bool, f: Cb) {
+        iter_bytes_2(&self.is_absolute, &self.components, lsb0, f)
+    }
+}
+
 // FIXME (#3227): when default methods in traits are working, de-duplicate
 // PosixPath and WindowsPath, most of their methods are common.
 impl PosixPath : GenericPath {
@@ -523,6 +572,32 @@ impl PosixPath : GenericPath {
           //  ..self
         }
     }
+
+    pure fn path_relative_from(&self, base: &PosixPath)
+        -> Option<PosixPath> {
+        if self.is_absolute != base.is_absolute { return None; }
+        match relative_components(self.components, base.components, false) {
+            Some(move cs) => Some(PosixPath {
+                is_absolute: false,
+                components: move cs
+            }),
+            None => None
+        }
+    }
+
+    pure fn each_component(&self, f: fn(&str) -> bool) {
+        for self.components.each |c| {
+            if !f(*c) { break; }
+        }
+    }
+
+    pure fn ends_with_path(&self, suffix: &PosixPath) -> bool {
+        if suffix.is_absolute { return *self == *suffix; }
+        let n = self.components.len();
+        let m = suffix.components.len();
+        m <= n && vec::view(self.components, n - m, n) ==
+            vec::view(suffix.components, 0, m)
+    }
 }
 
 
@@ -544,6 +619,47 @@ impl WindowsPath : ToStr {
     }
 }
 
+// Everything that takes part in comparing two Windows paths, with the
+// case folded. The host and device are marked so that they can't be
+// confused with each other or with a component.
+pure fn windows_key(p: &WindowsPath) -> ~[~str] {
+    let host = match p.host {
+        Some(ref h) => ~"\\\\" + str::to_lower(*h),
+        None => ~""
+    };
+    let device = match p.device {
+        Some(ref d) => str::to_lower(*d) + ":",
+        None => ~""
+    };
+    let mut key = ~[host, device, if p.is_absolute { ~"\\" } else { ~"" }];
+    for p.components.each |c| {
+        unsafe { key.push(str::to_lower(*c)); }
+    }
+    move key
+}
+
+impl WindowsPath : Eq {
+    pure fn eq(&self, other: &WindowsPath) -> bool {
+        windows_key(self) == windows_key(other)
+    }
+    pure fn ne(&self, other: &WindowsPath) -> bool { !(*self).eq(other) }
+}
+
+impl WindowsPath : Ord {
+    pure fn lt(&self, other: &WindowsPath) -> bool {
+        windows_key(self) < windows_key(other)
+    }
+    pure fn le(&self, other: &WindowsPath) -> bool { !(*other).lt(self) }
+    pure fn ge(&self, other: &WindowsPath) -> bool { !(*self).lt(other) }
+    pure fn gt(&self, other: &WindowsPath) -> bool { (*other).lt(self) }
+}
+
+impl WindowsPath : IterBytes {
+    pure fn iter_bytes(&self, lsb0: bool, f: Cb) {
+        windows_key(self).iter_bytes(lsb0, f)
+    }
+}
+
 
 impl WindowsPath : GenericPath {
 
@@ -730,6 +846,45 @@ impl WindowsPath : GenericPath {
             components: normalize(self.components)
         }
     }
+
+    pure fn path_relative_from(&self, base: &WindowsPath)
+        -> Option<WindowsPath> {
+        // The first three parts of the key are the host, the device and
+        // whether the path is absolute
+        if vec::view(windows_key(self), 0, 3) !=
+            vec::view(windows_key(base), 0, 3) {
+            return None;
+        }
+        match relative_components(self.components, base.components, true) {
+            Some(move cs) => Some(WindowsPath {
+                host: None,
+                device: None,
+                is_absolute: false,
+                components: move cs
+            }),
+            None => None
+        }
+    }
+
+    pure fn each_component(&self, f: fn(&str) -> bool) {
+        for self.components.each |c| {
+            if !f(*c) { break; }
+        }
+    }
+
+    pure fn ends_with_path(&self, suffix: &WindowsPath) -> bool {
+        if suffix.is_absolute { return *self == *suffix; }
+        let n = self.components.len();
+        let m = suffix.components.len();
+        if m > n { return false; }
+        for uint::range(0, m) |i| {
+            if str::to_lower(self.components[n - m + i]) !=
+                str::to_lower(suffix.components[i]) {
+                return false;
+            }
+        }
+        true
+    }
 }
 
 
@@ -740,7 +895,9 @@ pub pure fn normalize(components: &[~str]) -> ~[~str] {
             unsafe {
                 if *c == ~"." && components.len() > 1 { loop; }
                 if *c == ~"" { loop; }
-                if *c == ~".." && cs.len() != 0 {
+                // A `..` only cancels a real component, never another `..`
+                if *c == ~".." && cs.len() != 0 &&
+                    cs[cs.len() - 1] != ~".." {
                     cs.pop();
                     loop;
                 }
@@ -751,6 +908,39 @@ pub pure fn normalize(components: &[~str]) -> ~[~str] {
     move cs
 }
 
+// The components of the relative path leading from the directory `base`
+// to `path`, or None if `base` has a `..` beyond the part the two paths
+// share, since retracing it would need the name of the directory it left
+pure fn relative_components(path: &[~str], base: &[~str], fold_case: bool)
+    -> Option<~[~str]> {
+    let path = normalize(path);
+    let base = normalize(base);
+
+    let mut i = 0;
+    while i < path.len() && i < base.len() {
+        let same = if fold_case {
+            str::to_lower(path[i]) == str::to_lower(base[i])
+        } else {
+            path[i] == base[i]
+        };
+        if !same { break; }
+        i += 1;
+    }
+
+    let mut cs = ~[];
+    unsafe {
+        for uint::range(i, base.len()) |j| {
+            if base[j] == ~".." { return None; }
+            if base[j] != ~"." { cs.push(~".."); }
+        }
+        for uint::range(i, path.len()) |j| {
+            if path[j] != ~"." { cs.push(copy path[j]); }
+        }
+        if cs.is_empty() { cs.push(~"."); }
+    }
+    Some(move cs)
+}
+
 // Various windows helpers, and tests for the impl.
 pub mod windows {
     use libc;
@@ -798,9 +988,14 @@ pub mod windows {
 
 #[cfg(test)]
 mod tests {
+    use cmp::Eq;
+    use hashmap::linear::LinearMap;
     use option::{None, Some};
-    use path::{PosixPath, WindowsPath, windows};
+    use path::{GenericPath, PosixPath, WindowsPath, windows};
     use str;
+    use to_str::ToStr;
+    use uint;
+    use vec;
 
     #[test]
     fn test_double_slash_collapsing() {
@@ -913,6 +1108,89 @@ mod tests {
             .push("..").normalize()), "a/b");
     }
 
+    #[test]
+    fn test_normalize_table() {
+        let posix = [
+            ("a/./b/../c", "a/c"),
+            ("./a", "a"),
+            (".", "."),
+            ("a//b/", "a/b"),
+            ("../a/..", ".."),
+            ("../../a", "../../a"),
+            ("a/../../b", "../b"),
+            ("/a/b/../../..", "/.."),
+            ("/../a", "/../a"),
+        ];
+        for posix.each |t| {
+            let (input, expected) = *t;
+            assert PosixPath(input).normalize().to_str() ==
+                str::from_slice(expected);
+        }
+
+        let windows = [
+            ("c:\\a\\.\\b\\..\\c", "c:\\a\\c"),
+            ("C:a/../../b", "C:..\\b"),
+            ("c:\\..\\a", "c:\\..\\a"),
+            ("\\\\srv\\share\\a\\..\\b", "\\\\srv\\share\\b"),
+            ("..\\..\\a", "..\\..\\a"),
+        ];
+        for windows.each |t| {
+            let (input, expected) = *t;
+            assert WindowsPath(input).normalize().to_str() ==
+                str::from_slice(expected);
+        }
+    }
+
+    #[test]
+    fn test_path_relative_from() {
+        fn check<P: GenericPath Eq ToStr>(path: &P, base: &P,
+                                          expected: Option<&str>) {
+            match (path.path_relative_from(base), expected) {
+                (None, None) => (),
+                (Some(ref rel), Some(e)) => {
+                    assert rel.to_str() == str::from_slice(e);
+                    assert base.push_rel(rel).normalize() ==
+                        path.normalize();
+                }
+                _ => die!(fmt!("unexpected result for %s from %s",
+                               path.to_str(), base.to_str()))
+            }
+        }
+
+        let posix = [
+            ("/a/b/c", "/a/b", Some("c")),
+            ("/a/b", "/a/b/c/d", Some("../..")),
+            ("/a/x/y", "/a/b/c", Some("../../x/y")),
+            ("/a/b", "/a/b/", Some(".")),
+            ("/", "/a/b", Some("../..")),
+            ("a/./b/../c", "a/d", Some("../c")),
+            ("../x", "../y", Some("../x")),
+            ("../x", "y", Some("../../x")),
+            ("x", "../y", None),
+            ("a/b", "/a", None),
+            ("/a", "a", None),
+        ];
+        for posix.each |t| {
+            let (path, base, expected) = *t;
+            check(&PosixPath(path), &PosixPath(base), expected);
+        }
+
+        let windows = [
+            ("c:\\Windows\\System32", "C:\\windows", Some("System32")),
+            ("c:\\a\\b", "c:\\a\\c\\d", Some("..\\..\\b")),
+            ("\\\\srv\\share\\a", "\\\\SRV\\share\\b", Some("..\\a")),
+            ("a\\b", "a", Some("b")),
+            ("c:\\a", "d:\\a", None),
+            ("c:\\a", "\\a", None),
+            ("\\\\srv\\a", "\\\\other\\a", None),
+            ("c:a", "c:\\a", None),
+        ];
+        for windows.each |t| {
+            let (path, base, expected) = *t;
+            check(&WindowsPath(path), &WindowsPath(base), expected);
+        }
+    }
+
     #[test]
     fn test_extract_unc_prefixes() {
         assert windows::extract_unc_prefix("\\\\").is_none();
@@ -982,4 +1260,120 @@ mod tests {
             .with_filename("librustc.dll")),
           "c:\\program files (x86)\\rust\\lib\\librustc.dll");
     }
+
+    fn components<P: GenericPath>(p: &P) -> ~[~str] {
+        let mut cs = ~[];
+        for p.each_component |c| {
+            cs.push(str::from_slice(c));
+        }
+        cs
+    }
+
+    #[test]
+    fn test_each_component() {
+        assert components(&PosixPath("/usr/lib/")) == ~[~"usr", ~"lib"];
+        assert components(&PosixPath("a//b")) == ~[~"a", ~"b"];
+        assert components(&PosixPath("/")) == ~[];
+        assert components(&WindowsPath("c:\\windows/system32")) ==
+            ~[~"windows", ~"system32"];
+        assert components(&WindowsPath("\\\\server\\share\\f.txt")) ==
+            ~[~"share", ~"f.txt"];
+
+        let mut first = ~[];
+        for PosixPath("a/b/c").each_component |c| {
+            first.push(str::from_slice(c));
+            break;
+        }
+        assert first == ~[~"a"];
+    }
+
+    #[test]
+    fn test_ends_with_path() {
+        let p = PosixPath("/usr/lib/rust");
+        assert p.ends_with_path(&PosixPath("rust"));
+        assert p.ends_with_path(&PosixPath("lib/rust"));
+        assert p.ends_with_path(&PosixPath(""));
+        assert p.ends_with_path(&PosixPath("/usr/lib/rust"));
+        assert !p.ends_with_path(&PosixPath("/lib/rust"));
+        assert !p.ends_with_path(&PosixPath("Rust"));
+        assert !p.ends_with_path(&PosixPath("x/usr/lib/rust"));
+
+        let w = WindowsPath("C:\\Program Files\\Rust");
+        assert w.ends_with_path(&WindowsPath("program files/rust"));
+        assert w.ends_with_path(&WindowsPath("c:/program files/RUST"));
+        assert !w.ends_with_path(&WindowsPath("d:/program files/rust"));
+    }
+
+    #[test]
+    fn test_windows_eq() {
+        assert WindowsPath("C:\\Foo\\Bar") == WindowsPath("c:/foo/bar");
+        assert WindowsPath("foo\\bar\\") == WindowsPath("foo/bar");
+        assert WindowsPath("\\\\Host\\a") == WindowsPath("\\\\HOST\\A");
+        assert WindowsPath("c:\\foo") != WindowsPath("d:\\foo");
+        assert WindowsPath("c:\\foo") != WindowsPath("c:foo");
+        assert WindowsPath("\\\\c\\foo") != WindowsPath("c:\\foo");
+        assert WindowsPath("\\\\host\\foo") != WindowsPath("\\foo");
+    }
+
+    #[test]
+    fn test_posix_eq() {
+        assert PosixPath("/a/b/") == PosixPath("/a//b");
+        assert PosixPath("/a/b") != PosixPath("a/b");
+        assert PosixPath("/a/B") != PosixPath("/a/b");
+    }
+
+    #[test]
+    fn test_ord() {
+        assert PosixPath("a/b") < PosixPath("a/c");
+        assert PosixPath("a") < PosixPath("a/b");
+        assert PosixPath("z") < PosixPath("/a");
+        assert PosixPath("a/b") <= PosixPath("a/b");
+        assert PosixPath("b") > PosixPath("a/z");
+
+        assert WindowsPath("a\\B") < WindowsPath("A/c");
+        assert WindowsPath("A") <= WindowsPath("a");
+        assert WindowsPath("c:\\z") < WindowsPath("d:\\a");
+    }
+
+    fn sorted<T: Ord Copy>(v: &[T]) -> ~[T] {
+        let mut v = vec::from_slice(v);
+        for uint::range(1, v.len()) |i| {
+            let mut j = i;
+            while j > 0 && v[j] < v[j - 1] {
+                v[j] <-> v[j - 1];
+                j -= 1;
+            }
+        }
+        v
+    }
+
+    #[test]
+    fn test_sort() {
+        let paths = ~[PosixPath("/usr/lib"), PosixPath("b"),
+                      PosixPath("/usr"), PosixPath("a/c"), PosixPath("a")];
+        let paths = sorted(paths).map(|p| p.to_str());
+        assert paths == ~[~"a", ~"a/c", ~"b", ~"/usr", ~"/usr/lib"];
+
+        let paths = ~[WindowsPath("b"), WindowsPath("A"),
+                      WindowsPath("c:\\x"), WindowsPath("a\\b")];
+        let paths = sorted(paths).map(|p| p.to_str());
+        assert paths == ~[~"A", ~"a\\b", ~"b", ~"c:\\x"];
+    }
+
+    #[test]
+    fn test_map_keys() {
+        let mut map = LinearMap::new();
+        map.insert(PosixPath("/usr/lib/"), 1);
+        map.insert(PosixPath("usr/lib"), 2);
+        assert *map.find(&PosixPath("/usr//lib")).get() == 1;
+        assert *map.find(&PosixPath("usr/lib")).get() == 2;
+        assert map.find(&PosixPath("/usr/Lib")).is_none();
+
+        let mut map = LinearMap::new();
+        map.insert(WindowsPath("C:\\Windows"), 1);
+        map.insert(WindowsPath("\\\\host\\Windows"), 2);
+        assert *map.find(&WindowsPath("c:/windows/")).get() == 1;
+        assert *map.find(&WindowsPath("\\\\HOST\\windows")).get() == 2;
+        assert map.find(&WindowsPath("windows")).is_none();
+    }
 }
@@ -21,6 +21,7 @@ use result;
 use task;
 use task::{TaskBuilder, atomically};
 use uint;
+use util;
 
 #[path = "private/at_exit.rs"]
 pub mod at_exit;
@@ -105,9 +106,7 @@ type UnwrapProto = ~mut Option<(pipes::ChanOne<()>,  pipes::PortOne<bool>)>;
 struct ArcData<T> {
     mut count:     libc::intptr_t,
     mut unwrapper: int, // either a UnwrapProto or 0
-    // FIXME(#3224) should be able to make this non-option to save memory, and
-    // in unwrap() use "let ~ArcData { data: result, _ } = thing" to unwrap it
-    mut data:      Option<T>,
+    mut data:      T,
 }
 
 struct ArcDestruct<T> {
@@ -198,8 +197,9 @@ pub unsafe fn unwrap_shared_mutable_state<T: Owned>(rc: SharedMutableState<T>)
                 // We were the last owner. Can unwrap immediately.
                 // Also we have to free the server endpoints.
                 let _server: UnwrapProto = cast::transmute(move serverp);
-                option::swap_unwrap(&mut ptr.data)
-                // drop glue takes over.
+                // Moving the data out frees the rest of the box.
+                let ~ArcData { data: data, _ } = move ptr;
+                move data
             } else {
                 // The *next* person who sees the refcount hit 0 will wake us.
                 let end_result =
@@ -211,9 +211,10 @@ pub unsafe fn unwrap_shared_mutable_state<T: Owned>(rc: SharedMutableState<T>)
                 }
                 // Got here. Back in the 'unkillable' without getting killed.
                 // Recover ownership of ptr, then take the data out.
-                let ptr = option::swap_unwrap(&mut end_result.ptr);
-                option::swap_unwrap(&mut ptr.data)
-                // drop glue takes over.
+                let ptr = util::replace(&mut end_result.ptr, None).expect(
+                    "unwrap_shared_mutable_state: woken without the data");
+                let ~ArcData { data: data, _ } = move ptr;
+                move data
             }
         } else {
             // Somebody else was trying to unwrap. Avoid guaranteed deadlock.
@@ -235,7 +236,7 @@ pub type SharedMutableState<T> = ArcDestruct<T>;
 
 pub unsafe fn shared_mutable_state<T: Owned>(data: T) ->
         SharedMutableState<T> {
-    let data = ~ArcData { count: 1, unwrapper: 0, data: Some(move data) };
+    let data = ~ArcData { count: 1, unwrapper: 0, data: move data };
     unsafe {
         let ptr = cast::transmute(move data);
         ArcDestruct(ptr)
@@ -249,7 +250,7 @@ pub unsafe fn get_shared_mutable_state<T: Owned>(rc: &a/SharedMutableState<T>)
         let ptr: ~ArcData<T> = cast::reinterpret_cast(&(*rc).data);
         assert ptr.count > 0;
         // Cast us back into the correct region
-        let r = cast::transmute_region(option::get_ref(&ptr.data));
+        let r = cast::transmute_region(&ptr.data);
         cast::forget(move ptr);
         return cast::transmute_mut(r);
     }
@@ -261,7 +262,7 @@ pub unsafe fn get_shared_immutable_state<T: Owned>(
         let ptr: ~ArcData<T> = cast::reinterpret_cast(&(*rc).data);
         assert ptr.count > 0;
         // Cast us back into the correct region
-        let r = cast::transmute_region(option::get_ref(&ptr.data));
+        let r = cast::transmute_region(&ptr.data);
         cast::forget(move ptr);
         return r;
     }
@@ -458,9 +459,8 @@ pub mod tests {
     #[test]
     pub fn exclusive_unwrap_contended() {
         let x = exclusive(~~"hello");
-        let x2 = ~mut Some(x.clone());
+        let x2 = x.clone();
         do task::spawn |move x2| {
-            let x2 = option::swap_unwrap(x2);
             do x2.with |_hello| { }
             task::yield();
         }
@@ -468,11 +468,10 @@ pub mod tests {
 
         // Now try the same thing, but with the child task blocking.
         let x = exclusive(~~"hello");
-        let x2 = ~mut Some(x.clone());
+        let x2 = x.clone();
         let mut res = None;
-        do task::task().future_result(|+r| res = Some(move r)).spawn
+        do task::task().future_result(|+r| res = Some(move r)).spawn_once
               |move x2| {
-            let x2 = option::swap_unwrap(x2);
             assert unwrap_exclusive(move x2) == ~~"hello";
         }
         // Have to get rid of our reference before blocking.
@@ -484,11 +483,10 @@ pub mod tests {
     #[test] #[should_fail] #[ignore(reason = "random red")]
     pub fn exclusive_unwrap_conflict() {
         let x = exclusive(~~"hello");
-        let x2 = ~mut Some(x.clone());
+        let x2 = x.clone();
         let mut res = None;
-        do task::task().future_result(|+r| res = Some(move r)).spawn
+        do task::task().future_result(|+r| res = Some(move r)).spawn_once
            |move x2| {
-            let x2 = option::swap_unwrap(x2);
             assert unwrap_exclusive(move x2) == ~~"hello";
         }
         assert unwrap_exclusive(move x) == ~~"hello";
@@ -10,42 +10,67 @@
 
 use sys;
 use cast;
+use io;
+use io::WriterUtil;
+use os;
 use ptr;
 use task;
 use uint;
 use vec;
-use rand;
 use libc::{c_void, size_t};
 
 /**
 Register a function to be run during runtime shutdown.
 
 After all non-weak tasks have exited, registered exit functions will
-execute, in random order, on the primary scheduler. Each function runs
-in its own unsupervised task.
+execute, one at a time, on the primary scheduler. They run in the reverse
+of the order they were registered in, each in its own supervised task, so
+a failing function does not prevent the others from running. If any of
+them fail then a message is printed to stderr and the process exits with
+the failure status.
+
+This is equivalent to `at_exit_prioritized(0, f)`.
 */
 pub fn at_exit(f: ~fn()) {
+    at_exit_prioritized(0, f)
+}
+
+/**
+Register a function to be run during runtime shutdown, with a priority.
+
+Exit functions with a higher priority run before those with a lower one,
+and those with equal priority run in reverse registration order. For
+example, a function that must see the effects of all the others, like
+dumping a profile after buffers have been flushed, can be registered
+with a negative priority.
+*/
+pub fn at_exit_prioritized(priority: int, f: ~fn()) {
     unsafe {
         let runner: &fn(*ExitFunctions) = exit_runner;
         let runner_pair: sys::Closure = cast::transmute(runner);
         let runner_ptr = runner_pair.code;
         let runner_ptr = cast::transmute(runner_ptr);
-        rustrt::rust_register_exit_function(runner_ptr, ~f);
+        let exit_fn = ~ExitFunction { priority: priority, f: f };
+        rustrt::rust_register_exit_function(runner_ptr, exit_fn);
     }
 }
 
-// NB: The double pointer indirection here is because ~fn() is a fat
-// pointer and due to FFI problems I am more comfortable making the
-// interface use a normal pointer
+// NB: The runtime treats each exit function as an opaque pointer, which
+// keeps the FFI interface using a normal pointer rather than a ~fn()
 extern mod rustrt {
-    fn rust_register_exit_function(runner: *c_void, f: ~~fn());
+    fn rust_register_exit_function(runner: *c_void, f: ~ExitFunction);
+}
+
+struct ExitFunction {
+    priority: int,
+    f: ~fn()
 }
 
 struct ExitFunctions {
     // The number of exit functions
     count: size_t,
-    // The buffer of exit functions
-    start: *~~fn()
+    // The buffer of exit functions, in registration order
+    start: *~ExitFunction
 }
 
 fn exit_runner(exit_fns: *ExitFunctions) {
@@ -55,19 +80,42 @@ fn exit_runner(exit_fns: *ExitFunctions) {
 
     // NB: from_buf memcpys from the source, which will
     // give us ownership of the array of functions
-    let mut exit_fns_vec = unsafe { vec::from_buf(start, count as uint) };
-    // Let's not make any promises about execution order
-    rand::Rng().shuffle_mut(exit_fns_vec);
+    let exit_fns_vec = unsafe { vec::from_buf(start, count as uint) };
+    let mut exit_fns_vec = sort_exit_functions(move exit_fns_vec);
 
     debug!("running %u exit functions", exit_fns_vec.len());
 
+    let mut failed = false;
     while !exit_fns_vec.is_empty() {
-        match exit_fns_vec.pop() {
-            ~f => {
-                task::task().supervised().spawn(f);
+        match exit_fns_vec.shift() {
+            ~ExitFunction { f: f, _ } => {
+                if task::try(f).is_err() {
+                    io::stderr().write_line("rust: at_exit handler failed");
+                    failed = true;
+                }
             }
         }
     }
+
+    if failed {
+        os::set_exit_status(101);
+    }
+}
+
+// Orders exit functions by descending priority, most recently registered
+// first within a priority. A stable insertion sort, since there are few.
+fn sort_exit_functions(fns: ~[~ExitFunction]) -> ~[~ExitFunction] {
+    let mut fns = move fns;
+    let mut sorted = ~[];
+    while !fns.is_empty() {
+        sorted.push(fns.pop());
+        let mut j = sorted.len() - 1;
+        while j > 0 && sorted[j - 1].priority < sorted[j].priority {
+            sorted[j - 1] <-> sorted[j];
+            j -= 1;
+        }
+    }
+    move sorted
 }
 
 #[abi = "rust-intrinsic"]
@@ -95,4 +143,55 @@ fn test_at_exit_many() {
             assert j > i;
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_sort_exit_functions() {
+    use private::exclusive;
+
+    let order = exclusive(~[]);
+    let mut fns = ~[];
+    for [(0, 1), (5, 2), (0, 3), (-5, 4), (5, 5)].each |pair| {
+        let (priority, id) = *pair;
+        let order = order.clone();
+        let f: ~fn() = |move order| unsafe { order.with(|o| o.push(id)) };
+        fns.push(~ExitFunction { priority: priority, f: move f });
+    }
+    let mut fns = sort_exit_functions(move fns);
+    while !fns.is_empty() {
+        match fns.shift() {
+            ~ExitFunction { f: f, _ } => f()
+        }
+    }
+    unsafe {
+        do order.with |o| {
+            assert *o == ~[5, 2, 3, 1, 4];
+        }
+    }
+}
+
+#[test]
+fn test_at_exit_ordering() {
+    use int;
+    use private::exclusive;
+
+    let markers = exclusive(~[]);
+    for uint::range(0, 3) |i| {
+        let markers = markers.clone();
+        do at_exit |move markers| {
+            unsafe { markers.with(|m| m.push(i)); }
+        }
+    }
+    let first = markers.clone();
+    do at_exit_prioritized(10) |move first| {
+        unsafe { first.with(|m| m.push(100)); }
+    }
+    // Runs after everything else, including other tests' exit functions
+    do at_exit_prioritized(int::min_value) |move markers| {
+        unsafe {
+            do markers.with |m| {
+                assert *m == ~[100, 2, 1, 0];
+            }
+        }
+    }
+}
@@ -21,6 +21,18 @@ do || {
     alway_run_this();
 }
 ~~~
+
+`finally_status` additionally tells the cleanup block whether the
+protected block failed, and `try_finally` passes mutable state to both
+blocks, which suits commit-or-rollback patterns:
+
+~~~
+try_finally(&mut txn, |txn| {
+    apply_changes(txn);
+}, |txn, failed| {
+    if failed { txn.rollback() } else { txn.commit() }
+});
+~~~
 */
 
 use ops::Drop;
@@ -29,6 +41,7 @@ use task::{spawn, failing};
 #[cfg(stage0)]
 pub trait Finally<T> {
     fn finally(&self, +dtor: &fn()) -> T;
+    fn finally_status(&self, +dtor: &fn(failed: bool)) -> T;
 }
 
 #[cfg(stage1)]
@@ -36,6 +49,7 @@ pub trait Finally<T> {
 #[cfg(stage3)]
 pub trait Finally<T> {
     fn finally(&self, dtor: &fn()) -> T;
+    fn finally_status(&self, dtor: &fn(failed: bool)) -> T;
 }
 
 #[cfg(stage0)]
@@ -48,6 +62,14 @@ impl<T> &fn() -> T: Finally<T> {
 
         (*self)()
     }
+
+    fn finally_status(&self, +dtor: &fn(failed: bool)) -> T {
+        let _d = StatusFinallyalizer {
+            dtor: dtor
+        };
+
+        (*self)()
+    }
 }
 
 #[cfg(stage1)]
@@ -61,6 +83,14 @@ impl<T> &fn() -> T: Finally<T> {
 
         (*self)()
     }
+
+    fn finally_status(&self, dtor: &fn(failed: bool)) -> T {
+        let _d = StatusFinallyalizer {
+            dtor: dtor
+        };
+
+        (*self)()
+    }
 }
 
 struct Finallyalizer {
@@ -73,6 +103,30 @@ impl Finallyalizer: Drop {
     }
 }
 
+struct StatusFinallyalizer {
+    dtor: &fn(failed: bool)
+}
+
+impl StatusFinallyalizer: Drop {
+    fn finalize(&self) {
+        (self.dtor)(failing());
+    }
+}
+
+/**
+ * Runs `try_fn` on `state`, then `dtor` on whatever state it left behind
+ *
+ * `dtor` is run exactly once, whether `try_fn` returns or fails, and is
+ * told whether it failed.
+ */
+pub fn try_finally<S, T>(state: &mut S,
+                         try_fn: &fn(&mut S) -> T,
+                         dtor: &fn(&mut S, failed: bool)) -> T {
+    do (|| try_fn(state)).finally_status |failed| {
+        dtor(state, failed)
+    }
+}
+
 #[test]
 fn test_success() {
     let mut i = 0;
@@ -118,3 +172,79 @@ fn test_compact() {
     do_some_fallible_work.finally(
         but_always_run_this_function);
 }
+
+#[test]
+fn test_status_success() {
+    let mut runs = 0;
+    let i = do (fn&() -> int {
+        10
+    }).finally_status |failed| {
+        assert !failed;
+        runs += 1;
+    };
+    assert i == 10;
+    assert runs == 1;
+}
+
+#[test]
+#[ignore(cfg(windows))]
+#[should_fail]
+fn test_status_fail() {
+    do (|| {
+        die!();
+    }).finally_status |failed| {
+        assert failed;
+    }
+}
+
+#[test]
+#[ignore(cfg(windows))]
+fn test_status_fail_runs_once() {
+    use private::exclusive;
+    use task;
+
+    let runs = exclusive(~[]);
+    let child_runs = runs.clone();
+    let result = do task::try |move child_runs| {
+        do (|| {
+            die!();
+        }).finally_status |failed| {
+            unsafe { child_runs.with(|r| r.push(failed)); }
+        }
+    };
+    assert result.is_err();
+    unsafe {
+        assert runs.with(|r| copy *r) == ~[true];
+    }
+}
+
+#[test]
+fn test_try_finally() {
+    let mut log = ~[];
+    let n = try_finally(&mut log, |log| {
+        log.push(1);
+        log.push(2);
+        log.len()
+    }, |log, failed| {
+        assert !failed;
+        log.push(3);
+    });
+    assert n == 2;
+    assert log == ~[1, 2, 3];
+}
+
+#[test]
+#[ignore(cfg(windows))]
+#[should_fail]
+fn test_try_finally_partial_progress() {
+    let mut log = ~[];
+    try_finally(&mut log, |log| {
+        log.push(1);
+        die!();
+    }, |log, failed| {
+        assert failed;
+        // Roll back whatever was done before the failure
+        assert *log == ~[1];
+        log.truncate(0);
+    });
+}
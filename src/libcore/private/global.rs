@@ -16,7 +16,7 @@ An interface for creating and retrieving values with global
 
 Global values are stored in a map and protected by a single global
 mutex. Operations are provided for accessing and cloning the value
-under the mutex.
+under the mutex, or for borrowing values that can't be cloned.
 
 Because all globals go through a single mutex, they should be used
 sparingly.  The interface is intended to be used with clonable,
@@ -79,6 +79,90 @@ unsafe fn global_data_clone_create_<T: Owned Clone>(
     return clone_value.unwrap();
 }
 
+pub unsafe fn global_with<T: Owned, U>(
+    key: GlobalDataKey<T>, init: ~fn() -> T, f: &fn(&T) -> U) -> U {
+    /*!
+     * Borrow a global value or, if it has not been created,
+     * first construct the value then borrow it.
+     *
+     * Unlike `global_data_clone_create` the value need not be
+     * clonable. `f` is called with the global lock held, so
+     * the borrow can't outlive it.
+     *
+     * The value is destroyed by an exit function registered
+     * when it is created, or earlier by `global_data_destroy`.
+     *
+     * # Safety note
+     *
+     * `init` is called without the global lock held, so it may
+     * use other globals, but two tasks may race to construct the
+     * value. Exactly one of the values is kept and the others are
+     * dropped. Recursive use of the global interface in `f` will
+     * result in deadlock.
+     */
+    global_with_(key_ptr(key), init, f)
+}
+
+unsafe fn global_with_<T: Owned, U>(
+    key: uint, init: ~fn() -> T, f: &fn(&T) -> U) -> U {
+
+    loop {
+        let mut result = None;
+        do global_data_modify_(key) |value: Option<~T>| {
+            match value {
+                Some(value) => {
+                    result = Some(f(&*value));
+                    Some(value)
+                }
+                None => None
+            }
+        }
+        if result.is_some() {
+            return result.unwrap();
+        }
+
+        let mut new_value = Some(~init());
+        let mut created = false;
+        do global_data_modify_(key) |value: Option<~T>| {
+            match value {
+                // Somebody else won the race, and our value is dropped
+                Some(value) => Some(value),
+                None => {
+                    created = true;
+                    Some(new_value.swap_unwrap())
+                }
+            }
+        }
+        if created {
+            // NB: This runs before the exit function that tears down the
+            // global state, which was registered before any value existed
+            do at_exit {
+                unsafe { global_data_destroy_::<T>(key); }
+            }
+        }
+    }
+}
+
+pub unsafe fn global_data_destroy<T: Owned>(key: GlobalDataKey<T>) {
+    /*!
+     * Destroy a global value, if it exists.
+     *
+     * Useful for tearing down values like file handles at a known
+     * point rather than at runtime shutdown.
+     */
+    global_data_destroy_::<T>(key_ptr(key))
+}
+
+unsafe fn global_data_destroy_<T: Owned>(key: uint) {
+    let mut _old_value = None;
+    do global_data_modify_(key) |value: Option<~T>| {
+        _old_value = value;
+        None
+    }
+    // The value is dropped here, outside of the global lock, so that its
+    // destructor is free to use other globals
+}
+
 unsafe fn global_data_modify<T: Owned>(
     key: GlobalDataKey<T>, op: &fn(Option<~T>) -> Option<~T>) {
 
@@ -296,3 +380,90 @@ fn test_modify() {
         }
     }
 }
+
+#[test]
+fn test_global_with_race() {
+    use private::exclusive;
+    use task;
+
+    // Counts the values that have been constructed and dropped
+    struct Counted {
+        counts: Exclusive<(uint, uint)>
+    }
+
+    impl Counted: Drop {
+        fn finalize(&self) {
+            unsafe {
+                do self.counts.with |counts| {
+                    let (created, dropped) = *counts;
+                    *counts = (created, dropped + 1);
+                }
+            }
+        }
+    }
+
+    fn key(_v: Counted) { }
+
+    let counts = exclusive((0u, 0u));
+    let mut results = ~[];
+    for uint::range(0, 20) |_| {
+        let counts = counts.clone();
+        do task::task().future_result(|+r| results.push(move r)).spawn
+            |move counts| {
+            let init: ~fn() -> Counted = |move counts| {
+                unsafe {
+                    do counts.with |counts| {
+                        let (created, dropped) = *counts;
+                        *counts = (created + 1, dropped);
+                    }
+                }
+                // Give the other tasks a chance to construct a value too
+                task::yield();
+                Counted { counts: counts.clone() }
+            };
+            unsafe {
+                do global_with(key, init) |_value| { };
+            }
+        }
+    }
+    for results.each |r| { r.recv(); }
+
+    unsafe {
+        do counts.with |counts| {
+            let (created, dropped) = *counts;
+            assert created >= 1;
+            assert created == dropped + 1;
+        }
+
+        global_data_destroy(key);
+
+        do counts.with |counts| {
+            let (created, dropped) = *counts;
+            assert created == dropped;
+        }
+    }
+}
+
+#[test]
+fn test_global_with_non_clonable() {
+    struct NotClonable { value: int }
+
+    fn key(_v: NotClonable) { }
+
+    unsafe {
+        let v = do global_with(key, || NotClonable { value: 10 }) |v| {
+            v.value
+        };
+        assert v == 10;
+
+        // The constructor isn't called again once the value exists
+        let v = do global_with(key, || die!()) |v| { v.value + 1 };
+        assert v == 11;
+
+        global_data_destroy(key);
+        let v = do global_with(key, || NotClonable { value: 20 }) |v| {
+            v.value
+        };
+        assert v == 20;
+    }
+}
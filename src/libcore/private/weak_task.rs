@@ -16,6 +16,11 @@ do not keep the runtime alive. Normally the runtime exits when all
 tasks exits, but if a task is weak then the runtime may exit while
 it is running, sending a notification to the task that the runtime
 is trying to shut down.
+
+When the runtime shuts down every registered weak task is notified, in
+no particular order, and the runtime waits for all of them to exit. A
+task that weakens itself again after being notified (for example, to
+flush once more) is notified immediately.
 */
 
 use option::{Some, None, swap_unwrap};
@@ -23,6 +28,7 @@ use private::at_exit::at_exit;
 use private::global::global_data_clone_create;
 use private::finally::Finally;
 use pipes::{Port, Chan, SharedChan, GenericSmartChan, stream};
+use option;
 use task::{Task, task, spawn};
 use task::rt::{task_id, get_task_id};
 use hashmap::linear::LinearMap;
@@ -38,16 +44,43 @@ pub unsafe fn weaken_task(f: &fn(Port<ShutdownMsg>)) {
     let (shutdown_port, shutdown_chan) = stream::<ShutdownMsg>();
     let shutdown_port = ~mut Some(shutdown_port);
     let task = get_task_id();
-    // Expect the weak task service to be alive
-    assert service.try_send(RegisterWeakTask(task, shutdown_chan));
+    // The service exits once every weak task has been notified and has
+    // unregistered, so if it's gone we are already shutting down.
+    let registered =
+        service.try_send(RegisterWeakTask(task, shutdown_chan));
+    if !registered {
+        let (port, chan) = stream::<ShutdownMsg>();
+        chan.send(());
+        *shutdown_port = Some(port);
+    }
     unsafe { rust_inc_weak_task_count(); }
     do fn&() {
         let shutdown_port = swap_unwrap(&mut *shutdown_port);
         f(shutdown_port)
     }.finally || {
         unsafe { rust_dec_weak_task_count(); }
-        // Service my have already exited
-        service.send(UnregisterWeakTask(task));
+        if registered {
+            service.send(UnregisterWeakTask(task));
+        }
+    }
+}
+
+/**
+ * The number of tasks currently inside `weaken_task`
+ *
+ * The weak task service itself is not counted. Intended for diagnostics;
+ * the answer may be out of date by the time it is returned.
+ */
+pub fn count() -> uint {
+    unsafe {
+        let service = global_data_clone_create(global_data_key,
+                                               create_global_service);
+        let (port, chan) = stream::<uint>();
+        if service.try_send(CountWeakTasks(chan)) {
+            option::get_or_default(port.try_recv(), 0)
+        } else {
+            0
+        }
     }
 }
 
@@ -59,6 +92,7 @@ fn global_data_key(_v: WeakTaskService) { }
 enum ServiceMsg {
     RegisterWeakTask(TaskHandle, Chan<ShutdownMsg>),
     UnregisterWeakTask(TaskHandle),
+    CountWeakTasks(Chan<uint>),
     Shutdown
 }
 
@@ -97,10 +131,16 @@ fn create_global_service() -> ~WeakTaskService {
 fn run_weak_task_service(port: Port<ServiceMsg>) {
 
     let mut shutdown_map = LinearMap::new();
+    let mut shutting_down = false;
 
     loop {
         match port.recv() {
             RegisterWeakTask(task, shutdown_chan) => {
+                // Tasks that weaken themselves during shutdown are told
+                // straight away
+                if shutting_down {
+                    shutdown_chan.send(());
+                }
                 let previously_unregistered =
                     shutdown_map.insert(task, shutdown_chan);
                 assert previously_unregistered;
@@ -114,15 +154,25 @@ fn run_weak_task_service(port: Port<ServiceMsg>) {
                     }
                     None => die!()
                 }
+                if shutting_down && shutdown_map.is_empty() {
+                    break;
+                }
+            }
+            CountWeakTasks(reply_chan) => {
+                reply_chan.send(shutdown_map.len());
+            }
+            Shutdown => {
+                shutting_down = true;
+                for shutdown_map.each_value |shutdown_chan| {
+                    // Weak task may have already exited
+                    shutdown_chan.send(());
+                }
+                if shutdown_map.is_empty() {
+                    break;
+                }
             }
-            Shutdown => break
         }
     }
-
-    do shutdown_map.consume |_, shutdown_chan| {
-        // Weak task may have already exited
-        shutdown_chan.send(());
-    }
 }
 
 extern {
@@ -183,6 +233,27 @@ fn test_wait_for_signal_many() {
     }
 }
 
+#[test]
+fn test_count() {
+    let (ready_port, ready_chan) = stream();
+    let ready_chan = SharedChan(ready_chan);
+    for 2.times {
+        let ready_chan = ready_chan.clone();
+        do spawn {
+            unsafe {
+                do weaken_task |signal| {
+                    ready_chan.send(());
+                    signal.recv();
+                }
+            }
+        }
+    }
+    ready_port.recv();
+    ready_port.recv();
+    // Other tests may have weak tasks of their own
+    assert count() >= 2;
+}
+
 #[test]
 fn test_select_stream_and_oneshot() {
     use pipes::select2i;
@@ -10,7 +10,6 @@
 
 //! Random number generation
 
-use int;
 use prelude::*;
 use str;
 use task;
@@ -157,7 +156,9 @@ impl Rng {
      */
     fn gen_int_range(start: int, end: int) -> int {
         assert start < end;
-        start + int::abs(self.gen_int() % (end - start))
+        // The width of the range fits in a uint even when it overflows int
+        let range = (end as uint) - (start as uint);
+        start + self.gen_uint_range(0u, range) as int
     }
 
     /// Return a random i8
@@ -191,7 +192,16 @@ impl Rng {
      */
     fn gen_uint_range(start: uint, end: uint) -> uint {
         assert start < end;
-        start + (self.gen_uint() % (end - start))
+        let range = end - start;
+        // Taking every draw modulo the range would favour the low values
+        // whenever the range doesn't divide the number of uint values, so
+        // draws at or above the largest multiple of the range are thrown
+        // away
+        let zone = uint::max_value - uint::max_value % range;
+        loop {
+            let n = self.gen_uint();
+            if n < zone { return start + n % range; }
+        }
     }
 
     /// Return a random u8
@@ -243,7 +253,8 @@ impl Rng {
      */
     fn gen_char_from(chars: &str) -> char {
         assert !chars.is_empty();
-        self.choose(str::chars(chars))
+        let chars = str::chars(chars);
+        *self.choose(chars)
     }
 
     /// Return a random bool
@@ -284,8 +295,9 @@ impl Rng {
     }
 
     /// Choose an item randomly, failing if values is empty
-    fn choose<T:Copy>(values: &[T]) -> T {
-        self.choose_option(values).get()
+    fn choose<T>(values: &r/[T]) -> &r/T {
+        assert !values.is_empty();
+        &values[self.gen_uint_range(0u, values.len())]
     }
 
     /// Choose Some(item) randomly, returning None if values is empty
@@ -342,13 +354,30 @@ impl Rng {
         move r
     }
 
-    /// Shuffle a vec
+    /// Return a shuffled copy of a vec
     fn shuffle<T:Copy>(values: &[T]) -> ~[T] {
         let mut m = vec::from_slice(values);
-        self.shuffle_mut(m);
+        self.shuffle_in_place(m);
         move m
     }
 
+    /**
+     * Shuffle a vec in place, with every order equally likely
+     *
+     * This is the Fisher-Yates shuffle; the elements are only swapped, so
+     * they need not be copyable.
+     */
+    fn shuffle_in_place<T>(values: &mut [T]) {
+        let mut i = values.len();
+        while i >= 2u {
+            // invariant: elements with index >= i have been locked in place.
+            i -= 1u;
+            // lock element i in place.
+            let j = self.gen_uint_range(0u, i + 1u);
+            values[i] <-> values[j];
+        }
+    }
+
     /// Shuffle a mutable vec in place
     fn shuffle_mut<T>(values: &[mut T]) {
         let mut i = values.len();
@@ -404,10 +433,13 @@ pub fn Rng() -> Rng {
  * constructed with a given seed will generate the same sequence of values as
  * all other generators constructed with the same seed. The seed may be any
  * length.
+ *
+ * The generator is ISAAC, seeded with exactly the given bytes, which makes
+ * it the one to use for reproducible tests.
  */
-pub fn seeded_rng(seed: &~[u8]) -> Rng {
+pub fn seeded_rng(seed: &[u8]) -> Rng {
     unsafe {
-        @RandRes(rustrt::rand_new_seeded2(*seed)) as Rng
+        @RandRes(rustrt::rand_new_seeded2(vec::from_slice(seed))) as Rng
     }
 }
 
@@ -477,14 +509,17 @@ pub fn random() -> uint {
 #[cfg(test)]
 pub mod tests {
     use debug;
+    use int;
     use option::{None, Option, Some};
     use rand;
+    use uint;
+    use vec;
 
     #[test]
     pub fn rng_seeded() {
         let seed = rand::seed();
-        let ra = rand::seeded_rng(&seed);
-        let rb = rand::seeded_rng(&seed);
+        let ra = rand::seeded_rng(seed);
+        let rb = rand::seeded_rng(seed);
         assert ra.gen_str(100u) == rb.gen_str(100u);
     }
 
@@ -492,15 +527,15 @@ pub mod tests {
     pub fn rng_seeded_custom_seed() {
         // much shorter than generated seeds which are 1024 bytes
         let seed = ~[2u8, 32u8, 4u8, 32u8, 51u8];
-        let ra = rand::seeded_rng(&seed);
-        let rb = rand::seeded_rng(&seed);
+        let ra = rand::seeded_rng(seed);
+        let rb = rand::seeded_rng(seed);
         assert ra.gen_str(100u) == rb.gen_str(100u);
     }
 
     #[test]
     pub fn rng_seeded_custom_seed2() {
         let seed = ~[2u8, 32u8, 4u8, 32u8, 51u8];
-        let ra = rand::seeded_rng(&seed);
+        let ra = rand::seeded_rng(seed);
         // Regression test that isaac is actually using the above vector
         let r = ra.next();
         error!("%?", r);
@@ -540,6 +575,44 @@ pub mod tests {
         rand::Rng().gen_uint_range(5u, 2u);
     }
 
+    #[test]
+    pub fn gen_range_extremes() {
+        let r = rand::Rng();
+        let a = r.gen_uint_range(uint::max_value - 2u, uint::max_value);
+        assert a == uint::max_value - 2u || a == uint::max_value - 1u;
+        let b = r.gen_uint_range(0u, uint::max_value);
+        assert b < uint::max_value;
+        let c = r.gen_int_range(int::min_value, int::max_value);
+        assert c < int::max_value;
+        assert r.gen_int_range(int::min_value, int::min_value + 1) ==
+            int::min_value;
+    }
+
+    #[test]
+    pub fn gen_uint_range_uniform() {
+        // A chi-square test of 10000 draws over 10 values; with 9 degrees
+        // of freedom the statistic exceeds 33.7 only one time in 10000
+        let r = rand::seeded_rng([1, 2, 3]);
+        let mut counts = vec::from_elem(10u, 0u);
+        for 10000.times {
+            counts[r.gen_uint_range(0u, 10u)] += 1u;
+        }
+        let mut chi2 = 0.0;
+        for counts.each |c| {
+            assert *c > 0u;
+            let d = (*c as float) - 1000.0;
+            chi2 += d * d / 1000.0;
+        }
+        assert chi2 < 33.7;
+
+        // small ranges reach both ends
+        let mut seen = [false, false, false];
+        for 100.times {
+            seen[(r.gen_int_range(-1, 2) + 1) as uint] = true;
+        }
+        assert seen[0] && seen[1] && seen[2];
+    }
+
     #[test]
     pub fn gen_float() {
         let r = rand::Rng();
@@ -577,7 +650,15 @@ pub mod tests {
     #[test]
     pub fn choose() {
         let r = rand::Rng();
-        assert r.choose([1, 1, 1]) == 1;
+        assert *r.choose([1, 1, 1]) == 1;
+    }
+
+    #[test]
+    pub fn choose_ref() {
+        let r = rand::Rng();
+        let v = [~"a", ~"b", ~"c"];
+        let x = r.choose(v);
+        assert vec::contains(v, x);
     }
 
     #[test]
@@ -634,6 +715,45 @@ pub mod tests {
         assert r.shuffle(~[1, 1, 1]) == ~[1, 1, 1];
     }
 
+    #[test]
+    pub fn shuffle_in_place() {
+        let r = rand::Rng();
+        let mut empty: ~[~str] = ~[];
+        r.shuffle_in_place(empty);
+        assert empty.is_empty();
+
+        // owned values are moved, not copied, and none are lost
+        let orig = ~[~"a", ~"b", ~"b", ~"c", ~"d", ~"d", ~"d", ~"e"];
+        let mut v = copy orig;
+        for 20.times {
+            r.shuffle_in_place(v);
+            assert v.len() == orig.len();
+            for orig.each |x| {
+                assert vec::count(v, x) == vec::count(orig, x);
+            }
+        }
+    }
+
+    #[test]
+    pub fn seeded_sequences() {
+        let seed = [7u8, 3u8, 9u8];
+        let ra = rand::seeded_rng(seed);
+        let rb = rand::seeded_rng(seed);
+        let rc = rand::seeded_rng([7u8, 3u8, 10u8]);
+        let a = vec::from_fn(20u, |_| ra.gen_uint_range(0u, 1000u));
+        let b = vec::from_fn(20u, |_| rb.gen_uint_range(0u, 1000u));
+        let c = vec::from_fn(20u, |_| rc.gen_uint_range(0u, 1000u));
+        assert a == b;
+        assert a != c;
+
+        let mut va = vec::from_fn(50u, |i| i);
+        let mut vb = vec::from_fn(50u, |i| i);
+        ra.shuffle_in_place(va);
+        rb.shuffle_in_place(vb);
+        assert va == vb;
+        assert va != vec::from_fn(50u, |i| i);
+    }
+
     #[test]
     pub fn task_rng() {
         let r = rand::task_rng();
@@ -322,6 +322,14 @@ impl<V: TyVisitor MovePtr> MovePtrAdaptor<V>: TyVisitor {
         true
     }
 
+    #[cfg(stage1)]
+    #[cfg(stage2)]
+    #[cfg(stage3)]
+    fn visit_class_name(&self, name: &str) -> bool {
+        if ! self.inner.visit_class_name(name) { return false; }
+        true
+    }
+
     fn visit_enter_class(&self, n_fields: uint, sz: uint, align: uint)
                       -> bool {
         self.align(align);
@@ -402,6 +410,14 @@ impl<V: TyVisitor MovePtr> MovePtrAdaptor<V>: TyVisitor {
         true
     }
 
+    #[cfg(stage1)]
+    #[cfg(stage2)]
+    #[cfg(stage3)]
+    fn visit_enum_null_discrim(&self, disr_val: int) -> bool {
+        if ! self.inner.visit_enum_null_discrim(disr_val) { return false; }
+        true
+    }
+
     fn visit_enter_enum_variant(&self, variant: uint,
                                 disr_val: int,
                                 n_fields: uint,
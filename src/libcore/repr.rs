@@ -139,6 +139,9 @@ enum VariantState {
     Degenerate,
     TagMatch,
     TagMismatch,
+    // The enum is a pointer, null for the variant with this discriminant
+    NullMatch(int),
+    NullMismatch(int),
 }
 
 pub struct ReprVisitor {
@@ -413,9 +416,20 @@ impl ReprVisitor : TyVisitor {
         true
     }
 
-    fn visit_enter_class(&self, _n_fields: uint,
+    // Structs are written as they appear in source, `Name { field: value }`
+    #[cfg(stage1)]
+    #[cfg(stage2)]
+    #[cfg(stage3)]
+    fn visit_class_name(&self, name: &str) -> bool {
+        self.writer.write_str(name);
+        true
+    }
+
+    fn visit_enter_class(&self, n_fields: uint,
                          _sz: uint, _align: uint) -> bool {
-        self.writer.write_char('{');
+        if n_fields != 0 {
+            self.writer.write_str(" { ");
+        }
         true
     }
     fn visit_class_field(&self, i: uint, name: &str,
@@ -429,9 +443,11 @@ impl ReprVisitor : TyVisitor {
         self.visit_inner(inner);
         true
     }
-    fn visit_leave_class(&self, _n_fields: uint,
+    fn visit_leave_class(&self, n_fields: uint,
                          _sz: uint, _align: uint) -> bool {
-        self.writer.write_char('}');
+        if n_fields != 0 {
+            self.writer.write_str(" }");
+        }
         true
     }
 
@@ -463,6 +479,15 @@ impl ReprVisitor : TyVisitor {
         true
     }
 
+    #[cfg(stage1)]
+    #[cfg(stage2)]
+    #[cfg(stage3)]
+    fn visit_enum_null_discrim(&self, disr_val: int) -> bool {
+        self.var_stk.pop();
+        self.var_stk.push(NullMatch(disr_val));
+        true
+    }
+
     fn visit_enter_enum_variant(&self, _variant: uint,
                                 disr_val: int,
                                 n_fields: uint,
@@ -484,6 +509,17 @@ impl ReprVisitor : TyVisitor {
                 };
                 self.bump_past::<int>();
             }
+            NullMatch(null_disr) | NullMismatch(null_disr) => {
+                // The pointer is the variant's only field, so stay put
+                do self.get::<*c_void>() |p| {
+                    if (disr_val == null_disr) == ptr::is_null(*p) {
+                        write = true;
+                        self.var_stk.push(NullMatch(null_disr));
+                    } else {
+                        self.var_stk.push(NullMismatch(null_disr));
+                    }
+                };
+            }
         }
 
         if write {
@@ -497,7 +533,7 @@ impl ReprVisitor : TyVisitor {
 
     fn visit_enum_variant_field(&self, i: uint, inner: *TyDesc) -> bool {
         match self.var_stk.last() {
-            Degenerate | TagMatch => {
+            Degenerate | TagMatch | NullMatch(_) => {
                 if i != 0 {
                     self.writer.write_str(", ");
                 }
@@ -505,7 +541,7 @@ impl ReprVisitor : TyVisitor {
                     return false;
                 }
             }
-            TagMismatch => ()
+            TagMismatch | NullMismatch(_) => ()
         }
         true
     }
@@ -515,12 +551,12 @@ impl ReprVisitor : TyVisitor {
                                 n_fields: uint,
                                 _name: &str) -> bool {
         match self.var_stk.last() {
-            Degenerate | TagMatch => {
+            Degenerate | TagMatch | NullMatch(_) => {
                 if n_fields > 0 {
                     self.writer.write_char(')');
                 }
             }
-            TagMismatch => ()
+            TagMismatch | NullMismatch(_) => ()
         }
         true
     }
@@ -577,8 +613,12 @@ pub fn write_repr<T>(writer: @Writer, object: &T) {
 #[test]
 struct P {a: int, b: float}
 
+#[test]
+struct U;
+
 #[test]
 fn test_repr() {
+    use option::{None, Some};
 
     fn exact_test<T>(t: &T, e:&str) {
         let s : &str = io::with_str_writer(|w| repr::write_repr(w, t));
@@ -618,11 +658,12 @@ fn test_repr() {
     exact_test(&(&["hi", "there"]),
                "&[\"hi\", \"there\"]");
     exact_test(&(P{a:10, b:1.234}),
-               "{a: 10, b: 1.234}");
+               "P { a: 10, b: 1.234 }");
     exact_test(&(@P{a:10, b:1.234}),
-               "@{a: 10, b: 1.234}");
+               "@P { a: 10, b: 1.234 }");
     exact_test(&(~P{a:10, b:1.234}),
-               "~{a: 10, b: 1.234}");
+               "~P { a: 10, b: 1.234 }");
+    exact_test(&(U), "U");
     exact_test(&(10_u8, ~"hello"),
                "(10, ~\"hello\")");
     exact_test(&(10_u16, ~"hello"),
@@ -631,4 +672,10 @@ fn test_repr() {
                "(10, ~\"hello\")");
     exact_test(&(10_u64, ~"hello"),
                "(10, ~\"hello\")");
+
+    // Enums that are only a pointer
+    exact_test(&(Some(~10)), "Some(~10)");
+    exact_test(&(None::<~int>), "None");
+    exact_test(&(Some(@"hi")), "Some(@\"hi\")");
+    exact_test(&(Some(10)), "Some(10)");
 }
@@ -259,16 +259,41 @@ impl<T, E> Result<T, E> {
     pure fn chain_err<F>(self, op: fn(E) -> Result<T,F>) -> Result<T,F> {
         chain_err(self, op)
     }
-}
 
-impl<T: Copy, E> Result<T, E> {
+    /// Same as `chain`
     #[inline(always)]
-    pure fn get(&self) -> T { get(self) }
+    pure fn and_then<U>(self, op: fn(T) -> Result<U,E>) -> Result<U,E> {
+        chain(self, op)
+    }
 
+    /// Same as `chain_err`
     #[inline(always)]
-    pure fn map_err<F:Copy>(&self, op: fn(&E) -> F) -> Result<T,F> {
-        map_err(self, op)
+    pure fn or_else<F>(self, op: fn(E) -> Result<T,F>) -> Result<T,F> {
+        chain_err(self, op)
     }
+
+    /**
+     * Convert the error, leaving an `Ok` value untouched
+     *
+     * Unlike the `map_err` function this consumes the result, so neither
+     * the value nor the error need to be copyable.
+     *
+     * Example:
+     *
+     *     let res = read_file(file).map_err(|e| IoError(e));
+     */
+    #[inline(always)]
+    pure fn map_err<F>(self, op: fn(E) -> F) -> Result<T,F> {
+        match move self {
+          Ok(move t) => Ok(move t),
+          Err(move e) => Err(op(move e))
+        }
+    }
+}
+
+impl<T: Copy, E> Result<T, E> {
+    #[inline(always)]
+    pure fn get(&self) -> T { get(self) }
 }
 
 impl<T, E: Copy> Result<T, E> {
@@ -398,6 +423,9 @@ mod tests {
     use result::{Err, Ok, Result, chain, get, get_err};
     use result;
 
+    use int;
+    use str;
+
     pub fn op1() -> result::Result<int, ~str> { result::Ok(666) }
 
     pub fn op2(i: int) -> result::Result<uint, ~str> {
@@ -449,6 +477,62 @@ mod tests {
         assert Err::<~str, ~str>(~"a").map_err(|_x| ~"b") == Err(~"b");
     }
 
+    #[test]
+    pub fn test_impl_map_err_by_move() {
+        // Neither side is copyable
+        let r: Result<~int, ~str> = Err(~"a");
+        assert r.map_err(|e| ~[move e]) == Err(~[~"a"]);
+        let r: Result<~int, ~str> = Ok(~1);
+        assert r.map_err(|e| ~[move e]) == Ok(~1);
+    }
+
+    #[test]
+    pub fn test_impl_and_then_or_else() {
+        assert op1().and_then(op2) == Ok(667u);
+        assert op3().and_then(op2) == Err(~"sadface");
+        assert op3().or_else(|_e| Ok::<int, ()>(1)) == Ok(1);
+        assert op1().or_else(|_e| Err::<int, ()>(())) == Ok(666);
+    }
+
+    #[deriving_eq]
+    enum MyError {
+        Missing,
+        BadNumber(~str),
+        TooLarge(int),
+    }
+
+    fn parse(s: &str) -> Result<int, ~str> {
+        match int::from_str(s) {
+            Some(n) => Ok(n),
+            None => Err(str::from_slice(s))
+        }
+    }
+
+    fn check(n: int) -> Result<int, MyError> {
+        if n > 100 { Err(TooLarge(n)) } else { Ok(n) }
+    }
+
+    fn parse_and_check(s: &str) -> Result<int, MyError> {
+        let r = if str::is_empty(s) { Err(~"") } else { parse(s) };
+        r.map_err(|e| if str::is_empty(e) { Missing } else { BadNumber(e) })
+            .and_then(check)
+            .and_then(|n| Ok(n * 2))
+    }
+
+    #[test]
+    pub fn test_convert_error_pipeline() {
+        assert parse_and_check("21") == Ok(42);
+        assert parse_and_check("") == Err(Missing);
+        assert parse_and_check("x") == Err(BadNumber(~"x"));
+        assert parse_and_check("101") == Err(TooLarge(101));
+
+        let mut seen = 0;
+        parse_and_check("5").iter(|n| seen = *n);
+        parse_and_check("x").iter(|_n| seen = -1);
+        assert seen == 10;
+        parse_and_check("").iter_err(|e| assert *e == Missing);
+    }
+
     #[test]
     pub fn test_get_ref_method() {
         let foo: Result<int, ()> = Ok(100);
@@ -12,10 +12,10 @@
 
 //! Process spawning
 use io;
-use io::ReaderUtil;
+use io::{ReaderUtil, WriterUtil};
 use libc;
 use libc::{pid_t, c_void, c_int};
-use pipes::{stream, SharedChan};
+use pipes::{stream, Port, SharedChan};
 use option::{Some, None};
 use os;
 use prelude::*;
@@ -23,6 +23,7 @@ use ptr;
 use run;
 use str;
 use task;
+use uint;
 use vec;
 
 #[abi = "cdecl"]
@@ -31,6 +32,8 @@ extern mod rustrt {
                                dir: *libc::c_char,
                                in_fd: c_int, out_fd: c_int, err_fd: c_int)
                             -> pid_t;
+    unsafe fn rust_process_kill(pid: pid_t) -> c_int;
+    unsafe fn rust_block_sigpipe();
 }
 
 /// A value representing a child process
@@ -295,10 +298,20 @@ fn read_all(rd: io::Reader) -> ~str {
     str::from_bytes(buf)
 }
 
+/// The exit code and the captured output of a program
+pub struct ProgramOutput {
+    status: int,
+    out: ~str,
+    err: ~str,
+}
+
 /**
  * Spawns a process, waits for it to exit, and returns the exit code, and
  * contents of stdout and stderr.
  *
+ * Both streams are read at the same time, so a child that fills the pipe
+ * of one of them while the other is still open can't deadlock.
+ *
  * # Arguments
  *
  * * prog - The path to an executable
@@ -306,11 +319,10 @@ fn read_all(rd: io::Reader) -> ~str {
  *
  * # Return value
  *
- * A record, {status: int, out: str, err: str} containing the exit code,
- * the contents of stdout and the contents of stderr.
+ * A `ProgramOutput` containing the exit code, the contents of stdout and
+ * the contents of stderr.
  */
-pub fn program_output(prog: &str, args: &[~str]) ->
-   {status: int, out: ~str, err: ~str} {
+pub fn program_output(prog: &str, args: &[~str]) -> ProgramOutput {
     unsafe {
         let pipe_in = os::pipe();
         let pipe_out = os::pipe();
@@ -365,13 +377,242 @@ pub fn program_output(prog: &str, args: &[~str]) ->
             };
             count -= 1;
         };
-        return {status: status, out: move outs, err: move errs};
+        return ProgramOutput {
+            status: status,
+            out: move outs,
+            err: move errs
+        };
     }
 }
 
-pub fn writeclose(fd: c_int, s: ~str) {
-    use io::WriterUtil;
+struct Stage {
+    prog: ~str,
+    args: ~[~str],
+}
+
+/**
+ * A builder for a pipeline of processes, like `a | b | c` in a shell
+ *
+ * Each stage's stdout is connected to the next stage's stdin. The output
+ * of the last stage and the stderr of every stage are captured.
+ *
+ * # Example
+ *
+ * ~~~
+ * let res = Pipeline::new()
+ *     .cmd("sort", [~"-u"])
+ *     .cmd("head", [~"-5"])
+ *     .stdin(~"b\na\nb\n")
+ *     .run();
+ * assert res.success();
+ * ~~~
+ */
+pub struct Pipeline {
+    priv stages: ~[Stage],
+    priv input: Option<~str>,
+}
+
+/// The exit statuses and captured output of a finished pipeline
+pub struct PipelineResult {
+    /// The exit code of each stage, in order
+    status: ~[int],
+    /// The contents of the last stage's stdout
+    out: ~str,
+    /// The contents of each stage's stderr, in order
+    err: ~[~str],
+}
+
+pub impl PipelineResult {
+    /// Returns true if every stage exited with status 0
+    fn success() -> bool {
+        self.status.all(|s| *s == 0)
+    }
+}
+
+pub impl Pipeline {
+    static fn new() -> Pipeline {
+        Pipeline { stages: ~[], input: None }
+    }
+
+    /// Appends a stage running `prog` with `args`
+    fn cmd(prog: &str, args: &[~str]) -> Pipeline {
+        let mut stages = copy self.stages;
+        stages.push(Stage {
+            prog: str::from_slice(prog),
+            args: vec::from_slice(args)
+        });
+        Pipeline { stages: move stages, input: copy self.input }
+    }
+
+    /**
+     * Sets the data written to the first stage's stdin
+     *
+     * By default the first stage's stdin is empty rather than inherited
+     * from this process.
+     */
+    fn stdin(input: &str) -> Pipeline {
+        Pipeline {
+            stages: copy self.stages,
+            input: Some(str::from_slice(input))
+        }
+    }
+
+    /**
+     * Spawns every stage and returns without waiting for them
+     *
+     * Fails if there are no stages or if a stage can't be spawned, in
+     * which case the stages already started are killed.
+     */
+    fn start() -> RunningPipeline {
+        if self.stages.is_empty() { die!(~"pipeline has no stages"); }
+
+        let (port, chan) = stream();
+        let chan = SharedChan(chan);
+        let mut pids = ~[];
+
+        let input = os::pipe();
+        let mut in_fd = input.in;
+        for self.stages.eachi |i, stage| {
+            let output = os::pipe();
+            let err = os::pipe();
+            let pid = spawn_process(stage.prog, stage.args, &None, &None,
+                                    in_fd, output.out, err.out);
+            // Only the children need these ends; holding onto them would
+            // keep the next stage from ever seeing the end of its input.
+            os::close(in_fd);
+            os::close(output.out);
+            os::close(err.out);
+            if pid == -1 as pid_t {
+                os::close(input.out);
+                os::close(output.in);
+                os::close(err.in);
+                for pids.each |pid| {
+                    kill_process(*pid);
+                    waitpid(*pid);
+                }
+                die!(fmt!("failed to spawn pipeline stage '%s'",
+                          stage.prog));
+            }
+            pids.push(pid);
+
+            // As in program_output, every pipe is drained by its own
+            // scheduler so that a full pipe can't deadlock the others.
+            let err_in = err.in;
+            let chan = chan.clone();
+            do task::spawn_sched(task::SingleThreaded) {
+                chan.send(StageErr(i, readclose(err_in)));
+            }
+            in_fd = output.in;
+        }
+
+        let out_in = in_fd;
+        let out_chan = chan.clone();
+        do task::spawn_sched(task::SingleThreaded) {
+            out_chan.send(FinalOut(readclose(out_in)));
+        }
+
+        let input_out = input.out;
+        let data = copy self.input;
+        do task::spawn_sched(task::SingleThreaded) {
+            match data {
+                Some(ref s) => write_stage_input(input_out, *s),
+                None => ()
+            }
+            os::close(input_out);
+        }
+
+        RunningPipeline { pids: move pids, port: move port, finished: false }
+    }
+
+    /// Spawns every stage and waits for them all to exit
+    fn run() -> PipelineResult {
+        self.start().finish()
+    }
+}
+
+// Writes `input` to the first stage of a pipeline, giving up quietly if
+// the stage stops reading. A stage may exit without reading all of its
+// input, as `head` does, and that must neither kill this process with
+// SIGPIPE nor fail the task. Must run on a scheduler of its own, since it
+// blocks SIGPIPE for the thread it runs on.
+fn write_stage_input(fd: c_int, input: &str) {
+    unsafe {
+        rustrt::rust_block_sigpipe();
+        do str::byte_slice(input) |bytes| {
+            do vec::as_imm_buf(bytes) |buf, len| {
+                let mut count = 0u;
+                while count < len {
+                    let nout = libc::write(fd,
+                                           ptr::offset(buf, count) as *c_void,
+                                           (len - count) as libc::size_t);
+                    // Most likely EPIPE: nothing more will be read
+                    if nout < 0 as libc::ssize_t { break; }
+                    count += nout as uint;
+                }
+            }
+        }
+    }
+}
+
+enum PipelineOutput {
+    StageErr(uint, ~str),
+    FinalOut(~str),
+}
+
+/**
+ * A pipeline whose stages have been spawned
+ *
+ * If it is dropped without calling `finish` then every stage is killed.
+ */
+pub struct RunningPipeline {
+    priv pids: ~[pid_t],
+    priv port: Port<PipelineOutput>,
+    priv mut finished: bool,
+
+    drop {
+        if !self.finished {
+            for self.pids.each |pid| {
+                kill_process(*pid);
+                waitpid(*pid);
+            }
+        }
+    }
+}
+
+pub impl RunningPipeline {
+    /// Kills every stage that is still running
+    fn kill() {
+        if self.finished { return; }
+        for self.pids.each |pid| {
+            kill_process(*pid);
+        }
+    }
+
+    /// Waits for every stage to exit and collects their output
+    fn finish() -> PipelineResult {
+        if self.finished { die!(~"pipeline already finished"); }
+        self.finished = true;
+
+        let status = self.pids.map(|pid| waitpid(*pid));
+        let mut out = ~"";
+        let mut err = vec::from_elem(self.pids.len(), ~"");
+        for uint::range(0, self.pids.len() + 1) |_i| {
+            match self.port.recv() {
+                StageErr(i, move s) => err[i] = move s,
+                FinalOut(move s) => out = move s
+            }
+        }
+        PipelineResult { status: move status, out: move out, err: move err }
+    }
+}
 
+fn kill_process(pid: pid_t) {
+    unsafe {
+        rustrt::rust_process_kill(pid);
+    }
+}
+
+pub fn writeclose(fd: c_int, s: ~str) {
     error!("writeclose %d, %s", fd as int, s);
     let writer = io::fd_writer(fd, false);
     writer.write_str(s);
@@ -442,11 +683,12 @@ pub fn waitpid(pid: pid_t) -> int {
 #[cfg(test)]
 mod tests {
     use debug;
-    use io::WriterUtil;
+    use io::{ReaderUtil, WriterUtil};
     use option::{None, Some};
     use os;
-    use run::{readclose, writeclose};
+    use run::{Pipeline, readclose, writeclose};
     use run;
+    use str;
 
     // Regression test for memory leaks
     #[ignore(cfg(windows))] // FIXME (#2626)
@@ -483,6 +725,42 @@ mod tests {
         assert (expected == actual);
     }
 
+    #[test]
+    #[ignore(cfg(windows))]
+    pub fn test_program_output() {
+        let output = run::program_output(
+            "sh", [~"-c", ~"echo out; echo err >&2; exit 3"]);
+        assert output.status == 3;
+        assert output.out == ~"out\n";
+        assert output.err == ~"err\n";
+    }
+
+    #[test]
+    #[ignore(cfg(windows))]
+    pub fn test_program_output_large() {
+        // Far more than a pipe buffer on both streams, which would
+        // deadlock if they were read one after the other
+        let output = run::program_output(
+            "sh", [~"-c", ~"yes err | head -c 300000 >&2; " +
+                          "yes out | head -c 300000"]);
+        assert output.status == 0;
+        assert output.out.len() == 300000;
+        assert output.err.len() == 300000;
+        assert str::starts_with(output.out, "out\nout\n");
+        assert str::starts_with(output.err, "err\nerr\n");
+    }
+
+    #[test]
+    #[ignore(cfg(windows))]
+    pub fn test_program_input() {
+        let p = run::start_program("cat", []);
+        p.input().write_str("fed to stdin\n");
+        p.close_input();
+        assert str::from_bytes(p.output().read_whole_stream()) ==
+            ~"fed to stdin\n";
+        assert p.finish() == 0;
+    }
+
     #[test]
     pub fn waitpid() {
         let pid = run::spawn_process("false", [],
@@ -492,6 +770,92 @@ mod tests {
         assert status == 1;
     }
 
+    #[test]
+    #[ignore(cfg(windows))]
+    pub fn test_pipeline() {
+        let res = Pipeline::new()
+            .cmd("sort", [~"-u"])
+            .cmd("head", [~"-2"])
+            .stdin("c\na\nb\na\n")
+            .run();
+        assert res.status == ~[0, 0];
+        assert res.out == ~"a\nb\n";
+        assert res.err == ~[~"", ~""];
+        assert res.success();
+    }
+
+    #[test]
+    #[ignore(cfg(windows))]
+    pub fn test_pipeline_failing_stage() {
+        let res = Pipeline::new()
+            .cmd("cat", [])
+            .cmd("sh", [~"-c", ~"cat >/dev/null; echo oops >&2; exit 3"])
+            .cmd("cat", [])
+            .stdin("ignored\n")
+            .run();
+        assert res.status == ~[0, 3, 0];
+        assert res.err[1] == ~"oops\n";
+        assert res.out == ~"";
+        assert !res.success();
+    }
+
+    #[test]
+    #[ignore(cfg(windows))]
+    pub fn test_pipeline_large_data() {
+        let line = str::repeat("x", 99) + "\n";
+        let input = str::repeat(line, 20000);
+        let res = Pipeline::new()
+            .cmd("cat", [])
+            .cmd("cat", [])
+            .stdin(input)
+            .run();
+        assert res.success();
+        assert res.out.len() == input.len();
+        assert res.out == input;
+    }
+
+    #[test]
+    #[ignore(cfg(windows))]
+    pub fn test_pipeline_stage_stops_reading() {
+        // Far more input than a pipe holds, so writing it outlives `head`
+        let line = str::repeat("x", 99) + "\n";
+        let input = str::repeat(line, 20000);
+        let res = Pipeline::new()
+            .cmd("head", [~"-n1"])
+            .stdin(input)
+            .run();
+        assert res.success();
+        assert res.out == line;
+    }
+
+    #[test]
+    #[ignore(cfg(windows))]
+    pub fn test_pipeline_fd_hygiene() {
+        // Reports which descriptors above stderr the last stage inherited
+        let list_fds = ~"for fd in 3 4 5 6 7 8 9 10 11 12; do \
+                         if (exec <&$fd) 2>/dev/null; then echo $fd; fi; \
+                         done";
+        let res = Pipeline::new()
+            .cmd("cat", [])
+            .cmd("sh", [~"-c", list_fds])
+            .run();
+        assert res.success();
+        assert res.out == ~"";
+    }
+
+    #[test]
+    #[ignore(cfg(windows))]
+    pub fn test_pipeline_kill() {
+        let running = Pipeline::new()
+            .cmd("sleep", [~"1000"])
+            .cmd("cat", [])
+            .start();
+        running.kill();
+        let res = running.finish();
+        assert res.status[0] != 0;
+        assert !res.success();
+    }
+
 }
 
 // Local Variables:
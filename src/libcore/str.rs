@@ -20,6 +20,7 @@
 use at_vec;
 use cast;
 use char;
+use clone::Clone;
 use cmp::{Eq, Ord};
 use libc;
 use libc::size_t;
@@ -223,7 +224,12 @@ pub pure fn connect(v: &[~str], sep: &str) -> ~str {
 /// Given a string, make a new string with repeated copies of it
 pub pure fn repeat(ss: &str, nn: uint) -> ~str {
     let mut acc = ~"";
-    for nn.times { acc += ss; }
+    unsafe {
+        // Allocate the exact size up front so that the pushes never
+        // have to reallocate
+        reserve(&mut acc, len(ss) * nn);
+        for nn.times { push_str_no_overallocate(&mut acc, ss); }
+    }
     acc
 }
 
@@ -283,38 +289,44 @@ pub fn unshift_char(s: &mut ~str, ch: char) {
 /**
  * Returns a string with leading `chars_to_trim` removed.
  *
+ * The result is a slice of `s`. Characters are compared whole, so a
+ * multibyte character is either trimmed completely or not at all.
+ *
  * # Arguments
  *
  * * s - A string
  * * chars_to_trim - A vector of chars
  *
  */
-pub pure fn trim_left_chars(s: &str, chars_to_trim: &[char]) -> ~str {
-    if chars_to_trim.is_empty() { return from_slice(s); }
+pub pure fn trim_left_chars(s: &a/str, chars_to_trim: &[char]) -> &a/str {
+    if chars_to_trim.is_empty() { return s; }
 
     match find(s, |c| !chars_to_trim.contains(&c)) {
-      None => ~"",
-      Some(first) => unsafe { raw::slice_bytes(s, first, s.len()) }
+      None => "",
+      Some(first) => unsafe { raw::view_bytes(s, first, s.len()) }
     }
 }
 
 /**
  * Returns a string with trailing `chars_to_trim` removed.
  *
+ * The result is a slice of `s`. Characters are compared whole, so a
+ * multibyte character is either trimmed completely or not at all.
+ *
  * # Arguments
  *
  * * s - A string
  * * chars_to_trim - A vector of chars
  *
  */
-pub pure fn trim_right_chars(s: &str, chars_to_trim: &[char]) -> ~str {
-    if chars_to_trim.is_empty() { return str::from_slice(s); }
+pub pure fn trim_right_chars(s: &a/str, chars_to_trim: &[char]) -> &a/str {
+    if chars_to_trim.is_empty() { return s; }
 
     match rfind(s, |c| !chars_to_trim.contains(&c)) {
-      None => ~"",
+      None => "",
       Some(last) => {
         let next = char_range_at(s, last).next;
-        unsafe { raw::slice_bytes(s, 0u, next) }
+        unsafe { raw::view_bytes(s, 0u, next) }
       }
     }
 }
@@ -322,13 +334,15 @@ pub pure fn trim_right_chars(s: &str, chars_to_trim: &[char]) -> ~str {
 /**
  * Returns a string with leading and trailing `chars_to_trim` removed.
  *
+ * The result is a slice of `s`.
+ *
  * # Arguments
  *
  * * s - A string
  * * chars_to_trim - A vector of chars
  *
  */
-pub pure fn trim_chars(s: &str, chars_to_trim: &[char]) -> ~str {
+pub pure fn trim_chars(s: &a/str, chars_to_trim: &[char]) -> &a/str {
     trim_left_chars(trim_right_chars(s, chars_to_trim), chars_to_trim)
 }
 
@@ -447,32 +461,13 @@ pub pure fn split_char_nonempty(s: &str, sep: char) -> ~[~str] {
 
 pure fn split_char_inner(s: &str, sep: char, count: uint, allow_empty: bool)
     -> ~[~str] {
-    if sep < 128u as char {
-        let b = sep as u8, l = len(s);
-        let mut result = ~[], done = 0u;
-        let mut i = 0u, start = 0u;
-        while i < l && done < count {
-            if s[i] == b {
-                if allow_empty || start < i {
-                    unsafe {
-                        result.push(raw::slice_bytes(s, start, i));
-                    }
-                }
-                start = i + 1u;
-                done += 1u;
-            }
-            i += 1u;
-        }
-        if allow_empty || start < l {
-            unsafe { result.push(raw::slice_bytes(s, start, l) ) };
-        }
-        result
-    } else {
-        splitn(s, |cur| cur == sep, count)
+    let mut result = ~[];
+    for each_split_char_inner(s, sep, count, allow_empty, allow_empty) |v| {
+        unsafe { result.push(from_slice(v)); }
     }
+    result
 }
 
-
 /// Splits a string into substrings using a character function
 pub pure fn split(s: &str, sepfn: fn(char) -> bool) -> ~[~str] {
     split_inner(s, sepfn, len(s), true)
@@ -493,14 +488,95 @@ pub pure fn split_nonempty(s: &str, sepfn: fn(char) -> bool) -> ~[~str] {
 
 pure fn split_inner(s: &str, sepfn: fn(cc: char) -> bool, count: uint,
                allow_empty: bool) -> ~[~str] {
+    let mut result = ~[];
+    for each_split_inner(s, sepfn, count, allow_empty, allow_empty) |v| {
+        unsafe { result.push(from_slice(v)); }
+    }
+    result
+}
+
+/**
+ * Iterates over the substrings of `s` separated by a given character
+ *
+ * Like `split_char`, but each substring is a slice of `s` rather than a
+ * new string. Return false from `it` to stop early.
+ */
+pub pure fn each_split_char(s: &a/str, sep: char, it: fn(&a/str) -> bool) {
+    each_split_char_inner(s, sep, len(s), true, true, it)
+}
+
+/**
+ * Like `each_split_char`, but leaves out the empty substring that follows
+ * a separator at the very end of `s`
+ *
+ * So `"a,b,"` gives `"a"` and `"b"`, and the empty string gives nothing.
+ */
+pub pure fn each_split_char_no_trailing(s: &a/str, sep: char,
+                                        it: fn(&a/str) -> bool) {
+    each_split_char_inner(s, sep, len(s), true, false, it)
+}
+
+/**
+ * Iterates over the substrings of `s` separated by characters matching
+ * `sepfn`
+ *
+ * Like `split`, but each substring is a slice of `s` rather than a new
+ * string. Return false from `it` to stop early.
+ */
+pub pure fn each_split(s: &a/str, sepfn: fn(cc: char) -> bool,
+                       it: fn(&a/str) -> bool) {
+    each_split_inner(s, sepfn, len(s), true, true, it)
+}
+
+/**
+ * Like `each_split`, but leaves out the empty substring that follows a
+ * separator at the very end of `s`
+ */
+pub pure fn each_split_no_trailing(s: &a/str, sepfn: fn(cc: char) -> bool,
+                                   it: fn(&a/str) -> bool) {
+    each_split_inner(s, sepfn, len(s), true, false, it)
+}
+
+pure fn each_split_char_inner(s: &a/str, sep: char, count: uint,
+                              allow_empty: bool, allow_trailing_empty: bool,
+                              it: fn(&a/str) -> bool) {
+    if sep < 128u as char {
+        let b = sep as u8, l = len(s);
+        let mut done = 0u;
+        let mut i = 0u, start = 0u;
+        while i < l && done < count {
+            if s[i] == b {
+                if allow_empty || start < i {
+                    if !it(unsafe { raw::view_bytes(s, start, i) }) {
+                        return;
+                    }
+                }
+                start = i + 1u;
+                done += 1u;
+            }
+            i += 1u;
+        }
+        if allow_trailing_empty || start < l {
+            it(unsafe { raw::view_bytes(s, start, l) });
+        }
+    } else {
+        each_split_inner(s, |cur| cur == sep, count, allow_empty,
+                         allow_trailing_empty, it)
+    }
+}
+
+pure fn each_split_inner(s: &a/str, sepfn: fn(cc: char) -> bool,
+                         count: uint, allow_empty: bool,
+                         allow_trailing_empty: bool,
+                         it: fn(&a/str) -> bool) {
     let l = len(s);
-    let mut result = ~[], i = 0u, start = 0u, done = 0u;
+    let mut i = 0u, start = 0u, done = 0u;
     while i < l && done < count {
         let CharRange {ch, next} = char_range_at(s, i);
         if sepfn(ch) {
             if allow_empty || start < i {
-                unsafe {
-                    result.push(raw::slice_bytes(s, start, i));
+                if !it(unsafe { raw::view_bytes(s, start, i) }) {
+                    return;
                 }
             }
             start = next;
@@ -508,16 +584,13 @@ pure fn split_inner(s: &str, sepfn: fn(cc: char) -> bool, count: uint,
         }
         i = next;
     }
-    if allow_empty || start < l {
-        unsafe {
-            result.push(raw::slice_bytes(s, start, l));
-        }
+    if allow_trailing_empty || start < l {
+        it(unsafe { raw::view_bytes(s, start, l) });
     }
-    result
 }
 
 // See Issue #1932 for why this is a naive search
-pure fn iter_matches(s: &a/str, sep: &b/str, f: fn(uint, uint)) {
+pure fn iter_matches(s: &a/str, sep: &b/str, f: fn(uint, uint) -> bool) {
     let sep_len = len(sep), l = len(s);
     assert sep_len > 0u;
     let mut i = 0u, match_start = 0u, match_i = 0u;
@@ -528,7 +601,7 @@ pure fn iter_matches(s: &a/str, sep: &b/str, f: fn(uint, uint)) {
             match_i += 1u;
             // Found a match
             if match_i == sep_len {
-                f(match_start, i + 1u);
+                if !f(match_start, i + 1u) { return; }
                 match_i = 0u;
             }
             i += 1u;
@@ -544,10 +617,11 @@ pure fn iter_matches(s: &a/str, sep: &b/str, f: fn(uint, uint)) {
     }
 }
 
-pure fn iter_between_matches(s: &a/str, sep: &b/str, f: fn(uint, uint)) {
+pure fn iter_between_matches(s: &a/str, sep: &b/str,
+                             f: fn(uint, uint) -> bool) {
     let mut last_end = 0u;
-    do iter_matches(s, sep) |from, to| {
-        f(last_end, from);
+    for iter_matches(s, sep) |from, to| {
+        if !f(last_end, from) { return; }
         last_end = to;
     }
     f(last_end, len(s));
@@ -564,22 +638,47 @@ pure fn iter_between_matches(s: &a/str, sep: &b/str, f: fn(uint, uint)) {
  */
 pub pure fn split_str(s: &a/str, sep: &b/str) -> ~[~str] {
     let mut result = ~[];
-    do iter_between_matches(s, sep) |from, to| {
-        unsafe { result.push(raw::slice_bytes(s, from, to)); }
+    for each_split_str(s, sep) |v| {
+        unsafe { result.push(from_slice(v)); }
     }
     result
 }
 
 pub pure fn split_str_nonempty(s: &a/str, sep: &b/str) -> ~[~str] {
     let mut result = ~[];
-    do iter_between_matches(s, sep) |from, to| {
-        if to > from {
-            unsafe { result.push(raw::slice_bytes(s, from, to)); }
+    for each_split_str(s, sep) |v| {
+        if !is_empty(v) {
+            unsafe { result.push(from_slice(v)); }
         }
     }
     result
 }
 
+/**
+ * Iterates over the substrings of `s` separated by a given string
+ *
+ * Like `split_str`, but each substring is a slice of `s` rather than a new
+ * string. Return false from `it` to stop early.
+ */
+pub pure fn each_split_str(s: &a/str, sep: &b/str, it: fn(&a/str) -> bool) {
+    for iter_between_matches(s, sep) |from, to| {
+        if !it(unsafe { raw::view_bytes(s, from, to) }) { return; }
+    }
+}
+
+/**
+ * Like `each_split_str`, but leaves out the empty substring that follows
+ * a separator at the very end of `s`
+ */
+pub pure fn each_split_str_no_trailing(s: &a/str, sep: &b/str,
+                                       it: fn(&a/str) -> bool) {
+    let l = len(s);
+    for iter_between_matches(s, sep) |from, to| {
+        if from == l && to == l { return; }
+        if !it(unsafe { raw::view_bytes(s, from, to) }) { return; }
+    }
+}
+
 /**
  * Splits a string into a vector of the substrings separated by LF ('\n')
  */
@@ -621,8 +720,9 @@ pub fn split_within(ss: &str, lim: uint) -> ~[~str] {
         let word = copy *wptr;
 
         // if adding this word to the row would go over the limit,
-        // then start a new row
-        if row.len() + word.len() + 1 > lim {
+        // then start a new row. A word longer than the limit gets a row
+        // of its own.
+        if row.len() > 0 && row.len() + word.len() + 1 > lim {
             rows.push(copy row); // save previous row
             row = word;    // start a new one
         } else {
@@ -656,6 +756,13 @@ pub pure fn to_upper(s: &str) -> ~str {
 /**
  * Replace all occurrences of one string with another
  *
+ * Occurrences are found from left to right and do not overlap, so
+ * replacing `"aa"` in `"aaa"` only replaces the first two characters.
+ *
+ * # Failure
+ *
+ * Fails if `from` is empty
+ *
  * # Arguments
  *
  * * s - The string containing substrings to replace
@@ -668,7 +775,7 @@ pub pure fn to_upper(s: &str) -> ~str {
  */
 pub pure fn replace(s: &str, from: &str, to: &str) -> ~str {
     let mut result = ~"", first = true;
-    do iter_between_matches(s, from) |start, end| {
+    for iter_between_matches(s, from) |start, end| {
         if first {
             first = false;
         } else {
@@ -780,6 +887,11 @@ impl ~str : Eq {
     pure fn ne(&self, other: &~str) -> bool { !(*self).eq(other) }
 }
 
+impl ~str: Clone {
+    #[inline(always)]
+    fn clone(&self) -> ~str { from_slice(*self) }
+}
+
 #[cfg(notest)]
 impl @str : Eq {
     #[inline(always)]
@@ -904,6 +1016,16 @@ pub pure fn each_chari(s: &str, it: fn(uint, char) -> bool) {
     }
 }
 
+/// Iterates over the chars in a string, from the last to the first
+pub pure fn each_char_reverse(s: &str, it: fn(char) -> bool) {
+    let mut pos = len(s);
+    while pos > 0u {
+        let CharRange {ch, next} = char_range_at_reverse(s, pos);
+        pos = next;
+        if !it(ch) { break; }
+    }
+}
+
 /// Iterate over the characters in a string
 pub pure fn chars_each(s: &str, it: fn(char) -> bool) {
     let mut pos = 0u;
@@ -917,7 +1039,7 @@ pub pure fn chars_each(s: &str, it: fn(char) -> bool) {
 
 /// Apply a function to each substring after splitting by character
 pub pure fn split_char_each(ss: &str, cc: char, ff: fn(v: &str) -> bool) {
-    vec::each(split_char(ss, cc), |s| ff(*s))
+    each_split_char(ss, cc, ff)
 }
 
 /**
@@ -926,19 +1048,69 @@ pub pure fn split_char_each(ss: &str, cc: char, ff: fn(v: &str) -> bool) {
  */
 pub pure fn splitn_char_each(ss: &str, sep: char, count: uint,
                          ff: fn(v: &str) -> bool) {
-    vec::each(splitn_char(ss, sep, count), |s| ff(*s))
+    each_split_char_inner(ss, sep, count, true, true, ff)
 }
 
 /// Apply a function to each word
 pub pure fn words_each(ss: &str, ff: fn(v: &str) -> bool) {
-    vec::each(words(ss), |s| ff(*s))
+    each_word(ss, ff)
+}
+
+/**
+ * Iterates over the words of a string, the non-empty substrings separated
+ * by runs of whitespace
+ */
+pub pure fn each_word(s: &a/str, it: fn(&a/str) -> bool) {
+    each_split_inner(s, |c| char::is_whitespace(c), len(s), false, false, it)
+}
+
+/**
+ * Iterates over the lines of a string, without their terminators
+ *
+ * Lines are terminated by LF ('\n') or CR LF ("\r\n"). A terminator at
+ * the very end of the string does not start another, empty, line, so
+ * "a\nb\n" has the same two lines as "a\nb", and "" has none.
+ */
+pub pure fn each_line(s: &a/str, it: fn(&a/str) -> bool) {
+    each_line_inner(s, false, it)
+}
+
+/**
+ * Iterates over the lines of a string like `each_line`, but keeps the
+ * "\n" or "\r\n" that terminates each line
+ *
+ * Concatenating the lines gives back the original string.
+ */
+pub pure fn each_line_keep_ends(s: &a/str, it: fn(&a/str) -> bool) {
+    each_line_inner(s, true, it)
+}
+
+pure fn each_line_inner(s: &a/str, keep_ends: bool, it: fn(&a/str) -> bool) {
+    let l = len(s);
+    let mut start = 0u;
+    while start < l {
+        let (end, next) = match find_char_from(s, '\n', start) {
+            Some(nl) => {
+                if keep_ends {
+                    (nl + 1u, nl + 1u)
+                } else if nl > start && s[nl - 1u] == '\r' as u8 {
+                    (nl - 1u, nl + 1u)
+                } else {
+                    (nl, nl + 1u)
+                }
+            }
+            None => (l, l)
+        };
+        if !it(unsafe { raw::view_bytes(s, start, end) }) { return; }
+        start = next;
+    }
 }
 
 /**
  * Apply a function to each line (by '\n')
  */
 pub pure fn lines_each(ss: &str, ff: fn(v: &str) -> bool) {
-    vec::each(lines(ss), |s| ff(*s))
+    each_split_char(ss, '\n', ff)
 }
 
 /*
@@ -1348,6 +1520,61 @@ pub pure fn find_str_between(haystack: &a/str, needle: &b/str, start: uint,
     return None;
 }
 
+/**
+ * Returns the byte index of the last matching substring
+ *
+ * # Arguments
+ *
+ * * `haystack` - The string to search
+ * * `needle` - The string to search for
+ *
+ * # Return value
+ *
+ * An `option` containing the byte index of the last matching substring
+ * or `none` if there is no match
+ */
+pub pure fn rfind_str(haystack: &a/str, needle: &b/str) -> Option<uint> {
+    rfind_str_between(haystack, needle, len(haystack), 0u)
+}
+
+/**
+ * Returns the byte index of the last matching substring within a given range
+ *
+ * # Arguments
+ *
+ * * `haystack` - The string to search
+ * * `needle` - The string to search for
+ * * `start` - The byte index to begin searching at, exclusive
+ * * `end` - The byte index to end searching at, inclusive
+ *
+ * # Return value
+ *
+ * An `option` containing the byte index of the last substring that lies
+ * entirely within the range, or `none` if there is no match
+ *
+ * # Failure
+ *
+ * `end` must be less than or equal to `start` and `start` must be less
+ * than or equal to `len(haystack)`.
+ */
+pub pure fn rfind_str_between(haystack: &a/str, needle: &b/str, start: uint,
+                              end: uint)
+  -> Option<uint> {
+    assert start >= end;
+    assert start <= len(haystack);
+    let needle_len = len(needle);
+    if needle_len > start - end { return None; }
+
+    // A match of valid UTF-8 within valid UTF-8 always begins on a
+    // character boundary, so the byte-wise search is enough.
+    let mut i = start - needle_len;
+    loop {
+        if match_at(haystack, needle, i) { return Some(i); }
+        if i == end { return None; }
+        i -= 1u;
+    }
+}
+
 /**
  * Returns true if one string contains another
  *
@@ -1466,26 +1693,15 @@ pub pure fn is_utf8(v: &[const u8]) -> bool {
 
 /// Determines if a vector of `u16` contains valid UTF-16
 pub pure fn is_utf16(v: &[u16]) -> bool {
-    let len = vec::len(v);
-    let mut i = 0u;
-    while (i < len) {
-        let u = v[i];
-
-        if  u <= 0xD7FF_u16 || u >= 0xE000_u16 {
-            i += 1u;
-
-        } else {
-            if i+1u < len { return false; }
-            let u2 = v[i+1u];
-            if u < 0xD7FF_u16 || u > 0xDBFF_u16 { return false; }
-            if u2 < 0xDC00_u16 || u2 > 0xDFFF_u16 { return false; }
-            i += 2u;
-        }
-    }
-    return true;
+    decode_utf16(v, |_ch| ())
 }
 
-/// Converts to a vector of `u16` encoded as UTF-16
+/**
+ * Converts to a vector of `u16` encoded as UTF-16
+ *
+ * Fails if `s` contains a char that is a surrogate or lies beyond
+ * U+10FFFF, since neither can be encoded.
+ */
 pub pure fn to_utf16(s: &str) -> ~[u16] {
     let mut u = ~[];
     for chars_each(s) |cch| {
@@ -1511,20 +1727,26 @@ pub pure fn to_utf16(s: &str) -> ~[u16] {
     u
 }
 
-pub pure fn utf16_chars(v: &[u16], f: fn(char)) {
+/*
+ * Decodes UTF-16, passing each char to `f`. Stops and returns false at the
+ * first unpaired surrogate; everything before it has already been passed
+ * to `f`.
+ */
+pure fn decode_utf16(v: &[u16], f: fn(char)) -> bool {
     let len = vec::len(v);
     let mut i = 0u;
-    while (i < len && v[i] != 0u16) {
-        let mut u = v[i];
+    while i < len {
+        let u = v[i];
 
-        if  u <= 0xD7FF_u16 || u >= 0xE000_u16 {
+        if u <= 0xD7FF_u16 || u >= 0xE000_u16 {
             f(u as char);
             i += 1u;
-
         } else {
-            let u2 = v[i+1u];
-            assert u >= 0xD800_u16 && u <= 0xDBFF_u16;
-            assert u2 >= 0xDC00_u16 && u2 <= 0xDFFF_u16;
+            // A high (leading) surrogate must be followed by a low one
+            if u > 0xDBFF_u16 || i + 1u >= len { return false; }
+            let u2 = v[i + 1u];
+            if u2 < 0xDC00_u16 || u2 > 0xDFFF_u16 { return false; }
+
             let mut c = (u - 0xD800_u16) as char;
             c = c << 10;
             c |= (u2 - 0xDC00_u16) as char;
@@ -1533,16 +1755,44 @@ pub pure fn utf16_chars(v: &[u16], f: fn(char)) {
             i += 2u;
         }
     }
+    true
 }
 
+/**
+ * Decodes a vector of UTF-16 code units, passing each char to `f`
+ *
+ * Fails on an unpaired surrogate, after `f` has seen every char before it.
+ */
+pub pure fn utf16_chars(v: &[u16], f: fn(char)) {
+    if !decode_utf16(v, f) {
+        die!(~"utf16_chars: unpaired surrogate in UTF-16 input");
+    }
+}
 
-pub pure fn from_utf16(v: &[u16]) -> ~str {
+/**
+ * Converts a vector of UTF-16 code units to a string
+ *
+ * Returns `None` if `v` contains an unpaired surrogate.
+ */
+pub pure fn from_utf16_opt(v: &[u16]) -> Option<~str> {
     let mut buf = ~"";
-    unsafe {
-        reserve(&mut buf, vec::len(v));
-        utf16_chars(v, |ch| push_char(&mut buf, ch));
+    unsafe { reserve(&mut buf, vec::len(v)); }
+    let ok = do decode_utf16(v) |ch| {
+        unsafe { push_char(&mut buf, ch); }
+    };
+    if ok { Some(move buf) } else { None }
+}
+
+/**
+ * Converts a vector of UTF-16 code units to a string
+ *
+ * Fails if `v` contains an unpaired surrogate.
+ */
+pub pure fn from_utf16(v: &[u16]) -> ~str {
+    match move from_utf16_opt(v) {
+        Some(move s) => move s,
+        None => die!(~"from_utf16: unpaired surrogate in UTF-16 input")
     }
-    buf
 }
 
 pub pure fn with_capacity(capacity: uint) -> ~str {
@@ -2159,6 +2409,7 @@ pub trait StrSlice {
     pure fn eachi(it: fn(uint, u8) -> bool);
     pure fn each_char(it: fn(char) -> bool);
     pure fn each_chari(it: fn(uint, char) -> bool);
+    pure fn each_char_reverse(it: fn(char) -> bool);
     pure fn ends_with(needle: &str) -> bool;
     pure fn is_empty() -> bool;
     pure fn is_whitespace() -> bool;
@@ -2216,6 +2467,11 @@ impl &str: StrSlice {
     /// Iterate over the chars in a string, with indices
     #[inline]
     pure fn each_chari(it: fn(uint, char) -> bool) { each_chari(self, it) }
+    /// Iterate over the chars in a string, from the last to the first
+    #[inline]
+    pure fn each_char_reverse(it: fn(char) -> bool) {
+        each_char_reverse(self, it)
+    }
     /// Returns true if one string ends with another
     #[inline]
     pure fn ends_with(needle: &str) -> bool { ends_with(self, needle) }
@@ -2387,6 +2643,68 @@ mod tests {
         assert rfind_char(~"ประเทศไทย中华Việt Nam", '华') == Some(30u);
     }
 
+    #[test]
+    fn test_rfind_multibyte() {
+        // Matches right next to multibyte chars, and at position 0
+        let s = ~"é.ö.ü";
+        assert rfind_char(s, '.') == Some(5u);
+        assert rfind_char(s, 'ü') == Some(6u);
+        assert rfind_char(s, 'é') == Some(0u);
+        assert rfind_char(~"éé", 'é') == Some(2u);
+        assert rfind(s, |c| c == 'ö') == Some(3u);
+        assert rfind(s, |c| !char::is_ascii(c)) == Some(6u);
+        assert rfind(s, |c| c == 'é') == Some(0u);
+        assert rfind(~"", |_c| true).is_none();
+
+        // Extension and last path component
+        let path = ~"dir/naïve.tar.gz";
+        assert rfind_char(path, '.') == Some(14u);
+        assert rfind_char(path, '/') == Some(3u);
+        assert is_char_boundary(path, rfind_char(path, '.').get());
+    }
+
+    #[test]
+    fn test_rfind_str() {
+        assert rfind_str(~"banana", ~"apple pie").is_none();
+        assert rfind_str(~"", ~"") == Some(0u);
+        assert rfind_str(~"banana", ~"") == Some(6u);
+        assert rfind_str(~"banana", ~"ana") == Some(3u);
+        assert rfind_str(~"banana", ~"ban") == Some(0u);
+        assert rfind_str(~"banana", ~"banana") == Some(0u);
+        assert rfind_str(~"ban", ~"banana").is_none();
+
+        let data = ~"ประเทศไทย中华Việt Nam";
+        assert rfind_str(data, ~"ประเ") == Some(0u);
+        assert rfind_str(data, ~"中华") == Some(27u);
+        assert rfind_str(data, ~"华V") == Some(30u);
+        assert rfind_str(data, ~"ไท华").is_none();
+
+        let data = data + data;
+        assert rfind_str(data, ~"ประ") == Some(43u);
+        assert rfind_str_between(data, ~"ประ", 43u, 0u) == Some(0u);
+        assert rfind_str_between(data, ~"ประ", 45u, 1u).is_none();
+        assert rfind_str_between(data, ~"Nam", 86u, 43u) == Some(83u);
+        assert rfind_str_between(data, ~"", 43u, 0u) == Some(43u);
+    }
+
+    #[test]
+    fn test_each_char_reverse() {
+        let mut v = ~[];
+        for each_char_reverse(~"x\u03c0y\U0001F4A9") |ch| {
+            v.push(ch);
+        }
+        assert v == ~['\U0001F4A9', 'y', '\u03c0', 'x'];
+
+        let mut v = ~[];
+        for each_char_reverse(~"ab中华") |ch| {
+            v.push(ch);
+            if ch == '中' { break; }
+        }
+        assert v == ~['华', '中'];
+
+        for each_char_reverse(~"") |_ch| { die!(); }
+    }
+
     #[test]
     fn test_pop_char() {
         let mut data = ~"ประเทศไทย中华";
@@ -2544,6 +2862,94 @@ mod tests {
         assert ~[~"ok"] == split(~"ok", |cc| cc == 'z');
     }
 
+    fn collect(f: fn(it: fn(&str) -> bool)) -> ~[~str] {
+        let mut v = ~[];
+        for f |s| { v.push(from_slice(s)); }
+        v
+    }
+
+    #[test]
+    fn test_each_split_char() {
+        let data = ~"ประเทศไทย中华Việt Nam";
+        for [' ', 'ท', '华', 'V', 'z', 'ย'].each |c| {
+            assert collect(|it| each_split_char(data, *c, it)) ==
+                split_char(data, *c);
+        }
+        let data = ~"...hello.there.";
+        assert collect(|it| each_split_char(data, '.', it)) ==
+            split_char(data, '.');
+
+        assert collect(|it| each_split_char(~"", '.', it)) == ~[~""];
+        assert collect(|it| each_split_char_no_trailing(~"", '.', it)) ==
+            ~[];
+        assert collect(|it| each_split_char_no_trailing(~"a.b.", '.', it))
+            == ~[~"a", ~"b"];
+        assert collect(|it| each_split_char_no_trailing(~".a..b", '.', it))
+            == ~[~"", ~"a", ~"", ~"b"];
+        let data = ~"a华b华";
+        assert collect(|it| each_split_char_no_trailing(data, '华', it)) ==
+            ~[~"a", ~"b"];
+
+        // Early exit
+        let mut n = 0;
+        for each_split_char(~"a.b.c.d", '.') |s| {
+            n += 1;
+            if s == "b" { break; }
+        }
+        assert n == 2;
+    }
+
+    #[test]
+    fn test_each_split() {
+        let data = ~"ประเทศไทย中华Việt Nam";
+        assert collect(|it| each_split(data, |cc| cc == '华', it)) ==
+            split(data, |cc| cc == '华');
+        assert collect(|it| each_split(data, char::is_whitespace, it)) ==
+            split(data, char::is_whitespace);
+        assert collect(|it| each_split(~"zzXXXzYYYz", char::is_lowercase,
+                                       it)) ==
+            split(~"zzXXXzYYYz", char::is_lowercase);
+
+        assert collect(|it| each_split(~"", |cc| cc == 'z', it)) == ~[~""];
+        assert collect(|it| each_split_no_trailing(~"", |cc| cc == 'z', it))
+            == ~[];
+        let lower = char::is_lowercase;
+        assert collect(|it| each_split_no_trailing(~"XzYz", lower, it)) ==
+            ~[~"X", ~"Y"];
+
+        let mut n = 0;
+        for each_split(data, |cc| cc == 'ท') |_s| {
+            n += 1;
+            if n == 2 { break; }
+        }
+        assert n == 2;
+    }
+
+    #[test]
+    fn test_each_split_str() {
+        let data = ~"ประเทศไทย中华Việt Nam中华";
+        assert collect(|it| each_split_str(data, ~"中华", it)) ==
+            split_str(data, ~"中华");
+        let sep = ~"中华";
+        assert collect(|it| each_split_str_no_trailing(data, sep, it)) ==
+            ~[~"ประเทศไทย", ~"Việt Nam"];
+        assert collect(|it| each_split_str(~"zzXXXzzYYYzz", ~"zz", it)) ==
+            ~[~"", ~"XXX", ~"YYY", ~""];
+        assert collect(|it| each_split_str_no_trailing(~"zzXXXzzYYYzz",
+                                                       ~"zz", it)) ==
+            ~[~"", ~"XXX", ~"YYY"];
+        assert collect(|it| each_split_str(~"", ~".", it)) == ~[~""];
+        assert collect(|it| each_split_str_no_trailing(~"", ~".", it)) ==
+            ~[];
+
+        let mut n = 0;
+        for each_split_str(~"a::b::c", ~"::") |_s| {
+            n += 1;
+            if n == 2 { break; }
+        }
+        assert n == 2;
+    }
+
     #[test]
     fn test_lines() {
         let lf = ~"\nMary had a little lamb\nLittle lamb\n";
@@ -2590,6 +2996,10 @@ mod tests {
         assert split_within(data, 15) == ~[~"Mary had a",
                                            ~"little lamb",
                                            ~"Little lamb"];
+        assert split_within(~"verylongword a", 5) ==
+            ~[~"verylongword", ~"a"];
+        assert split_within(~"a verylongword b", 5) ==
+            ~[~"a", ~"verylongword", ~"b"];
     }
 
     #[test]
@@ -2676,6 +3086,70 @@ mod tests {
         assert repeat(~"hi", 0) == ~"";
     }
 
+    #[test]
+    fn test_repeat_exact_capacity() {
+        let s = repeat(~"ab\u03c0", 1000);
+        assert len(s) == 4000u;
+        assert capacity(&s) == 4000u;
+    }
+
+    #[test]
+    fn test_each_word() {
+        fn words_of(s: &str) -> ~[~str] {
+            let mut v = ~[];
+            for each_word(s) |w| { v.push(w.to_owned()); }
+            v
+        }
+
+        let data = ~"\nMary had\ta  little lamb\r\nLittle lamb\n";
+        assert words_of(data) == ~[~"Mary", ~"had", ~"a", ~"little",
+                                   ~"lamb", ~"Little", ~"lamb"];
+        assert words_of(~"ok") == ~[~"ok"];
+        assert words_of(~"  中华  Việt\u3000Nam ") ==
+            ~[~"中华", ~"Việt", ~"Nam"];
+        assert words_of(~"").is_empty();
+        assert words_of(~" \t\r\n ").is_empty();
+
+        let mut n = 0;
+        for each_word(~"one two three") |_w| { n += 1; if n == 2 { break; } }
+        assert n == 2;
+    }
+
+    #[test]
+    fn test_each_line() {
+        fn lines_of(s: &str, keep_ends: bool) -> ~[~str] {
+            let mut v = ~[];
+            if keep_ends {
+                for each_line_keep_ends(s) |l| { v.push(l.to_owned()); }
+            } else {
+                for each_line(s) |l| { v.push(l.to_owned()); }
+            }
+            v
+        }
+
+        let lf = ~"\nMary had a little lamb\nLittle lamb\n";
+        let crlf = ~"\r\nMary had a little lamb\r\nLittle lamb\r\n";
+        let expected = ~[~"", ~"Mary had a little lamb", ~"Little lamb"];
+        assert lines_of(lf, false) == expected;
+        assert lines_of(crlf, false) == expected;
+        assert lines_of(crlf, true) ==
+            ~[~"\r\n", ~"Mary had a little lamb\r\n", ~"Little lamb\r\n"];
+        assert concat(lines_of(crlf, true)) == crlf;
+
+        // No final terminator, mixed terminators, and a lone CR
+        assert lines_of(~"a\r\nb\nc", false) == ~[~"a", ~"b", ~"c"];
+        assert lines_of(~"a\r\nb\nc", true) == ~[~"a\r\n", ~"b\n", ~"c"];
+        assert lines_of(~"a\rb\n", false) == ~[~"a\rb"];
+
+        assert lines_of(~"", false).is_empty();
+        assert lines_of(~"", true).is_empty();
+        assert lines_of(~"\n", false) == ~[~""];
+        assert lines_of(~"\r\n", false) == ~[~""];
+        assert lines_of(~"\n\n", false) == ~[~"", ~""];
+        assert lines_of(~"  \t ", false) == ~[~"  \t "];
+        assert lines_of(~" \n \n", false) == ~[~" ", ~" "];
+    }
+
     #[test]
     fn test_to_upper() {
         // libc::toupper, and hence str::to_upper
@@ -2755,6 +3229,21 @@ mod tests {
         assert replace(~" test test ", test, ~"") == ~"   ";
     }
 
+    #[test]
+    fn test_replace_non_overlapping() {
+        assert replace(~"aaa", ~"aa", ~"b") == ~"ba";
+        assert replace(~"aaaa", ~"aa", ~"b") == ~"bb";
+        assert replace(~"abab", ~"ab", ~"ab!") == ~"ab!ab!";
+        assert replace(~"中华中华", ~"中华", ~"华") == ~"华华";
+    }
+
+    #[test]
+    #[should_fail]
+    #[ignore(cfg(windows))]
+    fn test_replace_empty_from() {
+        replace(~"abc", ~"", ~"x");
+    }
+
     #[test]
     fn test_replace_2a() {
         let data = ~"ประเทศไทย中华";
@@ -2861,26 +3350,49 @@ mod tests {
 
     #[test]
     fn test_trim_left_chars() {
-        assert trim_left_chars(~" *** foo *** ", ~[]) == ~" *** foo *** ";
-        assert trim_left_chars(~" *** foo *** ", ~['*', ' ']) == ~"foo *** ";
-        assert trim_left_chars(~" ***  *** ", ~['*', ' ']) == ~"";
-        assert trim_left_chars(~"foo *** ", ~['*', ' ']) == ~"foo *** ";
+        assert trim_left_chars(" *** foo *** ", ~[]) == " *** foo *** ";
+        assert trim_left_chars(" *** foo *** ", ~['*', ' ']) == "foo *** ";
+        assert trim_left_chars(" ***  *** ", ~['*', ' ']) == "";
+        assert trim_left_chars("foo *** ", ~['*', ' ']) == "foo *** ";
     }
 
     #[test]
     fn test_trim_right_chars() {
-        assert trim_right_chars(~" *** foo *** ", ~[]) == ~" *** foo *** ";
-        assert trim_right_chars(~" *** foo *** ", ~['*', ' ']) == ~" *** foo";
-        assert trim_right_chars(~" ***  *** ", ~['*', ' ']) == ~"";
-        assert trim_right_chars(~" *** foo", ~['*', ' ']) == ~" *** foo";
+        assert trim_right_chars(" *** foo *** ", ~[]) == " *** foo *** ";
+        assert trim_right_chars(" *** foo *** ", ~['*', ' ']) == " *** foo";
+        assert trim_right_chars(" ***  *** ", ~['*', ' ']) == "";
+        assert trim_right_chars(" *** foo", ~['*', ' ']) == " *** foo";
     }
 
     #[test]
     fn test_trim_chars() {
-        assert trim_chars(~" *** foo *** ", ~[]) == ~" *** foo *** ";
-        assert trim_chars(~" *** foo *** ", ~['*', ' ']) == ~"foo";
-        assert trim_chars(~" ***  *** ", ~['*', ' ']) == ~"";
-        assert trim_chars(~"foo", ~['*', ' ']) == ~"foo";
+        assert trim_chars(" *** foo *** ", ~[]) == " *** foo *** ";
+        assert trim_chars(" *** foo *** ", ~['*', ' ']) == "foo";
+        assert trim_chars(" ***  *** ", ~['*', ' ']) == "";
+        assert trim_chars("foo", ~['*', ' ']) == "foo";
+    }
+
+    #[test]
+    fn test_trim_chars_unicode() {
+        let data = ~"中华中Việt Nam华华";
+        assert trim_chars(data, ~['中', '华']) == "Việt Nam";
+        assert trim_left_chars(data, ~['中', '华']) == "Việt Nam华华";
+        assert trim_right_chars(data, ~['华']) == "中华中Việt Nam";
+        assert trim_chars(data, ~['中', '华', 'V', 'm']) == "iệt Na";
+        // 'ệ' shares no whole character with 'e', so nothing is trimmed
+        assert trim_chars("ệxệ", ~['e']) == "ệxệ";
+
+        // A combining accent is a character of its own
+        let accented = ~"e\u0301te\u0301";
+        assert trim_left_chars(accented, ~['e']) == "\u0301te\u0301";
+        assert trim_chars(accented, ~['e', '\u0301']) == "t";
+
+        // The result borrows from the original string
+        let s = ~"**ab**";
+        let t = trim_chars(s, ~['*']);
+        let tp = as_buf(t, |p, _len| p);
+        let sp = as_buf(s, |p, _len| p);
+        assert tp == ptr::offset(sp, 2);
     }
 
     #[test]
@@ -3284,6 +3796,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_utf16_astral_round_trip() {
+        // U+1F4A9 is outside the BMP and needs a surrogate pair
+        let s = ~"a\U0001F4A9b";
+        let u = ~[0x0061_u16, 0xd83d_u16, 0xdca9_u16, 0x0062_u16];
+        assert to_utf16(s) == u;
+        assert is_utf16(u);
+        assert from_utf16(u) == s;
+        assert from_utf16_opt(u) == Some(copy s);
+
+        let mut chars = ~[];
+        utf16_chars(u, |ch| chars.push(ch));
+        assert chars == ~['a', '\U0001F4A9', 'b'];
+    }
+
+    #[test]
+    fn test_utf16_embedded_nul() {
+        let u = ~[0x0061_u16, 0x0000_u16, 0x0062_u16];
+        assert from_utf16(u) == ~"a\x00b";
+        assert to_utf16(from_utf16(u)) == u;
+    }
+
+    #[test]
+    fn test_utf16_unpaired_surrogates() {
+        let bad = ~[
+            ~[0xd83d_u16],                          // lone high at the end
+            ~[0xd83d_u16, 0x0061_u16],              // high then non-low
+            ~[0xd83d_u16, 0xd83d_u16, 0xdca9_u16],  // high then high
+            ~[0xdca9_u16],                          // lone low
+            ~[0x0061_u16, 0xdca9_u16, 0xd83d_u16]   // pair in wrong order
+        ];
+        for bad.each |u| {
+            assert !is_utf16(*u);
+            assert from_utf16_opt(*u).is_none();
+        }
+
+        assert is_utf16(~[]);
+        assert is_utf16(~[0xd7ff_u16, 0xe000_u16, 0xffff_u16]);
+        assert is_utf16(~[0xdbff_u16, 0xdfff_u16]);
+        assert from_utf16_opt(~[]) == Some(~"");
+    }
+
+    #[test]
+    #[should_fail]
+    #[ignore(cfg(windows))]
+    fn test_from_utf16_lone_surrogate_fail() {
+        from_utf16(~[0x0061_u16, 0xdca9_u16]);
+    }
+
+    #[test]
+    #[should_fail]
+    #[ignore(cfg(windows))]
+    fn test_utf16_chars_lone_surrogate_fail() {
+        let mut n = 0;
+        utf16_chars(~[0x0061_u16, 0xd83d_u16], |_ch| n += 1);
+    }
+
     #[test]
     fn test_each_char() {
         let s = ~"abc";
@@ -43,6 +43,7 @@ use result::Result;
 use pipes::{stream, Chan, GenericChan, GenericPort, Port, SharedChan};
 use pipes;
 use prelude::*;
+use private;
 use ptr;
 use result;
 use task::local_data_priv::{local_get, local_set};
@@ -112,7 +113,7 @@ pub enum SchedMode {
     PlatformThread,
     /// All tasks run in the same OS thread
     SingleThreaded,
-    /// Tasks are distributed among available CPUs
+    /// Tasks are distributed among `default_sched_threads()` OS threads
     ThreadPerCore,
     /// Each task runs in its own OS thread
     ThreadPerTask,
@@ -174,6 +175,54 @@ pub struct TaskOpts {
     sched: SchedOpts
 }
 
+/**
+ * A handle for killing a task from outside
+ *
+ * Returned by `TaskBuilder::spawn_with_handle`. Killing a task makes it
+ * fail at its next kill point (yielding or blocking to receive), exactly
+ * as if it were killed by linked failure; if it is inside `unkillable`,
+ * the kill takes effect once it leaves. Like any failure, the kill
+ * propagates to linked tasks, so the task should usually be spawned
+ * unlinked or supervised.
+ *
+ * Handles are sendable and may be cloned. Killing a task that has
+ * already exited does nothing.
+ */
+pub struct KillHandle {
+    priv state: private::Exclusive<KillState>,
+}
+
+enum KillState {
+    NotStarted,
+    KillRequested,
+    Running(*rust_task),
+    Exited,
+}
+
+impl KillHandle: Clone {
+    fn clone(&self) -> KillHandle {
+        KillHandle { state: self.state.clone() }
+    }
+}
+
+impl KillHandle {
+    /// Kill the task, if it has not already exited
+    fn kill(&self) {
+        unsafe {
+            do self.state.with |state| {
+                match *state {
+                    // The task checks for this before running its body
+                    NotStarted => *state = KillRequested,
+                    // The task can't exit while we hold the lock, so the
+                    // pointer is still valid
+                    Running(task) => rt::rust_task_kill_other(task),
+                    KillRequested | Exited => ()
+                }
+            }
+        }
+    }
+}
+
 /**
  * The task builder type.
  *
@@ -399,13 +448,73 @@ impl TaskBuilder {
         };
         spawn::spawn_raw(move opts, (x.gen_body)(move f));
     }
+    /**
+     * Like `spawn`, but the closure may move out of its environment
+     *
+     * The task wrappers may call the body they are given more than once,
+     * so this costs an extra allocation over `spawn` to make sure the
+     * closure itself runs at most once.
+     */
+    fn spawn_once(f: ~once fn()) {
+        let f = ~mut Some(move f);
+        do self.spawn |move f| {
+            option::swap_unwrap(f)()
+        }
+    }
     /// Runs a task, while transfering ownership of one argument to the child.
-    fn spawn_with<A: Owned>(arg: A, f: fn~(v: A)) {
+    #[cfg(stage0)]
+    fn spawn_with<A: Owned>(arg: A, f: ~once fn(v: A)) {
         let arg = ~mut Some(move arg);
         do self.spawn |move arg, move f| {
             f(option::swap_unwrap(arg))
         }
     }
+    /// Runs a task, while transfering ownership of one argument to the child.
+    #[cfg(stage1)]
+    #[cfg(stage2)]
+    #[cfg(stage3)]
+    fn spawn_with<A: Owned>(arg: A, f: ~once fn(v: A)) {
+        do self.spawn_once |move arg, move f| {
+            f(move arg)
+        }
+    }
+
+    /**
+     * Creates and executes a new child task, returning a handle that can
+     * be used to kill it.
+     */
+    fn spawn_with_handle(f: ~once fn()) -> KillHandle {
+        struct MarkExited {
+            state: private::Exclusive<KillState>,
+            drop {
+                unsafe { self.state.with(|state| *state = Exited); }
+            }
+        }
+
+        let state = private::exclusive(NotStarted);
+        let handle = KillHandle { state: state.clone() };
+
+        do self.spawn_once |move state, move f| {
+            // Runs even if we fail, so a late kill won't touch a dead task
+            let guard = MarkExited { state: move state };
+            let killed = unsafe {
+                do guard.state.with |state| {
+                    match *state {
+                        KillRequested => true,
+                        _ => {
+                            *state = Running(rt::rust_get_task());
+                            false
+                        }
+                    }
+                }
+            };
+            if killed {
+                die!(~"killed");
+            }
+            f();
+        }
+        move handle
+    }
 
     /**
      * Execute a function in another task and return either the return value
@@ -420,14 +529,14 @@ impl TaskBuilder {
      * # Failure
      * Fails if a future_result was already set for this task.
      */
-    fn try<T: Owned>(f: fn~() -> T) -> Result<T,()> {
+    fn try<T: Owned>(f: ~once fn() -> T) -> Result<T,()> {
         let (po, ch) = stream::<T>();
         let mut result = None;
 
         let fr_task_builder = self.future_result(|+r| {
             result = Some(move r);
         });
-        do fr_task_builder.spawn |move f, move ch| {
+        do fr_task_builder.spawn_once |move f, move ch| {
             ch.send(f());
         }
         match option::unwrap(move result).recv() {
@@ -474,6 +583,17 @@ pub fn spawn(f: fn~()) {
     task().spawn(move f)
 }
 
+pub fn spawn_once(f: ~once fn()) {
+    /*!
+     * Creates and executes a new child task, running a closure that may
+     * move out of its environment
+     *
+     * This function is equivalent to `task().spawn_once(f)`.
+     */
+
+    task().spawn_once(move f)
+}
+
 pub fn spawn_unlinked(f: fn~()) {
     /*!
      * Creates a child task unlinked from the current one. If either this
@@ -492,7 +612,7 @@ pub fn spawn_supervised(f: fn~()) {
     task().supervised().spawn(move f)
 }
 
-pub fn spawn_with<A:Owned>(arg: A, f: fn~(v: A)) {
+pub fn spawn_with<A:Owned>(arg: A, f: ~once fn(v: A)) {
     /*!
      * Runs a task, while transfering ownership of one argument to the
      * child.
@@ -522,7 +642,7 @@ pub fn spawn_sched(mode: SchedMode, f: fn~()) {
     task().sched_mode(mode).spawn(move f)
 }
 
-pub fn try<T:Owned>(f: fn~() -> T) -> Result<T,()> {
+pub fn try<T:Owned>(f: ~once fn() -> T) -> Result<T,()> {
     /*!
      * Execute a function in another task and return either the return value
      * of the function or result::err.
@@ -568,6 +688,17 @@ pub fn get_scheduler() -> Scheduler {
     SchedulerHandle(unsafe { rt::rust_get_sched_id() })
 }
 
+pub fn default_sched_threads() -> uint {
+    /*!
+     * The number of threads a new `ThreadPerCore` scheduler will use
+     *
+     * This is the value of `RUST_THREADS` if it is set to a positive
+     * number, otherwise the number of CPUs reported by `os::num_cpus`.
+     */
+
+    unsafe { rt::rust_num_threads() as uint }
+}
+
 /**
  * Temporarily make the task unkillable
  *
@@ -873,6 +1004,79 @@ fn test_back_to_the_future_result() {
     let _ = task().future_result(util::ignore).future_result(util::ignore);
 }
 
+#[test]
+#[ignore(cfg(windows))]
+fn test_kill_handle() {
+    let mut result = None;
+    let handle = do task().unlinked().future_result(|+r| {
+        result = Some(move r);
+    }).spawn_with_handle {
+        loop { yield(); }
+    };
+    handle.kill();
+    assert option::unwrap(move result).recv() == Failure;
+    // Already dead; shouldn't do anything
+    handle.kill();
+}
+
+#[test]
+#[ignore(cfg(windows))]
+fn test_kill_handle_after_exit() {
+    let mut result = None;
+    let handle = do task().unlinked().future_result(|+r| {
+        result = Some(move r);
+    }).spawn_with_handle { };
+    assert option::unwrap(move result).recv() == Success;
+    handle.kill();
+    let other = handle.clone();
+    other.kill();
+}
+
+#[test]
+#[ignore(cfg(windows))]
+fn test_kill_handle_is_sendable() {
+    let (handle_po, handle_ch) = stream();
+    let mut result = None;
+    let handle = do task().unlinked().future_result(|+r| {
+        result = Some(move r);
+    }).spawn_with_handle {
+        loop { yield(); }
+    };
+    do spawn |move handle_po| {
+        let handle: KillHandle = handle_po.recv();
+        handle.kill();
+    }
+    handle_ch.send(move handle);
+    assert option::unwrap(move result).recv() == Failure;
+}
+
+#[test]
+#[ignore(cfg(windows))]
+fn test_kill_handle_unkillable() {
+    let (start_po, start_ch) = stream();
+    let (go_po, go_ch) = stream();
+    let (done_po, done_ch) = stream();
+    let mut result = None;
+    let handle = do task().unlinked().future_result(|+r| {
+        result = Some(move r);
+    }).spawn_with_handle |move start_ch, move go_po, move done_ch| {
+        unsafe {
+            do unkillable {
+                start_ch.send(());
+                // The kill arrives while we're blocked here
+                go_po.recv();
+                done_ch.send(());
+            }
+        }
+        loop { yield(); }
+    };
+    start_po.recv();
+    handle.kill();
+    go_ch.send(());
+    done_po.recv();
+    assert option::unwrap(move result).recv() == Failure;
+}
+
 #[test]
 fn test_try_success() {
     match do try {
@@ -1186,6 +1390,7 @@ fn test_sched_thread_per_core() {
             let cores = rt::rust_num_threads();
             let reported_threads = rt::rust_sched_threads();
             assert(cores as uint == reported_threads as uint);
+            assert(default_sched_threads() == reported_threads as uint);
             chan.send(());
         }
     }
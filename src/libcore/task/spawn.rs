@@ -74,6 +74,7 @@
 
 use cast;
 use container::Map;
+use logging;
 use option;
 use pipes::{Chan, GenericChan, GenericPort, Port, stream};
 use pipes;
@@ -88,7 +89,7 @@ use task::rt;
 use task::{Failure, ManualThreads, PlatformThread, SchedOpts, SingleThreaded};
 use task::{Success, TaskOpts, TaskResult, ThreadPerCore, ThreadPerTask};
 use task::{ExistingScheduler, SchedulerHandle};
-use task::{default_task_opts, unkillable};
+use task::{default_sched_threads, default_task_opts, unkillable};
 use uint;
 use util;
 
@@ -528,6 +529,8 @@ fn gen_child_taskgroup(linked: bool, supervised: bool)
 pub fn spawn_raw(opts: TaskOpts, f: fn~()) {
     let (child_tg, ancestors, is_main) =
         gen_child_taskgroup(opts.linked, opts.supervised);
+    // The child logs through the same logger as its parent
+    let f = logging::inherit_logger(move f);
 
     unsafe {
         let child_data = ~mut Some((move child_tg, move ancestors, move f));
@@ -653,7 +656,7 @@ pub fn spawn_raw(opts: TaskOpts, f: fn~()) {
             | ExistingScheduler(*)
             | PlatformThread => 0u, /* Won't be used */
             SingleThreaded => 1u,
-            ThreadPerCore => unsafe { rt::rust_num_threads() },
+            ThreadPerCore => default_sched_threads(),
             ThreadPerTask => {
                 die!(~"ThreadPerTask scheduling mode unimplemented")
             }
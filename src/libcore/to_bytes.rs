@@ -249,6 +249,18 @@ impl<A: IterBytes> @[A]: IterBytes {
     }
 }
 
+/**
+ * Feed the bytes of `a` to `f`, returning false if `f` asked to stop.
+ * Implementations of `iter_bytes` for aggregates use this to stop feeding
+ * their remaining parts once the callback has returned false.
+ */
+#[inline(always)]
+pub pure fn iter_bytes_until<A: IterBytes>(a: &A, lsb0: bool, f: Cb) -> bool {
+    let mut flag = true;
+    a.iter_bytes(lsb0, |bytes| {flag = f(bytes); flag});
+    flag
+}
+
 pub pure fn iter_bytes_2<A: IterBytes, B: IterBytes>(a: &A, b: &B,
                                             lsb0: bool, z: Cb) {
     let mut flag = true;
@@ -15,6 +15,7 @@ The `ToStr` trait for converting to strings
 */
 
 use kinds::Copy;
+use option::{None, Option, Some};
 use str;
 use vec;
 
@@ -92,6 +93,16 @@ impl<A: ToStr> ~[A]: ToStr {
     }
 }
 
+impl<A: ToStr> Option<A>: ToStr {
+    #[inline(always)]
+    pure fn to_str(&self) -> ~str {
+        match *self {
+            Some(ref a) => ~"Some(" + a.to_str() + ~")",
+            None => ~"None"
+        }
+    }
+}
+
 impl<A: ToStr> @A: ToStr {
     #[inline(always)]
     pure fn to_str(&self) -> ~str { ~"@" + (**self).to_str() }
@@ -104,6 +115,8 @@ impl<A: ToStr> ~A: ToStr {
 #[cfg(test)]
 #[allow(non_implicitly_copyable_typarams)]
 mod tests {
+    use option::{None, Some};
+
     #[test]
     fn test_simple_types() {
         assert 1i.to_str() == ~"1";
@@ -135,6 +148,14 @@ mod tests {
                ~"~[~[], ~[1], ~[1, 1]]";
     }
 
+    #[test]
+    fn test_option() {
+        assert Some(1).to_str() == ~"Some(1)";
+        assert None::<int>.to_str() == ~"None";
+        assert Some(Some(~"a")).to_str() == ~"Some(Some(a))";
+        assert (Some(1), None::<bool>).to_str() == ~"(Some(1), None)";
+    }
+
     #[test]
     fn test_pointer_types() {
         assert (@1).to_str() == ~"@1";
@@ -10,6 +10,7 @@
 
 //! Operations on tuples
 
+use clone::Clone;
 use cmp::{Eq, Ord};
 use kinds::Copy;
 use vec;
@@ -111,6 +112,24 @@ impl<A: Copy, B: Copy> (~[A], ~[B]): ExtendedTupleOps<A,B> {
     }
 }
 
+impl<A: Clone, B: Clone> (A, B): Clone {
+    #[inline(always)]
+    fn clone(&self) -> (A, B) {
+        match *self {
+            (ref a, ref b) => (a.clone(), b.clone())
+        }
+    }
+}
+
+impl<A: Clone, B: Clone, C: Clone> (A, B, C): Clone {
+    #[inline(always)]
+    fn clone(&self) -> (A, B, C) {
+        match *self {
+            (ref a, ref b, ref c) => (a.clone(), b.clone(), c.clone())
+        }
+    }
+}
+
 #[cfg(notest)]
 impl<A: Eq, B: Eq> (A, B) : Eq {
     #[inline(always)]
@@ -196,6 +215,175 @@ impl<A: Ord, B: Ord, C: Ord> (A, B, C) : Ord {
     pure fn gt(&self, other: &(A, B, C)) -> bool { (*other).lt(&(*self))  }
 }
 
+#[cfg(notest)]
+impl<A: Eq, B: Eq, C: Eq, D: Eq> (A, B, C, D) : Eq {
+    #[inline(always)]
+    pure fn eq(&self, other: &(A, B, C, D)) -> bool {
+        match (*self) {
+            (ref a1, ref b1, ref c1, ref d1) => match other {
+                &(ref a2, ref b2, ref c2, ref d2) => {
+                    (*a1).eq(a2) && (*b1).eq(b2) && (*c1).eq(c2)
+                        && (*d1).eq(d2)
+                }
+            }
+        }
+    }
+    #[inline(always)]
+    pure fn ne(&self, other: &(A, B, C, D)) -> bool {
+        !(*self).eq(other)
+    }
+}
+
+#[cfg(notest)]
+impl<A: Ord, B: Ord, C: Ord, D: Ord> (A, B, C, D) : Ord {
+    #[inline(always)]
+    pure fn lt(&self, other: &(A, B, C, D)) -> bool {
+        match (*self) {
+            (ref a1, ref b1, ref c1, ref d1) => {
+                match (*other) {
+                    (ref a2, ref b2, ref c2, ref d2) => {
+                        if (*a1).lt(a2) { return true; }
+                        if (*a2).lt(a1) { return false; }
+                        if (*b1).lt(b2) { return true; }
+                        if (*b2).lt(b1) { return false; }
+                        if (*c1).lt(c2) { return true; }
+                        if (*c2).lt(c1) { return false; }
+                        if (*d1).lt(d2) { return true; }
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+    #[inline(always)]
+    pure fn le(&self, other: &(A, B, C, D)) -> bool {
+        !(*other).lt(&(*self))
+    }
+    #[inline(always)]
+    pure fn ge(&self, other: &(A, B, C, D)) -> bool {
+        !(*self).lt(other)
+    }
+    #[inline(always)]
+    pure fn gt(&self, other: &(A, B, C, D)) -> bool {
+        (*other).lt(&(*self))
+    }
+}
+
+#[cfg(notest)]
+impl<A: Eq, B: Eq, C: Eq, D: Eq, E: Eq> (A, B, C, D, E) : Eq {
+    #[inline(always)]
+    pure fn eq(&self, other: &(A, B, C, D, E)) -> bool {
+        match (*self) {
+            (ref a1, ref b1, ref c1, ref d1, ref e1) => match other {
+                &(ref a2, ref b2, ref c2, ref d2, ref e2) => {
+                    (*a1).eq(a2) && (*b1).eq(b2) && (*c1).eq(c2)
+                        && (*d1).eq(d2) && (*e1).eq(e2)
+                }
+            }
+        }
+    }
+    #[inline(always)]
+    pure fn ne(&self, other: &(A, B, C, D, E)) -> bool {
+        !(*self).eq(other)
+    }
+}
+
+#[cfg(notest)]
+impl<A: Ord, B: Ord, C: Ord, D: Ord, E: Ord> (A, B, C, D, E) : Ord {
+    #[inline(always)]
+    pure fn lt(&self, other: &(A, B, C, D, E)) -> bool {
+        match (*self) {
+            (ref a1, ref b1, ref c1, ref d1, ref e1) => {
+                match (*other) {
+                    (ref a2, ref b2, ref c2, ref d2, ref e2) => {
+                        if (*a1).lt(a2) { return true; }
+                        if (*a2).lt(a1) { return false; }
+                        if (*b1).lt(b2) { return true; }
+                        if (*b2).lt(b1) { return false; }
+                        if (*c1).lt(c2) { return true; }
+                        if (*c2).lt(c1) { return false; }
+                        if (*d1).lt(d2) { return true; }
+                        if (*d2).lt(d1) { return false; }
+                        if (*e1).lt(e2) { return true; }
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+    #[inline(always)]
+    pure fn le(&self, other: &(A, B, C, D, E)) -> bool {
+        !(*other).lt(&(*self))
+    }
+    #[inline(always)]
+    pure fn ge(&self, other: &(A, B, C, D, E)) -> bool {
+        !(*self).lt(other)
+    }
+    #[inline(always)]
+    pure fn gt(&self, other: &(A, B, C, D, E)) -> bool {
+        (*other).lt(&(*self))
+    }
+}
+
+#[cfg(notest)]
+impl<A: Eq, B: Eq, C: Eq, D: Eq, E: Eq, F: Eq> (A, B, C, D, E, F) : Eq {
+    #[inline(always)]
+    pure fn eq(&self, other: &(A, B, C, D, E, F)) -> bool {
+        match (*self) {
+            (ref a1, ref b1, ref c1, ref d1, ref e1, ref f1) => match other {
+                &(ref a2, ref b2, ref c2, ref d2, ref e2, ref f2) => {
+                    (*a1).eq(a2) && (*b1).eq(b2) && (*c1).eq(c2)
+                        && (*d1).eq(d2) && (*e1).eq(e2) && (*f1).eq(f2)
+                }
+            }
+        }
+    }
+    #[inline(always)]
+    pure fn ne(&self, other: &(A, B, C, D, E, F)) -> bool {
+        !(*self).eq(other)
+    }
+}
+
+#[cfg(notest)]
+impl<A: Ord, B: Ord, C: Ord, D: Ord, E: Ord, F: Ord>
+    (A, B, C, D, E, F) : Ord {
+    #[inline(always)]
+    pure fn lt(&self, other: &(A, B, C, D, E, F)) -> bool {
+        match (*self) {
+            (ref a1, ref b1, ref c1, ref d1, ref e1, ref f1) => {
+                match (*other) {
+                    (ref a2, ref b2, ref c2, ref d2, ref e2, ref f2) => {
+                        if (*a1).lt(a2) { return true; }
+                        if (*a2).lt(a1) { return false; }
+                        if (*b1).lt(b2) { return true; }
+                        if (*b2).lt(b1) { return false; }
+                        if (*c1).lt(c2) { return true; }
+                        if (*c2).lt(c1) { return false; }
+                        if (*d1).lt(d2) { return true; }
+                        if (*d2).lt(d1) { return false; }
+                        if (*e1).lt(e2) { return true; }
+                        if (*e2).lt(e1) { return false; }
+                        if (*f1).lt(f2) { return true; }
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+    #[inline(always)]
+    pure fn le(&self, other: &(A, B, C, D, E, F)) -> bool {
+        !(*other).lt(&(*self))
+    }
+    #[inline(always)]
+    pure fn ge(&self, other: &(A, B, C, D, E, F)) -> bool {
+        !(*self).lt(other)
+    }
+    #[inline(always)]
+    pure fn gt(&self, other: &(A, B, C, D, E, F)) -> bool {
+        (*other).lt(&(*self))
+    }
+}
+
 #[test]
 fn test_tuple_ref() {
     let x = (~"foo", ~"bar");
@@ -211,3 +399,13 @@ fn test_tuple() {
     assert ('a', 2).swap() == (2, 'a');
 }
 
+
+#[test]
+fn test_tuple_cmp() {
+    assert (1, 2, 3, 4) == (1, 2, 3, 4);
+    assert (1, 2, 3, 4, 5) != (1, 2, 3, 4, 6);
+    assert (1, 2, 3, 4, 5, 6) < (1, 2, 3, 4, 5, 7);
+    assert (1, 2, 4, 0) > (1, 2, 3, 9);
+    assert (~"a", 'b', 2u, 3, 4, 5) >= (~"a", 'b', 2u, 3, 4, 5);
+    assert !((0, 0, 0, 0, 1) <= (0, 0, 0, 0, 0));
+}
@@ -16,6 +16,7 @@ Miscellaneous helpers for common patterns.
 
 use cmp::Eq;
 use prelude::*;
+use private;
 
 /// The identity function.
 #[inline(always)]
@@ -71,6 +72,46 @@ pub struct NonCopyable {
 
 pub fn NonCopyable() -> NonCopyable { NonCopyable { i: () } }
 
+/**
+ * A count of how many `DropCounter`s made from it have been destroyed.
+ *
+ * For tests that check a container destroys each of its elements exactly
+ * once, including when it fails part way. A clone shares the count, and
+ * can be moved into another task.
+ */
+pub struct DropCount {
+    priv count: private::Exclusive<uint>,
+}
+
+pub fn DropCount() -> DropCount {
+    DropCount { count: private::exclusive(0u) }
+}
+
+pub impl DropCount {
+    /// Make a value that adds one to the count when it is destroyed
+    fn counter(&self, val: uint) -> DropCounter {
+        DropCounter { val: val, count: self.count.clone() }
+    }
+
+    /// The number of counters destroyed so far
+    fn get(&self) -> uint {
+        unsafe { self.count.with_imm(|c| *c) }
+    }
+}
+
+impl DropCount: Clone {
+    fn clone(&self) -> DropCount {
+        DropCount { count: self.count.clone() }
+    }
+}
+
+/// A value made by `DropCount::counter`; `val` is for the test's own use
+pub struct DropCounter {
+    val: uint,
+    priv count: private::Exclusive<uint>,
+    drop { unsafe { do self.count.with |c| { *c += 1; } } }
+}
+
 /**
 A utility function for indicating unreachable code. It will fail if
 executed. This is occasionally useful to put after loops that never
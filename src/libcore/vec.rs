@@ -15,7 +15,9 @@
 use container::{Container, Mutable};
 use cast::transmute;
 use cast;
-use cmp::{Eq, Ord};
+use clone::Clone;
+use cmp::{Eq, Ord, Ordering, Less, Equal};
+use cmp;
 use iter::BaseIter;
 use iter;
 use kinds::Copy;
@@ -33,6 +35,7 @@ pub extern mod rustrt {
     unsafe fn vec_reserve_shared(++t: *sys::TypeDesc,
                                  ++v: **raw::VecRepr,
                                  ++n: libc::size_t);
+    unsafe fn vec_shrink_shared(++v: **raw::VecRepr);
 }
 
 #[abi = "rust-intrinsic"]
@@ -93,6 +96,20 @@ pub fn reserve_at_least<T>(v: &mut ~[T], n: uint) {
     reserve(v, uint::next_power_of_two(n));
 }
 
+/**
+ * Shrinks the capacity of the given vector down to its length.
+ *
+ * If the vector has no spare capacity then no action is taken.
+ */
+pub fn shrink_to_fit<T>(v: &mut ~[T]) {
+    if capacity(v) > v.len() {
+        unsafe {
+            let ptr: **raw::VecRepr = cast::transmute(v);
+            rustrt::vec_shrink_shared(ptr);
+        }
+    }
+}
+
 /// Returns the number of elements the vector can hold without reallocating
 #[inline(always)]
 pub pure fn capacity<T>(v: &const ~[T]) -> uint {
@@ -652,10 +669,15 @@ pub fn push_all_move<T>(v: &mut ~[T], mut rhs: ~[T]) {
     }
 }
 
-/// Shorten a vector, dropping excess elements.
+/**
+ * Shorten a vector, dropping excess elements.
+ *
+ * Does nothing if the vector is not longer than `newlen`. The capacity is
+ * left unchanged; see `shrink_to_fit`.
+ */
 pub fn truncate<T>(v: &mut ~[T], newlen: uint) {
     do as_mut_buf(*v) |p, oldlen| {
-        assert(newlen <= oldlen);
+        if newlen >= oldlen { return; }
         unsafe {
             // This loop is optimized out for non-drop types.
             for uint::range(newlen, oldlen) |i| {
@@ -671,40 +693,25 @@ pub fn truncate<T>(v: &mut ~[T], newlen: uint) {
 /**
  * Remove consecutive repeated elements from a vector; if the vector is
  * sorted, this removes all duplicates.
+ *
+ * Duplicates are swapped to the end and dropped by a final `truncate`, so
+ * if `eq` fails part way through every element is still dropped exactly
+ * once.
  */
 pub fn dedup<T: Eq>(v: &mut ~[T]) {
-    unsafe {
-        if v.len() < 1 { return; }
-        let mut last_written = 0, next_to_read = 1;
-        do as_const_buf(*v) |p, ln| {
-            // We have a mutable reference to v, so we can make arbitrary
-            // changes. (cf. push and pop)
-            let p = p as *mut T;
-            // last_written < next_to_read <= ln
-            while next_to_read < ln {
-                // last_written < next_to_read < ln
-                if *ptr::mut_offset(p, next_to_read) ==
-                    *ptr::mut_offset(p, last_written) {
-                    // FIXME #4204 Should be rusti::uninit() - don't need to
-                    // zero
-                    let mut dropped = rusti::init();
-                    dropped <-> *ptr::mut_offset(p, next_to_read);
-                } else {
-                    last_written += 1;
-                    // last_written <= next_to_read < ln
-                    if next_to_read != last_written {
-                        *ptr::mut_offset(p, last_written) <->
-                            *ptr::mut_offset(p, next_to_read);
-                    }
-                }
-                // last_written <= next_to_read < ln
-                next_to_read += 1;
-                // last_written < next_to_read <= ln
+    let ln = v.len();
+    if ln < 2 { return; }
+    let mut last_written = 0;
+    for uint::range(1, ln) |next_to_read| {
+        // last_written < next_to_read < ln
+        if v[next_to_read] != v[last_written] {
+            last_written += 1;
+            if next_to_read != last_written {
+                v[last_written] <-> v[next_to_read];
             }
         }
-        // last_written < next_to_read == ln
-        raw::set_len(v, last_written + 1);
     }
+    v.truncate(last_written + 1);
 }
 
 
@@ -914,8 +921,11 @@ pub pure fn filtered<T: Copy>(v: &[T], f: fn(t: &T) -> bool) -> ~[T] {
 
 /**
  * Like `filter()`, but in place.  Preserves order of `v`.  Linear time.
+ *
+ * Removed elements are dropped exactly once, even if `f` fails part way
+ * through.
  */
-pub fn retain<T>(v: &mut ~[T], f: pure fn(t: &T) -> bool) {
+pub fn retain<T>(v: &mut ~[T], f: fn(t: &T) -> bool) {
     let len = v.len();
     let mut deleted: uint = 0;
 
@@ -1448,6 +1458,88 @@ pub fn each2<U, T>(v1: &[U], v2: &[T], f: fn(u: &U, t: &T) -> bool) {
     }
 }
 
+/**
+ * Iterates over consecutive, non-overlapping slices of `size` elements
+ *
+ * The final chunk is shorter than `size` if `size` does not divide the
+ * length of `v`. Return true to continue, false to break.
+ *
+ * # Failure
+ *
+ * Fails if `size` is zero
+ */
+pub pure fn each_chunk<T>(v: &r/[T], size: uint, it: fn(&r/[T]) -> bool) {
+    assert size != 0u;
+    let ln = len(v);
+    let mut start = 0u;
+    while start < ln {
+        let end = if ln - start < size { ln } else { start + size };
+        if !it(view(v, start, end)) { return; }
+        start = end;
+    }
+}
+
+/**
+ * Iterates over every overlapping slice of `size` elements
+ *
+ * Nothing is yielded if `v` is shorter than `size`. Return true to
+ * continue, false to break.
+ *
+ * # Failure
+ *
+ * Fails if `size` is zero
+ */
+pub pure fn each_window<T>(v: &r/[T], size: uint, it: fn(&r/[T]) -> bool) {
+    assert size != 0u;
+    let ln = len(v);
+    if ln < size { return; }
+    for uint::range(0u, ln - size + 1u) |start| {
+        if !it(view(v, start, start + size)) { return; }
+    }
+}
+
+/**
+ * Finds where an element belongs in a sorted vector
+ *
+ * `f` compares an element of `v` against the value being looked for,
+ * returning `Less` if the element sorts before it. The result is the index
+ * of the first element for which `f` does not return `Less`, which is
+ * `len(v)` if there is none. Inserting the value there keeps `v` sorted.
+ */
+pub pure fn bsearch_insertion_point<T>(v: &[T], f: fn(&T) -> Ordering)
+    -> uint {
+    let mut lo = 0u, hi = len(v);
+    // Everything before lo is Less; nothing from hi on is
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2u;
+        if f(&v[mid]) == Less { lo = mid + 1u; } else { hi = mid; }
+    }
+    lo
+}
+
+/**
+ * Binary search a sorted vector
+ *
+ * `f` compares an element of `v` against the value being looked for,
+ * returning `Less` if the element sorts before it, `Greater` if after and
+ * `Equal` if it matches. If several elements match, the index of the first
+ * one is returned.
+ */
+pub pure fn bsearch<T>(v: &[T], f: fn(&T) -> Ordering) -> Option<uint> {
+    let i = bsearch_insertion_point(v, f);
+    if i < len(v) && f(&v[i]) == Equal { Some(i) } else { None }
+}
+
+/**
+ * Binary search a sorted vector for an element
+ *
+ * If several elements are equal to `x`, the index of the first one is
+ * returned.
+ */
+pub pure fn bsearch_elem<T: Ord>(v: &[T], x: &T) -> Option<uint> {
+    bsearch(v, |e| cmp::cmp(e, x))
+}
+
 /**
  * Iterate over all permutations of vector `v`.
  *
@@ -1456,32 +1548,100 @@ pub fn each2<U, T>(v1: &[U], v2: &[T], f: fn(u: &U, t: &T) -> bool) {
  * lexicographically sorted).
  *
  * The total number of permutations produced is `len(v)!`.  If `v` contains
- * repeated elements, then some permutations are repeated.
+ * repeated elements, then some permutations are repeated. The empty vector
+ * has a single, empty, permutation. Return false from `put` to stop early.
+ *
+ * The elements are not copied; each permutation is a view of them in a
+ * scratch buffer that is only valid for the duration of the call to `put`.
  */
-pure fn each_permutation<T: Copy>(v: &[T], put: fn(ts: &[T]) -> bool) {
+pub pure fn each_permutation<T>(v: &[T], put: fn(ts: &[T]) -> bool) {
     let ln = len(v);
-    if ln <= 1 {
-        put(v);
-    } else {
-        // This does not seem like the most efficient implementation.  You
-        // could make far fewer copies if you put your mind to it.
-        let mut i = 0u;
-        while i < ln {
-            let elt = v[i];
-            let mut rest = slice(v, 0u, i);
-            unsafe {
-                rest.push_all(const_view(v, i+1u, ln));
-                for each_permutation(rest) |permutation| {
-                    if !put(append(~[elt], permutation)) {
-                        return;
-                    }
-                }
+    let mut idx = from_fn(ln, |i| i);
+    unsafe {
+        let scratch: ~[T] = with_capacity(ln);
+        loop {
+            if !with_gathered(v, idx, &scratch, put) { return; }
+            if !next_permutation(idx) { return; }
+        }
+    }
+}
+
+/**
+ * Iterate over all combinations of `k` elements of vector `v`.
+ *
+ * Each combination keeps the elements in the order they appear in `v`, and
+ * combinations are produced in lexicographic order of their positions in
+ * `v`. There are `len(v)` choose `k` of them: a single empty combination if
+ * `k` is zero, and none at all if `k` is greater than `len(v)`. Return false
+ * from `put` to stop early.
+ *
+ * As with `each_permutation` the elements are not copied.
+ */
+pub pure fn each_combination<T>(v: &[T], k: uint, put: fn(ts: &[T]) -> bool) {
+    let ln = len(v);
+    if k > ln { return; }
+    let mut idx = from_fn(k, |i| i);
+    unsafe {
+        let scratch: ~[T] = with_capacity(k);
+        loop {
+            if !with_gathered(v, idx, &scratch, put) { return; }
+
+            // Advance the rightmost position that still has room to move,
+            // then pack everything after it up against it.
+            let mut i = k;
+            loop {
+                if i == 0 { return; }
+                i -= 1;
+                if idx[i] < ln - k + i { break; }
             }
-            i += 1u;
+            idx[i] += 1;
+            for uint::range(i + 1, k) |j| { idx[j] = idx[j - 1] + 1; }
         }
     }
 }
 
+/*
+ * Calls `put` with the elements of `v` at positions `idx`, laid out in the
+ * buffer of `scratch`. `scratch` keeps a length of zero, so the bitwise
+ * copies it holds are never dropped; `put` only ever sees them borrowed.
+ */
+unsafe fn with_gathered<T>(v: &[T], idx: &[uint], scratch: &~[T],
+                           put: fn(ts: &[T]) -> bool) -> bool {
+    assert capacity(scratch) >= idx.len();
+    let buf = raw::to_ptr(*scratch) as *mut T;
+    for idx.eachi |i, j| {
+        ptr::copy_memory(ptr::mut_offset(buf, i), addr_of(&v[*j]), 1);
+    }
+    raw::buf_as_slice(buf as *T, idx.len(), put)
+}
+
+/*
+ * Rearranges `a` into the next permutation in lexicographic order,
+ * returning false if it was already the last one.
+ */
+fn next_permutation(a: &mut [uint]) -> bool {
+    let ln = a.len();
+    if ln < 2 { return false; }
+
+    // Find the longest non-increasing suffix; the element before it is
+    // the one to bump.
+    let mut i = ln - 1;
+    while i > 0 && a[i - 1] >= a[i] { i -= 1; }
+    if i == 0 { return false; }
+
+    let mut j = ln - 1;
+    while a[j] <= a[i - 1] { j -= 1; }
+    a[i - 1] <-> a[j];
+
+    let mut lo = i, hi = ln - 1;
+    while lo < hi {
+        a[lo] <-> a[hi];
+        lo += 1;
+        hi -= 1;
+    }
+    true
+}
+
 pub pure fn windowed<TT: Copy>(nn: uint, xx: &[TT]) -> ~[~[TT]] {
     let mut ww = ~[];
     assert 1u <= nn;
@@ -1579,6 +1739,12 @@ impl<T: Eq> ~[T] : Eq {
     pure fn ne(&self, other: &~[T]) -> bool { !(*self).eq(other) }
 }
 
+impl<A: Clone> ~[A]: Clone {
+    /// Return a new vector holding a clone of each element
+    #[inline]
+    fn clone(&self) -> ~[A] { map(*self, |elt| elt.clone()) }
+}
+
 #[cfg(notest)]
 impl<T: Eq> @[T] : Eq {
     #[inline(always)]
@@ -1881,7 +2047,8 @@ pub trait OwnedVector<T> {
     fn remove(&mut self, i: uint) -> T;
     fn swap_remove(&mut self, index: uint) -> T;
     fn truncate(&mut self, newlen: uint);
-    fn retain(&mut self, f: pure fn(t: &T) -> bool);
+    fn shrink_to_fit(&mut self);
+    fn retain(&mut self, f: fn(t: &T) -> bool);
     fn consume(self, f: fn(uint, v: T));
     fn filter(self, f: fn(t: &T) -> bool) -> ~[T];
     fn partition(self, f: pure fn(&T) -> bool) -> (~[T], ~[T]);
@@ -1934,7 +2101,12 @@ impl<T> ~[T]: OwnedVector<T> {
     }
 
     #[inline]
-    fn retain(&mut self, f: pure fn(t: &T) -> bool) {
+    fn shrink_to_fit(&mut self) {
+        shrink_to_fit(self);
+    }
+
+    #[inline]
+    fn retain(&mut self, f: fn(t: &T) -> bool) {
         retain(self, f);
     }
 
@@ -2447,7 +2619,14 @@ impl<A:Copy> @[A] : iter::CopyableNonstrictIter<A> {
 #[cfg(test)]
 mod tests {
     use option::{None, Option, Some};
+    use cmp;
+    use int;
     use option;
+    use rand;
+    use ptr;
+    use task;
+    use uint;
+    use util::{DropCount, DropCounter};
     use vec::*;
 
     fn square(n: uint) -> uint { return n * n; }
@@ -2714,6 +2893,53 @@ mod tests {
         // If the unsafe block didn't drop things properly, we blow up here.
     }
 
+    #[test]
+    fn test_truncate_drops() {
+        let count = @mut 0;
+        let mut v = counted(~[1, 2, 3, 4, 5], count);
+        v.truncate(2);
+        assert *count == 3;
+        assert map(v, |x| x.val) == ~[1, 2];
+
+        // Not longer than the new length, so nothing happens
+        v.truncate(2);
+        v.truncate(10);
+        assert *count == 3;
+        assert v.len() == 2;
+
+        v.truncate(0);
+        assert *count == 5;
+        assert v.is_empty();
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut v: ~[uint] = ~[];
+        reserve(&mut v, 1000);
+        assert capacity(&v) >= 1000;
+        for uint::range(0, 10) |i| { v.push(i); }
+        assert capacity(&v) >= 1000;
+
+        v.shrink_to_fit();
+        assert capacity(&v) == 10;
+        assert v == ~[0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+        // Still usable afterwards
+        v.push(10);
+        assert v.len() == 11;
+        assert capacity(&v) >= 11;
+        v.truncate(3);
+        shrink_to_fit(&mut v);
+        assert capacity(&v) == 3;
+        assert v == ~[0, 1, 2];
+
+        let mut v: ~[~int] = ~[];
+        v.shrink_to_fit();
+        assert capacity(&v) == 0;
+        v.push(~1);
+        assert *v[0] == 1;
+    }
+
     #[test]
     fn test_clear() {
         let mut v = ~[@6,@5,@4];
@@ -2887,6 +3113,93 @@ mod tests {
         assert v == ~[1, 3, 5];
     }
 
+    // Comparing with a counter holding 0 fails
+    impl DropCounter: Eq {
+        pure fn eq(&self, other: &DropCounter) -> bool {
+            if self.val == 0 || other.val == 0 { die!(); }
+            self.val == other.val
+        }
+        pure fn ne(&self, other: &DropCounter) -> bool { !self.eq(other) }
+    }
+
+    fn counted(vals: &[uint], count: &DropCount) -> ~[DropCounter] {
+        map(vals, |v| count.counter(*v))
+    }
+
+    #[test]
+    fn test_retain_drops() {
+        let count = DropCount();
+        {
+            let mut v = counted(~[1, 2, 3, 4, 5, 6], &count);
+            v.retain(|x| x.val % 3 != 0);
+            assert count.get() == 2;
+            assert map(v, |x| x.val) == ~[1, 2, 4, 5];
+        }
+        assert count.get() == 6;
+
+        let mut v = ~[1, 2, 3];
+        v.retain(|_x| false);
+        assert v.is_empty();
+    }
+
+    #[test]
+    fn test_dedup_drops() {
+        let count = DropCount();
+        {
+            let mut v = counted(~[1, 1, 2, 3, 3, 3, 1], &count);
+            v.dedup();
+            assert count.get() == 3;
+            assert map(v, |x| x.val) == ~[1, 2, 3, 1];
+        }
+        assert count.get() == 7;
+
+        // Already unique
+        let count = DropCount();
+        let mut v = counted(~[1, 2, 3], &count);
+        v.dedup();
+        assert count.get() == 0;
+        assert v.len() == 3;
+
+        // All equal
+        let count = DropCount();
+        let mut v = counted(~[7, 7, 7, 7], &count);
+        v.dedup();
+        assert count.get() == 3;
+        assert v.len() == 1 && v[0].val == 7;
+    }
+
+    #[test]
+    #[ignore(cfg(windows))]
+    fn test_retain_fail_midway() {
+        let count = DropCount();
+        let count2 = count.clone();
+        let r = do task::try |move count2| {
+            let mut v = counted(~[1, 2, 3, 4, 5, 6], &count2);
+            do v.retain |x| {
+                if x.val == 5 { die!(); }
+                x.val % 2 == 0
+            }
+        };
+        assert r.is_err();
+        // Each element was destroyed once, whether it had been removed
+        // before the failure or not.
+        assert count.get() == 6;
+    }
+
+    #[test]
+    #[ignore(cfg(windows))]
+    fn test_dedup_fail_midway() {
+        let count = DropCount();
+        let count2 = count.clone();
+        let r = do task::try |move count2| {
+            // Fails comparing with the 0, after moving the 2 and 3 down
+            let mut v = counted(~[1, 1, 2, 2, 3, 0, 4, 4], &count2);
+            v.dedup();
+        };
+        assert r.is_err();
+        assert count.get() == 8;
+    }
+
     #[test]
     fn test_foldl() {
         // Test on-stack fold.
@@ -2997,6 +3310,83 @@ mod tests {
             ~[~[5,2,0],~[5,0,2],~[2,5,0],~[2,0,5],~[0,5,2],~[0,2,5]];
     }
 
+    #[test]
+    fn test_each_permutation_count() {
+        let mut results: ~[~[int]] = ~[];
+        for each_permutation(~[1, 2, 3, 4]) |v| {
+            results.push(from_slice(v));
+        }
+        assert results.len() == 24;
+        for results.eachi |i, a| {
+            for results.eachi |j, b| {
+                assert i == j || a != b;
+            }
+        }
+
+        // Early exit
+        let mut n = 0;
+        for each_permutation(~[1, 2, 3, 4]) |_v| {
+            n += 1;
+            if n == 5 { break; }
+        }
+        assert n == 5;
+    }
+
+    #[test]
+    fn test_each_permutation_owned() {
+        let mut results = ~[];
+        for each_permutation(~[~"a", ~"b", ~"c"]) |v| {
+            results.push(str::concat(v));
+        }
+        assert results ==
+            ~[~"abc", ~"acb", ~"bac", ~"bca", ~"cab", ~"cba"];
+    }
+
+    #[test]
+    fn test_each_combination() {
+        let mut results: ~[~[int]] = ~[];
+        for each_combination(~[1, 2, 3, 4, 5], 2) |v| {
+            results.push(from_slice(v));
+        }
+        assert results.len() == 10;
+        assert results[0] == ~[1, 2];
+        assert results[9] == ~[4, 5];
+        for results.eachi |i, a| {
+            for results.eachi |j, b| {
+                assert i == j || a != b;
+            }
+        }
+
+        results = ~[];
+        for each_combination(~[1, 2, 3], 0) |v| {
+            results.push(from_slice(v));
+        }
+        assert results == ~[~[]];
+
+        results = ~[];
+        for each_combination(~[1, 2, 3], 4) |v| {
+            results.push(from_slice(v));
+        }
+        assert results == ~[];
+
+        results = ~[];
+        for each_combination(~[], 0) |v| { results.push(from_slice(v)); }
+        assert results == ~[~[]];
+
+        results = ~[];
+        for each_combination(~[1, 2, 3], 3) |v| {
+            results.push(from_slice(v));
+        }
+        assert results == ~[~[1, 2, 3]];
+
+        let mut owned = ~[];
+        for each_combination(~[~"a", ~"b", ~"c"], 2) |v| {
+            owned.push(str::concat(v));
+            if owned.len() == 2 { break; }
+        }
+        assert owned == ~[~"ab", ~"ac"];
+    }
+
     #[test]
     fn test_any_and_all() {
         assert (any(~[1u, 2u, 3u], is_three));
@@ -3348,6 +3738,122 @@ mod tests {
         let _x = windowed (0u, ~[1u,2u,3u,4u,5u,6u]);
     }
 
+    #[test]
+    fn test_bsearch() {
+        let v = ~[1, 3, 3, 3, 5, 8];
+        assert bsearch_elem(v, &1) == Some(0u);
+        assert bsearch_elem(v, &3) == Some(1u);
+        assert bsearch_elem(v, &8) == Some(5u);
+        assert bsearch_elem(v, &0) == None;
+        assert bsearch_elem(v, &4) == None;
+        assert bsearch_elem(v, &9) == None;
+        assert bsearch_elem(~[], &1) == None;
+
+        assert bsearch_insertion_point(v, |e| cmp::cmp(e, &0)) == 0u;
+        assert bsearch_insertion_point(v, |e| cmp::cmp(e, &3)) == 1u;
+        assert bsearch_insertion_point(v, |e| cmp::cmp(e, &4)) == 4u;
+        assert bsearch_insertion_point(v, |e| cmp::cmp(e, &9)) == 6u;
+
+        // Searching on a key with a custom comparison
+        let pairs = ~[(1, ~"a"), (4, ~"b"), (9, ~"c")];
+        let i = bsearch(pairs, |p| { let (k, _) = *p; cmp::cmp(&k, &4) });
+        assert i == Some(1u);
+    }
+
+    #[test]
+    fn test_bsearch_random() {
+        let rng = rand::Rng();
+        for uint::range(0u, 200u) |_i| {
+            let n = rng.gen_uint_range(0u, 20u);
+            let mut v = ~[];
+            let mut x = 0;
+            for n.times {
+                x += rng.gen_int_range(0, 3);
+                v.push(x);
+            }
+
+            for int::range(-1, x + 2) |target| {
+                let mut first = None;
+                let mut point = len(v);
+                for eachi(v) |i, e| {
+                    if *e == target && first.is_none() { first = Some(i); }
+                    if *e >= target && point == len(v) { point = i; }
+                }
+                assert bsearch_elem(v, &target) == first;
+                assert bsearch_insertion_point(v, |e| cmp::cmp(e, &target))
+                    == point;
+            }
+        }
+    }
+
+    #[test]
+    fn test_each_chunk() {
+        fn chunks(v: &[int], size: uint) -> ~[~[int]] {
+            let mut r = ~[];
+            for each_chunk(v, size) |c| { r.push(from_slice(c)); }
+            r
+        }
+
+        assert chunks(~[], 2u) == ~[];
+        assert chunks(~[1, 2, 3, 4], 2u) == ~[~[1, 2], ~[3, 4]];
+        assert chunks(~[1, 2, 3, 4, 5], 2u) == ~[~[1, 2], ~[3, 4], ~[5]];
+        assert chunks(~[1, 2, 3], 5u) == ~[~[1, 2, 3]];
+        assert chunks(~[1, 2, 3], 1u) == ~[~[1], ~[2], ~[3]];
+
+        // Chunks are borrowed from the vector, not copied
+        let v = ~[1, 2, 3, 4, 5];
+        let mut i = 0u;
+        for each_chunk(v, 2u) |c| {
+            assert ptr::addr_of(&c[0]) == ptr::addr_of(&v[i]);
+            i += 2u;
+        }
+
+        let mut n = 0;
+        for each_chunk(~[1, 2, 3, 4, 5], 2u) |_c| {
+            n += 1;
+            if n == 2 { break; }
+        }
+        assert n == 2;
+    }
+
+    #[test]
+    #[should_fail]
+    #[ignore(cfg(windows))]
+    fn test_each_chunk_zero() {
+        for each_chunk(~[1, 2, 3], 0u) |_c| { }
+    }
+
+    #[test]
+    fn test_each_window() {
+        fn windows(v: &[int], size: uint) -> ~[~[int]] {
+            let mut r = ~[];
+            for each_window(v, size) |w| { r.push(from_slice(w)); }
+            r
+        }
+
+        assert windows(~[], 2u) == ~[];
+        assert windows(~[1, 2, 3], 4u) == ~[];
+        assert windows(~[1, 2, 3], 3u) == ~[~[1, 2, 3]];
+        assert windows(~[1, 2, 3, 4], 2u) ==
+            ~[~[1, 2], ~[2, 3], ~[3, 4]];
+        assert windows(~[1, 2, 3], 1u) == ~[~[1], ~[2], ~[3]];
+
+        let v = ~[1, 2, 3, 4];
+        let mut i = 0u;
+        for each_window(v, 3u) |w| {
+            assert ptr::addr_of(&w[0]) == ptr::addr_of(&v[i]);
+            i += 1u;
+        }
+        assert i == 2u;
+    }
+
+    #[test]
+    #[should_fail]
+    #[ignore(cfg(windows))]
+    fn test_each_window_zero() {
+        for each_window(~[1, 2, 3], 0u) |_w| { }
+    }
+
     #[test]
     fn cast_to_mut_no_copy() {
         unsafe {
@@ -37,7 +37,6 @@ use core::run;
 use core::str;
 use core::vec;
 use std::oldmap::HashMap;
-use std::sha1::sha1;
 use syntax::ast;
 use syntax::ast_map::{path, path_mod, path_name};
 use syntax::attr;
@@ -175,6 +175,18 @@ pub fn time<T>(do_it: bool, what: ~str, thunk: fn() -> T) -> T {
     move rv
 }
 
+// Finds the macros exported by the crate an `extern mod` links, for the
+// expander. If `required`, a crate that can't be found is fatal.
+pub fn macro_loader(sess: Session,
+                    required: bool) -> syntax::ext::base::MacroLoader {
+    |vi| creader::read_exported_macros(sess.diagnostic(), sess.filesearch,
+                                       session::sess_os_to_meta_os(
+                                           sess.targ_cfg.os),
+                                       sess.opts.static,
+                                       sess.parse_sess.interner,
+                                       required, vi)
+}
+
 pub enum compile_upto {
     cu_parse,
     cu_expand,
@@ -210,7 +222,7 @@ pub fn compile_upto(sess: Session, cfg: ast::crate_cfg,
 
     crate = time(time_passes, ~"expansion", ||
         syntax::ext::expand::expand_crate(sess.parse_sess, copy cfg,
-                                          crate));
+                                          macro_loader(sess, true), crate));
 
     if upto == cu_expand { return {crate: crate, tcx: None}; }
 
@@ -308,6 +320,9 @@ pub fn compile_upto(sess: Session, cfg: ast::crate_cfg,
         time(time_passes, ~"lint checking", ||
              lint::check_crate(ty_cx, crate));
 
+        time(time_passes, ~"dead code checking", ||
+             middle::dead::check_crate(ty_cx, method_map, exp_map2, crate));
+
         if upto == cu_no_trans { return {crate: crate, tcx: Some(ty_cx)}; }
 
         let maps = astencode::Maps {
@@ -145,6 +145,7 @@ fn fold_block(
         expr: option::map(&b.expr, |x| fld.fold_expr(*x)),
         id: b.id,
         rules: b.rules,
+        attrs: /*bad*/copy b.attrs,
     }
 }
 
@@ -193,7 +194,7 @@ pub fn metas_in_cfg(cfg: ast::crate_cfg,
     if !has_cfg_metas { return true; }
 
     for cfg_metas.each |cfg_mi| {
-        if attr::contains(cfg, *cfg_mi) { return true; }
+        if attr::cfg_matches(cfg, *cfg_mi) { return true; }
     }
 
     return false;
@@ -77,6 +77,7 @@ mod intrinsic {
         fn visit_leave_rec(&self, n_fields: uint,
                            sz: uint, align: uint) -> bool;
 
+        fn visit_class_name(&self, name: &str) -> bool;
         fn visit_enter_class(&self, n_fields: uint,
                              sz: uint, align: uint) -> bool;
         fn visit_class_field(&self, i: uint, name: &str,
@@ -92,6 +93,7 @@ mod intrinsic {
 
         fn visit_enter_enum(&self, n_variants: uint,
                             sz: uint, align: uint) -> bool;
+        fn visit_enum_null_discrim(&self, disr_val: int) -> bool;
         fn visit_enter_enum_variant(&self, variant: uint,
                                     disr_val: int,
                                     n_fields: uint,
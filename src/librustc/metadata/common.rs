@@ -153,5 +153,9 @@ pub const tag_lang_items_item: uint = 0x73;
 pub const tag_lang_items_item_id: uint = 0x74;
 pub const tag_lang_items_item_node_id: uint = 0x75;
 
+// The def IDs of a trait's supertraits, which name resolution needs before
+// the types in `tag_impl_trait` can be decoded
+pub const tag_item_supertrait_def_id: uint = 0x76;
+
 pub type link_meta = {name: @str, vers: @str, extras_hash: @str};
 
@@ -59,6 +59,45 @@ pub fn read_crates(diag: span_handler,
     warn_if_multiple_versions(e, diag, e.crate_cache.get());
 }
 
+// Loads the crate that an `extern mod` names, without resolving it, to
+// find the macros it exports. This runs during expansion, before
+// `read_crates`. A crate that can't be found is an error only if
+// `required`; otherwise it exports nothing.
+pub fn read_exported_macros(diag: span_handler,
+                            filesearch: FileSearch,
+                            os: loader::os,
+                            static: bool,
+                            intr: @ident_interner,
+                            required: bool,
+                            vi: @ast::view_item) -> ~[~str] {
+    match /*bad*/copy vi.node {
+      ast::view_item_use(ident, meta_items, _) => {
+        let load_ctxt: loader::ctxt = {
+            diag: diag,
+            filesearch: filesearch,
+            span: vi.span,
+            ident: ident,
+            metas: metas_with_ident(/*bad*/copy *intr.get(ident),
+                                    meta_items),
+            hash: ~"",
+            os: os,
+            static: static,
+            intr: intr
+        };
+        let lib = if required {
+            Some(loader::load_library_crate(load_ctxt))
+        } else {
+            loader::find_library_crate(load_ctxt)
+        };
+        match lib {
+          Some(lib) => decoder::get_exported_macros(lib.data),
+          None => ~[]
+        }
+      }
+      _ => ~[]
+    }
+}
+
 type cache_entry = {
     cnum: int,
     span: span,
@@ -145,6 +145,19 @@ pub fn get_method_names_if_trait(cstore: cstore::CStore, def: ast::def_id)
     return decoder::get_method_names_if_trait(cstore.intr, cdata, def.node);
 }
 
+pub fn get_supertrait_def_ids(cstore: cstore::CStore, def: ast::def_id)
+                           -> ~[ast::def_id] {
+    let cdata = cstore::get_crate_data(cstore, def.crate);
+    decoder::get_supertrait_def_ids(cdata, def.node)
+}
+
+pub fn get_static_methods_if_trait(cstore: cstore::CStore,
+                                   def: ast::def_id)
+                                -> Option<~[StaticMethodInfo]> {
+    let cdata = cstore::get_crate_data(cstore, def.crate);
+    decoder::get_static_methods_if_trait(cstore.intr, cdata, def.node)
+}
+
 pub fn get_type_name_if_impl(cstore: cstore::CStore, def: ast::def_id) ->
         Option<ast::ident> {
     let cdata = cstore::get_crate_data(cstore, def.crate);
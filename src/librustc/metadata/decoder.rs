@@ -766,6 +766,47 @@ pub fn get_supertraits(cdata: cmd, id: ast::node_id, tcx: ty::ctxt)
     return dvec::unwrap(move results);
 }
 
+// Returns the def IDs of a trait's supertraits. Unlike `get_supertraits`,
+// this doesn't need a type context, so name resolution can use it.
+pub fn get_supertrait_def_ids(cdata: cmd, id: ast::node_id)
+                           -> ~[ast::def_id] {
+    let results = dvec::DVec();
+    let item_doc = lookup_item(id, cdata.data);
+    for reader::tagged_docs(item_doc, tag_item_supertrait_def_id) |doc| {
+        results.push(translate_def_id(cdata, reader::with_doc_data(doc,
+            |d| parse_def_id(d))));
+    }
+    return dvec::unwrap(move results);
+}
+
+// If the item in question is a trait, returns its static methods.
+// Otherwise, returns none.
+pub fn get_static_methods_if_trait(intr: @ident_interner,
+                                   cdata: cmd,
+                                   node_id: ast::node_id)
+                                -> Option<~[StaticMethodInfo]> {
+    let item = lookup_item(node_id, cdata.data);
+    if item_family(item) != Trait {
+        return None;
+    }
+
+    let static_methods = DVec();
+    for reader::tagged_docs(item, tag_item_trait_method) |method| {
+        if get_self_ty(method) != ast::sty_static { loop; }
+        let purity = match item_family(method) {
+            UnsafeStaticMethod | UnsafeFn => ast::unsafe_fn,
+            PureStaticMethod | PureFn => ast::pure_fn,
+            _ => ast::impure_fn
+        };
+        static_methods.push(StaticMethodInfo {
+            ident: item_name(intr, method),
+            def_id: item_def_id(method, cdata),
+            purity: purity
+        });
+    }
+    return Some(dvec::unwrap(move static_methods));
+}
+
 // If the item in question is a trait, returns its set of methods and
 // their self types. Otherwise, returns none. This overlaps in an
 // annoying way with get_trait_methods.
@@ -1017,6 +1058,16 @@ pub fn get_crate_attributes(data: @~[u8]) -> ~[ast::attribute] {
     return get_attributes(reader::Doc(data));
 }
 
+// The source of each macro the crate exported with #[macro_export], which
+// the expander left among the crate attributes
+pub fn get_exported_macros(data: @~[u8]) -> ~[~str] {
+    let attrs = attr::find_attrs_by_name(get_crate_attributes(data),
+                                         ~"exported_macro");
+    do vec::filter_map(attrs) |a| {
+        attr::get_meta_item_value_str(attr::attr_meta(*a))
+    }
+}
+
 pub type crate_dep = {cnum: ast::crate_num, name: ast::ident,
                       vers: ~str, hash: ~str};
 
@@ -156,6 +156,12 @@ fn encode_trait_ref(ebml_w: writer::Encoder, ecx: @encode_ctxt,
     ebml_w.end_tag();
 }
 
+fn encode_supertrait_def_id(ebml_w: writer::Encoder, ecx: @encode_ctxt,
+                            t: @trait_ref) {
+    let did = def_id_of_def(ecx.tcx.def_map.get(&t.ref_id));
+    ebml_w.wr_tagged_str(tag_item_supertrait_def_id, def_to_str(did));
+}
+
 
 // Item info table encoding
 fn encode_family(ebml_w: writer::Encoder, c: char) {
@@ -794,7 +800,8 @@ fn encode_info_for_item(ecx: @encode_ctxt, ebml_w: writer::Encoder,
         }
         encode_path(ecx, ebml_w, path, ast_map::path_name(item.ident));
         for traits.each |associated_trait| {
-           encode_trait_ref(ebml_w, ecx, *associated_trait)
+           encode_trait_ref(ebml_w, ecx, *associated_trait);
+           encode_supertrait_def_id(ebml_w, ecx, *associated_trait);
         }
 
         ebml_w.end_tag();
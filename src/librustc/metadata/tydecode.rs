@@ -262,8 +262,11 @@ fn parse_ty(st: @pstate, conv: conv_did) -> ty::t {
         let def = parse_def(st, NominalType, conv);
         let substs = parse_substs(st, conv);
         let vstore = parse_vstore(st);
+        let mut bounds = ty::no_object_bounds();
+        if peek(st) == 'S' { next(st); bounds.owned = true; }
+        if peek(st) == 'K' { next(st); bounds.const_ = true; }
         assert next(st) == ']';
-        return ty::mk_trait(st.tcx, def, substs, vstore);
+        return ty::mk_trait(st.tcx, def, substs, vstore, bounds);
       }
       'p' => {
         let did = parse_def(st, TypeParameter, conv);
@@ -248,12 +248,15 @@ fn enc_sty(w: io::Writer, cx: @ctxt, +st: ty::sty) {
         enc_substs(w, cx, (*substs));
         w.write_char(']');
       }
-      ty::ty_trait(def, ref substs, vstore) => {
+      ty::ty_trait(def, ref substs, vstore, bounds) => {
         w.write_str(&"x[");
         w.write_str((cx.ds)(def));
         w.write_char('|');
         enc_substs(w, cx, (*substs));
         enc_vstore(w, cx, vstore);
+        // The object bounds use the letters of the matching param bounds
+        if bounds.owned { w.write_char('S'); }
+        if bounds.const_ { w.write_char('K'); }
         w.write_char(']');
       }
       ty::ty_tup(ts) => {
@@ -313,7 +313,8 @@ fn simplify_ast(ii: ast::inlined_item) -> ast::inlined_item {
             stmts: stmts_sans_items,
             expr: blk.expr,
             id: blk.id,
-            rules: blk.rules
+            rules: blk.rules,
+            attrs: /*bad*/copy blk.attrs
         };
         fold::noop_fold_block(blk_sans_items, fld)
     }
@@ -510,6 +511,7 @@ impl freevar_entry: tr {
     fn tr(xcx: extended_decode_ctxt) -> freevar_entry {
         freevar_entry {
             def: self.def.tr(xcx),
+            ident: self.ident,
             span: self.span.tr(xcx),
         }
     }
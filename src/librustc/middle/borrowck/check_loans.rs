@@ -23,7 +23,7 @@ use middle::moves;
 use middle::borrowck::{Loan, bckerr, BorrowckCtxt, inherent_mutability};
 use middle::borrowck::{req_maps, root_map_key, save_and_restore};
 use middle::borrowck::{MoveError, MoveOk, MoveFromIllegalCmt};
-use middle::borrowck::{MoveWhileBorrowed};
+use middle::borrowck::{MoveWhileBorrowed, loan_paths_overlap};
 use middle::mem_categorization::{cat_arg, cat_binding, cat_comp, cat_deref};
 use middle::mem_categorization::{cat_local, cat_rvalue, cat_self};
 use middle::mem_categorization::{cat_special, cmt, gc_ptr, loan_path, lp_arg};
@@ -299,6 +299,12 @@ impl check_loan_ctxt {
             return;
         }
 
+        // Borrowing `p.x` and `p.y` both loan out `p`, but they cannot
+        // interfere with one another.
+        if !loan_paths_overlap(old_loan.borrowed_lp, new_loan.borrowed_lp) {
+            return;
+        }
+
         match (old_loan.mutbl, new_loan.mutbl) {
             (m_const, _) | (_, m_const) | (m_imm, m_imm) => {
                 /*ok*/
@@ -384,10 +390,11 @@ impl check_loan_ctxt {
         // check_for_conflicting_loans()
         for cmt.lp.each |lp| {
             self.check_for_loan_conflicting_with_assignment(
-                at, ex, cmt, *lp);
+                at, ex, cmt, *lp, *lp);
         }
 
         self.bccx.add_to_mutbl_map(cmt);
+        self.bccx.note_used_mut(cmt);
 
         // Check for and insert write guards as necessary.
         self.add_write_guards_if_necessary(cmt);
@@ -420,9 +427,14 @@ impl check_loan_ctxt {
         at: assignment_type,
         ex: @ast::expr,
         cmt: cmt,
-        lp: @loan_path) {
+        lp: @loan_path,
+        assigned_lp: @loan_path) {
 
         for self.walk_loans_of(ex.id, lp) |loan| {
+            // A loan of `lp` made for the sake of a component disjoint
+            // from the one being assigned is not affected by it.
+            if !loan_paths_overlap(loan.borrowed_lp, assigned_lp) { loop; }
+
             match loan.mutbl {
               m_const => { /*ok*/ }
               m_mutbl | m_imm => {
@@ -449,7 +461,7 @@ impl check_loan_ctxt {
         match *lp {
           lp_comp(lp_base, ck) if inherent_mutability(ck) != m_mutbl => {
             self.check_for_loan_conflicting_with_assignment(
-                at, ex, cmt, lp_base);
+                at, ex, cmt, lp_base, assigned_lp);
           }
           lp_comp(*) | lp_self | lp_local(*) | lp_arg(*) | lp_deref(*) => ()
         }
@@ -578,7 +590,7 @@ fn check_loans_in_fn(fk: visit::fn_kind, decl: ast::fn_decl, body: ast::blk,
 
         visit::fk_anon(*) | visit::fk_fn_block(*) => {
             let fty_sigil = ty::ty_closure_sigil(fty);
-            check_moves_from_captured_variables(self, id, fty_sigil);
+            check_moves_from_captured_variables(self, id);
             declared_purity = ty::determine_inherited_purity(
                 copy self.declared_purity, ty::ty_fn_purity(fty),
                 fty_sigil);
@@ -621,46 +633,41 @@ fn check_loans_in_fn(fk: visit::fn_kind, decl: ast::fn_decl, body: ast::blk,
     debug!("purity on exit=%?", copy self.declared_purity);
 
     fn check_moves_from_captured_variables(&&self: check_loan_ctxt,
-                                           id: ast::node_id,
-                                           fty_sigil: ast::Sigil)
+                                           id: ast::node_id)
     {
-        match fty_sigil {
-            ast::ManagedSigil | ast::OwnedSigil => {
-                let cap_vars = self.bccx.capture_map.get(&id);
-                for cap_vars.each |cap_var| {
-                    match cap_var.mode {
-                        moves::CapRef | moves::CapCopy => { loop; }
-                        moves::CapMove => { }
-                    }
-                    let def_id = ast_util::def_id_of_def(cap_var.def).node;
-                    let ty = ty::node_id_to_type(self.tcx(), def_id);
-                    let cmt = self.bccx.cat_def(id, cap_var.span,
-                                                ty, cap_var.def);
-                    let move_err = self.analyze_move_out_from_cmt(cmt);
-                    match move_err {
-                        MoveOk => {}
-                        MoveFromIllegalCmt(move_cmt) => {
-                            self.bccx.span_err(
-                                cap_var.span,
-                                fmt!("illegal by-move capture of %s",
-                                     self.bccx.cmt_to_str(move_cmt)));
-                        }
-                        MoveWhileBorrowed(move_cmt, loan_cmt) => {
-                            self.bccx.span_err(
-                                cap_var.span,
-                                fmt!("by-move capture of %s prohibited \
-                                      due to outstanding loan",
-                                     self.bccx.cmt_to_str(move_cmt)));
-                            self.bccx.span_note(
-                                loan_cmt.span,
-                                fmt!("loan of %s granted here",
-                                     self.bccx.cmt_to_str(loan_cmt)));
-                        }
-                    }
+        // Stack closures capture by move only if they are once
+        // closures that move out of the captured variable.
+        let cap_vars = self.bccx.capture_map.get(&id);
+        for cap_vars.each |cap_var| {
+            match cap_var.mode {
+                moves::CapRef | moves::CapCopy => { loop; }
+                moves::CapMove => { }
+            }
+            let def_id = ast_util::def_id_of_def(cap_var.def).node;
+            let ty = ty::node_id_to_type(self.tcx(), def_id);
+            let cmt = self.bccx.cat_def(id, cap_var.span,
+                                        ty, cap_var.def);
+            let move_err = self.analyze_move_out_from_cmt(cmt);
+            match move_err {
+                MoveOk => {}
+                MoveFromIllegalCmt(move_cmt) => {
+                    self.bccx.span_err(
+                        cap_var.span,
+                        fmt!("illegal by-move capture of %s",
+                             self.bccx.cmt_to_str(move_cmt)));
+                }
+                MoveWhileBorrowed(move_cmt, loan_cmt) => {
+                    self.bccx.span_err(
+                        cap_var.span,
+                        fmt!("by-move capture of %s prohibited \
+                              due to outstanding loan",
+                             self.bccx.cmt_to_str(move_cmt)));
+                    self.bccx.span_note(
+                        loan_cmt.span,
+                        fmt!("loan of %s granted here",
+                             self.bccx.cmt_to_str(loan_cmt)));
                 }
             }
-
-            ast::BorrowedSigil => {}
         }
     }
 }
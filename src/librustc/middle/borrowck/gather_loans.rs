@@ -257,7 +257,7 @@ fn req_loans_in_expr(ex: @ast::expr,
       }
 
       // see explanation attached to the `root_ub` field:
-      ast::expr_while(cond, ref body) => {
+      ast::expr_while(cond, ref body, _) => {
         // during the condition, can only root for the condition
         self.root_ub = cond.id;
         (vt.visit_expr)(cond, self, vt);
@@ -347,6 +347,12 @@ impl gather_loan_ctxt {
                region_to_str(self.tcx(), scope_r));
         let _i = indenter();
 
+        // `&mut x`, `ref mut x` and calling a `&mut self` method on `x`
+        // all count as mutating `x`
+        if req_mutbl == m_mutbl {
+            self.bccx.note_used_mut(cmt);
+        }
+
         match cmt.lp {
           // If this expression is a loanable path, we MUST take out a
           // loan.  This is somewhat non-obvious.  You might think,
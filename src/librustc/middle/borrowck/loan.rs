@@ -65,6 +65,7 @@ impl BorrowckCtxt {
         let lc = LoanContext {
             bccx: self,
             scope_region: scope_region,
+            borrowed_lp: cmt.lp.get(),
             loans: ~[]
         };
         match lc.loan(cmt, mutbl, true) {
@@ -83,6 +84,10 @@ struct LoanContext {
     // the region scope for which we must preserve the memory
     scope_region: ty::Region,
 
+    // the loan path of the data being borrowed; the loans issued are for
+    // it and its bases
+    borrowed_lp: @loan_path,
+
     // accumulated list of loans that will be required
     mut loans: ~[Loan]
 }
@@ -150,8 +155,8 @@ impl LoanContext {
             self.loan_stable_comp(cmt, cmt_base, req_mutbl, m,
                                   owns_lent_data)
           }
-          cat_comp(cmt_base, comp_tuple) |
-          cat_comp(cmt_base, comp_anon_field) => {
+          cat_comp(cmt_base, comp_tuple(_)) |
+          cat_comp(cmt_base, comp_anon_field(_)) => {
             // As above.
             self.loan_stable_comp(cmt, cmt_base, req_mutbl, m_imm,
                                   owns_lent_data)
@@ -326,6 +331,7 @@ impl LoanContext {
                 // Note: cmt.lp must be Some(_) because otherwise this
                 // loan process does not apply at all.
                 lp: cmt.lp.get(),
+                borrowed_lp: self.borrowed_lp,
                 cmt: cmt,
                 mutbl: req_mutbl
             });
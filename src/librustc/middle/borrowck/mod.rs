@@ -239,6 +239,7 @@ use core::cmp;
 use core::dvec::DVec;
 use core::io;
 use core::result::{Result, Ok, Err};
+use core::uint;
 use std::list::{List, Cons, Nil};
 use std::list;
 use std::oldmap::{HashMap, Set};
@@ -383,7 +384,15 @@ pub enum MoveError {
 pub type bckres<T> = Result<T, bckerr>;
 
 /// a complete record of a loan that was granted
-pub struct Loan {lp: @loan_path, cmt: cmt, mutbl: ast::mutability}
+///
+/// Borrowing a component loans out its base as well, so `lp` is either
+/// `borrowed_lp`, the path that was actually borrowed, or a prefix of it.
+pub struct Loan {
+    lp: @loan_path,
+    borrowed_lp: @loan_path,
+    cmt: cmt,
+    mutbl: ast::mutability
+}
 
 /// maps computed by `gather_loans` that are then used by `check_loans`
 ///
@@ -502,6 +511,24 @@ pub impl BorrowckCtxt {
         }
     }
 
+    // Records that the `mut` local or argument that `cmt` lives in, if
+    // any, is mutated, so that the unused_mut lint leaves it alone.
+    fn note_used_mut(&self, cmt: cmt) {
+        match cmt.cat {
+          cat_local(id) | cat_arg(id) => {
+            self.tcx.used_mut_nodes.insert(id, ());
+          }
+          // A `mut` field or vector is mutable wherever it lives
+          cat_comp(_, comp_field(_, m_mutbl)) |
+          cat_comp(_, comp_index(_, m_mutbl)) => (),
+          cat_stack_upvar(b) | cat_comp(b, _) | cat_discr(b, _) |
+          cat_deref(b, _, uniq_ptr) => {
+            self.note_used_mut(b);
+          }
+          _ => ()
+        }
+    }
+
     fn bckerr_to_str(&self, err: bckerr) -> ~str {
         match err.code {
             err_mutbl(req) => {
@@ -584,8 +611,39 @@ pub impl BorrowckCtxt {
     }
 
     fn loan_to_repr(&self, loan: &Loan) -> ~str {
-        fmt!("Loan(lp=%?, cmt=%s, mutbl=%?)",
-             loan.lp, self.cmt_to_repr(loan.cmt), loan.mutbl)
+        fmt!("Loan(lp=%?, borrowed_lp=%?, cmt=%s, mutbl=%?)",
+             loan.lp, loan.borrowed_lp, self.cmt_to_repr(loan.cmt),
+             loan.mutbl)
+    }
+}
+
+// Two loan paths overlap if one is a prefix of the other.  Otherwise
+// they part ways at some component, and lead to disjoint memory: `p.x`
+// and `p.y` are disjoint, but `p` and `p.x` overlap.
+pub fn loan_paths_overlap(lp1: @loan_path, lp2: @loan_path) -> bool {
+    fn depth(lp: @loan_path) -> uint {
+        match *lp {
+          lp_local(_) | lp_arg(_) | lp_self => 0u,
+          lp_deref(base, _) | lp_comp(base, _) => depth(base) + 1u
+        }
+    }
+
+    fn prefix(lp: @loan_path, n: uint) -> @loan_path {
+        let mut lp = lp;
+        for uint::range(0u, n) |_| {
+            lp = match *lp {
+              lp_deref(base, _) | lp_comp(base, _) => base,
+              lp_local(_) | lp_arg(_) | lp_self => lp
+            };
+        }
+        lp
+    }
+
+    let (d1, d2) = (depth(lp1), depth(lp2));
+    if d1 > d2 {
+        prefix(lp1, d1 - d2) == lp2
+    } else {
+        prefix(lp2, d2 - d1) == lp1
     }
 }
 
@@ -595,7 +653,7 @@ pub impl BorrowckCtxt {
 // mutable structure.
 pub fn inherent_mutability(ck: comp_kind) -> mutability {
     match ck {
-      comp_tuple | comp_anon_field | comp_variant(_) => m_imm,
-      comp_field(_, m) | comp_index(_, m)            => m
+      comp_tuple(_) | comp_anon_field(_) | comp_variant(_) => m_imm,
+      comp_field(_, m) | comp_index(_, m)                  => m
     }
 }
@@ -153,8 +153,8 @@ impl PreserveCtxt {
           }
           cat_comp(cmt_base, comp_field(*)) |
           cat_comp(cmt_base, comp_index(*)) |
-          cat_comp(cmt_base, comp_tuple) |
-          cat_comp(cmt_base, comp_anon_field) => {
+          cat_comp(cmt_base, comp_tuple(_)) |
+          cat_comp(cmt_base, comp_anon_field(_)) => {
             // Most embedded components: if the base is stable, the
             // type never changes.
             self.preserve(cmt_base)
@@ -25,7 +25,7 @@ pub fn check_crate(tcx: ty::ctxt, crate: @crate) {
         },
         visit_expr: |e: @expr, cx: ctx, v: visit::vt<ctx>| {
             match e.node {
-              expr_while(e, ref b) => {
+              expr_while(e, ref b, _) => {
                 (v.visit_expr)(e, cx, v);
                 (v.visit_block)((*b), {in_loop: true,.. cx}, v);
               }
@@ -38,7 +38,7 @@ pub fn check_crate(tcx: ty::ctxt, crate: @crate) {
               expr_fn_block(_, ref b) => {
                 (v.visit_block)((*b), {in_loop: false, can_ret: false}, v);
               }
-              expr_loop_body(@expr {node: expr_fn_block(_, ref b), _}) => {
+              expr_loop_body(@expr {node: expr_fn_block(_, ref b), _}, _) => {
                 let sigil = ty::ty_closure_sigil(ty::expr_ty(tcx, e));
                 let blk = (sigil == BorrowedSigil);
                 (v.visit_block)((*b), {in_loop: true, can_ret: blk}, v);
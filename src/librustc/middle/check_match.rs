@@ -20,14 +20,19 @@ use middle::moves;
 use util::ppaux::ty_to_str;
 
 use core::cmp;
+use core::i64;
 use core::option;
+use core::str;
 use core::uint;
 use core::vec;
 use std::oldmap::HashMap;
 use std::sort;
 use syntax::ast::*;
-use syntax::ast_util::{variant_def_ids, unguarded_pat, walk_pat};
+use syntax::ast_map;
+use syntax::ast_util::{ident_to_path, variant_def_ids, unguarded_pat};
+use syntax::ast_util::{walk_pat};
 use syntax::codemap::{span, dummy_sp, spanned};
+use syntax::parse::token::special_idents;
 use syntax::print::pprust::pat_to_str;
 use syntax::visit;
 
@@ -133,47 +138,37 @@ pub fn raw_pat(p: @pat) -> @pat {
 
 pub fn check_exhaustive(cx: @MatchCheckCtxt, sp: span, pats: ~[@pat]) {
     assert(!pats.is_empty());
-    let ext = match is_useful(cx, vec::map(pats, |p| ~[*p]), ~[wild()]) {
+    let witnesses = match is_useful(cx, vec::map(pats, |p| ~[*p]),
+                                    ~[wild()]) {
       not_useful => return, // This is good, wildcard pattern isn't reachable
-      useful_ => None,
-      useful(ty, ref ctor) => {
-        match ty::get(ty).sty {
-          ty::ty_bool => {
-            match (*ctor) {
-              val(const_bool(true)) => Some(~"true"),
-              val(const_bool(false)) => Some(~"false"),
-              _ => None
-            }
-          }
-          ty::ty_enum(id, _) => {
-              let vid = match (*ctor) { variant(id) => id,
-              _ => die!(~"check_exhaustive: non-variant ctor") };
-            match vec::find(*ty::enum_variants(cx.tcx, id),
-                                |v| v.id == vid) {
-                Some(v) => Some(cx.tcx.sess.str_of(v.name)),
-              None => die!(~"check_exhaustive: bad variant in ctor")
-            }
-          }
-          ty::ty_unboxed_vec(*) | ty::ty_evec(*) => {
-            match (*ctor) {
-              vec(n) => Some(fmt!("vectors of length %u", n)),
-              _ => None
-            }
-          }
-          _ => None
-        }
-      }
+      useful(witnesses) => witnesses
     };
-    let msg = ~"non-exhaustive patterns" + match ext {
-      Some(ref s) => ~": " + (*s) + ~" not covered",
-      None => ~""
+    let shown = uint::min(witnesses.len(), max_witnesses);
+    let strs = do vec::map(vec::view(witnesses, 0u, shown)) |w| {
+        fmt!("`%s`", pat_to_str(w[0], cx.tcx.sess.intr()))
     };
-    cx.tcx.sess.span_err(sp, msg);
+    let listed = if witnesses.len() > max_witnesses {
+        str::connect(strs, ~", ") + ~" and more"
+    } else if strs.len() == 1u {
+        copy strs[0]
+    } else {
+        str::connect(vec::init(strs), ~", ") + ~" and " + strs.last()
+    };
+    cx.tcx.sess.span_err(sp, fmt!("non-exhaustive patterns: %s not covered",
+                                  listed));
 }
 
 pub type matrix = ~[~[@pat]];
 
-pub enum useful { useful(ty::t, ctor), useful_, not_useful }
+// An input that some vector of patterns matches but no row of a matrix
+// does, as a vector of patterns made up of constructors and wildcards.
+// These are only for showing to the user, and have no node IDs.
+pub type witness = ~[@pat];
+
+pub enum useful { useful(~[witness]), not_useful }
+
+// How many of the patterns a match fails to cover are reported
+const max_witnesses: uint = 3u;
 
 #[deriving_eq]
 pub enum ctor {
@@ -197,8 +192,12 @@ pub enum ctor {
 
 // Note: is_useful doesn't work on empty types, as the paper notes.
 // So it assumes that v is non-empty.
+//
+// When `v` is useful, some inputs that show it are returned too. They
+// are only meaningful when `v` is all wildcards, as it is when checking
+// exhaustiveness; the witnesses are then the inputs the matrix misses.
 pub fn is_useful(cx: @MatchCheckCtxt, +m: matrix, +v: &[@pat]) -> useful {
-    if m.len() == 0u { return useful_; }
+    if m.len() == 0u { return useful(~[vec::from_elem(v.len(), wild())]); }
     if m[0].len() == 0u { return not_useful; }
     let real_pat = match vec::find(m, |r| r[0].id != 0) {
       Some(r) => r[0], None => v[0]
@@ -208,8 +207,8 @@ pub fn is_useful(cx: @MatchCheckCtxt, +m: matrix, +v: &[@pat]) -> useful {
 
     match pat_ctor_id(cx, v[0]) {
       None => {
-        match missing_ctor(cx, m, left_ty) {
-          None => {
+        let missing = missing_ctors(cx, m, left_ty);
+        if missing.is_empty() {
             match ty::get(left_ty).sty {
               ty::ty_bool => {
                 match is_useful_specialized(cx, m, v,
@@ -233,6 +232,9 @@ pub fn is_useful(cx: @MatchCheckCtxt, +m: matrix, +v: &[@pat]) -> useful {
                 }
                 not_useful
               }
+              ty::ty_evec(_, ty::vstore_fixed(n)) => {
+                is_useful_specialized(cx, m, v, vec(n), n, left_ty)
+              }
               ty::ty_unboxed_vec(*) | ty::ty_evec(*) => {
                 let max_len = do m.foldr(0) |r, max_len| {
                   match /*bad*/copy r[0].node {
@@ -253,15 +255,34 @@ pub fn is_useful(cx: @MatchCheckCtxt, +m: matrix, +v: &[@pat]) -> useful {
                 is_useful_specialized(cx, m, v, single, arity, left_ty)
               }
             }
-          }
-          Some(ref ctor) => {
+        } else {
             match is_useful(cx,
                             vec::filter_map(m, |r| default(cx, r)),
                             vec::tail(v)) {
-              useful_ => useful(left_ty, (/*bad*/copy *ctor)),
-              ref u => (/*bad*/copy *u)
+              useful(witnesses) => {
+                // An input starting with any of the missing constructors
+                // gets past every row that starts with a constructor
+                let firsts = if vec::all(m, |r| is_wild(cx, r[0])) {
+                    ~[wild()]
+                } else {
+                    do missing.map |ctor| {
+                        let arity = ctor_arity(cx, /*bad*/copy *ctor,
+                                               left_ty);
+                        ctor_pat(cx, ctor, left_ty,
+                                 vec::from_elem(arity, wild()))
+                    }
+                };
+                let mut result = ~[];
+                for firsts.each |first| {
+                    for witnesses.each |w| {
+                        if result.len() > max_witnesses { break; }
+                        result.push(vec::append(~[*first], *w));
+                    }
+                }
+                useful(result)
+              }
+              not_useful => not_useful
             }
-          }
         }
       }
       Some(ref v0_ctor) => {
@@ -282,8 +303,16 @@ pub fn is_useful_specialized(cx: @MatchCheckCtxt,
     let could_be_useful = is_useful(
         cx, ms, specialize(cx, v, ctor, arity, lty).get());
     match could_be_useful {
-      useful_ => useful(lty, ctor),
-      ref u => (/*bad*/copy *u)
+      useful(witnesses) => {
+        // The first `arity` patterns of each witness are the arguments
+        // of `ctor`
+        useful(do witnesses.map |w| {
+            let args = vec::slice(*w, 0u, arity);
+            vec::append(~[ctor_pat(cx, &ctor, lty, args)],
+                        vec::view(*w, arity, w.len()))
+        })
+      }
+      not_useful => not_useful
     }
 }
 
@@ -338,17 +367,19 @@ pub fn is_wild(cx: @MatchCheckCtxt, p: @pat) -> bool {
     }
 }
 
-pub fn missing_ctor(cx: @MatchCheckCtxt,
-                    m: matrix,
-                    left_ty: ty::t)
-                 -> Option<ctor> {
+// The constructors of `left_ty` that no row of `m` starts with. If there
+// are none, the rows cover every constructor between them.
+pub fn missing_ctors(cx: @MatchCheckCtxt,
+                     m: matrix,
+                     left_ty: ty::t)
+                  -> ~[ctor] {
     match ty::get(left_ty).sty {
       ty::ty_box(_) | ty::ty_uniq(_) | ty::ty_rptr(*) | ty::ty_tup(_) |
       ty::ty_rec(_) | ty::ty_struct(*) => {
         for m.each |r| {
-            if !is_wild(cx, r[0]) { return None; }
+            if !is_wild(cx, r[0]) { return ~[]; }
         }
-        return Some(single);
+        return ~[single];
       }
       ty::ty_enum(eid, _) => {
         let mut found = ~[];
@@ -359,17 +390,12 @@ pub fn missing_ctor(cx: @MatchCheckCtxt,
                 }
             }
         }
-        let variants = ty::enum_variants(cx.tcx, eid);
-        if found.len() != (*variants).len() {
-            for vec::each(*variants) |v| {
-                if !found.contains(&(variant(v.id))) {
-                    return Some(variant(v.id));
-                }
-            }
-            die!();
-        } else { None }
+        do ty::enum_variants(cx.tcx, eid).filter_mapped |v| {
+            if found.contains(&(variant(v.id))) { None }
+            else { Some(variant(v.id)) }
+        }
       }
-      ty::ty_nil => None,
+      ty::ty_nil => ~[],
       ty::ty_bool => {
         let mut true_found = false, false_found = false;
         for m.each |r| {
@@ -380,9 +406,25 @@ pub fn missing_ctor(cx: @MatchCheckCtxt,
               _ => die!(~"impossible case")
             }
         }
-        if true_found && false_found { None }
-        else if true_found { Some(val(const_bool(false))) }
-        else { Some(val(const_bool(true))) }
+        let mut missing = ~[];
+        if !true_found { missing.push(val(const_bool(true))); }
+        if !false_found { missing.push(val(const_bool(false))); }
+        missing
+      }
+      ty::ty_evec(_, ty::vstore_fixed(n)) => {
+        // Only vectors of length `n` need covering.
+        for m.each |r| {
+            match r[0].node {
+                pat_vec(ref elems, ref tail) => {
+                    if elems.len() == n ||
+                            (tail.is_some() && elems.len() <= n) {
+                        return ~[];
+                    }
+                }
+                _ => ()
+            }
+        }
+        ~[vec(n)]
       }
       ty::ty_unboxed_vec(*) | ty::ty_evec(*) => {
 
@@ -430,11 +472,19 @@ pub fn missing_ctor(cx: @MatchCheckCtxt,
             missing = Some(next);
         }
         match missing {
-          Some(k) => Some(vec(k)),
-          None => None
+          Some(k) => ~[vec(k)],
+          None => ~[]
+        }
+      }
+      // There are too many integers to try each one, so find one that
+      // isn't matched to show, if any
+      ty::ty_int(_) | ty::ty_uint(_) => {
+        match uncovered_int(cx, m, left_ty) {
+          Some(v) => ~[val(v)],
+          None => ~[single]
         }
       }
-      _ => Some(single)
+      _ => ~[single]
     }
 }
 
@@ -466,6 +516,175 @@ pub fn wild() -> @pat {
     @pat {id: 0, node: pat_wild, span: dummy_sp()}
 }
 
+// A pattern for constructor `ctor` of type `ty` applied to `args`, to be
+// shown as part of a witness
+pub fn ctor_pat(cx: @MatchCheckCtxt, ctor: &ctor, ty: ty::t,
+                +args: ~[@pat]) -> @pat {
+    let node = match /*bad*/copy ty::get(ty).sty {
+      ty::ty_enum(eid, _) => {
+        let vid = match *ctor {
+          variant(vid) => vid,
+          _ => die!(~"ctor_pat: non-variant ctor")
+        };
+        let v = vec::find(*ty::enum_variants(cx.tcx, eid),
+                          |v| v.id == vid).get();
+        let path = ident_to_path(dummy_sp(), v.name);
+        let is_struct_like = match cx.tcx.items.find(&vid.node) {
+          Some(ast_map::node_variant(ref ast_variant, _, _))
+                  if vid.crate == local_crate => {
+            match ast_variant.node.kind {
+              struct_variant_kind(_) => true,
+              _ => false
+            }
+          }
+          _ => false
+        };
+        if is_struct_like {
+            struct_pat(cx, path, vid, args)
+        } else {
+            pat_enum(path, Some(args))
+        }
+      }
+      ty::ty_struct(cid, _) => {
+        let name = match ty::item_path(cx.tcx, cid).last() {
+          ast_map::path_name(name) | ast_map::path_mod(name) => name
+        };
+        struct_pat(cx, ident_to_path(dummy_sp(), name), cid, args)
+      }
+      ty::ty_rec(fields) => {
+        let (fields, etc) = named_field_pats(fields.map(|f| f.ident), args);
+        pat_rec(fields, etc)
+      }
+      ty::ty_tup(_) => pat_tup(args),
+      ty::ty_box(_) => pat_box(args[0]),
+      ty::ty_uniq(_) => pat_uniq(args[0]),
+      ty::ty_rptr(*) => pat_region(args[0]),
+      ty::ty_unboxed_vec(*) | ty::ty_evec(*) => pat_vec(args, None),
+      ty::ty_bool | ty::ty_int(_) | ty::ty_uint(_) => {
+        let lit = match /*bad*/copy *ctor {
+          val(const_bool(b)) => lit_bool(b),
+          val(const_int(c)) if ty::type_is_char(ty) => lit_int(c, ty_char),
+          val(const_int(i)) => lit_int_unsuffixed(i),
+          val(const_uint(u)) if u <= i64::max_value as u64 => {
+            lit_int_unsuffixed(u as i64)
+          }
+          val(const_uint(u)) => lit_uint(u, ty_u64),
+          _ => return wild()
+        };
+        pat_lit(@expr {
+            id: 0,
+            callee_id: 0,
+            node: expr_lit(@spanned {node: lit, span: dummy_sp()}),
+            span: dummy_sp()
+        })
+      }
+      _ => pat_wild
+    };
+    @pat {id: 0, node: node, span: dummy_sp()}
+}
+
+// A pattern for the struct or struct-like variant `did`, tuple-like if
+// its fields have no names
+fn struct_pat(cx: @MatchCheckCtxt, path: @path, did: def_id,
+              +args: ~[@pat]) -> pat_ {
+    let fields = ty::lookup_struct_fields(cx.tcx, did);
+    if fields.is_empty() ||
+            fields[0].ident == special_idents::unnamed_field {
+        pat_enum(path, Some(args))
+    } else {
+        let (fields, etc) = named_field_pats(fields.map(|f| f.ident), args);
+        pat_struct(path, fields, etc)
+    }
+}
+
+// The field patterns that say more than `_`, and whether any were left out
+fn named_field_pats(names: ~[ident], args: ~[@pat])
+    -> (~[field_pat], bool) {
+    let mut fields = ~[], etc = false;
+    for vec::each2(names, args) |name, arg| {
+        match arg.node {
+          pat_wild => etc = true,
+          _ => fields.push(field_pat {ident: *name, pat: *arg})
+        }
+    }
+    (fields, etc)
+}
+
+// An integer of type `ty` that none of the literals and ranges starting
+// the rows of `m` match, if they match any
+fn uncovered_int(cx: @MatchCheckCtxt, m: matrix, ty: ty::t)
+    -> Option<const_val> {
+    let (min, max) = int_bounds(cx, ty);
+    let covered = do m.filter_mapped |r| {
+        match pat_ctor_id(cx, r[0]) {
+          Some(val(v)) => {
+            let k = int_key(ty, v);
+            Some((k, k))
+          }
+          Some(range(lo, hi)) => Some((int_key(ty, lo), int_key(ty, hi))),
+          _ => None
+        }
+    };
+    if covered.is_empty() { return None; }
+
+    // If some value isn't covered, either zero isn't or the values either
+    // side of some range include one that isn't
+    let mut candidates = ~[int_key(ty, const_int(0))];
+    for covered.each |&(lo, hi)| {
+        if hi < max { candidates.push(hi + 1); }
+        if lo > min { candidates.push(lo - 1); }
+    }
+    let found = do vec::find(candidates) |c| {
+        !vec::any(covered, |&(lo, hi)| lo <= *c && *c <= hi)
+    };
+    found.map(|c| int_val(ty, *c))
+}
+
+// Integer values as i64s that compare as the values do. Unsigned values
+// are shifted down by 2^63 to make room.
+fn int_key(ty: ty::t, v: const_val) -> i64 {
+    let u = match v {
+      const_int(i) => i as u64,
+      const_uint(u) => u,
+      _ => die!(~"int_key: non-integer value")
+    };
+    match ty::get(ty).sty {
+      ty::ty_uint(_) => (u ^ 1u64 << 63) as i64,
+      _ => u as i64
+    }
+}
+
+fn int_val(ty: ty::t, key: i64) -> const_val {
+    match ty::get(ty).sty {
+      ty::ty_uint(_) => const_uint(key as u64 ^ 1u64 << 63),
+      _ => const_int(key)
+    }
+}
+
+// The keys of the smallest and largest values of integer type `ty`
+fn int_bounds(cx: @MatchCheckCtxt, ty: ty::t) -> (i64, i64) {
+    fn signed_bits(t: int_ty) -> uint {
+        match t { ty_i8 => 8u, ty_i16 => 16u, ty_i32 => 32u, _ => 64u }
+    }
+    fn unsigned_bits(t: uint_ty) -> uint {
+        match t { ty_u8 => 8u, ty_u16 => 16u, ty_u32 => 32u, _ => 64u }
+    }
+
+    let targ_cfg = cx.tcx.sess.targ_cfg;
+    let (signed, bits) = match ty::get(ty).sty {
+      ty::ty_int(ty_char) => return (0, 0x10ffff),
+      ty::ty_int(ty_i) => (true, signed_bits(targ_cfg.int_type)),
+      ty::ty_int(t) => (true, signed_bits(t)),
+      ty::ty_uint(ty_u) => (false, unsigned_bits(targ_cfg.uint_type)),
+      ty::ty_uint(t) => (false, unsigned_bits(t)),
+      _ => die!(~"int_bounds: non-integer type")
+    };
+    let max = if bits == 64u { i64::max_value }
+              else { (1i64 << (bits - 1u)) - 1 };
+    if signed { (-max - 1, max) }
+    else { (i64::min_value, int_key(ty, const_uint(max as u64 * 2 + 1))) }
+}
+
 pub fn specialize(cx: @MatchCheckCtxt,
                   +r: &[@pat],
                   ctor_id: ctor,
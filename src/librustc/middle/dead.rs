@@ -0,0 +1,306 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * The `dead_code` lint, which warns about functions, constants, structs,
+ * enum variants and `priv` methods that nothing uses.
+ *
+ * An item is live if the crate exports it, if it is `main`, the `#[start]`
+ * function, a test or a language item, or if a live item refers to it,
+ * whether in its body or in its signature.  Starting from the first kind, we walk the live items
+ * until no more turn up, and whatever is left over is dead.
+ *
+ * Methods take a little care, since a method call doesn't name the method
+ * it calls.  Every method of a trait impl is taken to be live, as it can
+ * be called through the trait from anywhere, including other crates.  So
+ * is every inherent method that isn't `priv`.  A `priv` method is live if
+ * typeck resolved some call to it, which we find in the method map.
+ *
+ * This runs on the expanded crate, so anything named inside a macro
+ * invocation counts as used like anything else.
+ */
+
+use core::prelude::*;
+
+use middle::lint::dead_code;
+use middle::resolve;
+use middle::ty;
+use middle::typeck::{method_map, method_map_entry, method_static};
+
+use core::dvec::DVec;
+use core::str;
+use std::oldmap::HashMap;
+use syntax::ast::*;
+use syntax::ast_map;
+use syntax::ast_util::def_id_of_def;
+use syntax::attr;
+use syntax::codemap::span;
+use syntax::visit;
+
+struct Context {
+    tcx: ty::ctxt,
+    method_map: method_map,
+    // the items found to be live so far
+    live: HashMap<node_id, ()>,
+    // live items that haven't been walked yet
+    worklist: DVec<node_id>
+}
+
+impl Context {
+    fn mark(&self, id: node_id) {
+        if !self.live.contains_key_ref(&id) {
+            self.live.insert(id, ());
+            self.worklist.push(id);
+        }
+    }
+
+    fn mark_def(&self, def: def) {
+        match def {
+            def_prim_ty(_) => {}
+            _ => {
+                let did = def_id_of_def(def);
+                if did.crate == local_crate { self.mark(did.node); }
+            }
+        }
+    }
+
+    fn mark_method_call(&self, id: node_id) {
+        match self.method_map.find(&id) {
+            Some(method_map_entry {origin: method_static(did), _})
+                    if did.crate == local_crate => {
+                self.mark(did.node);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn check_crate(tcx: ty::ctxt,
+                   method_map: method_map,
+                   exp_map2: resolve::ExportMap2,
+                   crate: @crate) {
+    let cx = @Context {
+        tcx: tcx,
+        method_map: method_map,
+        live: HashMap(),
+        worklist: DVec()
+    };
+
+    mark_exports(cx, exp_map2, crate_node_id);
+    match tcx.sess.main_fn {
+        Some((id, _)) => cx.mark(id),
+        None => {}
+    }
+    for crate.node.module.items.each |item| {
+        // With --test, `main` is no longer the main function, but it is
+        // still the program's entry point
+        match item.node {
+            item_fn(*) if tcx.sess.str_of(item.ident) == ~"main" => {
+                cx.mark(item.id);
+            }
+            _ => {}
+        }
+    }
+    visit::visit_crate(*crate, cx, visit::mk_vt(@visit::Visitor {
+        visit_item: mark_roots_in_item,
+        .. *visit::default_visitor()
+    }));
+
+    let walker = visit::mk_vt(@visit::Visitor {
+        // A nested item is live only if something live uses it
+        visit_item: |_i, _cx, _v| { },
+        visit_expr: walk_expr,
+        visit_pat: walk_pat,
+        visit_ty: walk_ty,
+        .. *visit::default_visitor()
+    });
+    while cx.worklist.len() > 0u {
+        walk_live_node(cx, cx.worklist.pop(), walker);
+    }
+
+    visit::visit_crate(*crate, cx, visit::mk_vt(@visit::Visitor {
+        visit_item: |i, cx, v| {
+            check_item(cx, i);
+            visit::visit_item(i, cx, v);
+        },
+        .. *visit::default_visitor()
+    }));
+
+    tcx.sess.abort_if_errors();
+}
+
+// Marks what a module exports, and what the modules it exports export.
+fn mark_exports(cx: @Context, exp_map2: resolve::ExportMap2,
+                mod_id: node_id) {
+    let exports = match exp_map2.find(&mod_id) {
+        Some(exports) => exports,
+        None => return
+    };
+    for exports.each |export| {
+        let did = export.def_id;
+        if did.crate != local_crate || cx.live.contains_key_ref(&did.node) {
+            loop;
+        }
+        cx.mark(did.node);
+        match cx.tcx.items.find(&did.node) {
+            Some(ast_map::node_item(@item {node: item_mod(_), _}, _)) => {
+                mark_exports(cx, exp_map2, did.node);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn mark_roots_in_item(item: @item, &&cx: @Context,
+                      v: visit::vt<@Context>) {
+    let roots = ["test", "bench", "lang", "rt", "no_mangle", "main", "start"];
+    for roots.each |name| {
+        if attr::attrs_contains_name(item.attrs, *name) {
+            cx.mark(item.id);
+        }
+    }
+
+    match item.node {
+        item_impl(_, opt_trait, _, ref methods) => {
+            for methods.each |method| {
+                let is_priv = match method.vis {
+                    private => true,
+                    inherited => item.vis == private,
+                    public => false
+                };
+                if opt_trait.is_some() || !is_priv {
+                    cx.mark(method.id);
+                }
+            }
+        }
+        item_trait(_, _, ref methods) => {
+            for methods.each |method| {
+                match *method {
+                    provided(m) => cx.mark(m.id),
+                    required(_) => {}
+                }
+            }
+        }
+        item_struct(struct_def, _) => {
+            // A destructor runs whenever its struct is dropped
+            for struct_def.dtor.each |dtor| {
+                cx.mark(dtor.node.id);
+            }
+        }
+        _ => {}
+    }
+
+    visit::visit_item(item, cx, v);
+}
+
+fn walk_live_node(cx: @Context, id: node_id, v: visit::vt<@Context>) {
+    match cx.tcx.items.find(&id) {
+        Some(ast_map::node_item(item, _)) => {
+            match item.node {
+                // Their contents are live only if used, or exported
+                item_mod(_) | item_foreign_mod(_) | item_impl(*) => {}
+                _ => visit::visit_item(item, cx, v)
+            }
+        }
+        Some(ast_map::node_method(method, _, _)) => {
+            visit::visit_method_helper(method, cx, v);
+        }
+        Some(ast_map::node_trait_method(method, _, _)) => {
+            match *method {
+                provided(m) => visit::visit_method_helper(m, cx, v),
+                required(_) => {}
+            }
+        }
+        Some(ast_map::node_dtor(_, dtor, _, _)) => {
+            (v.visit_block)(dtor.node.body, cx, v);
+        }
+        Some(ast_map::node_variant(_, enum_item, _)) => {
+            cx.mark(enum_item.id);
+        }
+        Some(ast_map::node_struct_ctor(_, struct_item, _)) => {
+            cx.mark(struct_item.id);
+        }
+        _ => {}
+    }
+}
+
+fn walk_expr(expr: @expr, &&cx: @Context, v: visit::vt<@Context>) {
+    for cx.tcx.def_map.find(&expr.id).each |def| {
+        cx.mark_def(*def);
+    }
+    cx.mark_method_call(expr.id);
+    cx.mark_method_call(expr.callee_id);
+    visit::visit_expr(expr, cx, v);
+}
+
+fn walk_pat(pat: @pat, &&cx: @Context, v: visit::vt<@Context>) {
+    for cx.tcx.def_map.find(&pat.id).each |def| {
+        cx.mark_def(*def);
+    }
+    visit::visit_pat(pat, cx, v);
+}
+
+fn walk_ty(ty: @Ty, &&cx: @Context, v: visit::vt<@Context>) {
+    match ty.node {
+        ty_path(_, path_id) => {
+            for cx.tcx.def_map.find(&path_id).each |def| {
+                cx.mark_def(*def);
+            }
+        }
+        _ => {}
+    }
+    visit::visit_ty(ty, cx, v);
+}
+
+fn check_item(cx: @Context, item: @item) {
+    let is_live = |id: node_id| cx.live.contains_key_ref(&id);
+    match item.node {
+        item_fn(*) if !is_live(item.id) => {
+            warn_dead(cx, item.id, item.span, "function", item.ident);
+        }
+        item_const(*) if !is_live(item.id) => {
+            warn_dead(cx, item.id, item.span, "constant", item.ident);
+        }
+        item_struct(*) if !is_live(item.id) => {
+            warn_dead(cx, item.id, item.span, "struct", item.ident);
+        }
+        item_enum(*) if !is_live(item.id) => {
+            warn_dead(cx, item.id, item.span, "enum", item.ident);
+        }
+        item_enum(ref enum_def, _) => {
+            for enum_def.variants.each |variant| {
+                if !is_live(variant.node.id) {
+                    warn_dead(cx, variant.node.id, variant.span, "variant",
+                              variant.node.name);
+                }
+            }
+        }
+        item_impl(_, None, _, ref methods) => {
+            for methods.each |method| {
+                if !is_live(method.id) {
+                    warn_dead(cx, method.id, method.span, "method",
+                              method.ident);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// `id` is the node whose lint attributes apply: the method or variant
+// itself, not its enclosing item
+fn warn_dead(cx: @Context, id: node_id, sp: span, kind: &str,
+             name: ident) {
+    let name = cx.tcx.sess.str_of(name);
+    // As with variables, a leading underscore says it's unused on purpose
+    if str::starts_with(name, "_") { return; }
+    cx.tcx.sess.span_lint(dead_code, id, id, sp,
+                          fmt!("%s `%s` is never used", kind, name));
+}
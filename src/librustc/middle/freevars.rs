@@ -28,8 +28,10 @@ use syntax::{ast, ast_util, visit};
 #[auto_encode]
 #[auto_decode]
 pub struct freevar_entry {
-    def: ast::def, //< The variable being accessed free.
-    span: span     //< First span where it is accessed (there can be multiple)
+    def: ast::def,     //< The variable being accessed free.
+    ident: ast::ident, //< The name it is accessed by.
+    span: span         //< First span where it is accessed (there can be
+                       //< multiple)
 }
 pub type freevar_info = @~[@freevar_entry];
 pub type freevar_map = HashMap<ast::node_id, freevar_info>;
@@ -54,7 +56,7 @@ fn collect_freevars(def_map: resolve::DefMap, blk: ast::blk)
               ast::expr_fn_block(*) => {
                 visit::visit_expr(expr, depth + 1, v);
               }
-              ast::expr_path(*) => {
+              ast::expr_path(path) => {
                   let mut i = 0;
                   match def_map.find(&expr.id) {
                     None => die!(~"path not found"),
@@ -72,6 +74,7 @@ fn collect_freevars(def_map: resolve::DefMap, blk: ast::blk)
                         if !seen.contains_key_ref(&dnum) {
                             refs.push(@freevar_entry {
                                 def: def,
+                                ident: path.idents.last(),
                                 span: expr.span,
                             });
                             seen.insert(dnum, ());
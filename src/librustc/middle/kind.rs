@@ -409,7 +409,7 @@ pub fn check_cast_for_escaping_regions(
     // worries.
     let target_ty = ty::expr_ty(cx.tcx, target);
     let target_substs = match ty::get(target_ty).sty {
-      ty::ty_trait(_, ref substs, _) => {(/*bad*/copy *substs)}
+      ty::ty_trait(_, ref substs, _, _) => {(/*bad*/copy *substs)}
       _ => { return; /* not a cast to a trait */ }
     };
 
@@ -444,20 +444,30 @@ pub fn check_cast_for_escaping_regions(
     }
 }
 
-/// Ensures that values placed into a ~Trait are copyable and sendable.
+/// Ensures that values placed into a ~Trait are copyable, and that values
+/// placed into any trait object have the kinds that its bounds promise.
 pub fn check_kind_bounds_of_cast(cx: ctx, source: @expr, target: @expr) {
     let target_ty = ty::expr_ty(cx.tcx, target);
     match ty::get(target_ty).sty {
-        ty::ty_trait(_, _, ty::vstore_uniq) => {
+        ty::ty_trait(_, _, vstore, bounds) => {
             let source_ty = ty::expr_ty(cx.tcx, source);
             let source_kind = ty::type_kind(cx.tcx, source_ty);
-            if !ty::kind_can_be_copied(source_kind) {
+            if vstore == ty::vstore_uniq &&
+                    !ty::kind_can_be_copied(source_kind) {
                 cx.tcx.sess.span_err(target.span,
                     ~"uniquely-owned trait objects must be copyable");
             }
-            if !ty::kind_can_be_sent(source_kind) {
+            if bounds.owned && !ty::kind_can_be_sent(source_kind) {
                 cx.tcx.sess.span_err(target.span,
-                    ~"uniquely-owned trait objects must be sendable");
+                    fmt!("cannot pack type `%s`, which is not owned, into \
+                          an object bounded by `Owned`",
+                         ty_to_str(cx.tcx, source_ty)));
+            }
+            if bounds.const_ && !ty::kind_is_const(source_kind) {
+                cx.tcx.sess.span_err(target.span,
+                    fmt!("cannot pack type `%s`, which is not const, into \
+                          an object bounded by `Const`",
+                         ty_to_str(cx.tcx, source_ty)));
             }
         }
         _ => {} // Nothing to do.
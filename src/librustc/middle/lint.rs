@@ -88,6 +88,10 @@ pub enum lint {
 
     legacy_modes,
 
+    dead_code,
+    unused_mut,
+    unnecessary_allocation,
+
     // FIXME(#3266)--make liveness warnings lintable
     // unused_variable,
     // dead_assignment
@@ -214,13 +218,28 @@ pub fn get_lint_dict() -> lint_dict {
         (~"default_methods",
          @{lint: default_methods,
            desc: "allow default methods",
-           default: deny}),
+           default: allow}),
 
         (~"deprecated_self",
          @{lint: deprecated_self,
            desc: "warn about deprecated uses of `self`",
            default: warn}),
 
+        (~"dead_code",
+         @{lint: dead_code,
+           desc: "detect private items that are never used",
+           default: warn}),
+
+        (~"unused_mut",
+         @{lint: unused_mut,
+           desc: "detect mut variables which don't need to be mutable",
+           default: warn}),
+
+        (~"unnecessary_allocation",
+         @{lint: unnecessary_allocation,
+           desc: "detect boxes which are only ever borrowed",
+           default: allow}),
+
         /* FIXME(#3266)--make liveness warnings lintable
         (~"unused_variable",
          @{lint: unused_variable,
@@ -389,10 +408,45 @@ fn build_settings_item(i: @ast::item, &&cx: ctxt, v: visit::vt<ctxt>) {
         if !cx.is_default {
             cx.sess.lint_settings.settings_map.insert(i.id, cx.curr);
         }
+        // Lints about a single method or variant, like dead_code, look up
+        // its own settings
+        match i.node {
+            ast::item_impl(_, _, _, ref methods) => {
+                for methods.each |m| {
+                    build_settings_node(cx, m.id, /*bad*/copy m.attrs);
+                }
+            }
+            ast::item_enum(ref enum_def, _) => {
+                for enum_def.variants.each |variant| {
+                    build_settings_node(cx, variant.node.id,
+                                        /*bad*/copy variant.node.attrs);
+                }
+            }
+            _ => {}
+        }
         visit::visit_item(i, cx, v);
     }
 }
 
+// A block's inner attributes apply to the rest of the block
+fn build_settings_block(b: ast::blk, &&cx: ctxt, v: visit::vt<ctxt>) {
+    do cx.with_lint_attrs(/*bad*/copy b.node.attrs) |cx| {
+        if !cx.is_default && !b.node.attrs.is_empty() {
+            cx.sess.lint_settings.settings_map.insert(b.node.id, cx.curr);
+        }
+        visit::visit_block(b, cx, v);
+    }
+}
+
+fn build_settings_node(cx: ctxt, id: ast::node_id,
+                       attrs: ~[ast::attribute]) {
+    do cx.with_lint_attrs(attrs) |cx| {
+        if !cx.is_default {
+            cx.sess.lint_settings.settings_map.insert(id, cx.curr);
+        }
+    }
+}
+
 pub fn build_settings_crate(sess: session::Session, crate: @ast::crate) {
     let cx = ctxt_({dict: get_lint_dict(),
                     curr: oldsmallintmap::mk(),
@@ -420,6 +474,7 @@ pub fn build_settings_crate(sess: session::Session, crate: @ast::crate) {
 
         let visit = visit::mk_vt(@visit::Visitor {
             visit_item: build_settings_item,
+            visit_block: build_settings_block,
             .. *visit::default_visitor()
         });
         visit::visit_crate(*crate, cx, visit);
@@ -439,6 +494,8 @@ fn check_item(i: @ast::item, cx: ty::ctxt) {
     check_item_type_limits(cx, i);
     check_item_default_methods(cx, i);
     check_item_deprecated_self(cx, i);
+    check_item_unused_mut(cx, i);
+    check_item_unnecessary_allocation(cx, i);
 }
 
 // Take a visitor, and modify it so that it will not proceed past subitems.
@@ -454,7 +511,7 @@ fn check_item_while_true(cx: ty::ctxt, it: @ast::item) {
         visit::mk_simple_visitor(@visit::SimpleVisitor {
             visit_expr: |e: @ast::expr| {
                 match e.node {
-                    ast::expr_while(cond, _) => {
+                    ast::expr_while(cond, _, _) => {
                         match cond.node {
                             ast::expr_lit(@codemap::spanned {
                                 node: ast::lit_bool(true), _}) =>
@@ -825,6 +882,124 @@ fn check_item_path_statement(cx: ty::ctxt, it: @ast::item) {
     visit::visit_item(it, (), visit);
 }
 
+fn check_item_unused_mut(tcx: ty::ctxt, it: @ast::item) {
+    // Borrowck has recorded every `mut` binding that is assigned to or
+    // mutably borrowed; complain about the rest. `settings_id` is the
+    // innermost item, method or block with lint attributes of its own.
+    let check_pat = fn@(p: @ast::pat, settings_id: ast::node_id) {
+        do pat_bindings(tcx.def_map, p) |_bm, id, sp, path| {
+            // Whether a binding in a macro's expansion needs to be
+            // mutable depends on how the macro is used
+            if sp.expn_info.is_none() &&
+                    !tcx.used_mut_nodes.contains_key_ref(&id) {
+                tcx.sess.span_lint(
+                    unused_mut, id, settings_id, sp,
+                    fmt!("variable `%s` does not need to be mutable",
+                         tcx.sess.str_of(path_to_ident(path))));
+            }
+        }
+    };
+    let settings_of = fn@(id: ast::node_id, outer: ast::node_id)
+                      -> ast::node_id {
+        if tcx.sess.lint_settings.settings_map.contains_key_ref(&id) {
+            id
+        } else {
+            outer
+        }
+    };
+
+    let visit = item_stopping_visitor(visit::mk_vt(@visit::Visitor {
+        visit_local: |l: @ast::local, settings_id: ast::node_id, v| {
+            if l.node.is_mutbl { check_pat(l.node.pat, settings_id); }
+            visit::visit_local(l, settings_id, v);
+        },
+        visit_block: |b: ast::blk, settings_id: ast::node_id, v| {
+            visit::visit_block(b, settings_of(b.node.id, settings_id), v);
+        },
+        visit_fn: |fk, decl: ast::fn_decl, b, sp, id,
+                   settings_id: ast::node_id, v| {
+            let settings_id = settings_of(id, settings_id);
+            for decl.inputs.each |arg| {
+                if arg.is_mutbl { check_pat(arg.pat, settings_id); }
+            }
+            visit::visit_fn(fk, decl, b, sp, id, settings_id, v);
+        },
+        .. *visit::default_visitor()
+    }));
+    visit::visit_item(it, it.id, visit);
+}
+
+fn check_item_unnecessary_allocation(tcx: ty::ctxt, it: @ast::item) {
+    // If `e` allocates a box, what to write instead when the box is only
+    // borrowed. A vector or string is borrowed as it is; anything else is
+    // borrowed after dereferencing the box.
+    fn borrowed_form(e: @ast::expr, derefd: bool) -> Option<~str> {
+        match e.node {
+            ast::expr_vstore(inner, ast::expr_vstore_uniq) |
+            ast::expr_vstore(inner, ast::expr_vstore_box) |
+            ast::expr_vstore(inner, ast::expr_vstore_mut_box)
+                    if !derefd => {
+                match inner.node {
+                    ast::expr_lit(@codemap::spanned {
+                        node: ast::lit_str(_), _
+                    }) => Some(~"a string literal"),
+                    _ => Some(~"`&[...]`")
+                }
+            }
+            ast::expr_unary(ast::box(_), _) if derefd => {
+                Some(~"`&` instead of `@`")
+            }
+            ast::expr_unary(ast::uniq(_), _) if derefd => {
+                Some(~"`&` instead of `~`")
+            }
+            _ => None
+        }
+    }
+
+    let report = fn@(e: @ast::expr, derefd: bool) {
+        for borrowed_form(e, derefd).each |form| {
+            tcx.sess.span_lint(
+                unnecessary_allocation, e.id, it.id, e.span,
+                fmt!("unnecessary allocation, the value is only \
+                      borrowed; use %s", *form));
+        }
+    };
+
+    // Typeck records the borrows that it inserts as adjustments, so a box
+    // that is adjusted to a borrowed pointer is used for nothing else
+    let visit = item_stopping_visitor(
+        visit::mk_simple_visitor(@visit::SimpleVisitor {
+            visit_expr: |e: @ast::expr| {
+                match tcx.adjustments.find(&e.id) {
+                    Some(@ty::AutoAdjustment {
+                        autoderefs: 0, autoref: Some(ref autoref)
+                    }) => {
+                        // Only borrowing a vector or string as a slice
+                        // makes the box unnecessary
+                        match autoref.kind {
+                            ty::AutoBorrowVec | ty::AutoBorrowVecRef => {
+                                report(e, false)
+                            }
+                            _ => ()
+                        }
+                    }
+                    Some(@ty::AutoAdjustment {autoref: Some(_), _}) => {
+                        report(e, true)
+                    }
+                    _ => ()
+                }
+                match e.node {
+                    ast::expr_addr_of(_, @ast::expr {
+                        node: ast::expr_unary(ast::deref, inner), _
+                    }) => report(inner, true),
+                    _ => ()
+                }
+            },
+            .. *visit::default_simple_visitor()
+        }));
+    visit::visit_item(it, (), visit);
+}
+
 fn check_item_non_camel_case_types(cx: ty::ctxt, it: @ast::item) {
     fn is_camel_case(cx: ty::ctxt, ident: ast::ident) -> bool {
         let ident = cx.sess.str_of(ident);
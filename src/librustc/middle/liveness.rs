@@ -105,6 +105,7 @@
 
 use core::prelude::*;
 
+use middle::freevars;
 use middle::pat_util;
 use middle::ty;
 use middle::typeck;
@@ -279,20 +280,10 @@ struct LocalInfo {
 enum VarKind {
     Arg(node_id, ident, rmode),
     Local(LocalInfo),
+    Upvar(node_id, ident),
     ImplicitRet
 }
 
-fn relevant_def(def: def) -> Option<node_id> {
-    match def {
-      def_binding(nid, _) |
-      def_arg(nid, _, _) |
-      def_local(nid, _) |
-      def_self(nid, _) => Some(nid),
-
-      _ => None
-    }
-}
-
 struct IrMaps {
     tcx: ty::ctxt,
     method_map: typeck::method_map,
@@ -356,7 +347,8 @@ impl IrMaps {
 
         match vk {
             Local(LocalInfo {id:node_id, _}) |
-            Arg(node_id, _, _) => {
+            Arg(node_id, _, _) |
+            Upvar(node_id, _) => {
                 self.variable_map.insert(node_id, v);
             }
             ImplicitRet => {}
@@ -380,7 +372,8 @@ impl IrMaps {
     fn variable_name(var: Variable) -> ~str {
         match copy self.var_kinds[*var] {
             Local(LocalInfo {ident: nm, _}) |
-            Arg(_, nm, _) => self.tcx.sess.str_of(nm),
+            Arg(_, nm, _) |
+            Upvar(_, nm) => self.tcx.sess.str_of(nm),
             ImplicitRet => ~"<implicit-ret>"
         }
     }
@@ -422,11 +415,26 @@ impl IrMaps {
             (*v).push(id);
           }
           Arg(_, _, by_ref) |
-          Arg(_, _, by_val) | ImplicitRet => {
+          Arg(_, _, by_val) | Upvar(*) | ImplicitRet => {
             debug!("--but it is not owned");
           }
         }
     }
+
+    fn relevant_def(def: def) -> Option<node_id> {
+        match def {
+          def_binding(nid, _) |
+          def_arg(nid, _, _) |
+          def_local(nid, _) |
+          def_self(nid, _) => Some(nid),
+
+          // Only the upvars of once closures are registered
+          def_upvar(nid, _, _, _)
+              if self.variable_map.contains_key_ref(&nid) => Some(nid),
+
+          _ => None
+        }
+    }
 }
 
 fn visit_fn(fk: visit::fn_kind, decl: fn_decl, body: blk,
@@ -478,6 +486,22 @@ fn visit_fn(fk: visit::fn_kind, decl: fn_decl, body: blk,
         fk_item_fn(*) | fk_anon(*) | fk_fn_block(*) => {}
     }
 
+    // A once closure can move out of its upvars, so track them like
+    // locals.  They start out initialized and are never live on exit.
+    match fk {
+        fk_anon(*) | fk_fn_block(*) => {
+            let fty = ty::node_id_to_type(self.tcx, id);
+            if ty::ty_closure_onceness(fty) == Once {
+                for freevars::get_freevars(self.tcx, id).each |fv| {
+                    let fv_id = ast_util::def_id_of_def(fv.def).node;
+                    debug!("adding upvar %d", fv_id);
+                    fn_maps.add_variable(Upvar(fv_id, fv.ident));
+                }
+            }
+        }
+        fk_item_fn(*) | fk_method(*) | fk_dtor(*) => {}
+    }
+
     // gather up the various local variables, significant expressions,
     // and so forth:
     visit::visit_fn(fk, decl, body, sp, id, fn_maps, v);
@@ -554,7 +578,7 @@ fn visit_expr(expr: @expr, &&self: @IrMaps, vt: vt<@IrMaps>) {
       expr_path(_) => {
         let def = self.tcx.def_map.get(&expr.id);
         debug!("expr %d: path that leads to %?", expr.id, def);
-        if relevant_def(def).is_some() {
+        if self.relevant_def(def).is_some() {
             self.add_live_node_for_node(expr.id, ExprNode(expr.span));
         }
         visit::visit_expr(expr, self, vt);
@@ -572,7 +596,7 @@ fn visit_expr(expr: @expr, &&self: @IrMaps, vt: vt<@IrMaps>) {
         let cvs = self.capture_map.get(&expr.id);
         let mut call_caps = ~[];
         for cvs.each |cv| {
-            match relevant_def(cv.def) {
+            match self.relevant_def(cv.def) {
               Some(rv) => {
                 let cv_ln = self.add_live_node(FreeVarNode(cv.span));
                 let is_move = match cv.mode {
@@ -703,7 +727,7 @@ impl Liveness {
         match expr.node {
           expr_path(_) => {
             let def = self.tcx.def_map.get(&expr.id);
-            relevant_def(def).map(
+            self.ir.relevant_def(def).map(
                 |rdef| self.variable(*rdef, expr.span)
             )
           }
@@ -719,7 +743,7 @@ impl Liveness {
                              span: span) -> Option<Variable> {
         match self.tcx.def_map.find(&node_id) {
           Some(def) => {
-            relevant_def(def).map(
+            self.ir.relevant_def(def).map(
                 |rdef| self.variable(*rdef, span)
             )
           }
@@ -1150,7 +1174,7 @@ impl Liveness {
             self.propagate_through_expr(cond, ln)
           }
 
-          expr_while(cond, ref blk) => {
+          expr_while(cond, ref blk, _) => {
             self.propagate_through_loop(expr, Some(cond), (*blk), succ)
           }
 
@@ -1328,7 +1352,7 @@ impl Liveness {
           expr_assert(e) |
           expr_addr_of(_, e) |
           expr_copy(e) |
-          expr_loop_body(e) |
+          expr_loop_body(e, _) |
           expr_do_body(e) |
           expr_cast(e, _) |
           expr_unary(_, e) |
@@ -1425,7 +1449,7 @@ impl Liveness {
 
     fn access_path(expr: @expr, succ: LiveNode, acc: uint) -> LiveNode {
         let def = self.tcx.def_map.get(&expr.id);
-        match relevant_def(def) {
+        match self.ir.relevant_def(def) {
           Some(nid) => {
             let ln = self.live_node(expr.id, expr.span);
             if acc != 0u {
@@ -1599,8 +1623,14 @@ fn check_expr(expr: @expr, &&self: @Liveness, vt: vt<@Liveness>) {
         visit::visit_expr(expr, self, vt);
       }
 
+      expr_call(f, _, _) => {
+        self.check_once_call(f);
+
+        visit::visit_expr(expr, self, vt);
+      }
+
       // no correctness conditions related to liveness
-      expr_call(*) | expr_method_call(*) |
+      expr_method_call(*) |
       expr_if(*) | expr_match(*) |
       expr_while(*) | expr_loop(*) |
       expr_index(*) | expr_field(*) | expr_vstore(*) |
@@ -1676,6 +1706,35 @@ impl @Liveness {
         }
     }
 
+    fn check_once_call(callee: @expr) {
+        /*!
+         *
+         * Checks that a once closure stored in a variable is not used
+         * again after `callee` calls it.  Being called from the exit
+         * node is fine: that is just a by-ref argument the caller owns.
+         */
+
+        match ty::get(ty::expr_ty(self.tcx, callee)).sty {
+          ty::ty_closure(ref f) if f.onceness == Once => {}
+          _ => return
+        }
+        for self.variable_from_path(callee).each |var| {
+            let ln = self.live_node(callee.id, callee.span);
+            match self.live_on_exit(ln, *var) {
+              None | Some(ExitNode) => {}
+              Some(lnk) => {
+                self.report_illegal_read(
+                    callee.span, lnk, *var, MovedValue);
+                self.tcx.sess.span_note(
+                    callee.span,
+                    fmt!("`%s` was called here, and a once function \
+                          can only be called once",
+                         self.ir.variable_name(*var)));
+              }
+            }
+        }
+    }
+
     fn consider_last_use(expr: @expr, ln: LiveNode, var: Variable) {
         debug!("consider_last_use(expr.id=%?, ln=%s, var=%s)",
                expr.id, ln.to_str(), var.to_str());
@@ -1699,7 +1758,7 @@ impl @Liveness {
                 self.warn_about_dead_assign(expr.span, ln, var);
               }
               def => {
-                match relevant_def(def) {
+                match self.ir.relevant_def(def) {
                   Some(nid) => {
                     let ln = self.live_node(expr.id, expr.span);
                     let var = self.variable(nid, expr.span);
@@ -1765,7 +1824,7 @@ impl @Liveness {
                           copy or move mode", self.tcx.sess.str_of(name)));
                 return;
               }
-              Local(*) | ImplicitRet => {
+              Local(*) | Upvar(*) | ImplicitRet => {
                 self.tcx.sess.span_bug(
                     move_expr.span,
                     fmt!("illegal reader (%?) for `%?`",
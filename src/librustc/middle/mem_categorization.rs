@@ -89,9 +89,9 @@ pub enum ptr_kind {
 // structure accessible without a dereference":
 #[deriving_eq]
 pub enum comp_kind {
-    comp_tuple,                  // elt in a tuple
-    comp_anon_field,             // anonymous field (in e.g.
-                                 // struct Foo(int, int);
+    comp_tuple(uint),            // elt in a tuple, by position
+    comp_anon_field(uint),       // anonymous field (in e.g.
+                                 // struct Foo(int, int), by position
     comp_variant(ast::def_id),   // internals to a variant of given enum
     comp_field(ast::ident,       // name of field
                ast::mutability), // declared mutability of field
@@ -183,7 +183,7 @@ pub fn opt_deref_kind(t: ty::t) -> Option<deref_kind> {
         }
 
         ty::ty_struct(_, _) => {
-            Some(deref_comp(comp_anon_field))
+            Some(deref_comp(comp_anon_field(0u)))
         }
 
         ty::ty_evec(mt, ty::vstore_fixed(_)) => {
@@ -463,11 +463,36 @@ pub impl &mem_categorization_ctxt {
             }
           }
 
-          ast::def_upvar(_, inner, fn_node_id, _) => {
+          ast::def_upvar(upvar_id, inner, fn_node_id, _) => {
             let ty = ty::node_id_to_type(self.tcx, fn_node_id);
             let sigil = ty::ty_closure_sigil(ty);
-            match sigil {
-                ast::BorrowedSigil => {
+            let onceness = ty::ty_closure_onceness(ty);
+            match (sigil, onceness) {
+                (ast::BorrowedSigil, ast::Once) => {
+                    // A once stack closure runs at most once, while the
+                    // enclosing frame waits, so its upvars are as good as
+                    // the variables they refer to.
+                    let upcmt = self.cat_def(id, span, expr_ty, *inner);
+                    @cmt_ {
+                        id:id,
+                        span:span,
+                        .. *upcmt
+                    }
+                }
+                (ast::OwnedSigil, ast::Once) |
+                (ast::ManagedSigil, ast::Once) => {
+                    // A once heap closure owns its environment outright,
+                    // so upvars can be moved out of, much like locals.
+                    @cmt_ {
+                        id:id,
+                        span:span,
+                        cat:cat_local(upvar_id),
+                        lp:Some(@lp_local(upvar_id)),
+                        mutbl:m_imm,
+                        ty:expr_ty
+                    }
+                }
+                (ast::BorrowedSigil, ast::Many) => {
                     let upcmt = self.cat_def(id, span, expr_ty, *inner);
                     @cmt_ {
                         id:id,
@@ -478,7 +503,8 @@ pub impl &mem_categorization_ctxt {
                         ty:upcmt.ty
                     }
                 }
-                ast::OwnedSigil | ast::ManagedSigil => {
+                (ast::OwnedSigil, ast::Many) |
+                (ast::ManagedSigil, ast::Many) => {
                     // FIXME #2152 allow mutation of moved upvars
                     @cmt_ {
                         id:id,
@@ -741,23 +767,24 @@ pub impl &mem_categorization_ctxt {
         }
     }
 
-    fn cat_tuple_elt<N: ast_node>(elt: N, cmt: cmt) -> cmt {
+    fn cat_tuple_elt<N: ast_node>(elt: N, cmt: cmt, idx: uint) -> cmt {
         @cmt_ {
             id: elt.id(),
             span: elt.span(),
-            cat: cat_comp(cmt, comp_tuple),
-            lp: cmt.lp.map(|l| @lp_comp(*l, comp_tuple) ),
+            cat: cat_comp(cmt, comp_tuple(idx)),
+            lp: cmt.lp.map(|l| @lp_comp(*l, comp_tuple(idx)) ),
             mutbl: cmt.mutbl, // imm iff in an immutable context
             ty: self.tcx.ty(elt)
         }
     }
 
-    fn cat_anon_struct_field<N: ast_node>(elt: N, cmt: cmt) -> cmt {
+    fn cat_anon_struct_field<N: ast_node>(elt: N, cmt: cmt,
+                                          idx: uint) -> cmt {
         @cmt_ {
             id: elt.id(),
             span: elt.span(),
-            cat: cat_comp(cmt, comp_anon_field),
-            lp: cmt.lp.map(|l| @lp_comp(*l, comp_anon_field)),
+            cat: cat_comp(cmt, comp_anon_field(idx)),
+            lp: cmt.lp.map(|l| @lp_comp(*l, comp_anon_field(idx))),
             mutbl: cmt.mutbl, // imm iff in an immutable context
             ty: self.tcx.ty(elt)
         }
@@ -836,9 +863,9 @@ pub impl &mem_categorization_ctxt {
                     }
                 }
                 Some(ast::def_struct(*)) => {
-                    for subpats.each |subpat| {
+                    for subpats.eachi |i, subpat| {
                         let cmt_field = self.cat_anon_struct_field(*subpat,
-                                                                   cmt);
+                                                                   cmt, i);
                         self.cat_pattern(cmt_field, *subpat, op);
                     }
                 }
@@ -869,8 +896,8 @@ pub impl &mem_categorization_ctxt {
 
           ast::pat_tup(ref subpats) => {
             // (p1, ..., pN)
-            for subpats.each |subpat| {
-                let subcmt = self.cat_tuple_elt(*subpat, cmt);
+            for subpats.eachi |i, subpat| {
+                let subcmt = self.cat_tuple_elt(*subpat, cmt, i);
                 self.cat_pattern(subcmt, *subpat, op);
             }
           }
@@ -945,8 +972,8 @@ pub impl &mem_categorization_ctxt {
         match comp {
           comp_field(fld, _) => self.tcx.sess.str_of(fld),
           comp_index(*) => ~"[]",
-          comp_tuple => ~"()",
-          comp_anon_field => ~"<anonymous field>",
+          comp_tuple(idx) => fmt!("(%u)", idx),
+          comp_anon_field(idx) => fmt!("<anonymous field %u>", idx),
           comp_variant(_) => ~"<enum>"
         }
     }
@@ -1000,8 +1027,8 @@ pub impl &mem_categorization_ctxt {
             ~"captured outer " + mut_str + ~" variable in a stack closure"
           }
           cat_comp(_, comp_field(*)) => mut_str + ~" field",
-          cat_comp(_, comp_tuple) => ~"tuple content",
-          cat_comp(_, comp_anon_field) => ~"anonymous field",
+          cat_comp(_, comp_tuple(_)) => ~"tuple content",
+          cat_comp(_, comp_anon_field(_)) => ~"anonymous field",
           cat_comp(_, comp_variant(_)) => ~"enum content",
           cat_comp(_, comp_index(t, _)) => {
             match ty::get(t).sty {
@@ -195,6 +195,14 @@ this information without redoing the efforts of the borrow checker.
 Liveness is somewhat complicated by having to deal with stack
 closures.  More information to come!
 
+A `once fn` may move out of the variables it closes over.  We walk
+the body of a closure before computing its captures and remember
+which upvars it moves from; a once stack closure captures those by
+move, so liveness treats the variable as moved where the closure is
+created.  Calling a once closure is a move of the closure itself,
+which lets borrowck reject calls through, say, a captured variable of
+a closure that may be called many times.
+
 ## Distributive property
 
 Copies are "distributive" over parenthesization, but blocks are
@@ -261,7 +269,10 @@ pub struct MoveMaps {
 struct VisitContext {
     tcx: ty::ctxt,
     method_map: HashMap<node_id,method_map_entry>,
-    move_maps: MoveMaps
+    move_maps: MoveMaps,
+    // (fn id, var id) for each upvar that is moved out of in the body
+    // of that fn
+    moved_upvars: HashMap<(node_id, node_id), ()>
 }
 
 enum UseMode {
@@ -285,7 +296,8 @@ pub fn compute_moves(tcx: ty::ctxt,
             moves_map: HashMap(),
             variable_moves_map: HashMap(),
             capture_map: HashMap()
-        }
+        },
+        moved_upvars: HashMap()
     };
     visit::visit_crate(*crate, visit_cx, visitor);
     return visit_cx.move_maps;
@@ -422,6 +434,7 @@ impl VisitContext {
                     MoveInPart(entire_expr) => {
                         self.move_maps.variable_moves_map.insert(
                             expr.id, entire_expr);
+                        self.note_upvar_move(self.tcx.def_map.get(&expr.id));
                     }
                     Read => {}
                     MoveInWhole => {
@@ -456,6 +469,16 @@ impl VisitContext {
             }
 
             expr_call(callee, ref args, _) => {    // callee(args)
+                // Calling a once closure uses it up.  This is checked
+                // by borrowck; liveness checks it separately, so the
+                // callee is not recorded as a variable move.
+                let callee_ty = ty::expr_ty(self.tcx, callee);
+                match ty::get(callee_ty).sty {
+                    ty::ty_closure(ref f) if f.onceness == Once => {
+                        self.move_maps.moves_map.insert(callee.id, ());
+                    }
+                    _ => {}
+                }
                 self.use_expr(callee, Read, visitor);
                 self.use_fn_args(callee.id, *args, visitor);
             }
@@ -575,7 +598,7 @@ impl VisitContext {
                 self.consume_expr(cond_expr, visitor);
             }
 
-            expr_while(cond_expr, ref blk) => {
+            expr_while(cond_expr, ref blk, _) => {
                 self.consume_expr(cond_expr, visitor);
                 self.consume_block(blk, visitor);
             }
@@ -637,16 +660,18 @@ impl VisitContext {
                 self.use_expr(rhs, Read, visitor);
             }
 
-            expr_loop_body(base) |
+            expr_loop_body(base, _) |
             expr_do_body(base) => {
                 self.use_expr(base, comp_mode, visitor);
             }
 
             expr_fn(_, _, ref body, _) |
             expr_fn_block(_, ref body) => {
+                // The body goes first, so that `compute_captures()` knows
+                // which upvars it moves.
+                self.consume_block(body, visitor);
                 let cap_vars = self.compute_captures(expr.id);
                 self.move_maps.capture_map.insert(expr.id, cap_vars);
-                self.consume_block(body, visitor);
             }
 
             expr_vstore(base, _) => {
@@ -815,12 +840,22 @@ impl VisitContext {
 
         let fn_ty = ty::node_id_to_type(self.tcx, fn_expr_id);
         let sigil = ty::ty_closure_sigil(fn_ty);
+        let onceness = ty::ty_closure_onceness(fn_ty);
         let freevars = freevars::get_freevars(self.tcx, fn_expr_id);
-        if sigil == BorrowedSigil {
-            // &fn() captures everything by ref
+        let captures = if sigil == BorrowedSigil {
+            // &fn() captures everything by ref, except that &once fn()
+            // captures by move whatever its body moves out of
             at_vec::from_fn(freevars.len(), |i| {
                 let fvar = &freevars[i];
-                CaptureVar {def: fvar.def, span: fvar.span, mode: CapRef}
+                let fvar_def_id = ast_util::def_id_of_def(fvar.def).node;
+                let mode = if onceness == Once &&
+                    self.moved_upvars.contains_key_ref(
+                        &(fn_expr_id, fvar_def_id)) {
+                    CapMove
+                } else {
+                    CapRef
+                };
+                CaptureVar {def: fvar.def, span: fvar.span, mode: mode}
             })
         } else {
             // @fn() and ~fn() capture by copy or by move depending on type
@@ -837,6 +872,35 @@ impl VisitContext {
                 };
                 CaptureVar {def: fvar.def, span: fvar.span, mode:mode}
             })
+        };
+
+        // Moving a variable into this closure moves it out of the
+        // environment of any closure it is itself an upvar of.
+        for captures.each |cap| {
+            match cap.mode {
+                CapMove => self.note_upvar_move(cap.def),
+                CapCopy | CapRef => {}
+            }
+        }
+        captures
+    }
+
+    fn note_upvar_move(&self, def: def) {
+        /*!
+         *
+         * Records that the variable `def` refers to is moved, for each
+         * closure that it is an upvar of.
+         */
+
+        let mut def = def;
+        loop {
+            match copy def {
+                def_upvar(var_id, inner, fn_id, _) => {
+                    self.moved_upvars.insert((fn_id, var_id), ());
+                    def = *inner;
+                }
+                _ => break
+            }
         }
     }
 }
@@ -290,7 +290,7 @@ pub fn resolve_expr(expr: @ast::expr, cx: ctxt, visitor: visit::vt<ctxt>) {
                                                            cx.sess.intr()));
         new_cx.parent = Some(expr.id);
       }
-      ast::expr_while(cond, _) => {
+      ast::expr_while(cond, _, _) => {
         new_cx.root_exprs.insert(cond.id, ());
       }
       _ => {}
@@ -14,6 +14,7 @@ use driver::session;
 use driver::session::Session;
 use metadata::csearch::{each_path, get_method_names_if_trait};
 use metadata::csearch::{get_static_methods_if_impl, get_type_name_if_impl};
+use metadata::csearch::{get_static_methods_if_trait, get_supertrait_def_ids};
 use metadata::cstore::find_use_stmt_cnum;
 use metadata::decoder::{def_like, dl_def, dl_field, dl_impl};
 use middle::lang_items::LanguageItems;
@@ -37,7 +38,8 @@ use syntax::ast::{expr_fn_block, expr_index, expr_method_call, expr_path};
 use syntax::ast::{def_prim_ty, def_region, def_self, def_ty, def_ty_param};
 use syntax::ast::{def_upvar, def_use, def_variant, div, eq};
 use syntax::ast::{enum_variant_kind, expr, expr_again, expr_assign_op};
-use syntax::ast::{expr_fn_block, expr_index, expr_loop};
+use syntax::ast::{expr_fn_block, expr_index, expr_loop, expr_loop_body};
+use syntax::ast::{expr_while};
 use syntax::ast::{expr_path, expr_struct, expr_unary, fn_decl};
 use syntax::ast::{foreign_item, foreign_item_const, foreign_item_fn, ge};
 use syntax::ast::{gt, ident, impure_fn, inherited, item, item_struct};
@@ -508,6 +510,17 @@ pub fn unused_import_lint_level(session: Session) -> level {
     return allow;
 }
 
+/// What name resolution needs to know about a local trait to find the
+/// static methods it inherits from its supertraits.
+pub struct LocalTraitInfo {
+    // The module holding the trait's own static methods
+    module_: @Module,
+    // The module the trait is declared in, which its supertrait paths are
+    // resolved from
+    parent: @Module,
+    supertraits: ~[@trait_ref],
+}
+
 // Records a possibly-private type definition.
 pub struct TypeNsDef {
     mut privacy: Privacy,
@@ -762,6 +775,7 @@ pub fn Resolver(session: Session,
         unused_import_lint_level: unused_import_lint_level(session),
 
         trait_info: HashMap(),
+        local_traits: HashMap(),
         structs: HashMap(),
 
         unresolved_imports: 0,
@@ -808,6 +822,8 @@ pub struct Resolver {
     unused_import_lint_level: level,
 
     trait_info: HashMap<def_id,@HashMap<ident,()>>,
+    // The local traits that have static methods or supertraits
+    local_traits: HashMap<node_id,@LocalTraitInfo>,
     structs: HashMap<def_id,()>,
 
     // The number of imports that are currently unresolved.
@@ -1249,7 +1265,7 @@ pub impl Resolver {
                 visit_item(item, parent, visitor);
             }
 
-            item_trait(_, _, ref methods) => {
+            item_trait(_, ref traits, ref methods) => {
                 let (name_bindings, new_parent) =
                     self.add_child(ident, parent, ForbidDuplicateTypes, sp);
 
@@ -1257,7 +1273,8 @@ pub impl Resolver {
                 // methods within to a new module.
                 //
                 // We only need to create the module if the trait has static
-                // methods, so check that first.
+                // methods, or supertraits whose static methods can be reached
+                // through it, so check that first.
                 let mut has_static_methods = false;
                 for (*methods).each |method| {
                     let ty_m = trait_method_to_ty_method(*method);
@@ -1272,13 +1289,18 @@ pub impl Resolver {
 
                 // Create the module if necessary.
                 let module_parent_opt;
-                if has_static_methods {
+                if has_static_methods || traits.len() > 0 {
                     let parent_link = self.get_parent_link(parent, ident);
                     name_bindings.define_module(privacy,
                                                 parent_link,
                                                 Some(local_def(item.id)),
                                                 TraitModuleKind,
                                                 sp);
+                    self.local_traits.insert(item.id, @LocalTraitInfo {
+                        module_: name_bindings.get_module(),
+                        parent: self.get_module_from_parent(parent),
+                        supertraits: copy *traits
+                    });
                     module_parent_opt = Some(ModuleReducedGraphParent(
                         name_bindings.get_module()));
                 } else {
@@ -1630,6 +1652,23 @@ pub impl Resolver {
                     }
                 }
                 self.trait_info.insert(def_id, interned_method_names);
+
+                // A trait with supertraits needs a module for paths like
+                // `Trait::method` to start from, even if it declares no
+                // static methods of its own.
+                if get_supertrait_def_ids(self.session.cstore,
+                                          def_id).len() > 0 {
+                    match child_name_bindings.get_module_if_available() {
+                        Some(module_) => module_.def_id = Some(def_id),
+                        None => {
+                            let parent_link =
+                                self.get_parent_link(new_parent, ident);
+                            child_name_bindings.define_module(
+                                Public, parent_link, Some(def_id),
+                                TraitModuleKind, dummy_sp());
+                        }
+                    }
+                }
               }
             }
 
@@ -4630,8 +4669,17 @@ pub impl Resolver {
                                                         namespace,
                                                         xray) {
             NoNameDefinition => {
-                // We failed to resolve the name. Report an error.
-                return None;
+                // The name may be a static method that a trait inherits.
+                match namespace {
+                    ValueNS => {
+                        return self.resolve_static_method_in_supertraits(
+                            containing_module, name, path.span);
+                    }
+                    TypeNS => {
+                        // We failed to resolve the name. Report an error.
+                        return None;
+                    }
+                }
             }
             ChildNameDefinition(def) | ImportNameDefinition(def) => {
                 return Some(def);
@@ -4639,6 +4687,129 @@ pub impl Resolver {
         }
     }
 
+    /**
+     * Looks for a static method named `name` in the supertraits of the trait
+     * that `module_` belongs to, so that `Sub::f` finds an `f` declared on a
+     * supertrait of `Sub`.
+     *
+     * A supertrait that can be reached along several paths is searched only
+     * once, and a supertrait that declares the method hides the traits it
+     * inherits from. If two different supertraits declare it, the name is
+     * ambiguous.
+     */
+    fn resolve_static_method_in_supertraits(module_: @Module,
+                                            name: ident,
+                                            span: span)
+                                         -> Option<def> {
+        let trait_did = match module_.def_id {
+            Some(def_id) if self.trait_info.contains_key_ref(&def_id) =>
+                def_id,
+            _ => return None
+        };
+
+        let found = @DVec();
+        let visited = HashMap();
+        visited.insert(trait_did, ());
+        self.search_supertraits_for_static_method(trait_did, name, found,
+                                                  visited);
+        match found.len() {
+            0 => None,
+            1 => Some(found.get_elt(0)),
+            _ => {
+                self.session.span_err(span,
+                                      fmt!("static method `%s` is declared \
+                                            in more than one supertrait",
+                                           self.session.str_of(name)));
+                Some(found.get_elt(0))
+            }
+        }
+    }
+
+    fn search_supertraits_for_static_method(trait_did: def_id,
+                                            name: ident,
+                                            found: @DVec<def>,
+                                            visited: HashMap<def_id,()>) {
+        for self.supertrait_def_ids(trait_did).each |supertrait_did| {
+            if visited.contains_key_ref(supertrait_did) { loop; }
+            visited.insert(*supertrait_did, ());
+
+            match self.static_method_of_trait(*supertrait_did, name) {
+                Some(def) => found.push(def),
+                None => {
+                    self.search_supertraits_for_static_method(
+                        *supertrait_did, name, found, visited);
+                }
+            }
+        }
+    }
+
+    fn supertrait_def_ids(trait_did: def_id) -> ~[def_id] {
+        if trait_did.crate != local_crate {
+            return get_supertrait_def_ids(self.session.cstore, trait_did);
+        }
+
+        let info = match self.local_traits.find(&trait_did.node) {
+            Some(info) => info,
+            None => return ~[]
+        };
+
+        // The supertrait paths are resolved from where the trait is
+        // declared, which may not have been visited yet.
+        let saved_module = self.current_module;
+        self.current_module = info.parent;
+        let mut result = ~[];
+        for info.supertraits.each |trait_ref| {
+            let path = trait_ref.path;
+            let def = if path.global {
+                self.resolve_crate_relative_path(path, self.xray_context,
+                                                 TypeNS)
+            } else if path.idents.len() > 1 {
+                self.resolve_module_relative_path(path, self.xray_context,
+                                                  TypeNS)
+            } else {
+                self.resolve_item_by_identifier_in_lexical_scope(
+                    path.idents.last(), TypeNS)
+            };
+            match def {
+                Some(def_ty(def_id)) => result.push(def_id),
+                // Reported when the trait itself is resolved.
+                _ => {}
+            }
+        }
+        self.current_module = saved_module;
+        result
+    }
+
+    fn static_method_of_trait(trait_did: def_id, name: ident)
+                           -> Option<def> {
+        if trait_did.crate != local_crate {
+            match get_static_methods_if_trait(self.session.cstore,
+                                              trait_did) {
+                Some(ref methods) => {
+                    for methods.each |method| {
+                        if method.ident == name {
+                            return Some(def_static_method(method.def_id,
+                                                          Some(trait_did),
+                                                          method.purity));
+                        }
+                    }
+                    return None;
+                }
+                None => return None
+            }
+        }
+
+        match self.local_traits.find(&trait_did.node) {
+            Some(info) => {
+                match info.module_.children.find(&name) {
+                    Some(bindings) => bindings.def_for_namespace(ValueNS),
+                    None => None
+                }
+            }
+            None => None
+        }
+    }
+
     fn resolve_crate_relative_path(path: @path,
                                    +xray: XrayFlag,
                                    namespace: Namespace)
@@ -4677,8 +4848,17 @@ pub impl Resolver {
                                                         namespace,
                                                         xray) {
             NoNameDefinition => {
-                // We failed to resolve the name. Report an error.
-                return None;
+                // The name may be a static method that a trait inherits.
+                match namespace {
+                    ValueNS => {
+                        return self.resolve_static_method_in_supertraits(
+                            containing_module, name, path.span);
+                    }
+                    TypeNS => {
+                        // We failed to resolve the name. Report an error.
+                        return None;
+                    }
+                }
             }
             ChildNameDefinition(def) | ImportNameDefinition(def) => {
                 return Some(def);
@@ -4872,7 +5052,7 @@ pub impl Resolver {
                 visit_expr(expr, (), visitor);
             }
 
-            expr_loop(_, Some(label)) => {
+            expr_loop(_, Some(label)) | expr_while(_, _, Some(label)) => {
                 do self.with_label_rib {
                     let def_like = dl_def(def_label(expr.id));
                     self.label_ribs.last().bindings.insert(label, def_like);
@@ -4881,29 +5061,80 @@ pub impl Resolver {
                 }
             }
 
-            expr_break(Some(label)) | expr_again(Some(label)) => {
-                match self.search_ribs(self.label_ribs, label, expr.span,
-                                       DontAllowCapturingSelf) {
-                    None =>
-                        self.session.span_err(expr.span,
-                                              fmt!("use of undeclared label \
-                                                   `%s`", self.session.str_of(
-                                                  label))),
-                    Some(dl_def(def @ def_label(_))) =>
-                        self.record_def(expr.id, def),
-                    Some(_) =>
-                        self.session.span_bug(expr.span,
-                                              ~"label wasn't mapped to a \
-                                                label def!")
+            expr_loop_body(body @ @expr {
+                node: expr_fn_block(_, ref block),
+                _
+            }, Some(label)) => {
+                // The body of a `for` loop is a closure, so the label names
+                // the closure body, which is what `break` and `loop` leave.
+                do self.with_label_rib {
+                    let def_like = dl_def(def_label(block.node.id));
+                    self.label_ribs.last().bindings.insert(label, def_like);
+
+                    self.resolve_expr(body, visitor);
                 }
             }
 
+            expr_break(Some(label)) => {
+                self.resolve_label(expr, label, "break");
+            }
+
+            expr_again(Some(label)) => {
+                self.resolve_label(expr, label, "loop");
+            }
+
             _ => {
                 visit_expr(expr, (), visitor);
             }
         }
     }
 
+    /// Resolves the label of a `break` or `loop` expression. Jumping out of
+    /// a closure is only allowed to the `for` loop whose body the closure
+    /// is.
+    fn resolve_label(expr: @expr, label: ident, keyword: &str) {
+        let mut i = (*self.label_ribs).len();
+        while i != 0 {
+            i -= 1;
+            match (*self.label_ribs).get_elt(i).bindings.find(&label) {
+                None => {
+                    // Continue.
+                }
+                Some(dl_def(def @ def_label(loop_id))) => {
+                    let mut j = i + 1;
+                    while j < (*self.label_ribs).len() {
+                        match (*self.label_ribs).get_elt(j).kind {
+                            NormalRibKind => {}
+                            FunctionRibKind(_, body_id)
+                                    if body_id == loop_id && j == i + 1 => {}
+                            _ => {
+                                self.session.span_err(
+                                    expr.span,
+                                    fmt!("cannot `%s` to label `'%s` from \
+                                          inside a closure",
+                                         keyword,
+                                         self.session.str_of(label)));
+                                return;
+                            }
+                        }
+                        j += 1;
+                    }
+                    self.record_def(expr.id, def);
+                    return;
+                }
+                Some(_) => {
+                    self.session.span_bug(expr.span,
+                                          ~"label wasn't mapped to a label \
+                                            def!");
+                }
+            }
+        }
+
+        self.session.span_err(expr.span,
+                              fmt!("use of undeclared label `'%s`",
+                                   self.session.str_of(label)));
+    }
+
     fn record_candidate_traits_for_expr_if_necessary(expr: @expr) {
         match expr.node {
             expr_field(_, ident, _) => {
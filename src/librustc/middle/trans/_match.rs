@@ -825,7 +825,8 @@ pub fn extract_variant_args(bcx: block,
                          -> {vals: ~[ValueRef], bcx: block} {
     let _icx = bcx.insn_ctxt("match::extract_variant_args");
     let ccx = bcx.fcx.ccx;
-    let enum_ty_substs = match ty::get(node_id_type(bcx, pat_id)).sty {
+    let enum_ty = node_id_type(bcx, pat_id);
+    let enum_ty_substs = match ty::get(enum_ty).sty {
       ty::ty_enum(id, ref substs) => {
         assert id == vdefs.enm;
         /*bad*/copy (*substs).tps
@@ -837,9 +838,7 @@ pub fn extract_variant_args(bcx: block,
     let size = ty::enum_variant_with_id(ccx.tcx, vdefs.enm,
                                         vdefs.var).args.len();
     if size > 0u && (*variants).len() != 1u {
-        let enumptr =
-            PointerCast(bcx, val, T_opaque_enum_ptr(ccx));
-        blobptr = GEPi(bcx, enumptr, [0u, 1u]);
+        blobptr = enum_blob_ptr(bcx, val, enum_ty);
     }
     let vdefs_tg = vdefs.enm;
     let vdefs_var = vdefs.var;
@@ -1363,10 +1362,8 @@ pub fn compile_submatch(bcx: block,
                 if (*ty::enum_variants(tcx, vdef.enm)).len() == 1u {
                     kind = single;
                 } else {
-                    let enumptr =
-                        PointerCast(bcx, val, T_opaque_enum_ptr(ccx));
-                    let discrimptr = GEPi(bcx, enumptr, [0u, 0u]);
-                    test_val = Load(bcx, discrimptr);
+                    let enum_ty = node_id_type(bcx, pat_id);
+                    test_val = load_discrim(bcx, val, enum_ty);
                     kind = switch;
                 }
             }
@@ -1792,7 +1789,7 @@ pub fn bind_irrefutable_pat(bcx: block,
                                            binding_mode);
             }
         }
-        ast::pat_box(inner) | ast::pat_uniq(inner) => {
+        ast::pat_box(inner) => {
             let llbox = Load(bcx, val);
             let unboxed = GEPi(bcx, llbox, [0u, abi::box_field_body]);
             bcx = bind_irrefutable_pat(bcx,
@@ -1801,6 +1798,20 @@ pub fn bind_irrefutable_pat(bcx: block,
                                        true,
                                        binding_mode);
         }
+        ast::pat_uniq(inner) => {
+            // The same as pat_box, except that the bindings may move out
+            // of the box (`make_copy` is passed through rather than forced
+            // to true). `val` owns the box as it would own inline
+            // contents, so a moved-out binding is zeroed there and
+            // dropping the box drops only what is left.
+            let llbox = Load(bcx, val);
+            let unboxed = GEPi(bcx, llbox, [0u, abi::box_field_body]);
+            bcx = bind_irrefutable_pat(bcx,
+                                       inner,
+                                       unboxed,
+                                       make_copy,
+                                       binding_mode);
+        }
         ast::pat_region(inner) => {
             let loaded_val = Load(bcx, val);
             bcx = bind_irrefutable_pat(bcx,
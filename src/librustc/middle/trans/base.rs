@@ -63,7 +63,7 @@ use middle::trans::shape::*;
 use middle::trans::tvec;
 use middle::trans::type_of::*;
 use middle::ty::arg;
-use util::common::indenter;
+use util::common::{block_query, indenter};
 use util::ppaux::{ty_to_str, ty_to_short_str};
 use util::ppaux;
 
@@ -261,6 +261,62 @@ pub fn GEP_enum(bcx: block, llblobptr: ValueRef, enum_id: ast::def_id,
     GEPi(bcx, typed_blobptr, [0u, ix])
 }
 
+// Loads the discriminant of the enum `llenumptr` points to, which has more
+// than one variant. See `nullable_enum_discrims` for enums that are no more
+// than a pointer.
+pub fn load_discrim(bcx: block, llenumptr: ValueRef,
+                    enum_ty: ty::t) -> ValueRef {
+    let ccx = bcx.ccx();
+    match nullable_enum_discrims(ccx.tcx, enum_ty) {
+      Some((null_disr, ptr_disr)) => {
+        let llptr = Load(bcx, PointerCast(bcx, llenumptr,
+                                          T_ptr(T_ptr(T_i8()))));
+        Select(bcx, IsNull(bcx, llptr),
+               C_int(ccx, null_disr), C_int(ccx, ptr_disr))
+      }
+      None => {
+        let llenumptr = PointerCast(bcx, llenumptr, T_opaque_enum_ptr(ccx));
+        Load(bcx, GEPi(bcx, llenumptr, [0u, 0u]))
+      }
+    }
+}
+
+// Writes the discriminant `disr` into the enum `llenumptr` points to and
+// returns a pointer to the data part that `GEP_enum` can index into. The
+// pointer variant of a nullable enum gets its discriminant when its field
+// is written.
+pub fn store_discrim(bcx: block, llenumptr: ValueRef, enum_ty: ty::t,
+                     disr: int) -> ValueRef {
+    let ccx = bcx.ccx();
+    match nullable_enum_discrims(ccx.tcx, enum_ty) {
+      Some((null_disr, _)) => {
+        if disr == null_disr {
+            let llptrptr = PointerCast(bcx, llenumptr, T_ptr(T_ptr(T_i8())));
+            Store(bcx, C_null(T_ptr(T_i8())), llptrptr);
+        }
+        llenumptr
+      }
+      None => {
+        let llenumptr = PointerCast(bcx, llenumptr, T_opaque_enum_ptr(ccx));
+        Store(bcx, C_int(ccx, disr), GEPi(bcx, llenumptr, [0u, 0u]));
+        GEPi(bcx, llenumptr, [0u, 1u])
+      }
+    }
+}
+
+// Returns a pointer to the data part of the enum `llenumptr` points to,
+// which has more than one variant.
+pub fn enum_blob_ptr(bcx: block, llenumptr: ValueRef,
+                     enum_ty: ty::t) -> ValueRef {
+    if nullable_enum_discrims(bcx.tcx(), enum_ty).is_some() {
+        llenumptr
+    } else {
+        let llenumptr =
+            PointerCast(bcx, llenumptr, T_opaque_enum_ptr(bcx.ccx()));
+        GEPi(bcx, llenumptr, [0u, 1u])
+    }
+}
+
 // Returns a pointer to the body for the box. The box may be an opaque
 // box. The result will be casted to the type of body_t, if it is statically
 // known.
@@ -669,15 +725,17 @@ pub fn iter_structural_ty(cx: block, av: ValueRef, t: ty::t,
         }
 
         let ccx = cx.ccx();
-        let llenumty = T_opaque_enum_ptr(ccx);
-        let av_enum = PointerCast(cx, av, llenumty);
-        let lldiscrim_a_ptr = GEPi(cx, av_enum, [0u, 0u]);
-        let llunion_a_ptr = GEPi(cx, av_enum, [0u, 1u]);
-        let lldiscrim_a = Load(cx, lldiscrim_a_ptr);
+        let llunion_a_ptr = enum_blob_ptr(cx, av, t);
+        let lldiscrim_a = load_discrim(cx, av, t);
 
         // NB: we must hit the discriminant first so that structural
         // comparison know not to proceed when the discriminants differ.
-        cx = f(cx, lldiscrim_a_ptr, ty::mk_int(cx.tcx()));
+        // A nullable enum has no discriminant apart from its pointer.
+        if nullable_enum_discrims(ccx.tcx, t).is_none() {
+            let av_enum = PointerCast(cx, av, T_opaque_enum_ptr(ccx));
+            let lldiscrim_a_ptr = GEPi(cx, av_enum, [0u, 0u]);
+            cx = f(cx, lldiscrim_a_ptr, ty::mk_int(cx.tcx()));
+        }
         let unr_cx = sub_block(cx, ~"enum-iter-unr");
         Unreachable(unr_cx);
         let llswitch = Switch(cx, lldiscrim_a, unr_cx.llbb, n_variants);
@@ -1105,7 +1163,13 @@ pub fn init_local(bcx: block, local: @ast::local) -> block {
     debug!("adding clean for %?/%s to bcx=%s",
            local.node.id, bcx.ty_to_str(ty),
            bcx.to_str());
-    add_clean(bcx, llptr, ty);
+    if bcx.fcx.nrvo_local == Some(local.node.id) {
+        // The slot is the caller's, so the cleanup must be one that
+        // trans_closure can revoke once the function returns normally
+        add_clean_temp_mem(bcx, llptr, ty);
+    } else {
+        add_clean(bcx, llptr, ty);
+    }
 
     return _match::bind_irrefutable_pat(bcx,
                                        local.node.pat,
@@ -1388,7 +1452,11 @@ pub fn alloc_local(cx: block, local: @ast::local) -> block {
       ast::pat_ident(_, pth, None) => Some(path_to_ident(pth)),
       _ => None
     };
-    let val = alloc_ty(cx, t);
+    let val = if cx.fcx.nrvo_local == Some(local.node.id) {
+        PointerCast(cx, cx.fcx.llretptr, T_ptr(type_of(cx.ccx(), t)))
+    } else {
+        alloc_ty(cx, t)
+    };
     if cx.sess().opts.debuginfo {
         do option::iter(&simple_name) |name| {
             str::as_c_str(cx.ccx().sess.str_of(*name), |buf| {
@@ -1555,6 +1623,7 @@ pub fn new_fn_ctxt_w_id(ccx: @crate_ctxt,
           mut llself: None,
           mut personality: None,
           mut loop_ret: None,
+          mut nrvo_local: None,
           llargs: HashMap(),
           lllocals: HashMap(),
           llupvars: HashMap(),
@@ -1777,7 +1846,24 @@ pub fn trans_closure(ccx: @crate_ctxt,
     {
         bcx = controlflow::trans_block(bcx, body, expr::Ignore);
     } else {
-        bcx = controlflow::trans_block(bcx, body, expr::SaveIn(fcx.llretptr));
+        match nrvo_local(bcx, body) {
+            Some(local_id) if fcx.loop_ret.is_none() => {
+                // The local is built in llretptr, so by the end of the body
+                // the result is already in place. It is dropped if the
+                // function fails, but not when it returns.
+                fcx.nrvo_local = Some(local_id);
+                bcx = controlflow::trans_block(bcx, body, expr::Ignore);
+                match fcx.lllocals.find(&local_id) {
+                    Some(local_mem(v)) => revoke_clean(bcx, v),
+                    _ => ccx.sess.span_bug(body.span,
+                                           ~"no slot for the returned local")
+                }
+            }
+            _ => {
+                bcx = controlflow::trans_block(bcx, body,
+                                               expr::SaveIn(fcx.llretptr));
+            }
+        }
     }
 
     finish(bcx);
@@ -1787,6 +1873,42 @@ pub fn trans_closure(ccx: @crate_ctxt,
     finish_fn(fcx, lltop);
 }
 
+// nrvo_local: The local, if any, that the function's body can build in
+// place in the return slot: one bound with a plain `let x` at the top level
+// of the body, whose value is `x`. Any `return` in the body would have to
+// go through the slot while `x` is still live in it, so if there is one
+// the result is built and copied as usual.
+pub fn nrvo_local(bcx: block, body: &ast::blk) -> Option<ast::node_id> {
+    let tcx = bcx.tcx();
+    let def_id = match body.node.expr {
+        Some(e) if !tcx.adjustments.contains_key_ref(&e.id) => {
+            match e.node {
+                ast::expr_path(_) => match tcx.def_map.find(&e.id) {
+                    Some(ast::def_local(id, _)) => id,
+                    _ => return None
+                },
+                _ => return None
+            }
+        }
+        _ => return None
+    };
+    let mut local_id = None;
+    do block_locals(body) |local| {
+        match local.node.pat.node {
+            ast::pat_ident(ast::bind_by_ref(_), _, _) => {}
+            ast::pat_ident(_, _, None) if local.node.pat.id == def_id => {
+                local_id = Some(local.node.id);
+            }
+            _ => {}
+        }
+    }
+    if local_id.is_none() { return None; }
+    let has_ret = do block_query(/*bad*/copy *body) |e| {
+        match e.node { ast::expr_ret(_) => true, _ => false }
+    };
+    if has_ret { None } else { local_id }
+}
+
 // trans_fn: creates an LLVM function corresponding to a source language
 // function.
 pub fn trans_fn(ccx: @crate_ctxt,
@@ -1858,11 +1980,8 @@ pub fn trans_enum_variant(ccx: @crate_ctxt,
     let llblobptr = if is_degen {
         fcx.llretptr
     } else {
-        let llenumptr =
-            PointerCast(bcx, fcx.llretptr, T_opaque_enum_ptr(ccx));
-        let lldiscrimptr = GEPi(bcx, llenumptr, [0u, 0u]);
-        Store(bcx, C_int(ccx, disr), lldiscrimptr);
-        GEPi(bcx, llenumptr, [0u, 1u])
+        let enum_ty = ty::ty_fn_ret(node_id_type(bcx, variant.node.id));
+        store_discrim(bcx, fcx.llretptr, enum_ty, disr)
     };
     let t_id = local_def(enum_id);
     let v_id = local_def(variant.node.id);
@@ -424,7 +424,7 @@ pub fn trans_call_inner(
               ast::expr_loop_body(@ast::expr {
                 node: ast::expr_fn_block(_, ref body),
                 _
-              }) =>  body_contains_ret((*body)),
+              }, _) =>  body_contains_ret((*body)),
               _ => false
             }
           }
@@ -630,7 +630,7 @@ pub fn trans_arg_expr(bcx: block,
                     blk @ @ast::expr {
                         node: ast::expr_fn_block(ref decl, ref body),
                         _
-                    }) =>
+                    }, _) =>
                 {
                     let scratch_ty = expr_ty(bcx, arg_expr);
                     let scratch = alloc_ty(bcx, scratch_ty);
@@ -283,6 +283,12 @@ pub fn build_closure(bcx0: block,
                 env_vals.push(EnvValue {action: EnvCopy,
                                         datum: datum});
             }
+            moves::CapMove if sigil == ast::BorrowedSigil => {
+                // A once stack closure moves out of the variable itself
+                // when it runs, zeroing it, so it needs only a reference.
+                env_vals.push(EnvValue {action: EnvRef,
+                                        datum: datum});
+            }
             moves::CapMove => {
                 env_vals.push(EnvValue {action: EnvMove,
                                         datum: datum});
@@ -290,6 +290,9 @@ pub struct fn_ctxt_ {
     // If this is a for-loop body that returns, this holds the pointers needed
     // for that
     mut loop_ret: Option<{flagptr: ValueRef, retptr: ValueRef}>,
+    // If the function's result is one of its locals, built in place in
+    // llretptr rather than in an alloca of its own, the id of that local
+    mut nrvo_local: Option<ast::node_id>,
 
     // Maps arguments to allocas created for them in llallocas.
     llargs: HashMap<ast::node_id, local_val>,
@@ -1061,6 +1064,44 @@ pub fn T_opaque_enum_ptr(cx: @crate_ctxt) -> TypeRef {
     return T_ptr(T_opaque_enum(cx));
 }
 
+// Whether a value of type `t` is a single pointer that can never be null.
+pub fn type_is_non_null_ptr(t: ty::t) -> bool {
+    match ty::get(t).sty {
+      ty::ty_box(_) | ty::ty_opaque_box | ty::ty_uniq(_) |
+      ty::ty_rptr(_, _) |
+      ty::ty_estr(ty::vstore_uniq) | ty::ty_estr(ty::vstore_box) |
+      ty::ty_evec(_, ty::vstore_uniq) |
+      ty::ty_evec(_, ty::vstore_box) => true,
+      _ => false
+    }
+}
+
+// An enum with two variants, one nullary and the other holding nothing but
+// a pointer that can't be null, is represented as that pointer alone, with
+// null standing for the nullary variant. For such an enum this returns the
+// discriminants of the nullary variant and of the pointer variant.
+pub fn nullable_enum_discrims(tcx: ty::ctxt, t: ty::t) -> Option<(int, int)> {
+    return match ty::get(t).sty {
+      ty::ty_enum(did, ref substs) => {
+        let variants = ty::substd_enum_variants(tcx, did, substs);
+        if variants.len() != 2u { return None; }
+        if variants[0].args.len() == 0u && is_ptr_variant(variants[1]) {
+            Some((variants[0].disr_val, variants[1].disr_val))
+        } else if variants[1].args.len() == 0u &&
+                  is_ptr_variant(variants[0]) {
+            Some((variants[1].disr_val, variants[0].disr_val))
+        } else {
+            None
+        }
+      }
+      _ => None
+    };
+
+    fn is_ptr_variant(v: ty::VariantInfo) -> bool {
+        v.args.len() == 1u && type_is_non_null_ptr(v.args[0])
+    }
+}
+
 pub fn T_captured_tydescs(cx: @crate_ctxt, n: uint) -> TypeRef {
     return T_struct(vec::from_elem::<TypeRef>(n, T_ptr(cx.tydesc_type)));
 }
@@ -431,10 +431,15 @@ pub fn const_expr(cx: @crate_ctxt, e: @ast::expr) -> ValueRef {
                     // variant or we wouldn't have gotten here -- the constant
                     // checker forbids paths that don't map to C-like enum
                     // variants.
-                    let lldiscrim = base::get_discrim_val(cx, e.span,
-                                                          enum_did,
-                                                          variant_did);
-                    C_struct(~[lldiscrim])
+                    let ety = ty::expr_ty(cx.tcx, e);
+                    if nullable_enum_discrims(cx.tcx, ety).is_some() {
+                        C_struct(~[C_null(T_ptr(T_i8()))])
+                    } else {
+                        let lldiscrim = base::get_discrim_val(cx, e.span,
+                                                              enum_did,
+                                                              variant_did);
+                        C_struct(~[lldiscrim])
+                    }
                 }
                 Some(ast::def_struct(_)) => {
                     let ety = ty::expr_ty(cx.tcx, e);
@@ -465,6 +470,13 @@ pub fn const_expr(cx: @crate_ctxt, e: @ast::expr) -> ValueRef {
                 let degen = ty::enum_is_univariant(cx.tcx, tid);
                 let size = machine::static_size_of_enum(cx, ety);
 
+                if nullable_enum_discrims(cx.tcx, ety).is_some() {
+                    // The pointer is all there is
+                    let llptr = const_expr(cx, args[0]);
+                    return C_struct(~[llvm::LLVMConstPointerCast(
+                        llptr, T_ptr(T_i8()))]);
+                }
+
                 let discrim = base::get_discrim_val(cx, e.span, tid, vid);
                 let c_args = C_struct(args.map(|a| const_expr(cx, *a)));
 
@@ -114,7 +114,11 @@ pub fn join_blocks(parent_bcx: block, in_cxs: ~[block]) -> block {
     return out;
 }
 
-pub fn trans_while(bcx: block, cond: @ast::expr, body: &ast::blk) -> block {
+pub fn trans_while(bcx: block,
+                   cond: @ast::expr,
+                   body: &ast::blk,
+                   opt_label: Option<ident>)
+                -> block {
     let _icx = bcx.insn_ctxt("trans_while");
     let next_bcx = sub_block(bcx, ~"while next");
 
@@ -131,7 +135,7 @@ pub fn trans_while(bcx: block, cond: @ast::expr, body: &ast::blk) -> block {
     //    |           body_bcx_out --+
     // next_bcx
 
-    let loop_bcx = loop_scope_block(bcx, next_bcx, None, ~"`while`",
+    let loop_bcx = loop_scope_block(bcx, next_bcx, opt_label, ~"`while`",
                                     body.info());
     let cond_bcx_in = scope_block(loop_bcx, cond.info(), ~"while loop cond");
     let body_bcx_in = scope_block(loop_bcx, body.info(), ~"while loop body");
@@ -185,6 +189,7 @@ pub fn trans_log(log_ex: @ast::expr,
         ));
     // XXX: Bad copy.
     let modname = path_str(ccx.sess, copy modpath);
+    let llmodname = C_cstr(ccx, copy modname);
 
     let global = if ccx.module_data.contains_key_ref(&modname) {
         ccx.module_data.get(&modname)
@@ -218,11 +223,23 @@ pub fn trans_log(log_ex: @ast::expr,
             // Translate the value to be logged
             let val_datum = unpack_datum!(bcx, expr::trans_to_datum(bcx, e));
 
-            // Call the polymorphic log function
+            // Tell the log function whether the value is a string, and
+            // which kind, so that it can log strings as they are. Must
+            // agree with the constants in core::logging.
+            let str_kind = match ty::get(val_datum.ty).sty {
+                ty::ty_estr(ty::vstore_uniq) => 1u,
+                ty::ty_estr(ty::vstore_box) => 2u,
+                ty::ty_estr(ty::vstore_slice(_)) => 3u,
+                _ => 0u
+            };
+
+            // Call the polymorphic log function with the module's name
             let val = val_datum.to_ref_llval(bcx);
+            let llmodname = PointerCast(bcx, llmodname, T_ptr(T_i8()));
             let did = bcx.tcx().lang_items.log_type_fn();
             let bcx = callee::trans_rtcall_or_lang_call_with_type_params(
-                bcx, did, ~[level, val], ~[val_datum.ty], expr::Ignore);
+                bcx, did, ~[level, llmodname, C_uint(ccx, str_kind), val],
+                ~[val_datum.ty], expr::Ignore);
             bcx
         }
     }
@@ -474,8 +474,8 @@ fn trans_rvalue_stmt_unadjusted(bcx: block, expr: @ast::expr) -> block {
         ast::expr_assert(a) => {
             return controlflow::trans_check_expr(bcx, expr, a, ~"Assertion");
         }
-        ast::expr_while(cond, ref body) => {
-            return controlflow::trans_while(bcx, cond, body);
+        ast::expr_while(cond, ref body, opt_label) => {
+            return controlflow::trans_while(bcx, cond, body, opt_label);
         }
         ast::expr_loop(ref body, opt_label) => {
             return controlflow::trans_loop(bcx, body, opt_label);
@@ -573,7 +573,7 @@ fn trans_rvalue_dps_unadjusted(bcx: block, expr: @ast::expr,
                                           expr.id, expr.id,
                                           None, dest);
         }
-        ast::expr_loop_body(blk) => {
+        ast::expr_loop_body(blk, _) => {
             let expr_ty = expr_ty(bcx, expr);
             let sigil = ty::ty_closure_sigil(expr_ty);
             match blk.node {
@@ -624,7 +624,7 @@ fn trans_rvalue_dps_unadjusted(bcx: block, expr: @ast::expr,
         }
         ast::expr_cast(val, _) => {
             match ty::get(node_id_type(bcx, expr.id)).sty {
-                ty::ty_trait(_, _, vstore) => {
+                ty::ty_trait(_, _, vstore, _) => {
                     return meth::trans_trait_cast(bcx, val, expr.id, dest,
                                                   vstore);
                 }
@@ -676,9 +676,8 @@ fn trans_def_dps_unadjusted(bcx: block, ref_expr: @ast::expr,
                 return fn_data_to_datum(bcx, vid, fn_data, lldest);
             } else {
                 // Nullary variant.
-                let lldiscrimptr = GEPi(bcx, lldest, [0u, 0u]);
-                let lldiscrim = C_int(bcx.ccx(), variant_info.disr_val);
-                Store(bcx, lldiscrim, lldiscrimptr);
+                let enum_ty = expr_ty(bcx, ref_expr);
+                store_discrim(bcx, lldest, enum_ty, variant_info.disr_val);
                 return bcx;
             }
         }
@@ -905,7 +904,8 @@ pub fn trans_local_var(bcx: block, def: ast::def) -> Datum {
 
     return match def {
         ast::def_upvar(nid, _, _, _) => {
-            // Can't move upvars, so this is never a ZeroMemLastUse.
+            // Upvars are only moved out of by once closures, which
+            // zero them, so this is never a ZeroMemLastUse.
             let local_ty = node_id_type(bcx, nid);
             match bcx.fcx.llupvars.find(&nid) {
                 Some(val) => {
@@ -283,7 +283,7 @@ pub fn normalize_for_monomorphization(tcx: ty::ctxt,
         ty::ty_closure(ref fty) => {
             Some(normalized_closure_ty(tcx, fty.sigil))
         }
-        ty::ty_trait(_, _, ref vstore) => {
+        ty::ty_trait(_, _, ref vstore, _) => {
             let sigil = match *vstore {
                 ty::vstore_uniq => ast::OwnedSigil,
                 ty::vstore_box => ast::ManagedSigil,
@@ -360,7 +360,10 @@ pub fn make_mono_id(ccx: @crate_ctxt, item: ast::def_id, substs: ~[ty::t],
                         if *uses == 0u {
                             mono_any
                         } else if *uses == type_use::use_repr &&
-                            !ty::type_needs_drop(ccx.tcx, subst)
+                            !ty::type_needs_drop(ccx.tcx, subst) &&
+                            // A pointer that is never null changes the
+                            // representation of enums holding it
+                            !type_is_non_null_ptr(subst)
                         {
                             let llty = type_of::type_of(ccx, subst);
                             let size = machine::llbitsize_of_real(ccx, llty);
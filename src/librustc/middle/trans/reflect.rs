@@ -27,6 +27,7 @@ use util::ppaux::ty_to_str;
 use std::oldmap::HashMap;
 use syntax::ast::def_id;
 use syntax::ast;
+use syntax::ast_map;
 
 pub enum reflector = {
     visitor_val: ValueRef,
@@ -245,6 +246,10 @@ pub impl reflector {
             let tcx = bcx.ccx().tcx;
             let fields = ty::struct_fields(tcx, did, substs);
 
+            let name = match ty::item_path(tcx, did).last() {
+                ast_map::path_mod(ident) | ast_map::path_name(ident) => ident
+            };
+            self.visit(~"class_name", ~[self.c_slice(tcx.sess.str_of(name))]);
             do self.bracketed(~"class", ~[self.c_uint(fields.len())]
                               + self.c_size_and_align(t)) {
                 for fields.eachi |i, field| {
@@ -269,6 +274,15 @@ pub impl reflector {
             do self.bracketed(~"enum",
                               ~[self.c_uint(vec::len(variants))]
                               + self.c_size_and_align(t)) {
+                // The visitor can't find the discriminant of an enum that is
+                // only a pointer without being told which variant null is
+                match nullable_enum_discrims(tcx, t) {
+                  Some((null_disr, _)) => {
+                    self.visit(~"enum_null_discrim",
+                               ~[self.c_int(null_disr)]);
+                  }
+                  None => ()
+                }
                 for variants.eachi |i, v| {
                     do self.bracketed(~"enum_variant",
                                       ~[self.c_uint(i),
@@ -287,7 +301,7 @@ pub impl reflector {
           }
 
           // Miscallaneous extra types
-          ty::ty_trait(_, _, _) => self.leaf(~"trait"),
+          ty::ty_trait(*) => self.leaf(~"trait"),
           ty::ty_infer(_) => self.leaf(~"infer"),
           ty::ty_err => self.leaf(~"err"),
           ty::ty_param(p) => self.visit(~"param", ~[self.c_uint(p.idx)]),
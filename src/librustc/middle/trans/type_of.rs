@@ -181,7 +181,7 @@ pub fn type_of(cx: @crate_ctxt, t: ty::t) -> TypeRef {
       // ty::ty_bare_fn(_) => T_ptr(type_of_fn_from_ty(cx, t)),
       ty::ty_bare_fn(_) => T_fn_pair(cx, type_of_fn_from_ty(cx, t)),
       ty::ty_closure(_) => T_fn_pair(cx, type_of_fn_from_ty(cx, t)),
-      ty::ty_trait(_, _, vstore) => T_opaque_trait(cx, vstore),
+      ty::ty_trait(_, _, vstore, _) => T_opaque_trait(cx, vstore),
       ty::ty_type => T_ptr(cx.tydesc_type),
       ty::ty_tup(elts) => {
         let mut tys = ~[];
@@ -244,7 +244,11 @@ pub fn fill_type_of_enum(cx: @crate_ctxt, did: ast::def_id, t: ty::t,
     let lltys = {
         let degen = ty::enum_is_univariant(cx.tcx, did);
         let size = machine::static_size_of_enum(cx, t);
-        if !degen {
+        if nullable_enum_discrims(cx.tcx, t).is_some() {
+            // The pointer is the discriminant
+            ~[T_ptr(T_i8())]
+        }
+        else if !degen {
             ~[T_enum_discrim(cx), T_array(T_i8(), size)]
         }
         else if size == 0u {
@@ -202,7 +202,7 @@ pub fn type_needs_inner(cx: ctx,
                 ty::ty_bare_fn(*) |
                 ty::ty_ptr(_) |
                 ty::ty_rptr(_, _) |
-                ty::ty_trait(_, _, _) => false,
+                ty::ty_trait(*) => false,
 
               ty::ty_enum(did, ref substs) => {
                 if option::is_none(&list::find(enums_seen, |id| *id == did)) {
@@ -349,7 +349,7 @@ pub fn mark_for_expr(cx: ctx, e: @expr) {
       expr_unary(_, _) | expr_lit(_) | expr_assert(_) |
       expr_mac(_) | expr_addr_of(_, _) |
       expr_ret(_) | expr_loop(_, _) |
-      expr_loop_body(_) | expr_do_body(_) => ()
+      expr_loop_body(*) | expr_do_body(_) => ()
     }
 }
 
@@ -25,7 +25,7 @@ use middle;
 use session::Session;
 use util::ppaux::{note_and_explain_region, bound_region_to_str};
 use util::ppaux::{region_to_str, explain_region, vstore_to_str};
-use util::ppaux::{ty_to_str, tys_to_str};
+use util::ppaux::{object_bounds_to_str, ty_to_str, tys_to_str};
 
 use core::cast;
 use core::cmp;
@@ -82,6 +82,18 @@ pub struct mt {
     mutbl: ast::mutability,
 }
 
+/// The builtin kinds that a trait object promises, on top of those every
+/// object has: `~Writer:Owned` may be sent to another task
+#[deriving_eq]
+pub struct ObjectBounds {
+    owned: bool,
+    const_: bool,
+}
+
+pub fn no_object_bounds() -> ObjectBounds {
+    ObjectBounds { owned: false, const_: false }
+}
+
 #[auto_encode]
 #[auto_decode]
 pub enum vstore {
@@ -286,7 +298,11 @@ struct ctxt_ {
     destructors: HashMap<ast::def_id, ()>,
 
     // Maps a trait onto a mapping from self-ty to impl
-    trait_impls: HashMap<ast::def_id, HashMap<t, @Impl>>
+    trait_impls: HashMap<ast::def_id, HashMap<t, @Impl>>,
+
+    // The `mut` locals and arguments that are assigned to or mutably
+    // borrowed somewhere. Filled in by borrowck, for the unused_mut lint.
+    used_mut_nodes: HashMap<node_id, ()>
 }
 
 enum tbox_flag {
@@ -499,7 +515,7 @@ pub enum sty {
     ty_rec(~[field]),
     ty_bare_fn(BareFnTy),
     ty_closure(ClosureTy),
-    ty_trait(def_id, substs, vstore),
+    ty_trait(def_id, substs, vstore, ObjectBounds),
     ty_struct(def_id, substs),
     ty_tup(~[t]),
 
@@ -558,6 +574,7 @@ pub enum type_err {
     terr_regions_insufficiently_polymorphic(bound_region, Region),
     terr_regions_overly_polymorphic(bound_region, Region),
     terr_vstores_differ(terr_vstore_kind, expected_found<vstore>),
+    terr_object_bounds(expected_found<ObjectBounds>),
     terr_in_field(@type_err, ast::ident),
     terr_sorts(expected_found<t>),
     terr_self_substs,
@@ -866,7 +883,8 @@ pub fn mk_ctxt(s: session::Session,
         supertraits: HashMap(),
         destructor_for_type: HashMap(),
         destructors: HashMap(),
-        trait_impls: HashMap()
+        trait_impls: HashMap(),
+        used_mut_nodes: HashMap()
      }
 }
 
@@ -914,7 +932,7 @@ fn mk_t_with_id(cx: ctxt, +st: sty, o_def_id: Option<ast::def_id>) -> t {
       &ty_infer(_) => flags |= needs_infer as uint,
       &ty_self => flags |= has_self as uint,
       &ty_enum(_, ref substs) | &ty_struct(_, ref substs) |
-      &ty_trait(_, ref substs, _) => {
+      &ty_trait(_, ref substs, _, _) => {
         flags |= sflags(substs);
       }
       &ty_box(ref m) | &ty_uniq(ref m) | &ty_evec(ref m, _) |
@@ -1070,10 +1088,10 @@ pub fn mk_ctor_fn(cx: ctxt, input_tys: &[ty::t], output: ty::t) -> t {
 }
 
 
-pub fn mk_trait(cx: ctxt, did: ast::def_id, +substs: substs, vstore: vstore)
-         -> t {
+pub fn mk_trait(cx: ctxt, did: ast::def_id, +substs: substs, vstore: vstore,
+                bounds: ObjectBounds) -> t {
     // take a copy of substs so that we own the vectors inside
-    mk_t(cx, ty_trait(did, substs, vstore))
+    mk_t(cx, ty_trait(did, substs, vstore, bounds))
 }
 
 pub fn mk_struct(cx: ctxt, struct_id: ast::def_id, +substs: substs) -> t {
@@ -1178,7 +1196,7 @@ pub fn maybe_walk_ty(ty: t, f: fn(t) -> bool) {
         maybe_walk_ty(tm.ty, f);
       }
       ty_enum(_, ref substs) | ty_struct(_, ref substs) |
-      ty_trait(_, ref substs, _) => {
+      ty_trait(_, ref substs, _, _) => {
         for (*substs).tps.each |subty| { maybe_walk_ty(*subty, f); }
       }
       ty_rec(fields) => {
@@ -1238,8 +1256,8 @@ fn fold_sty(sty: &sty, fldop: fn(t) -> t) -> sty {
         ty_enum(tid, ref substs) => {
             ty_enum(tid, fold_substs(substs, fldop))
         }
-        ty_trait(did, ref substs, vst) => {
-            ty_trait(did, fold_substs(substs, fldop), vst)
+        ty_trait(did, ref substs, vst, bounds) => {
+            ty_trait(did, fold_substs(substs, fldop), vst, bounds)
         }
         ty_rec(fields) => {
             let new_fields = do vec::map(fields) |fl| {
@@ -1337,8 +1355,9 @@ pub fn fold_regions_and_ty(
       ty_struct(def_id, ref substs) => {
         ty::mk_struct(cx, def_id, fold_substs(substs, fldr, fldt))
       }
-      ty_trait(def_id, ref substs, vst) => {
-        ty::mk_trait(cx, def_id, fold_substs(substs, fldr, fldt), vst)
+      ty_trait(def_id, ref substs, vst, bounds) => {
+        ty::mk_trait(cx, def_id, fold_substs(substs, fldr, fldt), vst,
+                     bounds)
       }
       ty_bare_fn(ref f) => {
           ty::mk_bare_fn(cx, BareFnTy {sig: fold_sig(&f.sig, fldfnt),
@@ -1644,10 +1663,10 @@ pub fn type_needs_drop(cx: ctxt, ty: t) -> bool {
       ty_evec(_, vstore_uniq) |
       ty_evec(_, vstore_box) => true,
 
-      ty_trait(_, _, vstore_box) |
-      ty_trait(_, _, vstore_uniq) => true,
-      ty_trait(_, _, vstore_fixed(_)) |
-      ty_trait(_, _, vstore_slice(_)) => false,
+      ty_trait(_, _, vstore_box, _) |
+      ty_trait(_, _, vstore_uniq, _) => true,
+      ty_trait(_, _, vstore_fixed(_), _) |
+      ty_trait(_, _, vstore_slice(_), _) => false,
 
       ty_param(*) | ty_infer(*) | ty_err => true,
 
@@ -1912,7 +1931,7 @@ pub pure fn kind_is_durable(k: Kind) -> bool {
     *k & KIND_MASK_DURABLE == KIND_MASK_DURABLE
 }
 
-pure fn kind_is_const(k: Kind) -> bool {
+pub pure fn kind_is_const(k: Kind) -> bool {
     *k & KIND_MASK_CONST == KIND_MASK_CONST
 }
 
@@ -1928,8 +1947,10 @@ fn closure_kind(cty: &ClosureTy) -> Kind {
         _ => kind - kind_owned_only() - kind_durable()
     };
 
+    // A once closure must not be copied, even explicitly: each copy could
+    // be called and move out of the same upvars.
     match cty.onceness {
-        ast::Once => kind - kind_implicitly_copyable(),
+        ast::Once => kind - kind_implicitly_copyable() - kind_copyable(),
         ast::Many => kind
     }
 }
@@ -2020,8 +2041,16 @@ pub fn type_kind_ext(cx: ctxt, ty: t, allow_ty_var: bool) -> Kind {
         remove_owned(mutable_type_kind(cx, tm) | kind_safe_for_default_mode())
       }
 
-      // XXX: This is wrong for ~Trait and &Trait!
-      ty_trait(_, _, _) => kind_safe_for_default_mode() | kind_durable(),
+      // XXX: This is wrong for &Trait!
+      // An object is only owned or const if its type says so; the cast
+      // that made it checked the concrete type; see
+      // kind::check_kind_bounds_of_cast.
+      ty_trait(_, _, _, bounds) => {
+        let mut kind = kind_safe_for_default_mode() | kind_durable();
+        if bounds.owned { kind = kind | kind_owned_only(); }
+        if bounds.const_ { kind = kind | kind_const(); }
+        kind
+      }
 
       // Static region pointers are copyable and sendable, but not owned
       ty_rptr(re_static, mt) =>
@@ -2303,7 +2332,7 @@ pub fn is_instantiable(cx: ctxt, r_ty: t) -> bool {
             }
           }
 
-          ty_trait(_, _, _) => {
+          ty_trait(_, _, _, _) => {
             false
           }
 
@@ -2455,7 +2484,7 @@ pub fn type_is_pod(cx: ctxt, ty: t) -> bool {
       ty_box(_) | ty_uniq(_) | ty_closure(_) |
       ty_estr(vstore_uniq) | ty_estr(vstore_box) |
       ty_evec(_, vstore_uniq) | ty_evec(_, vstore_box) |
-      ty_trait(_, _, _) | ty_rptr(_,_) | ty_opaque_box => result = false,
+      ty_trait(*) | ty_rptr(_,_) | ty_opaque_box => result = false,
       // Structural types
       ty_enum(did, ref substs) => {
         let variants = enum_variants(cx, did);
@@ -2664,6 +2693,13 @@ impl mt : to_bytes::IterBytes {
     }
 }
 
+impl ObjectBounds : to_bytes::IterBytes {
+    pure fn iter_bytes(&self, +lsb0: bool, f: to_bytes::Cb) {
+          to_bytes::iter_bytes_2(&self.owned,
+                                 &self.const_, lsb0, f)
+    }
+}
+
 impl field : to_bytes::IterBytes {
     pure fn iter_bytes(&self, +lsb0: bool, f: to_bytes::Cb) {
           to_bytes::iter_bytes_2(&self.ident,
@@ -2748,8 +2784,8 @@ impl sty : to_bytes::IterBytes {
           ty_uniq(ref mt) =>
           to_bytes::iter_bytes_2(&19u8, mt, lsb0, f),
 
-          ty_trait(ref did, ref substs, ref v) =>
-          to_bytes::iter_bytes_4(&20u8, did, substs, v, lsb0, f),
+          ty_trait(ref did, ref substs, ref v, ref bounds) =>
+          to_bytes::iter_bytes_5(&20u8, did, substs, v, bounds, lsb0, f),
 
           ty_opaque_closure_ptr(ref ck) =>
           to_bytes::iter_bytes_2(&21u8, ck, lsb0, f),
@@ -2816,6 +2852,16 @@ pub fn ty_closure_sigil(fty: t) -> Sigil {
     }
 }
 
+pub fn ty_closure_onceness(fty: t) -> ast::Onceness {
+    match get(fty).sty {
+        ty_closure(ref f) => f.onceness,
+        ref s => {
+            die!(fmt!("ty_closure_onceness() called on non-closure type: \
+                       %?", s))
+        }
+    }
+}
+
 pub fn ty_fn_purity(fty: t) -> ast::purity {
     match get(fty).sty {
         ty_bare_fn(ref f) => f.purity,
@@ -3402,7 +3448,7 @@ pub fn ty_sort_str(cx: ctxt, t: t) -> ~str {
       ty_rec(_) => ~"record",
       ty_bare_fn(_) => ~"extern fn",
       ty_closure(_) => ~"fn",
-      ty_trait(id, _, _) => fmt!("trait %s", item_path_str(cx, id)),
+      ty_trait(id, _, _, _) => fmt!("trait %s", item_path_str(cx, id)),
       ty_struct(id, _) => fmt!("struct %s", item_path_str(cx, id)),
       ty_tup(_) => ~"tuple",
       ty_infer(TyVar(_)) => ~"inferred type",
@@ -3512,6 +3558,18 @@ pub fn type_err_to_str(cx: ctxt, err: &type_err) -> ~str {
                  vstore_to_str(cx, (*values).expected),
                  vstore_to_str(cx, (*values).found))
         }
+        terr_object_bounds(ref values) => {
+            fn bounds_to_str(bounds: ObjectBounds) -> ~str {
+                if bounds == no_object_bounds() {
+                    ~"no bounds"
+                } else {
+                    fmt!("`%s`", object_bounds_to_str(bounds))
+                }
+            }
+            fmt!("trait object bounds differ: expected %s but found %s",
+                 bounds_to_str(values.expected),
+                 bounds_to_str(values.found))
+        }
         terr_in_field(err, fname) => {
             fmt!("in field `%s`, %s", cx.sess.str_of(fname),
                  type_err_to_str(cx, err))
@@ -3619,7 +3677,7 @@ pub fn trait_supertraits(cx: ctxt,
     let result = dvec::DVec();
     for csearch::get_supertraits(cx, id).each |trait_type| {
         match get(*trait_type).sty {
-            ty_trait(def_id, ref substs, _) => {
+            ty_trait(def_id, ref substs, _, _) => {
                 result.push(InstantiatedTraitRef {
                     def_id: def_id,
                     tpt: { substs: (/*bad*/copy *substs), ty: *trait_type }
@@ -3658,9 +3716,11 @@ pub fn trait_methods(cx: ctxt, id: ast::def_id) -> @~[method] {
 pub fn impl_traits(cx: ctxt, id: ast::def_id, vstore: vstore) -> ~[t] {
     fn vstoreify(cx: ctxt, ty: t, vstore: vstore) -> t {
         match ty::get(ty).sty {
-            ty::ty_trait(_, _, trait_vstore) if vstore == trait_vstore => ty,
-            ty::ty_trait(did, ref substs, _) => {
-                mk_trait(cx, did, (/*bad*/copy *substs), vstore)
+            ty::ty_trait(_, _, trait_vstore, _) if vstore == trait_vstore => {
+                ty
+            }
+            ty::ty_trait(did, ref substs, _, bounds) => {
+                mk_trait(cx, did, (/*bad*/copy *substs), vstore, bounds)
             }
             _ => cx.sess.bug(~"impl_traits: not a trait")
         }
@@ -3690,7 +3750,7 @@ pub fn impl_traits(cx: ctxt, id: ast::def_id, vstore: vstore) -> ~[t] {
 
 pub fn ty_to_def_id(ty: t) -> Option<ast::def_id> {
     match get(ty).sty {
-      ty_trait(id, _, _) | ty_struct(id, _) | ty_enum(id, _) => Some(id),
+      ty_trait(id, _, _, _) | ty_struct(id, _) | ty_enum(id, _) => Some(id),
       _ => None
     }
 }
@@ -202,6 +202,14 @@ pub fn ast_ty_to_ty<AC: ast_conv, RS: region_scope Copy Durable>(
     {
         let tcx = self.tcx();
 
+        // `~Trait:Owned`: the trait, and the kinds the object promises
+        let (a_seq_ty, ast_bounds) = match a_seq_ty.ty.node {
+            ast::ty_bounded(ty, ast_bounds) => {
+                (ast::mt { ty: ty, mutbl: a_seq_ty.mutbl }, Some(ast_bounds))
+            }
+            _ => (a_seq_ty, None)
+        };
+
         match a_seq_ty.ty.node {
             ast::ty_vec(mt) => {
                 let mut mt = ast_mt_to_mt(self, rscope, mt);
@@ -221,7 +229,7 @@ pub fn ast_ty_to_ty<AC: ast_conv, RS: region_scope Copy Durable>(
                             self, rscope,
                             type_def_id, path);
                         match ty::get(result.ty).sty {
-                            ty::ty_trait(trait_def_id, ref substs, _) => {
+                            ty::ty_trait(trait_def_id, ref substs, _, _) => {
                                 match vst {
                                     ty::vstore_box | ty::vstore_slice(*) |
                                     ty::vstore_uniq => {}
@@ -234,8 +242,16 @@ pub fn ast_ty_to_ty<AC: ast_conv, RS: region_scope Copy Durable>(
                                               trait");
                                     }
                                 }
+                                let bounds = match ast_bounds {
+                                    Some(ast_bounds) => {
+                                        ast_object_bounds(self, rscope,
+                                                          ast_bounds, vst)
+                                    }
+                                    None => ty::no_object_bounds()
+                                };
                                 return ty::mk_trait(tcx, trait_def_id,
-                                                    /*bad*/copy *substs, vst);
+                                                    /*bad*/copy *substs, vst,
+                                                    bounds);
 
                             }
                             _ => {}
@@ -247,10 +263,66 @@ pub fn ast_ty_to_ty<AC: ast_conv, RS: region_scope Copy Durable>(
             _ => {}
         }
 
+        if ast_bounds.is_some() {
+            tcx.sess.span_err(a_seq_ty.ty.span,
+                              ~"only trait objects may have bounds");
+        }
+
         let seq_ty = ast_mt_to_mt(self, rscope, a_seq_ty);
         return constr(seq_ty);
     }
 
+    // The builtin kinds after the colon in `~Trait:Owned`. Copy and Durable
+    // are accepted, though every object is already both.
+    fn ast_object_bounds<AC: ast_conv, RS: region_scope Copy Durable>(
+        self: AC,
+        rscope: RS,
+        ast_bounds: @~[ast::ty_param_bound],
+        vst: ty::vstore) -> ty::ObjectBounds
+    {
+        let tcx = self.tcx();
+        let li = &tcx.lang_items;
+        let mut bounds = ty::no_object_bounds();
+        for ast_bounds.each |ast_bound| {
+            match *ast_bound {
+                ast::TraitTyParamBound(b) => {
+                    let ity = ast_ty_to_ty(self, rscope, b);
+                    let did = match ty::get(ity).sty {
+                        ty::ty_trait(did, _, _, _) => Some(did),
+                        _ => None
+                    };
+                    match did {
+                        Some(did) if did == li.owned_trait() => {
+                            // Only a ~ box can carry its contents to another
+                            // task
+                            if vst == ty::vstore_uniq {
+                                bounds.owned = true;
+                            } else {
+                                tcx.sess.span_err(
+                                    b.span,
+                                    ~"only ~ trait objects may be `Owned`");
+                            }
+                        }
+                        Some(did) if did == li.const_trait() => {
+                            bounds.const_ = true;
+                        }
+                        Some(did) if did == li.copy_trait() ||
+                                     did == li.durable_trait() => {}
+                        _ => {
+                            tcx.sess.span_err(
+                                b.span,
+                                ~"trait object bounds must be builtin \
+                                  kinds: `Owned`, `Const`, `Copy` or \
+                                  `Durable`");
+                        }
+                    }
+                }
+                ast::RegionTyParamBound => {}
+            }
+        }
+        bounds
+    }
+
     fn check_path_args(tcx: ty::ctxt,
                        path: @ast::path,
                        flags: uint) {
@@ -399,6 +471,11 @@ pub fn ast_ty_to_ty<AC: ast_conv, RS: region_scope Copy Durable>(
             ast_ty.span,
             ~"found `ty_infer` in unexpected place");
       }
+      ast::ty_bounded(*) => {
+        tcx.sess.span_fatal(ast_ty.span,
+                            ~"bounds may only follow a trait behind `~`, \
+                              `@` or `&`");
+      }
       ast::ty_mac(_) => {
         tcx.sess.span_bug(ast_ty.span,
                           ~"found `ty_mac` in unexpected place");
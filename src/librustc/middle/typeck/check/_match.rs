@@ -22,6 +22,7 @@ use middle::typeck::require_same_types;
 use core::vec;
 use std::oldmap::HashMap;
 use syntax::ast;
+use syntax::ast_map;
 use syntax::ast_util::walk_pat;
 use syntax::ast_util;
 use syntax::codemap::span;
@@ -281,12 +282,39 @@ pub fn check_struct_pat(pcx: pat_ctxt, pat_id: ast::node_id, span: span,
     if ty::has_dtor(tcx, class_id) {
         tcx.sess.span_err(span, ~"deconstructing struct not allowed in \
                                   pattern (it has a destructor)");
+        note_struct_dtor(tcx, span, class_id);
     }
 
     check_struct_pat_fields(pcx, span, path, fields, class_fields, class_id,
                             substitutions, etc);
 }
 
+fn note_struct_dtor(tcx: ty::ctxt, span: span, class_id: ast::def_id) {
+    /*!
+     *
+     * Points at the destructor that keeps a struct from being taken
+     * apart by a pattern, and says what to do instead.
+     */
+
+    let dtor_id = match ty::ty_dtor(tcx, class_id) {
+        ty::TraitDtor(id) | ty::LegacyDtor(id) => id,
+        ty::NoDtor => return
+    };
+    let dtor_span = if dtor_id.crate == ast::local_crate {
+        match tcx.items.find(&dtor_id.node) {
+            Some(ast_map::node_method(m, _, _)) => m.span,
+            Some(ast_map::node_dtor(_, dtor, _, _)) => dtor.span,
+            _ => span
+        }
+    } else {
+        span
+    };
+    tcx.sess.span_note(dtor_span, ~"the destructor needs the struct whole; \
+                                    read its fields with `.` instead, or \
+                                    make them `Option`s and take them with \
+                                    `option::swap_unwrap`");
+}
+
 pub fn check_struct_like_enum_variant_pat(pcx: pat_ctxt,
                                           pat_id: ast::node_id,
                                           span: span,
@@ -284,7 +284,7 @@ pub impl LookupContext {
                 ty_param(p) => {
                     self.push_inherent_candidates_from_param(self_ty, p);
                 }
-                ty_trait(did, ref substs, vstore) => {
+                ty_trait(did, ref substs, vstore, _) => {
                     self.push_inherent_candidates_from_trait(
                         self_ty, did, substs, vstore);
                     self.push_inherent_impl_candidates_for_type(did);
@@ -375,7 +375,7 @@ pub impl LookupContext {
 
 
             let bound_substs = match ty::get(bound_trait_ty).sty {
-                ty::ty_trait(_, ref substs, _) => (/*bad*/copy *substs),
+                ty::ty_trait(_, ref substs, _, _) => (/*bad*/copy *substs),
                 _ => {
                     self.bug(fmt!("add_candidates_from_param: \
                                    non-trait bound %s",
@@ -1152,11 +1152,14 @@ pub impl LookupContext {
             method_trait(*) => {}
         }
 
+        // The object has forgotten its self type, so a method that takes
+        // or returns `Self` has nothing to take or return.
         if ty::type_has_self(method_fty) {
             self.tcx().sess.span_err(
                 self.expr.span,
-                ~"cannot call a method whose type contains a \
-                  self-type through a boxed trait");
+                fmt!("cannot call method `%s` through a boxed trait, \
+                      because its type contains `Self`",
+                     self.tcx().sess.str_of(self.m_name)));
         }
 
         if candidate.num_method_tps > 0 {
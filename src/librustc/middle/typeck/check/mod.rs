@@ -2249,7 +2249,7 @@ pub fn check_expr_with_unifier(fcx: @fn_ctxt,
         bot = check_expr_has_type(fcx, cond, ty::mk_bool(tcx));
         bot |= check_then_else(fcx, *thn, elsopt, id, expr.span);
       }
-      ast::expr_while(cond, ref body) => {
+      ast::expr_while(cond, ref body, _) => {
         bot = check_expr_has_type(fcx, cond, ty::mk_bool(tcx));
         check_block_no_value(fcx, (*body));
         fcx.write_ty(id, ty::mk_nil(tcx));
@@ -2270,7 +2270,7 @@ pub fn check_expr_with_unifier(fcx: @fn_ctxt,
         check_expr_fn(fcx, expr, None,
                       decl, (*body), Vanilla, expected);
       }
-      ast::expr_loop_body(loop_body) => {
+      ast::expr_loop_body(loop_body, _) => {
           check_loop_body(fcx, expr, expected, loop_body);
       }
       ast::expr_do_body(b) => {
@@ -15,8 +15,9 @@ use middle::ty::{param_ty, substs};
 use middle::ty;
 use middle::typeck::check::{fn_ctxt, impl_self_ty};
 use middle::typeck::check::{structurally_resolved_type};
-use middle::typeck::infer::{fixup_err_to_str, InferCtxt};
-use middle::typeck::infer::{resolve_and_force_all_but_regions, resolve_type};
+use middle::typeck::infer::{fixup_err_to_str, force_fvar, force_ivar};
+use middle::typeck::infer::{InferCtxt, resolve_type};
+use middle::typeck::infer::{resolve_and_force_all_but_regions};
 use middle::typeck::infer;
 use middle::typeck::{crate_ctxt, vtable_origin, vtable_param, vtable_res};
 use middle::typeck::{vtable_static, vtable_trait};
@@ -144,10 +145,11 @@ pub fn fixup_substs(vcx: &VtableContext, location_info: &LocationInfo,
                     is_early: bool) -> Option<ty::substs> {
     let tcx = vcx.tcx();
     // use a dummy type just to package up the substs that need fixing up
-    let t = ty::mk_trait(tcx, id, substs, ty::vstore_slice(ty::re_static));
+    let t = ty::mk_trait(tcx, id, substs, ty::vstore_slice(ty::re_static),
+                         ty::no_object_bounds());
     do fixup_ty(vcx, location_info, t, is_early).map |t_f| {
         match ty::get(*t_f).sty {
-          ty::ty_trait(_, ref substs_f, _) => (/*bad*/copy *substs_f),
+          ty::ty_trait(_, ref substs_f, _, _) => (/*bad*/copy *substs_f),
           _ => die!(~"t_f should be a trait")
         }
     }
@@ -173,12 +175,16 @@ pub fn lookup_vtable(vcx: &VtableContext,
 
     let tcx = vcx.tcx();
     let (trait_id, trait_substs, trait_vstore) = match ty::get(trait_ty).sty {
-        ty::ty_trait(did, ref substs, vstore) =>
+        ty::ty_trait(did, ref substs, vstore, _) =>
             (did, (/*bad*/copy *substs), vstore),
         _ => tcx.sess.impossible_case(location_info.span,
                                       "lookup_vtable: \
                                        don't know how to handle a non-trait")
     };
+    // The kinds an object promises don't decide which impl supplies its
+    // vtable; kind.rs checks them against the concrete type
+    let trait_ty = ty::mk_trait(tcx, trait_id, copy trait_substs,
+                                trait_vstore, ty::no_object_bounds());
     let ty = match fixup_ty(vcx, location_info, ty, is_early) {
         Some(ty) => ty,
         None => {
@@ -201,7 +207,7 @@ pub fn lookup_vtable(vcx: &VtableContext,
                        vcx.infcx.ty_to_str(ity));
 
                 match ty::get(ity).sty {
-                    ty::ty_trait(idid, _, _) => {
+                    ty::ty_trait(idid, _, _, _) => {
                         if trait_id == idid {
                             debug!("(checking vtable) @0 \
                                     relating ty to trait \
@@ -226,10 +232,12 @@ pub fn lookup_vtable(vcx: &VtableContext,
             }
         }
 
-        ty::ty_trait(did, ref substs, _) if trait_id == did => {
+        ty::ty_trait(did, ref substs, vstore, _) if trait_id == did => {
             debug!("(checking vtable) @1 relating ty to trait ty with did %?",
                    did);
 
+            let ty = ty::mk_trait(tcx, did, copy *substs, vstore,
+                                  ty::no_object_bounds());
             relate_trait_tys(vcx, location_info, trait_ty, ty);
             if !allow_unsafe && !is_early {
                 for vec::each(*ty::trait_methods(tcx, did)) |m| {
@@ -293,7 +301,7 @@ pub fn lookup_vtable(vcx: &VtableContext,
                         for vec::each(ty::impl_traits(tcx, im.did,
                                                       trait_vstore)) |of_ty| {
                             match ty::get(*of_ty).sty {
-                                ty::ty_trait(id, _, _) => {
+                                ty::ty_trait(id, _, _, _) => {
                                     // Not the trait we're looking for
                                     if id != trait_id { loop; }
                                 }
@@ -450,7 +458,16 @@ pub fn fixup_ty(vcx: &VtableContext,
                 ty: ty::t,
                 is_early: bool) -> Option<ty::t> {
     let tcx = vcx.tcx();
-    match resolve_type(vcx.infcx, ty, resolve_and_force_all_but_regions) {
+    // Early resolution happens before the whole fn has been checked, so a
+    // numeric literal whose type is still open must not be defaulted to
+    // `int` or `float` yet: a later expression may still pin it down.
+    let modes = if is_early {
+        resolve_and_force_all_but_regions & !(force_ivar | force_fvar)
+    } else {
+        resolve_and_force_all_but_regions
+    };
+    match resolve_type(vcx.infcx, ty, modes) {
+        Ok(new_type) if is_early && type_has_numeric_var(new_type) => None,
         Ok(new_type) => Some(new_type),
         Err(e) if !is_early => {
             tcx.sess.span_fatal(
@@ -465,6 +482,19 @@ pub fn fixup_ty(vcx: &VtableContext,
     }
 }
 
+fn type_has_numeric_var(ty: ty::t) -> bool {
+    let mut found = false;
+    do ty::walk_ty(ty) |t| {
+        match ty::get(t).sty {
+            ty::ty_infer(ty::IntVar(_)) | ty::ty_infer(ty::FloatVar(_)) => {
+                found = true;
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
 // Version of demand::suptype() that takes a vtable context instead of a
 // function context.
 pub fn demand_suptype(vcx: &VtableContext, sp: span, e: ty::t, a: ty::t) {
@@ -491,7 +521,7 @@ pub fn connect_trait_tps(vcx: &VtableContext,
     debug!("(connect trait tps) trait type is %?, impl did is %?",
            ty::get(trait_ty).sty, impl_did);
     match ty::get(trait_ty).sty {
-     ty::ty_trait(_, ref substs, _) => {
+     ty::ty_trait(_, ref substs, _, _) => {
          for vec::each2((*substs).tps, trait_tys) |a, b| {
             demand_suptype(vcx, location_info.span, *a, *b);
          }
@@ -578,7 +608,7 @@ pub fn early_resolve_expr(ex: @ast::expr, &&fcx: @fn_ctxt, is_early: bool) {
       ast::expr_cast(src, _) => {
         let target_ty = fcx.expr_ty(ex);
         match ty::get(target_ty).sty {
-          ty::ty_trait(_, _, vstore) => {
+          ty::ty_trait(_, _, vstore, _) => {
             // Look up vtables for the type we're casting to, passing in the
             // source and target type.
             //
@@ -131,7 +131,7 @@ pub fn get_base_type_def_id(inference_context: @InferCtxt,
             match get(base_type).sty {
                 ty_enum(def_id, _) |
                 ty_struct(def_id, _) |
-                ty_trait(def_id, _, _) => {
+                ty_trait(def_id, _, _, _) => {
                     return Some(def_id);
                 }
                 _ => {
@@ -741,9 +741,12 @@ pub impl CoherenceChecker {
                                 all_provided_methods: ~[@ProvidedMethodInfo],
                                 sess: driver::session::Session) {
             for all_provided_methods.each |provided_method| {
+                let ident = provided_method.method_info.ident;
+                // The impl's own definition overrides the default
+                if vec::any(*all_methods, |m| m.ident == ident) { loop; }
                 debug!(
                     "(creating impl) adding provided method `%s` to impl",
-                    sess.str_of(provided_method.method_info.ident));
+                    sess.str_of(ident));
                 vec::push(&mut *all_methods, provided_method.method_info);
             }
         }
@@ -765,31 +768,26 @@ pub impl CoherenceChecker {
                         trait_ref.path.span);
                 }
 
-                // For each trait that the impl implements, see which
-                // methods are provided.  For each of those methods,
-                // if a method of that name is not inherent to the
-                // impl, use the provided definition in the trait.
-                for trait_refs.each |trait_ref| {
-                    let trait_did =
-                        self.trait_ref_to_trait_def_id(*trait_ref);
-
-                    match self.crate_context.tcx
-                              .provided_methods
-                              .find(&local_def(item.id)) {
-                        None => {
-                            debug!("(creating impl) trait with node_id `%d` \
-                                    has no provided methods", trait_did.node);
-                            /* fall through */
-                        }
-                        Some(all_provided) => {
-                            debug!("(creating impl) trait with node_id `%d` \
-                                    has provided methods", trait_did.node);
-                            // Add all provided methods.
-                            add_provided_methods(
-                                &mut methods,
-                                all_provided.get(),
-                                self.crate_context.tcx.sess);
-                        }
+                // The provided methods of every trait that the impl
+                // implements have been gathered under the impl's ID. For
+                // each of those methods, if a method of that name is not
+                // inherent to the impl, use the provided definition in the
+                // trait.
+                match self.crate_context.tcx
+                          .provided_methods
+                          .find(&local_def(item.id)) {
+                    None => {
+                        debug!("(creating impl) impl with node_id `%d` \
+                                has no provided methods", item.id);
+                        /* fall through */
+                    }
+                    Some(all_provided) => {
+                        debug!("(creating impl) impl with node_id `%d` \
+                                has provided methods", item.id);
+                        add_provided_methods(
+                            &mut methods,
+                            all_provided.get(),
+                            self.crate_context.tcx.sess);
                     }
                 }
 
@@ -877,7 +875,7 @@ pub impl CoherenceChecker {
             // Record all the trait methods.
             for associated_traits.each |trait_type| {
                 match get(*trait_type).sty {
-                    ty_trait(trait_id, _, _) => {
+                    ty_trait(trait_id, _, _, _) => {
                         self.add_trait_method(trait_id, *implementation);
                     }
                     _ => {
@@ -917,6 +915,7 @@ pub impl CoherenceChecker {
 
         debug!("(adding default methods for trait) processing trait");
 
+        let method_infos = @DVec();
         for csearch::get_provided_trait_methods(tcx, trait_def_id).each
                                                 |trait_method_info| {
             debug!("(adding default methods for trait) found default method");
@@ -936,10 +935,9 @@ pub impl CoherenceChecker {
                     trait_method_def_id: trait_method_info.def_id
                 };
 
-            let method_infos = @DVec();
             method_infos.push(provided_method_info);
-            pmm.insert(trait_def_id, method_infos);
         }
+        pmm.insert(trait_def_id, method_infos);
     }
 
     // Adds implementations and traits from external crates to the coherence
@@ -84,7 +84,8 @@ pub fn collect_item_types(ccx: @crate_ctxt, crate: @ast::crate) {
                     match intrinsic_item.node {
                       ast::item_trait(*) => {
                         let ty = ty::mk_trait(ccx.tcx, def_id, substs,
-                                              ty::vstore_box);
+                                              ty::vstore_box,
+                                              ty::no_object_bounds());
                         ccx.tcx.intrinsic_defs.insert
                             (intrinsic_item.ident, (def_id, ty));
                       }
@@ -849,7 +850,8 @@ pub fn ty_of_item(ccx: @crate_ctxt, it: @ast::item)
       }
       ast::item_trait(tps, _, _) => {
         let {bounds: bounds, substs: substs} = mk_substs(ccx, tps, rp);
-        let t = ty::mk_trait(tcx, local_def(it.id), substs, ty::vstore_box);
+        let t = ty::mk_trait(tcx, local_def(it.id), substs, ty::vstore_box,
+                             ty::no_object_bounds());
         let tpt = {bounds: bounds,
                    region_param: rp,
                    ty: t};
@@ -901,7 +903,7 @@ pub fn compute_bounds(ccx: @crate_ctxt,
                 let li = &ccx.tcx.lang_items;
                 let ity = ast_ty_to_ty(ccx, empty_rscope, b);
                 match ty::get(ity).sty {
-                    ty::ty_trait(did, _, _) => {
+                    ty::ty_trait(did, _, _, _) => {
                         if did == li.owned_trait() {
                             ~[ty::bound_owned]
                         } else if did == li.copy_trait() {
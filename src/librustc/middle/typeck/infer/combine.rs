@@ -495,12 +495,16 @@ pub fn super_tys<C:Combine>(
         }
       }
 
-      (ty::ty_trait(a_id, ref a_substs, a_vstore),
-       ty::ty_trait(b_id, ref b_substs, b_vstore))
+      (ty::ty_trait(a_id, ref a_substs, a_vstore, a_bounds),
+       ty::ty_trait(b_id, ref b_substs, b_vstore, b_bounds))
       if a_id == b_id => {
+        if a_bounds != b_bounds {
+            return Err(ty::terr_object_bounds(
+                expected_found(self, a_bounds, b_bounds)));
+        }
         do self.substs(a_id, a_substs, b_substs).chain |substs| {
             do self.vstores(ty::terr_trait, a_vstore, b_vstore).chain |vs| {
-                Ok(ty::mk_trait(tcx, a_id, /*bad*/copy substs, vs))
+                Ok(ty::mk_trait(tcx, a_id, /*bad*/copy substs, vs, a_bounds))
             }
         }
       }
@@ -247,7 +247,7 @@ use core::prelude::*;
 pub use middle::ty::IntVarValue;
 pub use middle::typeck::infer::resolve::resolve_and_force_all_but_regions;
 pub use middle::typeck::infer::resolve::{force_all, not_regions};
-pub use middle::typeck::infer::resolve::{force_ivar};
+pub use middle::typeck::infer::resolve::{force_fvar, force_ivar};
 pub use middle::typeck::infer::resolve::{force_tvar, force_rvar};
 pub use middle::typeck::infer::resolve::{resolve_ivar, resolve_all};
 pub use middle::typeck::infer::resolve::{resolve_nested_tvar};
@@ -253,6 +253,13 @@ pub fn vstore_ty_to_str(cx: ctxt, ty: ~str, vs: ty::vstore) -> ~str {
     }
 }
 
+pub fn object_bounds_to_str(bounds: ty::ObjectBounds) -> ~str {
+    let mut names = ~[];
+    if bounds.owned { names.push(~"Owned"); }
+    if bounds.const_ { names.push(~"Const"); }
+    if names.is_empty() { ~"" } else { ~":" + str::connect(names, ~"+") }
+}
+
 pub fn expr_repr(cx: ctxt, expr: @ast::expr) -> ~str {
     fmt!("expr(%d: %s)",
          expr.id,
@@ -449,11 +456,11 @@ pub fn ty_to_str(cx: ctxt, typ: t) -> ~str {
         let base = ast_map::path_to_str(path, cx.sess.intr());
         parameterized(cx, base, substs.self_r, substs.tps)
       }
-      ty_trait(did, ref substs, vs) => {
+      ty_trait(did, ref substs, vs, bounds) => {
         let path = ty::item_path(cx, did);
         let base = ast_map::path_to_str(path, cx.sess.intr());
         let result = parameterized(cx, base, substs.self_r, substs.tps);
-        vstore_ty_to_str(cx, result, vs)
+        vstore_ty_to_str(cx, result, vs) + object_bounds_to_str(bounds)
       }
       ty_evec(mt, vs) => {
         vstore_ty_to_str(cx, fmt!("[%s]", mt_to_str(cx, mt)), vs)
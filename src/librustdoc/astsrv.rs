@@ -133,8 +133,13 @@ fn build_ctxt(sess: Session,
     use rustc::front::config;
 
     let ast = config::strip_unconfigured_items(ast);
+    // Documenting a crate doesn't need its dependencies, so one that
+    // can't be found just provides no macros
     let ast = syntax::ext::expand::expand_crate(sess.parse_sess,
-                                                copy sess.opts.cfg, ast);
+                                                copy sess.opts.cfg,
+                                                driver::macro_loader(sess,
+                                                                     false),
+                                                ast);
     let ast = front::test::modify_for_testing(sess, ast);
     let ast_map = ast_map::map_crate(sess.diagnostic(), *ast);
 
@@ -121,7 +121,8 @@ pub fn mock_program_output(_prog: &str, _args: &[~str]) -> ProcOut {
 }
 
 pub fn program_output(prog: &str, args: &[~str]) -> ProcOut {
-    let {status, out, err} = run::program_output(prog, args);
+    let run::ProgramOutput {status: status, out: out, err: err} =
+        run::program_output(prog, args);
     ProcOut {
         status: status,
         out: out,
@@ -0,0 +1,156 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Hexadecimal (base16) encoding, as described in RFC 4648
+
+use base_codec::Codec;
+use base_codec;
+
+use core::io::{Reader, Writer};
+use core::prelude::*;
+use core::str;
+
+pub use base_codec::{DecodeConfig, DecodeError, InvalidChar, InvalidLength};
+pub use base_codec::{CHUNK_SIZE, LENIENT, STRICT};
+
+// Encodes in lower case and decodes either case
+const HEX: Codec = Codec {
+    alphabet: "0123456789abcdef",
+    bits: 4u,
+    padded: false,
+    fold_case: true,
+};
+
+pub trait ToHex {
+    pure fn to_hex() -> ~str;
+}
+
+impl &[u8]: ToHex {
+    pure fn to_hex() -> ~str {
+        // unsafe only because the encoder keeps its state in mutable fields
+        unsafe { base_codec::encode(HEX, self) }
+    }
+}
+
+impl &str: ToHex {
+    pure fn to_hex() -> ~str {
+        str::to_bytes(self).to_hex()
+    }
+}
+
+pub trait FromHex {
+    fn from_hex() -> Result<~[u8], DecodeError>;
+}
+
+impl &str: FromHex {
+    /// Decode hex digits of either case, with no whitespace allowed
+    fn from_hex() -> Result<~[u8], DecodeError> {
+        decode(str::to_bytes(self), &STRICT)
+    }
+}
+
+/**
+ * Decode hexadecimal `input`, reporting where it is malformed
+ *
+ * Digits may be in either case. With `STRICT` nothing else is accepted;
+ * `LENIENT` also skips whitespace.
+ */
+pub fn decode(input: &[u8], config: &DecodeConfig)
+    -> Result<~[u8], DecodeError> {
+    base_codec::decode(HEX, input, config)
+}
+
+/**
+ * Write everything `rdr` yields to `wr` as hexadecimal
+ *
+ * The input is read and encoded `CHUNK_SIZE` bytes at a time, so it is
+ * never held in memory all at once. The output is the same as `to_hex`
+ * gives.
+ */
+pub fn encode_stream(rdr: Reader, wr: Writer) {
+    base_codec::encode_stream(HEX, rdr, wr)
+}
+
+/**
+ * Decode the hexadecimal that `rdr` yields, writing the bytes to `wr`
+ *
+ * Like `encode_stream`, this works through the input a chunk at a time.
+ * If the input is malformed, the bytes before the chunk holding the error
+ * have already been written.
+ */
+pub fn decode_stream(rdr: Reader, wr: Writer, config: &DecodeConfig)
+    -> Result<(), DecodeError> {
+    base_codec::decode_stream(HEX, rdr, wr, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::prelude::*;
+
+    use base16::*;
+    use base16::HEX;
+    use base_codec;
+
+    use core::io;
+    use core::str;
+
+    #[test]
+    fn test_to_hex() {
+        // RFC 4648 section 10, in lower case
+        assert (~"").to_hex() == ~"";
+        assert (~"f").to_hex() == ~"66";
+        assert (~"fo").to_hex() == ~"666f";
+        assert (~"foo").to_hex() == ~"666f6f";
+        assert (~"foob").to_hex() == ~"666f6f62";
+        assert (~"fooba").to_hex() == ~"666f6f6261";
+        assert (~"foobar").to_hex() == ~"666f6f626172";
+        assert (&[0u8, 9u8, 10u8, 255u8]).to_hex() == ~"00090aff";
+    }
+
+    #[test]
+    fn test_from_hex() {
+        assert (~"").from_hex() == Ok(~[]);
+        assert (~"666F6F626172").from_hex() == Ok(str::to_bytes("foobar"));
+        assert (~"666f6f626172").from_hex() == Ok(str::to_bytes("foobar"));
+        assert (~"00090aFF").from_hex() == Ok(~[0u8, 9u8, 10u8, 255u8]);
+    }
+
+    #[test]
+    fn test_from_hex_errors() {
+        assert (~"0g").from_hex() == Err(InvalidChar(1u, 'g'));
+        assert (~"abc").from_hex() == Err(InvalidLength(3u));
+        assert (~"ab cd").from_hex() == Err(InvalidChar(2u, ' '));
+        // hex has no padding
+        assert (~"a=").from_hex() == Err(InvalidChar(1u, '='));
+
+        assert decode(str::to_bytes("ab cd\n"), &LENIENT) ==
+            Ok(~[0xABu8, 0xCDu8]);
+        assert decode(str::to_bytes("a b c"), &LENIENT) ==
+            Err(InvalidLength(5u));
+    }
+
+    #[test]
+    fn test_stream_round_trip() {
+        base_codec::test::check_stream_round_trip(HEX, [0u, 1u, 2u]);
+    }
+
+    #[test]
+    fn test_decode_stream_error_offset() {
+        let mut input = str::to_bytes(str::repeat("ab", 5000u));
+        input[9001] = 'x' as u8;
+        let mut result = Ok(());
+        do io::with_bytes_writer |wr| {
+            do io::with_bytes_reader(input) |rdr| {
+                result = decode_stream(rdr, wr, &STRICT);
+            }
+        };
+        assert result == Err(InvalidChar(9001u, 'x'));
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Base32 encoding with the standard alphabet of RFC 4648
+
+use base_codec::Codec;
+use base_codec;
+
+use core::io::{Reader, Writer};
+use core::prelude::*;
+use core::str;
+
+pub use base_codec::{DecodeConfig, DecodeError, InvalidChar, InvalidLength};
+pub use base_codec::{CHUNK_SIZE, LENIENT, STRICT};
+
+const BASE32: Codec = Codec {
+    alphabet: "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+    bits: 5u,
+    padded: true,
+    fold_case: false,
+};
+
+pub trait ToBase32 {
+    pure fn to_base32() -> ~str;
+}
+
+impl &[u8]: ToBase32 {
+    pure fn to_base32() -> ~str {
+        // unsafe only because the encoder keeps its state in mutable fields
+        unsafe { base_codec::encode(BASE32, self) }
+    }
+}
+
+impl &str: ToBase32 {
+    pure fn to_base32() -> ~str {
+        str::to_bytes(self).to_base32()
+    }
+}
+
+pub trait FromBase32 {
+    fn from_base32() -> Result<~[u8], DecodeError>;
+}
+
+impl &str: FromBase32 {
+    /// Decode padded base32 with no whitespace
+    fn from_base32() -> Result<~[u8], DecodeError> {
+        decode(str::to_bytes(self), &STRICT)
+    }
+}
+
+/**
+ * Decode base32 `input`, reporting where it is malformed
+ *
+ * With `STRICT` only padded output of `to_base32` is accepted;
+ * `LENIENT` also skips whitespace and allows the padding to be left off.
+ */
+pub fn decode(input: &[u8], config: &DecodeConfig)
+    -> Result<~[u8], DecodeError> {
+    base_codec::decode(BASE32, input, config)
+}
+
+/**
+ * Write everything `rdr` yields to `wr` as base32
+ *
+ * The input is read and encoded `CHUNK_SIZE` bytes at a time, so it is
+ * never held in memory all at once. The output is the same as `to_base32`
+ * gives.
+ */
+pub fn encode_stream(rdr: Reader, wr: Writer) {
+    base_codec::encode_stream(BASE32, rdr, wr)
+}
+
+/**
+ * Decode the base32 that `rdr` yields, writing the bytes to `wr`
+ *
+ * Like `encode_stream`, this works through the input a chunk at a time.
+ * If the input is malformed, the bytes before the chunk holding the error
+ * have already been written.
+ */
+pub fn decode_stream(rdr: Reader, wr: Writer, config: &DecodeConfig)
+    -> Result<(), DecodeError> {
+    base_codec::decode_stream(BASE32, rdr, wr, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::prelude::*;
+
+    use base32::*;
+    use base32::BASE32;
+    use base_codec;
+
+    use core::str;
+
+    // RFC 4648 section 10
+    const vectors: &[(&str, &str)] = &[
+        ("", ""),
+        ("f", "MY======"),
+        ("fo", "MZXQ===="),
+        ("foo", "MZXW6==="),
+        ("foob", "MZXW6YQ="),
+        ("fooba", "MZXW6YTB"),
+        ("foobar", "MZXW6YTBOI======"),
+    ];
+
+    #[test]
+    fn test_to_base32() {
+        for vectors.each |v| {
+            let (plain, encoded) = *v;
+            assert plain.to_base32() == encoded.to_owned();
+        }
+    }
+
+    #[test]
+    fn test_from_base32() {
+        for vectors.each |v| {
+            let (plain, encoded) = *v;
+            assert encoded.from_base32() == Ok(str::to_bytes(plain));
+        }
+    }
+
+    #[test]
+    fn test_from_base32_errors() {
+        assert (~"MZXW6YT1").from_base32() == Err(InvalidChar(7u, '1'));
+        assert (~"mzxw6ytb").from_base32() == Err(InvalidChar(0u, 'm'));
+        // no padding
+        assert (~"MZXW6").from_base32() == Err(InvalidLength(5u));
+        // truncated padding
+        assert (~"MZXW6==").from_base32() == Err(InvalidLength(7u));
+        // three characters can't end the input, so can't be padded
+        assert (~"MZX=====").from_base32() == Err(InvalidChar(3u, '='));
+        // nothing may follow the padding
+        assert (~"MY======MY======").from_base32() ==
+            Err(InvalidChar(8u, 'M'));
+        assert (~"MZXW6===\n").from_base32() == Err(InvalidChar(8u, '\n'));
+    }
+
+    #[test]
+    fn test_lenient() {
+        assert decode(str::to_bytes("MZXW6"), &LENIENT) ==
+            Ok(str::to_bytes("foo"));
+        assert decode(str::to_bytes("MZXW\r\n6YTB\r\nOI"), &LENIENT) ==
+            Ok(str::to_bytes("foobar"));
+        assert decode(str::to_bytes(" MZXW6=== "), &LENIENT) ==
+            Ok(str::to_bytes("foo"));
+        // whitespace counts towards the offset
+        assert decode(str::to_bytes("MZ XW\n6YT8"), &LENIENT) ==
+            Err(InvalidChar(9u, '8'));
+        assert decode(str::to_bytes("MZX"), &LENIENT) ==
+            Err(InvalidLength(3u));
+    }
+
+    #[test]
+    fn test_stream_round_trip() {
+        base_codec::test::check_stream_round_trip(BASE32, [0u, 1u, 4u, 5u, 6u]);
+    }
+}
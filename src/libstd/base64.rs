@@ -8,10 +8,23 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use core::io::Reader;
-use core::iter;
+use base_codec::Codec;
+use base_codec;
+
+use core::io::{Reader, Writer};
+use core::prelude::*;
 use core::str;
-use core::vec;
+
+pub use base_codec::{DecodeConfig, DecodeError, InvalidChar, InvalidLength};
+pub use base_codec::{CHUNK_SIZE, LENIENT, STRICT};
+
+const BASE64: Codec = Codec {
+    alphabet:
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+    bits: 6u,
+    padded: true,
+    fold_case: false,
+};
 
 pub trait ToBase64 {
     pure fn to_base64() -> ~str;
@@ -19,54 +32,8 @@ pub trait ToBase64 {
 
 impl &[u8]: ToBase64 {
     pure fn to_base64() -> ~str {
-        let chars = str::chars(
-          ~"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/"
-        );
-
-        let mut s = ~"";
-        unsafe {
-            let len = self.len();
-            str::reserve(&mut s, ((len + 3u) / 4u) * 3u);
-
-            let mut i = 0u;
-
-            while i < len - (len % 3u) {
-                let n = (self[i] as uint) << 16u |
-                        (self[i + 1u] as uint) << 8u |
-                        (self[i + 2u] as uint);
-
-                // This 24-bit number gets separated into four 6-bit numbers.
-                str::push_char(&mut s, chars[(n >> 18u) & 63u]);
-                str::push_char(&mut s, chars[(n >> 12u) & 63u]);
-                str::push_char(&mut s, chars[(n >> 6u) & 63u]);
-                str::push_char(&mut s, chars[n & 63u]);
-
-                i += 3u;
-            }
-
-            // Heh, would be cool if we knew this was exhaustive
-            // (the dream of bounded integer types)
-            match len % 3 {
-              0 => (),
-              1 => {
-                let n = (self[i] as uint) << 16u;
-                str::push_char(&mut s, chars[(n >> 18u) & 63u]);
-                str::push_char(&mut s, chars[(n >> 12u) & 63u]);
-                str::push_char(&mut s, '=');
-                str::push_char(&mut s, '=');
-              }
-              2 => {
-                let n = (self[i] as uint) << 16u |
-                    (self[i + 1u] as uint) << 8u;
-                str::push_char(&mut s, chars[(n >> 18u) & 63u]);
-                str::push_char(&mut s, chars[(n >> 12u) & 63u]);
-                str::push_char(&mut s, chars[(n >> 6u) & 63u]);
-                str::push_char(&mut s, '=');
-              }
-              _ => die!(~"Algebra is broken, please alert the math police")
-            }
-        }
-        s
+        // unsafe only because the encoder keeps its state in mutable fields
+        unsafe { base_codec::encode(BASE64, self) }
     }
 }
 
@@ -81,64 +48,19 @@ pub trait FromBase64 {
 }
 
 impl ~[u8]: FromBase64 {
+    /// Decode the exact output of `to_base64`, failing on anything else.
+    /// Use `decode` to find out where the input is malformed.
     pure fn from_base64() -> ~[u8] {
-        if self.len() % 4u != 0u { die!(~"invalid base64 length"); }
-
-        let len = self.len();
-        let mut padding = 0u;
-
-        if len != 0u {
-            if self[len - 1u] == '=' as u8 { padding += 1u; }
-            if self[len - 2u] == '=' as u8 { padding += 1u; }
-        }
-
-        let mut r = vec::with_capacity((len / 4u) * 3u - padding);
-
+        // unsafe only because the decoder keeps its state in mutable fields
         unsafe {
-            let mut i = 0u;
-            while i < len {
-                let mut n = 0u;
-
-                for iter::repeat(4u) {
-                    let ch = self[i] as char;
-                    n <<= 6u;
-
-                    if ch >= 'A' && ch <= 'Z' {
-                        n |= (ch as uint) - 0x41u;
-                    } else if ch >= 'a' && ch <= 'z' {
-                        n |= (ch as uint) - 0x47u;
-                    } else if ch >= '0' && ch <= '9' {
-                        n |= (ch as uint) + 0x04u;
-                    } else if ch == '+' {
-                        n |= 0x3Eu;
-                    } else if ch == '/' {
-                        n |= 0x3Fu;
-                    } else if ch == '=' {
-                        match len - i {
-                          1u => {
-                            r.push(((n >> 16u) & 0xFFu) as u8);
-                            r.push(((n >> 8u ) & 0xFFu) as u8);
-                            return copy r;
-                          }
-                          2u => {
-                            r.push(((n >> 10u) & 0xFFu) as u8);
-                            return copy r;
-                          }
-                          _ => die!(~"invalid base64 padding")
-                        }
-                    } else {
-                        die!(~"invalid base64 character");
-                    }
-
-                    i += 1u;
-                };
-
-                r.push(((n >> 16u) & 0xFFu) as u8);
-                r.push(((n >> 8u ) & 0xFFu) as u8);
-                r.push(((n       ) & 0xFFu) as u8);
+            match base_codec::decode(BASE64, self, &STRICT) {
+                Ok(move bytes) => move bytes,
+                Err(InvalidChar(_, c)) => {
+                    die!(fmt!("invalid base64 character '%c'", c))
+                }
+                Err(InvalidLength(_)) => die!(~"invalid base64 length")
             }
         }
-        r
     }
 }
 
@@ -148,9 +70,53 @@ impl ~str: FromBase64 {
     }
 }
 
+/**
+ * Decode base64 `input`, reporting where it is malformed
+ *
+ * Unlike `from_base64`, which fails on bad input, this returns the offset
+ * of the first character that could not be decoded. With `STRICT` only
+ * padded output of `to_base64` is accepted; `LENIENT` also skips
+ * whitespace and allows the padding to be left off.
+ */
+pub fn decode(input: &[u8], config: &DecodeConfig)
+    -> Result<~[u8], DecodeError> {
+    base_codec::decode(BASE64, input, config)
+}
+
+/**
+ * Write everything `rdr` yields to `wr` as base64
+ *
+ * The input is read and encoded `CHUNK_SIZE` bytes at a time, so it is
+ * never held in memory all at once. The output is the same as `to_base64`
+ * gives.
+ */
+pub fn encode_stream(rdr: Reader, wr: Writer) {
+    base_codec::encode_stream(BASE64, rdr, wr)
+}
+
+/**
+ * Decode the base64 that `rdr` yields, writing the bytes to `wr`
+ *
+ * Like `encode_stream`, this works through the input a chunk at a time.
+ * If the input is malformed, the bytes before the chunk holding the error
+ * have already been written.
+ */
+pub fn decode_stream(rdr: Reader, wr: Writer, config: &DecodeConfig)
+    -> Result<(), DecodeError> {
+    base_codec::decode_stream(BASE64, rdr, wr, config)
+}
+
 #[cfg(test)]
 mod tests {
+    use core::prelude::*;
+
+    use base64::*;
+    use base64::BASE64;
+    use base_codec;
+
+    use core::rand;
     use core::str;
+    use core::uint;
 
     #[test]
     pub fn test_to_base64() {
@@ -173,4 +139,44 @@ mod tests {
         assert (~"Zm9vYmE=").from_base64() == str::to_bytes(~"fooba");
         assert (~"Zm9vYmFy").from_base64() == str::to_bytes(~"foobar");
     }
+
+    #[test] #[should_fail] #[ignore(cfg(windows))]
+    pub fn test_from_base64_unpadded() {
+        (~"Zm9vYmE").from_base64();
+    }
+
+    #[test]
+    pub fn test_decode() {
+        assert decode(str::to_bytes("Zm9vYmE="), &STRICT) ==
+            Ok(str::to_bytes("fooba"));
+        assert decode(str::to_bytes("Zm9vYmE"), &STRICT) ==
+            Err(InvalidLength(7u));
+        assert decode(str::to_bytes("Zm9vYmE"), &LENIENT) ==
+            Ok(str::to_bytes("fooba"));
+        assert decode(str::to_bytes("Zm9v\nYmFy\n"), &LENIENT) ==
+            Ok(str::to_bytes("foobar"));
+        assert decode(str::to_bytes("Zm9v\nYmFy"), &STRICT) ==
+            Err(InvalidChar(4u, '\n'));
+        assert decode(str::to_bytes("Zm9v*mFy"), &STRICT) ==
+            Err(InvalidChar(4u, '*'));
+        // one character can't end the input, so can't be padded
+        assert decode(str::to_bytes("Zm9vY==="), &STRICT) ==
+            Err(InvalidChar(5u, '='));
+        assert decode(str::to_bytes("Zg==Zg=="), &STRICT) ==
+            Err(InvalidChar(4u, 'Z'));
+        assert decode(str::to_bytes("Zg="), &STRICT) ==
+            Err(InvalidLength(3u));
+    }
+
+    #[test]
+    pub fn test_stream_round_trip() {
+        base_codec::test::check_stream_round_trip(BASE64,
+                                                  [0u, 1u, 2u, 3u, 4u]);
+
+        let rng = rand::Rng();
+        for uint::range(0u, 100u) |_| {
+            let bytes = rng.gen_bytes(rng.gen_uint_range(0u, 64u));
+            assert bytes.to_base64().from_base64() == bytes;
+        }
+    }
 }
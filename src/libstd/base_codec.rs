@@ -0,0 +1,336 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * The engine shared by the base16, base32 and base64 codecs
+ *
+ * Each codec turns every `bits` bits of input into one character of its
+ * alphabet. The encoder and decoder carry the bits that do not yet make a
+ * whole character or byte from one call to the next, so input can be fed
+ * to them in chunks of any size.
+ */
+
+use core::io::{Reader, ReaderUtil, Writer};
+use core::prelude::*;
+use core::str;
+use core::uint;
+use core::vec;
+
+/// The number of bytes the streaming functions read at a time
+pub const CHUNK_SIZE: uint = 8192;
+
+pub struct Codec {
+    alphabet: &static/str,
+    bits: uint,
+    // whether a partial final block is filled out with '='
+    padded: bool,
+    // whether letters decode in either case
+    fold_case: bool,
+}
+
+/// How strictly to decode
+pub struct DecodeConfig {
+    /// Skip spaces, tabs and line breaks anywhere in the input
+    ignore_whitespace: bool,
+    /// Reject a final partial block that is not padded out with `=`. Only
+    /// affects codecs that pad.
+    require_padding: bool,
+}
+
+/// Accept only the exact output of the encoder
+pub const STRICT: DecodeConfig = DecodeConfig {
+    ignore_whitespace: false,
+    require_padding: true,
+};
+
+/// Also accept line-wrapped input and input with the padding left off
+pub const LENIENT: DecodeConfig = DecodeConfig {
+    ignore_whitespace: true,
+    require_padding: false,
+};
+
+/// Why decoding failed. Offsets count bytes of the encoded input,
+/// including any skipped whitespace.
+#[deriving_eq]
+pub enum DecodeError {
+    /// A character outside the alphabet, or misplaced padding
+    InvalidChar(uint, char),
+    /// The input stopped part way through a block, at the given length
+    InvalidLength(uint),
+}
+
+pub impl Codec {
+    /// The number of characters that encode a whole number of bytes
+    pure fn block_chars(&self) -> uint {
+        let mut n = 1u;
+        while n * self.bits % 8u != 0u { n += 1u; }
+        n
+    }
+
+    // Whether `n` characters can end the input: they hold at least one
+    // byte, and fewer than a character's worth of bits are left over
+    pure fn partial_ok(&self, n: uint) -> bool {
+        n > 0u && n * self.bits % 8u < self.bits
+    }
+}
+
+pub struct Encoder {
+    priv codec: Codec,
+    priv mut acc: uint,
+    priv mut nbits: uint,
+    priv mut nchars: uint,
+}
+
+pub fn Encoder(codec: Codec) -> Encoder {
+    Encoder { codec: codec, acc: 0u, nbits: 0u, nchars: 0u }
+}
+
+pub impl Encoder {
+    /// Encode `bytes`, appending every completed character to `out`
+    fn update(bytes: &[u8], out: &mut ~[u8]) {
+        let bits = self.codec.bits;
+        let mask = (1u << bits) - 1u;
+        for uint::range(0u, bytes.len()) |i| {
+            self.acc = (self.acc << 8u) | (bytes[i] as uint);
+            self.nbits += 8u;
+            while self.nbits >= bits {
+                self.nbits -= bits;
+                let n = (self.acc >> self.nbits) & mask;
+                out.push(self.codec.alphabet[n]);
+                self.nchars += 1u;
+            }
+            self.acc &= (1u << self.nbits) - 1u;
+        }
+    }
+
+    /// Append the last partial character and any padding to `out`
+    fn finish(out: &mut ~[u8]) {
+        let bits = self.codec.bits;
+        if self.nbits > 0u {
+            let mask = (1u << bits) - 1u;
+            let n = (self.acc << (bits - self.nbits)) & mask;
+            out.push(self.codec.alphabet[n]);
+            self.nchars += 1u;
+            self.acc = 0u;
+            self.nbits = 0u;
+        }
+        if self.codec.padded {
+            let block = self.codec.block_chars();
+            while self.nchars % block != 0u {
+                out.push('=' as u8);
+                self.nchars += 1u;
+            }
+        }
+    }
+}
+
+const INVALID: u8 = 0xFF;
+
+pub struct Decoder {
+    priv codec: Codec,
+    priv config: DecodeConfig,
+    // the value of each byte, or INVALID
+    priv table: ~[u8],
+    priv mut acc: uint,
+    priv mut nbits: uint,
+    // bytes of input seen so far
+    priv mut offset: uint,
+    // characters of the current block seen so far, padding included
+    priv mut block_pos: uint,
+    priv mut padding: bool,
+}
+
+pub fn Decoder(codec: Codec, config: DecodeConfig) -> Decoder {
+    let mut table = vec::from_elem(256u, INVALID);
+    for uint::range(0u, codec.alphabet.len()) |i| {
+        let c = codec.alphabet[i];
+        table[c as uint] = i as u8;
+        if codec.fold_case {
+            if c >= 'a' as u8 && c <= 'z' as u8 {
+                table[(c - 32u8) as uint] = i as u8;
+            } else if c >= 'A' as u8 && c <= 'Z' as u8 {
+                table[(c + 32u8) as uint] = i as u8;
+            }
+        }
+    }
+    Decoder {
+        codec: codec,
+        config: config,
+        table: table,
+        acc: 0u,
+        nbits: 0u,
+        offset: 0u,
+        block_pos: 0u,
+        padding: false
+    }
+}
+
+pub impl Decoder {
+    /// Decode `input`, appending every completed byte to `out`
+    fn update(input: &[u8], out: &mut ~[u8]) -> Result<(), DecodeError> {
+        let bits = self.codec.bits;
+        let block = self.codec.block_chars();
+        for uint::range(0u, input.len()) |i| {
+            let c = input[i];
+            let offset = self.offset;
+            self.offset += 1u;
+
+            if self.config.ignore_whitespace && is_whitespace(c) {
+                loop;
+            }
+            if c == '=' as u8 && self.codec.padded {
+                // Padding fills out a block that holds a valid tail
+                let ok = if self.padding {
+                    self.block_pos != 0u
+                } else {
+                    self.codec.partial_ok(self.block_pos)
+                };
+                if !ok { return Err(InvalidChar(offset, c as char)); }
+                self.padding = true;
+            } else {
+                let n = self.table[c as uint];
+                if n == INVALID || self.padding {
+                    return Err(InvalidChar(offset, c as char));
+                }
+                self.acc = (self.acc << bits) | (n as uint);
+                self.nbits += bits;
+                if self.nbits >= 8u {
+                    self.nbits -= 8u;
+                    out.push(((self.acc >> self.nbits) & 0xFFu) as u8);
+                }
+                self.acc &= (1u << self.nbits) - 1u;
+            }
+            self.block_pos = (self.block_pos + 1u) % block;
+        }
+        Ok(())
+    }
+
+    /// Check that the input did not stop part way through a block
+    fn finish() -> Result<(), DecodeError> {
+        let complete = self.block_pos == 0u || (!self.padding &&
+            !(self.codec.padded && self.config.require_padding) &&
+            self.codec.partial_ok(self.block_pos));
+        if complete { Ok(()) } else { Err(InvalidLength(self.offset)) }
+    }
+}
+
+pure fn is_whitespace(c: u8) -> bool {
+    c == ' ' as u8 || c == '\t' as u8 || c == '\r' as u8 || c == '\n' as u8
+}
+
+pub fn encode(codec: Codec, bytes: &[u8]) -> ~str {
+    let block = codec.block_chars();
+    let mut out = vec::with_capacity((bytes.len() * 8u / codec.bits + block)
+                                     / block * block);
+    let encoder = Encoder(codec);
+    encoder.update(bytes, &mut out);
+    encoder.finish(&mut out);
+    str::from_bytes(out)
+}
+
+pub fn decode(codec: Codec, input: &[u8], config: &DecodeConfig)
+    -> Result<~[u8], DecodeError> {
+    let mut out = vec::with_capacity(input.len() * codec.bits / 8u);
+    let decoder = Decoder(codec, *config);
+    match decoder.update(input, &mut out) {
+        Ok(()) => (),
+        Err(e) => return Err(e)
+    }
+    match decoder.finish() {
+        Ok(()) => Ok(out),
+        Err(e) => Err(e)
+    }
+}
+
+pub fn encode_stream(codec: Codec, rdr: Reader, wr: Writer) {
+    let encoder = Encoder(codec);
+    let mut out = ~[];
+    loop {
+        let chunk = rdr.read_bytes(CHUNK_SIZE);
+        if chunk.is_empty() { break; }
+        encoder.update(chunk, &mut out);
+        wr.write(out);
+        vec::truncate(&mut out, 0u);
+    }
+    encoder.finish(&mut out);
+    wr.write(out);
+}
+
+// Bytes decoded before an error has been found have already been written
+pub fn decode_stream(codec: Codec, rdr: Reader, wr: Writer,
+                     config: &DecodeConfig) -> Result<(), DecodeError> {
+    let decoder = Decoder(codec, *config);
+    let mut out = ~[];
+    loop {
+        let chunk = rdr.read_bytes(CHUNK_SIZE);
+        if chunk.is_empty() { break; }
+        match decoder.update(chunk, &mut out) {
+            Ok(()) => (),
+            Err(e) => return Err(e)
+        }
+        wr.write(out);
+        vec::truncate(&mut out, 0u);
+    }
+    decoder.finish()
+}
+
+#[cfg(test)]
+pub mod test {
+    use core::prelude::*;
+
+    use base_codec::*;
+
+    use core::io;
+    use core::rand;
+    use core::str;
+    use core::uint;
+    use core::vec;
+
+    /**
+     * Check that `codec` streams the same text that `encode` produces, and
+     * that the text streams and decodes back to the input. `sizes` should
+     * name the input lengths around the codec's block boundaries; lengths
+     * that cross chunk boundaries are added here.
+     */
+    pub fn check_stream_round_trip(codec: Codec, sizes: &[uint]) {
+        let rng = rand::Rng();
+        let block = codec.block_chars();
+        let block_bytes = block * codec.bits / 8u;
+        let mut all_sizes = vec::from_slice(sizes);
+        all_sizes.push_all([1000u, CHUNK_SIZE, CHUNK_SIZE * 3u + 1u]);
+
+        for all_sizes.each |size| {
+            let bytes = rng.gen_bytes(*size);
+            let encoded = do io::with_bytes_writer |wr| {
+                do io::with_bytes_reader(bytes) |rdr| {
+                    encode_stream(codec, rdr, wr);
+                }
+            };
+            assert encoded.len() ==
+                (*size + block_bytes - 1u) / block_bytes * block;
+            assert str::from_bytes(encoded) == encode(codec, bytes);
+
+            let mut result = Ok(());
+            let decoded = do io::with_bytes_writer |wr| {
+                do io::with_bytes_reader(encoded) |rdr| {
+                    result = decode_stream(codec, rdr, wr, &STRICT);
+                }
+            };
+            assert result.is_ok();
+            assert decoded == bytes;
+        }
+
+        for uint::range(0u, 100u) |_| {
+            let bytes = rng.gen_bytes(rng.gen_uint_range(0u, 64u));
+            let encoded = str::to_bytes(encode(codec, bytes));
+            assert decode(codec, encoded, &STRICT) == Ok(copy bytes);
+        }
+    }
+}
@@ -12,8 +12,12 @@
 
 A Big integer (signed version: BigInt, unsigned version: BigUint).
 
-A BigUint is represented as an array of BigDigits.
-A BigInt is a combination of BigUint and Sign.
+A BigUint is represented as a vector of BigDigits (half of a machine
+word each), least significant digit first, with no trailing zero digits;
+zero is the empty vector. A BigInt is a combination of BigUint and Sign.
+
+Conversions to machine integers (`to_uint`, `to_int`) saturate; use
+`to_uint_opt` to find out whether the value actually fits.
 */
 
 use core::cmp::{Eq, Ord};
@@ -68,8 +72,9 @@ pub mod BigDigit {
 /**
 A big unsigned integer type.
 
-A BigUint-typed value BigUint { data: @[a, b, c] } represents a number
-(a + b * BigDigit::base + c * BigDigit::base^2).
+A BigUint-typed value BigUint { data: ~[a, b, c] } represents a number
+(a + b * BigDigit::base + c * BigDigit::base^2). The digits are stored
+little-endian and the last digit is never zero.
 */
 pub struct BigUint {
     priv data: ~[BigDigit]
@@ -430,6 +435,11 @@ pub impl BigUint {
         }
     }
 
+    /// Converts to a uint, or returns `None` if the value does not fit
+    pure fn to_uint_opt(&self) -> Option<uint> {
+        if self.data.len() > 2 { None } else { Some(self.to_uint()) }
+    }
+
     pure fn to_str_radix(&self, radix: uint) -> ~str {
         assert 1 < radix && radix <= 16;
         let (base, max_len) = get_radix_base(radix);
@@ -455,10 +465,11 @@ pub impl BigUint {
 
         pure fn fill_concat(v: &[BigDigit], radix: uint, l: uint) -> ~str {
             if v.is_empty() { return ~"0" }
-            str::trim_left_chars(str::concat(vec::reversed(v).map(|n| {
+            let s = str::concat(vec::reversed(v).map(|n| {
                 let s = uint::to_str_radix(*n as uint, radix);
                 str::from_chars(vec::from_elem(l - s.len(), '0')) + s
-            })), ['0'])
+            }));
+            str::from_slice(str::trim_left_chars(s, ['0']))
         }
     }
 
@@ -851,6 +862,16 @@ pub impl BigInt {
         }
     }
 
+    /// Converts to a uint, or returns `None` if the value is negative or
+    /// does not fit
+    pure fn to_uint_opt(&self) -> Option<uint> {
+        match self.sign {
+            Plus  => self.data.to_uint_opt(),
+            Zero  => Some(0),
+            Minus => None
+        }
+    }
+
     pure fn to_str_radix(&self, radix: uint) -> ~str {
         match self.sign {
             Plus  => self.data.to_str_radix(radix),
@@ -1025,6 +1046,7 @@ mod biguint_tests {
             let b = BigUint::new(v);
             assert b == BigUint::from_uint(u);
             assert b.to_uint() == u;
+            assert b.to_uint_opt() == Some(u);
         }
 
         check(~[], 0);
@@ -1036,6 +1058,7 @@ mod biguint_tests {
 
         assert BigUint::new(~[0, 0, 1]).to_uint()  == uint::max_value;
         assert BigUint::new(~[0, 0, -1]).to_uint() == uint::max_value;
+        assert BigUint::new(~[0, 0, 1]).to_uint_opt() == None;
     }
 
     const sum_triples: &[(&[BigDigit], &[BigDigit], &[BigDigit])] = &[
@@ -1266,6 +1289,35 @@ mod biguint_tests {
         check(10, "3628800");
         check(20, "2432902008176640000");
         check(30, "265252859812191058636308480000000");
+        check(40, "815915283247897734345611269596115894272000000000");
+
+        let f = factor(40);
+        assert f / factor(39) == BigUint::from_uint(40);
+        assert f % factor(38) == Zero::zero();
+    }
+
+    fn rand_biguint(rng: rand::Rng, max_digits: uint) -> BigUint {
+        let len = rng.gen_uint_range(0, max_digits + 1);
+        BigUint::new(vec::from_fn(len, |_| rng.gen_uint() as BigDigit))
+    }
+
+    #[test]
+    fn test_mul_div_round_trip() {
+        let rng = rand::Rng();
+        for 500.times {
+            let a = rand_biguint(rng, 8);
+            let b = rand_biguint(rng, 5);
+            if b.is_zero() { loop; }
+
+            let prod = a * b;
+            assert prod / b == a;
+            assert prod % b == Zero::zero();
+
+            let (q, r) = a.divmod(&b);
+            assert r < b;
+            assert q * b + r == a;
+            assert (prod + r) / b == a;
+        }
     }
 }
 
@@ -1384,6 +1436,15 @@ mod bigint_tests {
         assert BigInt::from_biguint(
             Minus, BigUint::new(~[1, 2, 3])
         ).to_uint() == 0;
+
+        assert BigInt::from_uint(uint::max_value).to_uint_opt() ==
+            Some(uint::max_value);
+        assert BigInt::from_biguint(
+            Plus, BigUint::new(~[1, 2, 3])
+        ).to_uint_opt() == None;
+        assert BigInt::from_biguint(
+            Minus, BigUint::from_uint(1)
+        ).to_uint_opt() == None;
     }
 
     const sum_triples: &[(&[BigDigit], &[BigDigit], &[BigDigit])] = &[
@@ -14,6 +14,14 @@ use core::uint;
 use core::vec::{cast_to_mut, from_elem};
 use core::vec;
 
+#[abi = "rust-intrinsic"]
+extern mod rusti {
+    fn ctpop32(x: i32) -> i32;
+    fn ctpop64(x: i64) -> i64;
+    fn cttz32(x: i32) -> i32;
+    fn cttz64(x: i64) -> i64;
+}
+
 struct SmallBitv {
     /// only the lowest nbits of this value are used. the rest is undefined.
     mut bits: u32
@@ -26,7 +34,7 @@ fn SmallBitv(bits: u32) -> SmallBitv {
 /// a mask that has a 1 for each defined bit in a small_bitv, assuming n bits
 #[inline(always)]
 fn small_mask(nbits: uint) -> u32 {
-    (1 << nbits) - 1
+    if nbits == 32 { !0 } else { (1 << nbits) - 1 }
 }
 
 impl SmallBitv {
@@ -100,6 +108,29 @@ impl SmallBitv {
     #[inline(always)]
     fn invert() { self.bits = !self.bits; }
 
+    #[inline(always)]
+    fn is_disjoint(s: &SmallBitv, nbits: uint) -> bool {
+        small_mask(nbits) & self.bits & s.bits == 0
+    }
+
+    #[inline(always)]
+    fn count_ones(nbits: uint) -> uint {
+        let w = small_mask(nbits) & self.bits;
+        unsafe { rusti::ctpop32(w as i32) as uint }
+    }
+
+    #[inline(always)]
+    fn each_set_bit(nbits: uint, f: fn(uint) -> bool) -> bool {
+        let mut w = small_mask(nbits) & self.bits;
+        while w != 0 {
+            if !f(unsafe { rusti::cttz32(w as i32) as uint }) {
+                return false;
+            }
+            w &= w - 1; // clear the lowest set bit
+        }
+        true
+    }
+
 }
 
 struct BigBitv {
@@ -211,6 +242,41 @@ impl BigBitv {
         }
     }
 
+    #[inline(always)]
+    fn is_disjoint(b: &BigBitv, nbits: uint) -> bool {
+        let len = b.storage.len();
+        assert (self.storage.len() == len);
+        for uint::range(0, len) |i| {
+            if big_mask(nbits, i) & self.storage[i] & b.storage[i] != 0 {
+                return false;
+            }
+        }
+        true
+    }
+
+    #[inline(always)]
+    fn count_ones(nbits: uint) -> uint {
+        let mut count = 0;
+        for uint::range(0, self.storage.len()) |i| {
+            let w = big_mask(nbits, i) & self.storage[i];
+            count += unsafe { rusti::ctpop64(w as i64) as uint };
+        }
+        count
+    }
+
+    #[inline(always)]
+    fn each_set_bit(nbits: uint, f: fn(uint) -> bool) -> bool {
+        for uint::range(0, self.storage.len()) |i| {
+            let mut w = big_mask(nbits, i) & self.storage[i];
+            while w != 0 {
+                let b = unsafe { rusti::cttz64(w as i64) as uint };
+                if !f(i * uint_bits + b) { return false; }
+                w &= w - 1; // clear the lowest set bit
+            }
+        }
+        true
+    }
+
 }
 
 enum BitvVariant { Big(~BigBitv), Small(~SmallBitv) }
@@ -383,6 +449,47 @@ impl Bitv {
     #[inline(always)]
     fn difference(v: &Bitv) -> bool { self.do_op(Difference, v) }
 
+    /**
+     * Returns true if `self` and `v` have no bits set in common
+     *
+     * Both bitvectors must be the same length.
+     */
+    fn is_disjoint(v: &Bitv) -> bool {
+        if self.nbits != v.nbits { self.die(); }
+        match self.rep {
+          Small(ref s) => match v.rep {
+            Small(ref s1) => s.is_disjoint(*s1, self.nbits),
+            Big(_) => self.die()
+          },
+          Big(ref s) => match v.rep {
+            Small(_) => self.die(),
+            Big(ref s1) => s.is_disjoint(*s1, self.nbits)
+          }
+        }
+    }
+
+    /// Returns the number of bits set to 1
+    fn count_ones() -> uint {
+        match self.rep {
+          Small(ref s) => s.count_ones(self.nbits),
+          Big(ref s) => s.count_ones(self.nbits)
+        }
+    }
+
+    /**
+     * Visit the indices of the bits set to 1, in increasing order
+     *
+     * Each word is scanned with a trailing-zero count, so the cost is
+     * proportional to the number of words plus the number of set bits
+     * rather than to the length of the bitvector.
+     */
+    fn each_set_bit(f: fn(uint) -> bool) {
+        match self.rep {
+          Small(ref s) => { s.each_set_bit(self.nbits, f); }
+          Big(ref s) => { s.each_set_bit(self.nbits, f); }
+        }
+    }
+
     /// Returns true if all bits are 1
     #[inline(always)]
     fn is_true() -> bool {
@@ -497,13 +604,7 @@ impl Bitv {
         true
     }
 
-    fn ones(f: fn(uint) -> bool) {
-        for uint::range(0, self.nbits) |i| {
-            if self.get(i) {
-                if !f(i) { break }
-            }
-        }
-    }
+    fn ones(f: fn(uint) -> bool) { self.each_set_bit(f) }
 
 }
 
@@ -516,9 +617,7 @@ impl Bitv: Clone {
             Bitv{nbits: self.nbits, rep: Small(~SmallBitv{bits: b.bits})}
           }
           Big(ref b) => {
-            let mut st = from_elem(self.nbits / uint_bits + 1, 0);
-            let len = st.len();
-            for uint::range(0, len) |i| { st[i] = b.storage[i]; };
+            let st = copy b.storage;
             Bitv{nbits: self.nbits, rep: Big(~BigBitv{storage: move st})}
           }
         }
@@ -581,6 +680,7 @@ mod tests {
     use bitv::*;
     use bitv;
 
+    use core::rand;
     use core::uint;
     use core::vec;
 
@@ -997,6 +1097,119 @@ mod tests {
           die!(fmt!("found 1 at %?", i));
       }
     }
+
+    // The sizes around the boundaries between the small and big
+    // representations, and between the words of the big one
+    const boundary_sizes: &[uint] = &[0, 1, 31, 32, 33, 63, 64, 65, 129];
+
+    fn random_bools(rng: rand::Rng, n: uint) -> ~[bool] {
+        vec::from_fn(n, |_| rng.gen_bool())
+    }
+
+    fn set_bits(b: &Bitv) -> ~[uint] {
+        let mut v = ~[];
+        for b.each_set_bit |i| { v.push(i); }
+        v
+    }
+
+    fn set_bools(bools: &[bool]) -> ~[uint] {
+        let mut v = ~[];
+        for bools.eachi |i, b| { if *b { v.push(i); } }
+        v
+    }
+
+    #[test]
+    pub fn test_each_set_bit_and_count_ones() {
+        let rng = rand::Rng();
+        for boundary_sizes.each |n| {
+            for 10.times {
+                let bools = random_bools(rng, *n);
+                let b = from_bools(bools);
+                assert set_bits(&b) == set_bools(bools);
+                assert b.count_ones() == set_bools(bools).len();
+            }
+
+            // The undefined bits past the end must not show up
+            let b = Bitv(*n, false);
+            b.invert();
+            assert set_bits(&b) == vec::from_fn(*n, |i| i);
+            assert b.count_ones() == *n;
+            b.clear();
+            assert b.count_ones() == 0;
+            assert set_bits(&b).is_empty();
+        }
+    }
+
+    #[test]
+    pub fn test_each_set_bit_stops() {
+        let b = Bitv(100, true);
+        let mut seen = ~[];
+        for b.each_set_bit |i| {
+            seen.push(i);
+            if i == 40 { break; }
+        }
+        assert seen == vec::from_fn(41, |i| i);
+    }
+
+    #[test]
+    pub fn test_set_ops_at_boundaries() {
+        let rng = rand::Rng();
+        for boundary_sizes.each |n| {
+            for 10.times {
+                let xs = random_bools(rng, *n);
+                let ys = random_bools(rng, *n);
+                let x = from_bools(xs);
+                let y = from_bools(ys);
+
+                let u = x.clone();
+                u.union(&y);
+                assert u.to_bools() == vec::from_fn(*n, |i| xs[i] || ys[i]);
+
+                let both = x.clone();
+                both.intersect(&y);
+                assert both.to_bools() ==
+                    vec::from_fn(*n, |i| xs[i] && ys[i]);
+
+                let d = x.clone();
+                d.difference(&y);
+                assert d.to_bools() == vec::from_fn(*n, |i| xs[i] && !ys[i]);
+
+                assert x.is_disjoint(&y) == (both.count_ones() == 0);
+                assert d.is_disjoint(&y);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_is_disjoint_ignores_undefined_bits() {
+        for boundary_sizes.each |n| {
+            let a = Bitv(*n, false);
+            let b = Bitv(*n, false);
+            a.invert();
+            assert a.is_disjoint(&b) && b.is_disjoint(&a);
+            if *n > 0 {
+                b.set(*n - 1, true);
+                assert !a.is_disjoint(&b);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_clone_at_word_boundary() {
+        for boundary_sizes.each |n| {
+            let b = Bitv(*n, true);
+            let c = b.clone();
+            assert c.equal(&b);
+            assert c.count_ones() == *n;
+        }
+    }
+
+    #[test]
+    #[should_fail]
+    #[ignore(cfg(windows))]
+    pub fn test_is_disjoint_differing_sizes() {
+        Bitv(32, false).is_disjoint(&Bitv(33, false));
+    }
 }
 
 //
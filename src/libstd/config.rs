@@ -0,0 +1,533 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[forbid(non_camel_case_types)];
+
+/*!
+ * INI-style configuration files
+ *
+ * A configuration file is a sequence of lines, each of which is one of:
+ *
+ * * blank, or a comment starting with `#` or `;`
+ * * a section header, `[name]`
+ * * an entry, `key = value`
+ *
+ * Entries that appear before the first section header belong to the
+ * unnamed section `""`. Values are typed by their syntax: `true` and
+ * `false` are booleans, `42` and `-7` are integers, `1.5` and `2e10` are
+ * floats, and anything else is a string. Strings may be quoted with `"`,
+ * in which case they may contain `#`, `;`, leading or trailing spaces and
+ * the escapes `\\`, `\"`, `\n`, `\r` and `\t`. A quoted value is always a
+ * string, so `"true"` is not a boolean.
+ *
+ * If a key appears more than once in a section the last value wins, but
+ * the key keeps the position of its first appearance. Likewise a repeated
+ * section header continues the earlier section.
+ *
+ * # Example
+ *
+ * ~~~
+ * let config = config::from_str("[server]\nport = 8080\n").get();
+ * assert config.get_int("server", "port") == Some(8080);
+ * ~~~
+ */
+
+use core::char;
+use core::float;
+use core::int;
+use core::io::{WriterUtil, ReaderUtil};
+use core::io;
+use core::prelude::*;
+use core::str;
+use core::uint;
+use core::vec;
+
+/// A typed configuration value
+#[deriving_eq]
+pub enum Value {
+    String(~str),
+    Int(int),
+    Float(float),
+    Bool(bool),
+}
+
+/// An error encountered while parsing a configuration file
+#[deriving_eq]
+pub struct Error {
+    line: uint,
+    msg: @~str,
+}
+
+struct Section {
+    name: ~str,
+    entries: ~[(~str, Value)],
+}
+
+/// A parsed configuration file
+pub struct Config {
+    priv mut sections: ~[Section],
+}
+
+/// Create an empty configuration
+pub fn Config() -> Config {
+    Config { sections: ~[] }
+}
+
+pub impl Config {
+    /// Look up a value; `None` if the section or key does not exist
+    fn get(&self, section: &str, key: &str) -> Option<Value> {
+        match self.find_section(section) {
+            Some(i) => {
+                for self.sections[i].entries.each |entry| {
+                    let (ref k, ref v) = *entry;
+                    if str::eq_slice(*k, key) { return Some(copy *v); }
+                }
+                None
+            }
+            None => None
+        }
+    }
+
+    /// Look up a string value
+    fn get_str(&self, section: &str, key: &str) -> Option<~str> {
+        match self.get(section, key) {
+            Some(String(move s)) => Some(move s),
+            _ => None
+        }
+    }
+
+    /// Look up an integer value
+    fn get_int(&self, section: &str, key: &str) -> Option<int> {
+        match self.get(section, key) {
+            Some(Int(i)) => Some(i),
+            _ => None
+        }
+    }
+
+    /// Look up a float value. Integer values are converted.
+    fn get_float(&self, section: &str, key: &str) -> Option<float> {
+        match self.get(section, key) {
+            Some(Float(f)) => Some(f),
+            Some(Int(i)) => Some(i as float),
+            _ => None
+        }
+    }
+
+    /// Look up a boolean value
+    fn get_bool(&self, section: &str, key: &str) -> Option<bool> {
+        match self.get(section, key) {
+            Some(Bool(b)) => Some(b),
+            _ => None
+        }
+    }
+
+    /**
+     * Set a value, creating the section if necessary
+     *
+     * An existing key keeps its position; a new key is added after the
+     * other keys in its section.
+     */
+    fn set(&self, section: &str, key: &str, value: Value) {
+        let i = match self.find_section(section) {
+            Some(i) => i,
+            None => {
+                self.sections.push(Section {
+                    name: str::from_slice(section),
+                    entries: ~[]
+                });
+                self.sections.len() - 1
+            }
+        };
+        let entries = &mut self.sections[i].entries;
+        for uint::range(0, entries.len()) |j| {
+            if str::eq_slice(*entries[j].first_ref(), key) {
+                entries[j] = (str::from_slice(key), move value);
+                return;
+            }
+        }
+        entries.push((str::from_slice(key), move value));
+    }
+
+    /// True if the section exists, even if it has no entries
+    pure fn has_section(&self, section: &str) -> bool {
+        self.find_section(section).is_some()
+    }
+
+    /// Iterate over the section names in the order they first appeared
+    pure fn each_section(&self, f: fn(&str) -> bool) {
+        for self.sections.each |s| {
+            if !f(s.name) { break; }
+        }
+    }
+
+    /// Iterate over the entries of a section in order
+    pure fn each_entry(&self, section: &str, f: fn(&str, &Value) -> bool) {
+        match self.find_section(section) {
+            Some(i) => {
+                for self.sections[i].entries.each |entry| {
+                    let (ref k, ref v) = *entry;
+                    if !f(*k, v) { break; }
+                }
+            }
+            None => ()
+        }
+    }
+}
+
+priv impl Config {
+    pure fn find_section(&self, section: &str) -> Option<uint> {
+        vec::position(self.sections, |s| str::eq_slice(s.name, section))
+    }
+}
+
+/// Parse a configuration from an io::reader
+pub fn from_reader(rdr: io::Reader) -> Result<Config, Error> {
+    let config = Config();
+    let mut section = ~"";
+    let mut line_no = 0u;
+
+    while !rdr.eof() {
+        line_no += 1;
+        let line = str::trim(rdr.read_line());
+
+        if is_blank_or_comment(line) { loop; }
+
+        let err = |msg: ~str| Err(Error { line: line_no, msg: @msg });
+
+        if line[0] == '[' as u8 {
+            match str::find_char(line, ']') {
+                Some(end) => {
+                    if !is_blank_or_comment(str::view(line, end + 1,
+                                                      line.len())) {
+                        return err(~"unexpected characters after section");
+                    }
+                    section = str::trim(str::view(line, 1, end));
+                    if str::is_empty(section) {
+                        return err(~"empty section name");
+                    }
+                    if !config.has_section(section) {
+                        config.sections.push(Section {
+                            name: copy section,
+                            entries: ~[]
+                        });
+                    }
+                }
+                None => return err(~"expected `]`")
+            }
+            loop;
+        }
+
+        let eq = match str::find_char(line, '=') {
+            Some(eq) => eq,
+            None => return err(~"expected `key = value`")
+        };
+        let key = str::trim(str::view(line, 0, eq));
+        if str::is_empty(key) {
+            return err(~"missing key before `=`");
+        }
+        match parse_value(str::view(line, eq + 1, line.len())) {
+            Ok(move value) => config.set(section, key, move value),
+            Err(move msg) => return err(move msg)
+        }
+    }
+
+    Ok(move config)
+}
+
+/// Parse a configuration from a string
+pub fn from_str(s: &str) -> Result<Config, Error> {
+    do io::with_str_reader(s) |rdr| {
+        from_reader(rdr)
+    }
+}
+
+pure fn is_blank_or_comment(s: &str) -> bool {
+    let s = str::trim_left(s);
+    str::is_empty(s) || s[0] == '#' as u8 || s[0] == ';' as u8
+}
+
+fn parse_value(s: &str) -> Result<Value, ~str> {
+    let s = str::trim_left(s);
+
+    if !str::is_empty(s) && s[0] == '"' as u8 {
+        return parse_quoted(s);
+    }
+
+    // An unquoted value runs up to the start of a comment
+    let end = match str::find(s, |c| c == '#' || c == ';') {
+        Some(end) => end,
+        None => s.len()
+    };
+    let s = str::trim(str::view(s, 0, end));
+
+    if s == ~"true" { return Ok(Bool(true)); }
+    if s == ~"false" { return Ok(Bool(false)); }
+    match int::from_str(s) {
+        Some(i) => return Ok(Int(i)),
+        None => ()
+    }
+    // Require a digit so that words like `inf` and `.` stay strings
+    if str::any(s, char::is_digit) {
+        match float::from_str(s) {
+            Some(f) => return Ok(Float(f)),
+            None => ()
+        }
+    }
+    Ok(String(move s))
+}
+
+fn parse_quoted(s: &str) -> Result<Value, ~str> {
+    let mut value = ~"";
+    let mut i = 1;
+    let len = s.len();
+
+    loop {
+        if i >= len { return Err(~"unterminated string"); }
+        let str::CharRange {ch, next} = str::char_range_at(s, i);
+        i = next;
+        match ch {
+            '"' => break,
+            '\\' => {
+                if i >= len { return Err(~"unterminated string"); }
+                let str::CharRange {ch, next} = str::char_range_at(s, i);
+                i = next;
+                match ch {
+                    '\\' => str::push_char(&mut value, '\\'),
+                    '"' => str::push_char(&mut value, '"'),
+                    'n' => str::push_char(&mut value, '\n'),
+                    'r' => str::push_char(&mut value, '\r'),
+                    't' => str::push_char(&mut value, '\t'),
+                    _ => return Err(fmt!("invalid escape `\\%c`", ch))
+                }
+            }
+            _ => str::push_char(&mut value, ch)
+        }
+    }
+
+    if is_blank_or_comment(str::view(s, i, len)) {
+        Ok(String(move value))
+    } else {
+        Err(~"unexpected characters after string")
+    }
+}
+
+fn escape_str(s: &str) -> ~str {
+    let mut escaped = ~"\"";
+    for str::chars_each(s) |c| {
+        match c {
+            '"' => escaped += ~"\\\"",
+            '\\' => escaped += ~"\\\\",
+            '\n' => escaped += ~"\\n",
+            '\r' => escaped += ~"\\r",
+            '\t' => escaped += ~"\\t",
+            _ => str::push_char(&mut escaped, c)
+        }
+    }
+    escaped += ~"\"";
+    escaped
+}
+
+fn value_to_str(value: &Value) -> ~str {
+    match *value {
+        String(ref s) => escape_str(*s),
+        Int(i) => int::to_str(i),
+        Float(f) => {
+            // Make sure the value reads back as a float, not an int
+            let s = float::to_str(f);
+            if str::any(s, |c| !char::is_digit(c) && c != '-') {
+                s
+            } else {
+                s + ~".0"
+            }
+        }
+        Bool(b) => if b { ~"true" } else { ~"false" }
+    }
+}
+
+/**
+ * Serialize a configuration to an io::writer
+ *
+ * Sections and keys are written in order, except that the unnamed section
+ * always comes first since it has no header. Strings are always quoted so
+ * that the output parses back to the same types.
+ */
+pub fn to_writer(wr: io::Writer, config: &Config) {
+    let mut first = true;
+    let mut write_section = |s: &Section| {
+        if !first { wr.write_char('\n'); }
+        if !str::is_empty(s.name) {
+            wr.write_line(fmt!("[%s]", s.name));
+        }
+        for s.entries.each |entry| {
+            let (ref k, ref v) = *entry;
+            wr.write_line(fmt!("%s = %s", *k, value_to_str(v)));
+        }
+        first = false;
+    };
+
+    for config.sections.each |s| {
+        if str::is_empty(s.name) { write_section(s); }
+    }
+    for config.sections.each |s| {
+        if !str::is_empty(s.name) { write_section(s); }
+    }
+}
+
+/// Serialize a configuration to a string
+pub fn to_str(config: &Config) -> ~str {
+    io::with_str_writer(|wr| to_writer(wr, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use core::prelude::*;
+
+    use config::*;
+
+    use core::str;
+
+    fn parse(s: &str) -> Config {
+        match from_str(s) {
+            Ok(move config) => move config,
+            Err(e) => die!(fmt!("line %u: %s", e.line, *e.msg))
+        }
+    }
+
+    fn parse_error(s: &str) -> Error {
+        match from_str(s) {
+            Ok(_) => die!(~"expected a parse error"),
+            Err(e) => e
+        }
+    }
+
+    const FIXTURE: &str = "\
+        # top-level comment\n\
+        name = example\n\
+        \n\
+        [server]\n\
+        host = \"localhost\"   ; trailing comment\n\
+        port = 8080\n\
+        ratio = -0.25\n\
+        big = 2e3\n\
+        debug = true\n\
+        \n\
+        [strings]\n\
+        quoted = \"tab\\there \\\"quotes\\\" \\\\ # not a comment\"\n\
+        spaces = \"  padded  \"\n\
+        bare = hello world # comment\n\
+        empty =\n\
+        typed = \"true\"\n";
+
+    #[test]
+    fn test_fixture() {
+        let c = parse(FIXTURE);
+
+        assert c.get_str("", "name") == Some(~"example");
+
+        assert c.get_str("server", "host") == Some(~"localhost");
+        assert c.get_int("server", "port") == Some(8080);
+        assert c.get_float("server", "ratio") == Some(-0.25);
+        assert c.get_float("server", "big") == Some(2000.);
+        assert c.get_float("server", "port") == Some(8080.);
+        assert c.get_bool("server", "debug") == Some(true);
+
+        assert c.get_str("strings", "quoted") ==
+            Some(~"tab\there \"quotes\" \\ # not a comment");
+        assert c.get_str("strings", "spaces") == Some(~"  padded  ");
+        assert c.get_str("strings", "bare") == Some(~"hello world");
+        assert c.get_str("strings", "empty") == Some(~"");
+        assert c.get_str("strings", "typed") == Some(~"true");
+        assert c.get_bool("strings", "typed") == None;
+    }
+
+    #[test]
+    fn test_missing() {
+        let c = parse(FIXTURE);
+        assert !c.has_section("nope");
+        assert c.get("nope", "port") == None;
+        assert c.get("server", "nope") == None;
+        // Wrong type
+        assert c.get_int("server", "host") == None;
+        assert c.get_str("server", "port") == None;
+    }
+
+    #[test]
+    fn test_section_order() {
+        let c = parse(FIXTURE);
+        let mut names = ~[];
+        for c.each_section |name| { names.push(str::from_slice(name)); }
+        assert names == ~[~"", ~"server", ~"strings"];
+
+        let mut keys = ~[];
+        for c.each_entry("server") |k, _| { keys.push(str::from_slice(k)); }
+        assert keys == ~[~"host", ~"port", ~"ratio", ~"big", ~"debug"];
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let c = parse("[a]\nx = 1\ny = 2\nx = 3\n[b]\n[a]\nz = 4\n");
+        assert c.get_int("a", "x") == Some(3);
+
+        let mut keys = ~[];
+        for c.each_entry("a") |k, _| { keys.push(str::from_slice(k)); }
+        assert keys == ~[~"x", ~"y", ~"z"];
+
+        let mut names = ~[];
+        for c.each_section |name| { names.push(str::from_slice(name)); }
+        assert names == ~[~"a", ~"b"];
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let c = parse(FIXTURE);
+        let s = to_str(&c);
+        let c2 = parse(s);
+        assert s == to_str(&c2);
+        for c.each_section |section| {
+            for c.each_entry(section) |k, v| {
+                assert c2.get(section, k) == Some(copy *v);
+            }
+        }
+    }
+
+    #[test]
+    fn test_set() {
+        let c = Config();
+        c.set("s", "b", Float(1.));
+        c.set("", "a", Int(1));
+        c.set("", "a", String(~"x"));
+        assert to_str(&c) == ~"a = \"x\"\n\n[s]\nb = 1.0\n";
+    }
+
+    #[test]
+    fn test_errors() {
+        let e = parse_error("[a]\nok = 1\nno equals sign\n");
+        assert e.line == 3;
+
+        let e = parse_error("\n\n[unclosed\n");
+        assert e.line == 3;
+
+        let e = parse_error("[]\n");
+        assert e.line == 1;
+
+        let e = parse_error("a = 1\n = 2\n");
+        assert e.line == 2;
+
+        let e = parse_error("a = \"unterminated\n");
+        assert e.line == 1;
+        assert *e.msg == ~"unterminated string";
+
+        let e = parse_error("a = 1\nb = \"bad \\q escape\"\n");
+        assert e.line == 2;
+
+        let e = parse_error("a = \"x\" y\n");
+        assert e.line == 1;
+    }
+}
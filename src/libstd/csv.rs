@@ -0,0 +1,437 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[forbid(non_camel_case_types)];
+
+/*!
+ * Reading and writing delimiter-separated records (CSV, TSV, ...)
+ *
+ * Fields may be quoted with `"`. Inside a quoted field the delimiter,
+ * line breaks and doubled quotes (`""`, meaning a literal `"`) are all
+ * taken literally. Records end at `\n`, `\r\n` or `\r`.
+ *
+ * # Example
+ *
+ * ~~~
+ * let rdr = CsvReader(io::stdin(), ',');
+ * for rdr.each_record |record| {
+ *     io::println(record[0]);
+ * }
+ * ~~~
+ */
+
+use core::io::{WriterUtil, ReaderUtil};
+use core::io;
+use core::prelude::*;
+use core::str;
+use core::vec;
+
+/// An error encountered while reading records
+#[deriving_eq]
+pub struct Error {
+    line: uint,
+    msg: @~str,
+}
+
+pub struct CsvReader {
+    priv rdr: io::Reader,
+    priv delim: char,
+    priv mut ch: char,
+    priv mut line: uint,
+}
+
+/**
+ * Read records from an io::reader
+ *
+ * `delim` separates the fields of a record; use `','` for CSV and `'\t'`
+ * for TSV.
+ */
+pub fn CsvReader(rdr: io::Reader, delim: char) -> CsvReader {
+    CsvReader {
+        rdr: rdr,
+        delim: delim,
+        ch: rdr.read_char(),
+        line: 1,
+    }
+}
+
+pub impl CsvReader {
+    /// Read the next record, returning `None` at the end of the input
+    fn read_record() -> Result<Option<~[~str]>, Error> {
+        if self.eof() { return Ok(None); }
+
+        let mut record = ~[];
+        loop {
+            match move self.read_field() {
+                Ok(move field) => record.push(move field),
+                Err(move e) => return Err(move e)
+            }
+            if self.ch != self.delim { break; }
+            self.bump();
+        }
+
+        if self.ch == '\r' { self.bump(); }
+        if self.ch == '\n' { self.bump(); }
+
+        Ok(Some(move record))
+    }
+
+    /**
+     * Iterate over the remaining records without collecting them
+     *
+     * Only the current record is held in memory. Stops early if `f`
+     * returns false, and stops at the first malformed record, returning
+     * the error.
+     */
+    fn each_record(f: fn(&[~str]) -> bool) -> Result<(), Error> {
+        loop {
+            match move self.read_record() {
+                Ok(Some(move record)) => if !f(record) { return Ok(()); },
+                Ok(None) => return Ok(()),
+                Err(move e) => return Err(move e)
+            }
+        }
+    }
+
+    /// Read all of the remaining records
+    fn read_all() -> Result<~[~[~str]], Error> {
+        let mut records = ~[];
+        match move self.each_record(|record| {
+            records.push(vec::from_slice(record));
+            true
+        }) {
+            Ok(()) => Ok(move records),
+            Err(move e) => Err(move e)
+        }
+    }
+}
+
+priv impl CsvReader {
+    fn eof() -> bool { self.ch == -1 as char }
+
+    fn bump() {
+        let prev = self.ch;
+        self.ch = self.rdr.read_char();
+        // `\r\n` ends a single line, at its `\n`
+        if prev == '\n' || (prev == '\r' && self.ch != '\n') {
+            self.line += 1u;
+        }
+    }
+
+    fn error<T>(msg: ~str) -> Result<T, Error> {
+        Err(Error { line: self.line, msg: @msg })
+    }
+
+    fn at_field_end() -> bool {
+        self.eof() || self.ch == self.delim || self.ch == '\r' ||
+            self.ch == '\n'
+    }
+
+    fn read_field() -> Result<~str, Error> {
+        let mut field = ~"";
+
+        if self.ch != '"' {
+            while !self.at_field_end() {
+                str::push_char(&mut field, self.ch);
+                self.bump();
+            }
+            return Ok(move field);
+        }
+
+        // Report an unterminated quote where it was opened, since the
+        // end of the input is usually far away from the mistake.
+        let start_line = self.line;
+        self.bump();
+        loop {
+            if self.eof() {
+                return Err(Error {
+                    line: start_line,
+                    msg: @~"unterminated quoted field"
+                });
+            }
+            if self.ch == '"' {
+                self.bump();
+                if self.ch != '"' { break; }
+            }
+            str::push_char(&mut field, self.ch);
+            self.bump();
+        }
+
+        if self.at_field_end() {
+            Ok(move field)
+        } else {
+            self.error(~"unexpected character after quoted field")
+        }
+    }
+}
+
+pub struct CsvWriter {
+    priv wr: io::Writer,
+    priv delim: char,
+}
+
+/**
+ * Write records to an io::writer
+ *
+ * Fields are only quoted when they contain the delimiter, a quote or a
+ * line break. Each record is terminated by `\n`.
+ */
+pub fn CsvWriter(wr: io::Writer, delim: char) -> CsvWriter {
+    CsvWriter { wr: wr, delim: delim }
+}
+
+pub impl CsvWriter {
+    fn write_record(record: &[~str]) {
+        for record.eachi |i, field| {
+            if i != 0 { self.wr.write_char(self.delim); }
+
+            // A lone empty field would otherwise read back as a blank line
+            if self.needs_quotes(*field) ||
+                    (record.len() == 1 && str::is_empty(*field)) {
+                self.write_quoted(*field);
+            } else {
+                self.wr.write_str(*field);
+            }
+        }
+        self.wr.write_char('\n');
+    }
+}
+
+priv impl CsvWriter {
+    fn needs_quotes(field: &str) -> bool {
+        str::any(field, |c| {
+            c == self.delim || c == '"' || c == '\r' || c == '\n'
+        })
+    }
+
+    fn write_quoted(field: &str) {
+        self.wr.write_char('"');
+        for str::chars_each(field) |c| {
+            if c == '"' { self.wr.write_char('"'); }
+            self.wr.write_char(c);
+        }
+        self.wr.write_char('"');
+    }
+}
+
+/// Read all of the records in a string
+pub fn from_str(s: &str, delim: char) -> Result<~[~[~str]], Error> {
+    do io::with_str_reader(s) |rdr| {
+        CsvReader(rdr, delim).read_all()
+    }
+}
+
+/// Write records to a string
+pub fn to_str(records: &[~[~str]], delim: char) -> ~str {
+    do io::with_str_writer |wr| {
+        let csv = CsvWriter(wr, delim);
+        for records.each |record| {
+            csv.write_record(*record);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::prelude::*;
+
+    use csv::*;
+
+    use core::io;
+    use core::libc;
+    use core::str;
+    use core::uint;
+    use core::vec;
+
+    fn check_round_trip(records: &[~[~str]], delim: char) {
+        let s = to_str(records, delim);
+        match from_str(s, delim) {
+            Ok(read) => assert read == vec::from_slice(records),
+            Err(e) => die!(fmt!("line %u: %s", e.line, *e.msg))
+        }
+    }
+
+    #[test]
+    fn test_read_simple() {
+        assert from_str("a,b,c\n1,2,3\n", ',') == Ok(~[
+            ~[~"a", ~"b", ~"c"],
+            ~[~"1", ~"2", ~"3"]
+        ]);
+        // No trailing line break, and CRLF
+        assert from_str("a,b\r\nc,d", ',') == Ok(~[
+            ~[~"a", ~"b"],
+            ~[~"c", ~"d"]
+        ]);
+        assert from_str("", ',') == Ok(~[]);
+    }
+
+    #[test]
+    fn test_read_quoted() {
+        assert from_str("\"a,b\",\"say \"\"hi\"\"\"\n", ',') == Ok(~[
+            ~[~"a,b", ~"say \"hi\""]
+        ]);
+        assert from_str("\"line 1\r\nline 2\",x\ny,z\n", ',') == Ok(~[
+            ~[~"line 1\r\nline 2", ~"x"],
+            ~[~"y", ~"z"]
+        ]);
+    }
+
+    #[test]
+    fn test_read_empty_fields() {
+        assert from_str("a,,\n,b,\n", ',') == Ok(~[
+            ~[~"a", ~"", ~""],
+            ~[~"", ~"b", ~""]
+        ]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        check_round_trip(~[
+            ~[~"plain", ~"with,comma", ~"with \"quotes\""],
+            ~[~"multi\nline", ~"crlf\r\ninside", ~""],
+            ~[~""],
+            ~[~"", ~""],
+            ~[~"trailing", ~"", ~""]
+        ], ',');
+    }
+
+    #[test]
+    fn test_tsv() {
+        assert from_str("a,b\tc\n", '\t') == Ok(~[~[~"a,b", ~"c"]]);
+        assert to_str(~[~[~"a,b", ~"c\td"]], '\t') == ~"a,b\t\"c\td\"\n";
+        check_round_trip(~[~[~"x\ty", ~"z"], ~[~"1", ~"2"]], '\t');
+    }
+
+    #[test]
+    fn test_write_quotes_only_when_needed() {
+        assert to_str(~[~[~"a", ~"b c", ~"d,e", ~"f\"g"]], ',') ==
+            ~"a,b c,\"d,e\",\"f\"\"g\"\n";
+    }
+
+    #[test]
+    fn test_unterminated_quote() {
+        match from_str("a,b\nc,\"d\ne\nf\n", ',') {
+            Err(e) => {
+                assert e.line == 2;
+                assert *e.msg == ~"unterminated quoted field";
+            }
+            Ok(_) => die!()
+        }
+    }
+
+    #[test]
+    fn test_garbage_after_quote() {
+        match from_str("a\n\"b\"c\n", ',') {
+            Err(e) => assert e.line == 2,
+            Ok(_) => die!()
+        }
+    }
+
+    #[test]
+    fn test_error_line_with_cr_line_breaks() {
+        for ["\n", "\r\n", "\r"].each |eol| {
+            let s = str::connect(~[~"a", ~"\"x\"y", ~""], *eol);
+            match from_str(s, ',') {
+                Err(e) => assert e.line == 2,
+                Ok(_) => die!()
+            }
+            // Line breaks inside a quoted field count too
+            let s = str::connect(~[~"\"a", ~"b\"", ~"\"c"], *eol);
+            match from_str(s, ',') {
+                Err(e) => assert e.line == 3,
+                Ok(_) => die!()
+            }
+        }
+    }
+
+    #[test]
+    fn test_each_record_stops_early() {
+        do io::with_str_reader("1\n2\n3\n") |rdr| {
+            let mut seen = ~[];
+            let r = do CsvReader(rdr, ',').each_record |record| {
+                seen.push(copy record[0]);
+                record[0] != ~"2"
+            };
+            assert r.is_ok();
+            assert seen == ~[~"1", ~"2"];
+        }
+    }
+
+    // Generates rows on demand so that the whole input never exists in
+    // memory at once.
+    struct RowGenerator {
+        rows: uint,
+        mut row: uint,
+        mut buf: ~[u8],
+        mut pos: uint,
+    }
+
+    impl RowGenerator: io::Reader {
+        fn read(&self, bytes: &[mut u8], len: uint) -> uint {
+            let mut count = 0;
+            while count < len {
+                let b = self.read_byte();
+                if b < 0 { break; }
+                bytes[count] = b as u8;
+                count += 1;
+            }
+            count
+        }
+        fn read_byte(&self) -> int {
+            if self.pos == self.buf.len() {
+                if self.row == self.rows { return -1; }
+                self.buf = str::to_bytes(
+                    fmt!("%u,\"quoted, %u\",\"two\nlines\"\n",
+                         self.row, self.row));
+                self.pos = 0;
+                self.row += 1;
+            }
+            self.pos += 1;
+            self.buf[self.pos - 1] as int
+        }
+        fn eof(&self) -> bool {
+            self.pos == self.buf.len() && self.row == self.rows
+        }
+        fn seek(&self, _offset: int, _whence: io::SeekStyle) { die!() }
+        fn tell(&self) -> uint { die!() }
+    }
+
+    #[nolink]
+    extern mod testrt {
+        unsafe fn rust_dbg_exchange_live_allocs() -> libc::intptr_t;
+    }
+
+    fn live_allocs() -> uint {
+        unsafe { testrt::rust_dbg_exchange_live_allocs() as uint }
+    }
+
+    #[test]
+    fn test_stream_large_input() {
+        let rows = 20000u;
+        let gen = @RowGenerator { rows: rows, row: 0, buf: ~[], pos: 0 };
+        let rdr = CsvReader(gen as @io::Reader, ',');
+        let mut count = 0u;
+        let before = live_allocs();
+        let mut peak = before;
+        let r = do rdr.each_record |record| {
+            assert record.len() == 3;
+            assert record[0] == uint::to_str(count);
+            assert record[2] == ~"two\nlines";
+            count += 1;
+            peak = uint::max(peak, live_allocs());
+            true
+        };
+        assert r.is_ok();
+        assert count == rows;
+        // Holding on to the records would take several allocations each.
+        // Other tests share the exchange heap, so allow some slack.
+        assert peak - before < rows;
+    }
+}
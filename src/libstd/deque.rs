@@ -8,178 +8,197 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! A deque. Untested as of yet. Likely buggy
+//! A double-ended queue implemented as a growable ring buffer
 #[forbid(non_camel_case_types)];
 
-use core::cmp::Eq;
-use core::dvec::DVec;
-use core::dvec;
+use core::container::{Container, Mutable};
 use core::prelude::*;
 use core::uint;
 use core::vec;
 
-pub trait Deque<T> {
-    fn size() -> uint;
-    fn add_front(v: T);
-    fn add_back(v: T);
-    fn pop_front() -> T;
-    fn pop_back() -> T;
-    fn peek_front() -> T;
-    fn peek_back() -> T;
-    fn get(int) -> T;
+const initial_capacity: uint = 32u; // 2^5
+
+pub struct Deque<T> {
+    priv nelts: uint,
+    priv lo: uint,
+    priv hi: uint,
+    priv elts: ~[Option<T>]
+}
+
+impl <T> Deque<T>: Container {
+    /// Return the number of elements in the deque
+    pure fn len(&self) -> uint { self.nelts }
+
+    /// Return true if the deque contains no elements
+    pure fn is_empty(&self) -> bool { self.len() == 0 }
 }
 
-// FIXME (#2343) eventually, a proper datatype plus an exported impl would
-// be preferrable.
-pub fn create<T: Copy>() -> Deque<T> {
-    type Cell<T> = Option<T>;
-
-    let initial_capacity: uint = 32u; // 2^5
-     /**
-      * Grow is only called on full elts, so nelts is also len(elts), unlike
-      * elsewhere.
-      */
-    fn grow<T: Copy>(nelts: uint, lo: uint, elts: ~[Cell<T>])
-      -> ~[Cell<T>] {
-        let mut elts = move elts;
-        assert (nelts == vec::len(elts));
-        let mut rv = ~[];
-
-        let mut i = 0u;
-        let nalloc = uint::next_power_of_two(nelts + 1u);
-        while i < nalloc {
-            if i < nelts {
-                rv.push(elts[(lo + i) % nelts]);
-            } else { rv.push(None); }
-            i += 1u;
+impl <T> Deque<T>: Mutable {
+    /// Remove all elements from the deque
+    fn clear(&mut self) {
+        for uint::range(0, self.elts.len()) |i| {
+            self.elts[i] = None;
         }
+        self.nelts = 0;
+        self.lo = 0;
+        self.hi = 0;
+    }
+}
 
-        move rv
+impl <T> Deque<T> {
+    /// Create an empty Deque
+    static pure fn new() -> Deque<T> {
+        Deque{nelts: 0, lo: 0, hi: 0,
+              elts: vec::from_fn(initial_capacity, |_| None)}
     }
-    fn get<T: Copy>(elts: &DVec<Cell<T>>, i: uint) -> T {
-        match (*elts).get_elt(i) { Some(move t) => t, _ => die!() }
+
+    /// Return the first element, or None if the deque is empty
+    pure fn peek_front(&self) -> Option<&self/T> {
+        if self.is_empty() { None } else { Some(self.get(0)) }
     }
 
-    struct Repr<T> {
-        mut nelts: uint,
-        mut lo: uint,
-        mut hi: uint,
-        elts: DVec<Cell<T>>,
+    /// Return the last element, or None if the deque is empty
+    pure fn peek_back(&self) -> Option<&self/T> {
+        if self.is_empty() { None } else { Some(self.get(self.nelts - 1)) }
     }
 
-    impl <T: Copy> Repr<T>: Deque<T> {
-        fn size() -> uint { return self.nelts; }
-        fn add_front(t: T) {
-            let oldlo: uint = self.lo;
-            if self.lo == 0u {
-                self.lo = self.elts.len() - 1u;
-            } else { self.lo -= 1u; }
-            if self.lo == self.hi {
-                self.elts.swap(|v| grow(self.nelts, oldlo, move v));
-                self.lo = self.elts.len() - 1u;
-                self.hi = self.nelts;
-            }
-            self.elts.set_elt(self.lo, Some(t));
-            self.nelts += 1u;
-        }
-        fn add_back(t: T) {
-            if self.lo == self.hi && self.nelts != 0u {
-                self.elts.swap(|v| grow(self.nelts, self.lo, move v));
-                self.lo = 0u;
-                self.hi = self.nelts;
-            }
-            self.elts.set_elt(self.hi, Some(t));
-            self.hi = (self.hi + 1u) % self.elts.len();
-            self.nelts += 1u;
-        }
-        /**
-         * We actually release (turn to none()) the T we're popping so
-         * that we don't keep anyone's refcount up unexpectedly.
-         */
-        fn pop_front() -> T {
-            let t: T = get(&self.elts, self.lo);
-            self.elts.set_elt(self.lo, None);
-            self.lo = (self.lo + 1u) % self.elts.len();
-            self.nelts -= 1u;
-            return t;
-        }
-        fn pop_back() -> T {
-            if self.hi == 0u {
-                self.hi = self.elts.len() - 1u;
-            } else { self.hi -= 1u; }
-            let t: T = get(&self.elts, self.hi);
-            self.elts.set_elt(self.hi, None);
-            self.nelts -= 1u;
-            return t;
+    /// Return the element at index `i`, counting from the front - fails
+    /// if `i` is out of bounds
+    pure fn get(&self, i: uint) -> &self/T {
+        if i >= self.nelts { die!(~"Deque::get: index out of bounds") }
+        get(self.elts, (self.lo + i) % self.elts.len())
+    }
+
+    /// Visit the elements from front to back
+    pure fn each(&self, f: fn(&T) -> bool) {
+        for uint::range(0, self.nelts) |i| {
+            if !f(self.get(i)) { return; }
         }
-        fn peek_front() -> T { return get(&self.elts, self.lo); }
-        fn peek_back() -> T { return get(&self.elts, self.hi - 1u); }
-        fn get(i: int) -> T {
-            let idx = (self.lo + (i as uint)) % self.elts.len();
-            return get(&self.elts, idx);
+    }
+
+    /// Visit the elements from back to front
+    pure fn each_reverse(&self, f: fn(&T) -> bool) {
+        let mut i = self.nelts;
+        while i > 0 {
+            i -= 1;
+            if !f(self.get(i)) { return; }
         }
     }
 
-    let repr: Repr<T> = Repr {
-        nelts: 0u,
-        lo: 0u,
-        hi: 0u,
-        elts: dvec::from_vec(vec::from_elem(initial_capacity, None)),
-    };
+    /// Remove and return the first element - fails if empty
+    fn pop_front(&mut self) -> T {
+        if self.is_empty() { die!(~"Deque::pop_front: empty deque") }
+        let result = self.elts[self.lo].swap_unwrap();
+        self.lo = (self.lo + 1u) % self.elts.len();
+        self.nelts -= 1u;
+        result
+    }
+
+    /// Remove and return the last element - fails if empty
+    fn pop_back(&mut self) -> T {
+        if self.is_empty() { die!(~"Deque::pop_back: empty deque") }
+        if self.hi == 0u {
+            self.hi = self.elts.len() - 1u;
+        } else { self.hi -= 1u; }
+        let result = self.elts[self.hi].swap_unwrap();
+        self.nelts -= 1u;
+        result
+    }
+
+    /// Prepend an element to the deque
+    fn add_front(&mut self, t: T) {
+        if self.nelts == self.elts.len() { self.grow(); }
+        if self.lo == 0u {
+            self.lo = self.elts.len() - 1u;
+        } else { self.lo -= 1u; }
+        self.elts[self.lo] = Some(t);
+        self.nelts += 1u;
+    }
+
+    /// Append an element to the deque
+    fn add_back(&mut self, t: T) {
+        if self.nelts == self.elts.len() { self.grow(); }
+        self.elts[self.hi] = Some(t);
+        self.hi = (self.hi + 1u) % self.elts.len();
+        self.nelts += 1u;
+    }
 
-    repr as Deque::<T>
+    // Only called when the buffer is full. The elements are moved into
+    // a buffer twice the size, unwrapping the ring so that the front
+    // element ends up at index 0.
+    priv fn grow(&mut self) {
+        let nelts = self.nelts;
+        let lo = self.lo;
+        let mut old = ~[];
+        old <-> self.elts;
+        self.elts = do vec::from_fn(nelts * 2) |i| {
+            let mut elt = None;
+            if i < nelts { elt <-> old[(lo + i) % nelts]; }
+            elt
+        };
+        self.lo = 0;
+        self.hi = nelts;
+    }
+}
+
+pure fn get<T>(elts: &r/[Option<T>], i: uint) -> &r/T {
+    match elts[i] {
+        Some(ref t) => t,
+        None => die!(~"Deque: internal logic error")
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use core::prelude::*;
 
-    use deque::*;
-    use deque;
+    use deque::Deque;
+    use core::uint;
+    use core::util::DropCount;
 
     #[test]
     fn test_simple() {
-        let d: deque::Deque<int> = deque::create::<int>();
-        assert (d.size() == 0u);
+        let mut d = Deque::new();
+        assert d.len() == 0u;
         d.add_front(17);
         d.add_front(42);
         d.add_back(137);
-        assert (d.size() == 3u);
+        assert d.len() == 3u;
         d.add_back(137);
-        assert (d.size() == 4u);
+        assert d.len() == 4u;
         log(debug, d.peek_front());
-        assert (d.peek_front() == 42);
+        assert *d.peek_front().get() == 42;
         log(debug, d.peek_back());
-        assert (d.peek_back() == 137);
+        assert *d.peek_back().get() == 137;
         let mut i: int = d.pop_front();
         log(debug, i);
-        assert (i == 42);
+        assert i == 42;
         i = d.pop_back();
         log(debug, i);
-        assert (i == 137);
+        assert i == 137;
         i = d.pop_back();
         log(debug, i);
-        assert (i == 137);
+        assert i == 137;
         i = d.pop_back();
         log(debug, i);
-        assert (i == 17);
-        assert (d.size() == 0u);
+        assert i == 17;
+        assert d.len() == 0u;
         d.add_back(3);
-        assert (d.size() == 1u);
+        assert d.len() == 1u;
         d.add_front(2);
-        assert (d.size() == 2u);
+        assert d.len() == 2u;
         d.add_back(4);
-        assert (d.size() == 3u);
+        assert d.len() == 3u;
         d.add_front(1);
-        assert (d.size() == 4u);
+        assert d.len() == 4u;
         log(debug, d.get(0));
         log(debug, d.get(1));
         log(debug, d.get(2));
         log(debug, d.get(3));
-        assert (d.get(0) == 1);
-        assert (d.get(1) == 2);
-        assert (d.get(2) == 3);
-        assert (d.get(3) == 4);
+        assert *d.get(0) == 1;
+        assert *d.get(1) == 2;
+        assert *d.get(2) == 3;
+        assert *d.get(3) == 4;
     }
 
     #[test]
@@ -189,63 +208,63 @@ mod tests {
         let c: @int = @64;
         let d: @int = @175;
 
-        let deq: deque::Deque<@int> = deque::create::<@int>();
-        assert (deq.size() == 0u);
+        let mut deq = Deque::new();
+        assert deq.len() == 0;
         deq.add_front(a);
         deq.add_front(b);
         deq.add_back(c);
-        assert (deq.size() == 3u);
+        assert deq.len() == 3;
         deq.add_back(d);
-        assert (deq.size() == 4u);
-        assert (deq.peek_front() == b);
-        assert (deq.peek_back() == d);
-        assert (deq.pop_front() == b);
-        assert (deq.pop_back() == d);
-        assert (deq.pop_back() == c);
-        assert (deq.pop_back() == a);
-        assert (deq.size() == 0u);
+        assert deq.len() == 4;
+        assert *deq.peek_front().get() == b;
+        assert *deq.peek_back().get() == d;
+        assert deq.pop_front() == b;
+        assert deq.pop_back() == d;
+        assert deq.pop_back() == c;
+        assert deq.pop_back() == a;
+        assert deq.len() == 0;
         deq.add_back(c);
-        assert (deq.size() == 1u);
+        assert deq.len() == 1;
         deq.add_front(b);
-        assert (deq.size() == 2u);
+        assert deq.len() == 2;
         deq.add_back(d);
-        assert (deq.size() == 3u);
+        assert deq.len() == 3;
         deq.add_front(a);
-        assert (deq.size() == 4u);
-        assert (deq.get(0) == a);
-        assert (deq.get(1) == b);
-        assert (deq.get(2) == c);
-        assert (deq.get(3) == d);
+        assert deq.len() == 4;
+        assert *deq.get(0) == a;
+        assert *deq.get(1) == b;
+        assert *deq.get(2) == c;
+        assert *deq.get(3) == d;
     }
 
     fn test_parameterized<T: Copy Eq Durable>(a: T, b: T, c: T, d: T) {
-        let deq: deque::Deque<T> = deque::create::<T>();
-        assert (deq.size() == 0u);
+        let mut deq = Deque::new();
+        assert deq.len() == 0;
         deq.add_front(a);
         deq.add_front(b);
         deq.add_back(c);
-        assert (deq.size() == 3u);
+        assert deq.len() == 3;
         deq.add_back(d);
-        assert (deq.size() == 4u);
-        assert deq.peek_front() == b;
-        assert deq.peek_back() == d;
+        assert deq.len() == 4;
+        assert *deq.peek_front().get() == b;
+        assert *deq.peek_back().get() == d;
         assert deq.pop_front() == b;
         assert deq.pop_back() == d;
         assert deq.pop_back() == c;
         assert deq.pop_back() == a;
-        assert (deq.size() == 0u);
+        assert deq.len() == 0;
         deq.add_back(c);
-        assert (deq.size() == 1u);
+        assert deq.len() == 1;
         deq.add_front(b);
-        assert (deq.size() == 2u);
+        assert deq.len() == 2;
         deq.add_back(d);
-        assert (deq.size() == 3u);
+        assert deq.len() == 3;
         deq.add_front(a);
-        assert (deq.size() == 4u);
-        assert deq.get(0) == a;
-        assert deq.get(1) == b;
-        assert deq.get(2) == c;
-        assert deq.get(3) == d;
+        assert deq.len() == 4;
+        assert *deq.get(0) == a;
+        assert *deq.get(1) == b;
+        assert *deq.get(2) == c;
+        assert *deq.get(3) == d;
     }
 
     #[deriving_eq]
@@ -295,4 +314,106 @@ mod tests {
         let reccy4 = RecCy { x: 19, y: 252, t: Two(17, 42) };
         test_parameterized::<RecCy>(reccy1, reccy2, reccy3, reccy4);
     }
+
+    #[test]
+    fn test_empty() {
+        let mut d = Deque::new::<int>();
+        assert d.is_empty();
+        assert d.peek_front().is_none();
+        assert d.peek_back().is_none();
+        d.add_back(1);
+        d.clear();
+        assert d.is_empty();
+        assert d.peek_front().is_none();
+        d.add_front(2);
+        assert *d.peek_back().get() == 2;
+    }
+
+    #[test]
+    #[should_fail]
+    #[ignore(cfg(windows))]
+    fn test_empty_pop() { let mut d = Deque::new::<int>(); d.pop_front(); }
+
+    #[test]
+    #[should_fail]
+    #[ignore(cfg(windows))]
+    fn test_get_out_of_bounds() {
+        let mut d = Deque::new();
+        d.add_back(1);
+        d.get(1);
+    }
+
+    fn check_order(d: &Deque<~str>, expected: &[~str]) {
+        assert d.len() == expected.len();
+        for uint::range(0, expected.len()) |i| {
+            assert *d.get(i) == expected[i];
+        }
+        let mut i = 0;
+        for d.each |s| {
+            assert *s == expected[i];
+            i += 1;
+        }
+        assert i == expected.len();
+        for d.each_reverse |s| {
+            i -= 1;
+            assert *s == expected[i];
+        }
+        assert i == 0;
+    }
+
+    #[test]
+    fn test_owned_wrap_around() {
+        let mut d = Deque::new();
+        let mut expected = ~[];
+
+        // Move the front of the ring away from index 0 so that growing
+        // has to unwrap it
+        for uint::range(0, 20) |i| {
+            d.add_back(uint::to_str(i));
+            assert d.pop_front() == uint::to_str(i);
+        }
+
+        // Grow several times from both ends
+        for uint::range(0, 300) |i| {
+            if i % 3 == 0 {
+                d.add_front(uint::to_str(i));
+                expected.unshift(uint::to_str(i));
+            } else {
+                d.add_back(uint::to_str(i));
+                expected.push(uint::to_str(i));
+            }
+        }
+        check_order(&d, expected);
+        assert *d.peek_front().get() == expected[0];
+        assert *d.peek_back().get() == expected.last();
+
+        // Drain from both ends
+        while d.len() > 100 {
+            assert d.pop_front() == expected.shift();
+            assert d.pop_back() == expected.pop();
+        }
+        check_order(&d, expected);
+    }
+
+    #[test]
+    fn test_drops() {
+        let count = DropCount();
+        {
+            let mut d = Deque::new();
+            for uint::range(0, 200) |i| {
+                if i % 2 == 0 {
+                    d.add_back(count.counter(i));
+                } else {
+                    d.add_front(count.counter(i));
+                }
+            }
+            assert count.get() == 0;
+            for 50.times {
+                d.pop_front();
+                d.pop_back();
+            }
+            assert count.get() == 100;
+        }
+        assert count.get() == 200;
+    }
 }
@@ -0,0 +1,117 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * The interface shared by the message digests, `sha1::Sha1` and
+ * `md5::Md5`
+ *
+ * Feed a digest its message with `input`, as many times as needed, then
+ * read the hash with `result`. After that it takes no more input until it
+ * is `reset`. Anything that can be written to an `io::Writer` can be
+ * hashed through a `DigestWriter`.
+ */
+
+use base16::ToHex;
+
+use core::io::{SeekStyle, Writer, WriterType};
+use core::io;
+use core::prelude::*;
+use core::str;
+use core::vec;
+
+pub trait Digest {
+    /// Add `input` to the message
+    fn input(&mut self, input: &[const u8]);
+
+    /**
+     * Finish the message and write the digest, `output_bits() / 8` bytes,
+     * to the start of `out`
+     *
+     * Asking again gives the same digest; no further input may be given
+     * until `reset` is called.
+     */
+    fn result(&mut self, out: &mut [u8]);
+
+    /// Start a new, empty message
+    fn reset(&mut self);
+
+    /// The size of the digest in bits
+    fn output_bits(&self) -> uint;
+}
+
+/// Convenience methods for all digests
+pub trait DigestUtil {
+    /// Add the UTF-8 bytes of `input` to the message
+    fn input_str(&mut self, input: &str);
+
+    /// Finish the message and return the digest as a vector of bytes
+    fn result_bytes(&mut self) -> ~[u8];
+
+    /// Finish the message and return the digest in lower case hex
+    fn result_str(&mut self) -> ~str;
+}
+
+impl<D: Digest> D: DigestUtil {
+    fn input_str(&mut self, input: &str) {
+        do str::byte_slice(input) |bytes| {
+            self.input(bytes);
+        }
+    }
+
+    fn result_bytes(&mut self) -> ~[u8] {
+        let mut out = vec::from_elem(self.output_bits() / 8u, 0u8);
+        self.result(out);
+        out
+    }
+
+    fn result_str(&mut self) -> ~str {
+        digest_to_str(self)
+    }
+}
+
+/// Finish the message and return the digest in lower case hex
+pub fn digest_to_str<D: Digest>(digest: &mut D) -> ~str {
+    digest.result_bytes().to_hex()
+}
+
+/// An `io::Writer` that adds whatever is written to it to a message
+pub struct DigestWriter<D> {
+    priv mut digest: D,
+    priv mut len: uint,
+}
+
+pub fn DigestWriter<D: Digest>(digest: D) -> DigestWriter<D> {
+    DigestWriter { digest: digest, len: 0u }
+}
+
+pub impl<D: Digest> DigestWriter<D> {
+    /// Finish the message and return the digest in lower case hex
+    fn result_str(&self) -> ~str {
+        digest_to_str(&mut self.digest)
+    }
+
+    /// Finish the message and return the digest as a vector of bytes
+    fn result_bytes(&self) -> ~[u8] {
+        self.digest.result_bytes()
+    }
+}
+
+impl<D: Digest> DigestWriter<D>: Writer {
+    fn write(&self, v: &[const u8]) {
+        self.digest.input(v);
+        self.len += v.len();
+    }
+    fn seek(&self, _offset: int, _whence: SeekStyle) {
+        die!(~"a DigestWriter can't seek");
+    }
+    fn tell(&self) -> uint { self.len }
+    fn flush(&self) -> int { 0 }
+    fn get_type(&self) -> WriterType { io::File }
+}
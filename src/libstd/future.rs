@@ -125,7 +125,8 @@ pub fn from_fn<A>(f: ~fn() -> A) -> Future<A> {
     Future {state: Pending(move f)}
 }
 
-pub fn spawn<A:Owned>(blk: fn~() -> A) -> Future<A> {
+#[cfg(stage0)]
+pub fn spawn<A:Owned>(blk: ~once fn() -> A) -> Future<A> {
     /*!
      * Create a future from a unique closure.
      *
@@ -144,6 +145,26 @@ pub fn spawn<A:Owned>(blk: fn~() -> A) -> Future<A> {
     return from_port(move port);
 }
 
+#[cfg(stage1)]
+#[cfg(stage2)]
+#[cfg(stage3)]
+pub fn spawn<A:Owned>(blk: ~once fn() -> A) -> Future<A> {
+    /*!
+     * Create a future from a unique closure.
+     *
+     * The closure will be run in a new task and its result used as the
+     * value of the future.
+     */
+
+    let (chan, port) = oneshot::init();
+
+    do task::spawn_once |move blk, move chan| {
+        send_one(move chan, blk());
+    }
+
+    return from_port(move port);
+}
+
 #[allow(non_implicitly_copyable_typarams)]
 pub mod test {
     use core::prelude::*;
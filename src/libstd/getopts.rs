@@ -80,6 +80,7 @@ use core::result;
 use core::option;
 use core::option::{Some, None};
 use core::str;
+use core::uint;
 use core::vec;
 
 #[deriving_eq]
@@ -94,12 +95,28 @@ pub enum HasArg { Yes, No, Maybe, }
 #[deriving_eq]
 pub enum Occur { Req, Optional, Multi, }
 
+/**
+ * Checks the argument given to an option, returning a message describing
+ * the problem if it is not acceptable
+ */
+pub type Validator = extern fn(&str) -> result::Result<(), ~str>;
+
 /// A description of a possible option
-#[deriving_eq]
 pub struct Opt {
     name: Name,
     hasarg: HasArg,
-    occur: Occur
+    occur: Occur,
+    validate: Option<Validator>
+}
+
+// Validators can't be compared, so only whether one is present counts
+impl Opt : Eq {
+    pure fn eq(&self, other: &Opt) -> bool {
+        self.name == other.name && self.hasarg == other.hasarg &&
+            self.occur == other.occur &&
+            self.validate.is_some() == other.validate.is_some()
+    }
+    pure fn ne(&self, other: &Opt) -> bool { !self.eq(other) }
 }
 
 fn mkname(nm: &str) -> Name {
@@ -111,27 +128,32 @@ fn mkname(nm: &str) -> Name {
 
 /// Create an option that is required and takes an argument
 pub fn reqopt(name: &str) -> Opt {
-    return Opt {name: mkname(name), hasarg: Yes, occur: Req};
+    return Opt {name: mkname(name), hasarg: Yes, occur: Req,
+                validate: None};
 }
 
 /// Create an option that is optional and takes an argument
 pub fn optopt(name: &str) -> Opt {
-    return Opt {name: mkname(name), hasarg: Yes, occur: Optional};
+    return Opt {name: mkname(name), hasarg: Yes, occur: Optional,
+                validate: None};
 }
 
 /// Create an option that is optional and does not take an argument
 pub fn optflag(name: &str) -> Opt {
-    return Opt {name: mkname(name), hasarg: No, occur: Optional};
+    return Opt {name: mkname(name), hasarg: No, occur: Optional,
+                validate: None};
 }
 
 /// Create an option that is optional and does not take an argument
 pub fn optflagmulti(name: &str) -> Opt {
-    return Opt {name: mkname(name), hasarg: No, occur: Multi};
+    return Opt {name: mkname(name), hasarg: No, occur: Multi,
+                validate: None};
 }
 
 /// Create an option that is optional and takes an optional argument
 pub fn optflagopt(name: &str) -> Opt {
-    return Opt {name: mkname(name), hasarg: Maybe, occur: Optional};
+    return Opt {name: mkname(name), hasarg: Maybe, occur: Optional,
+                validate: None};
 }
 
 /**
@@ -139,7 +161,29 @@ pub fn optflagopt(name: &str) -> Opt {
  * multiple times
  */
 pub fn optmulti(name: &str) -> Opt {
-    return Opt {name: mkname(name), hasarg: Yes, occur: Multi};
+    return Opt {name: mkname(name), hasarg: Yes, occur: Multi,
+                validate: None};
+}
+
+/**
+ * Create an option that is required and takes an argument which must be
+ * accepted by `validate`
+ *
+ * If `validate` rejects the argument then `getopts` fails with
+ * `InvalidArgument`, carrying the option name and the validator's message.
+ */
+pub fn req_with(name: &str, validate: Validator) -> Opt {
+    return Opt {name: mkname(name), hasarg: Yes, occur: Req,
+                validate: Some(validate)};
+}
+
+/**
+ * Create an option that is optional and takes an argument which must be
+ * accepted by `validate`
+ */
+pub fn opt_with(name: &str, validate: Validator) -> Opt {
+    return Opt {name: mkname(name), hasarg: Yes, occur: Optional,
+                validate: Some(validate)};
 }
 
 #[deriving_eq]
@@ -148,12 +192,17 @@ enum Optval { Val(~str), Given, }
 /**
  * The result of checking command line arguments. Contains a vector
  * of matches and a vector of free strings.
+ *
+ * `stopped_at` is the index of the argument at which option parsing
+ * stopped, if it stopped before the end: a `--`, or the first free
+ * argument when stopping there. All the arguments after it are free.
  */
 #[deriving_eq]
 pub struct Matches {
     opts: ~[Opt],
     vals: ~[~[Optval]],
-    free: ~[~str]
+    free: ~[~str],
+    stopped_at: Option<uint>
 }
 
 fn is_arg(arg: &str) -> bool {
@@ -182,6 +231,7 @@ pub enum Fail_ {
     OptionMissing(~str),
     OptionDuplicated(~str),
     UnexpectedArgument(~str),
+    InvalidArgument(~str, ~str),
 }
 
 /// Convert a `fail_` enum into an error string
@@ -202,6 +252,9 @@ pub fn fail_str(f: Fail_) -> ~str {
         UnexpectedArgument(ref nm) => {
             ~"Option " + *nm + ~" does not take an argument."
         }
+        InvalidArgument(ref nm, ref msg) => {
+            ~"Invalid argument to option '" + *nm + ~"': " + *msg
+        }
     };
 }
 
@@ -217,21 +270,50 @@ pub type Result = result::Result<Matches, Fail_>;
  * On success returns `ok(Opt)`. Use functions such as `opt_present`
  * `opt_str`, etc. to interrogate results.  Returns `err(Fail_)` on failure.
  * Use <fail_str> to get an error message.
+ *
+ * Free arguments may come before, between and after the options. An
+ * argument of `--` ends the options, and the arguments after it are free
+ * even if they start with a dash.
  */
 pub fn getopts(args: &[~str], opts: &[Opt]) -> Result {
+    getopts_internal(args, opts, false)
+}
+
+/**
+ * Parse command line arguments like `getopts`, but stop at the first free
+ * argument, which makes it and every argument after it free. This leaves
+ * the arguments of a subcommand, as in `tool -v build -x`, for the
+ * subcommand to parse.
+ *
+ * An option that takes an optional argument takes a following free
+ * argument as its own, so it does not end the options.
+ */
+pub fn getopts_stop_at_first_free(args: &[~str], opts: &[Opt]) -> Result {
+    getopts_internal(args, opts, true)
+}
+
+fn getopts_internal(args: &[~str], opts: &[Opt], stop_at_free: bool)
+    -> Result {
     unsafe {
         let n_opts = opts.len();
         fn f(_x: uint) -> ~[Optval] { return ~[]; }
         let vals = vec::cast_to_mut(vec::from_fn(n_opts, f));
         let mut free: ~[~str] = ~[];
+        let mut stopped_at = None;
         let l = args.len();
         let mut i = 0;
         while i < l {
             let cur = args[i];
             let curlen = cur.len();
-            if !is_arg(cur) {
+            if !is_arg(cur) && stop_at_free {
+                stopped_at = Some(i);
+                let mut j = i;
+                while j < l { free.push(args[j]); j += 1; }
+                break;
+            } else if !is_arg(cur) {
                 free.push(cur);
             } else if cur == ~"--" {
+                stopped_at = Some(i);
                 let mut j = i + 1;
                 while j < l { free.push(args[j]); j += 1; }
                 break;
@@ -334,11 +416,29 @@ pub fn getopts(args: &[~str], opts: &[Opt]) -> Result {
                     return Err(OptionDuplicated(name_str(&(opts[i].name))));
                 }
             }
+            match opts[i].validate {
+              Some(validate) => {
+                for vals[i].each |v| {
+                    match *v {
+                      Val(ref s) => match validate(*s) {
+                        Ok(()) => (),
+                        Err(move msg) => {
+                            return Err(InvalidArgument(
+                                name_str(&(opts[i].name)), msg));
+                        }
+                      },
+                      Given => ()
+                    }
+                }
+              }
+              None => ()
+            }
             i += 1;
         }
         return Ok(Matches {opts: vec::from_slice(opts),
                    vals: vec::cast_from_mut(move vals),
-                   free: free});
+                   free: free,
+                   stopped_at: stopped_at});
     }
 }
 
@@ -442,6 +542,104 @@ pub fn opt_default(mm: &Matches, nm: &str, def: &str) -> Option<~str> {
                            _      => Some::<~str>(str::from_slice(def)) }
 }
 
+// Parses each argument given to an option with `f`, which returns a
+// description of what it expected when the argument is unacceptable
+fn opt_parse_vals<T>(mm: &Matches, nm: &str,
+                     f: fn(&str) -> result::Result<T, ~str>)
+    -> result::Result<~[T], ~str> {
+    let mut acc = ~[];
+    for vec::each(opt_vals(mm, nm)) |v| {
+        match *v {
+          Val(ref s) => match f(*s) {
+            Ok(move t) => acc.push(move t),
+            Err(move expected) => {
+                return Err(fmt!("Option '%s' expects %s, found '%s'.",
+                                nm, expected, *s));
+            }
+          },
+          Given => ()
+        }
+    }
+    Ok(move acc)
+}
+
+fn opt_parse_val<T>(mm: &Matches, nm: &str,
+                    f: fn(&str) -> result::Result<T, ~str>)
+    -> result::Result<Option<T>, ~str> {
+    match move opt_parse_vals(mm, nm, f) {
+      Ok(move vals) => {
+        let mut vals = move vals;
+        if vals.is_empty() { Ok(None) } else { Ok(Some(vals.shift())) }
+      }
+      Err(move e) => Err(move e)
+    }
+}
+
+fn parse_uint(s: &str) -> result::Result<uint, ~str> {
+    match uint::from_str(s) {
+      Some(n) => Ok(n),
+      None => Err(~"an unsigned integer")
+    }
+}
+
+fn parse_enum(allowed: &[&str],
+              s: &str) -> result::Result<uint, ~str> {
+    match vec::position(allowed, |a| str::eq_slice(*a, s)) {
+      Some(idx) => Ok(idx),
+      None => {
+        let quoted = allowed.map(|a| ~"'" + *a + ~"'");
+        Err(~"one of " + str::connect(quoted, ~", "))
+      }
+    }
+}
+
+/**
+ * Returns the argument supplied to a matching option as an unsigned
+ * integer, or none
+ *
+ * Returns none if the option was not present or was given without an
+ * argument, and an error naming the option if the argument is not a
+ * number.
+ */
+pub fn opt_uint(mm: &Matches,
+                nm: &str) -> result::Result<Option<uint>, ~str> {
+    opt_parse_val(mm, nm, parse_uint)
+}
+
+/// Returns the arguments to all matches of an option as unsigned integers
+pub fn opt_uints(mm: &Matches,
+                 nm: &str) -> result::Result<~[uint], ~str> {
+    opt_parse_vals(mm, nm, parse_uint)
+}
+
+/// Returns the argument supplied to a matching option as a path, or none
+pub fn opt_path(mm: &Matches, nm: &str) -> Option<Path> {
+    opt_maybe_str(mm, nm).map(|s| Path(*s))
+}
+
+/// Returns the arguments to all matches of an option as paths
+pub fn opt_paths(mm: &Matches, nm: &str) -> ~[Path] {
+    opt_strs(mm, nm).map(|s| Path(*s))
+}
+
+/**
+ * Returns the index in `allowed` of the argument supplied to a matching
+ * option, or none
+ *
+ * Arguments are compared case-sensitively. Returns an error naming the
+ * option and listing the allowed values if the argument isn't one of them.
+ */
+pub fn opt_enum(mm: &Matches, nm: &str,
+                allowed: &[&str]) -> result::Result<Option<uint>, ~str> {
+    opt_parse_val(mm, nm, |s| parse_enum(allowed, s))
+}
+
+/// Returns the indices in `allowed` of the arguments to all matches
+pub fn opt_enums(mm: &Matches, nm: &str,
+                 allowed: &[&str]) -> result::Result<~[uint], ~str> {
+    opt_parse_vals(mm, nm, |s| parse_enum(allowed, s))
+}
+
 #[deriving_eq]
 enum FailType {
     ArgumentMissing_,
@@ -449,6 +647,7 @@ enum FailType {
     OptionMissing_,
     OptionDuplicated_,
     UnexpectedArgument_,
+    InvalidArgument_,
 }
 
 /** A module which provides a way to specify descriptions and
@@ -553,18 +752,22 @@ pub mod groups {
 
            (0,_) => ~[Opt {name:   Long(((*lopt).long_name)),
                            hasarg: (*lopt).hasarg,
-                           occur:  (*lopt).occur}],
+                           occur:  (*lopt).occur,
+                           validate: None}],
 
            (1,0) => ~[Opt {name: Short(str::char_at((*lopt).short_name, 0)),
                            hasarg: (*lopt).hasarg,
-                           occur:  (*lopt).occur}],
+                           occur:  (*lopt).occur,
+                           validate: None}],
 
            (1,_) => ~[Opt {name: Short(str::char_at((*lopt).short_name, 0)),
                            hasarg: (*lopt).hasarg,
-                           occur:  (*lopt).occur},
+                           occur:  (*lopt).occur,
+                           validate: None},
                       Opt {name:   Long(((*lopt).long_name)),
                            hasarg: (*lopt).hasarg,
-                           occur:  (*lopt).occur}],
+                           occur:  (*lopt).occur,
+                           validate: None}],
 
            (_,_) => die!(~"something is wrong with the long-form opt")
         }
@@ -577,12 +780,45 @@ pub mod groups {
         ::getopts::getopts(args, vec::flat_map(opts, long_to_short))
     }
 
+    /*
+     * Parse command line args with the provided long format options,
+     * stopping at the first free argument
+     */
+    pub fn getopts_stop_at_first_free(args: &[~str], opts: &[OptGroup])
+        -> ::getopts::Result {
+        ::getopts::getopts_stop_at_first_free(
+            args, vec::flat_map(opts, long_to_short))
+    }
+
+    // Wrap a description to lines of at most `lim` columns, keeping the
+    // line breaks it already has
+    fn wrap_desc(desc: &str, lim: uint) -> ~[~str] {
+        let mut lines = ~[];
+        for str::split_char(desc, '\n').each |para| {
+            let wrapped = str::split_within(*para, lim);
+            if wrapped.is_empty() {
+                lines.push(~"");
+            } else {
+                lines.push_all_move(wrapped);
+            }
+        }
+        while !lines.is_empty() && lines.last().is_empty() {
+            lines.pop();
+        }
+        lines
+    }
+
     /**
      * Derive a usage message from a set of long options
+     *
+     * Each option gets a row listing its names and argument, followed by
+     * its description wrapped so that the message fits in 80 columns.
+     * Line breaks in a description are kept, and the long names of options
+     * with no short name line up with the others.
      */
     pub fn usage(brief: &str, opts: &[OptGroup]) -> ~str {
 
-        let desc_sep = ~"\n" + str::repeat(~" ", 24);
+        let desc_indent = str::repeat(~" ", 24);
 
         let rows = vec::map(opts, |optref| {
             let short_name = (*optref).short_name;
@@ -595,7 +831,7 @@ pub mod groups {
 
             // short option
             row += match short_name.len() {
-                0 => ~"",
+                0 => ~"   ",
                 1 => ~"-" + short_name + " ",
                 _ => die!(~"the short name should only be 1 char long"),
             };
@@ -613,18 +849,32 @@ pub mod groups {
                 Maybe => ~"[" + hint + ~"]",
             };
 
-            // here we just need to indent the start of the description
-            let rowlen = row.len();
-            row += if rowlen < 24 {
-                str::repeat(~" ", 24 - rowlen)
+            let lines = wrap_desc(desc, 54);
+            if lines.is_empty() {
+                str::trim_right(row)
             } else {
-                desc_sep
-            };
+                // here we just need to indent the start of the description
+                let rowlen = row.len();
+                if rowlen < 24 {
+                    row += str::repeat(~" ", 24 - rowlen);
+                } else {
+                    row = str::trim_right(row) + ~"\n" + desc_indent;
+                }
 
-            // wrapped description
-            row += str::connect(str::split_within(desc, 54), desc_sep);
+                // wrapped description, with no trailing spaces on the
+                // blank lines
+                for lines.eachi |i, line| {
+                    if i > 0 {
+                        str::push_char(&mut row, '\n');
+                        if !line.is_empty() {
+                            str::push_str(&mut row, desc_indent);
+                        }
+                    }
+                    str::push_str(&mut row, *line);
+                }
 
-            row
+                row
+            }
         });
 
         return str::from_slice(brief)    +
@@ -644,6 +894,7 @@ mod tests {
 
     use core::result::{Err, Ok};
     use core::result;
+    use core::uint;
 
     pub fn check_fail_type(f: Fail_, ft: FailType) {
         match f {
@@ -651,7 +902,8 @@ mod tests {
           UnrecognizedOption(_) => assert ft == UnrecognizedOption_,
           OptionMissing(_) => assert ft == OptionMissing_,
           OptionDuplicated(_) => assert ft == OptionDuplicated_,
-          UnexpectedArgument(_) => assert ft == UnexpectedArgument_
+          UnexpectedArgument(_) => assert ft == UnexpectedArgument_,
+          InvalidArgument(*) => assert ft == InvalidArgument_
         }
     }
 
@@ -1207,6 +1459,230 @@ mod tests {
 
     }
 
+    #[test]
+    pub fn test_stop_at_first_free() {
+        let opts = ~[optflag(~"v"), optflag(~"x")];
+        let args = ~[~"-v", ~"build", ~"-x"];
+        let matches = &match getopts_stop_at_first_free(args, opts) {
+          result::Ok(move m) => m,
+          result::Err(_) => die!()
+        };
+        assert opt_present(matches, ~"v");
+        assert !opt_present(matches, ~"x");
+        assert matches.free == ~[~"build", ~"-x"];
+        assert matches.stopped_at == Some(1);
+
+        // Without stopping -x is an option, and an unknown one is an error
+        let matches = &match getopts(args, opts) {
+          result::Ok(move m) => m,
+          result::Err(_) => die!()
+        };
+        assert opt_present(matches, ~"x");
+        assert matches.free == ~[~"build"];
+        assert matches.stopped_at == None;
+        match getopts(~[~"-v", ~"build", ~"-y"], opts) {
+          result::Err(f) => check_fail_type(f, UnrecognizedOption_),
+          _ => die!()
+        }
+        assert getopts_stop_at_first_free(~[~"-v", ~"build", ~"-y"], opts)
+            .is_ok();
+    }
+
+    #[test]
+    pub fn test_stop_at_first_free_option_args() {
+        // The argument of an option is not free
+        let opts = ~[optopt(~"o"), optflagopt(~"c"), optflag(~"v")];
+        let args = ~[~"-o", ~"out", ~"-c", ~"auto", ~"run", ~"-v"];
+        let matches = &match getopts_stop_at_first_free(args, opts) {
+          result::Ok(move m) => m,
+          result::Err(_) => die!()
+        };
+        assert opt_str(matches, ~"o") == ~"out";
+        assert opt_str(matches, ~"c") == ~"auto";
+        assert !opt_present(matches, ~"v");
+        assert matches.free == ~[~"run", ~"-v"];
+        assert matches.stopped_at == Some(4);
+
+        let args = ~[~"-", ~"-v"];
+        let matches = &match getopts_stop_at_first_free(args, opts) {
+          result::Ok(move m) => m,
+          result::Err(_) => die!()
+        };
+        assert matches.free == ~[~"-", ~"-v"];
+        assert matches.stopped_at == Some(0);
+    }
+
+    fn check_double_dash(parse: fn(&[~str], &[Opt]) -> opt::Result) {
+        let opts = ~[optflag(~"v")];
+        let matches = &match parse(~[~"--", ~"-v"], opts) {
+          result::Ok(move m) => m,
+          result::Err(_) => die!()
+        };
+        assert !opt_present(matches, ~"v");
+        assert matches.free == ~[~"-v"];
+        assert matches.stopped_at == Some(0);
+
+        let matches = &match parse(~[~"-v", ~"--", ~"--", ~"-v"], opts) {
+          result::Ok(move m) => m,
+          result::Err(_) => die!()
+        };
+        assert opt_count(matches, ~"v") == 1;
+        assert matches.free == ~[~"--", ~"-v"];
+        assert matches.stopped_at == Some(1);
+    }
+
+    #[test]
+    pub fn test_double_dash() {
+        check_double_dash(getopts);
+        check_double_dash(getopts_stop_at_first_free);
+
+        // A free argument before the -- is kept when not stopping at it
+        let opts = ~[optflag(~"v")];
+        let matches = &match getopts(~[~"a", ~"-v", ~"--", ~"b"], opts) {
+          result::Ok(move m) => m,
+          result::Err(_) => die!()
+        };
+        assert opt_present(matches, ~"v");
+        assert matches.free == ~[~"a", ~"b"];
+        assert matches.stopped_at == Some(2);
+    }
+
+    #[test]
+    pub fn test_empty_args() {
+        let opts = ~[optflag(~"v")];
+        let empty: ~[~str] = ~[];
+        assert getopts(empty, opts) == Ok(Matches {
+            opts: copy opts,
+            vals: ~[~[]],
+            free: ~[],
+            stopped_at: None
+        });
+        assert getopts_stop_at_first_free(empty, opts) ==
+            getopts(empty, opts);
+        match getopts_stop_at_first_free(~[], ~[reqopt(~"o")]) {
+          result::Err(f) => check_fail_type(f, OptionMissing_),
+          _ => die!()
+        }
+    }
+
+    #[test]
+    pub fn test_groups_stop_at_first_free() {
+        let opts = ~[groups::optflag(~"v", ~"verbose", ~"Desc")];
+        let args = ~[~"--verbose", ~"build", ~"--verbose"];
+        let matches = &result::unwrap(
+            groups::getopts_stop_at_first_free(args, opts));
+        assert opt_present(matches, ~"verbose");
+        assert matches.free == ~[~"build", ~"--verbose"];
+    }
+
+    #[test]
+    pub fn test_opt_uint() {
+        let args = ~[~"-n", ~"42", ~"--count=7", ~"--count=8"];
+        let opts = ~[optopt(~"n"), optopt(~"m"), optmulti(~"count")];
+        let matches = &match getopts(args, opts) {
+          result::Ok(move m) => m,
+          result::Err(_) => die!()
+        };
+        assert opt_uint(matches, ~"n") == Ok(Some(42u));
+        assert opt_uint(matches, ~"m") == Ok(None);
+        assert opt_uints(matches, ~"count") == Ok(~[7u, 8u]);
+    }
+
+    #[test]
+    pub fn test_opt_uint_invalid() {
+        let args = ~[~"--jobs=many", ~"--count=1", ~"--count=-2"];
+        let opts = ~[optopt(~"jobs"), optmulti(~"count")];
+        let matches = &match getopts(args, opts) {
+          result::Ok(move m) => m,
+          result::Err(_) => die!()
+        };
+        assert opt_uint(matches, ~"jobs") ==
+            Err(~"Option 'jobs' expects an unsigned integer, found 'many'.");
+        assert opt_uints(matches, ~"count") ==
+            Err(~"Option 'count' expects an unsigned integer, found '-2'.");
+    }
+
+    #[test]
+    pub fn test_opt_uint_default() {
+        let args = ~[~"--level"];
+        let opts = ~[optflagopt(~"level"), optopt(~"n")];
+        let matches = &match getopts(args, opts) {
+          result::Ok(move m) => m,
+          result::Err(_) => die!()
+        };
+        // Given without an argument, and not given at all
+        assert opt_uint(matches, ~"level").get().get_or_default(3) == 3;
+        assert opt_uint(matches, ~"n").get().get_or_default(5) == 5;
+        assert opt_default(matches, ~"level", ~"3") == Some(~"3");
+    }
+
+    #[test]
+    pub fn test_opt_path() {
+        let args = ~[~"-o", ~"out/a.txt", ~"-I", ~"x", ~"-I", ~"y/z"];
+        let opts = ~[optopt(~"o"), optopt(~"p"), optmulti(~"I")];
+        let matches = &match getopts(args, opts) {
+          result::Ok(move m) => m,
+          result::Err(_) => die!()
+        };
+        assert opt_path(matches, ~"o") == Some(Path("out/a.txt"));
+        assert opt_path(matches, ~"p").is_none();
+        assert opt_paths(matches, ~"I") == ~[Path("x"), Path("y/z")];
+    }
+
+    #[test]
+    pub fn test_opt_enum() {
+        let colors = ["never", "auto", "always"];
+        let args = ~[~"--color=always", ~"--bad=Auto", ~"-c", ~"never",
+                     ~"-c", ~"auto"];
+        let opts = ~[optopt(~"color"), optopt(~"bad"), optmulti(~"c")];
+        let matches = &match getopts(args, opts) {
+          result::Ok(move m) => m,
+          result::Err(_) => die!()
+        };
+        assert opt_enum(matches, ~"color", colors) == Ok(Some(2u));
+        // Matching is case-sensitive
+        assert opt_enum(matches, ~"bad", colors) ==
+            Err(~"Option 'bad' expects one of 'never', 'auto', 'always', \
+                  found 'Auto'.");
+        assert opt_enums(matches, ~"c", colors) == Ok(~[0u, 1u]);
+    }
+
+    fn check_even(s: &str) -> result::Result<(), ~str> {
+        match uint::from_str(s) {
+          Some(n) if n % 2 == 0 => Ok(()),
+          _ => Err(~"must be an even number")
+        }
+    }
+
+    #[test]
+    pub fn test_req_with() {
+        let opts = ~[req_with(~"width", check_even),
+                     opt_with(~"w", check_even)];
+        match getopts(~[~"--width=4", ~"-w", ~"6"], opts) {
+          Ok(ref m) => assert opt_uint(m, ~"width") == Ok(Some(4u)),
+          _ => die!()
+        }
+        match getopts(~[~"--width=5"], opts) {
+          Err(copy f) => {
+            check_fail_type(f, InvalidArgument_);
+            assert fail_str(f) ==
+                ~"Invalid argument to option 'width': must be an even number";
+          }
+          _ => die!()
+        }
+        match getopts(~[~"--width=2", ~"-w", ~"x"], opts) {
+          Err(copy f) => {
+            assert f == InvalidArgument(~"w", ~"must be an even number");
+          }
+          _ => die!()
+        }
+        // A missing required option is still reported as such
+        match getopts(~[], opts) {
+          Err(copy f) => check_fail_type(f, OptionMissing_),
+          _ => die!()
+        }
+    }
+
     #[test]
     pub fn test_groups_reqopt() {
         let opt = groups::reqopt(~"b", ~"banana", ~"some bananas", ~"VAL");
@@ -1358,6 +1834,66 @@ Options:
         debug!("generated: <<%s>>", usage);
         assert usage == expected
     }
+
+    #[test]
+    pub fn test_groups_usage_no_short_name() {
+        let optgroups = ~[
+            groups::optopt(~"o", ~"output", ~"set output file", ~"FILE"),
+            groups::optflag(~"", ~"verbose", ~"say more"),
+            groups::optflagopt(~"", ~"color", ~"use colors", ~"WHEN"),
+            groups::optflag(~"h", ~"", ~"print this help"),
+        ];
+
+        let expected =
+~"Usage: prog [options]
+
+Options:
+    -o --output FILE    set output file
+       --verbose        say more
+       --color [WHEN]   use colors
+    -h                  print this help
+
+";
+
+        assert groups::usage(~"Usage: prog [options]", optgroups) == expected;
+    }
+
+    #[test]
+    pub fn test_groups_usage_multiline_description() {
+        let optgroups = ~[
+            groups::optopt(~"e", ~"emit", ~"Output kind. One of:\n\
+                                              \n  \
+                                              asm, llvm-ir, obj, or link \
+                                              (the default), which builds \
+                                              an executable or library\n",
+                           ~"KIND"),
+            groups::optflag(~"", ~"a-very-long-option-name", ~"Desc\nMore"),
+            groups::optflag(~"", ~"another-very-long-option-name", ~""),
+            groups::optflag(~"x", ~"", ~""),
+        ];
+
+        let expected =
+~"Usage: prog
+
+Options:
+    -e --emit KIND      Output kind. One of:
+
+                        asm, llvm-ir, obj, or link (the default), which builds
+                        an executable or library
+       --a-very-long-option-name
+                        Desc
+                        More
+       --another-very-long-option-name
+    -x
+
+";
+
+        let usage = groups::usage(~"Usage: prog", optgroups);
+
+        debug!("expected: <<%s>>", expected);
+        debug!("generated: <<%s>>", usage);
+        assert usage == expected
+    }
 }
 
 // Local Variables:
@@ -0,0 +1,475 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[forbid(non_camel_case_types)];
+
+//! Shell-style pattern matching for paths
+//!
+//! Patterns support the following syntax:
+//!
+//! * `?` matches any single character except `/`
+//! * `*` matches any sequence of characters not containing `/`
+//! * `**`, as a whole path component, matches any number of directories,
+//!   including none; so `a/**/b` matches `a/b` and `a/x/y/b`. At the end of
+//!   a pattern it matches everything below a directory, so `a/**` matches
+//!   `a/b` and `a/x/y/b` but not `a` itself
+//! * `[abc]` matches any one of the listed characters, and `[a-z]` any
+//!   character in the range. `[!...]` matches any character not listed.
+//!
+//! There is no escape character, since `\` is a path separator on
+//! windows. To match a metacharacter literally, put it in brackets:
+//! `[*]`, `[?]` and `[[]` match `*`, `?` and `[`. A `]` or `-` may be
+//! listed first in a bracket expression (after any `!`) to match it
+//! literally.
+//!
+//! Wildcards match dotfiles like any other name. Patterns use `/` to
+//! separate components, and on windows `\` as well; there a pattern may
+//! also start at the root of a drive, as in `C:/*.txt`. Paths are `~str`s,
+//! so file names that are not valid UTF-8 are never matched.
+
+use core::char;
+use core::os;
+use core::prelude::*;
+use core::str;
+use core::uint;
+use core::vec;
+use sort;
+
+enum Token {
+    Char(char),
+    AnyChar,
+    AnySequence,
+    AnyRecursiveSequence,
+    AnyWithin(~[CharSpecifier]),
+    AnyExcept(~[CharSpecifier]),
+}
+
+enum CharSpecifier {
+    SingleChar(char),
+    CharRange(char, char),
+}
+
+/**
+ * Test whether a string matches a pattern, without touching the
+ * filesystem
+ *
+ * The whole string must match. A malformed bracket expression (one with
+ * no closing `]`) matches its characters literally.
+ */
+pub pure fn pattern_matches(pattern: &str, s: &str) -> bool {
+    let tokens = parse(pattern);
+    let chars = str::chars(s);
+    matches_from(tokens, chars)
+}
+
+/**
+ * Find all of the paths that match a pattern
+ *
+ * Relative patterns are matched against the current directory and yield
+ * relative paths. The results are sorted and contain no duplicates.
+ * Symbolic links to directories are followed by `**`, so a link cycle
+ * will recurse until the path becomes too long to open.
+ */
+pub fn glob(pattern: &str) -> ~[Path] {
+    let (root, rest) = split_root(pattern);
+    let components = str::split_nonempty(rest, is_sep);
+
+    let mut results = ~[];
+    glob_from(&Path(root), components, &mut results);
+
+    // The walk only narrows down the candidates; whether a path matches is
+    // up to `pattern_matches`, so that both functions always agree
+    let key = match_key(pattern);
+    let results = do vec::filter(move results) |p| {
+        pattern_matches(key, match_key(p.to_str()))
+    };
+
+    let sorted = sort::merge_sort(results, |a, b| a.to_str() <= b.to_str());
+    let mut deduped: ~[Path] = ~[];
+    for sorted.each |p| {
+        if deduped.is_empty() || vec::last(deduped) != *p {
+            deduped.push(copy *p);
+        }
+    }
+    move deduped
+}
+
+fn glob_from(dir: &Path, components: &[~str], results: &mut ~[Path]) {
+    if components.is_empty() {
+        results.push(copy *dir);
+        return;
+    }
+    let component = copy components[0];
+    let rest = vec::view(components, 1, components.len());
+
+    // An empty relative path means the current directory
+    let listing_dir = if str::is_empty(dir.to_str()) { Path(".") }
+                      else { copy *dir };
+
+    if component == ~"**" {
+        glob_from(dir, rest, results);
+        for os::list_dir(&listing_dir).each |entry| {
+            let child = dir.push(*entry);
+            if os::path_is_dir(&child) {
+                glob_from(&child, components, results);
+            } else if rest.is_empty() {
+                results.push(child);
+            }
+        }
+    } else if !has_metacharacters(component) {
+        let child = dir.push(component);
+        if os::path_exists(&child) {
+            glob_from(&child, rest, results);
+        }
+    } else {
+        let tokens = parse(component);
+        for os::list_dir(&listing_dir).each |entry| {
+            if matches_from(tokens, str::chars(*entry)) {
+                glob_from(&dir.push(*entry), rest, results);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+pure fn is_sep(c: char) -> bool { c == '/' || c == '\\' }
+
+#[cfg(unix)]
+pure fn is_sep(c: char) -> bool { c == '/' }
+
+// Whether `s` starts at the root of a drive, like `C:/`
+#[cfg(windows)]
+pure fn has_drive_root(s: &str) -> bool {
+    s.len() >= 3 && char::is_alphabetic(s[0] as char) &&
+        s[1] == ':' as u8 && is_sep(s[2] as char)
+}
+
+#[cfg(unix)]
+pure fn has_drive_root(_s: &str) -> bool { false }
+
+/// Split a pattern or path into its root, written with `/`, and the rest
+pure fn split_root(s: &str) -> (~str, ~str) {
+    if has_drive_root(s) {
+        (str::slice(s, 0, 2) + "/", str::slice(s, 3, s.len()))
+    } else if !s.is_empty() && is_sep(s[0] as char) {
+        (~"/", str::slice(s, 1, s.len()))
+    } else {
+        (~"", str::from_slice(s))
+    }
+}
+
+/**
+ * The form in which a pattern and the paths found for it are compared:
+ * components separated by a single `/`, with no `.` components. Without
+ * it a pattern like `a//b` or `./a` would reject the paths its walk
+ * found, and on windows, where paths print with `\`, so would every
+ * pattern.
+ */
+pure fn match_key(s: &str) -> ~str {
+    let (root, rest) = split_root(s);
+    let components = str::split_nonempty(rest, is_sep);
+    let components = components.filtered(|c| *c != ~".");
+    root + str::connect(components, "/")
+}
+
+pure fn has_metacharacters(s: &str) -> bool {
+    str::any(s, |c| c == '*' || c == '?' || c == '[')
+}
+
+pure fn parse(pattern: &str) -> ~[Token] {
+    let chars = str::chars(pattern);
+    let len = chars.len();
+    let mut tokens = ~[];
+    let mut i = 0;
+
+    while i < len {
+        match chars[i] {
+            '?' => {
+                tokens.push(AnyChar);
+                i += 1;
+            }
+            '*' => {
+                // `**` is only special as a whole path component
+                let is_start = i == 0 || chars[i - 1] == '/';
+                if is_start && i + 1 < len && chars[i + 1] == '*' &&
+                        (i + 2 == len || chars[i + 2] == '/') {
+                    tokens.push(AnyRecursiveSequence);
+                    // The separator is part of the match
+                    i += 3;
+                } else {
+                    // Collapse runs of `*`
+                    while i < len && chars[i] == '*' { i += 1; }
+                    tokens.push(AnySequence);
+                }
+            }
+            '[' => {
+                match parse_brackets(chars, i + 1) {
+                    Some((move token, next)) => {
+                        tokens.push(move token);
+                        i = next;
+                    }
+                    None => {
+                        tokens.push(Char('['));
+                        i += 1;
+                    }
+                }
+            }
+            c => {
+                tokens.push(Char(c));
+                i += 1;
+            }
+        }
+    }
+    move tokens
+}
+
+/// Parse a bracket expression starting just after its `[`, returning the
+/// token and the index just after the closing `]`
+pure fn parse_brackets(chars: &[char], start: uint) -> Option<(Token, uint)> {
+    let len = chars.len();
+    let mut i = start;
+
+    let negated = i < len && chars[i] == '!';
+    if negated { i += 1; }
+
+    let mut specifiers = ~[];
+    let mut first = true;
+    while i < len {
+        let c = chars[i];
+        if c == ']' && !first {
+            let token = if negated { AnyExcept(move specifiers) }
+                        else { AnyWithin(move specifiers) };
+            return Some((move token, i + 1));
+        }
+        if i + 2 < len && chars[i + 1] == '-' && chars[i + 2] != ']' {
+            specifiers.push(CharRange(c, chars[i + 2]));
+            i += 3;
+        } else {
+            specifiers.push(SingleChar(c));
+            i += 1;
+        }
+        first = false;
+    }
+    None
+}
+
+pure fn in_specifiers(specifiers: &[CharSpecifier], c: char) -> bool {
+    for specifiers.each |specifier| {
+        match *specifier {
+            SingleChar(sc) => if c == sc { return true; },
+            CharRange(lo, hi) => if c >= lo && c <= hi { return true; }
+        }
+    }
+    false
+}
+
+pure fn matches_from(tokens: &[Token], chars: &[char]) -> bool {
+    if tokens.is_empty() { return chars.is_empty(); }
+
+    let rest = vec::view(tokens, 1, tokens.len());
+    match tokens[0] {
+        AnySequence => {
+            for uint::range(0, chars.len() + 1) |i| {
+                if i > 0 && chars[i - 1] == '/' { break; }
+                if matches_from(rest, vec::view(chars, i, chars.len())) {
+                    return true;
+                }
+            }
+            false
+        }
+        AnyRecursiveSequence => {
+            // Matches nothing, or any number of whole components
+            if rest.is_empty() { return true; }
+            for uint::range(0, chars.len() + 1) |i| {
+                if (i == 0 || chars[i - 1] == '/') &&
+                        matches_from(rest, vec::view(chars, i, chars.len())) {
+                    return true;
+                }
+            }
+            false
+        }
+        _ if chars.is_empty() => false,
+        Char(c) => {
+            chars[0] == c &&
+                matches_from(rest, vec::view(chars, 1, chars.len()))
+        }
+        AnyChar => {
+            chars[0] != '/' &&
+                matches_from(rest, vec::view(chars, 1, chars.len()))
+        }
+        AnyWithin(ref specifiers) => {
+            chars[0] != '/' && in_specifiers(*specifiers, chars[0]) &&
+                matches_from(rest, vec::view(chars, 1, chars.len()))
+        }
+        AnyExcept(ref specifiers) => {
+            chars[0] != '/' && !in_specifiers(*specifiers, chars[0]) &&
+                matches_from(rest, vec::view(chars, 1, chars.len()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::prelude::*;
+
+    use glob::*;
+    use tempfile;
+
+    use core::io;
+    use core::os;
+    use core::str;
+
+    #[test]
+    fn test_wildcards() {
+        assert pattern_matches("a*b", "a_b");
+        assert pattern_matches("a*b*c", "abc");
+        assert !pattern_matches("a*b*c", "abcd");
+        assert pattern_matches("a*b*c", "a_b_c");
+        assert pattern_matches("a*b*c", "a___b___c");
+        assert pattern_matches("abc*abc*abc", "abcabcabcabcabcabcabc");
+        assert !pattern_matches("abc*abc*abc", "abcabcabcabcabcabcabca");
+        assert pattern_matches("*", "");
+        assert pattern_matches("???", "abc");
+        assert !pattern_matches("???", "ab");
+        assert !pattern_matches("a*", "a/b");
+        assert !pattern_matches("a?b", "a/b");
+    }
+
+    #[test]
+    fn test_recursive_wildcards() {
+        assert pattern_matches("some/**/needle.txt", "some/needle.txt");
+        assert pattern_matches("some/**/needle.txt",
+                               "some/one/two/needle.txt");
+        assert !pattern_matches("some/**/needle.txt", "some/other.txt");
+        assert pattern_matches("**/*.rs", "a.rs");
+        assert pattern_matches("**/*.rs", "src/lib/a.rs");
+        assert pattern_matches("src/**", "src/a/b");
+        assert pattern_matches("src/**", "src/a");
+        assert !pattern_matches("src/**", "src");
+        assert !pattern_matches("src/**", "other/a");
+        // Not a whole component, so it's an ordinary `*`
+        assert !pattern_matches("a**/b", "ax/y/b");
+        assert pattern_matches("a**/b", "axy/b");
+    }
+
+    #[test]
+    fn test_character_classes() {
+        assert pattern_matches("[abc]", "b");
+        assert !pattern_matches("[abc]", "d");
+        assert pattern_matches("[a-z]x", "qx");
+        assert !pattern_matches("[a-z]", "Q");
+        assert pattern_matches("[!a-z]", "Q");
+        assert !pattern_matches("[!a-z]", "q");
+        assert pattern_matches("[0-9a-f]", "c");
+        assert pattern_matches("[]]", "]");
+        assert pattern_matches("[!]]", "a");
+        assert pattern_matches("[-a]", "-");
+        assert pattern_matches("[a-]", "-");
+        assert !pattern_matches("[a-z]", "/");
+        assert !pattern_matches("[!a]", "/");
+    }
+
+    #[test]
+    fn test_match_key() {
+        assert match_key("a//b") == ~"a/b";
+        assert match_key("./a/./b/") == ~"a/b";
+        assert match_key("//x//*.rs") == ~"/x/*.rs";
+        assert match_key(".") == ~"";
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_match_key_windows() {
+        assert match_key("C:\\foo\\a.txt") == ~"C:/foo/a.txt";
+        assert match_key("c:/foo//*.txt") == ~"c:/foo/*.txt";
+        assert match_key("\\foo") == ~"/foo";
+        assert split_root("C:\\") == (~"C:/", ~"");
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert pattern_matches("[*]", "*");
+        assert !pattern_matches("[*]", "x");
+        assert pattern_matches("a[?]", "a?");
+        assert !pattern_matches("a[?]", "ab");
+        assert pattern_matches("[[]x]", "[x]");
+        // An unclosed bracket is literal
+        assert pattern_matches("a[b", "a[b");
+    }
+
+    fn touch(p: &Path) {
+        match io::file_writer(p, ~[io::Create]) {
+            Ok(_) => (),
+            Err(e) => die!(e)
+        }
+    }
+
+    #[test]
+    fn test_glob_filesystem() {
+        let tmp = tempfile::TempDir(&Path("glob")).unwrap();
+        let root = tmp.path();
+        assert os::make_dir(&root.push("src"), 0x1c0);
+        assert os::make_dir(&root.push_many(~[~"src", ~"sub"]), 0x1c0);
+        touch(&root.push("b.txt"));
+        touch(&root.push("a.txt"));
+        touch(&root.push("c.rs"));
+        touch(&root.push_many(~[~"src", ~"lib.rs"]));
+        touch(&root.push_many(~[~"src", ~"sub", ~"mod.rs"]));
+        touch(&root.push_many(~[~"src", ~"sub", ~"[x].txt"]));
+
+        let base = root.to_str();
+        let glob_in = |pattern: &str| {
+            glob(base + "/" + pattern).map(|p| {
+                str::replace(p.to_str(), base + "/", "")
+            })
+        };
+
+        assert glob_in("*.txt") == ~[~"a.txt", ~"b.txt"];
+        assert glob_in("?.*") == ~[~"a.txt", ~"b.txt", ~"c.rs"];
+        assert glob_in("[ab].txt") == ~[~"a.txt", ~"b.txt"];
+        assert glob_in("**/*.rs") ==
+            ~[~"c.rs", ~"src/lib.rs", ~"src/sub/mod.rs"];
+        // Agrees with `pattern_matches` in leaving out `src` itself
+        assert glob_in("src/**") ==
+            ~[~"src/lib.rs", ~"src/sub", ~"src/sub/[x].txt",
+              ~"src/sub/mod.rs"];
+        for glob(base + "/src/**").each |p| {
+            assert pattern_matches(base + "/src/**", p.to_str());
+        }
+        assert glob_in("src/**/mod.rs") == ~[~"src/sub/mod.rs"];
+        assert glob_in("src/**/lib.rs") == ~[~"src/lib.rs"];
+        assert glob_in("src/*/[[]x].txt") == ~[~"src/sub/[x].txt"];
+        assert glob_in("src/lib.rs") == ~[~"src/lib.rs"];
+        assert glob_in("nothing*") == ~[];
+        assert glob_in("missing/*") == ~[];
+
+        // Doubled separators and `.` components don't lose matches
+        assert glob_in("src//*.rs") == ~[~"src/lib.rs"];
+        assert glob_in("src/./sub/*.rs") == ~[~"src/./sub/mod.rs"];
+        assert glob_in("./*.rs") == ~[~"./c.rs"];
+        assert glob(base + "//c.rs").len() == 1;
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_glob_drive_root() {
+        let tmp = tempfile::TempDir(&Path("glob")).unwrap();
+        let root = tmp.path();
+        touch(&root.push("a.txt"));
+        touch(&root.push("b.rs"));
+
+        // A path like `C:\...`, in either kind of separator
+        let base = root.to_str();
+        assert has_drive_root(base);
+        assert glob(base + "\\*.txt") == ~[root.push("a.txt")];
+        assert glob(str::replace(base, "\\", "/") + "/*.txt") ==
+            ~[root.push("a.txt")];
+    }
+}
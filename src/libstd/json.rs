@@ -25,6 +25,8 @@ use core::io::{WriterUtil, ReaderUtil};
 use core::io;
 use core::prelude::*;
 use core::hashmap::linear::LinearMap;
+use core::i64;
+use core::ptr;
 use core::str;
 use core::to_str;
 use core::vec;
@@ -42,13 +44,43 @@ pub enum Json {
 pub type List = ~[Json];
 pub type Object = LinearMap<~str, Json>;
 
+/// What was wrong with a json document
+#[deriving_eq]
+pub enum ErrorKind {
+    EOFWhileParsingValue,
+    EOFWhileParsingString,
+    EOFWhileParsingList,
+    EOFWhileParsingObject,
+    InvalidSyntax,
+    InvalidNumber,
+    InvalidEscape,
+    InvalidUnicodeEscape,
+    UnpairedSurrogate,
+    KeyMustBeAString,
+    ExpectedColon,
+    ExpectedCommaOrListEnd,
+    ExpectedCommaOrObjectEnd,
+    TrailingComma,
+    TrailingCharacters,
+}
+
+/**
+ * An error in a json document, found at the char `offset` bytes from the
+ * start of the document. The line and column of that char count from 1,
+ * with columns counted in chars.
+ */
+#[deriving_eq]
 pub struct Error {
+    offset: uint,
     line: uint,
     col: uint,
-    msg: @~str,
+    kind: ErrorKind,
 }
 
-fn escape_str(s: &str) -> ~str {
+// Quote a string for json. Control characters are always escaped, and
+// every non-ASCII character is written as a \u escape (a surrogate pair
+// outside the basic multilingual plane) if `ascii_only` is set.
+fn escape_str(s: &str, ascii_only: bool) -> ~str {
     let mut escaped = ~"\"";
     for str::chars_each(s) |c| {
         match c {
@@ -59,7 +91,11 @@ fn escape_str(s: &str) -> ~str {
           '\n' => escaped += ~"\\n",
           '\r' => escaped += ~"\\r",
           '\t' => escaped += ~"\\t",
-          _ => escaped += str::from_char(c)
+          '\x00' .. '\x1f' | '\x7f' => push_unicode_escape(&mut escaped, c),
+          _ if ascii_only && c > '\x7f' => {
+              push_unicode_escape(&mut escaped, c)
+          }
+          _ => str::push_char(&mut escaped, c)
         }
     };
 
@@ -68,6 +104,51 @@ fn escape_str(s: &str) -> ~str {
     escaped
 }
 
+fn push_unicode_escape(s: &mut ~str, c: char) {
+    fn push_hex4(s: &mut ~str, n: uint) {
+        str::push_str(s, "\\u");
+        for [12u, 8u, 4u, 0u].each |shift| {
+            let digit = (n >> *shift) & 0xf;
+            str::push_char(s, char::from_digit(digit, 16).get());
+        }
+    }
+
+    let n = c as uint;
+    if n > 0xffff {
+        let n = n - 0x10000;
+        push_hex4(s, 0xd800 + (n >> 10));
+        push_hex4(s, 0xdc00 + (n & 0x3ff));
+    } else {
+        push_hex4(s, n);
+    }
+}
+
+// Integral numbers are written without a fractional part, and the values
+// json has no representation for as null
+fn number_to_str(v: float) -> ~str {
+    if float::is_NaN(v) || float::is_infinite(v) {
+        ~"null"
+    } else if v == float::floor(v) && float::abs(v) < 9007199254740992f {
+        i64::to_str(v as i64)
+    } else {
+        float::to_str_digits(v, 6u)
+    }
+}
+
+// The number of bytes in the utf-8 encoding of a char
+fn utf8_len(c: char) -> uint {
+    let n = c as uint;
+    if n < 0x80 {
+        1
+    } else if n < 0x800 {
+        2
+    } else if n < 0x10000 {
+        3
+    } else {
+        4
+    }
+}
+
 fn spaces(n: uint) -> ~str {
     let mut ss = ~"";
     for n.times { str::push_str(&mut ss, " "); }
@@ -108,12 +189,14 @@ pub impl Encoder: serialize::Encoder {
     fn emit_f64(&self, v: f64) { self.emit_float(v as float); }
     fn emit_f32(&self, v: f32) { self.emit_float(v as float); }
     fn emit_float(&self, v: float) {
-        self.wr.write_str(float::to_str_digits(v, 6u));
+        self.wr.write_str(number_to_str(v));
     }
 
     fn emit_char(&self, v: char) { self.emit_borrowed_str(str::from_char(v)) }
 
-    fn emit_borrowed_str(&self, v: &str) { self.wr.write_str(escape_str(v)) }
+    fn emit_borrowed_str(&self, v: &str) {
+        self.wr.write_str(escape_str(v, false))
+    }
     fn emit_owned_str(&self, v: &str) { self.emit_borrowed_str(v) }
     fn emit_managed_str(&self, v: &str) { self.emit_borrowed_str(v) }
 
@@ -164,7 +247,7 @@ pub impl Encoder: serialize::Encoder {
     }
     fn emit_field(&self, name: &str, idx: uint, f: fn()) {
         if idx != 0 { self.wr.write_char(','); }
-        self.wr.write_str(escape_str(name));
+        self.wr.write_str(escape_str(name, false));
         self.wr.write_char(':');
         f();
     }
@@ -177,13 +260,58 @@ pub impl Encoder: serialize::Encoder {
     }
 }
 
+/**
+ * An encoder writing one value per line, with nested values indented by
+ * `indent` spaces per level (two by default). If `ascii_only` is set,
+ * non-ASCII characters in strings are written as \u escapes.
+ */
 pub struct PrettyEncoder {
     priv wr: io::Writer,
-    priv mut indent: uint,
+    indent: uint,
+    ascii_only: bool,
+    priv mut depth: uint,
+    // Whether the innermost list or object being written has any items
+    priv mut nonempty: bool,
 }
 
 pub fn PrettyEncoder(wr: io::Writer) -> PrettyEncoder {
-    PrettyEncoder { wr: wr, indent: 0 }
+    PrettyEncoder {
+        wr: wr,
+        indent: 2,
+        ascii_only: false,
+        depth: 0,
+        nonempty: false,
+    }
+}
+
+priv impl PrettyEncoder {
+    // Write a list or object, putting the closing bracket on a line of its
+    // own unless it is empty
+    fn emit_nested(&self, open: char, close: char, f: fn()) {
+        let nonempty = self.nonempty;
+        self.wr.write_char(open);
+        self.nonempty = false;
+        self.depth += 1;
+        f();
+        self.depth -= 1;
+        if self.nonempty {
+            self.wr.write_char('\n');
+            self.wr.write_str(spaces(self.depth * self.indent));
+        }
+        self.wr.write_char(close);
+        self.nonempty = nonempty;
+    }
+
+    // Start a new line for an item of the innermost list or object
+    fn emit_item_line(&self, idx: uint) {
+        if idx == 0 {
+            self.wr.write_char('\n');
+        } else {
+            self.wr.write_str(",\n");
+        }
+        self.wr.write_str(spaces(self.depth * self.indent));
+        self.nonempty = true;
+    }
 }
 
 pub impl PrettyEncoder: serialize::Encoder {
@@ -212,12 +340,14 @@ pub impl PrettyEncoder: serialize::Encoder {
     fn emit_f64(&self, v: f64) { self.emit_float(v as float); }
     fn emit_f32(&self, v: f32) { self.emit_float(v as float); }
     fn emit_float(&self, v: float) {
-        self.wr.write_str(float::to_str_digits(v, 6u));
+        self.wr.write_str(number_to_str(v));
     }
 
     fn emit_char(&self, v: char) { self.emit_borrowed_str(str::from_char(v)) }
 
-    fn emit_borrowed_str(&self, v: &str) { self.wr.write_str(escape_str(v)); }
+    fn emit_borrowed_str(&self, v: &str) {
+        self.wr.write_str(escape_str(v, self.ascii_only));
+    }
     fn emit_owned_str(&self, v: &str) { self.emit_borrowed_str(v) }
     fn emit_managed_str(&self, v: &str) { self.emit_borrowed_str(v) }
 
@@ -241,11 +371,7 @@ pub impl PrettyEncoder: serialize::Encoder {
     }
 
     fn emit_borrowed_vec(&self, _len: uint, f: fn()) {
-        self.wr.write_char('[');
-        self.indent += 2;
-        f();
-        self.indent -= 2;
-        self.wr.write_char(']');
+        self.emit_nested('[', ']', f)
     }
     fn emit_owned_vec(&self, len: uint, f: fn()) {
         self.emit_borrowed_vec(len, f)
@@ -254,33 +380,19 @@ pub impl PrettyEncoder: serialize::Encoder {
         self.emit_borrowed_vec(len, f)
     }
     fn emit_vec_elt(&self, idx: uint, f: fn()) {
-        if idx == 0 {
-            self.wr.write_char('\n');
-        } else {
-            self.wr.write_str(",\n");
-        }
-        self.wr.write_str(spaces(self.indent));
+        self.emit_item_line(idx);
         f()
     }
 
     fn emit_rec(&self, f: fn()) {
-        self.wr.write_char('{');
-        self.indent += 2;
-        f();
-        self.indent -= 2;
-        self.wr.write_char('}');
+        self.emit_nested('{', '}', f)
     }
     fn emit_struct(&self, _name: &str, _len: uint, f: fn()) {
         self.emit_rec(f)
     }
     fn emit_field(&self, name: &str, idx: uint, f: fn()) {
-        if idx == 0 {
-            self.wr.write_char('\n');
-        } else {
-            self.wr.write_str(",\n");
-        }
-        self.wr.write_str(spaces(self.indent));
-        self.wr.write_str(escape_str(name));
+        self.emit_item_line(idx);
+        self.wr.write_str(escape_str(name, self.ascii_only));
         self.wr.write_str(": ");
         f();
     }
@@ -300,13 +412,16 @@ pub impl<S: serialize::Encoder> Json: serialize::Encodable<S> {
             Boolean(v) => v.encode(s),
             List(ref v) => v.encode(s),
             Object(ref v) => {
+                // Sort the keys so that the output does not depend on the
+                // order of the hash map
+                let mut keys = ~[];
+                for v.each_key |key| { keys.push(copy *key); }
+                keys.qsort();
                 do s.emit_rec || {
-                    let mut idx = 0;
-                    for v.each |key, value| {
+                    for keys.eachi |idx, key| {
                         do s.emit_field(*key, idx) {
-                            value.encode(s);
+                            v.get(key).encode(s);
                         }
-                        idx += 1;
                     }
                 }
             },
@@ -328,19 +443,28 @@ pub pure fn to_str(json: &Json) -> ~str {
     }
 }
 
-/// Encodes a json value into a io::writer
+/// Encodes a json value into a io::writer, one value per line
 pub fn to_pretty_writer(wr: io::Writer, json: &Json) {
     json.encode(&PrettyEncoder(wr))
 }
 
-/// Encodes a json value into a string
+/// Encodes a json value into a string, one value per line
 pub fn to_pretty_str(json: &Json) -> ~str {
     io::with_str_writer(|wr| to_pretty_writer(wr, json))
 }
 
+/**
+ * A parser reading a json value from an io::reader one char at a time, so
+ * that the document never has to be in memory as a whole.
+ *
+ * A comma before the `]` or `}` closing a list or object is an error
+ * unless `allow_trailing_commas` is set.
+ */
 pub struct Parser {
     priv rdr: io::Reader,
+    allow_trailing_commas: bool,
     priv mut ch: char,
+    priv mut offset: uint,
     priv mut line: uint,
     priv mut col: uint,
 }
@@ -349,7 +473,9 @@ pub struct Parser {
 pub fn Parser(rdr: io::Reader) -> Parser {
     Parser {
         rdr: rdr,
+        allow_trailing_commas: false,
         ch: rdr.read_char(),
+        offset: 0,
         line: 1,
         col: 1,
     }
@@ -365,7 +491,7 @@ pub impl Parser {
             if self.eof() {
                 Ok(move value)
             } else {
-                self.error(~"trailing characters")
+                self.error(TrailingCharacters)
             }
           }
           Err(move e) => Err(e)
@@ -377,14 +503,17 @@ priv impl Parser {
     fn eof() -> bool { self.ch == -1 as char }
 
     fn bump() {
-        self.ch = self.rdr.read_char();
+        if self.eof() { return; }
 
+        self.offset += utf8_len(self.ch);
         if self.ch == '\n' {
             self.line += 1u;
             self.col = 1u;
         } else {
             self.col += 1u;
         }
+
+        self.ch = self.rdr.read_char();
     }
 
     fn next_char() -> char {
@@ -392,14 +521,19 @@ priv impl Parser {
         self.ch
     }
 
-    fn error<T>(msg: ~str) -> Result<T, Error> {
-        Err(Error { line: self.line, col: self.col, msg: @msg })
+    fn error<T>(kind: ErrorKind) -> Result<T, Error> {
+        Err(Error {
+            offset: self.offset,
+            line: self.line,
+            col: self.col,
+            kind: kind
+        })
     }
 
     fn parse_value() -> Result<Json, Error> {
         self.parse_whitespace();
 
-        if self.eof() { return self.error(~"EOF while parsing value"); }
+        if self.eof() { return self.error(EOFWhileParsingValue); }
 
         match self.ch {
           'n' => self.parse_ident(~"ull", Null),
@@ -413,8 +547,29 @@ priv impl Parser {
             },
           '[' => self.parse_list(),
           '{' => self.parse_object(),
-          _ => self.error(~"invalid syntax")
+          _ => self.error(InvalidSyntax)
+        }
+    }
+
+    // Parse the four hex digits of a \u escape
+    fn parse_hex4() -> Result<uint, Error> {
+        let mut i = 0u;
+        let mut n = 0u;
+        while i < 4u {
+            match self.next_char() {
+              '0' .. '9' => n = n * 16u + (self.ch as uint) - ('0' as uint),
+              'a' | 'A' => n = n * 16u + 10u,
+              'b' | 'B' => n = n * 16u + 11u,
+              'c' | 'C' => n = n * 16u + 12u,
+              'd' | 'D' => n = n * 16u + 13u,
+              'e' | 'E' => n = n * 16u + 14u,
+              'f' | 'F' => n = n * 16u + 15u,
+              _ => return self.error(InvalidUnicodeEscape)
+            }
+            i += 1u;
         }
+
+        Ok(n)
     }
 
     fn parse_whitespace() {
@@ -426,7 +581,7 @@ priv impl Parser {
             self.bump();
             Ok(move value)
         } else {
-            self.error(~"invalid syntax")
+            self.error(InvalidSyntax)
         }
     }
 
@@ -469,7 +624,7 @@ priv impl Parser {
 
             // There can be only one leading '0'.
             match self.ch {
-              '0' .. '9' => return self.error(~"invalid number"),
+              '0' .. '9' => return self.error(InvalidNumber),
               _ => ()
             }
           }
@@ -486,7 +641,7 @@ priv impl Parser {
                 }
             }
           }
-          _ => return self.error(~"invalid number")
+          _ => return self.error(InvalidNumber)
         }
 
         Ok(res)
@@ -498,7 +653,7 @@ priv impl Parser {
         // Make sure a digit follows the decimal place.
         match self.ch {
           '0' .. '9' => (),
-          _ => return self.error(~"invalid number")
+          _ => return self.error(InvalidNumber)
         }
 
         let mut res = res;
@@ -534,7 +689,7 @@ priv impl Parser {
         // Make sure a digit follows the exponent place.
         match self.ch {
           '0' .. '9' => (),
-          _ => return self.error(~"invalid number")
+          _ => return self.error(InvalidNumber)
         }
 
         while !self.eof() {
@@ -577,36 +732,35 @@ priv impl Parser {
                   'r' => str::push_char(&mut res, '\r'),
                   't' => str::push_char(&mut res, '\t'),
                   'u' => {
-                      // Parse \u1234.
-                      let mut i = 0u;
-                      let mut n = 0u;
-                      while i < 4u {
-                          match self.next_char() {
-                            '0' .. '9' => {
-                              n = n * 16u + (self.ch as uint)
-                                          - ('0'     as uint);
-                            },
-                            'a' | 'A' => n = n * 16u + 10u,
-                            'b' | 'B' => n = n * 16u + 11u,
-                            'c' | 'C' => n = n * 16u + 12u,
-                            'd' | 'D' => n = n * 16u + 13u,
-                            'e' | 'E' => n = n * 16u + 14u,
-                            'f' | 'F' => n = n * 16u + 15u,
-                            _ => return self.error(
-                                   ~"invalid \\u escape (unrecognized hex)")
-                          }
-                          i += 1u;
+                      let n = match self.parse_hex4() {
+                        Ok(n) => n,
+                        Err(e) => return Err(e)
+                      };
+                      // A character outside the basic multilingual plane
+                      // is escaped as a surrogate pair.
+                      if n >= 0xdc00 && n < 0xe000 {
+                          return self.error(UnpairedSurrogate);
                       }
-
-                      // Error out if we didn't parse 4 digits.
-                      if i != 4u {
-                          return self.error(
-                            ~"invalid \\u escape (not four digits)");
+                      if n >= 0xd800 && n < 0xdc00 {
+                          if self.next_char() != '\\' ||
+                             self.next_char() != 'u' {
+                              return self.error(UnpairedSurrogate);
+                          }
+                          let low = match self.parse_hex4() {
+                            Ok(low) => low,
+                            Err(e) => return Err(e)
+                          };
+                          if low < 0xdc00 || low >= 0xe000 {
+                              return self.error(UnpairedSurrogate);
+                          }
+                          let c = 0x10000 + ((n - 0xd800) << 10) +
+                                  (low - 0xdc00);
+                          str::push_char(&mut res, c as char);
+                      } else {
+                          str::push_char(&mut res, n as char);
                       }
-
-                      str::push_char(&mut res, n as char);
                   }
-                  _ => return self.error(~"invalid escape")
+                  _ => return self.error(InvalidEscape)
                 }
                 escape = false;
             } else if self.ch == '\\' {
@@ -620,7 +774,7 @@ priv impl Parser {
             }
         }
 
-        self.error(~"EOF while parsing string")
+        self.error(EOFWhileParsingString)
     }
 
     fn parse_list() -> Result<Json, Error> {
@@ -642,13 +796,23 @@ priv impl Parser {
 
             self.parse_whitespace();
             if self.eof() {
-                return self.error(~"EOF while parsing list");
+                return self.error(EOFWhileParsingList);
             }
 
             match self.ch {
-              ',' => self.bump(),
+              ',' => {
+                self.bump();
+                self.parse_whitespace();
+                if self.ch == ']' {
+                    if !self.allow_trailing_commas {
+                        return self.error(TrailingComma);
+                    }
+                    self.bump();
+                    return Ok(List(move values));
+                }
+              }
               ']' => { self.bump(); return Ok(List(move values)); }
-              _ => return self.error(~"expected `,` or `]`")
+              _ => return self.error(ExpectedCommaOrListEnd)
             }
         };
     }
@@ -668,7 +832,7 @@ priv impl Parser {
             self.parse_whitespace();
 
             if self.ch != '"' {
-                return self.error(~"key must be a string");
+                return self.error(KeyMustBeAString);
             }
 
             let key = match move self.parse_str() {
@@ -680,7 +844,7 @@ priv impl Parser {
 
             if self.ch != ':' {
                 if self.eof() { break; }
-                return self.error(~"expected `:`");
+                return self.error(ExpectedColon);
             }
             self.bump();
 
@@ -691,16 +855,26 @@ priv impl Parser {
             self.parse_whitespace();
 
             match self.ch {
-              ',' => self.bump(),
+              ',' => {
+                self.bump();
+                self.parse_whitespace();
+                if self.ch == '}' {
+                    if !self.allow_trailing_commas {
+                        return self.error(TrailingComma);
+                    }
+                    self.bump();
+                    return Ok(Object(move values));
+                }
+              }
               '}' => { self.bump(); return Ok(Object(move values)); }
               _ => {
                   if self.eof() { break; }
-                  return self.error(~"expected `,` or `}`");
+                  return self.error(ExpectedCommaOrObjectEnd);
               }
             }
         }
 
-        return self.error(~"EOF while parsing object");
+        return self.error(EOFWhileParsingObject);
     }
 }
 
@@ -716,13 +890,57 @@ pub fn from_str(s: &str) -> Result<Json, Error> {
     }
 }
 
+/**
+ * An error found while decoding a value from json: the value at `path`,
+ * e.g. `server.ports[2]`, was not of the `expected` type. A missing field
+ * is found as `no value`, which is never the same error as a field that is
+ * `null`.
+ */
+#[deriving_eq]
+pub struct DecodeError {
+    path: ~str,
+    expected: ~str,
+    found: ~str,
+}
+
+/**
+ * Decodes values from a Json value.
+ *
+ * An `Option` is `None` when it is `null`. A field missing from its object
+ * is an error whatever its type, so an optional field must be present, even
+ * if only as `null`; this is how `Encoder` writes `None`. Since `Some(v)` is
+ * encoded as just `v`, a value of `Some(None)` decodes as `None`.
+ *
+ * A value of the wrong type, or a missing field, does not fail the
+ * task. The decoder records the first such error, which `error` returns
+ * afterwards, and carries on with placeholder values, so a value decoded
+ * with an error is meaningless. `decode` does this check for you.
+ */
 pub struct Decoder {
     priv json: Json,
     priv mut stack: ~[&Json],
+    // The fields and list indices leading to the value being decoded, each
+    // already formatted as it appears in a path
+    priv mut path: ~[~str],
+    priv mut first_error: Option<DecodeError>,
+    // Stands in for missing fields, and for the contents of values of the
+    // wrong type
+    priv null: Json,
 }
 
 pub fn Decoder(json: Json) -> Decoder {
-    Decoder { json: move json, stack: ~[] }
+    Decoder {
+        json: move json,
+        stack: ~[],
+        path: ~[],
+        first_error: None,
+        null: Null,
+    }
+}
+
+pub impl Decoder {
+    /// Return the first error found while decoding, if any
+    fn error(&self) -> Option<DecodeError> { copy self.first_error }
 }
 
 priv impl Decoder {
@@ -735,14 +953,53 @@ priv impl Decoder {
         if self.stack.len() == 0 { self.stack.push(&self.json); }
         self.stack.pop()
     }
+
+    // Whether `json` is the placeholder for a missing value, rather than a
+    // `null` in the document
+    fn is_missing(&self, json: &Json) -> bool {
+        ptr::ref_eq(json, &self.null)
+    }
+
+    // Record that the value at the current path is not what was expected,
+    // unless an error has already been found
+    fn mismatch(&self, expected: &str, found: &Json) {
+        if self.first_error.is_some() { return; }
+        let found = if self.is_missing(found) {
+            ~"no value"
+        } else {
+            match *found {
+                Number(_) => ~"a number",
+                String(_) => ~"a string",
+                Boolean(_) => ~"a boolean",
+                List(_) => ~"a list",
+                Object(_) => ~"an object",
+                Null => ~"null",
+            }
+        };
+        self.first_error = Some(DecodeError {
+            path: str::concat(self.path),
+            expected: expected.to_owned(),
+            found: found,
+        });
+    }
+
+    // Decode `value` with `f`, naming it `elt` in paths
+    fn read_elt<T>(&self, elt: ~str, value: &self/Json, f: fn() -> T) -> T {
+        self.path.push(elt);
+        self.stack.push(value);
+        let res = f();
+        self.path.pop();
+        move res
+    }
 }
 
 pub impl Decoder: serialize::Decoder {
     fn read_nil(&self) -> () {
         debug!("read_nil");
-        match *self.pop() {
-            Null => (),
-            _ => die!(~"not a null")
+        let json = self.pop();
+        match *json {
+            Null if !self.is_missing(json) => (),
+            _ => self.mismatch("null", json)
         }
     }
 
@@ -760,9 +1017,10 @@ pub impl Decoder: serialize::Decoder {
 
     fn read_bool(&self) -> bool {
         debug!("read_bool");
-        match *self.pop() {
+        let json = self.pop();
+        match *json {
             Boolean(b) => b,
-            _ => die!(~"not a boolean")
+            _ => { self.mismatch("a boolean", json); false }
         }
     }
 
@@ -770,31 +1028,37 @@ pub impl Decoder: serialize::Decoder {
     fn read_f32(&self) -> f32 { self.read_float() as f32 }
     fn read_float(&self) -> float {
         debug!("read_float");
-        match *self.pop() {
+        let json = self.pop();
+        match *json {
             Number(f) => f,
-            _ => die!(~"not a number")
+            _ => { self.mismatch("a number", json); 0f }
         }
     }
 
     fn read_char(&self) -> char {
-        let v = str::chars(self.read_owned_str());
-        if v.len() != 1 { die!(~"string must have one character") }
-        v[0]
+        debug!("read_char");
+        let json = self.pop();
+        match *json {
+            String(ref s) if str::char_len(*s) == 1 => str::char_at(*s, 0),
+            _ => { self.mismatch("a string of one character", json); ' ' }
+        }
     }
 
     fn read_owned_str(&self) -> ~str {
         debug!("read_owned_str");
-        match *self.pop() {
+        let json = self.pop();
+        match *json {
             String(ref s) => copy *s,
-            _ => die!(~"not a string")
+            _ => { self.mismatch("a string", json); ~"" }
         }
     }
 
     fn read_managed_str(&self) -> @str {
         debug!("read_managed_str");
-        match *self.pop() {
+        let json = self.pop();
+        match *json {
             String(ref s) => s.to_managed(),
-            _ => die!(~"not a string")
+            _ => { self.mismatch("a string", json); "".to_managed() }
         }
     }
 
@@ -816,11 +1080,22 @@ pub impl Decoder: serialize::Decoder {
 
     fn read_enum_variant<T>(&self, f: fn(uint) -> T) -> T {
         debug!("read_enum_variant()");
-        let idx = match *self.peek() {
-            Null => 0,
-            _ => 1,
+        let json = self.peek();
+        if self.is_missing(json) {
+            self.mismatch("a value or null", json);
+            self.pop();
+            return f(0);
+        }
+        let is_null = match *json {
+            Null => true,
+            _ => false,
         };
-        f(idx)
+        if is_null {
+            self.pop();
+            f(0)
+        } else {
+            f(1)
+        }
     }
 
     fn read_enum_variant_arg<T>(&self, idx: uint, f: fn() -> T) -> T {
@@ -831,9 +1106,10 @@ pub impl Decoder: serialize::Decoder {
 
     fn read_owned_vec<T>(&self, f: fn(uint) -> T) -> T {
         debug!("read_owned_vec()");
-        let len = match *self.peek() {
-            List(list) => list.len(),
-            _ => die!(~"not a list"),
+        let json = self.peek();
+        let len = match *json {
+            List(ref list) => list.len(),
+            _ => { self.mismatch("a list", json); 0 }
         };
         let res = f(len);
         self.pop();
@@ -841,29 +1117,25 @@ pub impl Decoder: serialize::Decoder {
     }
 
     fn read_managed_vec<T>(&self, f: fn(uint) -> T) -> T {
-        debug!("read_owned_vec()");
-        let len = match *self.peek() {
-            List(ref list) => list.len(),
-            _ => die!(~"not a list"),
-        };
-        let res = f(len);
-        self.pop();
-        move res
+        debug!("read_managed_vec()");
+        self.read_owned_vec(f)
     }
 
     fn read_vec_elt<T>(&self, idx: uint, f: fn() -> T) -> T {
         debug!("read_vec_elt(idx=%u)", idx);
         match *self.peek() {
-            List(ref list) => {
-                self.stack.push(&list[idx]);
-                f()
-            }
+            List(ref list) => self.read_elt(fmt!("[%u]", idx), &list[idx], f),
             _ => die!(~"not a list"),
         }
     }
 
     fn read_rec<T>(&self, f: fn() -> T) -> T {
         debug!("read_rec()");
+        let json = self.peek();
+        match *json {
+            Object(_) => (),
+            _ => self.mismatch("an object", json)
+        }
         let value = f();
         self.pop();
         move value
@@ -871,36 +1143,35 @@ pub impl Decoder: serialize::Decoder {
 
     fn read_struct<T>(&self, _name: &str, _len: uint, f: fn() -> T) -> T {
         debug!("read_struct()");
-        let value = f();
-        self.pop();
-        move value
+        self.read_rec(f)
     }
 
     fn read_field<T>(&self, name: &str, idx: uint, f: fn() -> T) -> T {
-        debug!("read_rec_field(%s, idx=%u)", name, idx);
-        let top = self.peek();
-        match *top {
+        debug!("read_field(%s, idx=%u)", name, idx);
+        let elt = if self.path.is_empty() {
+            name.to_owned()
+        } else {
+            ~"." + name
+        };
+        match *self.peek() {
             Object(ref obj) => {
                 match obj.find(&name.to_owned()) {
-                    None => die!(fmt!("no such field: %s", name)),
-                    Some(json) => {
-                        self.stack.push(json);
-                        f()
-                    }
+                    Some(json) => self.read_elt(elt, json, f),
+                    None => self.read_elt(elt, &self.null, f)
                 }
             }
-            Number(_) => die!(~"num"),
-            String(_) => die!(~"str"),
-            Boolean(_) => die!(~"bool"),
-            List(_) => die!(fmt!("list: %?", top)),
-            Null => die!(~"null"),
-
-            //_ => die!(fmt!("not an object: %?", *top))
+            // read_rec has recorded the error
+            _ => self.read_elt(elt, &self.null, f)
         }
     }
 
     fn read_tup<T>(&self, len: uint, f: fn() -> T) -> T {
         debug!("read_tup(len=%u)", len);
+        let json = self.peek();
+        match *json {
+            List(ref list) if list.len() == len => (),
+            _ => self.mismatch(fmt!("a list of %u values", len), json)
+        }
         let value = f();
         self.pop();
         move value
@@ -908,16 +1179,28 @@ pub impl Decoder: serialize::Decoder {
 
     fn read_tup_elt<T>(&self, idx: uint, f: fn() -> T) -> T {
         debug!("read_tup_elt(idx=%u)", idx);
+        let elt = fmt!("[%u]", idx);
         match *self.peek() {
-            List(ref list) => {
-                self.stack.push(&list[idx]);
-                f()
+            List(ref list) if idx < list.len() => {
+                self.read_elt(elt, &list[idx], f)
             }
-            _ => die!(~"not a list")
+            // read_tup has recorded the error
+            _ => self.read_elt(elt, &self.null, f)
         }
     }
 }
 
+/// Decode a value from json, or return the first error found in it
+pub fn decode<T: serialize::Decodable<Decoder>>(json: Json)
+    -> Result<T, DecodeError> {
+    let d = Decoder(move json);
+    let value = serialize::Decodable::decode(&d);
+    match d.error() {
+        Some(move e) => Err(e),
+        None => Ok(move value)
+    }
+}
+
 impl Json : Eq {
     pure fn eq(&self, other: &Json) -> bool {
         match (self) {
@@ -1032,15 +1315,6 @@ impl Json : Ord {
     pure fn gt(&self, other: &Json) -> bool { (*other).lt(&(*self))  }
 }
 
-impl Error : Eq {
-    pure fn eq(&self, other: &Error) -> bool {
-        (*self).line == other.line &&
-        (*self).col == other.col &&
-        (*self).msg == other.msg
-    }
-    pure fn ne(&self, other: &Error) -> bool { !(*self).eq(other) }
-}
-
 trait ToJson { fn to_json() -> Json; }
 
 impl Json: ToJson {
@@ -1166,9 +1440,42 @@ impl Json: to_str::ToStr {
     pure fn to_str(&self) -> ~str { to_str(self) }
 }
 
+impl ErrorKind: to_str::ToStr {
+    pure fn to_str(&self) -> ~str {
+        match *self {
+          EOFWhileParsingValue => ~"EOF while parsing value",
+          EOFWhileParsingString => ~"EOF while parsing string",
+          EOFWhileParsingList => ~"EOF while parsing list",
+          EOFWhileParsingObject => ~"EOF while parsing object",
+          InvalidSyntax => ~"invalid syntax",
+          InvalidNumber => ~"invalid number",
+          InvalidEscape => ~"invalid escape",
+          InvalidUnicodeEscape => ~"invalid \\u escape",
+          UnpairedSurrogate => ~"invalid \\u escape (unpaired surrogate)",
+          KeyMustBeAString => ~"key must be a string",
+          ExpectedColon => ~"expected `:`",
+          ExpectedCommaOrListEnd => ~"expected `,` or `]`",
+          ExpectedCommaOrObjectEnd => ~"expected `,` or `}`",
+          TrailingComma => ~"trailing comma",
+          TrailingCharacters => ~"trailing characters",
+        }
+    }
+}
+
 impl Error: to_str::ToStr {
     pure fn to_str(&self) -> ~str {
-        fmt!("%u:%u: %s", self.line, self.col, *self.msg)
+        fmt!("%u:%u: %s", self.line, self.col, self.kind.to_str())
+    }
+}
+
+impl DecodeError: to_str::ToStr {
+    pure fn to_str(&self) -> ~str {
+        let msg = fmt!("expected %s, found %s", self.expected, self.found);
+        if self.path.is_empty() {
+            msg
+        } else {
+            fmt!("%s: %s", self.path, msg)
+        }
     }
 }
 
@@ -1177,8 +1484,14 @@ mod tests {
     use core::prelude::*;
 
     use json::*;
+    use serialize::Decodable;
 
+    use core::float;
+    use core::io;
+    use core::rand;
     use core::result;
+    use core::str;
+    use core::vec;
     use core::hashmap::linear::LinearMap;
 
     fn mk_object(items: &[(~str, Json)]) -> Json {
@@ -1193,6 +1506,11 @@ mod tests {
         Object(move d)
     }
 
+    fn err_at(offset: uint, line: uint, col: uint, kind: ErrorKind)
+              -> Result<Json, Error> {
+        Err(Error { offset: offset, line: line, col: col, kind: kind })
+    }
+
     #[test]
     fn test_write_null() {
         assert to_str(&Null) == ~"null";
@@ -1247,38 +1565,167 @@ mod tests {
         assert a == b;
     }
 
+    fn to_pretty_str_with(json: &Json, indent: uint, ascii_only: bool)
+                         -> ~str {
+        do io::with_str_writer |wr| {
+            let mut encoder = PrettyEncoder(wr);
+            encoder.indent = indent;
+            encoder.ascii_only = ascii_only;
+            json.encode(&encoder);
+        }
+    }
+
+    #[test]
+    fn test_write_sorted_keys() {
+        let a = mk_object(~[
+            (~"c", Null),
+            (~"a", mk_object(~[(~"z", Null), (~"y", Null)])),
+            (~"b", Null)
+        ]);
+        assert to_str(&a) ==
+            ~"{\"a\":{\"y\":null,\"z\":null},\"b\":null,\"c\":null}";
+    }
+
+    #[test]
+    fn test_write_pretty() {
+        assert to_pretty_str(&Null) == ~"null";
+        assert to_pretty_str(&List(~[])) == ~"[]";
+        assert to_pretty_str(&mk_object(~[])) == ~"{}";
+
+        let a = mk_object(~[
+            (~"b", List(~[Number(1f), Number(2.5f), List(~[])])),
+            (~"a", mk_object(~[(~"d", mk_object(~[])), (~"c", Null)])),
+            (~"e", String(~"x"))
+        ]);
+        assert to_pretty_str(&a) == ~"\
+{
+  \"a\": {
+    \"c\": null,
+    \"d\": {}
+  },
+  \"b\": [
+    1,
+    2.5,
+    []
+  ],
+  \"e\": \"x\"
+}";
+        assert to_pretty_str_with(&a, 4, false) == ~"\
+{
+    \"a\": {
+        \"c\": null,
+        \"d\": {}
+    },
+    \"b\": [
+        1,
+        2.5,
+        []
+    ],
+    \"e\": \"x\"
+}";
+        assert to_pretty_str_with(&List(~[List(~[Null])]), 0, false) ==
+            ~"[\n[\nnull\n]\n]";
+    }
+
+    #[test]
+    fn test_write_escapes() {
+        assert to_str(&String(~"\x00\x1f\x7f\"\\")) ==
+            ~"\"\\u0000\\u001f\\u007f\\\"\\\\\"";
+        assert to_str(&String(~"\xe9\u4e2d\U0001d11e")) ==
+            ~"\"\xe9\u4e2d\U0001d11e\"";
+        assert to_pretty_str_with(&String(~"\xe9\u4e2d\U0001d11e"), 2, true)
+            == ~"\"\\u00e9\\u4e2d\\ud834\\udd1e\"";
+        assert to_pretty_str_with(&mk_object(~[(~"\xe9", Null)]), 2, true)
+            == ~"{\n  \"\\u00e9\": null\n}";
+    }
+
+    #[test]
+    fn test_write_integral_number() {
+        assert to_str(&Number(1f)) == ~"1";
+        assert to_str(&Number(-7f)) == ~"-7";
+        assert to_str(&Number(0f)) == ~"0";
+        assert to_str(&Number(1e15f)) == ~"1000000000000000";
+        assert to_pretty_str(&Number(100f)) == ~"100";
+        assert to_str(&Number(float::NaN)) == ~"null";
+        assert to_str(&Number(float::infinity)) == ~"null";
+    }
+
+    fn rand_str(rng: rand::Rng) -> ~str {
+        let chars = ['a', 'Z', ' ', '"', '\\', '/', '\n', '\x01', '\x7f',
+                     '\xe9', '\u4e2d', '\U0001d11e'];
+        let mut s = ~"";
+        for rng.gen_uint_range(0, 6).times {
+            str::push_char(&mut s, *rng.choose(chars));
+        }
+        s
+    }
+
+    // Numbers are kept to halves, which the parser reads back exactly
+    fn rand_json(rng: rand::Rng, depth: uint) -> Json {
+        match rng.gen_uint_range(0, if depth == 0 { 4 } else { 6 }) {
+          0 => Null,
+          1 => Boolean(rng.gen_bool()),
+          2 => Number(rng.gen_int_range(-1000, 1000) as float / 2f),
+          3 => String(rand_str(rng)),
+          4 => List(vec::from_fn(rng.gen_uint_range(0, 4),
+                                 |_| rand_json(rng, depth - 1))),
+          _ => {
+            let mut d = ~LinearMap::new();
+            for rng.gen_uint_range(0, 4).times {
+                d.insert(rand_str(rng), rand_json(rng, depth - 1));
+            }
+            Object(move d)
+          }
+        }
+    }
+
+    #[test]
+    fn test_pretty_round_trip() {
+        let rng = rand::seeded_rng([7]);
+        for 200.times {
+            let a = rand_json(rng, 4);
+            assert from_str(to_str(&a)) == Ok(copy a);
+            assert from_str(to_pretty_str(&a)) == Ok(copy a);
+            for [0u, 3u].each |indent| {
+                let s = to_pretty_str_with(&a, *indent, true);
+                assert str::is_ascii(s);
+                assert from_str(s) == Ok(copy a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_surrogate_pair() {
+        assert from_str(~"\"\\ud834\\udd1e\"") ==
+            Ok(String(~"\U0001d11e"));
+        assert from_str(~"\"\\ud834x\"") ==
+            err_at(7, 1, 8, UnpairedSurrogate);
+        assert from_str(~"\"\\ud834\\u0041\"") ==
+            err_at(12, 1, 13, UnpairedSurrogate);
+        assert from_str(~"\"\\udd1e\"") ==
+            err_at(6, 1, 7, UnpairedSurrogate);
+    }
+
     #[test]
     fn test_trailing_characters() {
-        assert from_str(~"nulla") ==
-            Err(Error {line: 1u, col: 5u, msg: @~"trailing characters"});
-        assert from_str(~"truea") ==
-            Err(Error {line: 1u, col: 5u, msg: @~"trailing characters"});
-        assert from_str(~"falsea") ==
-            Err(Error {line: 1u, col: 6u, msg: @~"trailing characters"});
-        assert from_str(~"1a") ==
-            Err(Error {line: 1u, col: 2u, msg: @~"trailing characters"});
-        assert from_str(~"[]a") ==
-            Err(Error {line: 1u, col: 3u, msg: @~"trailing characters"});
-        assert from_str(~"{}a") ==
-            Err(Error {line: 1u, col: 3u, msg: @~"trailing characters"});
+        assert from_str(~"nulla") == err_at(4, 1, 5, TrailingCharacters);
+        assert from_str(~"truea") == err_at(4, 1, 5, TrailingCharacters);
+        assert from_str(~"falsea") == err_at(5, 1, 6, TrailingCharacters);
+        assert from_str(~"1a") == err_at(1, 1, 2, TrailingCharacters);
+        assert from_str(~"[]a") == err_at(2, 1, 3, TrailingCharacters);
+        assert from_str(~"{}a") == err_at(2, 1, 3, TrailingCharacters);
     }
 
     #[test]
     fn test_read_identifiers() {
-        assert from_str(~"n") ==
-            Err(Error {line: 1u, col: 2u, msg: @~"invalid syntax"});
-        assert from_str(~"nul") ==
-            Err(Error {line: 1u, col: 4u, msg: @~"invalid syntax"});
+        assert from_str(~"n") == err_at(1, 1, 2, InvalidSyntax);
+        assert from_str(~"nul") == err_at(3, 1, 4, InvalidSyntax);
 
-        assert from_str(~"t") ==
-            Err(Error {line: 1u, col: 2u, msg: @~"invalid syntax"});
-        assert from_str(~"truz") ==
-            Err(Error {line: 1u, col: 4u, msg: @~"invalid syntax"});
+        assert from_str(~"t") == err_at(1, 1, 2, InvalidSyntax);
+        assert from_str(~"truz") == err_at(3, 1, 4, InvalidSyntax);
 
-        assert from_str(~"f") ==
-            Err(Error {line: 1u, col: 2u, msg: @~"invalid syntax"});
-        assert from_str(~"faz") ==
-            Err(Error {line: 1u, col: 3u, msg: @~"invalid syntax"});
+        assert from_str(~"f") == err_at(1, 1, 2, InvalidSyntax);
+        assert from_str(~"faz") == err_at(2, 1, 3, InvalidSyntax);
 
         assert from_str(~"null") == Ok(Null);
         assert from_str(~"true") == Ok(Boolean(true));
@@ -1290,21 +1737,14 @@ mod tests {
 
     #[test]
     fn test_read_number() {
-        assert from_str(~"+") ==
-            Err(Error {line: 1u, col: 1u, msg: @~"invalid syntax"});
-        assert from_str(~".") ==
-            Err(Error {line: 1u, col: 1u, msg: @~"invalid syntax"});
-
-        assert from_str(~"-") ==
-            Err(Error {line: 1u, col: 2u, msg: @~"invalid number"});
-        assert from_str(~"00") ==
-            Err(Error {line: 1u, col: 2u, msg: @~"invalid number"});
-        assert from_str(~"1.") ==
-            Err(Error {line: 1u, col: 3u, msg: @~"invalid number"});
-        assert from_str(~"1e") ==
-            Err(Error {line: 1u, col: 3u, msg: @~"invalid number"});
-        assert from_str(~"1e+") ==
-            Err(Error {line: 1u, col: 4u, msg: @~"invalid number"});
+        assert from_str(~"+") == err_at(0, 1, 1, InvalidSyntax);
+        assert from_str(~".") == err_at(0, 1, 1, InvalidSyntax);
+
+        assert from_str(~"-") == err_at(1, 1, 2, InvalidNumber);
+        assert from_str(~"00") == err_at(1, 1, 2, InvalidNumber);
+        assert from_str(~"1.") == err_at(2, 1, 3, InvalidNumber);
+        assert from_str(~"1e") == err_at(2, 1, 3, InvalidNumber);
+        assert from_str(~"1e+") == err_at(3, 1, 4, InvalidNumber);
 
         assert from_str(~"3") == Ok(Number(3f));
         assert from_str(~"3.1") == Ok(Number(3.1f));
@@ -1318,10 +1758,8 @@ mod tests {
 
     #[test]
     fn test_read_str() {
-        assert from_str(~"\"") ==
-            Err(Error {line: 1u, col: 2u, msg: @~"EOF while parsing string"});
-        assert from_str(~"\"lol") ==
-            Err(Error {line: 1u, col: 5u, msg: @~"EOF while parsing string"});
+        assert from_str(~"\"") == err_at(1, 1, 2, EOFWhileParsingString);
+        assert from_str(~"\"lol") == err_at(4, 1, 5, EOFWhileParsingString);
 
         assert from_str(~"\"\"") == Ok(String(~""));
         assert from_str(~"\"foo\"") == Ok(String(~"foo"));
@@ -1341,16 +1779,11 @@ mod tests {
 
     #[test]
     fn test_read_list() {
-        assert from_str(~"[") ==
-            Err(Error {line: 1u, col: 2u, msg: @~"EOF while parsing value"});
-        assert from_str(~"[1") ==
-            Err(Error {line: 1u, col: 3u, msg: @~"EOF while parsing list"});
-        assert from_str(~"[1,") ==
-            Err(Error {line: 1u, col: 4u, msg: @~"EOF while parsing value"});
-        assert from_str(~"[1,]") ==
-            Err(Error {line: 1u, col: 4u, msg: @~"invalid syntax"});
-        assert from_str(~"[6 7]") ==
-            Err(Error {line: 1u, col: 4u, msg: @~"expected `,` or `]`"});
+        assert from_str(~"[") == err_at(1, 1, 2, EOFWhileParsingValue);
+        assert from_str(~"[1") == err_at(2, 1, 3, EOFWhileParsingList);
+        assert from_str(~"[1,") == err_at(3, 1, 4, EOFWhileParsingValue);
+        assert from_str(~"[1,]") == err_at(3, 1, 4, TrailingComma);
+        assert from_str(~"[6 7]") == err_at(3, 1, 4, ExpectedCommaOrListEnd);
 
         assert from_str(~"[]") == Ok(List(~[]));
         assert from_str(~"[ ]") == Ok(List(~[]));
@@ -1365,29 +1798,21 @@ mod tests {
 
     #[test]
     fn test_read_object() {
-        assert from_str(~"{") ==
-            Err(Error {line: 1u, col: 2u, msg: @~"EOF while parsing object"});
-        assert from_str(~"{ ") ==
-            Err(Error {line: 1u, col: 3u, msg: @~"EOF while parsing object"});
-        assert from_str(~"{1") ==
-            Err(Error {line: 1u, col: 2u, msg: @~"key must be a string"});
-        assert from_str(~"{ \"a\"") ==
-            Err(Error {line: 1u, col: 6u, msg: @~"EOF while parsing object"});
-        assert from_str(~"{\"a\"") ==
-            Err(Error {line: 1u, col: 5u, msg: @~"EOF while parsing object"});
-        assert from_str(~"{\"a\" ") ==
-            Err(Error {line: 1u, col: 6u, msg: @~"EOF while parsing object"});
-
-        assert from_str(~"{\"a\" 1") ==
-            Err(Error {line: 1u, col: 6u, msg: @~"expected `:`"});
-        assert from_str(~"{\"a\":") ==
-            Err(Error {line: 1u, col: 6u, msg: @~"EOF while parsing value"});
+        assert from_str(~"{") == err_at(1, 1, 2, EOFWhileParsingObject);
+        assert from_str(~"{ ") == err_at(2, 1, 3, EOFWhileParsingObject);
+        assert from_str(~"{1") == err_at(1, 1, 2, KeyMustBeAString);
+        assert from_str(~"{ \"a\"") == err_at(5, 1, 6, EOFWhileParsingObject);
+        assert from_str(~"{\"a\"") == err_at(4, 1, 5, EOFWhileParsingObject);
+        assert from_str(~"{\"a\" ") == err_at(5, 1, 6, EOFWhileParsingObject);
+
+        assert from_str(~"{\"a\" 1") == err_at(5, 1, 6, ExpectedColon);
+        assert from_str(~"{\"a\":") == err_at(5, 1, 6, EOFWhileParsingValue);
         assert from_str(~"{\"a\":1") ==
-            Err(Error {line: 1u, col: 7u, msg: @~"EOF while parsing object"});
+            err_at(6, 1, 7, EOFWhileParsingObject);
         assert from_str(~"{\"a\":1 1") ==
-            Err(Error {line: 1u, col: 8u, msg: @~"expected `,` or `}`"});
+            err_at(7, 1, 8, ExpectedCommaOrObjectEnd);
         assert from_str(~"{\"a\":1,") ==
-            Err(Error {line: 1u, col: 8u, msg: @~"EOF while parsing object"});
+            err_at(7, 1, 8, EOFWhileParsingObject);
 
         assert result::unwrap(from_str(~"{}")) == mk_object(~[]);
         assert result::unwrap(from_str(~"{\"a\": 3}")) ==
@@ -1428,9 +1853,301 @@ mod tests {
                   ]);
     }
 
+    #[test]
+    fn test_error_positions() {
+        // Offsets are in bytes and columns in chars
+        assert from_str(~"[\"\xe9\", x]") == err_at(7, 1, 7, InvalidSyntax);
+        assert from_str(~"\"\u4e2d\\q\"") ==
+            err_at(5, 1, 4, InvalidEscape);
+        assert from_str(~"\"\\u12g4\"") ==
+            err_at(5, 1, 6, InvalidUnicodeEscape);
+        assert from_str(~"{\"a\" 1}") == err_at(5, 1, 6, ExpectedColon);
+        assert from_str(~"{\"a\": 1 \"b\": 2}") ==
+            err_at(8, 1, 9, ExpectedCommaOrObjectEnd);
+        assert from_str(~"[1 2]") == err_at(3, 1, 4, ExpectedCommaOrListEnd);
+        assert from_str(~"{\"a\": [1, 2,\n]}") ==
+            err_at(13, 2, 1, TrailingComma);
+        assert from_str(~"[]\n\n  x") == err_at(6, 3, 3, TrailingCharacters);
+        assert from_str(~"[1, {\"a\":\n  -}]") ==
+            err_at(13, 2, 4, InvalidNumber);
+        assert from_str(~"[01]") == err_at(2, 1, 3, InvalidNumber);
+        assert from_str(~"{\"a\": tru}") == err_at(9, 1, 10, InvalidSyntax);
+        assert from_str(~"{1: 2}") == err_at(1, 1, 2, KeyMustBeAString);
+        assert from_str(~"") == err_at(0, 1, 1, EOFWhileParsingValue);
+    }
+
+    #[test]
+    fn test_trailing_commas() {
+        assert from_str(~"[1, 2, ]") == err_at(7, 1, 8, TrailingComma);
+        assert from_str(~"{\"a\": 1,}") == err_at(8, 1, 9, TrailingComma);
+        assert from_str(~"[,]") == err_at(1, 1, 2, InvalidSyntax);
+
+        let parse = |s: &str| {
+            do io::with_str_reader(s) |rdr| {
+                let mut parser = Parser(rdr);
+                parser.allow_trailing_commas = true;
+                parser.parse()
+            }
+        };
+        assert parse("[1, 2, ]") == Ok(List(~[Number(1f), Number(2f)]));
+        assert parse("{\"a\": [],}") == Ok(mk_object(~[(~"a", List(~[]))]));
+        assert parse("[1, 2]") == Ok(List(~[Number(1f), Number(2f)]));
+        assert parse("[,]") == err_at(1, 1, 2, InvalidSyntax);
+        assert parse("{,}") == err_at(1, 1, 2, KeyMustBeAString);
+        assert parse("[1,,]") == err_at(3, 1, 4, InvalidSyntax);
+    }
+
+    // Generates a large document on demand, a few bytes per read, so that
+    // the whole input never exists in memory at once
+    struct DocGenerator {
+        items: uint,
+        mut item: uint,
+        mut buf: ~[u8],
+        mut pos: uint,
+    }
+
+    impl DocGenerator: io::Reader {
+        fn read(&self, bytes: &[mut u8], len: uint) -> uint {
+            let mut count = 0;
+            while count < len && count < 3 {
+                let b = self.read_byte();
+                if b < 0 { break; }
+                bytes[count] = b as u8;
+                count += 1;
+            }
+            count
+        }
+        fn read_byte(&self) -> int {
+            if self.pos == self.buf.len() {
+                if self.item > self.items { return -1; }
+                let s = if self.item == 0 {
+                    ~"["
+                } else if self.item == self.items {
+                    ~"]"
+                } else {
+                    fmt!("%s{\"n\": %u, \"s\": \"\xe9\u4e2d\"}\n",
+                         if self.item == 1 { "" } else { "," }, self.item)
+                };
+                self.buf = str::to_bytes(s);
+                self.pos = 0;
+                self.item += 1;
+            }
+            self.pos += 1;
+            self.buf[self.pos - 1] as int
+        }
+        fn eof(&self) -> bool {
+            self.pos == self.buf.len() && self.item > self.items
+        }
+        fn seek(&self, _offset: int, _whence: io::SeekStyle) { die!() }
+        fn tell(&self) -> uint { die!() }
+    }
+
+    #[test]
+    fn test_stream_large_document() {
+        let items = 20000u;
+        let gen = @DocGenerator { items: items, item: 0, buf: ~[], pos: 0 };
+        let list = match from_reader(gen as @io::Reader) {
+            Ok(List(move list)) => list,
+            _ => die!()
+        };
+        assert list.len() == items - 1;
+        for list.eachi |i, v| {
+            assert *v == mk_object(~[(~"n", Number((i + 1) as float)),
+                                     (~"s", String(~"\xe9\u4e2d"))]);
+        }
+    }
+
     #[test]
     fn test_multiline_errors() {
         assert from_str(~"{\n  \"foo\":\n \"bar\"") ==
-            Err(Error {line: 3u, col: 8u, msg: @~"EOF while parsing object"});
+            err_at(17, 3, 7, EOFWhileParsingObject);
+    }
+
+    fn decode_from_str<T: Decodable<Decoder>>(s: &str) -> T {
+        result::unwrap(decode(result::unwrap(from_str(s))))
+    }
+
+    fn decode_error_from_str<T: Decodable<Decoder>>(s: &str) -> DecodeError {
+        match decode::<T>(result::unwrap(from_str(s))) {
+            Ok(_) => die!(~"decoded without an error"),
+            Err(move e) => e
+        }
+    }
+
+    fn decode_error(path: &str, expected: &str, found: &str) -> DecodeError {
+        DecodeError {
+            path: path.to_owned(),
+            expected: expected.to_owned(),
+            found: found.to_owned()
+        }
+    }
+
+    #[auto_decode]
+    #[deriving_eq]
+    struct Fields {
+        a: Option<int>,
+        b: Option<~str>
+    }
+
+    #[auto_decode]
+    struct Required {
+        a: int
+    }
+
+    fn fields(a: Option<int>, b: Option<~str>) -> Fields {
+        Fields { a: a, b: b }
+    }
+
+    #[test]
+    fn test_decode_option() {
+        let v: ~[Option<int>] = decode_from_str("[1, null, 3, null]");
+        assert v == ~[Some(1), None, Some(3), None];
+
+        let f: Fields = decode_from_str("{\"a\": 1, \"b\": \"x\"}");
+        assert f == fields(Some(1), Some(~"x"));
+        let f: Fields = decode_from_str("{\"a\": null, \"b\": null}");
+        assert f == fields(None, None);
+
+        // A missing optional field is not taken for null
+        assert decode_error_from_str::<Fields>("{\"b\": \"x\"}") ==
+            decode_error("a", "a value or null", "no value");
+        assert decode_error_from_str::<Fields>("{\"a\": null}") ==
+            decode_error("b", "a value or null", "no value");
+    }
+
+    #[test]
+    fn test_decode_missing_field() {
+        assert decode_error_from_str::<Required>("{\"b\": 1}") ==
+            decode_error("a", "a number", "no value");
+        assert decode_error_from_str::<Required>("{\"a\": null}") ==
+            decode_error("a", "a number", "null");
+    }
+
+    #[auto_decode]
+    struct Unit {
+        u: ()
+    }
+
+    #[test]
+    fn test_decode_missing_nil() {
+        let _: Unit = decode_from_str("{\"u\": null}");
+        assert decode_error_from_str::<Unit>("{}") ==
+            decode_error("u", "null", "no value");
+    }
+
+    #[auto_decode]
+    #[deriving_eq]
+    struct Server {
+        host: ~str,
+        ports: ~[uint],
+        tls: Option<bool>
+    }
+
+    #[auto_decode]
+    #[deriving_eq]
+    struct Config {
+        name: ~str,
+        server: Server,
+        backup: Option<Server>,
+        limits: (uint, float)
+    }
+
+    #[test]
+    fn test_decode_config() {
+        let c: Config = decode_from_str("{
+            \"name\": \"web\",
+            \"server\": {\"host\": \"a\", \"ports\": [80, 443],
+                         \"tls\": null},
+            \"backup\": null,
+            \"limits\": [10, 0.5]
+        }");
+        assert c == Config {
+            name: ~"web",
+            server: Server { host: ~"a", ports: ~[80, 443], tls: None },
+            backup: None,
+            limits: (10, 0.5)
+        };
+
+        let c: Config = decode_from_str("{
+            \"name\": \"web\",
+            \"server\": {\"host\": \"a\", \"ports\": [], \"tls\": true},
+            \"backup\": {\"host\": \"b\", \"ports\": [8080], \"tls\": null},
+            \"limits\": [0, 1]
+        }");
+        assert c.server.tls == Some(true);
+        assert c.backup == Some(Server {
+            host: ~"b",
+            ports: ~[8080],
+            tls: None
+        });
+    }
+
+    #[test]
+    fn test_decode_config_errors() {
+        let server = "{\"host\": \"a\", \"ports\": [80], \"tls\": null}";
+        let config = |name: &str, server: &str, limits: &str| {
+            fmt!("{\"name\": %s, \"server\": %s, \"backup\": null, \
+                  \"limits\": %s}", name, server, limits)
+        };
+
+        assert decode_error_from_str::<Config>(
+            config("1", server, "[0, 0]")) ==
+            decode_error("name", "a string", "a number");
+        assert decode_error_from_str::<Config>(
+            config("\"web\"", "{\"host\": \"a\", \"ports\": [80, \"x\"]}",
+                   "[0, 0]")) ==
+            decode_error("server.ports[1]", "a number", "a string");
+        assert decode_error_from_str::<Config>(
+            config("\"web\"", "{\"ports\": [80]}", "[0, 0]")) ==
+            decode_error("server.host", "a string", "no value");
+        assert decode_error_from_str::<Config>(
+            config("\"web\"", "[]", "[0, 0]")) ==
+            decode_error("server", "an object", "a list");
+        assert decode_error_from_str::<Config>(
+            config("\"web\"", server, "[0]")) ==
+            decode_error("limits", "a list of 2 values", "a list");
+        assert decode_error_from_str::<Config>(
+            config("\"web\"", server, "[0, null]")) ==
+            decode_error("limits[1]", "a number", "null");
+        assert decode_error_from_str::<Config>(
+            "{\"name\": \"web\", \"limits\": [0, 0]}") ==
+            decode_error("server", "an object", "no value");
+        assert decode_error_from_str::<Config>(
+            fmt!("{\"name\": \"web\", \"server\": %s, \
+                  \"limits\": [0, 0]}", server)) ==
+            decode_error("backup", "a value or null", "no value");
+
+        // Errors inside an optional value are still reported
+        let doc = fmt!("{\"name\": \"web\", \"server\": %s, \
+                        \"backup\": {\"host\": \"b\", \"ports\": 1}, \
+                        \"limits\": [0, 0]}", server);
+        assert decode_error_from_str::<Config>(doc) ==
+            decode_error("backup.ports", "a list", "a number");
+
+        // Only the first error is reported
+        assert decode_error_from_str::<Config>(
+            config("1", "{\"host\": 2}", "3")) ==
+            decode_error("name", "a string", "a number");
+
+        assert decode_error_from_str::<~[Config]>("[{}]") ==
+            decode_error("[0].name", "a string", "no value");
+        assert decode_error_from_str::<~[int]>("{}") ==
+            decode_error("", "a list", "an object");
+        assert decode_error_from_str::<char>("\"ab\"") ==
+            decode_error("", "a string of one character", "a string");
+
+        assert decode_error("server.ports[2]", "a number", "null").to_str()
+            == ~"server.ports[2]: expected a number, found null";
+        assert decode_error("", "a list", "an object").to_str()
+            == ~"expected a list, found an object";
+    }
+
+    #[test]
+    fn test_decode_map_duplicate_keys() {
+        let m: LinearMap<~str, int> =
+            decode_from_str("[[\"a\", 1], [\"b\", 2], [\"a\", 3]]");
+        assert m.len() == 2;
+        assert m.find(&~"a") == Some(&3);
+        assert m.find(&~"b") == Some(&2);
     }
 }
@@ -0,0 +1,212 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * Control over logging while the program runs.
+ *
+ * Every module that contains a log statement has a level, set at startup
+ * from the RUST_LOG environment variable, and a log statement only runs
+ * when its level is at most that of its module. The levels are the
+ * `error`, `warn`, `info` and `debug` constants of core (1 to 4); a
+ * module at level 0 logs nothing.
+ *
+ * Modules are named by their path, starting with the crate, e.g.
+ * `std::net_tcp`. Running a program with `RUST_LOG=::help` lists the
+ * names of all the modules that log.
+ *
+ * Messages go to the console unless the task has a logger, a function
+ * that is given each message instead. Tasks start with the logger of the
+ * task that spawned them.
+ */
+
+use core::prelude::*;
+
+use core::io::{Writer, WriterUtil};
+use core::libc::{c_char, size_t};
+use core::pipes::SharedChan;
+use core::str;
+use core::uint;
+
+pub use core::logging::{Logger, clear_logger, set_logger};
+
+#[abi = "cdecl"]
+extern mod rustrt {
+    pub unsafe fn rust_log_set_level(prefix: *c_char, level: u32) -> size_t;
+    pub unsafe fn rust_log_get_level(name: *c_char, level: *mut u32) -> bool;
+}
+
+/**
+ * Set the log level of every module whose path starts with
+ * `module_path`, as a RUST_LOG directive would, and return the number of
+ * modules changed.
+ *
+ * The new level applies at once to all tasks, including ones that are
+ * already running.
+ */
+pub fn set_level(module_path: &str, level: u32) -> uint {
+    unsafe {
+        do str::as_c_str(module_path) |buf| {
+            rustrt::rust_log_set_level(buf, level) as uint
+        }
+    }
+}
+
+/// Return the log level of the module with exactly the given path, or
+/// None if that module contains no log statements
+pub fn get_level(module_path: &str) -> Option<u32> {
+    let mut level = 0;
+    let found = unsafe {
+        do str::as_c_str(module_path) |buf| {
+            rustrt::rust_log_get_level(buf, &mut level)
+        }
+    };
+    if found { Some(level) } else { None }
+}
+
+/**
+ * A logger writing each message to `writer` as a line that starts with
+ * the level and module, e.g. `debug std::net_tcp: connected`
+ */
+pub fn writer_logger<W: Writer Owned>(writer: W) -> Logger {
+    fn~(move writer, level: u32, module: &str, msg: &str) {
+        writer.write_line(fmt!("%s %s: %s", level_name(level), module, msg));
+    }
+}
+
+/// A logger sending the level, module and text of each message over `chan`
+pub fn chan_logger(chan: SharedChan<(u32, ~str, ~str)>) -> Logger {
+    fn~(move chan, level: u32, module: &str, msg: &str) {
+        chan.send((level, module.to_owned(), msg.to_owned()));
+    }
+}
+
+fn level_name(level: u32) -> ~str {
+    match level {
+      1 => ~"error",
+      2 => ~"warn",
+      3 => ~"info",
+      4 => ~"debug",
+      _ => uint::to_str(level as uint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::prelude::*;
+
+    use logging::{chan_logger, clear_logger, get_level, set_level};
+    use logging::{set_logger, writer_logger};
+
+    use core::libc;
+    use core::os;
+    use core::pipes::{Peekable, SharedChan};
+    use core::pipes;
+    use core::run;
+    use core::task;
+
+    // The tests below change the levels of these modules, so each has its
+    // own to keep them from interfering
+
+    mod levels {
+        // Returns the number of times the debug! argument was evaluated,
+        // which only happens when the message is logged
+        pub fn count_debug() -> uint {
+            let mut count = 0u;
+            debug!("count_debug: %u", { count += 1; count });
+            count
+        }
+    }
+
+    mod loggers {
+        pub const path: &str = "std::logging::tests::loggers";
+
+        pub fn log_all(n: int) {
+            error!("error %d", n);
+            warn!("warn %d", n);
+            info!("info %d", n);
+            debug!("debug %d", n);
+        }
+    }
+
+    #[test]
+    fn test_set_level() {
+        let path = "std::logging::tests::levels";
+        let start = get_level(path).get();
+        assert get_level("std::logging::no_such_module").is_none();
+        assert set_level("std::logging::no_such_module", debug) == 0;
+
+        assert set_level(path, debug) == 1;
+        assert get_level(path) == Some(debug);
+        assert levels::count_debug() == 1;
+
+        assert set_level(path, info) == 1;
+        assert get_level(path) == Some(info);
+        assert levels::count_debug() == 0;
+
+        // A task that is already running sees the change
+        let (wait_port, wait_chan) = pipes::stream();
+        let (result_port, result_chan) = pipes::stream();
+        do task::spawn |move wait_port, move result_chan| {
+            result_chan.send(levels::count_debug());
+            let () = wait_port.recv();
+            result_chan.send(levels::count_debug());
+        }
+        assert result_port.recv() == 0;
+        set_level(path, debug);
+        wait_chan.send(());
+        assert result_port.recv() == 1;
+
+        set_level(path, start);
+        assert get_level(path) == Some(start);
+    }
+
+    #[test]
+    fn test_chan_logger() {
+        set_level(loggers::path, debug);
+        let (port, chan) = pipes::stream();
+        set_logger(chan_logger(SharedChan(move chan)));
+        loggers::log_all(1);
+        // Spawned tasks share the logger
+        assert task::try(|| loggers::log_all(2)).is_ok();
+        clear_logger();
+        loggers::log_all(3);
+        assert task::try(|| loggers::log_all(4)).is_ok();
+
+        let expected = [(error, 1, "error"), (warn, 1, "warn"),
+                        (info, 1, "info"), (debug, 1, "debug"),
+                        (error, 2, "error"), (warn, 2, "warn"),
+                        (info, 2, "info"), (debug, 2, "debug")];
+        for expected.each |e| {
+            let (level, n, name) = *e;
+            let (l, module, msg) = port.recv();
+            assert l == level;
+            assert module == loggers::path.to_owned();
+            assert msg == fmt!("%s %d", name, n);
+        }
+        assert !port.peek();
+    }
+
+    #[test]
+    fn test_writer_logger() {
+        set_level(loggers::path, debug);
+        let pipe = os::pipe();
+        set_logger(writer_logger(pipe.out));
+        loggers::log_all(5);
+        clear_logger();
+        unsafe { libc::close(pipe.out); }
+
+        let prefix = loggers::path;
+        let out = run::readclose(pipe.in);
+        assert out == fmt!("error %s: error 5\n", prefix) +
+                      fmt!("warn %s: warn 5\n", prefix) +
+                      fmt!("info %s: info 5\n", prefix) +
+                      fmt!("debug %s: debug 5\n", prefix);
+    }
+}
@@ -0,0 +1,349 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * A cache holding a bounded number of entries, which evicts the least
+ * recently used entry to make room for a new one.
+ *
+ * The entries are nodes of a doubly-linked list ordered from the most to
+ * the least recently used. The nodes live in a vector and link to each
+ * other by index, and a hash map from keys to node indices finds them, so
+ * that lookups, insertions and evictions all take constant time.
+ */
+
+use core::cast;
+use core::container::{Container, Mutable};
+use core::hash::Hash;
+use core::hashmap::linear::LinearMap;
+use core::prelude::*;
+use core::to_bytes::IterBytes;
+
+// The index standing for the end of the list
+const nil: uint = -1 as uint;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    prev: uint,
+    next: uint,
+}
+
+pub struct LruCache<K, V> {
+    priv map: LinearMap<K, uint>,
+    priv nodes: ~[Option<Node<K, V>>],
+    priv free: ~[uint],
+    priv head: uint,
+    priv tail: uint,
+    priv capacity: uint,
+}
+
+impl <K: Hash IterBytes Eq Copy, V> LruCache<K, V>: Container {
+    /// Return the number of entries in the cache
+    pure fn len(&self) -> uint { self.map.len() }
+
+    /// Return true if the cache contains no entries
+    pure fn is_empty(&self) -> bool { self.len() == 0 }
+}
+
+impl <K: Hash IterBytes Eq Copy, V> LruCache<K, V>: Mutable {
+    /// Remove all entries from the cache
+    fn clear(&mut self) {
+        self.map.clear();
+        self.nodes = ~[];
+        self.free = ~[];
+        self.head = nil;
+        self.tail = nil;
+    }
+}
+
+pub impl <K: Hash IterBytes Eq Copy, V> LruCache<K, V> {
+    /// Create an empty cache holding at most `capacity` entries
+    static fn new(capacity: uint) -> LruCache<K, V> {
+        LruCache {
+            map: LinearMap::new(),
+            nodes: ~[],
+            free: ~[],
+            head: nil,
+            tail: nil,
+            capacity: capacity
+        }
+    }
+
+    /// Return the maximum number of entries the cache holds
+    pure fn capacity(&self) -> uint { self.capacity }
+
+    /// Return true if the cache contains the key. This does not count as
+    /// a use of the entry.
+    pure fn contains_key(&self, k: &K) -> bool { self.map.contains_key(k) }
+
+    /// Return the value for the key, marking the entry as the most
+    /// recently used. A missing key leaves the order of the entries
+    /// untouched.
+    fn find(&mut self, k: &K) -> Option<&self/V> {
+        let idx = match self.map.find(k) {
+            Some(idx) => *idx,
+            None => return None
+        };
+        self.unlink(idx);
+        self.push_front(idx);
+        unsafe {
+            // FIXME(#4903) requires a flow-sensitive borrow checker
+            Some(cast::transmute_region(&self.node(idx).value))
+        }
+    }
+
+    /// Insert a key-value pair as the most recently used entry, evicting
+    /// the least recently used entry if the cache is full. An existing
+    /// value for the key is replaced. Return true if the key was not
+    /// already in the cache. A cache with a capacity of zero never
+    /// stores anything.
+    fn insert(&mut self, k: K, v: V) -> bool {
+        if self.capacity == 0 { return true; }
+
+        let existing = match self.map.find(&k) {
+            Some(idx) => Some(*idx),
+            None => None
+        };
+        match existing {
+            Some(idx) => {
+                self.node(idx).value = v;
+                self.unlink(idx);
+                self.push_front(idx);
+                false
+            }
+            None => {
+                if self.len() == self.capacity {
+                    let tail = self.tail;
+                    let Node{key: key, _} = self.remove_node(tail);
+                    self.map.remove(&key);
+                }
+                let node = Node{key: copy k, value: v, prev: nil, next: nil};
+                let idx = if self.free.is_empty() {
+                    self.nodes.push(Some(node));
+                    self.nodes.len() - 1
+                } else {
+                    let idx = self.free.pop();
+                    self.nodes[idx] = Some(node);
+                    idx
+                };
+                self.push_front(idx);
+                self.map.insert(k, idx);
+                true
+            }
+        }
+    }
+
+    /// Remove the entry for the key and return its value
+    fn pop(&mut self, k: &K) -> Option<V> {
+        match self.map.pop(k) {
+            Some(idx) => {
+                let Node{value: value, _} = self.remove_node(idx);
+                Some(value)
+            }
+            None => None
+        }
+    }
+
+    /// Remove the entry for the key. Return true if it was present.
+    fn remove(&mut self, k: &K) -> bool { self.pop(k).is_some() }
+
+    /// Visit the entries from the most to the least recently used,
+    /// without counting as a use of any of them
+    pure fn each(&self, f: fn(&K, &V) -> bool) {
+        let mut idx = self.head;
+        while idx != nil {
+            match self.nodes[idx] {
+                Some(ref node) => {
+                    if !f(&node.key, &node.value) { return; }
+                    idx = node.next;
+                }
+                None => die!(~"LruCache: internal logic error")
+            }
+        }
+    }
+}
+
+priv impl <K: Hash IterBytes Eq Copy, V> LruCache<K, V> {
+    fn node(&mut self, idx: uint) -> &self/mut Node<K, V> {
+        match self.nodes[idx] {
+            Some(ref mut node) => node,
+            None => die!(~"LruCache: internal logic error")
+        }
+    }
+
+    // Take the node out of the list, leaving its own links dangling
+    fn unlink(&mut self, idx: uint) {
+        let (prev, next) = {
+            let node = self.node(idx);
+            (node.prev, node.next)
+        };
+        if prev == nil {
+            self.head = next;
+        } else {
+            self.node(prev).next = next;
+        }
+        if next == nil {
+            self.tail = prev;
+        } else {
+            self.node(next).prev = prev;
+        }
+    }
+
+    // Make an unlinked node the most recently used one
+    fn push_front(&mut self, idx: uint) {
+        let head = self.head;
+        {
+            let node = self.node(idx);
+            node.prev = nil;
+            node.next = head;
+        }
+        if head == nil {
+            self.tail = idx;
+        } else {
+            self.node(head).prev = idx;
+        }
+        self.head = idx;
+    }
+
+    fn remove_node(&mut self, idx: uint) -> Node<K, V> {
+        self.unlink(idx);
+        self.free.push(idx);
+        self.nodes[idx].swap_unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::prelude::*;
+
+    use lru_cache::LruCache;
+
+    use core::uint;
+
+    fn keys(cache: &LruCache<uint, ~str>) -> ~[uint] {
+        let mut result = ~[];
+        for cache.each |k, v| {
+            assert *v == uint::to_str(*k);
+            result.push(*k);
+        }
+        result
+    }
+
+    fn filled(capacity: uint, n: uint) -> LruCache<uint, ~str> {
+        let mut cache = LruCache::new(capacity);
+        for uint::range(0, n) |i| {
+            assert cache.insert(i, uint::to_str(i));
+        }
+        cache
+    }
+
+    #[test]
+    fn test_insert_and_find() {
+        let mut cache = filled(4, 3);
+        assert cache.len() == 3;
+        assert cache.find(&1) == Some(&~"1");
+        assert cache.find(&7) == None;
+        assert !cache.insert(1, ~"one");
+        assert cache.find(&1) == Some(&~"one");
+        assert cache.len() == 3;
+    }
+
+    #[test]
+    fn test_eviction_order() {
+        let cache = filled(5, 12);
+        assert cache.len() == 5;
+        assert keys(&cache) == ~[11, 10, 9, 8, 7];
+        for uint::range(0, 7) |i| {
+            assert !cache.contains_key(&i);
+        }
+    }
+
+    #[test]
+    fn test_find_refreshes() {
+        let mut cache = filled(3, 3);
+        assert cache.find(&0).is_some();
+        assert keys(&cache) == ~[0, 2, 1];
+        cache.insert(3, ~"3");
+        cache.insert(4, ~"4");
+        assert keys(&cache) == ~[4, 3, 0];
+    }
+
+    #[test]
+    fn test_find_missing_does_not_refresh() {
+        let mut cache = filled(3, 3);
+        assert cache.find(&5).is_none();
+        assert keys(&cache) == ~[2, 1, 0];
+        cache.insert(3, ~"3");
+        assert keys(&cache) == ~[3, 2, 1];
+    }
+
+    #[test]
+    fn test_insert_existing_refreshes() {
+        let mut cache = filled(3, 3);
+        assert !cache.insert(0, ~"0");
+        cache.insert(3, ~"3");
+        assert keys(&cache) == ~[3, 0, 2];
+    }
+
+    #[test]
+    fn test_pop() {
+        let mut cache = filled(3, 3);
+        assert cache.pop(&1) == Some(~"1");
+        assert cache.pop(&1) == None;
+        assert keys(&cache) == ~[2, 0];
+
+        // The freed slot is reused and the capacity is still respected
+        cache.insert(3, ~"3");
+        cache.insert(4, ~"4");
+        assert keys(&cache) == ~[4, 3, 2];
+        assert cache.remove(&4);
+        assert cache.remove(&2);
+        assert keys(&cache) == ~[3];
+        assert cache.remove(&3);
+        assert cache.is_empty();
+        assert keys(&cache) == ~[];
+
+        cache.insert(5, ~"5");
+        assert keys(&cache) == ~[5];
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut cache = filled(3, 3);
+        cache.clear();
+        assert cache.is_empty();
+        assert cache.find(&0).is_none();
+        cache.insert(7, ~"7");
+        assert keys(&cache) == ~[7];
+    }
+
+    #[test]
+    fn test_capacity_zero() {
+        let mut cache = filled(0, 3);
+        assert cache.is_empty();
+        assert cache.find(&0).is_none();
+        assert cache.pop(&2).is_none();
+        assert keys(&cache) == ~[];
+    }
+
+    #[test]
+    fn test_capacity_one() {
+        let mut cache = filled(1, 3);
+        assert keys(&cache) == ~[2];
+        assert cache.find(&2) == Some(&~"2");
+        assert !cache.insert(2, ~"2");
+        assert cache.insert(3, ~"3");
+        assert keys(&cache) == ~[3];
+        assert cache.pop(&3) == Some(~"3");
+        assert cache.is_empty();
+        assert cache.insert(4, ~"4");
+        assert keys(&cache) == ~[4];
+    }
+}
@@ -0,0 +1,278 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * An implementation of the MD5 message digest (RFC 1321).
+ *
+ * MD5 is broken as a cryptographic hash; use it for checksums and for
+ * naming content, not where an attacker may choose the input.
+ *
+ * It is used like `sha1::Sha1`, through the `digest::Digest` trait.
+ */
+
+use digest::Digest;
+
+use core::prelude::*;
+use core::uint;
+use core::vec;
+
+const msg_block_len: uint = 64u;
+
+// The per-round left rotations, four for each of the four rounds
+const shifts: [u32 * 16] = [
+    7u32, 12u32, 17u32, 22u32,
+    5u32, 9u32, 14u32, 20u32,
+    4u32, 11u32, 16u32, 23u32,
+    6u32, 10u32, 15u32, 21u32,
+];
+
+// The integer part of 2^32 * abs(sin(i + 1)) for each step i
+const k: [u32 * 64] = [
+    0xd76aa478u32, 0xe8c7b756u32, 0x242070dbu32, 0xc1bdceeeu32,
+    0xf57c0fafu32, 0x4787c62au32, 0xa8304613u32, 0xfd469501u32,
+    0x698098d8u32, 0x8b44f7afu32, 0xffff5bb1u32, 0x895cd7beu32,
+    0x6b901122u32, 0xfd987193u32, 0xa679438eu32, 0x49b40821u32,
+    0xf61e2562u32, 0xc040b340u32, 0x265e5a51u32, 0xe9b6c7aau32,
+    0xd62f105du32, 0x02441453u32, 0xd8a1e681u32, 0xe7d3fbc8u32,
+    0x21e1cde6u32, 0xc33707d6u32, 0xf4d50d87u32, 0x455a14edu32,
+    0xa9e3e905u32, 0xfcefa3f8u32, 0x676f02d9u32, 0x8d2a4c8au32,
+    0xfffa3942u32, 0x8771f681u32, 0x6d9d6122u32, 0xfde5380cu32,
+    0xa4beea44u32, 0x4bdecfa9u32, 0xf6bb4b60u32, 0xbebfbc70u32,
+    0x289b7ec6u32, 0xeaa127fau32, 0xd4ef3085u32, 0x04881d05u32,
+    0xd9d4d039u32, 0xe6db99e5u32, 0x1fa27cf8u32, 0xc4ac5665u32,
+    0xf4292244u32, 0x432aff97u32, 0xab9423a7u32, 0xfc93a039u32,
+    0x655b59c3u32, 0x8f0ccc92u32, 0xffeff47du32, 0x85845dd1u32,
+    0x6fa87e4fu32, 0xfe2ce6e0u32, 0xa3014314u32, 0x4e0811a1u32,
+    0xf7537e82u32, 0xbd3af235u32, 0x2ad7d2bbu32, 0xeb86d391u32,
+];
+
+/// The state of an MD5 hash
+pub struct Md5 {
+    priv h: [u32 * 4],
+    // the length of the message so far, in bytes
+    priv len: u64,
+    priv msg_block: ~[u8],
+    priv msg_block_idx: uint,
+    priv computed: bool,
+}
+
+pub impl Md5 {
+    /// Construct an `Md5` object for an empty message
+    static fn new() -> Md5 {
+        let mut st = Md5 {
+            h: [0u32, 0u32, 0u32, 0u32],
+            len: 0u64,
+            msg_block: vec::from_elem(msg_block_len, 0u8),
+            msg_block_idx: 0u,
+            computed: false
+        };
+        st.reset();
+        st
+    }
+}
+
+fn add_input(st: &mut Md5, msg: &[const u8]) {
+    assert !st.computed;
+    let mut i = 0u;
+    while i < msg.len() {
+        st.msg_block[st.msg_block_idx] = msg[i];
+        st.msg_block_idx += 1u;
+        if st.msg_block_idx == msg_block_len { process_msg_block(st); }
+        i += 1u;
+    }
+    st.len += msg.len() as u64;
+}
+
+fn process_msg_block(st: &mut Md5) {
+    // The block as sixteen little-endian words
+    let mut m = [0u32, 0u32, 0u32, 0u32, 0u32, 0u32, 0u32, 0u32,
+                 0u32, 0u32, 0u32, 0u32, 0u32, 0u32, 0u32, 0u32];
+    for uint::range(0u, 16u) |j| {
+        m[j] = (st.msg_block[j * 4u] as u32) |
+            (st.msg_block[j * 4u + 1u] as u32) << 8u32 |
+            (st.msg_block[j * 4u + 2u] as u32) << 16u32 |
+            (st.msg_block[j * 4u + 3u] as u32) << 24u32;
+    }
+
+    let mut a = st.h[0];
+    let mut b = st.h[1];
+    let mut c = st.h[2];
+    let mut d = st.h[3];
+    for uint::range(0u, 64u) |i| {
+        let (f, g) = if i < 16u {
+            (b & c | !b & d, i)
+        } else if i < 32u {
+            (d & b | !d & c, (5u * i + 1u) % 16u)
+        } else if i < 48u {
+            (b ^ c ^ d, (3u * i + 5u) % 16u)
+        } else {
+            (c ^ (b | !d), (7u * i) % 16u)
+        };
+        let temp = d;
+        d = c;
+        c = b;
+        b = b + rotate_left(a + f + k[i] + m[g],
+                            shifts[(i / 16u) * 4u + i % 4u]);
+        a = temp;
+    }
+    st.h[0] += a;
+    st.h[1] += b;
+    st.h[2] += c;
+    st.h[3] += d;
+    st.msg_block_idx = 0u;
+}
+
+fn rotate_left(word: u32, bits: u32) -> u32 {
+    word << bits | word >> 32u32 - bits
+}
+
+// Pad the message as for SHA-1: a '1' bit, zeros up to the last 8 bytes
+// of a block, then the length in bits, except little-endian
+fn pad_msg(st: &mut Md5) {
+    st.msg_block[st.msg_block_idx] = 0x80u8;
+    st.msg_block_idx += 1u;
+    if st.msg_block_idx > 56u {
+        while st.msg_block_idx < msg_block_len {
+            st.msg_block[st.msg_block_idx] = 0u8;
+            st.msg_block_idx += 1u;
+        }
+        process_msg_block(st);
+    }
+    while st.msg_block_idx < 56u {
+        st.msg_block[st.msg_block_idx] = 0u8;
+        st.msg_block_idx += 1u;
+    }
+
+    let bits = st.len << 3u64;
+    for uint::range(0u, 8u) |i| {
+        st.msg_block[56u + i] = (bits >> (i * 8u) as u64) as u8;
+    }
+    process_msg_block(st);
+}
+
+impl Md5: Digest {
+    fn input(&mut self, msg: &[const u8]) { add_input(self, msg); }
+
+    fn result(&mut self, out: &mut [u8]) {
+        assert out.len() >= 16u;
+        if !self.computed { pad_msg(self); self.computed = true; }
+        for uint::range(0u, 4u) |i| {
+            let hpart = self.h[i];
+            out[i * 4u] = (hpart & 0xFFu32) as u8;
+            out[i * 4u + 1u] = (hpart >> 8u32 & 0xFFu32) as u8;
+            out[i * 4u + 2u] = (hpart >> 16u32 & 0xFFu32) as u8;
+            out[i * 4u + 3u] = (hpart >> 24u32 & 0xFFu32) as u8;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.len = 0u64;
+        self.msg_block_idx = 0u;
+        self.h = [0x67452301u32, 0xefcdab89u32, 0x98badcfeu32, 0x10325476u32];
+        self.computed = false;
+    }
+
+    fn output_bits(&self) -> uint { 128u }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::prelude::*;
+
+    use digest::{Digest, DigestUtil, DigestWriter};
+    use md5::Md5;
+
+    use core::io::WriterUtil;
+    use core::rand;
+    use core::str;
+    use core::uint;
+    use core::vec;
+
+    #[test]
+    fn test_rfc1321_suite() {
+        let tests = [
+            ("", "d41d8cd98f00b204e9800998ecf8427e"),
+            ("a", "0cc175b9c0f1b6a831c399e269772661"),
+            ("abc", "900150983cd24fb0d6963f7d28e17f72"),
+            ("message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+            ("abcdefghijklmnopqrstuvwxyz",
+             "c3fcd3d76192e4007dfb496cca67e13b"),
+            ("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+             "d174ab98d277d9f5a5611c2c9f419d9f"),
+            ("1234567890123456789012345678901234567890\
+              1234567890123456789012345678901234567890",
+             "57edf4a22be3c955ac49da2e2107b67a"),
+        ];
+        let mut md5 = Md5::new();
+        assert md5.output_bits() == 128u;
+        for tests.each |t| {
+            let (input, output) = *t;
+            md5.input_str(input);
+            assert md5.result_str() == output.to_owned();
+            // asking again gives the same answer
+            assert md5.result_str() == output.to_owned();
+            md5.reset();
+        }
+    }
+
+    #[test]
+    fn test_block_boundaries() {
+        // 55 bytes leave room for the length in the same block; 56 to 63
+        // need another; 64 fill a block exactly
+        let tests = [
+            (55u, "ef1772b6dff9a122358552954ad0df65"),
+            (56u, "3b0c8ac703f828b04c6c197006d17218"),
+            (63u, "b06521f39153d618550606be297466d5"),
+            (64u, "014842d480b571495a4a0363793f7367"),
+            (65u, "c743a45e0d2e6a95cb859adae0248435"),
+            (1000u, "cabe45dcc9ae5b66ba86600cca6b8ba8"),
+        ];
+        let mut md5 = Md5::new();
+        for tests.each |t| {
+            let (len, output) = *t;
+            md5.input_str(str::repeat("a", len));
+            assert md5.result_str() == output.to_owned();
+            md5.reset();
+        }
+    }
+
+    #[test]
+    fn test_streaming_matches_oneshot() {
+        let rng = rand::Rng();
+        for uint::range(0u, 200u) |_| {
+            let len = rng.gen_uint_range(0u, 300u);
+            let msg = rng.gen_bytes(len);
+
+            let mut oneshot = Md5::new();
+            oneshot.input(msg);
+            let expected = oneshot.result_bytes();
+
+            // feed the same bytes in random, unaligned pieces
+            let mut streamed = Md5::new();
+            let mut pos = 0u;
+            while pos < len {
+                let take = rng.gen_uint_range(1u, 70u);
+                let end = uint::min(len, pos + take);
+                streamed.input(vec::view(msg, pos, end));
+                pos = end;
+            }
+            assert streamed.result_bytes() == expected;
+        }
+    }
+
+    #[test]
+    fn test_digest_writer() {
+        let wr = DigestWriter(Md5::new());
+        wr.write_str("message ");
+        wr.write_str("digest");
+        assert wr.tell() == 14u;
+        assert wr.result_str() == ~"f96b697d7cb7938d525a2f31aaf161d0";
+    }
+}
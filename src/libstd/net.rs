@@ -14,12 +14,14 @@ Top-level module for network-related functionality.
 Basically, including this module gives you:
 
 * `net_tcp`
+* `net_udp`
 * `net_ip`
 * `net_url`
 
-See each of those three modules for documentation on what they do.
+See each of those modules for documentation on what they do.
 */
 
 pub use tcp = net_tcp;
+pub use udp = net_udp;
 pub use ip = net_ip;
 pub use url = net_url;
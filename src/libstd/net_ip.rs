@@ -94,6 +94,26 @@ pub fn get_port(ip: &IpAddr) -> uint {
     }
 }
 
+/**
+ * Return the same address with a different port, such as the
+ * destination of a `net::udp::send_to` call
+ *
+ * # Arguments
+ * * ip - a `std::net::ip::IpAddr`
+ * * port - the port of the new address
+ */
+pub fn with_port(ip: &IpAddr, port: uint) -> IpAddr {
+    let addr_str = format_addr(ip);
+    match *ip {
+        Ipv4(_) => unsafe {
+            Ipv4(uv_ip4_addr(addr_str, port as int))
+        },
+        Ipv6(_) => unsafe {
+            Ipv6(uv_ip6_addr(addr_str, port as int))
+        }
+    }
+}
+
 /// Represents errors returned from `net::ip::get_addr()`
 enum IpGetAddrErr {
     GetAddrUnknownError
@@ -392,6 +412,17 @@ mod test {
         assert format_result == localhost_str;
     }
     #[test]
+    fn test_ip_with_port() {
+        let addr = with_port(&v4::parse_addr(~"127.0.0.1"), 8080u);
+        assert format_addr(&addr) == ~"127.0.0.1";
+        assert get_port(&addr) == 8080u;
+        assert get_port(&with_port(&addr, 53u)) == 53u;
+
+        let addr = with_port(&v6::parse_addr(~"::1"), 8080u);
+        assert format_addr(&addr) == ~"::1";
+        assert get_port(&addr) == 8080u;
+    }
+    #[test]
     fn test_ip_ipv4_bad_parse() {
         match v4::try_parse_addr(~"b4df00d") {
           result::Err(ref err_info) => {
@@ -16,6 +16,7 @@
 use future;
 use future_spawn = future::spawn;
 use ip = net_ip;
+use time::{Timeout, Infinite, Millis};
 use uv;
 use uv::iotask;
 use uv::iotask::IoTask;
@@ -392,19 +393,57 @@ pub fn read_stop(sock: &TcpSocket) ->
  *
  * Does a blocking read operation for a single chunk of data from a
  * `tcp_socket` until a data arrives or an error is received. The provided
- * `timeout_msecs` value is used to raise an error if the timeout period
- * passes without any data received.
+ * `timeout` is used to raise an error if the timeout period passes without
+ * any data received.
  *
  * # Arguments
  *
  * * `sock` - a `net::tcp::tcp_socket` that you wish to read from
- * * `timeout_msecs` - a `uint` value, in msecs, to wait before dropping the
- * read attempt. Pass `0u` to wait indefinitely
+ * * `timeout` - how long to wait before dropping the read attempt. Pass
+ * `time::Infinite` to wait indefinitely
  */
-pub fn read(sock: &TcpSocket, timeout_msecs: uint)
+pub fn read(sock: &TcpSocket, timeout: Timeout)
     -> result::Result<~[u8],TcpErrData> {
     let socket_data = ptr::addr_of(&(*(sock.socket_data)));
-    read_common_impl(socket_data, timeout_msecs)
+    read_common_impl(socket_data, timeout)
+}
+
+/**
+ * Reads a single chunk of data from `tcp_socket`, waiting for at most
+ * `msecs` milliseconds
+ *
+ * The same as `read` with a `time::Millis` timeout. If no data arrives in
+ * time the error's `err_name` is `TIMEOUT`.
+ */
+pub fn read_timeout(sock: &TcpSocket, msecs: uint)
+    -> result::Result<~[u8],TcpErrData> {
+    read(sock, Millis(msecs as u64))
+}
+
+/**
+ * Enable or disable Nagle's algorithm on `tcp_socket`
+ *
+ * With `nodelay` set, small writes are sent at once instead of being held
+ * back to be combined with later ones, which lowers the latency of
+ * protocols that exchange small messages.
+ */
+pub fn set_nodelay(sock: &TcpSocket, nodelay: bool)
+    -> result::Result<(), TcpErrData> {
+    let socket_data = ptr::addr_of(&(*(sock.socket_data)));
+    set_option_common_impl(socket_data, NoDelay(nodelay))
+}
+
+/**
+ * Enable or disable TCP keep-alive probes on `tcp_socket`
+ *
+ * `Some(secs)` starts sending probes once the connection has been idle
+ * for `secs` seconds, which must be more than 0; `None` turns the probes
+ * off.
+ */
+pub fn set_keepalive(sock: &TcpSocket, delay: Option<uint>)
+    -> result::Result<(), TcpErrData> {
+    let socket_data = ptr::addr_of(&(*(sock.socket_data)));
+    set_option_common_impl(socket_data, KeepAlive(delay))
 }
 
 /**
@@ -414,9 +453,8 @@ pub fn read(sock: &TcpSocket, timeout_msecs: uint)
  * Does a non-blocking read operation for a single chunk of data from a
  * `tcp_socket` and immediately returns a `future` value representing the
  * result. When resolving the returned `future`, it will block until data
- * arrives or an error is received. The provided `timeout_msecs`
- * value is used to raise an error if the timeout period passes without any
- * data received.
+ * arrives or an error is received. The provided `timeout` is used to
+ * raise an error if the timeout period passes without any data received.
  *
  * # Safety
  *
@@ -433,14 +471,14 @@ pub fn read(sock: &TcpSocket, timeout_msecs: uint)
  * # Arguments
  *
  * * `sock` - a `net::tcp::tcp_socket` that you wish to read from
- * * `timeout_msecs` - a `uint` value, in msecs, to wait before dropping the
- * read attempt. Pass `0u` to wait indefinitely
+ * * `timeout` - how long to wait before dropping the read attempt. Pass
+ * `time::Infinite` to wait indefinitely
  */
-fn read_future(sock: &TcpSocket, timeout_msecs: uint)
+fn read_future(sock: &TcpSocket, timeout: Timeout)
     -> future::Future<result::Result<~[u8],TcpErrData>> {
     let socket_data = ptr::addr_of(&(*(sock.socket_data)));
     do future_spawn {
-        read_common_impl(socket_data, timeout_msecs)
+        read_common_impl(socket_data, timeout)
     }
 }
 
@@ -829,13 +867,24 @@ impl TcpSocket {
         result::Result<(), TcpErrData> {
         read_stop(&self)
     }
-    fn read(timeout_msecs: uint) ->
+    fn read(timeout: Timeout) ->
         result::Result<~[u8], TcpErrData> {
-        read(&self, timeout_msecs)
+        read(&self, timeout)
     }
-    fn read_future(timeout_msecs: uint) ->
+    fn read_future(timeout: Timeout) ->
         future::Future<result::Result<~[u8], TcpErrData>> {
-        read_future(&self, timeout_msecs)
+        read_future(&self, timeout)
+    }
+    fn read_timeout(msecs: uint) ->
+        result::Result<~[u8], TcpErrData> {
+        read_timeout(&self, msecs)
+    }
+    pub fn set_nodelay(nodelay: bool) -> result::Result<(), TcpErrData> {
+        set_nodelay(&self, nodelay)
+    }
+    pub fn set_keepalive(delay: Option<uint>)
+        -> result::Result<(), TcpErrData> {
+        set_keepalive(&self, delay)
     }
     pub fn write(raw_write_data: ~[u8])
         -> result::Result<(), TcpErrData> {
@@ -899,7 +948,7 @@ impl TcpSocketBuf: io::Reader {
           // need to read in data from the socket. Note that the internal
           // buffer is of no use anymore as we read all bytes from it,
           // so we can throw it away.
-          let read_result = read(&self.data.sock, 0u);
+          let read_result = read(&self.data.sock, Infinite);
           if read_result.is_err() {
               let err_data = read_result.get_err();
 
@@ -931,7 +980,7 @@ impl TcpSocketBuf: io::Reader {
             return c as int
           }
 
-          let read_result = read(&self.data.sock, 0u);
+          let read_result = read(&self.data.sock, Infinite);
           if read_result.is_err() {
               let err_data = read_result.get_err();
 
@@ -1026,7 +1075,7 @@ fn tear_down_socket_data(socket_data: @TcpSocketData) {
 }
 
 // shared implementation for tcp::read
-fn read_common_impl(socket_data: *TcpSocketData, timeout_msecs: uint)
+fn read_common_impl(socket_data: *TcpSocketData, timeout: Timeout)
     -> result::Result<~[u8],TcpErrData> {
     unsafe {
         use timer;
@@ -1040,12 +1089,8 @@ fn read_common_impl(socket_data: *TcpSocketData, timeout_msecs: uint)
         }
         else {
             log(debug, ~"tcp::read before recv_timeout");
-            let read_result = if timeout_msecs > 0u {
-                timer::recv_timeout(
-                    iotask, timeout_msecs, result::unwrap(rs_result))
-            } else {
-                Some(result::get(&rs_result).recv())
-            };
+            let read_result = timer::recv_timeout(
+                iotask, timeout, result::unwrap(rs_result));
             log(debug, ~"tcp::read after recv_timeout");
             match move read_result {
                 None => {
@@ -1097,6 +1142,46 @@ fn read_stop_common_impl(socket_data: *TcpSocketData) ->
     }
 }
 
+enum TcpOption {
+    NoDelay(bool),
+    KeepAlive(Option<uint>)
+}
+
+// shared impl for set_nodelay and set_keepalive
+fn set_option_common_impl(socket_data: *TcpSocketData, opt: TcpOption)
+    -> result::Result<(), TcpErrData> {
+    unsafe {
+        let stream_handle_ptr = (*socket_data).stream_handle_ptr;
+        let (result_po, result_ch) = stream::<Option<TcpErrData>>();
+        do iotask::interact(&(*socket_data).iotask) |loop_ptr| {
+            unsafe {
+                let status = match opt {
+                    NoDelay(nodelay) => {
+                        uv::ll::tcp_nodelay(stream_handle_ptr, nodelay)
+                    }
+                    KeepAlive(Some(delay)) => {
+                        uv::ll::tcp_keepalive(stream_handle_ptr, true, delay)
+                    }
+                    KeepAlive(None) => {
+                        uv::ll::tcp_keepalive(stream_handle_ptr, false, 0u)
+                    }
+                };
+                if status == 0i32 {
+                    result_ch.send(None);
+                } else {
+                    log(debug, fmt!("failure setting tcp option %?", opt));
+                    let err_data = uv::ll::get_last_err_data(loop_ptr);
+                    result_ch.send(Some(err_data.to_tcp_err()));
+                }
+            }
+        }
+        match result_po.recv() {
+            Some(move err_data) => Err(err_data),
+            None => Ok(())
+        }
+    }
+}
+
 // shared impl for read_start
 fn read_start_common_impl(socket_data: *TcpSocketData)
     -> result::Result<@Port<
@@ -1436,6 +1521,7 @@ pub mod test {
     use net::tcp::{GenericListenErr, TcpConnectErrData, TcpListenErrData};
     use net::tcp::{connect, accept, read, listen, TcpSocket, socket_buf};
     use net;
+    use time::Infinite;
     use uv::iotask::IoTask;
     use uv;
 
@@ -1498,6 +1584,10 @@ pub mod test {
             fn test_tcp_socket_impl_reader_handles_eof() {
                 impl_tcp_socket_impl_reader_handles_eof();
             }
+            #[test]
+            fn test_gl_tcp_ipv4_socket_options() {
+                impl_gl_tcp_ipv4_socket_options();
+            }
         }
         #[cfg(target_arch="x86")]
         #[cfg(target_arch="arm")]
@@ -1546,6 +1636,11 @@ pub mod test {
             fn test_gl_tcp_ipv4_server_client_reader_writer() {
                 impl_gl_tcp_ipv4_server_client_reader_writer();
             }
+            #[test]
+            #[ignore(cfg(target_os = "linux"))]
+            fn test_gl_tcp_ipv4_socket_options() {
+                impl_gl_tcp_ipv4_socket_options();
+            }
         }
     }
     pub fn impl_gl_tcp_ipv4_server_and_client() {
@@ -1628,7 +1723,7 @@ pub mod test {
         let resp_bytes = str::to_bytes(~"ping");
         tcp_write_single(&sock, resp_bytes);
         debug!("message sent");
-        sock.read(0u);
+        sock.read(Infinite);
         debug!("result read");
     }
     pub fn impl_gl_tcp_ipv4_client_error_connection_refused() {
@@ -1797,6 +1892,54 @@ pub mod test {
         assert expected_resp == actual_response;
     }
 
+    pub fn impl_gl_tcp_ipv4_socket_options() {
+        let hl_loop = &uv::global_loop::get();
+        let server_ip = ~"127.0.0.1";
+        let server_port = 8892u;
+        let expected_req = ~"ping";
+        let expected_resp = ~"pong";
+
+        let (server_result_po, server_result_ch) = stream::<~str>();
+
+        let (cont_po, cont_ch) = stream::<()>();
+        let cont_ch = SharedChan(cont_ch);
+        // server
+        let hl_loop_clone = hl_loop.clone();
+        do task::spawn_sched(task::ManualThreads(1u)) {
+            let cont_ch = cont_ch.clone();
+            let actual_req = run_tcp_test_server(
+                server_ip,
+                server_port,
+                expected_resp,
+                cont_ch.clone(),
+                &hl_loop_clone);
+            server_result_ch.send(actual_req);
+        };
+        cont_po.recv();
+        // client
+        let server_addr = ip::v4::parse_addr(server_ip);
+        let sock = result::unwrap(connect(server_addr, server_port, hl_loop));
+        assert sock.set_nodelay(true).is_ok();
+        assert sock.set_nodelay(false).is_ok();
+        assert sock.set_nodelay(true).is_ok();
+        assert sock.set_keepalive(Some(30u)).is_ok();
+        assert sock.set_keepalive(None).is_ok();
+
+        // The server says nothing until it gets a request
+        match sock.read_timeout(100u) {
+            Err(ref err_data) => assert err_data.err_name == ~"TIMEOUT",
+            Ok(_) => die!(~"read from a silent server did not time out")
+        }
+
+        // The socket still works after the timed out read
+        tcp_write_single(&sock, str::to_bytes(expected_req));
+        let actual_resp = str::from_bytes(result::unwrap(
+            sock.read_timeout(10000u)));
+        let actual_req = server_result_po.recv();
+        assert str::contains(actual_req, expected_req);
+        assert str::contains(actual_resp, expected_resp);
+    }
+
     fn buf_write<W:io::Writer>(w: &W, val: &str) {
         debug!("BUF_WRITE: val len %?", str::len(val));
         do str::byte_slice(val) |b_slice| {
@@ -1855,7 +1998,7 @@ pub mod test {
                                 connection from %s:%u",
                                  ip::format_addr(&peer_addr),
                                  ip::get_port(&peer_addr));
-                        let received_req_bytes = read(&sock, 0u);
+                        let received_req_bytes = read(&sock, Infinite);
                         match move received_req_bytes {
                           result::Ok(move data) => {
                             debug!("SERVER: got REQ str::from_bytes..");
@@ -1940,7 +2083,7 @@ pub mod test {
             let sock = result::unwrap(move connect_result);
             let resp_bytes = str::to_bytes(resp);
             tcp_write_single(&sock, resp_bytes);
-            let read_result = sock.read(0u);
+            let read_result = sock.read(Infinite);
             if read_result.is_err() {
                 debug!("CLIENT: failure to read");
                 Ok(~"")
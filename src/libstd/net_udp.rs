@@ -0,0 +1,579 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! High-level interface to libuv's UDP functionality
+
+use ip = net_ip;
+use time::{Timeout, Infinite};
+use uv;
+use uv::iotask;
+use uv::iotask::IoTask;
+
+use core::libc::size_t;
+use core::libc;
+use core::pipes::{stream, Port, SharedChan, Peekable};
+use core::prelude::*;
+use core::ptr;
+use core::result;
+use core::vec;
+
+#[nolink]
+extern mod rustrt {
+    unsafe fn rust_uv_current_kernel_malloc(size: libc::c_uint)
+                                         -> *libc::c_void;
+    unsafe fn rust_uv_current_kernel_free(mem: *libc::c_void);
+}
+
+/**
+ * A UDP socket bound to a local address
+ *
+ * Like `net::tcp::TcpSocket`, it closes the underlying libuv handle when
+ * it goes out of scope.
+ */
+pub struct UdpSocket {
+    socket_data: @UdpSocketData,
+}
+
+impl UdpSocket : Drop {
+    fn finalize(&self) {
+        unsafe {
+            tear_down_socket_data(self.socket_data)
+        }
+    }
+}
+
+/// Contains raw, string-based, error information returned from libuv
+pub struct UdpErrData {
+    err_name: ~str,
+    err_msg: ~str,
+}
+
+/**
+ * Bind a UDP socket to a local address
+ *
+ * # Arguments
+ *
+ * * `ip` - the local address to receive datagrams on
+ * * `port` - the local port to receive datagrams on
+ * * `iotask` - the `uv::iotask` that the socket's requests will run on
+ *
+ * # Returns
+ *
+ * A `result` containing the bound `UdpSocket`, or a `UdpErrData` if the
+ * socket could not be created or bound
+ */
+pub fn bind(ip: ip::IpAddr, port: uint, iotask: &IoTask)
+    -> result::Result<UdpSocket, UdpErrData> {
+    unsafe {
+        let (reader_po, reader_ch) = stream::<UdpRecvResult>();
+        let handle_ptr = rustrt::rust_uv_current_kernel_malloc(
+            uv::ll::udp_t_size() as libc::c_uint);
+        let send_req_ptr = rustrt::rust_uv_current_kernel_malloc(
+            uv::ll::udp_send_t_size() as libc::c_uint);
+        let socket_data = @UdpSocketData {
+            reader_po: reader_po,
+            reader_ch: SharedChan(reader_ch),
+            handle_ptr: handle_ptr,
+            send_req_ptr: send_req_ptr,
+            iotask: iotask.clone()
+        };
+        let socket_data_ptr = ptr::addr_of(&(*socket_data));
+        let (result_po, result_ch) = stream::<BindResult>();
+        do iotask::interact(iotask) |move ip, move result_ch, loop_ptr| {
+            unsafe {
+                match uv::ll::udp_init(loop_ptr, handle_ptr) {
+                    0i32 => {
+                        uv::ll::set_data_for_uv_handle(handle_ptr,
+                                                       socket_data_ptr);
+                        let addr_str = ip::format_addr(&ip);
+                        let bind_result = match ip {
+                            ip::Ipv4(_) => {
+                                let in_addr = uv::ll::ip4_addr(addr_str,
+                                                               port as int);
+                                uv::ll::udp_bind(handle_ptr,
+                                                 ptr::addr_of(&in_addr))
+                            }
+                            ip::Ipv6(_) => {
+                                let in_addr = uv::ll::ip6_addr(addr_str,
+                                                               port as int);
+                                uv::ll::udp_bind6(handle_ptr,
+                                                  ptr::addr_of(&in_addr))
+                            }
+                        };
+                        match bind_result {
+                            0i32 => result_ch.send(BindSuccess),
+                            _ => {
+                                let err_data =
+                                    uv::ll::get_last_err_data(loop_ptr);
+                                result_ch.send(
+                                    BindFailure(err_data.to_udp_err()));
+                            }
+                        }
+                    }
+                    _ => {
+                        let err_data = uv::ll::get_last_err_data(loop_ptr);
+                        result_ch.send(InitFailure(err_data.to_udp_err()));
+                    }
+                }
+            }
+        }
+        match result_po.recv() {
+            BindSuccess => result::Ok(UdpSocket { socket_data: socket_data }),
+            // The handle is open, so let the socket's destructor close it
+            BindFailure(move err_data) => {
+                let _sock = UdpSocket { socket_data: socket_data };
+                result::Err(err_data)
+            }
+            InitFailure(move err_data) => {
+                rustrt::rust_uv_current_kernel_free(handle_ptr);
+                rustrt::rust_uv_current_kernel_free(send_req_ptr);
+                result::Err(err_data)
+            }
+        }
+    }
+}
+
+/**
+ * Send a datagram; blocks until the operation completes
+ *
+ * # Arguments
+ *
+ * * `sock` - the `UdpSocket` to send from
+ * * `buf` - the contents of the datagram
+ * * `addr` - the address and port to send the datagram to. See
+ * `net::ip::with_port`.
+ */
+pub fn send_to(sock: &UdpSocket, buf: &[u8], addr: &ip::IpAddr)
+    -> result::Result<(), UdpErrData> {
+    unsafe {
+        let socket_data_ptr = ptr::addr_of(&(*(sock.socket_data)));
+        send_common_impl(socket_data_ptr, buf, addr)
+    }
+}
+
+/**
+ * Receive a single datagram; blocks until one arrives or an error is
+ * received
+ *
+ * # Returns
+ *
+ * A `result` containing the datagram and the address it was sent from, or
+ * a `UdpErrData`
+ */
+pub fn recv_from(sock: &UdpSocket)
+    -> result::Result<(~[u8], ip::IpAddr), UdpErrData> {
+    recv_from_timeout(sock, Infinite)
+}
+
+/**
+ * Receive a single datagram, giving up after `timeout`
+ *
+ * If no datagram arrives in time the error's `err_name` is `TIMEOUT`.
+ */
+pub fn recv_from_timeout(sock: &UdpSocket, timeout: Timeout)
+    -> result::Result<(~[u8], ip::IpAddr), UdpErrData> {
+    let socket_data_ptr = ptr::addr_of(&(*(sock.socket_data)));
+    recv_common_impl(socket_data_ptr, timeout)
+}
+
+/// Convenience methods extending `net::udp::UdpSocket`
+pub impl UdpSocket {
+    fn send_to(&self, buf: &[u8], addr: &ip::IpAddr)
+        -> result::Result<(), UdpErrData> {
+        send_to(self, buf, addr)
+    }
+    fn recv_from(&self)
+        -> result::Result<(~[u8], ip::IpAddr), UdpErrData> {
+        recv_from(self)
+    }
+    fn recv_from_timeout(&self, timeout: Timeout)
+        -> result::Result<(~[u8], ip::IpAddr), UdpErrData> {
+        recv_from_timeout(self, timeout)
+    }
+
+    /**
+     * Allow sending datagrams to broadcast addresses
+     *
+     * Reserved for later; this binding does not support it yet, so it
+     * always returns an `ENOTSUP` error.
+     */
+    fn set_broadcast(&self, _on: bool) -> result::Result<(), UdpErrData> {
+        not_supported(~"broadcast")
+    }
+
+    /**
+     * Receive the datagrams sent to the multicast group `group`
+     *
+     * Reserved for later; this binding does not support it yet, so it
+     * always returns an `ENOTSUP` error.
+     */
+    fn join_multicast(&self, _group: &ip::IpAddr)
+        -> result::Result<(), UdpErrData> {
+        not_supported(~"multicast")
+    }
+
+    /**
+     * Stop receiving the datagrams sent to the multicast group `group`
+     *
+     * Reserved for later, like `join_multicast`.
+     */
+    fn leave_multicast(&self, _group: &ip::IpAddr)
+        -> result::Result<(), UdpErrData> {
+        not_supported(~"multicast")
+    }
+}
+
+// INTERNAL API
+
+fn not_supported(what: ~str) -> result::Result<(), UdpErrData> {
+    result::Err(UdpErrData {
+        err_name: ~"ENOTSUP",
+        err_msg: what + ~" is not supported by net::udp yet"
+    })
+}
+
+type UdpRecvResult = result::Result<(~[u8], ip::IpAddr), UdpErrData>;
+
+struct UdpSocketData {
+    reader_po: Port<UdpRecvResult>,
+    reader_ch: SharedChan<UdpRecvResult>,
+    handle_ptr: *uv::ll::uv_udp_t,
+    send_req_ptr: *uv::ll::uv_udp_send_t,
+    iotask: IoTask,
+}
+
+enum BindResult {
+    BindSuccess,
+    // the handle was initialized and has to be closed
+    BindFailure(UdpErrData),
+    InitFailure(UdpErrData)
+}
+
+struct SendReqData {
+    result_ch: SharedChan<Option<UdpErrData>>,
+    handle_ptr: *uv::ll::uv_udp_t,
+}
+
+struct UdpSocketCloseData {
+    closed_ch: SharedChan<()>,
+}
+
+trait ToUdpErr {
+    fn to_udp_err() -> UdpErrData;
+}
+
+impl uv::ll::uv_err_data: ToUdpErr {
+    fn to_udp_err() -> UdpErrData {
+        UdpErrData { err_name: self.err_name, err_msg: self.err_msg }
+    }
+}
+
+fn tear_down_socket_data(socket_data: @UdpSocketData) {
+    unsafe {
+        let (closed_po, closed_ch) = stream::<()>();
+        let close_data = UdpSocketCloseData {
+            closed_ch: SharedChan(closed_ch)
+        };
+        let close_data_ptr = ptr::addr_of(&close_data);
+        let handle_ptr = (*socket_data).handle_ptr;
+        do iotask::interact(&(*socket_data).iotask) |loop_ptr| {
+            unsafe {
+                log(debug,
+                    fmt!("interact dtor for udp_socket handle %? loop %?",
+                         handle_ptr, loop_ptr));
+                uv::ll::set_data_for_uv_handle(handle_ptr, close_data_ptr);
+                uv::ll::close(handle_ptr, udp_socket_dtor_close_cb);
+            }
+        };
+        closed_po.recv();
+        rustrt::rust_uv_current_kernel_free(handle_ptr);
+        rustrt::rust_uv_current_kernel_free((*socket_data).send_req_ptr);
+        log(debug, ~"exiting dtor for udp_socket");
+    }
+}
+
+// shared implementation for send_to
+fn send_common_impl(socket_data_ptr: *UdpSocketData, buf: &[u8],
+                    addr: &ip::IpAddr)
+    -> result::Result<(), UdpErrData> {
+    unsafe {
+        let send_req_ptr = (*socket_data_ptr).send_req_ptr;
+        let handle_ptr = (*socket_data_ptr).handle_ptr;
+        let send_buf_vec = ~[ uv::ll::buf_init(vec::raw::to_ptr(buf),
+                                               vec::len(buf)) ];
+        let send_buf_vec_ptr = ptr::addr_of(&send_buf_vec);
+        let (result_po, result_ch) = stream::<Option<UdpErrData>>();
+        let send_data = SendReqData {
+            result_ch: SharedChan(result_ch),
+            handle_ptr: handle_ptr
+        };
+        let send_data_ptr = ptr::addr_of(&send_data);
+        let addr = copy *addr;
+        do iotask::interact(&(*socket_data_ptr).iotask) |move addr,
+                                                         loop_ptr| {
+            unsafe {
+                let send_result = match addr {
+                    ip::Ipv4(ref in_addr) => {
+                        uv::ll::udp_send(send_req_ptr, handle_ptr,
+                                         send_buf_vec_ptr,
+                                         ptr::addr_of(in_addr),
+                                         udp_send_complete_cb)
+                    }
+                    ip::Ipv6(ref in_addr) => {
+                        uv::ll::udp_send6(send_req_ptr, handle_ptr,
+                                          send_buf_vec_ptr,
+                                          ptr::addr_of(in_addr),
+                                          udp_send_complete_cb)
+                    }
+                };
+                match send_result {
+                    0i32 => {
+                        uv::ll::set_data_for_req(send_req_ptr,
+                                                 send_data_ptr);
+                    }
+                    _ => {
+                        log(debug, ~"error invoking uv_udp_send()");
+                        let err_data = uv::ll::get_last_err_data(loop_ptr);
+                        let result_ch = (*send_data_ptr).result_ch.clone();
+                        result_ch.send(Some(err_data.to_udp_err()));
+                    }
+                }
+            }
+        }
+        // As in net::tcp::write, the buffer and request data live on this
+        // stack, so wait here until libuv is done with them
+        match result_po.recv() {
+            Some(move err_data) => result::Err(err_data),
+            None => result::Ok(())
+        }
+    }
+}
+
+// shared implementation for recv_from and recv_from_timeout
+fn recv_common_impl(socket_data_ptr: *UdpSocketData, timeout: Timeout)
+    -> UdpRecvResult {
+    unsafe {
+        use timer;
+
+        // A datagram that arrived just before the last receive stopped
+        let reader_po = &(*socket_data_ptr).reader_po;
+        if reader_po.peek() {
+            return reader_po.recv();
+        }
+
+        match recv_start_stop_common_impl(socket_data_ptr, true) {
+            Some(move err_data) => return result::Err(err_data),
+            None => ()
+        }
+        let recv_result = timer::recv_timeout(
+            &(*socket_data_ptr).iotask, timeout, reader_po);
+        recv_start_stop_common_impl(socket_data_ptr, false);
+        match move recv_result {
+            Some(move data_result) => data_result,
+            None => {
+                log(debug, ~"udp::recv_from: timed out..");
+                result::Err(UdpErrData {
+                    err_name: ~"TIMEOUT",
+                    err_msg: ~"req timed out"
+                })
+            }
+        }
+    }
+}
+
+// Start or stop delivering datagrams to the socket's reader port.
+// Receiving only while a caller waits leaves any backlog in the kernel
+// rather than in an unbounded port.
+fn recv_start_stop_common_impl(socket_data_ptr: *UdpSocketData,
+                               start: bool) -> Option<UdpErrData> {
+    unsafe {
+        let handle_ptr = (*socket_data_ptr).handle_ptr;
+        let (result_po, result_ch) = stream::<Option<UdpErrData>>();
+        do iotask::interact(&(*socket_data_ptr).iotask) |move result_ch,
+                                                         loop_ptr| {
+            unsafe {
+                let status = if start {
+                    uv::ll::udp_recv_start(handle_ptr, on_alloc_cb,
+                                           on_udp_recv_cb)
+                } else {
+                    uv::ll::udp_recv_stop(handle_ptr)
+                };
+                match status {
+                    0i32 => result_ch.send(None),
+                    _ => {
+                        let err_data = uv::ll::get_last_err_data(loop_ptr);
+                        result_ch.send(Some(err_data.to_udp_err()));
+                    }
+                }
+            }
+        }
+        result_po.recv()
+    }
+}
+
+unsafe fn sockaddr_to_ip(addr: *libc::c_void) -> ip::IpAddr {
+    if uv::ll::is_ipv4_sockaddr(addr) {
+        ip::Ipv4(copy *(addr as *uv::ll::sockaddr_in))
+    } else {
+        ip::Ipv6(copy *(addr as *uv::ll::sockaddr_in6))
+    }
+}
+
+extern fn on_udp_recv_cb(handle: *uv::ll::uv_udp_t,
+                         nread: libc::ssize_t,
+                         ++buf: uv::ll::uv_buf_t,
+                         addr: *libc::c_void,
+                         _flags: libc::c_uint) {
+    unsafe {
+        log(debug, fmt!("entering on_udp_recv_cb handle: %? nread: %?",
+                        handle, nread));
+        let socket_data_ptr = uv::ll::get_data_for_uv_handle(handle)
+            as *UdpSocketData;
+        let reader_ch = &(*socket_data_ptr).reader_ch;
+        if (nread as int) < 0 {
+            let loop_ptr = uv::ll::get_loop_for_uv_handle(handle);
+            let err_data = uv::ll::get_last_err_data(loop_ptr).to_udp_err();
+            reader_ch.send(result::Err(err_data));
+        } else if addr.is_not_null() {
+            // A datagram, which may be empty. Without an address there
+            // was nothing to read and the buffer is unneeded.
+            let buf_base = uv::ll::get_base_from_buf(buf);
+            let new_bytes = vec::from_buf(buf_base, nread as uint);
+            reader_ch.send(result::Ok((new_bytes, sockaddr_to_ip(addr))));
+        }
+        uv::ll::free_base_of_buf(buf);
+    }
+}
+
+extern fn on_alloc_cb(handle: *libc::c_void,
+                      suggested_size: size_t)
+    -> uv::ll::uv_buf_t {
+    unsafe {
+        log(debug, fmt!("udp recv on_alloc_cb h: %? sugsize: %u",
+                        handle, suggested_size as uint));
+        let char_ptr = uv::ll::malloc_buf_base_of(suggested_size);
+        uv::ll::buf_init(char_ptr, suggested_size as uint)
+    }
+}
+
+extern fn udp_send_complete_cb(send_req: *uv::ll::uv_udp_send_t,
+                               status: libc::c_int) {
+    unsafe {
+        let send_data_ptr = uv::ll::get_data_for_req(send_req)
+            as *SendReqData;
+        let result_ch = (*send_data_ptr).result_ch.clone();
+        if status == 0i32 {
+            result_ch.send(None);
+        } else {
+            let loop_ptr = uv::ll::get_loop_for_uv_handle(
+                (*send_data_ptr).handle_ptr);
+            let err_data = uv::ll::get_last_err_data(loop_ptr);
+            result_ch.send(Some(err_data.to_udp_err()));
+        }
+    }
+}
+
+extern fn udp_socket_dtor_close_cb(handle: *uv::ll::uv_udp_t) {
+    unsafe {
+        let data = uv::ll::get_data_for_uv_handle(handle)
+            as *UdpSocketCloseData;
+        let closed_ch = (*data).closed_ch.clone();
+        closed_ch.send(());
+        log(debug, ~"udp_socket_dtor_close_cb exiting..");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::prelude::*;
+
+    use net::ip;
+    use net::udp::*;
+    use time::Millis;
+    use uv;
+
+    use core::result;
+    use core::str;
+    use core::uint;
+    use core::vec;
+
+    fn local(port: uint) -> ip::IpAddr {
+        ip::with_port(&ip::v4::parse_addr(~"127.0.0.1"), port)
+    }
+
+    fn bind_local(port: uint) -> UdpSocket {
+        let iotask = &uv::global_loop::get();
+        match bind(ip::v4::parse_addr(~"127.0.0.1"), port, iotask) {
+            Ok(move sock) => move sock,
+            Err(ref err_data) => die!(fmt!("bind failed: %s %s",
+                                           err_data.err_name,
+                                           err_data.err_msg))
+        }
+    }
+
+    #[test]
+    fn test_udp_echo() {
+        let a = bind_local(9021u);
+        let b = bind_local(9022u);
+
+        assert a.send_to(str::to_bytes(~"ping"), &local(9022u)).is_ok();
+        let (data, from) = result::unwrap(b.recv_from());
+        assert data == str::to_bytes(~"ping");
+        assert ip::format_addr(&from) == ~"127.0.0.1";
+        assert ip::get_port(&from) == 9021u;
+
+        // Reply to whoever sent the request
+        assert b.send_to(str::to_bytes(~"pong"), &from).is_ok();
+        let (data, from) = result::unwrap(a.recv_from());
+        assert data == str::to_bytes(~"pong");
+        assert ip::get_port(&from) == 9022u;
+    }
+
+    #[test]
+    fn test_udp_datagram_boundaries() {
+        let a = bind_local(9023u);
+        let b = bind_local(9024u);
+        let dest = local(9024u);
+
+        // Each send arrives as one datagram, including an empty one
+        for uint::range(0u, 10u) |i| {
+            let buf = vec::from_elem(i * 100u, i as u8);
+            assert a.send_to(buf, &dest).is_ok();
+        }
+        for uint::range(0u, 10u) |i| {
+            let (data, _) = result::unwrap(b.recv_from());
+            assert data == vec::from_elem(i * 100u, i as u8);
+        }
+    }
+
+    #[test]
+    fn test_udp_recv_timeout() {
+        let a = bind_local(9025u);
+        match a.recv_from_timeout(Millis(50)) {
+            Err(ref err_data) => assert err_data.err_name == ~"TIMEOUT",
+            Ok(_) => die!(~"received a datagram nobody sent")
+        }
+
+        // The socket still works afterwards
+        let b = bind_local(9026u);
+        assert b.send_to(~[1u8, 2u8, 3u8], &local(9025u)).is_ok();
+        let (data, _) = result::unwrap(a.recv_from_timeout(Millis(5000)));
+        assert data == ~[1u8, 2u8, 3u8];
+    }
+
+    #[test]
+    fn test_udp_unsupported_options() {
+        let a = bind_local(9027u);
+        let group = ip::v4::parse_addr(~"224.0.0.1");
+        assert a.set_broadcast(true).get_err().err_name == ~"ENOTSUP";
+        assert a.join_multicast(&group).get_err().err_name == ~"ENOTSUP";
+        assert a.leave_multicast(&group).get_err().err_name == ~"ENOTSUP";
+    }
+}
@@ -84,7 +84,8 @@ impl <T: Ord> PriorityQueue<T> {
         item
     }
 
-    /// Optimized version of a pop followed by a push - fails if empty
+    /// Optimized version of a pop followed by a push, sifting down only
+    /// once - fails if empty
     fn replace(&mut self, mut item: T) -> T {
         item <-> self.data[0];
         self.siftdown(0);
@@ -110,7 +111,9 @@ impl <T: Ord> PriorityQueue<T> {
     /// Create an empty PriorityQueue
     static pure fn new() -> PriorityQueue<T> { PriorityQueue{data: ~[],} }
 
-    /// Create a PriorityQueue from a vector (heapify)
+    /// Create a PriorityQueue from a vector (heapify). This sifts down
+    /// each internal node from the bottom up, taking O(n) time rather
+    /// than the O(n log n) of n pushes.
     static pure fn from_vec(xs: ~[T]) -> PriorityQueue<T> {
         let mut q = PriorityQueue{data: xs,};
         let mut n = q.len() / 2;
@@ -178,6 +181,9 @@ impl <T: Ord> PriorityQueue<T> {
 mod tests {
     use sort::merge_sort;
     use core::cmp::le;
+    use core::rand;
+    use core::uint;
+    use core::vec;
     use priority_queue::PriorityQueue::{from_vec, new};
 
     #[test]
@@ -286,6 +292,44 @@ mod tests {
         check_to_vec(~[5, 4, 3, 2, 1, 5, 4, 3, 2, 1, 5, 4, 3, 2, 1]);
     }
 
+    fn random_vec(rng: rand::Rng, len: uint) -> ~[int] {
+        vec::from_fn(len, |_| rng.gen_int_range(-50, 50))
+    }
+
+    fn is_heap(v: &[int]) -> bool {
+        for uint::range(1, v.len()) |i| {
+            if v[i] > v[(i - 1) / 2] { return false; }
+        }
+        true
+    }
+
+    #[test]
+    fn test_from_vec_random() {
+        let rng = rand::Rng();
+        for uint::range(0, 200) |len| {
+            let data = random_vec(rng, len);
+            assert is_heap(from_vec(copy data).to_vec());
+            assert from_vec(copy data).to_sorted_vec() ==
+                merge_sort(data, le);
+        }
+    }
+
+    #[test]
+    fn test_replace_random() {
+        let rng = rand::Rng();
+        let data = random_vec(rng, 100);
+        let mut control = merge_sort(data, le);
+        let mut heap = from_vec(data);
+        for 500.times {
+            let item = rng.gen_int_range(-50, 50);
+            assert heap.replace(item) == control.pop();
+            control.push(item);
+            control = merge_sort(control, le);
+            assert *heap.top() == control.last();
+        }
+        assert heap.to_sorted_vec() == control;
+    }
+
     #[test]
     #[should_fail]
     #[ignore(cfg(windows))]
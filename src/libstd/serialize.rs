@@ -17,8 +17,14 @@ Core encoding and decoding interfaces.
 #[forbid(non_camel_case_types)];
 
 use core::at_vec;
+use core::container::{Container, Map, Set};
+use core::hash::Hash;
+use core::hashmap::linear::{LinearMap, LinearSet};
 use core::prelude::*;
+use core::to_bytes::IterBytes;
+use core::uint;
 use core::vec;
+use treemap::TreeMap;
 
 pub trait Encoder {
     // Primitive types:
@@ -549,6 +555,121 @@ pub impl<
     }
 }
 
+// ___________________________________________________________________________
+// Collections
+//
+// Maps are encoded as sequences of key-value pairs and sets as sequences of
+// their elements, in the order the collection visits them. When a decoded
+// sequence holds the same key more than once, the last pair wins.
+
+fn emit_pair<S: Encoder, K: Encodable<S>, V: Encodable<S>>(s: &S, idx: uint,
+                                                          k: &K, v: &V) {
+    do s.emit_vec_elt(idx) {
+        do s.emit_tup(2) {
+            s.emit_tup_elt(0, || k.encode(s));
+            s.emit_tup_elt(1, || v.encode(s));
+        }
+    }
+}
+
+pub impl<
+    S: Encoder,
+    K: Encodable<S> Hash IterBytes Eq,
+    V: Encodable<S>
+> LinearMap<K, V>: Encodable<S> {
+    fn encode(&self, s: &S) {
+        do s.emit_owned_vec(self.len()) {
+            let mut i = 0;
+            for self.each |k, v| {
+                emit_pair(s, i, k, v);
+                i += 1;
+            }
+        }
+    }
+}
+
+pub impl<
+    D: Decoder,
+    K: Decodable<D> Hash IterBytes Eq,
+    V: Decodable<D>
+> LinearMap<K, V>: Decodable<D> {
+    static fn decode(&self, d: &D) -> LinearMap<K, V> {
+        do d.read_owned_vec |len| {
+            let mut map = LinearMap::new();
+            for uint::range(0, len) |i| {
+                let (k, v): (K, V) =
+                    d.read_vec_elt(i, || Decodable::decode(d));
+                map.insert(k, v);
+            }
+            move map
+        }
+    }
+}
+
+pub impl<
+    S: Encoder,
+    T: Encodable<S> Hash IterBytes Eq
+> LinearSet<T>: Encodable<S> {
+    fn encode(&self, s: &S) {
+        do s.emit_owned_vec(self.len()) {
+            let mut i = 0;
+            for self.each |e| {
+                s.emit_vec_elt(i, || e.encode(s));
+                i += 1;
+            }
+        }
+    }
+}
+
+pub impl<
+    D: Decoder,
+    T: Decodable<D> Hash IterBytes Eq
+> LinearSet<T>: Decodable<D> {
+    static fn decode(&self, d: &D) -> LinearSet<T> {
+        do d.read_owned_vec |len| {
+            let mut set = LinearSet::new();
+            for uint::range(0, len) |i| {
+                set.insert(d.read_vec_elt(i, || Decodable::decode(d)));
+            }
+            move set
+        }
+    }
+}
+
+pub impl<
+    S: Encoder,
+    K: Encodable<S> Ord,
+    V: Encodable<S>
+> TreeMap<K, V>: Encodable<S> {
+    fn encode(&self, s: &S) {
+        do s.emit_owned_vec(self.len()) {
+            let mut i = 0;
+            for self.each |k, v| {
+                emit_pair(s, i, k, v);
+                i += 1;
+            }
+        }
+    }
+}
+
+pub impl<
+    D: Decoder,
+    K: Decodable<D> Ord,
+    V: Decodable<D>
+> TreeMap<K, V>: Decodable<D> {
+    static fn decode(&self, d: &D) -> TreeMap<K, V> {
+        do d.read_owned_vec |len| {
+            let mut map = TreeMap::new();
+            for uint::range(0, len) |i| {
+                let (k, v): (K, V) =
+                    d.read_vec_elt(i, || Decodable::decode(d));
+                map.insert(k, v);
+            }
+            move map
+        }
+    }
+}
+
 // ___________________________________________________________________________
 // Helper routines
 //
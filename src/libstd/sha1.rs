@@ -11,18 +11,20 @@
 /*!
  * An implementation of the SHA-1 cryptographic hash.
  *
- * First create a `sha1` object using the `sha1` constructor, then
- * feed it input using the `input` or `input_str` methods, which may be
- * called any number of times.
+ * First create a `Sha1` object using `Sha1::new`, then feed it input
+ * using the `input` method of `digest::Digest`, or `input_str` from
+ * `digest::DigestUtil`, which may be called any number of times.
  *
  * After the entire input has been fed to the hash read the result using
- * the `result` or `result_str` methods.
+ * the `result` method, or `result_str` for hex.
  *
- * The `sha1` object may be reused to create multiple hashes by calling
+ * The `Sha1` object may be reused to create multiple hashes by calling
  * the `reset` method.
  */
 
-use core::str;
+use digest::Digest;
+
+use core::prelude::*;
 use core::uint;
 use core::vec;
 
@@ -32,26 +34,6 @@ use core::vec;
  * point this will want to be rewritten.
  */
 
-/// The SHA-1 interface
-trait Sha1 {
-    /// Provide message input as bytes
-    fn input(&[const u8]);
-    /// Provide message input as string
-    fn input_str(&str);
-    /**
-     * Read the digest as a vector of 20 bytes. After calling this no further
-     * input may be provided until reset is called.
-     */
-    fn result() -> ~[u8];
-    /**
-     * Read the digest as a hex string. After calling this no further
-     * input may be provided until reset is called.
-     */
-    fn result_str() -> ~str;
-    /// Reset the SHA-1 state for reuse
-    fn reset();
-}
-
 // Some unexported constants
 const digest_buf_len: uint = 5u;
 const msg_block_len: uint = 64u;
@@ -61,220 +43,209 @@ const k1: u32 = 0x6ED9EBA1u32;
 const k2: u32 = 0x8F1BBCDCu32;
 const k3: u32 = 0xCA62C1D6u32;
 
+/// The state of a SHA-1 hash
+pub struct Sha1 {
+    priv h: ~[u32],
+    // the length of the message so far, in bytes
+    priv len: u64,
+    priv msg_block: ~[u8],
+    priv msg_block_idx: uint,
+    priv computed: bool,
+    priv work_buf: ~[u32],
+}
 
-/// Construct a `sha` object
-pub fn sha1() -> Sha1 {
-    struct Sha1State
-        {h: ~[mut u32],
-         mut len_low: u32,
-         mut len_high: u32,
-         msg_block: ~[mut u8],
-         mut msg_block_idx: uint,
-         mut computed: bool,
-         work_buf: @~[mut u32]};
-
-    fn add_input(st: &Sha1State, msg: &[const u8]) {
-        assert (!st.computed);
-        for vec::each_const(msg) |element| {
-            st.msg_block[st.msg_block_idx] = *element;
-            st.msg_block_idx += 1u;
-            st.len_low += 8u32;
-            if st.len_low == 0u32 {
-                st.len_high += 1u32;
-                if st.len_high == 0u32 {
-                    // FIXME: Need better failure mode (#2346)
-                    die!();
-                }
-            }
-            if st.msg_block_idx == msg_block_len { process_msg_block(st); }
-        }
+pub impl Sha1 {
+    /// Construct a `Sha1` object for an empty message
+    static fn new() -> Sha1 {
+        let mut st = Sha1 {
+            h: vec::from_elem(digest_buf_len, 0u32),
+            len: 0u64,
+            msg_block: vec::from_elem(msg_block_len, 0u8),
+            msg_block_idx: 0u,
+            computed: false,
+            work_buf: vec::from_elem(work_buf_len, 0u32)
+        };
+        st.reset();
+        st
     }
-    fn process_msg_block(st: &Sha1State) {
-        assert (vec::len(st.h) == digest_buf_len);
-        assert (vec::len(*st.work_buf) == work_buf_len);
-        let mut t: int; // Loop counter
-        let w = st.work_buf;
-
-        // Initialize the first 16 words of the vector w
-        t = 0;
-        while t < 16 {
-            let mut tmp;
-            tmp = (st.msg_block[t * 4] as u32) << 24u32;
-            tmp = tmp | (st.msg_block[t * 4 + 1] as u32) << 16u32;
-            tmp = tmp | (st.msg_block[t * 4 + 2] as u32) << 8u32;
-            tmp = tmp | (st.msg_block[t * 4 + 3] as u32);
-            w[t] = tmp;
-            t += 1;
-        }
+}
 
-        // Initialize the rest of vector w
-        while t < 80 {
-            let val = w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16];
-            w[t] = circular_shift(1u32, val);
-            t += 1;
-        }
-        let mut a = st.h[0];
-        let mut b = st.h[1];
-        let mut c = st.h[2];
-        let mut d = st.h[3];
-        let mut e = st.h[4];
-        let mut temp: u32;
-        t = 0;
-        while t < 20 {
-            temp = circular_shift(5u32, a) + (b & c | !b & d) + e + w[t] + k0;
-            e = d;
-            d = c;
-            c = circular_shift(30u32, b);
-            b = a;
-            a = temp;
-            t += 1;
-        }
-        while t < 40 {
-            temp = circular_shift(5u32, a) + (b ^ c ^ d) + e + w[t] + k1;
-            e = d;
-            d = c;
-            c = circular_shift(30u32, b);
-            b = a;
-            a = temp;
-            t += 1;
-        }
-        while t < 60 {
-            temp =
-                circular_shift(5u32, a) + (b & c | b & d | c & d) + e + w[t] +
-                    k2;
-            e = d;
-            d = c;
-            c = circular_shift(30u32, b);
-            b = a;
-            a = temp;
-            t += 1;
-        }
-        while t < 80 {
-            temp = circular_shift(5u32, a) + (b ^ c ^ d) + e + w[t] + k3;
-            e = d;
-            d = c;
-            c = circular_shift(30u32, b);
-            b = a;
-            a = temp;
-            t += 1;
-        }
-        st.h[0] = st.h[0] + a;
-        st.h[1] = st.h[1] + b;
-        st.h[2] = st.h[2] + c;
-        st.h[3] = st.h[3] + d;
-        st.h[4] = st.h[4] + e;
-        st.msg_block_idx = 0u;
+fn add_input(st: &mut Sha1, msg: &[const u8]) {
+    assert (!st.computed);
+    let mut i = 0u;
+    while i < msg.len() {
+        st.msg_block[st.msg_block_idx] = msg[i];
+        st.msg_block_idx += 1u;
+        if st.msg_block_idx == msg_block_len { process_msg_block(st); }
+        i += 1u;
+    }
+    st.len += msg.len() as u64;
+}
+
+fn process_msg_block(st: &mut Sha1) {
+    assert (vec::len(st.h) == digest_buf_len);
+    assert (vec::len(st.work_buf) == work_buf_len);
+    let mut t: int; // Loop counter
+    let w = &mut st.work_buf;
+
+    // Initialize the first 16 words of the vector w
+    t = 0;
+    while t < 16 {
+        let mut tmp;
+        tmp = (st.msg_block[t * 4] as u32) << 24u32;
+        tmp = tmp | (st.msg_block[t * 4 + 1] as u32) << 16u32;
+        tmp = tmp | (st.msg_block[t * 4 + 2] as u32) << 8u32;
+        tmp = tmp | (st.msg_block[t * 4 + 3] as u32);
+        w[t] = tmp;
+        t += 1;
+    }
+
+    // Initialize the rest of vector w
+    while t < 80 {
+        let val = w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16];
+        w[t] = circular_shift(1u32, val);
+        t += 1;
     }
-    fn circular_shift(bits: u32, word: u32) -> u32 {
-        return word << bits | word >> 32u32 - bits;
+    let mut a = st.h[0];
+    let mut b = st.h[1];
+    let mut c = st.h[2];
+    let mut d = st.h[3];
+    let mut e = st.h[4];
+    let mut temp: u32;
+    t = 0;
+    while t < 20 {
+        temp = circular_shift(5u32, a) + (b & c | !b & d) + e + w[t] + k0;
+        e = d;
+        d = c;
+        c = circular_shift(30u32, b);
+        b = a;
+        a = temp;
+        t += 1;
     }
-    fn mk_result(st: &Sha1State) -> ~[u8] {
-        if !(*st).computed { pad_msg(st); (*st).computed = true; }
-        let mut rs: ~[u8] = ~[];
-        for vec::each_mut((*st).h) |ptr_hpart| {
-            let hpart = *ptr_hpart;
-            let a = (hpart >> 24u32 & 0xFFu32) as u8;
-            let b = (hpart >> 16u32 & 0xFFu32) as u8;
-            let c = (hpart >> 8u32 & 0xFFu32) as u8;
-            let d = (hpart & 0xFFu32) as u8;
-            rs = vec::append(rs, ~[a, b, c, d]);
-        }
-        return rs;
+    while t < 40 {
+        temp = circular_shift(5u32, a) + (b ^ c ^ d) + e + w[t] + k1;
+        e = d;
+        d = c;
+        c = circular_shift(30u32, b);
+        b = a;
+        a = temp;
+        t += 1;
     }
+    while t < 60 {
+        temp =
+            circular_shift(5u32, a) + (b & c | b & d | c & d) + e + w[t] +
+                k2;
+        e = d;
+        d = c;
+        c = circular_shift(30u32, b);
+        b = a;
+        a = temp;
+        t += 1;
+    }
+    while t < 80 {
+        temp = circular_shift(5u32, a) + (b ^ c ^ d) + e + w[t] + k3;
+        e = d;
+        d = c;
+        c = circular_shift(30u32, b);
+        b = a;
+        a = temp;
+        t += 1;
+    }
+    st.h[0] = st.h[0] + a;
+    st.h[1] = st.h[1] + b;
+    st.h[2] = st.h[2] + c;
+    st.h[3] = st.h[3] + d;
+    st.h[4] = st.h[4] + e;
+    st.msg_block_idx = 0u;
+}
+
+fn circular_shift(bits: u32, word: u32) -> u32 {
+    return word << bits | word >> 32u32 - bits;
+}
+
+/*
+ * According to the standard, the message must be padded to an even
+ * 512 bits.  The first padding bit must be a '1'.  The last 64 bits
+ * represent the length of the original message.  All bits in between
+ * should be 0.  This function will pad the message according to those
+ * rules by filling the msg_block vector accordingly.  It will also
+ * call process_msg_block() appropriately.  When it returns, it
+ * can be assumed that the message digest has been computed.
+ */
+fn pad_msg(st: &mut Sha1) {
+    assert (vec::len(st.msg_block) == msg_block_len);
 
     /*
-     * According to the standard, the message must be padded to an even
-     * 512 bits.  The first padding bit must be a '1'.  The last 64 bits
-     * represent the length of the original message.  All bits in between
-     * should be 0.  This function will pad the message according to those
-     * rules by filling the msg_block vector accordingly.  It will also
-     * call process_msg_block() appropriately.  When it returns, it
-     * can be assumed that the message digest has been computed.
+     * Check to see if the current message block is too small to hold
+     * the initial padding bits and length.  If so, we will pad the
+     * block, process it, and then continue padding into a second block.
      */
-    fn pad_msg(st: &Sha1State) {
-        assert (vec::len((*st).msg_block) == msg_block_len);
-
-        /*
-         * Check to see if the current message block is too small to hold
-         * the initial padding bits and length.  If so, we will pad the
-         * block, process it, and then continue padding into a second block.
-         */
-        if (*st).msg_block_idx > 55u {
-            (*st).msg_block[(*st).msg_block_idx] = 0x80u8;
-            (*st).msg_block_idx += 1u;
-            while (*st).msg_block_idx < msg_block_len {
-                (*st).msg_block[(*st).msg_block_idx] = 0u8;
-                (*st).msg_block_idx += 1u;
-            }
-            process_msg_block(st);
-        } else {
-            (*st).msg_block[(*st).msg_block_idx] = 0x80u8;
-            (*st).msg_block_idx += 1u;
-        }
-        while (*st).msg_block_idx < 56u {
-            (*st).msg_block[(*st).msg_block_idx] = 0u8;
-            (*st).msg_block_idx += 1u;
+    if st.msg_block_idx > 55u {
+        st.msg_block[st.msg_block_idx] = 0x80u8;
+        st.msg_block_idx += 1u;
+        while st.msg_block_idx < msg_block_len {
+            st.msg_block[st.msg_block_idx] = 0u8;
+            st.msg_block_idx += 1u;
         }
-
-        // Store the message length as the last 8 octets
-        (*st).msg_block[56] = ((*st).len_high >> 24u32 & 0xFFu32) as u8;
-        (*st).msg_block[57] = ((*st).len_high >> 16u32 & 0xFFu32) as u8;
-        (*st).msg_block[58] = ((*st).len_high >> 8u32 & 0xFFu32) as u8;
-        (*st).msg_block[59] = ((*st).len_high & 0xFFu32) as u8;
-        (*st).msg_block[60] = ((*st).len_low >> 24u32 & 0xFFu32) as u8;
-        (*st).msg_block[61] = ((*st).len_low >> 16u32 & 0xFFu32) as u8;
-        (*st).msg_block[62] = ((*st).len_low >> 8u32 & 0xFFu32) as u8;
-        (*st).msg_block[63] = ((*st).len_low & 0xFFu32) as u8;
         process_msg_block(st);
+    } else {
+        st.msg_block[st.msg_block_idx] = 0x80u8;
+        st.msg_block_idx += 1u;
+    }
+    while st.msg_block_idx < 56u {
+        st.msg_block[st.msg_block_idx] = 0u8;
+        st.msg_block_idx += 1u;
     }
 
-    impl Sha1State: Sha1 {
-        fn reset() {
-            assert (vec::len(self.h) == digest_buf_len);
-            self.len_low = 0u32;
-            self.len_high = 0u32;
-            self.msg_block_idx = 0u;
-            self.h[0] = 0x67452301u32;
-            self.h[1] = 0xEFCDAB89u32;
-            self.h[2] = 0x98BADCFEu32;
-            self.h[3] = 0x10325476u32;
-            self.h[4] = 0xC3D2E1F0u32;
-            self.computed = false;
-        }
-        fn input(msg: &[const u8]) { add_input(&self, msg); }
-        fn input_str(msg: &str) {
-            let bs = str::to_bytes(msg);
-            add_input(&self, bs);
-        }
-        fn result() -> ~[u8] { return mk_result(&self); }
-        fn result_str() -> ~str {
-            let rr = mk_result(&self);
-            let mut s = ~"";
-            for vec::each(rr) |b| {
-                s += uint::to_str_radix(*b as uint, 16u);
-            }
-            return s;
+    // Store the message length in bits as the last 8 octets, big-endian
+    let bits = st.len << 3u64;
+    for uint::range(0u, 8u) |i| {
+        st.msg_block[56u + i] = (bits >> (56u - i * 8u) as u64) as u8;
+    }
+    process_msg_block(st);
+}
+
+impl Sha1: Digest {
+    fn input(&mut self, msg: &[const u8]) { add_input(self, msg); }
+
+    fn result(&mut self, out: &mut [u8]) {
+        assert out.len() >= digest_buf_len * 4u;
+        if !self.computed { pad_msg(self); self.computed = true; }
+        for uint::range(0u, digest_buf_len) |i| {
+            let hpart = self.h[i];
+            out[i * 4u] = (hpart >> 24u32 & 0xFFu32) as u8;
+            out[i * 4u + 1u] = (hpart >> 16u32 & 0xFFu32) as u8;
+            out[i * 4u + 2u] = (hpart >> 8u32 & 0xFFu32) as u8;
+            out[i * 4u + 3u] = (hpart & 0xFFu32) as u8;
         }
     }
-    let st = Sha1State {
-        h: vec::cast_to_mut(vec::from_elem(digest_buf_len, 0u32)),
-        mut len_low: 0u32,
-        mut len_high: 0u32,
-        msg_block: vec::cast_to_mut(vec::from_elem(msg_block_len, 0u8)),
-        mut msg_block_idx: 0u,
-        mut computed: false,
-        work_buf: @vec::cast_to_mut(vec::from_elem(work_buf_len, 0u32))
-    };
-    let sh = (move st) as Sha1;
-    sh.reset();
-    return sh;
+
+    fn reset(&mut self) {
+        assert (vec::len(self.h) == digest_buf_len);
+        self.len = 0u64;
+        self.msg_block_idx = 0u;
+        self.h[0] = 0x67452301u32;
+        self.h[1] = 0xEFCDAB89u32;
+        self.h[2] = 0x98BADCFEu32;
+        self.h[3] = 0x10325476u32;
+        self.h[4] = 0xC3D2E1F0u32;
+        self.computed = false;
+    }
+
+    fn output_bits(&self) -> uint { 160u }
 }
 
 #[cfg(test)]
 mod tests {
-    use sha1;
+    use core::prelude::*;
+
+    use digest::{Digest, DigestUtil, DigestWriter, digest_to_str};
+    use sha1::Sha1;
 
+    use core::io::WriterUtil;
+    use core::rand;
     use core::str;
+    use core::uint;
     use core::vec;
 
     #[test]
@@ -368,10 +339,10 @@ mod tests {
             }
             // Test that it works when accepting the message all at once
 
-            let sh = sha1::sha1();
+            let mut sh = Sha1::new();
             for vec::each(tests) |t| {
                 sh.input_str(t.input);
-                let out = sh.result();
+                let out = sh.result_bytes();
                 check_vec_eq(t.output, out);
                 sh.reset();
             }
@@ -387,12 +358,72 @@ mod tests {
                                  take + len - left));
                     left = left - take;
                 }
-                let out = sh.result();
+                let out = sh.result_bytes();
                 check_vec_eq(t.output, out);
                 sh.reset();
             }
         }
     }
+
+    #[test]
+    pub fn test_empty_and_hex() {
+        let mut sh = Sha1::new();
+        assert sh.output_bits() == 160u;
+        assert sh.result_str() == ~"da39a3ee5e6b4b0d3255bfef95601890afd80709";
+        // asking again gives the same answer
+        assert digest_to_str(&mut sh) ==
+            ~"da39a3ee5e6b4b0d3255bfef95601890afd80709";
+
+        // the leading zero of each byte is kept
+        sh.reset();
+        sh.input_str("abc");
+        assert sh.result_str() == ~"a9993e364706816aba3e25717850c26c9cd0d89d";
+
+        // result only fills the start of a larger buffer
+        sh.reset();
+        let mut out = vec::from_elem(24u, 0xFFu8);
+        sh.result(out);
+        assert vec::slice(out, 0u, 2u) == ~[0xDAu8, 0x39u8];
+        assert vec::slice(out, 20u, 24u) == ~[0xFFu8, 0xFFu8, 0xFFu8, 0xFFu8];
+    }
+
+    #[test]
+    pub fn test_streaming_matches_oneshot() {
+        let rng = rand::Rng();
+        for uint::range(0u, 200u) |_| {
+            // lengths around the block and padding boundaries
+            let len = rng.gen_uint_range(0u, 300u);
+            let msg = rng.gen_bytes(len);
+
+            let mut oneshot = Sha1::new();
+            oneshot.input(msg);
+            let expected = oneshot.result_bytes();
+
+            // feed the same bytes in random, unaligned pieces
+            let mut streamed = Sha1::new();
+            let mut pos = 0u;
+            while pos < len {
+                let take = rng.gen_uint_range(1u, 70u);
+                let end = uint::min(len, pos + take);
+                streamed.input(vec::view(msg, pos, end));
+                pos = end;
+            }
+            assert streamed.result_bytes() == expected;
+
+            let wr = DigestWriter(Sha1::new());
+            wr.write(msg);
+            assert wr.tell() == len;
+            assert wr.result_bytes() == expected;
+        }
+    }
+
+    #[test]
+    pub fn test_digest_writer() {
+        let wr = DigestWriter(Sha1::new());
+        wr.write_str("The quick brown fox ");
+        wr.write_str("jumps over the lazy dog");
+        assert wr.result_str() == ~"2fd4e1c67a2d28fced849ee1bb76e7391b93eb12";
+    }
 }
 
 // Local Variables:
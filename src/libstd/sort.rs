@@ -13,6 +13,7 @@
 use core::cmp::{Eq, Ord};
 use core::dvec::DVec;
 use core::prelude::*;
+use core::ptr;
 use core::util;
 use core::vec::{len, push};
 use core::vec;
@@ -173,6 +174,107 @@ impl<T: Copy Ord Eq> &mut [T] : Sort {
     fn qsort(self) { quick_sort3(self); }
 }
 
+/**
+ * Stable sort. Sorts a mut vector in place.
+ *
+ * Elements are only ever moved, never copied, so this works for vectors
+ * of owned values such as `~[~str]`. Has worst case O(n log n)
+ * performance, best case O(n) on input that is already sorted, and
+ * allocates scratch space for at most n/2 elements.
+ */
+pub fn stable_sort<T: Ord>(v: &mut [T]) {
+    sort_by(v, |a, b| *a <= *b)
+}
+
+/**
+ * Stable sort with a custom ordering. Sorts a mut vector in place.
+ *
+ * `le` should return true if its first argument sorts before, or equal to,
+ * its second. Elements that compare equal keep their original order. See
+ * `stable_sort`.
+ */
+pub fn sort_by<T>(v: &mut [T], le: fn(&T, &T) -> bool) {
+    let len = v.len();
+    if len < 2 { return; }
+    // The scratch vector's length stays at zero, so dropping it only frees
+    // the buffer; the elements passing through it are owned by `v`.
+    let scratch: ~[T] = vec::with_capacity(len / 2);
+    unsafe {
+        let buf = vec::raw::to_ptr(scratch) as *mut T;
+        stable_sort_range(v, 0, len, buf, le);
+    }
+}
+
+const STABLE_SORT_INSERTION_MAX: uint = 8;
+
+unsafe fn stable_sort_range<T>(v: &mut [T], lo: uint, hi: uint,
+                               buf: *mut T, le: fn(&T, &T) -> bool) {
+    if hi - lo <= STABLE_SORT_INSERTION_MAX {
+        let mut i = lo + 1;
+        while i < hi {
+            let mut j = i;
+            while j > lo && !le(&v[j - 1], &v[j]) {
+                v[j - 1] <-> v[j];
+                j -= 1;
+            }
+            i += 1;
+        }
+        return;
+    }
+
+    let mid = lo + (hi - lo) / 2;
+    stable_sort_range(v, lo, mid, buf, le);
+    stable_sort_range(v, mid, hi, buf, le);
+    // Already in order, as happens throughout sorted input
+    if le(&v[mid - 1], &v[mid]) { return; }
+    merge_lo(v, lo, mid, hi, buf, le);
+}
+
+/*
+ * The part of a merge that has been moved out of `v`: `remaining` elements
+ * of the left run starting at `src`, and the hole of the same size at
+ * `dest` that they belong in. Whether the merge finishes or `le` fails,
+ * dropping this moves them back, so every element ends up in `v` exactly
+ * once.
+ */
+struct MergeHole<T> {
+    mut src: *T,
+    mut remaining: uint,
+    mut dest: *mut T,
+    drop {
+        unsafe {
+            ptr::copy_memory(self.dest, self.src, self.remaining);
+        }
+    }
+}
+
+unsafe fn merge_lo<T>(v: &mut [T], lo: uint, mid: uint, hi: uint,
+                      buf: *mut T, le: fn(&T, &T) -> bool) {
+    let base = ptr::to_mut_unsafe_ptr(&mut v[0]);
+    let left_len = mid - lo;
+    ptr::copy_memory(buf, ptr::mut_offset(base, lo) as *T, left_len);
+
+    let hole = MergeHole {
+        src: buf as *T,
+        remaining: left_len,
+        dest: ptr::mut_offset(base, lo)
+    };
+    let mut right = mid;
+    // Whatever is left of the right run is already in place
+    while hole.remaining > 0 && right < hi {
+        let r = ptr::mut_offset(base, right) as *T;
+        if le(&*hole.src, &*r) {
+            ptr::copy_memory(hole.dest, hole.src, 1);
+            hole.src = ptr::offset(hole.src, 1);
+            hole.remaining -= 1;
+        } else {
+            ptr::copy_memory(hole.dest, r, 1);
+            right += 1;
+        }
+        hole.dest = ptr::mut_offset(hole.dest, 1);
+    }
+}
+
 const MIN_MERGE: uint = 64;
 const MIN_GALLOP: uint = 7;
 const INITIAL_TMP_STORAGE: uint = 128;
@@ -895,6 +997,113 @@ mod tests {
     }
 }
 
+#[cfg(test)]
+mod test_stable_sort {
+    use core::prelude::*;
+
+    use sort::*;
+
+    use core::rand;
+    use core::task;
+    use core::uint;
+    use core::util::DropCount;
+    use core::vec;
+
+    fn check_stable(v: &[(uint, uint)]) {
+        for uint::range(1, v.len()) |i| {
+            let (k0, i0) = v[i - 1];
+            let (k1, i1) = v[i];
+            assert k0 < k1 || (k0 == k1 && i0 < i1);
+        }
+    }
+
+    fn key_le(a: &(uint, uint), b: &(uint, uint)) -> bool {
+        let (ka, _) = *a;
+        let (kb, _) = *b;
+        ka <= kb
+    }
+
+    #[test]
+    fn test_stable_sort_small() {
+        let mut v: ~[int] = ~[];
+        stable_sort(v);
+        assert v == ~[];
+
+        let mut v = ~[3, 7, 4, 5, 2, 9, 5, 8];
+        stable_sort(v);
+        assert v == ~[2, 3, 4, 5, 5, 7, 8, 9];
+
+        let mut v = ~[~"pear", ~"apple", ~"fig", ~"apple"];
+        stable_sort(v);
+        assert v == ~[~"apple", ~"apple", ~"fig", ~"pear"];
+    }
+
+    #[test]
+    fn test_sort_by_random_stable() {
+        let rng = rand::Rng();
+        for uint::range(0, 100) |_i| {
+            let len = rng.gen_uint_range(0, 300);
+            let keys = rng.gen_uint_range(1, 20);
+            let mut v = do vec::from_fn(len) |i| {
+                (rng.gen_uint_range(0, keys), i)
+            };
+            sort_by(v, key_le);
+            assert v.len() == len;
+            check_stable(v);
+        }
+    }
+
+    #[test]
+    fn test_sort_by_presorted() {
+        let n = 1000u;
+        let mut calls = 0u;
+        let mut v = vec::from_fn(n, |i| (i / 3, i));
+        do sort_by(v) |a, b| { calls += 1; key_le(a, b) }
+        check_stable(v);
+        // Sorted runs are recognised without merging
+        assert calls < 2 * n;
+
+        let mut v = vec::from_fn(n, |i| (n - i / 3, i));
+        sort_by(v, key_le);
+        check_stable(v);
+
+        let mut v = vec::from_fn(n, |i| Boxed { key: ~(n - i), tag: ~[i] });
+        do sort_by(v) |a, b| { a.key <= b.key }
+        for uint::range(0, n) |i| {
+            assert *v[i].key == i + 1;
+            assert v[i].tag == ~[n - i - 1];
+        }
+    }
+
+    struct Boxed {
+        key: ~uint,
+        tag: ~[uint],
+    }
+
+    #[test]
+    #[ignore(cfg(windows))]
+    fn test_sort_by_fail_midway() {
+        let count = DropCount();
+        let count2 = count.clone();
+        let n = 100u;
+        let r = do task::try |move count2| {
+            let rng = rand::Rng();
+            let mut v = do vec::from_fn(n) |_i| {
+                count2.counter(rng.gen_uint_range(0, 50))
+            };
+            let mut calls = 0;
+            do sort_by(v) |a, b| {
+                calls += 1;
+                if calls == 300 { die!(); }
+                a.val <= b.val
+            }
+        };
+        assert r.is_err();
+        // Nothing was lost or duplicated by the failed merge
+        assert count.get() == n;
+    }
+}
+
 #[cfg(test)]
 mod test_tim_sort {
     use core::prelude::*;
@@ -0,0 +1,55 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * Task operations that need the standard library's timers, which
+ * `core::task` cannot reach.
+ */
+
+use time::Timeout;
+use timer;
+use uv;
+
+use core::prelude::*;
+
+pub use timer::{TimeoutOrFailure, TimedOut, ChildFailed};
+
+/**
+ * Execute a function in a child task, giving up after a timeout
+ *
+ * This is `timer::try_timeout` on the global I/O loop; see there for how
+ * the child is treated when the timeout fires first.
+ */
+pub fn try_timeout<T: Owned>(timeout: Timeout, f: ~fn() -> T)
+                          -> Result<T, TimeoutOrFailure> {
+    timer::try_timeout(&uv::global_loop::get(), timeout, move f)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::prelude::*;
+
+    use task::*;
+    use time::{Infinite, Millis};
+
+    use core::pipes::stream;
+
+    #[test]
+    fn test_try_timeout() {
+        assert try_timeout(Infinite, || 7) == Ok(7);
+
+        let (never_po, _never_ch) = stream::<()>();
+        let r = do try_timeout(Millis(1)) |move never_po| {
+            // Returns once the test ends and the channel is dropped
+            let _ = never_po.try_recv();
+        };
+        assert r == Err(TimedOut);
+    }
+}
@@ -13,12 +13,31 @@
 use core::os;
 use core::prelude::*;
 use core::rand;
-use core::str;
 
-pub fn mkdtemp(tmpdir: &Path, suffix: &str) -> Option<Path> {
+/**
+ * Create a new directory with a unique name, readable only by the owner
+ *
+ * The name of the directory is the last component of `prefix` followed
+ * by random characters. A relative prefix is taken relative to the
+ * temporary directory (`TMPDIR` on unix), not the working directory.
+ * Returns None if no unused name turned up after a bounded number of
+ * attempts, or if the parent directory doesn't exist.
+ */
+pub fn mkdtemp(prefix: &Path) -> Option<Path> {
+    let prefix = if prefix.is_absolute {
+        copy *prefix
+    } else {
+        os::tmpdir().push_rel(prefix)
+    };
+    let (parent, name) = match prefix.filename() {
+        Some(move name) => (prefix.pop(), move name),
+        None => (copy prefix, ~"")
+    };
+    if !os::path_is_dir(&parent) { return None; }
+
     let r = rand::Rng();
     for 1000.times {
-        let p = tmpdir.push(r.gen_str(16) + suffix);
+        let p = parent.push(name + r.gen_str(16));
         if os::make_dir(&p, 0x1c0) { // 700
             return Some(p);
         }
@@ -26,9 +45,104 @@ pub fn mkdtemp(tmpdir: &Path, suffix: &str) -> Option<Path> {
     None
 }
 
-#[test]
-fn test_mkdtemp() {
-    let p = mkdtemp(&Path("."), "foobar").unwrap();
-    os::remove_dir(&p);
-    assert str::ends_with(p.to_str(), "foobar");
+/**
+ * A directory created by `mkdtemp` that is deleted, with everything in
+ * it, when the `TempDir` goes out of scope
+ *
+ * Failing to delete it is ignored, so that a `TempDir` is safe to drop
+ * while the task is unwinding.
+ */
+pub struct TempDir {
+    priv path: Path,
+}
+
+impl TempDir : Drop {
+    fn finalize(&self) {
+        os::remove_dir_recursive(&self.path);
+    }
+}
+
+/// Create a temporary directory as `mkdtemp` does
+pub fn TempDir(prefix: &Path) -> Option<TempDir> {
+    match mkdtemp(prefix) {
+        Some(move path) => Some(TempDir { path: move path }),
+        None => None
+    }
+}
+
+pub impl TempDir {
+    /// The path of the directory
+    pure fn path(&self) -> &self/Path { &self.path }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::prelude::*;
+
+    use tempfile::{TempDir, mkdtemp};
+
+    use core::io;
+    use core::os;
+    use core::str;
+
+    fn touch(p: &Path) {
+        io::file_writer(p, [io::Create, io::Truncate]).get();
+    }
+
+    #[test]
+    fn test_mkdtemp() {
+        let p = mkdtemp(&Path("foobar")).unwrap();
+        assert os::path_is_dir(&p);
+        assert p.dir_path() == os::tmpdir();
+        assert str::starts_with(p.filename().get(), "foobar");
+        assert p.filename().get().len() == 6 + 16;
+        assert os::remove_dir(&p);
+    }
+
+    #[test]
+    fn test_mkdtemp_absolute() {
+        let parent = mkdtemp(&Path("parent")).unwrap();
+        let p = mkdtemp(&parent.push("child")).unwrap();
+        assert p.dir_path() == parent;
+        assert os::remove_dir(&p);
+        assert os::remove_dir(&parent);
+    }
+
+    #[test]
+    fn test_mkdtemp_unique() {
+        let a = mkdtemp(&Path("unique")).unwrap();
+        let b = mkdtemp(&Path("unique")).unwrap();
+        assert a != b;
+        assert os::remove_dir(&a);
+        assert os::remove_dir(&b);
+    }
+
+    #[test]
+    fn test_mkdtemp_missing_parent() {
+        let parent = mkdtemp(&Path("missing")).unwrap();
+        assert os::remove_dir(&parent);
+        assert mkdtemp(&parent.push("child")).is_none();
+    }
+
+    #[test]
+    fn test_tempdir_removed() {
+        let path;
+        {
+            let tmp = TempDir(&Path("tempdir")).unwrap();
+            path = copy *tmp.path();
+            assert os::make_dir(&path.push("sub"), 0x1c0);
+            touch(&path.push("a.txt"));
+            touch(&path.push_many([~"sub", ~"b.txt"]));
+            assert os::path_is_dir(&path);
+            assert os::path_exists(&path.push_many([~"sub", ~"b.txt"]));
+        }
+        assert !os::path_exists(&path);
+    }
+
+    #[test]
+    fn test_tempdir_already_removed() {
+        // The destructor must not fail when there is nothing to remove
+        let tmp = TempDir(&Path("tempdir")).unwrap();
+        assert os::remove_dir(tmp.path());
+    }
 }
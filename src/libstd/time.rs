@@ -17,6 +17,7 @@ use core::io;
 use core::prelude::*;
 use core::result::{Result, Ok, Err};
 use core::str;
+use core::u64;
 
 const NSEC_PER_SEC: i32 = 1_000_000_000_i32;
 
@@ -71,6 +72,36 @@ impl Timespec : Ord {
     pure fn gt(&self, other: &Timespec) -> bool { !self.le(other) }
 }
 
+/*
+ * A Timespec also serves as a duration, which is normalized in the same
+ * way as a time before the epoch: ninety seconds from now is `now +
+ * Timespec::new(90, 0)`, and a quarter of a second earlier is `now +
+ * Timespec::new(-1, 750_000_000)`.
+ */
+impl Timespec : Add<Timespec, Timespec> {
+    pure fn add(&self, other: &Timespec) -> Timespec {
+        let mut sec = self.sec + other.sec;
+        let mut nsec = self.nsec + other.nsec;
+        if nsec >= NSEC_PER_SEC {
+            nsec -= NSEC_PER_SEC;
+            sec += 1;
+        }
+        Timespec::new(sec, nsec)
+    }
+}
+
+impl Timespec : Sub<Timespec, Timespec> {
+    pure fn sub(&self, other: &Timespec) -> Timespec {
+        let mut sec = self.sec - other.sec;
+        let mut nsec = self.nsec - other.nsec;
+        if nsec < 0 {
+            nsec += NSEC_PER_SEC;
+            sec -= 1;
+        }
+        Timespec::new(sec, nsec)
+    }
+}
+
 /**
  * Returns the current time as a `timespec` containing the seconds and
  * nanoseconds since 1970-01-01T00:00:00Z.
@@ -106,6 +137,115 @@ pub fn precise_time_s() -> float {
     return (precise_time_ns() as float) / 1000000000.;
 }
 
+/**
+ * How long a blocking operation may wait before giving up
+ *
+ * Taken by `timer::recv_timeout`, `timer::try_timeout`, `net_tcp::read`
+ * and `net_udp::recv_from_timeout`. `net_tcp::read_timeout`, which takes
+ * a `uint` of milliseconds, is kept as a shorthand for `read` with a
+ * `Millis` timeout.
+ *
+ * Not every blocking operation takes one. `std::sync` has no timed
+ * waits, and the blocking operations of `core`, such as `pipes::recv`,
+ * cannot name this type since `Timespec` lives here; to put a time limit
+ * on them, wrap them in `timer::recv_timeout` or `timer::try_timeout`.
+ * `timer::sleep`, `timer::delayed_send` and `timer::periodic` take a
+ * `uint` of milliseconds, as they always wait out the whole interval.
+ */
+#[deriving_eq]
+pub enum Timeout {
+    /// Wait for as long as it takes
+    Infinite,
+    /// Wait for at most this many milliseconds from the start of the call
+    Millis(u64),
+    /// Wait until this wall-clock time
+    Deadline(Timespec),
+}
+
+/// How much longer a started `Timeout` allows a caller to wait
+#[deriving_eq]
+pub enum Remaining {
+    /// The timeout is `Infinite`; block without a timer
+    WaitForever,
+    /// Wait for at most this many milliseconds, then check again
+    WaitFor(uint),
+    /// The time is up. Make one non-blocking attempt and report a timeout
+    Expired,
+}
+
+/*
+ * The longest wait handed to the underlying primitive in one go. libuv
+ * timers take a 32-bit count of milliseconds, so longer timeouts are
+ * served by waiting repeatedly, asking `remaining` again each time.
+ */
+const MAX_WAIT_MS: u64 = 0xffff_ffff_u64;
+const NSEC_PER_MSEC: u64 = 1_000_000_u64;
+
+/**
+ * A `Timeout` that has started counting down
+ *
+ * Blocking operations call `Timeout::start` once on entry and then ask
+ * `remaining` before every wait, so that a `Millis` timeout covers the
+ * whole call rather than each internal retry.
+ */
+pub struct Countdown {
+    priv timeout: Timeout,
+    priv start_ns: u64,
+}
+
+pub impl Timeout {
+    fn start(&self) -> Countdown {
+        Countdown { timeout: *self, start_ns: precise_time_ns() }
+    }
+}
+
+pub impl Countdown {
+    fn remaining(&self) -> Remaining {
+        self.remaining_at(precise_time_ns(), get_time())
+    }
+}
+
+priv impl Countdown {
+    fn remaining_at(&self, now_ns: u64, now: Timespec) -> Remaining {
+        match self.timeout {
+            Infinite => WaitForever,
+            Millis(ms) => {
+                let total_ns = if ms > u64::max_value / NSEC_PER_MSEC {
+                    u64::max_value
+                } else {
+                    ms * NSEC_PER_MSEC
+                };
+                let elapsed_ns = now_ns - self.start_ns;
+                if elapsed_ns >= total_ns {
+                    Expired
+                } else {
+                    wait_for_ns(total_ns - elapsed_ns)
+                }
+            }
+            Deadline(deadline) => {
+                if now >= deadline { return Expired; }
+                let secs = deadline.sec - now.sec;
+                if secs as u64 > MAX_WAIT_MS / 1000 {
+                    return WaitFor(MAX_WAIT_MS as uint);
+                }
+                let ns = secs * (NSEC_PER_SEC as i64) +
+                    ((deadline.nsec - now.nsec) as i64);
+                wait_for_ns(ns as u64)
+            }
+        }
+    }
+}
+
+// Rounds up, so that a wait never ends just short of the deadline and
+// leaves the caller spinning on a zero-length timer.
+fn wait_for_ns(ns: u64) -> Remaining {
+    if ns == 0 { return Expired; }
+    let mut ms = ns / NSEC_PER_MSEC;
+    if ns % NSEC_PER_MSEC != 0 { ms += 1; }
+    if ms > MAX_WAIT_MS { ms = MAX_WAIT_MS; }
+    WaitFor(ms as uint)
+}
+
 pub fn tzset() {
     unsafe {
         rustrt::rust_tzset();
@@ -209,16 +349,17 @@ pub pure fn strftime(format: &str, tm: &Tm) -> ~str {
 }
 
 impl Tm {
-    /// Convert time to the seconds from January 1, 1970
+    /**
+     * Convert time to the seconds from January 1, 1970. The fields are
+     * read as the wall-clock time at `tm_gmtoff` seconds east of UTC, so
+     * a time parsed with a `%z` offset converts correctly whatever the
+     * local timezone is.
+     */
     fn to_timespec() -> Timespec {
         unsafe {
             let mut sec = 0i64;
-            if self.tm_gmtoff == 0_i32 {
-                rustrt::rust_timegm(self, &mut sec);
-            } else {
-                rustrt::rust_mktime(self, &mut sec);
-            }
-            Timespec::new(sec, self.tm_nsec)
+            rustrt::rust_timegm(self, &mut sec);
+            Timespec::new(sec - self.tm_gmtoff as i64, self.tm_nsec)
         }
     }
 
@@ -291,7 +432,7 @@ priv fn do_strptime(s: &str, format: &str) -> Result<Tm, ~str> {
     fn match_str(s: &str, pos: uint, needle: &str) -> bool {
         let mut i = pos;
         for str::each(needle) |ch| {
-            if s[i] != ch {
+            if i >= str::len(s) || s[i] != ch {
                 return false;
             }
             i += 1u;
@@ -322,6 +463,7 @@ priv fn do_strptime(s: &str, format: &str) -> Result<Tm, ~str> {
 
         let mut i = 0u;
         while i < digits {
+            if pos >= str::len(ss) { return None; }
             let range = str::char_range_at(str::from_slice(ss), pos);
             pos = range.next;
 
@@ -349,6 +491,10 @@ priv fn do_strptime(s: &str, format: &str) -> Result<Tm, ~str> {
     }
 
     fn parse_char(s: &str, pos: uint, c: char) -> Result<uint, ~str> {
+        if pos >= str::len(s) {
+            return Err(fmt!("Expected %?, found the end of the string",
+                            str::from_char(c)));
+        }
         let range = str::char_range_at(s, pos);
 
         if c == range.ch {
@@ -458,6 +604,29 @@ priv fn do_strptime(s: &str, format: &str) -> Result<Tm, ~str> {
             Some(item) => { let (v, pos) = item; tm.tm_mday = v; Ok(pos) }
             None => Err(~"Invalid day of the month")
           },
+          'f' => {
+            // One to nine digits of a fraction of a second
+            let len = str::len(s);
+            let mut pos = pos;
+            let mut nsec = 0_i32;
+            let mut scale = NSEC_PER_SEC;
+            while pos < len && scale > 1_i32 {
+                match s[pos] as char {
+                  '0' .. '9' => {
+                    scale /= 10_i32;
+                    nsec += (s[pos] as i32 - '0' as i32) * scale;
+                    pos += 1u;
+                  }
+                  _ => break
+                }
+            }
+            if scale == NSEC_PER_SEC {
+                Err(~"Invalid number of nanoseconds")
+            } else {
+                tm.tm_nsec = nsec;
+                Ok(pos)
+            }
+          }
           'F' => {
             parse_type(s, pos, 'Y', &mut *tm)
                 .chain(|pos| parse_char(s, pos, '-'))
@@ -635,23 +804,37 @@ priv fn do_strptime(s: &str, format: &str) -> Result<Tm, ~str> {
             }
           }
           'z' => {
-            let range = str::char_range_at(s, pos);
-
-            if range.ch == '+' || range.ch == '-' {
-                match match_digits(s, range.next, 4u, false) {
-                  Some(item) => {
-                    let (v, pos) = item;
-                    if v == 0_i32 {
-                        tm.tm_gmtoff = 0_i32;
-                        tm.tm_zone = ~"UTC";
-                    }
+            if match_str(s, pos, "Z") {
+                tm.tm_gmtoff = 0_i32;
+                tm.tm_zone = ~"UTC";
+                return Ok(pos + 1u);
+            }
 
-                    Ok(pos)
-                  }
-                  None => Err(~"Invalid zone offset")
-                }
+            let sign = if match_str(s, pos, "+") {
+                1_i32
+            } else if match_str(s, pos, "-") {
+                -1_i32
             } else {
-                Err(~"Invalid zone offset")
+                return Err(~"Invalid zone offset");
+            };
+
+            // Both +0530 and the RFC 3339 form +05:30 are accepted
+            let (h, pos) = match match_digits_in_range(s, pos + 1u, 2u,
+                                                       false, 0_i32, 23_i32) {
+              Some(item) => item,
+              None => return Err(~"Invalid zone offset")
+            };
+            let pos = if match_str(s, pos, ":") { pos + 1u } else { pos };
+            match match_digits_in_range(s, pos, 2u, false, 0_i32, 59_i32) {
+              Some(item) => {
+                let (m, pos) = item;
+                tm.tm_gmtoff = sign * (h * 3600_i32 + m * 60_i32);
+                if tm.tm_gmtoff == 0_i32 {
+                    tm.tm_zone = ~"UTC";
+                }
+                Ok(pos)
+              }
+              None => Err(~"Invalid zone offset")
             }
           }
           '%' => parse_char(s, pos, '%'),
@@ -790,6 +973,7 @@ priv fn do_strftime(format: &str, tm: &Tm) -> ~str {
           }
           'd' => fmt!("%02d", tm.tm_mday as int),
           'e' => fmt!("%2d", tm.tm_mday as int),
+          'f' => fmt!("%09d", tm.tm_nsec as int),
           'F' => {
             fmt!("%s-%s-%s",
                 parse_type('Y', tm),
@@ -890,7 +1074,9 @@ mod tests {
     use time::*;
 
     use core::float;
+    use core::i64;
     use core::os;
+    use core::rand;
     use core::result;
     use core::str;
     use core::u64;
@@ -1163,7 +1349,11 @@ mod tests {
         assert result::unwrap(strptime(~"UTC", ~"%Z")).tm_zone == ~"UTC";
         assert result::unwrap(strptime(~"PST", ~"%Z")).tm_zone == ~"";
         assert result::unwrap(strptime(~"-0000", ~"%z")).tm_gmtoff == 0;
-        assert result::unwrap(strptime(~"-0800", ~"%z")).tm_gmtoff == 0;
+        assert result::unwrap(strptime(~"-0800", ~"%z")).tm_gmtoff ==
+            -28800;
+        assert test(~"+0530", ~"%z");
+        assert test(~"-0945", ~"%z");
+        assert test(~"000054321", ~"%f");
         assert test(~"%", ~"%%");
     }
 
@@ -1199,6 +1389,7 @@ mod tests {
         assert local.strftime(~"%D") == ~"02/13/09";
         assert local.strftime(~"%d") == ~"13";
         assert local.strftime(~"%e") == ~"13";
+        assert local.strftime(~"%f") == ~"000054321";
         assert local.strftime(~"%F") == ~"2009-02-13";
         // assert local.strftime("%G") == "2009";
         // assert local.strftime("%g") == "09";
@@ -1302,4 +1493,253 @@ mod tests {
         test_strftime();
         test_timespec_eq_ord();
     }
+
+    #[test]
+    fn test_timespec_add_sub() {
+        let t = Timespec::new(1234567890, 900_000_000);
+        assert t + Timespec::new(0, 0) == t;
+        assert t + Timespec::new(90, 0) == Timespec::new(1234567980,
+                                                         900_000_000);
+        assert t + Timespec::new(0, 200_000_000) ==
+            Timespec::new(1234567891, 100_000_000);
+        assert t + Timespec::new(-1, 750_000_000) ==
+            Timespec::new(1234567890, 650_000_000);
+        assert t - Timespec::new(0, 950_000_000) ==
+            Timespec::new(1234567889, 950_000_000);
+        assert t - t == Timespec::new(0, 0);
+
+        // Differences and times before the epoch are normalized alike
+        let epoch = Timespec::new(0, 0);
+        assert epoch - Timespec::new(1, 200_000_000) ==
+            Timespec::new(-2, 800_000_000);
+        assert Timespec::new(-2, 800_000_000) + Timespec::new(1, 200_000_000)
+            == epoch;
+
+        let a = Timespec::new(1000, 999_999_999);
+        let b = Timespec::new(-3000, 1);
+        assert (a - b) + b == a;
+        assert (b - a) + a == b;
+        assert a - b > epoch;
+        assert b - a < epoch;
+    }
+
+    #[test]
+    fn test_strptime_offsets() {
+        let parse = |s: &str| result::unwrap(strptime(s, "%F %T%z"));
+
+        let tm = parse("2012-02-29 05:30:00+0530");
+        assert tm.tm_gmtoff == 19800;
+        assert tm.tm_zone == ~"";
+        assert tm.to_timespec() == Timespec::new(1330473600, 0);
+
+        let tm = parse("2012-02-28 16:00:00-08:00");
+        assert tm.tm_gmtoff == -28800;
+        assert tm.to_timespec() == Timespec::new(1330473600, 0);
+
+        let tm = parse("2012-02-29 00:00:00Z");
+        assert tm.tm_gmtoff == 0;
+        assert tm.tm_zone == ~"UTC";
+        assert tm.to_timespec() == Timespec::new(1330473600, 0);
+
+        assert strptime("2012-02-29 00:00:00+05", "%F %T%z") ==
+            Err(~"Invalid zone offset");
+        assert strptime("2012-02-29 00:00:00+2400", "%F %T%z") ==
+            Err(~"Invalid zone offset");
+        assert strptime("2012-02-29 00:00:00 0530", "%F %T %z") ==
+            Err(~"Invalid zone offset");
+    }
+
+    #[test]
+    fn test_strptime_fraction() {
+        let parse = |s: &str| result::unwrap(strptime(s, "%S.%f"));
+        assert parse("01.5").tm_nsec == 500_000_000;
+        assert parse("01.000000001").tm_nsec == 1;
+        assert parse("01.123456").tm_nsec == 123_456_000;
+        assert strptime("01.x", "%S.%f") ==
+            Err(~"Invalid number of nanoseconds");
+        // Digits beyond nanoseconds are not part of the fraction
+        assert strptime("01.1234567891", "%S.%f") == Err(~"Invalid time");
+    }
+
+    #[test]
+    fn test_strptime_truncated() {
+        assert strptime("20", "%Y") == Err(~"Invalid year");
+        assert strptime("2009-02", "%Y-%m-%d") == Err(~"Invalid time");
+        assert strptime("2009-02-13 15", "%F %T") ==
+            Err(~"Expected ~\":\", found the end of the string");
+        assert strptime("+", "%z") == Err(~"Invalid zone offset");
+        assert strptime("Fe", "%b") == Err(~"Invalid month");
+    }
+
+    #[test]
+    fn test_leap_years() {
+        let parse = |s: &str| result::unwrap(strptime(s, "%F %T"));
+        assert parse("2012-02-29 00:00:00").to_timespec() ==
+            Timespec::new(1330473600, 0);
+        assert parse("2000-02-29 12:00:00").to_timespec() ==
+            Timespec::new(951825600, 0);
+
+        let day = Timespec::new(86400, 0);
+        let feb28 = parse("2000-02-28 00:00:00").to_timespec();
+        assert at_utc(feb28 + day).strftime("%F %j") == ~"2000-02-29 060";
+        let dec30 = parse("2012-12-30 00:00:00").to_timespec();
+        assert at_utc(dec30 + day).strftime("%F %j") == ~"2012-12-31 366";
+    }
+
+    // 1900 and 2100 fall outside a 32-bit time_t, and Windows' _mkgmtime
+    // rejects anything before 1970
+    #[test]
+    #[ignore(cfg(windows))]
+    #[ignore(cfg(target_arch = "x86"))]
+    #[ignore(cfg(target_arch = "arm"))]
+    fn test_century_leap_years() {
+        let parse = |s: &str| result::unwrap(strptime(s, "%F %T"));
+        let day = Timespec::new(86400, 0);
+        let feb28 = parse("1900-02-28 00:00:00").to_timespec();
+        assert feb28 == Timespec::new(-2203977600, 0);
+        assert at_utc(feb28 + day).strftime("%F %j") == ~"1900-03-01 060";
+        let feb28 = parse("2100-02-28 00:00:00").to_timespec();
+        assert at_utc(feb28 + day).strftime("%F %j") == ~"2100-03-01 060";
+    }
+
+    // The time `ts` as seen at `offset` seconds east of UTC
+    fn at_offset(ts: Timespec, offset: i32) -> Tm {
+        let mut tm = at_utc(ts + Timespec::new(offset as i64, 0));
+        tm.tm_gmtoff = offset;
+        if offset != 0 { tm.tm_zone = ~""; }
+        tm
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // Formats that identify an instant, and whether they keep the
+        // fraction of a second
+        let instants = [
+            ("%Y-%m-%dT%H:%M:%S.%f%z", true),
+            ("%a, %d %b %Y %H:%M:%S.%f %z", true),
+            ("%Y-%m-%d %H:%M:%S %z", false),
+            ("%A %e %B %Y %I:%M:%S %p %f %z", true),
+        ];
+        let others = ["%c", "%D %r", "%v %R %u %w", "%y-%m-%d %k:%M",
+                      "%Y %j %H%M%S %z"];
+
+        let rng = rand::seeded_rng([4, 7, 1, 9]);
+        for uint::range(0, 400) |_| {
+            // Any time from 1970 to 2037, which both a 32-bit time_t and
+            // Windows' _mkgmtime can hold, at any offset from -12:00 to
+            // +14:00 in steps of a quarter of an hour
+            let days = rng.gen_int_range(1, 24837) as i64;
+            let secs = rng.gen_int_range(0, 86400) as i64;
+            let nsec = rng.gen_int_range(0, NSEC_PER_SEC as int) as i32;
+            let ts = Timespec::new(days * 86400 + secs, nsec);
+            let whole = Timespec::new(ts.sec, 0);
+            let offset = rng.gen_int_range(-48, 57) as i32 * 900;
+            let tm = at_offset(ts, offset);
+
+            assert tm.to_timespec() == ts;
+
+            for instants.each |entry| {
+                let (format, exact) = *entry;
+                let s = tm.strftime(format);
+                let parsed = match strptime(s, format) {
+                    Ok(move tm) => move tm,
+                    Err(move e) => die!(fmt!("%s: %s", s, e))
+                };
+                assert parsed.strftime(format) == s;
+                assert parsed.to_timespec() == if exact { ts } else { whole };
+            }
+
+            for others.each |format| {
+                let s = tm.strftime(*format);
+                assert result::unwrap(strptime(s, *format)).strftime(*format)
+                    == s;
+            }
+
+            let s = tm.rfc822z();
+            let parsed = result::unwrap(strptime(s, "%a, %d %b %Y %T %z"));
+            assert parsed.rfc822z() == s;
+            assert parsed.to_timespec() == whole;
+
+            let s = tm.rfc3339();
+            let parsed = result::unwrap(strptime(s, "%Y-%m-%dT%H:%M:%S%z"));
+            assert parsed.rfc3339() == s;
+            assert parsed.to_timespec() == whole;
+
+            let utc = at_utc(ts);
+            let s = utc.rfc822();
+            let parsed = result::unwrap(strptime(s, "%a, %d %b %Y %T GMT"));
+            assert parsed.rfc822() == s;
+            assert parsed.to_timespec() == whole;
+        }
+    }
+
+    fn countdown(timeout: Timeout, start_ns: u64) -> Countdown {
+        Countdown { timeout: timeout, start_ns: start_ns }
+    }
+
+    #[test]
+    fn test_timeout_infinite() {
+        let now = Timespec::new(1234567890, 0);
+        assert countdown(Infinite, 0).remaining_at(u64::max_value, now) ==
+            WaitForever;
+        assert Infinite.start().remaining() == WaitForever;
+    }
+
+    #[test]
+    fn test_timeout_millis() {
+        let now = Timespec::new(0, 0);
+        let c = countdown(Millis(10), 1000);
+        assert c.remaining_at(1000, now) == WaitFor(10);
+        // Partial milliseconds round up rather than down to zero
+        assert c.remaining_at(1001, now) == WaitFor(10);
+        assert c.remaining_at(9_999_999, now) == WaitFor(1);
+        assert c.remaining_at(10_001_000, now) == Expired;
+        assert c.remaining_at(20_000_000, now) == Expired;
+
+        assert countdown(Millis(0), 0).remaining_at(0, now) == Expired;
+        assert Millis(0).start().remaining() == Expired;
+    }
+
+    #[test]
+    fn test_timeout_millis_u64_boundaries() {
+        let now = Timespec::new(0, 0);
+        let max_ms = u64::max_value;
+
+        // Converting to nanoseconds saturates instead of wrapping
+        let c = countdown(Millis(max_ms), 0);
+        assert c.remaining_at(0, now) == WaitFor(MAX_WAIT_MS as uint);
+        assert c.remaining_at(u64::max_value - 1, now) == WaitFor(1);
+        assert c.remaining_at(u64::max_value, now) == Expired;
+
+        let big = u64::max_value / NSEC_PER_MSEC;
+        let c = countdown(Millis(big), 0);
+        assert c.remaining_at(big * NSEC_PER_MSEC - 1, now) == WaitFor(1);
+        assert c.remaining_at(big * NSEC_PER_MSEC, now) == Expired;
+
+        // Single waits are capped; the caller asks again afterwards
+        let c = countdown(Millis(MAX_WAIT_MS + 1), 0);
+        assert c.remaining_at(0, now) == WaitFor(MAX_WAIT_MS as uint);
+        assert c.remaining_at(MAX_WAIT_MS * NSEC_PER_MSEC, now) ==
+            WaitFor(1);
+    }
+
+    #[test]
+    fn test_timeout_deadline() {
+        let c = countdown(Deadline(Timespec::new(100, 500_000_000)), 0);
+        assert c.remaining_at(0, Timespec::new(100, 0)) == WaitFor(500);
+        assert c.remaining_at(0, Timespec::new(99, 900_000_000)) ==
+            WaitFor(600);
+        assert c.remaining_at(0, Timespec::new(100, 499_999_999)) ==
+            WaitFor(1);
+        assert c.remaining_at(0, Timespec::new(100, 500_000_000)) ==
+            Expired;
+        assert c.remaining_at(0, Timespec::new(101, 0)) == Expired;
+
+        let far = countdown(Deadline(Timespec::new(i64::max_value, 0)), 0);
+        assert far.remaining_at(0, Timespec::new(0, 0)) ==
+            WaitFor(MAX_WAIT_MS as uint);
+
+        let past = Deadline(Timespec::new(0, 0));
+        assert past.start().remaining() == Expired;
+    }
 }
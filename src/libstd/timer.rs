@@ -10,19 +10,31 @@
 
 //! Utilities that leverage libuv's `uv_timer_*` API
 
+use time::{Timeout, Infinite, WaitForever, WaitFor, Expired};
 use uv;
 use uv::iotask;
 use uv::iotask::IoTask;
 
-use core::either;
 use core::libc;
 use core::libc::c_void;
 use core::cast::transmute;
-use core::pipes::{stream, Chan, SharedChan, Port, select2i};
+use core::option;
+use core::pipes::{stream, Chan, SharedChan, Port, Peekable, select2i};
 use core::prelude::*;
+use core::private::{Exclusive, exclusive};
 use core::ptr;
+use core::task;
 use core;
 
+/// The reason a call to `try_timeout` did not produce a value
+#[deriving_eq]
+pub enum TimeoutOrFailure {
+    /// The child task did not finish within the allotted time
+    TimedOut,
+    /// The child task failed before producing a value
+    ChildFailed,
+}
+
 /**
  * Wait for timeout period then send provided value over a channel
  *
@@ -109,12 +121,13 @@ pub fn sleep(iotask: &IoTask, msecs: uint) {
  * This call will block to receive on the provided port for up to the
  * specified timeout. Depending on whether the provided port receives in that
  * time period, `recv_timeout` will return an `Option<T>` representing the
- * result.
+ * result. A timeout that has already expired still checks the port once
+ * without blocking.
  *
  * # Arguments
  *
  * * `iotask' - `uv::iotask` that the tcp request will run on
- * * msecs - an mount of time, in milliseconds, to wait to receive
+ * * timeout - how long to wait to receive
  * * wait_port - a `core::comm::port<T>` to receive on
  *
  * # Returns
@@ -124,19 +137,239 @@ pub fn sleep(iotask: &IoTask, msecs: uint) {
  * be a `Some(T)`. If not, then `None` will be returned.
  */
 pub fn recv_timeout<T: Copy Owned>(iotask: &IoTask,
-                                   msecs: uint,
+                                   timeout: Timeout,
                                    wait_po: &Port<T>)
                                 -> Option<T> {
-    let (timeout_po, timeout_ch) = stream::<()>();
-    delayed_send(iotask, msecs, &timeout_ch, ());
-    // FIXME: This could be written clearer (#2618)
-    either::either(
-        |_| {
-            None
-        }, |_| {
-            Some(wait_po.recv())
-        }, &select2i(&timeout_po, wait_po)
-    )
+    let countdown = timeout.start();
+    loop {
+        match countdown.remaining() {
+            WaitForever => return Some(wait_po.recv()),
+            Expired => return if wait_po.peek() {
+                Some(wait_po.recv())
+            } else {
+                None
+            },
+            WaitFor(msecs) => {
+                let (timeout_po, timeout_ch) = stream::<()>();
+                delayed_send(iotask, msecs, &timeout_ch, ());
+                match select2i(&timeout_po, wait_po) {
+                    // Go around again; the countdown decides whether
+                    // that was the last wait or just one of several.
+                    Left(()) => (),
+                    Right(()) => return Some(wait_po.recv())
+                }
+            }
+        }
+    }
+}
+
+/**
+ * Execute a function in a child task, giving up after a timeout
+ *
+ * This is `task::try` with a deadline. If the child produces a value
+ * within the timeout then the value is returned; if it fails first then
+ * `ChildFailed` is returned, and if the timer fires first then `TimedOut`
+ * is returned. A timeout that has already expired returns `TimedOut`
+ * without running `f` at all.
+ *
+ * On timeout the child is *not* killed. It is spawned unlinked, so it is
+ * left running, detached from the caller, and whatever it eventually
+ * produces is discarded. Callers that need the work to stop must arrange
+ * for that themselves.
+ *
+ * If the child finishes at the same moment the timer fires, the child's
+ * value is always preferred over the timeout.
+ *
+ * # Arguments
+ *
+ * * `iotask` - a `uv::iotask` that the timer will run on
+ * * timeout - how long to wait for the child
+ * * f - the function to run in the child task
+ */
+pub fn try_timeout<T: Owned>(iotask: &IoTask,
+                             timeout: Timeout,
+                             f: ~fn() -> T)
+                          -> Result<T, TimeoutOrFailure> {
+    let countdown = timeout.start();
+    if countdown.remaining() == Expired { return Err(TimedOut); }
+
+    let (result_po, result_ch) = stream::<T>();
+    let mut notify_po = None;
+
+    let fr_task_builder = task::task().unlinked().future_result(|+r| {
+        notify_po = Some(move r);
+    });
+    do fr_task_builder.spawn |move f, move result_ch| {
+        result_ch.send(f());
+    }
+    let notify_po = option::unwrap(move notify_po);
+
+    if timeout != Infinite {
+        // The timer runs in its own task since sleep blocks until it
+        // fires. Once we've returned nobody is listening, which is fine: a
+        // send on a closed stream is silently dropped.
+        let (timeout_po, timeout_ch) = stream::<()>();
+        let iotask = iotask.clone();
+        do task::spawn_unlinked |move timeout_ch, move iotask| {
+            loop {
+                match countdown.remaining() {
+                    WaitFor(msecs) => sleep(&iotask, msecs),
+                    _ => { timeout_ch.send(()); break }
+                }
+            }
+        }
+
+        match select2i(&timeout_po, &notify_po) {
+            // The child sends its value before it exits, so a value that
+            // raced in alongside the timer is already waiting for us.
+            Left(()) => return if result_po.peek() {
+                Ok(result_po.recv())
+            } else {
+                Err(TimedOut)
+            },
+            Right(()) => ()
+        }
+    }
+
+    match notify_po.recv() {
+        task::Success => Ok(result_po.recv()),
+        task::Failure => Err(ChildFailed)
+    }
+}
+
+/**
+ * A timer that ticks repeatedly, created by `periodic`
+ *
+ * Receive the ticks with `recv`, `try_recv` and `peek`, as from a port.
+ * Ticks are coalesced: while one tick is waiting to be received the timer
+ * sends no more, so a receiver that falls behind finds a single tick
+ * however many intervals it missed, rather than a growing backlog.
+ *
+ * The timer runs until `cancel` is called or the `Ticker` is dropped.
+ */
+pub struct Ticker {
+    priv port: Port<()>,
+    priv pending: Exclusive<bool>,
+    priv iotask: IoTask,
+    priv timer: ~uv::ll::uv_timer_t,
+    priv mut cancelled: bool,
+    // True once the port has reported that no more ticks can come
+    priv mut closed: bool,
+}
+
+/**
+ * Start a timer that ticks every `interval_ms` milliseconds
+ *
+ * The first tick comes one interval after the call. The libuv timer
+ * re-arms itself, so the ticks do not drift by however long the receiver
+ * takes to handle each one.
+ *
+ * # Arguments
+ *
+ * * `iotask` - a `uv::iotask` that the timer will run on
+ * * interval_ms - the time between ticks, in milliseconds; must not be 0
+ */
+pub fn periodic(iotask: &IoTask, interval_ms: uint) -> Ticker {
+    assert interval_ms > 0;
+    unsafe {
+        let (port, chan) = stream::<()>();
+        let pending = exclusive(false);
+        let data = ~TickerData { chan: move chan, pending: pending.clone() };
+        let data_ptr = transmute::<~TickerData, *c_void>(move data);
+        let timer = ~uv::ll::timer_t();
+        let timer_ptr = ptr::addr_of(&*timer);
+        do iotask::interact(iotask) |loop_ptr| {
+            unsafe {
+                if uv::ll::timer_init(loop_ptr, timer_ptr) != 0i32 {
+                    let error_msg = uv::ll::get_last_err_info(loop_ptr);
+                    die!(~"timer::periodic() init failed: " + error_msg);
+                }
+                uv::ll::set_data_for_uv_handle(timer_ptr, data_ptr);
+                if uv::ll::timer_start(timer_ptr, periodic_cb, interval_ms,
+                                       interval_ms) != 0i32 {
+                    let error_msg = uv::ll::get_last_err_info(loop_ptr);
+                    die!(~"timer::periodic() start failed: " + error_msg);
+                }
+            }
+        };
+        Ticker {
+            port: move port,
+            pending: move pending,
+            iotask: iotask.clone(),
+            timer: move timer,
+            cancelled: false,
+            closed: false
+        }
+    }
+}
+
+pub impl Ticker {
+    /**
+     * Stop the timer and free its libuv resources
+     *
+     * No tick is sent once this returns, though one sent before may
+     * still be waiting to be received. Once that one is received,
+     * `try_recv` returns `None`, `peek` returns false and `recv` fails.
+     * Cancelling twice does nothing.
+     */
+    fn cancel(&self) {
+        if self.cancelled { return; }
+        self.cancelled = true;
+        unsafe {
+            let (done_po, done_ch) = stream::<()>();
+            let done_ch_ptr = transmute::<~SharedChan<()>, *c_void>(
+                ~SharedChan(move done_ch));
+            let timer_ptr = ptr::addr_of(&*self.timer);
+            do iotask::interact(&self.iotask) |_loop_ptr| {
+                unsafe {
+                    uv::ll::timer_stop(timer_ptr);
+                    // Drop the tick channel, and have the close callback
+                    // tell us when libuv is done with the handle
+                    let data_ptr = uv::ll::get_data_for_uv_handle(timer_ptr);
+                    let _data = transmute::<*c_void, ~TickerData>(data_ptr);
+                    uv::ll::set_data_for_uv_handle(timer_ptr, done_ch_ptr);
+                    uv::ll::close(timer_ptr, delayed_send_close_cb);
+                }
+            };
+            done_po.recv();
+        }
+    }
+}
+
+priv impl Ticker {
+    // Let the timer send the next tick
+    fn received(&self) {
+        unsafe { self.pending.with(|pending| *pending = false); }
+    }
+}
+
+impl Ticker: GenericPort<()> {
+    fn recv() {
+        match self.try_recv() {
+            Some(()) => (),
+            None => die!(~"timer::Ticker::recv() on a cancelled timer")
+        }
+    }
+
+    fn try_recv() -> Option<()> {
+        // The port gives up its endpoint when it finds the timer gone, so
+        // it must not be asked again
+        if self.closed { return None; }
+        let tick = self.port.try_recv();
+        match tick {
+            Some(()) => self.received(),
+            None => self.closed = true
+        }
+        tick
+    }
+}
+
+impl Ticker: Peekable<()> {
+    pure fn peek() -> bool { !self.closed && self.port.peek() }
+}
+
+impl Ticker: Drop {
+    fn finalize(&self) { self.cancel(); }
 }
 
 // INTERNAL API
@@ -162,6 +395,28 @@ extern fn delayed_send_cb(handle: *uv::ll::uv_timer_t,
     }
 }
 
+struct TickerData {
+    chan: Chan<()>,
+    // True while a tick is waiting to be received
+    pending: Exclusive<bool>,
+}
+
+extern fn periodic_cb(handle: *uv::ll::uv_timer_t, status: libc::c_int) {
+    unsafe {
+        log(debug, fmt!("periodic_cb handle %? status %?", handle, status));
+        // Faking a borrowed pointer to our ~TickerData
+        let data_ptr: &*c_void = &uv::ll::get_data_for_uv_handle(handle);
+        let data = transmute::<&*c_void, &~TickerData>(data_ptr);
+        // The lock is only held for the check; sending may reschedule
+        let send = do data.pending.with |pending| {
+            let send = !*pending;
+            *pending = true;
+            send
+        };
+        if send { data.chan.send(()); }
+    }
+}
+
 extern fn delayed_send_close_cb(handle: *uv::ll::uv_timer_t) {
     unsafe {
         log(debug, fmt!("delayed_send_close_cb handle %?", handle));
@@ -176,13 +431,15 @@ extern fn delayed_send_close_cb(handle: *uv::ll::uv_timer_t) {
 mod test {
     use core::prelude::*;
 
+    use time::{Infinite, Millis, Deadline, Timespec, get_time,
+               precise_time_ns};
     use timer::*;
     use uv;
 
     use core::iter;
     use core::rand;
     use core::task;
-    use core::pipes::{stream, SharedChan};
+    use core::pipes::{stream, Peekable, SharedChan};
 
     #[test]
     pub fn test_gl_timer_simple_sleep_test() {
@@ -259,7 +516,7 @@ mod test {
                 delayed_send(hl_loop, 1u, &test_ch, expected);
             };
 
-            match recv_timeout(hl_loop, 10u, &test_po) {
+            match recv_timeout(hl_loop, Millis(10), &test_po) {
               Some(val) => {
                 assert val == expected;
                 successes += 1;
@@ -286,7 +543,7 @@ mod test {
                 delayed_send(&hl_loop_clone, 50u, &test_ch, expected);
             };
 
-            match recv_timeout(&hl_loop, 1u, &test_po) {
+            match recv_timeout(&hl_loop, Millis(1), &test_po) {
               None => successes += 1,
               _ => failures += 1
             };
@@ -294,4 +551,145 @@ mod test {
 
         assert successes > times / 2;
     }
+
+    #[test]
+    pub fn test_gl_timer_try_timeout_success() {
+        let hl_loop = &uv::global_loop::get();
+        match try_timeout(hl_loop, Millis(1000), || ~"done") {
+            Ok(move val) => assert val == ~"done",
+            Err(_) => die!()
+        }
+    }
+
+    #[test]
+    pub fn test_gl_timer_try_timeout_timed_out() {
+        let hl_loop = &uv::global_loop::get();
+        let (never_po, _never_ch) = stream::<()>();
+        let hl_loop_clone = hl_loop.clone();
+        let r = do try_timeout(hl_loop, Millis(1)) |move never_po,
+                                                    move hl_loop_clone| {
+            // Wait well past the deadline, then give up quietly
+            let _ = recv_timeout(&hl_loop_clone, Millis(500), &never_po);
+        };
+        assert r == Err(TimedOut);
+    }
+
+    #[test]
+    #[ignore(cfg(windows))]
+    pub fn test_gl_timer_try_timeout_child_failed() {
+        let hl_loop = &uv::global_loop::get();
+        let r: Result<(), TimeoutOrFailure> =
+            do try_timeout(hl_loop, Millis(1000)) { die!() };
+        assert r == Err(ChildFailed);
+    }
+
+    #[test]
+    pub fn test_gl_timer_recv_timeout_infinite() {
+        let hl_loop = &uv::global_loop::get();
+        let (po, ch) = stream::<int>();
+        let hl_loop_clone = hl_loop.clone();
+        do task::spawn |move ch, move hl_loop_clone| {
+            sleep(&hl_loop_clone, 20u);
+            ch.send(10);
+        }
+        assert recv_timeout(hl_loop, Infinite, &po) == Some(10);
+    }
+
+    #[test]
+    pub fn test_gl_timer_recv_timeout_expired_is_a_try_recv() {
+        let hl_loop = &uv::global_loop::get();
+        let (po, ch) = stream::<int>();
+        assert recv_timeout(hl_loop, Millis(0), &po) == None;
+        ch.send(10);
+        assert recv_timeout(hl_loop, Millis(0), &po) == Some(10);
+
+        let past = Deadline(Timespec::new(0, 0));
+        ch.send(20);
+        assert recv_timeout(hl_loop, past, &po) == Some(20);
+        assert recv_timeout(hl_loop, past, &po) == None;
+    }
+
+    #[test]
+    pub fn test_gl_timer_recv_timeout_deadline() {
+        let hl_loop = &uv::global_loop::get();
+        let (po, _ch) = stream::<int>();
+
+        // The deadline is shared by every wait the call makes, so the
+        // whole call takes about 50ms rather than 50ms per wait.
+        let now = get_time();
+        let deadline = if now.nsec < 950_000_000 {
+            Timespec::new(now.sec, now.nsec + 50_000_000)
+        } else {
+            Timespec::new(now.sec + 1, now.nsec - 950_000_000)
+        };
+        let start = precise_time_ns();
+        assert recv_timeout(hl_loop, Deadline(deadline), &po) == None;
+        let elapsed_ms = (precise_time_ns() - start) / 1_000_000;
+        assert get_time() >= deadline;
+        assert elapsed_ms < 1000;
+    }
+
+    #[test]
+    pub fn test_gl_timer_try_timeout_expired() {
+        let hl_loop = &uv::global_loop::get();
+        let r: Result<(), TimeoutOrFailure> =
+            do try_timeout(hl_loop, Millis(0)) { die!() };
+        assert r == Err(TimedOut);
+    }
+
+    #[test]
+    pub fn test_gl_timer_try_timeout_infinite() {
+        let hl_loop = &uv::global_loop::get();
+        let hl_loop_clone = hl_loop.clone();
+        let r = do try_timeout(hl_loop, Infinite) |move hl_loop_clone| {
+            sleep(&hl_loop_clone, 20u);
+            ~"done"
+        };
+        assert r == Ok(~"done");
+    }
+
+    #[test]
+    pub fn test_gl_timer_periodic() {
+        let hl_loop = &uv::global_loop::get();
+        let start = precise_time_ns();
+        let ticker = periodic(hl_loop, 20u);
+        for iter::repeat(5) {
+            ticker.recv();
+        }
+        let elapsed_ms = (precise_time_ns() - start) / 1_000_000;
+        // libuv may fire a little early since it caches the loop time
+        assert elapsed_ms >= 90;
+        assert elapsed_ms < 2000;
+
+        ticker.cancel();
+        sleep(hl_loop, 100u);
+        // At most the one tick that was already waiting
+        if ticker.try_recv().is_some() {
+            assert ticker.try_recv().is_none();
+        }
+        assert !ticker.peek();
+        assert ticker.try_recv().is_none();
+        ticker.cancel();
+    }
+
+    #[test]
+    pub fn test_gl_timer_periodic_coalesces() {
+        let hl_loop = &uv::global_loop::get();
+        let ticker = periodic(hl_loop, 5u);
+        // Twenty or so intervals go by without a receive
+        sleep(hl_loop, 100u);
+        assert ticker.peek();
+        ticker.cancel();
+        assert ticker.try_recv().is_some();
+        assert ticker.try_recv().is_none();
+    }
+
+    #[test]
+    pub fn test_gl_timer_periodic_drop() {
+        let hl_loop = &uv::global_loop::get();
+        for iter::repeat(20) {
+            let ticker = periodic(hl_loop, 1u);
+            ticker.recv();
+        }
+    }
 }
@@ -190,6 +190,34 @@ impl <K: Ord, V> TreeMap<K, V> {
         self.each_reverse(|_, v| f(v))
     }
 
+    /// Visit the key-value pairs with keys greater than or equal to
+    /// `lower`, in order
+    pure fn each_key_from(&self, lower: &K, f: fn(&K, &V) -> bool) {
+        each_between(&self.root, Some(lower), None, f);
+    }
+
+    /// Visit the key-value pairs with keys greater than or equal to
+    /// `lower`, in reverse order
+    pure fn each_key_from_reverse(&self, lower: &K,
+                                  f: fn(&K, &V) -> bool) {
+        each_between_reverse(&self.root, Some(lower), None, f);
+    }
+
+    /// Visit the key-value pairs with keys in the range `[lower, upper)`,
+    /// in order. Only the parts of the tree overlapping the range are
+    /// walked, so this takes O(log n + k) time for k matching pairs.
+    pure fn each_between(&self, lower: &K, upper: &K,
+                         f: fn(&K, &V) -> bool) {
+        each_between(&self.root, Some(lower), Some(upper), f);
+    }
+
+    /// Visit the key-value pairs with keys in the range `[lower, upper)`,
+    /// in reverse order
+    pure fn each_between_reverse(&self, lower: &K, upper: &K,
+                                 f: fn(&K, &V) -> bool) {
+        each_between_reverse(&self.root, Some(lower), Some(upper), f);
+    }
+
     /// Get a lazy iterator over the key-value pairs in the map.
     /// Requires that it be frozen (immutable).
     pure fn iter(&self) -> TreeMapIterator/&self<K, V> {
@@ -565,6 +593,46 @@ pure fn each_reverse<K: Ord, V>(node: &Option<~TreeNode<K, V>>,
     };
 }
 
+// In-order traversal of the keys in [lower, upper), where a missing
+// bound is unlimited. Subtrees lying entirely outside of the range are
+// skipped. Returns false if `f` asked to stop.
+pure fn each_between<K: Ord, V>(node: &Option<~TreeNode<K, V>>,
+                                lower: Option<&K>, upper: Option<&K>,
+                                f: fn(&K, &V) -> bool) -> bool {
+    match *node {
+        None => true,
+        Some(ref x) => {
+            let above = match lower { Some(l) => *l <= x.key, None => true };
+            let below = match upper { Some(u) => x.key < *u, None => true };
+
+            if above && !each_between(&x.left, lower, upper, f) {
+                return false;
+            }
+            if above && below && !f(&x.key, &x.value) { return false; }
+            !below || each_between(&x.right, lower, upper, f)
+        }
+    }
+}
+
+pure fn each_between_reverse<K: Ord, V>(node: &Option<~TreeNode<K, V>>,
+                                        lower: Option<&K>,
+                                        upper: Option<&K>,
+                                        f: fn(&K, &V) -> bool) -> bool {
+    match *node {
+        None => true,
+        Some(ref x) => {
+            let above = match lower { Some(l) => *l <= x.key, None => true };
+            let below = match upper { Some(u) => x.key < *u, None => true };
+
+            if below && !each_between_reverse(&x.right, lower, upper, f) {
+                return false;
+            }
+            if above && below && !f(&x.key, &x.value) { return false; }
+            !above || each_between_reverse(&x.left, lower, upper, f)
+        }
+    }
+}
+
 // Remove left horizontal link by rotating right
 fn skew<K: Ord, V>(mut node: ~TreeNode<K, V>) -> ~TreeNode<K, V> {
     if node.left.map_default(false, |x| x.level == node.level) {
@@ -693,7 +761,9 @@ fn remove<K: Ord, V>(node: &mut Option<~TreeNode<K, V>>, key: &K) -> bool {
 #[cfg(test)]
 mod test_treemap {
     use super::*;
+    use core::int;
     use core::str;
+    use core::vec;
 
     #[test]
     fn find_empty() {
@@ -814,7 +884,7 @@ mod test_treemap {
         check_equal(ctrl, &map);
         assert map.find(&5).is_none();
 
-        let rng = rand::seeded_rng(&~[42]);
+        let rng = rand::seeded_rng([42]);
 
         for 3.times {
             for 90.times {
@@ -977,6 +1047,131 @@ mod test_treemap {
         map_next(&mut iter);
         assert iter.get().is_none();
     }
+
+    fn collect_range(m: &TreeMap<int, int>, lower: int, upper: int,
+                     reverse: bool) -> ~[int] {
+        let mut keys = ~[];
+        if reverse {
+            for m.each_between_reverse(&lower, &upper) |k, v| {
+                assert *v == *k * 2;
+                keys.push(*k);
+            }
+        } else {
+            for m.each_between(&lower, &upper) |k, v| {
+                assert *v == *k * 2;
+                keys.push(*k);
+            }
+        }
+        keys
+    }
+
+    #[test]
+    fn test_each_between() {
+        // The even numbers in [0, 100)
+        let mut m = TreeMap::new();
+        for int::range(0, 50) |i| {
+            assert m.insert(i * 2, i * 4);
+        }
+
+        // Bounds present as keys: lower included, upper excluded
+        assert collect_range(&m, 10, 16, false) == ~[10, 12, 14];
+        assert collect_range(&m, 10, 16, true) == ~[14, 12, 10];
+
+        // Bounds absent from the map
+        assert collect_range(&m, 9, 17, false) == ~[10, 12, 14, 16];
+        assert collect_range(&m, 9, 17, true) == ~[16, 14, 12, 10];
+
+        // Empty and out of range intervals
+        assert collect_range(&m, 10, 10, false).is_empty();
+        assert collect_range(&m, 11, 12, false).is_empty();
+        assert collect_range(&m, 20, 10, false).is_empty();
+        assert collect_range(&m, 200, 300, true).is_empty();
+        assert collect_range(&m, -10, 0, false).is_empty();
+
+        // Ranges covering the ends of the map
+        assert collect_range(&m, -10, 3, false) == ~[0, 2];
+        assert collect_range(&m, 95, 1000, true) == ~[98, 96];
+    }
+
+    #[test]
+    fn test_each_key_from() {
+        let mut m = TreeMap::new();
+        for int::range(0, 50) |i| {
+            assert m.insert(i * 2, i * 4);
+        }
+
+        let mut keys = ~[];
+        for m.each_key_from(&91) |k, _| { keys.push(*k); }
+        assert keys == ~[92, 94, 96, 98];
+
+        let mut keys = ~[];
+        for m.each_key_from_reverse(&92) |k, _| { keys.push(*k); }
+        assert keys == ~[98, 96, 94, 92];
+
+        let mut keys = ~[];
+        for m.each_key_from(&0) |k, _| {
+            keys.push(*k);
+            if *k == 4 { break; }
+        }
+        assert keys == ~[0, 2, 4];
+
+        let mut n = 0;
+        for m.each_key_from(&99) |_, _| { n += 1; }
+        assert n == 0;
+    }
+
+    // A key which counts how many times it has been compared
+    struct CountedKey {
+        val: int,
+        count: @mut uint,
+    }
+
+    impl CountedKey : Eq {
+        pure fn eq(&self, other: &CountedKey) -> bool {
+            unsafe { *self.count += 1; }
+            self.val == other.val
+        }
+        pure fn ne(&self, other: &CountedKey) -> bool { !self.eq(other) }
+    }
+
+    impl CountedKey : Ord {
+        pure fn lt(&self, other: &CountedKey) -> bool {
+            unsafe { *self.count += 1; }
+            self.val < other.val
+        }
+        pure fn le(&self, other: &CountedKey) -> bool { !other.lt(self) }
+        pure fn ge(&self, other: &CountedKey) -> bool { !self.lt(other) }
+        pure fn gt(&self, other: &CountedKey) -> bool { other.lt(self) }
+    }
+
+    #[test]
+    fn test_each_between_prunes() {
+        let count = @mut 0u;
+        let key = |val| CountedKey { val: val, count: count };
+
+        let n = 1 << 16;
+        let mut m = TreeMap::new();
+        for int::range(0, n) |i| {
+            m.insert(key(i), ());
+        }
+
+        // A full traversal of the tree would compare at least n keys
+        *count = 0;
+        let mut seen = ~[];
+        for m.each_between(&key(1000), &key(1010)) |k, _| {
+            seen.push(k.val);
+        }
+        assert seen == vec::from_fn(10, |i| 1000 + i as int);
+        assert *count < 500;
+
+        *count = 0;
+        let mut seen = ~[];
+        for m.each_key_from_reverse(&key(n - 5)) |k, _| {
+            seen.push(k.val);
+        }
+        assert seen == ~[n - 1, n - 2, n - 3, n - 4, n - 5];
+        assert *count < 500;
+    }
 }
 
 #[cfg(test)]
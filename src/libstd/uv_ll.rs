@@ -322,6 +322,12 @@ pub mod addrinfo_impl {
     }
 }
 
+// The layouts of the UDP handle and send request are left to libuv;
+// they are allocated with the size libuv reports and only ever used
+// through pointers
+pub type uv_udp_t = libc::c_void;
+pub type uv_udp_send_t = libc::c_void;
+
 // unix size: 72
 pub struct uv_getaddrinfo_t {
     a00: *u8, a01: *u8, a02: *u8, a03: *u8, a04: *u8, a05: *u8,
@@ -704,6 +710,39 @@ extern mod rustrt {
                                       ++name: *sockaddr_in) -> libc::c_int;
     unsafe fn rust_uv_tcp_getpeername6(tcp_handle_ptr: *uv_tcp_t,
                                        ++name: *sockaddr_in6) ->libc::c_int;
+    unsafe fn rust_uv_udp_init(loop_handle: *libc::c_void,
+                               handle_ptr: *uv_udp_t) -> libc::c_int;
+    // FIXME ref #2064
+    unsafe fn rust_uv_udp_bind(handle_ptr: *uv_udp_t,
+                               ++addr: *sockaddr_in,
+                               flags: libc::c_uint) -> libc::c_int;
+    // FIXME ref #2064
+    unsafe fn rust_uv_udp_bind6(handle_ptr: *uv_udp_t,
+                                ++addr: *sockaddr_in6,
+                                flags: libc::c_uint) -> libc::c_int;
+    // FIXME ref #2064
+    unsafe fn rust_uv_udp_send(req: *uv_udp_send_t,
+                               handle_ptr: *uv_udp_t,
+                               ++buf_in: *uv_buf_t,
+                               buf_cnt: libc::c_int,
+                               ++addr: *sockaddr_in,
+                               cb: *u8) -> libc::c_int;
+    // FIXME ref #2064
+    unsafe fn rust_uv_udp_send6(req: *uv_udp_send_t,
+                                handle_ptr: *uv_udp_t,
+                                ++buf_in: *uv_buf_t,
+                                buf_cnt: libc::c_int,
+                                ++addr: *sockaddr_in6,
+                                cb: *u8) -> libc::c_int;
+    unsafe fn rust_uv_udp_recv_start(handle_ptr: *uv_udp_t,
+                                     on_alloc: *u8,
+                                     on_recv: *u8) -> libc::c_int;
+    unsafe fn rust_uv_udp_recv_stop(handle_ptr: *uv_udp_t) -> libc::c_int;
+    unsafe fn rust_uv_tcp_nodelay(tcp_handle_ptr: *uv_tcp_t,
+                                  enable: libc::c_int) -> libc::c_int;
+    unsafe fn rust_uv_tcp_keepalive(tcp_handle_ptr: *uv_tcp_t,
+                                    enable: libc::c_int,
+                                    delay: libc::c_uint) -> libc::c_int;
     unsafe fn rust_uv_listen(stream: *libc::c_void,
                              backlog: libc::c_int,
                              cb: *u8) -> libc::c_int;
@@ -744,6 +783,7 @@ extern mod rustrt {
     unsafe fn rust_uv_helper_get_INADDR_NONE() -> u32;
     unsafe fn rust_uv_is_ipv4_addrinfo(input: *addrinfo) -> bool;
     unsafe fn rust_uv_is_ipv6_addrinfo(input: *addrinfo) -> bool;
+    unsafe fn rust_uv_is_ipv4_sockaddr(input: *libc::c_void) -> bool;
     unsafe fn rust_uv_get_next_addrinfo(input: *addrinfo) -> *addrinfo;
     unsafe fn rust_uv_addrinfo_as_sockaddr_in(input: *addrinfo)
                                            -> *sockaddr_in;
@@ -775,6 +815,8 @@ extern mod rustrt {
 
     // sizeof testing helpers
     unsafe fn rust_uv_helper_uv_tcp_t_size() -> libc::c_uint;
+    unsafe fn rust_uv_helper_uv_udp_t_size() -> libc::c_uint;
+    unsafe fn rust_uv_helper_uv_udp_send_t_size() -> libc::c_uint;
     unsafe fn rust_uv_helper_uv_connect_t_size() -> libc::c_uint;
     unsafe fn rust_uv_helper_uv_buf_t_size() -> libc::c_uint;
     unsafe fn rust_uv_helper_uv_write_t_size() -> libc::c_uint;
@@ -855,6 +897,79 @@ pub unsafe fn tcp_getpeername6(tcp_handle_ptr: *uv_tcp_t,
     return rustrt::rust_uv_tcp_getpeername6(tcp_handle_ptr, name);
 }
 
+pub unsafe fn udp_init(loop_ptr: *libc::c_void,
+                       handle_ptr: *uv_udp_t) -> libc::c_int {
+    return rustrt::rust_uv_udp_init(loop_ptr, handle_ptr);
+}
+
+// FIXME ref #2064
+pub unsafe fn udp_bind(handle_ptr: *uv_udp_t,
+                       addr_ptr: *sockaddr_in) -> libc::c_int {
+    return rustrt::rust_uv_udp_bind(handle_ptr, addr_ptr, 0u32);
+}
+
+// FIXME ref #2064
+pub unsafe fn udp_bind6(handle_ptr: *uv_udp_t,
+                        addr_ptr: *sockaddr_in6) -> libc::c_int {
+    return rustrt::rust_uv_udp_bind6(handle_ptr, addr_ptr, 0u32);
+}
+
+// FIXME ref #2064
+pub unsafe fn udp_send(req: *uv_udp_send_t, handle_ptr: *uv_udp_t,
+                       buf_in: *~[uv_buf_t], addr_ptr: *sockaddr_in,
+                       cb: *u8) -> libc::c_int {
+    let buf_ptr = vec::raw::to_ptr(*buf_in);
+    let buf_cnt = vec::len(*buf_in) as i32;
+    return rustrt::rust_uv_udp_send(req, handle_ptr, buf_ptr, buf_cnt,
+                                    addr_ptr, cb);
+}
+
+// FIXME ref #2064
+pub unsafe fn udp_send6(req: *uv_udp_send_t, handle_ptr: *uv_udp_t,
+                        buf_in: *~[uv_buf_t], addr_ptr: *sockaddr_in6,
+                        cb: *u8) -> libc::c_int {
+    let buf_ptr = vec::raw::to_ptr(*buf_in);
+    let buf_cnt = vec::len(*buf_in) as i32;
+    return rustrt::rust_uv_udp_send6(req, handle_ptr, buf_ptr, buf_cnt,
+                                     addr_ptr, cb);
+}
+
+pub unsafe fn udp_recv_start(handle_ptr: *uv_udp_t, on_alloc: *u8,
+                             on_recv: *u8) -> libc::c_int {
+    return rustrt::rust_uv_udp_recv_start(handle_ptr, on_alloc, on_recv);
+}
+
+pub unsafe fn udp_recv_stop(handle_ptr: *uv_udp_t) -> libc::c_int {
+    return rustrt::rust_uv_udp_recv_stop(handle_ptr);
+}
+
+pub unsafe fn udp_t_size() -> uint {
+    return rustrt::rust_uv_helper_uv_udp_t_size() as uint;
+}
+
+pub unsafe fn udp_send_t_size() -> uint {
+    return rustrt::rust_uv_helper_uv_udp_send_t_size() as uint;
+}
+
+// Whether a `struct sockaddr` passed to a libuv callback is a
+// `sockaddr_in` rather than a `sockaddr_in6`
+pub unsafe fn is_ipv4_sockaddr(addr: *libc::c_void) -> bool {
+    return rustrt::rust_uv_is_ipv4_sockaddr(addr);
+}
+
+pub unsafe fn tcp_nodelay(tcp_handle_ptr: *uv_tcp_t,
+                          enable: bool) -> libc::c_int {
+    let enable = if enable { 1 } else { 0 };
+    return rustrt::rust_uv_tcp_nodelay(tcp_handle_ptr, enable);
+}
+
+pub unsafe fn tcp_keepalive(tcp_handle_ptr: *uv_tcp_t, enable: bool,
+                            delay: uint) -> libc::c_int {
+    let enable = if enable { 1 } else { 0 };
+    return rustrt::rust_uv_tcp_keepalive(tcp_handle_ptr, enable,
+                                         delay as libc::c_uint);
+}
+
 pub unsafe fn listen<T>(stream: *T, backlog: libc::c_int,
                  cb: *u8) -> libc::c_int {
     return rustrt::rust_uv_listen(stream as *libc::c_void, backlog, cb);
@@ -314,6 +314,9 @@ pub struct blk_ {
     expr: Option<@expr>,
     id: node_id,
     rules: blk_check_mode,
+    // Inner attributes, like `#[allow(unused_mut)];`. Those of a function
+    // body belong to the function instead.
+    attrs: ~[attribute],
 }
 
 #[auto_encode]
@@ -717,7 +720,7 @@ pub enum expr_ {
     expr_lit(@lit),
     expr_cast(@expr, @Ty),
     expr_if(@expr, blk, Option<@expr>),
-    expr_while(@expr, blk),
+    expr_while(@expr, blk, Option<ident>),
     /* Conditionless loop (can be exited with break, cont, or ret)
        Same semantics as while(true) { body }, but typestate knows that the
        (implicit) condition is always true. */
@@ -730,8 +733,8 @@ pub enum expr_ {
     expr_fn_block(fn_decl, blk),
     // Inner expr is always an expr_fn_block. We need the wrapping node to
     // easily type this (a function returning nil on the inside but bool on
-    // the outside).
-    expr_loop_body(@expr),
+    // the outside). The label, if any, names the loop.
+    expr_loop_body(@expr, Option<ident>),
     // Like expr_loop_body but for 'do' blocks
     expr_do_body(@expr),
     expr_block(blk),
@@ -1176,6 +1179,9 @@ pub enum ty_ {
     ty_bare_fn(@TyBareFn),
     ty_tup(~[@Ty]),
     ty_path(@path, node_id),
+    // A trait type behind a pointer, with the builtin kinds that the object
+    // promises: `~Writer:Owned`
+    ty_bounded(@Ty, @~[ty_param_bound]),
     ty_mac(mac),
     // ty_infer means the type should be inferred instead of it having been
     // specified. This should only appear at the "top level" of a type and not
@@ -221,6 +221,7 @@ pub fn default_block(
         expr: expr1,
         id: id1,
         rules: default_blk,
+        attrs: ~[],
     }
 }
 
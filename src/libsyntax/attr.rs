@@ -181,6 +181,20 @@ pub fn contains(haystack: &[@ast::meta_item],
     return false;
 }
 
+/**
+ * Whether the configuration `cfg` satisfies `mi`, one of the requirements
+ * written inside `#[cfg(...)]`. A word or name-value pair must be in `cfg`,
+ * and `not(a, b)` holds when neither `a` nor `b` does.
+ */
+pub fn cfg_matches(cfg: &[@ast::meta_item], mi: @ast::meta_item) -> bool {
+    match mi.node {
+      ast::meta_list(ref name, ref mis) if *name == ~"not" => {
+        !vec::any(*mis, |m| cfg_matches(cfg, *m))
+      }
+      _ => contains(cfg, mi)
+    }
+}
+
 fn eq(a: @ast::meta_item, b: @ast::meta_item) -> bool {
     return match a.node {
           ast::meta_word(ref na) => match b.node {
@@ -349,6 +349,7 @@ priv impl ext_ctxt {
                 expr: None,
                 id: self.next_id(),
                 rules: ast::default_blk,
+                attrs: ~[],
             },
             span: span,
         }
@@ -362,6 +363,7 @@ priv impl ext_ctxt {
                 expr: Some(expr),
                 id: self.next_id(),
                 rules: ast::default_blk,
+                attrs: ~[],
             },
             span: expr.span,
         }
@@ -55,6 +55,10 @@ pub struct SyntaxExpanderTTItem {
 pub type SyntaxExpanderTTItemFun
     = fn@(ext_ctxt, span, ast::ident, ~[ast::token_tree]) -> MacResult;
 
+// Given an `extern mod` view item, returns the source of every macro that
+// the crate it names exported with `#[macro_export]`
+pub type MacroLoader = fn@(@ast::view_item) -> ~[~str];
+
 pub enum MacResult {
     MRExpr(@ast::expr),
     MRItem(@ast::item),
@@ -90,6 +94,8 @@ pub fn syntax_expander_table() -> HashMap<~str, SyntaxExtension> {
                                 ext::tt::macro_rules::add_new_extension));
     syntax_expanders.insert(~"fmt",
                             builtin_normal_tt(ext::fmt::expand_syntax_ext));
+    syntax_expanders.insert(~"write_fmt",
+                            builtin_normal_tt(ext::fmt::expand_write_fmt));
     syntax_expanders.insert(
         ~"auto_encode",
         ItemDecorator(ext::auto_encode::expand_auto_encode));
@@ -98,6 +104,11 @@ pub fn syntax_expander_table() -> HashMap<~str, SyntaxExtension> {
         ItemDecorator(ext::auto_encode::expand_auto_decode));
     syntax_expanders.insert(~"env",
                             builtin_normal_tt(ext::env::expand_syntax_ext));
+    syntax_expanders.insert(~"cfg",
+                            builtin_normal_tt(ext::cfg::expand_cfg));
+    syntax_expanders.insert(~"concat",
+                            builtin_normal_tt(
+                                ext::concat::expand_syntax_ext));
     syntax_expanders.insert(~"concat_idents",
                             builtin_normal_tt(
                                 ext::concat_idents::expand_syntax_ext));
@@ -107,9 +118,21 @@ pub fn syntax_expander_table() -> HashMap<~str, SyntaxExtension> {
     syntax_expanders.insert(~"deriving_eq",
                             ItemDecorator(
                                 ext::deriving::expand_deriving_eq));
+    syntax_expanders.insert(~"deriving_ord",
+                            ItemDecorator(
+                                ext::deriving::expand_deriving_ord));
     syntax_expanders.insert(~"deriving_iter_bytes",
                             ItemDecorator(
                                 ext::deriving::expand_deriving_iter_bytes));
+    syntax_expanders.insert(~"deriving_to_str",
+                            ItemDecorator(
+                                ext::deriving::expand_deriving_to_str));
+    syntax_expanders.insert(~"deriving_clone",
+                            ItemDecorator(
+                                ext::deriving::expand_deriving_clone));
+    syntax_expanders.insert(~"deriving",
+                            ItemDecorator(
+                                ext::deriving::expand_meta_deriving));
 
     // Quasi-quoting expanders
     syntax_expanders.insert(
@@ -181,6 +204,8 @@ pub trait ext_ctxt {
     fn next_id() -> ast::node_id;
     pure fn trace_macros() -> bool;
     fn set_trace_macros(x: bool);
+    fn export_macro(source: ~str);
+    fn exported_macros() -> ~[~str];
     /* for unhygienic identifier transformation */
     fn str_of(id: ast::ident) -> ~str;
     fn ident_of(st: ~str) -> ast::ident;
@@ -192,7 +217,8 @@ pub fn mk_ctxt(parse_sess: parse::parse_sess,
                       cfg: ast::crate_cfg,
                       mut backtrace: Option<@ExpnInfo>,
                       mut mod_path: ~[ast::ident],
-                      mut trace_mac: bool};
+                      mut trace_mac: bool,
+                      mut exported_macros: ~[~str]};
     impl ctxt_repr: ext_ctxt {
         fn codemap() -> @CodeMap { self.parse_sess.cm }
         fn parse_sess() -> parse::parse_sess { self.parse_sess }
@@ -262,6 +288,12 @@ pub fn mk_ctxt(parse_sess: parse::parse_sess,
         fn set_trace_macros(x: bool) {
             self.trace_mac = x
         }
+        fn export_macro(source: ~str) {
+            self.exported_macros.push(source);
+        }
+        fn exported_macros() -> ~[~str] {
+            copy self.exported_macros
+        }
 
         fn str_of(id: ast::ident) -> ~str {
             *self.parse_sess.interner.get(id)
@@ -275,7 +307,8 @@ pub fn mk_ctxt(parse_sess: parse::parse_sess,
         cfg: cfg,
         mut backtrace: None,
         mut mod_path: ~[],
-        mut trace_mac: false
+        mut trace_mac: false,
+        mut exported_macros: ~[]
     };
     move ((move imp) as ext_ctxt)
 }
@@ -233,6 +233,7 @@ pub fn mk_block(cx: ext_ctxt, span: span,
              expr: expr,
              id: cx.next_id(),
              rules: ast::default_blk,
+             attrs: ~[],
         },
         span: span,
     };
@@ -249,6 +250,7 @@ pub fn mk_block_(cx: ext_ctxt,
             expr: None,
             id: cx.next_id(),
             rules: ast::default_blk,
+            attrs: ~[],
         },
         span: span,
     }
@@ -264,6 +266,7 @@ pub fn mk_simple_block(cx: ext_ctxt,
             expr: Some(expr),
             id: cx.next_id(),
             rules: ast::default_blk,
+            attrs: ~[],
         },
         span: span,
     }
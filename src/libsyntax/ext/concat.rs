@@ -0,0 +1,60 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*
+ * The compiler code necessary to support the concat! extension, which joins
+ * string, character and integer literals into a single string literal.
+ */
+
+use core::prelude::*;
+
+use ast;
+use codemap::span;
+use ext::base::*;
+use ext::base;
+use ext::build::mk_base_str;
+
+use core::i64;
+use core::str;
+use core::u64;
+
+pub fn expand_syntax_ext(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
+    -> base::MacResult {
+    let mut res = ~"";
+    for get_exprs_from_tts(cx, tts).each |e| {
+        match e.node {
+          ast::expr_lit(lit) => match lit.node {
+            ast::lit_str(s) => str::push_str(&mut res, *s),
+            ast::lit_int(c, ast::ty_char) => {
+                str::push_char(&mut res, c as char)
+            }
+            ast::lit_int(i, _) | ast::lit_int_unsuffixed(i) => {
+                str::push_str(&mut res, i64::to_str(i))
+            }
+            ast::lit_uint(u, _) => str::push_str(&mut res, u64::to_str(u)),
+            _ => cx.span_fatal(e.span, ~"concat! requires string, \
+                                         character or integer literals")
+          },
+          _ => cx.span_fatal(e.span, ~"concat! requires string, \
+                                       character or integer literals")
+        }
+    }
+    MRExpr(mk_base_str(cx, sp, res))
+}
+
+//
+// Local Variables:
+// mode: rust
+// fill-column: 78;
+// indent-tabs-mode: nil
+// c-basic-offset: 4
+// buffer-file-coding-system: utf-8-unix
+// End:
+//
@@ -17,21 +17,26 @@ pub fn expand_syntax_ext(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
     -> base::MacResult {
     let mut res_str = ~"";
     for tts.eachi |i, e| {
+        // blame the offending token when there is one
+        let tok_sp = match *e { ast::tt_tok(tsp, _) => tsp, _ => sp };
         if i & 1 == 1 {
             match *e {
                 ast::tt_tok(_, token::COMMA) => (),
-                _ => cx.span_fatal(sp, ~"concat_idents! \
-                                         expecting comma.")
+                _ => cx.span_fatal(tok_sp, ~"concat_idents! \
+                                             expecting comma.")
             }
         } else {
             match *e {
                 ast::tt_tok(_, token::IDENT(ident,_)) =>
                 res_str += cx.str_of(ident),
-                _ => cx.span_fatal(sp, ~"concat_idents! \
-                                         requires ident args.")
+                _ => cx.span_fatal(tok_sp, ~"concat_idents! \
+                                             requires ident args.")
             }
         }
     }
+    if res_str.is_empty() {
+        cx.span_fatal(sp, ~"concat_idents! requires ident args.");
+    }
     let res = cx.parse_sess().interner.intern(@res_str);
 
     let e = @ast::expr {
@@ -8,19 +8,23 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-/// The compiler code necessary to implement the #[deriving_eq] and
-/// #[deriving_iter_bytes] extensions.
+/// The compiler code necessary to implement the #[deriving_eq],
+/// #[deriving_ord], #[deriving_iter_bytes], #[deriving_clone] and
+/// #[deriving_to_str] extensions, and the #[deriving(...)] attribute that
+/// combines them.
 
 use core::prelude::*;
 
 use ast;
 use ast::{TraitTyParamBound, Ty, and, bind_by_ref, binop, deref, enum_def};
-use ast::{enum_variant_kind, expr, expr_match, ident, item, item_};
-use ast::{item_enum, item_impl, item_struct, m_imm, meta_item, method};
-use ast::{named_field, or, pat, pat_ident, pat_wild, public, pure_fn};
-use ast::{re_anon, stmt, struct_def, struct_variant_kind};
-use ast::{sty_by_ref, sty_region, tuple_variant_kind, ty_nil, ty_param};
-use ast::{ty_param_bound, ty_path, ty_rptr, unnamed_field, variant};
+use ast::{enum_variant_kind, expr, expr_if, expr_match, expr_ret, ident};
+use ast::{impure_fn, item, item_, item_enum, item_impl, item_struct, m_imm};
+use ast::{meta_item, meta_list, meta_name_value, meta_word, method};
+use ast::{named_field, or, pat, pat_enum, pat_ident, pat_struct, pat_wild};
+use ast::{public, pure_fn, re_anon, stmt, struct_def, struct_variant_kind};
+use ast::{sty_by_ref, sty_region, tuple_variant_kind, ty_box, ty_nil};
+use ast::{ty_param, ty_param_bound, ty_path, ty_rptr, ty_uniq, unnamed_field};
+use ast::{variant};
 use ext::base::ext_ctxt;
 use ext::build;
 use codemap::{span, spanned};
@@ -28,6 +32,7 @@ use parse::token::special_idents::clownshoes_extensions;
 
 use core::dvec;
 use core::uint;
+use core::vec;
 
 enum Junction {
     Conjunction,
@@ -68,6 +73,18 @@ pub fn expand_deriving_eq(cx: ext_ctxt,
                     expand_deriving_eq_enum_def)
 }
 
+pub fn expand_deriving_ord(cx: ext_ctxt,
+                           span: span,
+                           _mitem: meta_item,
+                           in_items: ~[@item])
+                        -> ~[@item] {
+    expand_deriving(cx,
+                    span,
+                    in_items,
+                    expand_deriving_ord_struct_def,
+                    expand_deriving_ord_enum_def)
+}
+
 pub fn expand_deriving_iter_bytes(cx: ext_ctxt,
                                   span: span,
                                   _mitem: meta_item,
@@ -80,6 +97,82 @@ pub fn expand_deriving_iter_bytes(cx: ext_ctxt,
                     expand_deriving_iter_bytes_enum_def)
 }
 
+pub fn expand_deriving_clone(cx: ext_ctxt,
+                             span: span,
+                             _mitem: meta_item,
+                             in_items: ~[@item])
+                          -> ~[@item] {
+    expand_deriving(cx,
+                    span,
+                    in_items,
+                    expand_deriving_clone_struct_def,
+                    expand_deriving_clone_enum_def)
+}
+
+pub fn expand_deriving_to_str(cx: ext_ctxt,
+                              span: span,
+                              _mitem: meta_item,
+                              in_items: ~[@item])
+                           -> ~[@item] {
+    expand_deriving(cx,
+                    span,
+                    in_items,
+                    expand_deriving_to_str_struct_def,
+                    expand_deriving_to_str_enum_def)
+}
+
+/// Expands `#[deriving(Clone, Eq)]` as if each of the listed traits had
+/// been derived with its own `#[deriving_*]` attribute.
+pub fn expand_meta_deriving(cx: ext_ctxt,
+                            _span: span,
+                            mitem: meta_item,
+                            in_items: ~[@item])
+                         -> ~[@item] {
+    let trait_items = match mitem.node {
+        meta_list(_, ref trait_items) => copy *trait_items,
+        meta_word(*) | meta_name_value(*) => {
+            cx.span_err(mitem.span, ~"`deriving` expects a list of \
+                                      traits, e.g. `#[deriving(Clone, Eq)]`");
+            return in_items;
+        }
+    };
+
+    let mut items = in_items;
+    for trait_items.each |trait_item| {
+        let trait_name = match trait_item.node {
+            meta_word(ref name) => copy *name,
+            meta_list(ref name, _) | meta_name_value(ref name, _) => {
+                cx.span_err(trait_item.span,
+                            fmt!("`deriving` expects trait names, \
+                                  not `%s(...)` or `%s = ...`",
+                                 *name, *name));
+                loop;
+            }
+        };
+        items = match trait_name {
+            ~"Clone" => expand_deriving_clone(cx, trait_item.span,
+                                              **trait_item, items),
+            ~"Eq" => expand_deriving_eq(cx, trait_item.span,
+                                        **trait_item, items),
+            ~"Ord" => expand_deriving_ord(cx, trait_item.span,
+                                          **trait_item, items),
+            ~"IterBytes" => expand_deriving_iter_bytes(cx, trait_item.span,
+                                                       **trait_item, items),
+            ~"ToStr" => expand_deriving_to_str(cx, trait_item.span,
+                                               **trait_item, items),
+            _ => {
+                cx.span_err(trait_item.span,
+                            fmt!("cannot derive the trait `%s`; `deriving` \
+                                  supports `Clone`, `Eq`, `Ord`, \
+                                  `IterBytes` and `ToStr`",
+                                 trait_name));
+                items
+            }
+        };
+    }
+    items
+}
+
 fn expand_deriving(cx: ext_ctxt,
                    span: span,
                    in_items: ~[@item],
@@ -270,6 +363,20 @@ fn create_derived_eq_impl(cx: ext_ctxt,
     create_derived_impl(cx, span, type_ident, ty_params, methods, trait_path)
 }
 
+fn create_derived_ord_impl(cx: ext_ctxt,
+                           span: span,
+                           type_ident: ident,
+                           +ty_params: ~[ty_param],
+                           methods: &[@method])
+                        -> @item {
+    let trait_path = [
+        cx.ident_of(~"core"),
+        cx.ident_of(~"cmp"),
+        cx.ident_of(~"Ord")
+    ];
+    create_derived_impl(cx, span, type_ident, ty_params, methods, trait_path)
+}
+
 fn create_derived_iter_bytes_impl(cx: ext_ctxt,
                                   span: span,
                                   type_ident: ident,
@@ -285,6 +392,115 @@ fn create_derived_iter_bytes_impl(cx: ext_ctxt,
     create_derived_impl(cx, span, type_ident, ty_params, methods, trait_path)
 }
 
+fn create_derived_clone_impl(cx: ext_ctxt,
+                             span: span,
+                             type_ident: ident,
+                             +ty_params: ~[ty_param],
+                             method: @method)
+                          -> @item {
+    let methods = [ method ];
+    let trait_path = [
+        cx.ident_of(~"core"),
+        cx.ident_of(~"clone"),
+        cx.ident_of(~"Clone")
+    ];
+    create_derived_impl(cx, span, type_ident, ty_params, methods, trait_path)
+}
+
+// Creates a method from the given expression conforming to the signature of
+// the `clone` method.
+fn create_clone_method(cx: ext_ctxt,
+                       span: span,
+                       type_ident: ast::ident,
+                       ty_params: &[ty_param],
+                       expr: @ast::expr)
+                    -> @method {
+    // Create the type of the return value.
+    let output_type = create_self_type_with_params(cx,
+                                                   span,
+                                                   type_ident,
+                                                   ty_params);
+
+    // Create the function declaration.
+    let fn_decl = build::mk_fn_decl(~[], output_type);
+
+    // Create the body block.
+    let body_block = build::mk_simple_block(cx, span, expr);
+
+    // Create the self type and method identifier.
+    let self_ty = spanned { node: sty_region(m_imm), span: span };
+    let method_ident = cx.ident_of(~"clone");
+
+    // Create the method.
+    @ast::method {
+        ident: method_ident,
+        attrs: ~[],
+        tps: ~[],
+        self_ty: self_ty,
+        purity: impure_fn,
+        decl: move fn_decl,
+        body: move body_block,
+        id: cx.next_id(),
+        span: span,
+        self_id: cx.next_id(),
+        vis: public,
+    }
+}
+
+fn create_derived_to_str_impl(cx: ext_ctxt,
+                              span: span,
+                              type_ident: ident,
+                              +ty_params: ~[ty_param],
+                              method: @method)
+                           -> @item {
+    let methods = [ method ];
+    let trait_path = [
+        cx.ident_of(~"core"),
+        cx.ident_of(~"to_str"),
+        cx.ident_of(~"ToStr")
+    ];
+    create_derived_impl(cx, span, type_ident, ty_params, methods, trait_path)
+}
+
+// Creates a method from the given expression conforming to the signature of
+// the `to_str` method.
+fn create_to_str_method(cx: ext_ctxt,
+                        span: span,
+                        expr: @ast::expr)
+                     -> @method {
+    // Create the type of the return value.
+    let str_ident = cx.ident_of(~"str");
+    let str_type = build::mk_simple_ty_path(cx, span, str_ident);
+    let output_type = @ast::Ty {
+        id: cx.next_id(),
+        node: ty_uniq(ast::mt { ty: str_type, mutbl: m_imm }),
+        span: span,
+    };
+
+    // Create the function declaration.
+    let fn_decl = build::mk_fn_decl(~[], output_type);
+
+    // Create the body block.
+    let body_block = build::mk_simple_block(cx, span, expr);
+
+    // Create the method.
+    let self_ty = spanned { node: sty_region(m_imm), span: span };
+    let method_ident = cx.ident_of(~"to_str");
+    @ast::method {
+        ident: method_ident,
+        attrs: ~[],
+        tps: ~[],
+        self_ty: self_ty,
+        purity: pure_fn,
+        decl: move fn_decl,
+        body: move body_block,
+        id: cx.next_id(),
+        span: span,
+        self_id: cx.next_id(),
+        vis: public,
+    }
+}
+
 // Creates a method from the given set of statements conforming to the
 // signature of the `iter_bytes` method.
 fn create_iter_bytes_method(cx: ext_ctxt,
@@ -400,6 +616,40 @@ fn create_enum_variant_pattern(cx: ext_ctxt,
     }
 }
 
+fn is_tuple_struct(struct_def: &struct_def) -> bool {
+    struct_def.fields.len() > 0 && match struct_def.fields[0].node.kind {
+        unnamed_field => true,
+        named_field(*) => false
+    }
+}
+
+// Creates `match *value { Type(ref prefix0, ref prefix1, ...) => body }`,
+// binding each field of a tuple struct by reference.
+fn create_tuple_struct_match(cx: ext_ctxt,
+                             span: span,
+                             struct_def: &struct_def,
+                             type_ident: ident,
+                             value_ident: ident,
+                             prefix: ~str,
+                             +body: ast::blk)
+                          -> @expr {
+    let subpats = create_subpatterns(cx,
+                                     span,
+                                     prefix,
+                                     struct_def.fields.len());
+    let matching_path = build::mk_raw_path(span, ~[ type_ident ]);
+    let pat = build::mk_pat_enum(cx, span, matching_path, move subpats);
+    let arm = ast::arm {
+        pats: ~[ pat ],
+        guard: None,
+        body: move body,
+    };
+
+    let value_expr = build::mk_path(cx, span, ~[ value_ident ]);
+    let value_expr = build::mk_unary(cx, span, deref, value_expr);
+    build::mk_expr(cx, span, expr_match(value_expr, ~[ arm ]))
+}
+
 fn call_substructure_eq_method(cx: ext_ctxt,
                                span: span,
                                self_field: @expr,
@@ -429,6 +679,85 @@ fn call_substructure_eq_method(cx: ext_ctxt,
     };
 }
 
+// Creates a reference to a field compared by a derived `Ord` method: the
+// named field of `self` or `__other`, or else the `i`th variable bound by
+// `create_subpatterns` with the `__self` or `__other` prefix.
+fn create_ord_field_ref(cx: ext_ctxt,
+                        span: span,
+                        field_ident: Option<ident>,
+                        i: uint,
+                        is_self: bool)
+                     -> @expr {
+    match field_ident {
+        Some(ident) => {
+            let base_ident = if is_self {
+                cx.ident_of(~"self")
+            } else {
+                cx.ident_of(~"__other")
+            };
+            let field = build::mk_access(cx, span, ~[ base_ident ], ident);
+            build::mk_addr_of(cx, span, field)
+        }
+        None => {
+            let prefix = if is_self { ~"__self" } else { ~"__other" };
+            let bound_ident = cx.ident_of(prefix + i.to_str());
+            build::mk_path(cx, span, ~[ bound_ident ])
+        }
+    }
+}
+
+// Creates the lexicographic comparison of the given fields performed by the
+// `method_ident` method of `Ord`. Every field but the last decides the
+// result with `strict_ident` (`lt` or `gt`) if it differs; otherwise the
+// next field is compared, and the last is compared with `method_ident`
+// itself. With no fields to compare, the two values are equal.
+fn create_ord_chain_expr(cx: ext_ctxt,
+                         span: span,
+                         fields: &[Option<ident>],
+                         method_ident: ident,
+                         strict_ident: ident)
+                      -> @expr {
+    let call = |field_ident: Option<ident>, i: uint, method: ident,
+                self_first: bool| {
+        let recv = create_ord_field_ref(cx, span, field_ident, i, self_first);
+        let arg = create_ord_field_ref(cx, span, field_ident, i, !self_first);
+        let recv_method = build::mk_access_(cx, span, recv, method);
+        build::mk_call_(cx, span, recv_method, ~[ arg ])
+    };
+
+    if fields.len() == 0 {
+        return build::mk_bool(cx, span, method_ident != strict_ident);
+    }
+
+    let last = fields.len() - 1;
+    let mut chain_expr = call(fields[last], last, method_ident, true);
+    for uint::range_rev(last, 0) |j| {
+        let i = j - 1;
+        // self.f.strict(&other.f) || (!other.f.strict(&self.f) && chain)
+        let decided_expr = call(fields[i], i, strict_ident, true);
+        let undecided_expr = call(fields[i], i, strict_ident, false);
+        let undecided_expr = build::mk_unary(cx, span, ast::not,
+                                             undecided_expr);
+        let rest_expr = build::mk_binary(cx, span, and, undecided_expr,
+                                         chain_expr);
+        chain_expr = build::mk_binary(cx, span, or, decided_expr, rest_expr);
+    }
+    chain_expr
+}
+
+// Returns the method identifiers of `Ord` paired with the strict comparison
+// each of them uses to order differing fields.
+fn ord_method_idents(cx: ext_ctxt) -> ~[(ident, ident)] {
+    let lt_ident = cx.ident_of(~"lt");
+    let gt_ident = cx.ident_of(~"gt");
+    ~[
+        (lt_ident, lt_ident),
+        (cx.ident_of(~"le"), lt_ident),
+        (cx.ident_of(~"ge"), gt_ident),
+        (gt_ident, gt_ident)
+    ]
+}
+
 fn finish_eq_chain_expr(cx: ext_ctxt,
                         span: span,
                         chain_expr: Option<@expr>,
@@ -445,6 +774,8 @@ fn finish_eq_chain_expr(cx: ext_ctxt,
     }
 }
 
+// Feeds the substructure to the byte iteration function, returning early
+// from the method if the function asks to stop.
 fn call_substructure_iter_bytes_method(cx: ext_ctxt,
                                        span: span,
                                        self_field: @expr)
@@ -455,19 +786,169 @@ fn call_substructure_iter_bytes_method(cx: ext_ctxt,
     let lsb0_expr = build::mk_path(cx, span, ~[ lsb0_ident ]);
     let f_expr = build::mk_path(cx, span, ~[ f_ident ]);
 
-    // Call the substructure method.
-    let iter_bytes_ident = cx.ident_of(~"iter_bytes");
-    let self_method = build::mk_access_(cx,
-                                        span,
-                                        self_field,
-                                        iter_bytes_ident);
-    let self_call = build::mk_call_(cx,
-                                    span,
-                                    self_method,
-                                    ~[ lsb0_expr, f_expr ]);
+    // Call `core::to_bytes::iter_bytes_until`, which tells us whether the
+    // byte iteration function wants more bytes.
+    let iter_bytes_until_path = ~[
+        cx.ident_of(~"core"),
+        cx.ident_of(~"to_bytes"),
+        cx.ident_of(~"iter_bytes_until")
+    ];
+    let self_field = build::mk_addr_of(cx, span, self_field);
+    let self_call = build::mk_call_global(cx,
+                                          span,
+                                          iter_bytes_until_path,
+                                          ~[ self_field, lsb0_expr, f_expr ]);
+
+    // Return if it doesn't.
+    let stop_expr = build::mk_unary(cx, span, ast::not, self_call);
+    let return_expr = build::mk_expr(cx, span, expr_ret(None));
+    let return_stmt = build::mk_stmt(cx, span, return_expr);
+    let return_block = build::mk_block_(cx, span, ~[ return_stmt ]);
+    let if_expr = build::mk_expr(cx,
+                                 span,
+                                 expr_if(stop_expr, return_block, None));
 
     // Create a statement out of this expression.
-    build::mk_stmt(cx, span, self_call)
+    build::mk_stmt(cx, span, if_expr)
+}
+
+fn call_substructure_clone_method(cx: ext_ctxt,
+                                  span: span,
+                                  self_field: @expr)
+                               -> @expr {
+    // Call the substructure method.
+    let clone_ident = cx.ident_of(~"clone");
+    let self_method = build::mk_access_(cx, span, self_field, clone_ident);
+    build::mk_call_(cx, span, self_method, ~[])
+}
+
+// Returns true if the type is written as a pointer to `str`, whose values
+// `deriving_to_str` quotes.
+fn is_str_type(cx: ext_ctxt, ty: @Ty) -> bool {
+    match ty.node {
+        ty_uniq(ref mt) | ty_box(ref mt) | ty_rptr(_, ref mt) => {
+            match mt.ty.node {
+                ty_path(path, _) => {
+                    !path.global && path.idents.len() == 1 &&
+                        path.types.len() == 0 &&
+                        cx.str_of(path.idents[0]) == ~"str"
+                }
+                _ => false
+            }
+        }
+        _ => false
+    }
+}
+
+// Converts the field that `field_ref` points to to a string, quoting and
+// escaping strings.
+fn call_substructure_to_str_method(cx: ext_ctxt,
+                                   span: span,
+                                   field_ref: @expr,
+                                   is_str: bool)
+                                -> @expr {
+    if is_str {
+        let escape_path = ~[
+            cx.ident_of(~"core"),
+            cx.ident_of(~"str"),
+            cx.ident_of(~"escape_default")
+        ];
+        let field = build::mk_unary(cx, span, deref, field_ref);
+        let escaped = build::mk_call_global(cx, span, escape_path,
+                                            ~[ field ]);
+        let open_quote = build::mk_uniq_str(cx, span, ~"\"");
+        let close_quote = build::mk_uniq_str(cx, span, ~"\"");
+        let quoted = build::mk_binary(cx, span, ast::add, open_quote,
+                                      escaped);
+        build::mk_binary(cx, span, ast::add, quoted, close_quote)
+    } else {
+        let to_str_ident = cx.ident_of(~"to_str");
+        let self_method = build::mk_access_(cx, span, field_ref,
+                                            to_str_ident);
+        build::mk_call_(cx, span, self_method, ~[])
+    }
+}
+
+// Concatenates `name`, then `open`, then each of the strings `fields`
+// separated by commas, then `close`. With no fields, the result is just
+// `name`.
+fn create_to_str_concat_expr(cx: ext_ctxt,
+                             span: span,
+                             name: ~str,
+                             open: ~str,
+                             fields: ~[@expr],
+                             close: ~str)
+                          -> @expr {
+    if fields.len() == 0 {
+        return build::mk_uniq_str(cx, span, name);
+    }
+
+    let mut result = build::mk_uniq_str(cx, span, name + open);
+    for fields.eachi |i, field| {
+        if i > 0 {
+            let comma = build::mk_uniq_str(cx, span, ~", ");
+            result = build::mk_binary(cx, span, ast::add, result, comma);
+        }
+        result = build::mk_binary(cx, span, ast::add, result, *field);
+    }
+    let close = build::mk_uniq_str(cx, span, close);
+    build::mk_binary(cx, span, ast::add, result, close)
+}
+
+// Creates the strings for the fields of a struct or struct-like variant,
+// each as `name: value`. The field values are the `__self`-prefixed
+// variables bound by `create_subpatterns`, or else the fields of `self`.
+fn create_to_str_named_fields(cx: ext_ctxt,
+                              span: span,
+                              struct_def: &struct_def,
+                              bound: bool)
+                           -> ~[@expr] {
+    do struct_def.fields.mapi |i, struct_field| {
+        let ident = match struct_field.node.kind {
+            named_field(ident, _, _) => ident,
+            unnamed_field => {
+                cx.span_bug(span, ~"unexpected unnamed field");
+            }
+        };
+        let field_ref = if bound {
+            let field_ident = cx.ident_of(~"__self" + i.to_str());
+            build::mk_path(cx, span, ~[ field_ident ])
+        } else {
+            let self_ident = cx.ident_of(~"self");
+            let field = build::mk_access(cx, span, ~[ self_ident ], ident);
+            build::mk_addr_of(cx, span, field)
+        };
+        let is_str = is_str_type(cx, struct_field.node.ty);
+        let value = call_substructure_to_str_method(cx, span, field_ref,
+                                                    is_str);
+        let label = build::mk_uniq_str(cx, span, cx.str_of(ident) + ~": ");
+        build::mk_binary(cx, span, ast::add, label, value)
+    }
+}
+
+// Creates the strings for the `__self`-prefixed variables bound by
+// `create_subpatterns` for fields of the given types.
+fn create_to_str_bound_fields(cx: ext_ctxt,
+                              span: span,
+                              tys: &[@Ty])
+                           -> ~[@expr] {
+    do tys.mapi |i, ty| {
+        let field_ident = cx.ident_of(~"__self" + i.to_str());
+        let field_ref = build::mk_path(cx, span, ~[ field_ident ]);
+        call_substructure_to_str_method(cx, span, field_ref,
+                                        is_str_type(cx, *ty))
+    }
+}
+
+// Clones the `i`th variable bound by `create_subpatterns`.
+fn clone_bound_field(cx: ext_ctxt,
+                     span: span,
+                     prefix: ~str,
+                     i: uint)
+                  -> @expr {
+    let field_ident = cx.ident_of(prefix + i.to_str());
+    let field = build::mk_path(cx, span, ~[ field_ident ]);
+    call_substructure_clone_method(cx, span, field)
 }
 
 fn variant_arg_count(cx: ext_ctxt, span: span, variant: &variant) -> uint {
@@ -546,6 +1027,58 @@ fn expand_deriving_eq_enum_def(cx: ext_ctxt,
                                   ne_method);
 }
 
+fn expand_deriving_ord_struct_def(cx: ext_ctxt,
+                                  span: span,
+                                  struct_def: &struct_def,
+                                  type_ident: ident,
+                                  +ty_params: ~[ty_param])
+                               -> @item {
+    // Create the methods.
+    let methods = do ord_method_idents(cx).map |idents| {
+        let (method_ident, strict_ident) = *idents;
+        expand_deriving_ord_struct_method(cx,
+                                          span,
+                                          struct_def,
+                                          method_ident,
+                                          strict_ident,
+                                          type_ident,
+                                          ty_params)
+    };
+
+    // Create the implementation.
+    return create_derived_ord_impl(cx,
+                                   span,
+                                   type_ident,
+                                   move ty_params,
+                                   methods);
+}
+
+fn expand_deriving_ord_enum_def(cx: ext_ctxt,
+                                span: span,
+                                enum_definition: &enum_def,
+                                type_ident: ident,
+                                +ty_params: ~[ty_param])
+                             -> @item {
+    // Create the methods.
+    let methods = do ord_method_idents(cx).map |idents| {
+        let (method_ident, strict_ident) = *idents;
+        expand_deriving_ord_enum_method(cx,
+                                        span,
+                                        enum_definition,
+                                        method_ident,
+                                        strict_ident,
+                                        type_ident,
+                                        ty_params)
+    };
+
+    // Create the implementation.
+    return create_derived_ord_impl(cx,
+                                   span,
+                                   type_ident,
+                                   move ty_params,
+                                   methods);
+}
+
 fn expand_deriving_iter_bytes_struct_def(cx: ext_ctxt,
                                          span: span,
                                          struct_def: &struct_def,
@@ -555,7 +1088,8 @@ fn expand_deriving_iter_bytes_struct_def(cx: ext_ctxt,
     // Create the method.
     let method = expand_deriving_iter_bytes_struct_method(cx,
                                                           span,
-                                                          struct_def);
+                                                          struct_def,
+                                                          type_ident);
 
     // Create the implementation.
     return create_derived_iter_bytes_impl(cx,
@@ -584,6 +1118,87 @@ fn expand_deriving_iter_bytes_enum_def(cx: ext_ctxt,
                                           method);
 }
 
+fn expand_deriving_clone_struct_def(cx: ext_ctxt,
+                                    span: span,
+                                    struct_def: &struct_def,
+                                    type_ident: ident,
+                                    +ty_params: ~[ty_param])
+                                 -> @item {
+    // Create the method.
+    let method = expand_deriving_clone_struct_method(cx,
+                                                     span,
+                                                     struct_def,
+                                                     type_ident,
+                                                     ty_params);
+
+    // Create the implementation.
+    return create_derived_clone_impl(cx,
+                                     span,
+                                     type_ident,
+                                     move ty_params,
+                                     method);
+}
+
+fn expand_deriving_clone_enum_def(cx: ext_ctxt,
+                                  span: span,
+                                  enum_definition: &enum_def,
+                                  type_ident: ident,
+                                  +ty_params: ~[ty_param])
+                               -> @item {
+    // Create the method.
+    let method = expand_deriving_clone_enum_method(cx,
+                                                   span,
+                                                   enum_definition,
+                                                   type_ident,
+                                                   ty_params);
+
+    // Create the implementation.
+    return create_derived_clone_impl(cx,
+                                     span,
+                                     type_ident,
+                                     move ty_params,
+                                     method);
+}
+
+fn expand_deriving_to_str_struct_def(cx: ext_ctxt,
+                                     span: span,
+                                     struct_def: &struct_def,
+                                     type_ident: ident,
+                                     +ty_params: ~[ty_param])
+                                  -> @item {
+    // Create the method.
+    let method = expand_deriving_to_str_struct_method(cx,
+                                                      span,
+                                                      struct_def,
+                                                      type_ident);
+
+    // Create the implementation.
+    return create_derived_to_str_impl(cx,
+                                      span,
+                                      type_ident,
+                                      move ty_params,
+                                      method);
+}
+
+fn expand_deriving_to_str_enum_def(cx: ext_ctxt,
+                                   span: span,
+                                   enum_definition: &enum_def,
+                                   type_ident: ident,
+                                   +ty_params: ~[ty_param])
+                                -> @item {
+    // Create the method.
+    let method = expand_deriving_to_str_enum_method(cx,
+                                                    span,
+                                                    enum_definition);
+
+    // Create the implementation.
+    return create_derived_to_str_impl(cx,
+                                      span,
+                                      type_ident,
+                                      move ty_params,
+                                      method);
+}
+
 fn expand_deriving_eq_struct_method(cx: ext_ctxt,
                                     span: span,
                                     struct_def: &struct_def,
@@ -595,6 +1210,16 @@ fn expand_deriving_eq_struct_method(cx: ext_ctxt,
     let self_ident = cx.ident_of(~"self");
     let other_ident = cx.ident_of(~"__other");
 
+    if is_tuple_struct(struct_def) {
+        return expand_deriving_eq_tuple_struct_method(cx,
+                                                      span,
+                                                      struct_def,
+                                                      method_ident,
+                                                      type_ident,
+                                                      ty_params,
+                                                      junction);
+    }
+
     // Create the body of the method.
     let mut outer_expr = None;
     for struct_def.fields.each |struct_field| {
@@ -625,7 +1250,7 @@ fn expand_deriving_eq_struct_method(cx: ext_ctxt,
                                             &mut outer_expr);
             }
             unnamed_field => {
-                cx.span_unimpl(span, ~"unnamed fields with `deriving_eq`");
+                cx.span_bug(span, ~"unexpected unnamed field");
             }
         }
     }
@@ -640,12 +1265,107 @@ fn expand_deriving_eq_struct_method(cx: ext_ctxt,
                             body);
 }
 
+fn expand_deriving_eq_tuple_struct_method(cx: ext_ctxt,
+                                          span: span,
+                                          struct_def: &struct_def,
+                                          method_ident: ident,
+                                          type_ident: ident,
+                                          ty_params: &[ty_param],
+                                          junction: Junction)
+                                       -> @method {
+    let self_ident = cx.ident_of(~"self");
+    let other_ident = cx.ident_of(~"__other");
+
+    // Compare the fields bound by the two matches below.
+    let mut outer_expr = None;
+    for uint::range(0, struct_def.fields.len()) |i| {
+        let other_field_ident = cx.ident_of(~"__other" + i.to_str());
+        let other_field = build::mk_path(cx, span, ~[ other_field_ident ]);
+        let self_field_ident = cx.ident_of(~"__self" + i.to_str());
+        let self_field = build::mk_path(cx, span, ~[ self_field_ident ]);
+
+        // Call the substructure method.
+        call_substructure_eq_method(cx,
+                                    span,
+                                    self_field,
+                                    other_field,
+                                    method_ident,
+                                    junction,
+                                    &mut outer_expr);
+    }
+    let body = finish_eq_chain_expr(cx, span, outer_expr, junction);
+
+    // Destructure `other` inside the destructuring of `self`.
+    let other_match_expr = create_tuple_struct_match(
+        cx, span, struct_def, type_ident, other_ident, ~"__other",
+        build::mk_simple_block(cx, span, body));
+    let self_match_expr = create_tuple_struct_match(
+        cx, span, struct_def, type_ident, self_ident, ~"__self",
+        build::mk_simple_block(cx, span, other_match_expr));
+
+    // Create the method itself.
+    create_eq_method(cx,
+                     span,
+                     method_ident,
+                     type_ident,
+                     ty_params,
+                     self_match_expr)
+}
+
+fn expand_deriving_ord_struct_method(cx: ext_ctxt,
+                                     span: span,
+                                     struct_def: &struct_def,
+                                     method_ident: ident,
+                                     strict_ident: ident,
+                                     type_ident: ident,
+                                     ty_params: &[ty_param])
+                                  -> @method {
+    let body = if is_tuple_struct(struct_def) {
+        // Compare the fields bound by destructuring `__other` inside the
+        // destructuring of `self`.
+        let fields = vec::from_elem(struct_def.fields.len(), None);
+        let chain_expr = create_ord_chain_expr(cx,
+                                               span,
+                                               fields,
+                                               method_ident,
+                                               strict_ident);
+        let other_match_expr = create_tuple_struct_match(
+            cx, span, struct_def, type_ident, cx.ident_of(~"__other"),
+            ~"__other", build::mk_simple_block(cx, span, chain_expr));
+        create_tuple_struct_match(
+            cx, span, struct_def, type_ident, cx.ident_of(~"self"),
+            ~"__self", build::mk_simple_block(cx, span, other_match_expr))
+    } else {
+        // Compare the named fields directly.
+        let fields = do struct_def.fields.map |struct_field| {
+            match struct_field.node.kind {
+                named_field(ident, _, _) => Some(ident),
+                unnamed_field => {
+                    cx.span_bug(span, ~"unexpected unnamed field");
+                }
+            }
+        };
+        create_ord_chain_expr(cx, span, fields, method_ident, strict_ident)
+    };
+
+    // Create the method itself.
+    create_eq_method(cx, span, method_ident, type_ident, ty_params, body)
+}
+
 fn expand_deriving_iter_bytes_struct_method(cx: ext_ctxt,
                                             span: span,
-                                            struct_def: &struct_def)
+                                            struct_def: &struct_def,
+                                            type_ident: ident)
                                          -> @method {
     let self_ident = cx.ident_of(~"self");
 
+    if is_tuple_struct(struct_def) {
+        return expand_deriving_iter_bytes_tuple_struct_method(cx,
+                                                              span,
+                                                              struct_def,
+                                                              type_ident);
+    }
+
     // Create the body of the method.
     let statements = dvec::DVec();
     for struct_def.fields.each |struct_field| {
@@ -664,8 +1384,7 @@ fn expand_deriving_iter_bytes_struct_method(cx: ext_ctxt,
                 statements.push(stmt);
             }
             unnamed_field => {
-                cx.span_unimpl(span,
-                               ~"unnamed fields with `deriving_iter_bytes`");
+                cx.span_bug(span, ~"unexpected unnamed field");
             }
         }
     }
@@ -675,6 +1394,29 @@ fn expand_deriving_iter_bytes_struct_method(cx: ext_ctxt,
     return create_iter_bytes_method(cx, span, move statements);
 }
 
+fn expand_deriving_iter_bytes_tuple_struct_method(cx: ext_ctxt,
+                                                  span: span,
+                                                  struct_def: &struct_def,
+                                                  type_ident: ident)
+                                               -> @method {
+    // Feed each field to the byte iteration function.
+    let stmts = do vec::from_fn(struct_def.fields.len()) |i| {
+        let field_ident = cx.ident_of(~"__self" + i.to_str());
+        let field = build::mk_path(cx, span, ~[ field_ident ]);
+        call_substructure_iter_bytes_method(cx, span, field)
+    };
+
+    // Create the method body.
+    let self_ident = cx.ident_of(~"self");
+    let self_match_expr = create_tuple_struct_match(
+        cx, span, struct_def, type_ident, self_ident, ~"__self",
+        build::mk_block_(cx, span, stmts));
+    let self_match_stmt = build::mk_stmt(cx, span, self_match_expr);
+
+    // Create the method.
+    create_iter_bytes_method(cx, span, ~[ self_match_stmt ])
+}
+
 fn expand_deriving_eq_enum_method(cx: ext_ctxt,
                                   span: span,
                                   enum_definition: &enum_def,
@@ -811,6 +1553,147 @@ fn expand_deriving_eq_enum_method(cx: ext_ctxt,
                             self_match_expr);
 }
 
+// Creates a pattern matching any value of the variant.
+fn create_enum_variant_wild_pattern(cx: ext_ctxt,
+                                    span: span,
+                                    variant: &variant)
+                                 -> @pat {
+    let variant_ident = variant.node.name;
+    match variant.node.kind {
+        tuple_variant_kind(ref variant_args) => {
+            if variant_args.len() == 0 {
+                return build::mk_pat_ident_with_binding_mode(
+                    cx, span, variant_ident, ast::bind_infer);
+            }
+            let matching_path = build::mk_raw_path(span, ~[ variant_ident ]);
+            build::mk_pat(cx, span, pat_enum(matching_path, None))
+        }
+        struct_variant_kind(*) => {
+            let matching_path = build::mk_raw_path(span, ~[ variant_ident ]);
+            build::mk_pat(cx, span, pat_struct(matching_path, ~[], true))
+        }
+        enum_variant_kind(*) => {
+            cx.span_unimpl(span, ~"enum variants for `deriving`");
+        }
+    }
+}
+
+fn expand_deriving_ord_enum_method(cx: ext_ctxt,
+                                   span: span,
+                                   enum_definition: &enum_def,
+                                   method_ident: ident,
+                                   strict_ident: ident,
+                                   type_ident: ident,
+                                   ty_params: &[ty_param])
+                                -> @method {
+    let self_ident = cx.ident_of(~"self");
+    let other_ident = cx.ident_of(~"__other");
+    let other_index_ident = cx.ident_of(~"__other_index");
+    let variant_count = enum_definition.variants.len();
+
+    // Variants are ordered by their position in the enum, so the strict
+    // comparison of differing variants compares their positions.
+    let index_binop = if strict_ident == cx.ident_of(~"lt") {
+        ast::lt
+    } else {
+        ast::gt
+    };
+
+    // Create the arms of the self match in the method body.
+    let self_arms = do enum_definition.variants.mapi |i, self_variant| {
+        let mut other_arms = ~[];
+
+        // Compare the fields of two values of the same variant.
+        let matching_pat = create_enum_variant_pattern(cx,
+                                                       span,
+                                                       self_variant,
+                                                       ~"__other");
+        let arg_count = variant_arg_count(cx, span, self_variant);
+        let fields = vec::from_elem(arg_count, None);
+        let matching_body_expr = create_ord_chain_expr(cx,
+                                                       span,
+                                                       fields,
+                                                       method_ident,
+                                                       strict_ident);
+        other_arms.push(ast::arm {
+            pats: ~[ matching_pat ],
+            guard: None,
+            body: build::mk_simple_block(cx, span, matching_body_expr)
+        });
+
+        // Compare the positions of differing variants. If there is only
+        // one variant then there will always be a match.
+        if variant_count > 1 {
+            let nonmatching_pat = @ast::pat {
+                id: cx.next_id(),
+                node: pat_wild,
+                span: span
+            };
+            let self_index = build::mk_uint(cx, span, i);
+            let other_index = build::mk_path(cx, span,
+                                             ~[ other_index_ident ]);
+            let nonmatching_expr = build::mk_binary(cx,
+                                                    span,
+                                                    index_binop,
+                                                    self_index,
+                                                    other_index);
+            other_arms.push(ast::arm {
+                pats: ~[ nonmatching_pat ],
+                guard: None,
+                body: build::mk_simple_block(cx, span, nonmatching_expr)
+            });
+        }
+
+        // Create the self arm.
+        let self_pat = create_enum_variant_pattern(cx,
+                                                   span,
+                                                   self_variant,
+                                                   ~"__self");
+        let other_expr = build::mk_path(cx, span, ~[ other_ident ]);
+        let other_expr = build::mk_unary(cx, span, deref, other_expr);
+        let other_match_expr = expr_match(other_expr, move other_arms);
+        let other_match_expr = build::mk_expr(cx, span, other_match_expr);
+        ast::arm {
+            pats: ~[ self_pat ],
+            guard: None,
+            body: build::mk_simple_block(cx, span, other_match_expr)
+        }
+    };
+
+    // Create the self match.
+    let self_expr = build::mk_path(cx, span, ~[ self_ident ]);
+    let self_expr = build::mk_unary(cx, span, deref, self_expr);
+    let self_match_expr = expr_match(self_expr, move self_arms);
+    let self_match_expr = build::mk_expr(cx, span, self_match_expr);
+
+    // Find the position of the variant of `other` first, when it may
+    // differ from that of `self`.
+    let mut stmts = ~[];
+    if variant_count > 1 {
+        let index_arms = do enum_definition.variants.mapi |i, variant| {
+            let index_pat = create_enum_variant_wild_pattern(cx,
+                                                             span,
+                                                             variant);
+            let index_expr = build::mk_uint(cx, span, i);
+            ast::arm {
+                pats: ~[ index_pat ],
+                guard: None,
+                body: build::mk_simple_block(cx, span, index_expr)
+            }
+        };
+        let other_expr = build::mk_path(cx, span, ~[ other_ident ]);
+        let other_expr = build::mk_unary(cx, span, deref, other_expr);
+        let index_match_expr = expr_match(other_expr, index_arms);
+        let index_match_expr = build::mk_expr(cx, span, index_match_expr);
+        stmts.push(build::mk_local(cx, span, false, other_index_ident,
+                                   index_match_expr));
+    }
+    let body = build::mk_block(cx, span, ~[], stmts, Some(self_match_expr));
+
+    // Create the method.
+    create_eq_method(cx, span, method_ident, type_ident, ty_params, body)
+}
+
 fn expand_deriving_iter_bytes_enum_method(cx: ext_ctxt,
                                           span: span,
                                           enum_definition: &enum_def)
@@ -871,3 +1754,199 @@ fn expand_deriving_iter_bytes_enum_method(cx: ext_ctxt,
     create_iter_bytes_method(cx, span, ~[ self_match_stmt ])
 }
 
+fn expand_deriving_clone_struct_method(cx: ext_ctxt,
+                                       span: span,
+                                       struct_def: &struct_def,
+                                       type_ident: ident,
+                                       ty_params: &[ty_param])
+                                    -> @method {
+    let self_ident = cx.ident_of(~"self");
+
+    // A unit-like struct is its own clone.
+    if struct_def.fields.len() == 0 {
+        let self_expr = build::mk_path(cx, span, ~[ type_ident ]);
+        return create_clone_method(cx,
+                                   span,
+                                   type_ident,
+                                   ty_params,
+                                   self_expr);
+    }
+
+    let body = match struct_def.fields[0].node.kind {
+        named_field(*) => {
+            // Create a struct literal cloning each field.
+            let fields = do struct_def.fields.map |struct_field| {
+                let ident = match struct_field.node.kind {
+                    named_field(ident, _, _) => ident,
+                    unnamed_field => {
+                        cx.span_bug(span, ~"unexpected unnamed field");
+                    }
+                };
+
+                // Create the accessor for this field.
+                let self_field = build::mk_access(cx,
+                                                  span,
+                                                  ~[ self_ident ],
+                                                  ident);
+
+                // Call the substructure method.
+                let ex = call_substructure_clone_method(cx, span, self_field);
+                { ident: ident, ex: ex }
+            };
+            build::mk_struct_e(cx, span, ~[ type_ident ], fields)
+        }
+        unnamed_field => {
+            // A tuple struct: bind each field by reference and call the
+            // constructor with their clones.
+            let args = do vec::from_fn(struct_def.fields.len()) |i| {
+                clone_bound_field(cx, span, ~"__self", i)
+            };
+            let call = build::mk_call(cx, span, ~[ type_ident ], args);
+            create_tuple_struct_match(cx, span, struct_def, type_ident,
+                                      self_ident, ~"__self",
+                                      build::mk_simple_block(cx, span, call))
+        }
+    };
+
+    // Create the method itself.
+    create_clone_method(cx, span, type_ident, ty_params, body)
+}
+
+fn expand_deriving_clone_enum_method(cx: ext_ctxt,
+                                     span: span,
+                                     enum_definition: &enum_def,
+                                     type_ident: ident,
+                                     ty_params: &[ty_param])
+                                  -> @method {
+    // Create the arms of the match in the method body.
+    let arms = do enum_definition.variants.map |variant| {
+        // Create the matching pattern.
+        let pat = create_enum_variant_pattern(cx, span, variant, ~"__self");
+
+        // Rebuild the variant from the clones of its fields.
+        let variant_ident = variant.node.name;
+        let body = match variant.node.kind {
+            tuple_variant_kind(ref variant_args) => {
+                if variant_args.len() == 0 {
+                    build::mk_path(cx, span, ~[ variant_ident ])
+                } else {
+                    let args = do vec::from_fn(variant_args.len()) |i| {
+                        clone_bound_field(cx, span, ~"__self", i)
+                    };
+                    build::mk_call(cx, span, ~[ variant_ident ], args)
+                }
+            }
+            struct_variant_kind(struct_def) => {
+                let fields = do struct_def.fields.mapi |i, struct_field| {
+                    let ident = match struct_field.node.kind {
+                        named_field(ident, _, _) => ident,
+                        unnamed_field => {
+                            cx.span_bug(span, ~"unexpected unnamed field");
+                        }
+                    };
+                    {
+                        ident: ident,
+                        ex: clone_bound_field(cx, span, ~"__self", i)
+                    }
+                };
+                build::mk_struct_e(cx, span, ~[ variant_ident ], fields)
+            }
+            enum_variant_kind(*) => {
+                cx.span_unimpl(span, ~"enum variants for `deriving`");
+            }
+        };
+
+        // Create the arm.
+        ast::arm {
+            pats: ~[ pat ],
+            guard: None,
+            body: build::mk_simple_block(cx, span, body),
+        }
+    };
+
+    // Create the method body.
+    let self_ident = cx.ident_of(~"self");
+    let self_expr = build::mk_path(cx, span, ~[ self_ident ]);
+    let self_expr = build::mk_unary(cx, span, deref, self_expr);
+    let self_match_expr = expr_match(self_expr, arms);
+    let self_match_expr = build::mk_expr(cx, span, self_match_expr);
+
+    // Create the method.
+    create_clone_method(cx, span, type_ident, ty_params, self_match_expr)
+}
+
+fn expand_deriving_to_str_struct_method(cx: ext_ctxt,
+                                        span: span,
+                                        struct_def: &struct_def,
+                                        type_ident: ident)
+                                     -> @method {
+    let type_name = cx.str_of(type_ident);
+
+    let body = if is_tuple_struct(struct_def) {
+        // Format the fields bound by destructuring `self` as `Name(a, b)`.
+        let tys = struct_def.fields.map(|f| f.node.ty);
+        let fields = create_to_str_bound_fields(cx, span, tys);
+        let concat_expr = create_to_str_concat_expr(cx, span, type_name,
+                                                    ~"(", fields, ~")");
+        let self_ident = cx.ident_of(~"self");
+        create_tuple_struct_match(cx, span, struct_def, type_ident,
+                                  self_ident, ~"__self",
+                                  build::mk_simple_block(cx, span,
+                                                         concat_expr))
+    } else {
+        // Format the fields as `Name { a: x, b: y }`.
+        let fields = create_to_str_named_fields(cx, span, struct_def, false);
+        create_to_str_concat_expr(cx, span, type_name, ~" { ", fields, ~" }")
+    };
+
+    // Create the method itself.
+    create_to_str_method(cx, span, body)
+}
+
+fn expand_deriving_to_str_enum_method(cx: ext_ctxt,
+                                      span: span,
+                                      enum_definition: &enum_def)
+                                   -> @method {
+    // Create the arms of the match in the method body.
+    let arms = do enum_definition.variants.map |variant| {
+        // Create the matching pattern.
+        let pat = create_enum_variant_pattern(cx, span, variant, ~"__self");
+
+        // Format the variant and its fields.
+        let variant_name = cx.str_of(variant.node.name);
+        let body = match variant.node.kind {
+            tuple_variant_kind(ref variant_args) => {
+                let tys = variant_args.map(|arg| arg.ty);
+                let fields = create_to_str_bound_fields(cx, span, tys);
+                create_to_str_concat_expr(cx, span, variant_name, ~"(",
+                                          fields, ~")")
+            }
+            struct_variant_kind(struct_def) => {
+                let fields = create_to_str_named_fields(cx, span, struct_def,
+                                                        true);
+                create_to_str_concat_expr(cx, span, variant_name, ~" { ",
+                                          fields, ~" }")
+            }
+            enum_variant_kind(*) => {
+                cx.span_unimpl(span, ~"enum variants for `deriving`");
+            }
+        };
+
+        // Create the arm.
+        ast::arm {
+            pats: ~[ pat ],
+            guard: None,
+            body: build::mk_simple_block(cx, span, body),
+        }
+    };
+
+    // Create the method body.
+    let self_ident = cx.ident_of(~"self");
+    let self_expr = build::mk_path(cx, span, ~[ self_ident ]);
+    let self_expr = build::mk_unary(cx, span, deref, self_expr);
+    let self_match_expr = expr_match(self_expr, arms);
+    let self_match_expr = build::mk_expr(cx, span, self_match_expr);
+
+    // Create the method.
+    create_to_str_method(cx, span, self_match_expr)
+}
@@ -10,13 +10,16 @@
 
 use core::prelude::*;
 
-use ast::{crate, expr_, expr_mac, mac_invoc_tt};
+use ast::{crate, expr_, expr_mac, mac_invoc_tt, view_item_use};
 use ast::{tt_delim, tt_tok, item_mac, stmt_, stmt_mac, stmt_expr, stmt_semi};
 use ast;
-use codemap::{span, ExpandedFrom};
+use attr;
+use codemap::{span, spanned, ExpandedFrom};
 use ext::base::*;
 use fold::*;
 use parse::{parser, parse_expr_from_source_str, new_parser_from_tts};
+use parse::parse_item_from_source_str;
+use print::pprust;
 
 use core::option;
 use core::vec;
@@ -201,6 +204,15 @@ pub fn expand_item_mac(exts: HashMap<~str, SyntaxExtension>,
             option::chain(item_maker(), |i| {fld.fold_item(i)}),
         MRDef(ref mdef) => {
             exts.insert((*mdef).name, (*mdef).ext);
+            if attr::contains_name(attr::attr_metas(/*bad*/copy it.attrs),
+                                   ~"macro_export") {
+                // Kept as source so that a crate linking this one can
+                // parse it back into its own interner
+                cx.export_macro(fmt!("%s! %s(%s)", *extname,
+                                     cx.str_of(it.ident),
+                                     pprust::tts_to_str(
+                                         tts, cx.parse_sess().interner)));
+            }
             None
         }
     };
@@ -291,8 +303,13 @@ pub fn core_macros() -> ~str {
         ($msg: expr) => (
             ::core::sys::begin_unwind($msg, file!().to_owned(), line!())
         );
+        ($fmt: expr, $( $arg:expr ),+) => (
+            ::core::sys::begin_unwind(fmt!( $fmt, $($arg),+ ),
+                                      file!().to_owned(), line!())
+        );
         () => (
-            die!(~\"explicit failure\")
+            ::core::sys::begin_unwind(~\"explicit failure\",
+                                      file!().to_owned(), line!())
         )
     )
 
@@ -300,8 +317,13 @@ pub fn core_macros() -> ~str {
         ($msg: expr) => (
             ::core::sys::begin_unwind($msg, file!().to_owned(), line!())
         );
+        ($fmt: expr, $( $arg:expr ),+) => (
+            ::core::sys::begin_unwind(fmt!( $fmt, $($arg),+ ),
+                                      file!().to_owned(), line!())
+        );
         () => (
-            die!(~\"explicit failure\")
+            ::core::sys::begin_unwind(~\"explicit failure\",
+                                      file!().to_owned(), line!())
         )
     )
 
@@ -310,9 +332,27 @@ pub fn core_macros() -> ~str {
             if !$cond {
                 die!(~\"assertion failed: \" + stringify!($cond))
             }
+        };
+        ($cond:expr, $( $arg:expr ),+) => {
+            if !$cond {
+                die!(fmt!( $($arg),+ ))
+            }
         }
     )
 
+    macro_rules! assert_eq (
+        ($left:expr , $right:expr) => ({
+            let __assert_eq_left = $left;
+            let __assert_eq_right = $right;
+            if !(__assert_eq_left == __assert_eq_right) {
+                let values = fmt!(\"(left: `%?`, right: `%?`)\",
+                                  __assert_eq_left, __assert_eq_right);
+                die!(~\"assertion failed: `\" + stringify!($left) +
+                     \" == \" + stringify!($right) + \"` \" + values)
+            }
+        })
+    )
+
     macro_rules! condition (
 
         { $c:ident: $in:ty -> $out:ty; } => {
@@ -333,7 +373,8 @@ pub fn core_macros() -> ~str {
 }
 
 pub fn expand_crate(parse_sess: parse::parse_sess,
-                    cfg: ast::crate_cfg, c: @crate) -> @crate {
+                    cfg: ast::crate_cfg, macro_loader: MacroLoader,
+                    c: @crate) -> @crate {
     let exts = syntax_expander_table();
     let afp = default_ast_fold();
     let cx: ext_ctxt = mk_ctxt(parse_sess, cfg);
@@ -354,8 +395,38 @@ pub fn expand_crate(parse_sess: parse::parse_sess,
     // as it registers all the core macros as expanders.
     f.fold_expr(cm);
 
-    let res = @f.fold_crate(*c);
-    return res;
+    // Likewise for the macros exported by the crates this one links
+    for c.node.module.view_items.each |vi| {
+        match vi.node {
+          view_item_use(ident, _, _) => {
+            let name = fmt!("<%s macros>", cx.str_of(ident));
+            for macro_loader(*vi).each |src| {
+                match parse_item_from_source_str(copy name, @copy *src,
+                                                 copy cfg, ~[],
+                                                 parse_sess) {
+                  Some(it) => { f.fold_item(it); }
+                  None => cx.span_bug(vi.span, ~"bad exported macro")
+                }
+            }
+          }
+          _ => ()
+        }
+    }
+
+    let res = f.fold_crate(*c);
+
+    // Record the exported macros where the metadata encoder will find them
+    let exported = do cx.exported_macros().map |src| {
+        attr::mk_attr(attr::mk_name_value_item_str(~"exported_macro",
+                                                   copy *src))
+    };
+    return @spanned {
+        node: ast::crate_ {
+            attrs: vec::append(copy res.node.attrs, exported),
+            .. copy res.node
+        },
+        .. res
+    };
 }
 // Local Variables:
 // mode: rust
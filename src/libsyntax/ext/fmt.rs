@@ -17,6 +17,7 @@
  */
 
 use core::prelude::*;
+use core::vec;
 
 use ast;
 use codemap::span;
@@ -31,10 +32,33 @@ pub fn expand_syntax_ext(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
     if args.len() == 0 {
         cx.span_fatal(sp, "fmt! takes at least 1 argument.");
     }
+    let pieces = parse_fmt(cx, args[0], "fmt!");
+    MRExpr(pieces_to_expr(cx, sp, pieces, args))
+}
+
+/*
+ * write_fmt!(wr, "...", args) writes the same output as
+ * wr.write_str(fmt!("...", args)), but writes each piece to the
+ * io::Writer as soon as it is converted instead of building the whole
+ * string first. Literal text and plain %s conversions are written without
+ * allocating at all.
+ */
+pub fn expand_write_fmt(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
+    -> base::MacResult {
+    let args = get_exprs_from_tts(cx, copy tts);
+    if args.len() < 2 {
+        cx.span_fatal(sp, "write_fmt! takes a writer and a format string.");
+    }
+    let pieces = parse_fmt(cx, args[1], "write_fmt!");
+    MRExpr(pieces_to_write_expr(cx, sp, args[0], pieces, vec::tail(args)))
+}
+
+fn parse_fmt(cx: ext_ctxt, fmt_expr: @ast::expr, name: &str) -> ~[Piece] {
     let fmt =
-        expr_to_str(cx, args[0],
-                    ~"first argument to fmt! must be a string literal.");
-    let fmtspan = args[0].span;
+        expr_to_str(cx, fmt_expr,
+                    fmt!("format argument to %s must be a string literal.",
+                         name));
+    let fmtspan = fmt_expr.span;
     debug!("Format string:");
     log(debug, fmt);
     fn parse_fmt_err_(cx: ext_ctxt, sp: span, msg: &str) -> ! {
@@ -43,17 +67,57 @@ pub fn expand_syntax_ext(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
     let parse_fmt_err = fn@(s: &str) -> ! {
         parse_fmt_err_(cx, fmtspan, s)
     };
-    let pieces = parse_fmt_string(fmt, parse_fmt_err);
-    MRExpr(pieces_to_expr(cx, sp, pieces, args))
+    parse_fmt_string(fmt, parse_fmt_err)
+}
+
+fn pieces_to_expr(cx: ext_ctxt, sp: span,
+                  pieces: ~[Piece], args: ~[@ast::expr])
+   -> @ast::expr {
+    let fmt_sp = args[0].span;
+    let piece_exprs = pieces_to_exprs(cx, sp, pieces, args, "fmt!", false);
+    let arg_vec = mk_fixed_vec_e(cx, fmt_sp, piece_exprs);
+    return mk_call_global(cx,
+                          fmt_sp,
+                          ~[cx.parse_sess().interner.intern(@~"str"),
+                            cx.parse_sess().interner.intern(@~"concat")],
+                          ~[arg_vec]);
+}
+
+// Expands to
+// { let __write_fmt_wr = wr; ::extfmt::rt::write_str(__write_fmt_wr, p0);
+//   ... }
+// so the arguments are evaluated in between writing the pieces.
+fn pieces_to_write_expr(cx: ext_ctxt, sp: span, wr: @ast::expr,
+                        pieces: ~[Piece], args: ~[@ast::expr])
+   -> @ast::expr {
+    let fmt_sp = args[0].span;
+    let intr = cx.parse_sess().interner;
+    let wr_ident = intr.intern(@~"__write_fmt_wr");
+    let write_path = ~[intr.intern(@~"extfmt"), intr.intern(@~"rt"),
+                       intr.intern(@~"write_str")];
+    let mut stmts = ~[mk_local(cx, sp, false, wr_ident, wr)];
+    for pieces_to_exprs(cx, sp, pieces, args, "write_fmt!", true).each |e| {
+        let wr_expr = mk_path(cx, sp, ~[wr_ident]);
+        let call = mk_call_global(cx, fmt_sp, copy write_path,
+                                  ~[wr_expr, *e]);
+        stmts.push(mk_stmt(cx, fmt_sp, call));
+    }
+    mk_block(cx, sp, ~[], stmts, None)
 }
 
 // FIXME (#2249): A lot of these functions for producing expressions can
 // probably be factored out in common with other code that builds
 // expressions.  Also: Cleanup the naming of these functions.
 // Note: Moved many of the common ones to build.rs --kevina
-fn pieces_to_expr(cx: ext_ctxt, sp: span,
-                  pieces: ~[Piece], args: ~[@ast::expr])
-   -> @ast::expr {
+
+// Builds an expression for each piece of the output. `args[0]` is the
+// format string. With `borrowed`, literal text is a static string and a
+// plain %s conversion is the argument itself, which suits writing the
+// pieces out one at a time; otherwise every piece is an owned string.
+fn pieces_to_exprs(cx: ext_ctxt, sp: span,
+                   pieces: ~[Piece], args: ~[@ast::expr], name: &str,
+                   borrowed: bool)
+   -> ~[@ast::expr] {
     fn make_path_vec(cx: ext_ctxt, ident: @~str) -> ~[ast::ident] {
         let intr = cx.parse_sess().interner;
         return ~[intr.intern(@~"extfmt"), intr.intern(@~"rt"),
@@ -66,7 +130,9 @@ fn pieces_to_expr(cx: ext_ctxt, sp: span,
     // Produces an AST expression that represents a RT::conv record,
     // which tells the RT::conv* functions how to perform the conversion
 
-    fn make_rt_conv_expr(cx: ext_ctxt, sp: span, cnv: Conv) -> @ast::expr {
+    fn make_rt_conv_expr(cx: ext_ctxt, sp: span, cnv: Conv,
+                         width_arg: Option<@ast::expr>,
+                         prec_arg: Option<@ast::expr>) -> @ast::expr {
         fn make_flags(cx: ext_ctxt, sp: span, flags: ~[Flag]) -> @ast::expr {
             let mut tmp_expr = make_rt_path_expr(cx, sp, @~"flag_none");
             for flags.each |f| {
@@ -82,7 +148,10 @@ fn pieces_to_expr(cx: ext_ctxt, sp: span,
             }
             return tmp_expr;
         }
-        fn make_count(cx: ext_ctxt, sp: span, cnt: Count) -> @ast::expr {
+        // A count given by '*' takes its value from the argument consumed
+        // for it, which must be a uint
+        fn make_count(cx: ext_ctxt, sp: span, cnt: Count,
+                      arg: Option<@ast::expr>) -> @ast::expr {
             match cnt {
               CountImplied => {
                 return make_rt_path_expr(cx, sp, @~"CountImplied");
@@ -93,6 +162,11 @@ fn pieces_to_expr(cx: ext_ctxt, sp: span,
                 let count_is_args = ~[count_lit];
                 return mk_call_global(cx, sp, count_is_path, count_is_args);
               }
+              CountIsNextParam => {
+                let arg = arg.get();
+                let count_is_path = make_path_vec(cx, @~"CountIs");
+                return mk_call_global(cx, arg.span, count_is_path, ~[arg]);
+              }
               _ => cx.span_unimpl(sp, ~"unimplemented fmt! conversion")
             }
         }
@@ -126,23 +200,25 @@ fn pieces_to_expr(cx: ext_ctxt, sp: span,
             )
         }
         let rt_conv_flags = make_flags(cx, sp, cnv.flags);
-        let rt_conv_width = make_count(cx, sp, cnv.width);
-        let rt_conv_precision = make_count(cx, sp, cnv.precision);
+        let rt_conv_width = make_count(cx, sp, cnv.width, width_arg);
+        let rt_conv_precision = make_count(cx, sp, cnv.precision, prec_arg);
         let rt_conv_ty = make_ty(cx, sp, cnv.ty);
         make_conv_struct(cx, sp, rt_conv_flags, rt_conv_width,
                          rt_conv_precision, rt_conv_ty)
     }
     fn make_conv_call(cx: ext_ctxt, sp: span, conv_type: ~str, cnv: Conv,
-                      arg: @ast::expr) -> @ast::expr {
+                      arg: @ast::expr, width_arg: Option<@ast::expr>,
+                      prec_arg: Option<@ast::expr>) -> @ast::expr {
         let fname = ~"conv_" + conv_type;
         let path = make_path_vec(cx, @fname);
-        let cnv_expr = make_rt_conv_expr(cx, sp, cnv);
+        let cnv_expr = make_rt_conv_expr(cx, sp, cnv, width_arg, prec_arg);
         let args = ~[cnv_expr, arg];
         return mk_call_global(cx, arg.span, path, args);
     }
 
-    fn make_new_conv(cx: ext_ctxt, sp: span, cnv: Conv, arg: @ast::expr) ->
-       @ast::expr {
+    fn make_new_conv(cx: ext_ctxt, sp: span, cnv: Conv, arg: @ast::expr,
+                     width_arg: Option<@ast::expr>,
+                     prec_arg: Option<@ast::expr>) -> @ast::expr {
         // FIXME: Move validation code into core::extfmt (Issue #2249)
 
         fn is_signed_type(cnv: Conv) -> bool {
@@ -184,33 +260,32 @@ fn pieces_to_expr(cx: ext_ctxt, sp: span,
         match cnv.width {
           CountImplied => (),
           CountIs(_) => (),
+          CountIsNextParam => (),
           _ => cx.span_unimpl(sp, unsupported)
         }
         match cnv.precision {
           CountImplied => (),
           CountIs(_) => (),
+          CountIsNextParam => (),
           _ => cx.span_unimpl(sp, unsupported)
         }
+        let conv_call = |conv_type: ~str, arg: @ast::expr| {
+            make_conv_call(cx, arg.span, conv_type, cnv, arg, width_arg,
+                           prec_arg)
+        };
         match cnv.ty {
-          TyStr => return make_conv_call(cx, arg.span, ~"str", cnv, arg),
+          TyStr => return conv_call(~"str", arg),
           TyInt(sign) => match sign {
-            Signed => return make_conv_call(cx, arg.span, ~"int", cnv, arg),
-            Unsigned => {
-                return make_conv_call(cx, arg.span, ~"uint", cnv, arg)
-            }
+            Signed => return conv_call(~"int", arg),
+            Unsigned => return conv_call(~"uint", arg)
           },
-          TyBool => return make_conv_call(cx, arg.span, ~"bool", cnv, arg),
-          TyChar => return make_conv_call(cx, arg.span, ~"char", cnv, arg),
-          TyHex(_) => {
-            return make_conv_call(cx, arg.span, ~"uint", cnv, arg);
-          }
-          TyBits => return make_conv_call(cx, arg.span, ~"uint", cnv, arg),
-          TyOctal => return make_conv_call(cx, arg.span, ~"uint", cnv, arg),
-          TyFloat => {
-            return make_conv_call(cx, arg.span, ~"float", cnv, arg);
-          }
-          TyPoly => return make_conv_call(cx, arg.span, ~"poly", cnv,
-                       mk_addr_of(cx, sp, arg))
+          TyBool => return conv_call(~"bool", arg),
+          TyChar => return conv_call(~"char", arg),
+          TyHex(_) => return conv_call(~"uint", arg),
+          TyBits => return conv_call(~"uint", arg),
+          TyOctal => return conv_call(~"uint", arg),
+          TyFloat => return conv_call(~"float", arg),
+          TyPoly => return conv_call(~"poly", mk_addr_of(cx, sp, arg))
         }
     }
     fn log_conv(c: Conv) {
@@ -261,6 +336,15 @@ fn pieces_to_expr(cx: ext_ctxt, sp: span,
           TyPoly => debug!("type: poly")
         }
     }
+    // A %s with no flags, width or precision copies the string unchanged
+    fn is_plain_str(cnv: Conv) -> bool {
+        let implied = |c| match c { CountImplied => true, _ => false };
+        match cnv.ty {
+          TyStr => cnv.param.is_none() && cnv.flags.is_empty() &&
+              implied(cnv.width) && implied(cnv.precision),
+          _ => false
+        }
+    }
     let fmt_sp = args[0].span;
     let mut n = 0u;
     let mut piece_exprs = ~[];
@@ -268,37 +352,53 @@ fn pieces_to_expr(cx: ext_ctxt, sp: span,
     for pieces.each |pc| {
         match *pc {
           PieceString(ref s) => {
-            piece_exprs.push(mk_uniq_str(cx, fmt_sp, (*s)))
+            if borrowed {
+                piece_exprs.push(mk_base_str(cx, fmt_sp, copy *s))
+            } else {
+                piece_exprs.push(mk_uniq_str(cx, fmt_sp, copy *s))
+            }
           }
           PieceConv(conv) => {
-            n += 1u;
-            if n >= nargs {
-                cx.span_fatal(sp,
-                              ~"not enough arguments to fmt! " +
-                                  ~"for the given format string");
-            }
+            // As in C, a '*' width or precision takes its argument before
+            // the one being converted
+            let next_arg = || {
+                n += 1u;
+                if n >= nargs {
+                    cx.span_fatal(sp,
+                                  fmt!("not enough arguments to %s", name) +
+                                      ~" for the given format string");
+                }
+                args[n]
+            };
+            let width_arg = match conv.width {
+              CountIsNextParam => Some(next_arg()),
+              _ => None
+            };
+            let prec_arg = match conv.precision {
+              CountIsNextParam => Some(next_arg()),
+              _ => None
+            };
+            let arg_expr = next_arg();
             debug!("Building conversion:");
             log_conv(conv);
-            let arg_expr = args[n];
-            let c_expr = make_new_conv(cx, fmt_sp, conv, arg_expr);
-            piece_exprs.push(c_expr);
+            if borrowed && is_plain_str(conv) {
+                piece_exprs.push(arg_expr);
+            } else {
+                let c_expr = make_new_conv(cx, fmt_sp, conv, arg_expr,
+                                           width_arg, prec_arg);
+                piece_exprs.push(c_expr);
+            }
           }
         }
     }
-    let expected_nargs = n + 1u; // n conversions + the fmt string
+    let expected_nargs = n + 1u; // n arguments + the fmt string
 
     if expected_nargs < nargs {
         cx.span_fatal
-            (sp, fmt!("too many arguments to fmt!. found %u, expected %u",
-                           nargs, expected_nargs));
+            (sp, fmt!("too many arguments to %s. found %u, expected %u",
+                      name, nargs, expected_nargs));
     }
-
-    let arg_vec = mk_fixed_vec_e(cx, fmt_sp, piece_exprs);
-    return mk_call_global(cx,
-                          fmt_sp,
-                          ~[cx.parse_sess().interner.intern(@~"str"),
-                            cx.parse_sess().interner.intern(@~"concat")],
-                          ~[arg_vec]);
+    move piece_exprs
 }
 //
 // Local Variables:
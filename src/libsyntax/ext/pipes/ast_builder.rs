@@ -212,6 +212,7 @@ pub impl ext_ctxt: ext_ctxt_ast_builder {
             expr: Some(e),
             id: self.next_id(),
             rules: ast::default_blk,
+            attrs: ~[],
         };
 
         spanned { node: blk, span: dummy_sp() }
@@ -9,10 +9,10 @@
 // except according to those terms.
 
 use codemap;
-use codemap::{FileMap, Loc, Pos, span};
+use codemap::{ExpandedFrom, FileMap, Loc, Pos, span};
 use ext::base::*;
 use ext::base;
-use ext::build::{mk_base_vec_e, mk_uint, mk_u8, mk_base_str};
+use ext::build::{mk_base_str, mk_slice_vec_e, mk_u8, mk_uint};
 use print::pprust;
 
 use core::io;
@@ -21,11 +21,13 @@ use core::result;
 use core::str;
 use core::vec;
 
-/* line!(): expands to the current line number */
+/* line!(): expands to the current line number. Like col!() and file!(),
+ * when used inside a macro it refers to where the outermost macro was
+ * invoked. */
 pub fn expand_line(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
     -> base::MacResult {
     base::check_zero_tts(cx, sp, tts, "line!");
-    let loc = cx.codemap().lookup_char_pos(sp.lo);
+    let loc = cx.codemap().lookup_char_pos(topmost_call_site(cx).lo);
     base::MRExpr(mk_uint(cx, sp, loc.line))
 }
 
@@ -33,7 +35,7 @@ pub fn expand_line(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
 pub fn expand_col(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
     -> base::MacResult {
     base::check_zero_tts(cx, sp, tts, "col!");
-    let loc = cx.codemap().lookup_char_pos(sp.lo);
+    let loc = cx.codemap().lookup_char_pos(topmost_call_site(cx).lo);
     base::MRExpr(mk_uint(cx, sp, loc.col.to_uint()))
 }
 
@@ -44,10 +46,22 @@ pub fn expand_file(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
     -> base::MacResult {
     base::check_zero_tts(cx, sp, tts, "file!");
     let Loc { file: @FileMap { name: filename, _ }, _ } =
-        cx.codemap().lookup_char_pos(sp.lo);
+        cx.codemap().lookup_char_pos(topmost_call_site(cx).lo);
     base::MRExpr(mk_base_str(cx, sp, filename))
 }
 
+// The span of the macro invocation, in the source being compiled, whose
+// expansion is underway
+fn topmost_call_site(cx: ext_ctxt) -> span {
+    let mut site = cx.call_site();
+    loop {
+        match site.expn_info {
+          Some(@ExpandedFrom({call_site: cs, _})) => site = cs,
+          None => return site
+        }
+    }
+}
+
 pub fn expand_stringify(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
     -> base::MacResult {
     let s = pprust::tts_to_str(tts, cx.parse_sess().interner);
@@ -74,29 +88,26 @@ pub fn expand_include(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
 pub fn expand_include_str(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
     -> base::MacResult {
     let file = get_single_str_from_tts(cx, sp, tts, "include_str!");
-    let res = io::read_whole_file_str(&res_rel_file(cx, sp, &Path(file)));
-    match res {
-      result::Ok(_) => { /* Continue. */ }
-      result::Err(ref e) => {
-        cx.parse_sess().span_diagnostic.handler().fatal((*e));
-      }
+    let bytes = read_included_file(cx, sp, &Path(file));
+    if !str::is_utf8(bytes) {
+        cx.span_fatal(sp, fmt!("include_str! file %s is not UTF-8", file));
     }
-
-    base::MRExpr(mk_base_str(cx, sp, result::unwrap(res)))
+    base::MRExpr(mk_base_str(cx, sp, str::from_bytes(bytes)))
 }
 
 pub fn expand_include_bin(cx: ext_ctxt, sp: span, tts: ~[ast::token_tree])
     -> base::MacResult {
     let file = get_single_str_from_tts(cx, sp, tts, "include_bin!");
-    match io::read_whole_file(&res_rel_file(cx, sp, &Path(file))) {
-      result::Ok(src) => {
-        let u8_exprs = vec::map(src, |char| {
-            mk_u8(cx, sp, *char)
-        });
-        base::MRExpr(mk_base_vec_e(cx, sp, u8_exprs))
-      }
+    let bytes = read_included_file(cx, sp, &Path(file));
+    let u8_exprs = vec::map(bytes, |b| mk_u8(cx, sp, *b));
+    base::MRExpr(mk_slice_vec_e(cx, sp, u8_exprs))
+}
+
+fn read_included_file(cx: ext_ctxt, sp: span, file: &Path) -> ~[u8] {
+    match io::read_whole_file(&res_rel_file(cx, sp, file)) {
+      result::Ok(move bytes) => move bytes,
       result::Err(ref e) => {
-        cx.parse_sess().span_diagnostic.handler().fatal((*e))
+        cx.span_fatal(sp, fmt!("couldn't read %s: %s", file.to_str(), *e))
       }
     }
 }
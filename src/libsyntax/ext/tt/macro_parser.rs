@@ -353,7 +353,20 @@ pub fn parse(sess: parse_sess,
                 return failure(sp, ~"Unexpected end of macro invocation");
             }
         } else {
-            if (bb_eis.len() > 0u && next_eis.len() > 0u)
+            if bb_eis.len() > 1u && next_eis.len() == 0u
+                && same_binder(bb_eis) {
+                // the same variable, reached through different numbers of
+                // trips around the enclosing repetitions
+                let bind = match bb_eis[0].elts[bb_eis[0].idx].node {
+                  match_nonterminal(bind, _, _) => bind,
+                  _ => die!()
+                };
+                return error(sp, fmt!(
+                    "ambiguous repetition: this token could be matched by \
+                     '%s' in either an inner or an outer repetition; \
+                     separate the repetitions with a separator token",
+                    *sess.interner.get(bind)));
+            } else if (bb_eis.len() > 0u && next_eis.len() > 0u)
                 || bb_eis.len() > 1u {
                 let nts = str::connect(vec::map(bb_eis, |ei| {
                     match ei.elts[ei.idx].node {
@@ -400,6 +413,18 @@ pub fn parse(sess: parse_sess,
     }
 }
 
+// Whether all of the items are about to match the same variable
+fn same_binder(eis: &[earley_item]) -> bool {
+    fn binder(ei: &earley_item) -> Option<ident> {
+        match ei.elts[ei.idx].node {
+          match_nonterminal(bind, _, _) => Some(bind),
+          _ => None
+        }
+    }
+    let first = binder(&eis[0]);
+    first.is_some() && eis.all(|ei| binder(ei) == first)
+}
+
 pub fn parse_nt(p: Parser, name: ~str) -> nonterminal {
     match name {
       ~"item" => match p.parse_item(~[]) {
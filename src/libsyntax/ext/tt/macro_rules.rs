@@ -11,7 +11,7 @@
 use core::prelude::*;
 
 use ast::{ident, matcher_, matcher, match_tok, match_nonterminal, match_seq};
-use ast::{tt_delim};
+use ast::{token_tree, tt_delim, tt_nonterminal, tt_seq, tt_tok};
 use ast;
 use codemap::{span, spanned, dummy_sp};
 use ext::base::{ext_ctxt, MacResult, MRAny, MRDef, MacroDef, NormalTT};
@@ -26,6 +26,8 @@ use parse::token::{FAT_ARROW, SEMI, LBRACE, RBRACE, nt_matchers, nt_tt};
 use print;
 
 use core::io;
+use core::uint;
+use core::vec;
 use std::oldmap::HashMap;
 
 pub fn add_new_extension(cx: ext_ctxt, sp: span, name: ident,
@@ -68,6 +70,17 @@ pub fn add_new_extension(cx: ext_ctxt, sp: span, name: ident,
       _ => cx.span_bug(sp, ~"wrong-structured rhs")
     };
 
+    // Reject transcribers that could never expand before anyone uses them
+    for lhses.eachi() |i, lhs| {
+        match (*lhs, rhses[i]) {
+          (@matched_nonterminal(nt_matchers(ref mtcs)),
+           @matched_nonterminal(nt_tt(@ref tt))) => {
+            check_rhs(cx, binder_depths(*mtcs), *tt);
+          }
+          _ => cx.span_bug(sp, ~"wrong-structured macro arm")
+        }
+    }
+
     // Given `lhses` and `rhses`, this is the new macro we create
     fn generic_extension(cx: ext_ctxt, sp: span, name: ident,
                          arg: ~[ast::token_tree],
@@ -135,6 +148,69 @@ pub fn add_new_extension(cx: ext_ctxt, sp: span, name: ident,
         cx.span_fatal(best_fail_spot, best_fail_msg);
     }
 
+    // The number of repetitions each variable is matched inside of
+    fn binder_depths(mtcs: &[matcher]) -> HashMap<ident, uint> {
+        fn walk(mtcs: &[matcher], depth: uint,
+                depths: HashMap<ident, uint>) {
+            for mtcs.each |m| {
+                match m.node {
+                  match_tok(_) => (),
+                  match_nonterminal(bind, _, _) => {
+                      depths.insert(bind, depth);
+                  }
+                  match_seq(ref ms, _, _, _, _) => {
+                      walk(*ms, depth + 1u, depths);
+                  }
+                }
+            }
+        }
+        let depths = HashMap();
+        walk(mtcs, 0u, depths);
+        depths
+    }
+
+    // Check that every variable in a transcriber is bound, and is used
+    // inside at least as many repetitions as it was matched inside of,
+    // and that every repetition contains a variable to drive it. Returns
+    // the deepest repetition any variable in `tt` was matched inside of.
+    fn check_rhs(cx: ext_ctxt, depths: HashMap<ident, uint>,
+                 tt: token_tree) -> uint {
+        fn walk(cx: ext_ctxt, depths: HashMap<ident, uint>,
+                tt: token_tree, depth: uint) -> uint {
+            match tt {
+              tt_tok(*) => 0u,
+              tt_delim(ref tts) => {
+                vec::foldl(0u, *tts, |max, tt| {
+                    uint::max(max, walk(cx, depths, *tt, depth))
+                })
+              }
+              tt_seq(sp, ref tts, _, _) => {
+                let inner = vec::foldl(0u, *tts, |max, tt| {
+                    uint::max(max, walk(cx, depths, *tt, depth + 1u))
+                });
+                if inner <= depth {
+                    cx.span_fatal(sp, ~"attempted to repeat an expression \
+                                        containing no syntax variables \
+                                        matched as repeating at this depth");
+                }
+                inner
+              }
+              tt_nonterminal(sp, name) => match depths.find(&name) {
+                Some(d) if d > depth => {
+                    cx.span_fatal(sp, fmt!(
+                        "variable '%s' is still repeating at this depth: \
+                         it is matched inside %u repetitions but used \
+                         inside %u", cx.str_of(name), d, depth));
+                }
+                Some(d) => d,
+                None => cx.span_fatal(sp, fmt!("unknown macro variable '%s'",
+                                               cx.str_of(name)))
+              }
+            }
+        }
+        walk(cx, depths, tt, 0u)
+    }
+
     let exp: @fn(ext_ctxt, span, ~[ast::token_tree]) -> MacResult =
         |cx, sp, arg| generic_extension(cx, sp, name, arg, lhses, rhses);
 
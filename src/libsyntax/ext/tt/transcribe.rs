@@ -253,7 +253,7 @@ pub fn tt_next_token(&&r: tt_reader) -> TokenAndSpan {
               }
               matched_seq(*) => {
                 r.sp_diag.span_fatal(
-                    copy r.cur_span, /* blame the macro writer */
+                    sp, /* blame the macro writer */
                     fmt!("variable '%s' is still repeating at this depth",
                          *r.interner.get(ident)));
               }
@@ -316,6 +316,7 @@ pub fn noop_fold_block(b: blk_, fld: ast_fold) -> blk_ {
         expr: b.expr.map(|x| fld.fold_expr(*x)),
         id: fld.new_id(b.id),
         rules: b.rules,
+        attrs: b.attrs.map(|x| fold_attribute_(*x, fld)),
     }
 }
 
@@ -446,7 +447,10 @@ pub fn noop_fold_expr(e: expr_, fld: ast_fold) -> expr_ {
             expr_binary(binop, fld.fold_expr(lhs), fld.fold_expr(rhs))
           }
           expr_unary(binop, ohs) => expr_unary(binop, fld.fold_expr(ohs)),
-          expr_loop_body(f) => expr_loop_body(fld.fold_expr(f)),
+          expr_loop_body(f, opt_ident) => {
+            expr_loop_body(fld.fold_expr(f),
+                           option::map(&opt_ident, |x| fld.fold_ident(*x)))
+          }
           expr_do_body(f) => expr_do_body(fld.fold_expr(f)),
           expr_lit(_) => copy e,
           expr_cast(expr, ty) => expr_cast(fld.fold_expr(expr), ty),
@@ -455,8 +459,9 @@ pub fn noop_fold_expr(e: expr_, fld: ast_fold) -> expr_ {
             expr_if(fld.fold_expr(cond), fld.fold_block((*tr)),
                     option::map(&fl, |x| fld.fold_expr(*x)))
           }
-          expr_while(cond, ref body) => {
-            expr_while(fld.fold_expr(cond), fld.fold_block((*body)))
+          expr_while(cond, ref body, opt_ident) => {
+            expr_while(fld.fold_expr(cond), fld.fold_block((*body)),
+                       option::map(&opt_ident, |x| fld.fold_ident(*x)))
           }
           expr_loop(ref body, opt_ident) => {
               expr_loop(fld.fold_block((*body)),
@@ -552,6 +557,9 @@ pub fn noop_fold_ty(t: ty_, fld: ast_fold) -> ty_ {
         }),
       ty_tup(tys) => ty_tup(vec::map(tys, |ty| fld.fold_ty(*ty))),
       ty_path(path, id) => ty_path(fld.fold_path(path), fld.new_id(id)),
+      ty_bounded(ty, bounds) =>
+        ty_bounded(fld.fold_ty(ty),
+                   @bounds.map(|x| fold_ty_param_bound(*x, fld))),
       ty_fixed_length_vec(mt, vs) =>
         ty_fixed_length_vec(fold_mt(mt, fld), vs),
       ty_mac(ref mac) => ty_mac(fold_mac((*mac)))
@@ -501,18 +501,26 @@ fn scan_numeric_escape(rdr: string_reader, n_hex_digits: uint) -> char {
     return accum_int as char;
 }
 
+fn ident_start(c: char) -> bool {
+    (c >= 'a' && c <= 'z')
+        || (c >= 'A' && c <= 'Z')
+        || c == '_'
+        || (c > 'z' && char::is_XID_start(c))
+}
+
+fn ident_continue(c: char) -> bool {
+    (c >= 'a' && c <= 'z')
+        || (c >= 'A' && c <= 'Z')
+        || (c >= '0' && c <= '9')
+        || c == '_'
+        || (c > 'z' && char::is_XID_continue(c))
+}
+
 fn next_token_inner(rdr: string_reader) -> token::Token {
     let mut accum_str = ~"";
     let mut c = rdr.curr;
-    if (c >= 'a' && c <= 'z')
-        || (c >= 'A' && c <= 'Z')
-        || c == '_'
-        || (c > 'z' && char::is_XID_start(c)) {
-        while (c >= 'a' && c <= 'z')
-            || (c >= 'A' && c <= 'Z')
-            || (c >= '0' && c <= '9')
-            || c == '_'
-            || (c > 'z' && char::is_XID_continue(c)) {
+    if ident_start(c) {
+        while ident_continue(c) {
             str::push_char(&mut accum_str, c);
             bump(rdr);
             c = rdr.curr;
@@ -620,6 +628,18 @@ fn next_token_inner(rdr: string_reader) -> token::Token {
         bump(rdr);
         let mut c2 = rdr.curr;
         bump(rdr);
+
+        // A quote before an identifier with no closing quote after its
+        // first character names a loop, as in `'outer`
+        if ident_start(c2) && rdr.curr != '\'' {
+            let mut accum_str = str::from_char(c2);
+            while ident_continue(rdr.curr) {
+                str::push_char(&mut accum_str, rdr.curr);
+                bump(rdr);
+            }
+            return token::LIFETIME(rdr.interner.intern(@accum_str));
+        }
+
         if c2 == '\\' {
             let escaped = rdr.curr;
             bump(rdr);
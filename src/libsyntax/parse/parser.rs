@@ -48,7 +48,8 @@ use ast::{stmt_expr, stmt_semi, stmt_mac, struct_def, struct_field};
 use ast::{struct_immutable, struct_mutable, struct_variant_kind, subtract};
 use ast::{sty_box, sty_by_ref, sty_region, sty_static, sty_uniq, sty_value};
 use ast::{token_tree, trait_method, trait_ref, tt_delim, tt_seq, tt_tok};
-use ast::{tt_nonterminal, tuple_variant_kind, Ty, ty_, ty_bot, ty_box};
+use ast::{tt_nonterminal, tuple_variant_kind, Ty, ty_, ty_bot, ty_bounded};
+use ast::{ty_box};
 use ast::{ty_field, ty_fixed_length_vec, ty_closure, ty_bare_fn};
 use ast::{ty_infer, ty_mac, ty_method};
 use ast::{ty_nil, ty_param, ty_param_bound, ty_path, ty_ptr, ty_rec, ty_rptr};
@@ -77,7 +78,8 @@ use parse::obsolete::{ObsoleteSyntax, ObsoleteLowerCaseKindBounds};
 use parse::obsolete::{ObsoleteUnsafeBlock};
 use parse::prec::{as_prec, token_to_binop};
 use parse::token::{can_begin_expr, is_ident, is_ident_or_path};
-use parse::token::{is_plain_ident, INTERPOLATED, special_idents};
+use parse::token::{is_lifetime, is_plain_ident, INTERPOLATED};
+use parse::token::{special_idents};
 use parse::token;
 use parse::{new_sub_parser_from_file, next_node_id, parse_sess};
 use print::pprust::expr_to_str;
@@ -209,6 +211,7 @@ pub fn Parser(sess: parse_sess,
         reserved_keywords: token::reserved_keyword_table(),
         obsolete_set: HashMap(),
         mod_path_stack: ~[],
+        allow_object_bounds: true,
     }
 }
 
@@ -234,6 +237,9 @@ pub struct Parser {
     obsolete_set: HashMap<ObsoleteSyntax, ()>,
     /// Used to determine the path to externally loaded source files
     mut mod_path_stack: ~[~str],
+    /// Whether a colon after a pointer to a path type starts the bounds of
+    /// a trait object. It doesn't in `impl ~T : Trait`.
+    mut allow_object_bounds: bool,
 
     drop {} /* do not copy the parser; its state is tied to outside state */
 }
@@ -645,7 +651,7 @@ pub impl Parser {
         // rather than boxed ptrs.  But the special casing of str/vec is not
         // reflected in the AST type.
         let mt = self.parse_mt();
-        ctor(mt)
+        ctor(self.parse_object_bounds(mt))
     }
 
     fn parse_borrowed_pointee() -> ty_ {
@@ -668,7 +674,26 @@ pub impl Parser {
 
         let r = self.region_from_name(rname);
         let mt = self.parse_mt();
-        return ty_rptr(r, mt);
+        return ty_rptr(r, self.parse_object_bounds(mt));
+    }
+
+    // A path behind a pointer may be a trait, followed by the builtin kinds
+    // that the object promises: `~Writer:Owned`, `@Trait:Const`
+    fn parse_object_bounds(mt: mt) -> mt {
+        match mt.ty.node {
+            ty_path(*) if self.token == token::COLON &&
+                          self.allow_object_bounds => {
+                let lo = mt.ty.span.lo;
+                let bounds = self.parse_optional_ty_param_bounds();
+                let ty = @Ty {
+                    id: self.get_id(),
+                    node: ty_bounded(mt.ty, bounds),
+                    span: mk_sp(lo, self.last_span.hi)
+                };
+                mt { ty: ty, mutbl: mt.mutbl }
+            }
+            _ => mt
+        }
     }
 
     fn parse_arg_mode() -> mode {
@@ -1001,12 +1026,15 @@ pub impl Parser {
             return self.parse_if_expr();
         } else if self.eat_keyword(~"for") {
             return self.parse_sugary_call_expr(~"for", ForSugar,
-                                               expr_loop_body);
+                                               |e| expr_loop_body(e, None));
         } else if self.eat_keyword(~"do") {
             return self.parse_sugary_call_expr(~"do", DoSugar,
                                                expr_do_body);
         } else if self.eat_keyword(~"while") {
-            return self.parse_while_expr();
+            return self.parse_while_expr(None);
+        } else if is_lifetime(self.token) &&
+                  self.look_ahead(1) == token::COLON {
+            return self.parse_labeled_loop_expr();
         } else if self.eat_keyword(~"loop") {
             return self.parse_loop_expr();
         } else if self.eat_keyword(~"match") {
@@ -1074,11 +1102,7 @@ pub impl Parser {
                 ex = expr_ret(Some(e));
             } else { ex = expr_ret(None); }
         } else if self.eat_keyword(~"break") {
-            if is_ident(self.token) {
-                ex = expr_break(Some(self.parse_ident()));
-            } else {
-                ex = expr_break(None);
-            }
+            ex = expr_break(self.parse_opt_label());
             hi = self.span.hi;
         } else if self.eat_keyword(~"copy") {
             let e = self.parse_expr();
@@ -1645,6 +1669,7 @@ pub impl Parser {
             expr: Some(body),
             id: self.get_id(),
             rules: default_blk,
+            attrs: ~[],
         };
         let fakeblock = spanned(body.span.lo, body.span.hi,
                                 fakeblock);
@@ -1718,12 +1743,44 @@ pub impl Parser {
         }
     }
 
-    fn parse_while_expr() -> @expr {
+    fn parse_while_expr(opt_label: Option<ident>) -> @expr {
         let lo = self.last_span.lo;
         let cond = self.parse_expr();
         let body = self.parse_block_no_value();
         let mut hi = body.span.hi;
-        return self.mk_expr(lo, hi, expr_while(cond, body));
+        return self.mk_expr(lo, hi, expr_while(cond, body, opt_label));
+    }
+
+    // A loop named by the label in front of it, as in
+    // `'outer: for v.each |x| { ... }`
+    fn parse_labeled_loop_expr() -> @expr {
+        let lo = self.span.lo;
+        let label = match copy self.token {
+            token::LIFETIME(ident) => { self.bump(); ident }
+            _ => self.bug(~"expected a label")
+        };
+        self.expect(token::COLON);
+        if self.eat_keyword(~"loop") {
+            let body = self.parse_block_no_value();
+            self.mk_expr(lo, body.span.hi, expr_loop(body, Some(label)))
+        } else if self.eat_keyword(~"while") {
+            self.parse_while_expr(Some(label))
+        } else if self.eat_keyword(~"for") {
+            self.parse_sugary_call_expr(~"for", ForSugar,
+                                        |e| expr_loop_body(e, Some(label)))
+        } else {
+            self.fatal(~"expected `loop`, `while` or `for` after a label")
+        }
+    }
+
+    // The label after `break` or `loop`, written `'outer` or, in the older
+    // style, `outer`
+    fn parse_opt_label() -> Option<ident> {
+        match copy self.token {
+            token::LIFETIME(ident) => { self.bump(); Some(ident) }
+            _ if is_ident(self.token) => Some(self.parse_ident()),
+            _ => None
+        }
     }
 
     fn parse_loop_expr() -> @expr {
@@ -1755,11 +1812,7 @@ pub impl Parser {
         } else {
             // This is a 'continue' expression
             let lo = self.span.lo;
-            let ex = if is_ident(self.token) {
-                expr_again(Some(self.parse_ident()))
-            } else {
-                expr_again(None)
-            };
+            let ex = expr_again(self.parse_opt_label());
             let hi = self.span.hi;
             return self.mk_expr(lo, hi, ex);
         }
@@ -1831,6 +1884,7 @@ pub impl Parser {
                     expr: Some(expr),
                     id: self.get_id(),
                     rules: default_blk,
+                    attrs: ~[],
                 },
                 span: expr.span,
             };
@@ -1907,6 +1961,12 @@ pub impl Parser {
                         span, ~"expected an identifier or `_`"
                     )
                 }
+                if self.token != token::RBRACKET {
+                    self.span_fatal(
+                        subpat.span,
+                        ~"a `..` pattern must come last in a vector pattern"
+                    );
+                }
                 tail = Some(subpat);
                 break;
             }
@@ -2343,28 +2403,25 @@ pub impl Parser {
         return blk;
     }
 
+    // With `parse_attrs`, the block's inner attributes are returned for
+    // the enclosing item to take, rather than kept in the block
     fn parse_inner_attrs_and_block(parse_attrs: bool)
         -> (~[attribute], blk) {
 
         maybe_whole!(pair_empty self, nt_block);
 
-        fn maybe_parse_inner_attrs_and_next(p: Parser, parse_attrs: bool) ->
-            {inner: ~[attribute], next: ~[attribute]} {
-            if parse_attrs {
-                p.parse_inner_attrs_and_next()
-            } else {
-                {inner: ~[], next: ~[]}
-            }
-        }
-
         let lo = self.span.lo;
         if self.eat_keyword(~"unsafe") {
             self.obsolete(copy self.span, ObsoleteUnsafeBlock);
         }
         self.expect(token::LBRACE);
-        let {inner: move inner, next: move next} =
-            maybe_parse_inner_attrs_and_next(self, parse_attrs);
-        return (inner, self.parse_block_tail_(lo, default_blk, next));
+        if parse_attrs {
+            let {inner: move inner, next: move next} =
+                self.parse_inner_attrs_and_next();
+            (inner, self.parse_block_tail_(lo, default_blk, ~[], next))
+        } else {
+            (~[], self.parse_block_tail(lo, default_blk))
+        }
     }
 
     fn parse_block_no_value() -> blk {
@@ -2379,10 +2436,13 @@ pub impl Parser {
     // necessary, and this should take a qualifier.
     // some blocks start with "#{"...
     fn parse_block_tail(lo: BytePos, s: blk_check_mode) -> blk {
-        self.parse_block_tail_(lo, s, ~[])
+        let {inner: move inner, next: move next} =
+            self.parse_inner_attrs_and_next();
+        self.parse_block_tail_(lo, s, inner, next)
     }
 
     fn parse_block_tail_(lo: BytePos, s: blk_check_mode,
+                         +attrs: ~[attribute],
                          +first_item_attrs: ~[attribute]) -> blk {
         let mut stmts = ~[];
         let mut expr = None;
@@ -2479,6 +2539,7 @@ pub impl Parser {
             expr: expr,
             id: self.get_id(),
             rules: s,
+            attrs: attrs,
         };
         spanned(lo, hi, bloc)
     }
@@ -2827,8 +2888,11 @@ pub impl Parser {
         let ident = special_idents::clownshoes_extensions;
 
         // Parse the type. (If this is `impl trait for type`, however, this
-        // actually parses the trait.)
+        // actually parses the trait.) A colon after it starts the trait, not
+        // the bounds of an object type.
+        self.allow_object_bounds = false;
         let mut ty = self.parse_ty(false);
+        self.allow_object_bounds = true;
 
         // Parse traits, if necessary.
         let opt_trait = if self.token == token::COLON {
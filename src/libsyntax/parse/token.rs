@@ -88,6 +88,8 @@ pub enum Token {
     /* Name components */
     IDENT(ast::ident, bool),
     UNDERSCORE,
+    // `'ident`, the name of a loop
+    LIFETIME(ast::ident),
 
     /* For interpolation */
     INTERPOLATED(nonterminal),
@@ -195,28 +197,25 @@ pub fn to_str(in: @ident_interner, t: Token) -> ~str {
       IDENT(s, _) => *in.get(s),
 
       UNDERSCORE => ~"_",
+      LIFETIME(s) => ~"'" + *in.get(s),
 
       /* Other */
       DOC_COMMENT(s) => *in.get(s),
       EOF => ~"<eof>",
       INTERPOLATED(ref nt) => {
-        match nt {
-            &nt_expr(e) => ::print::pprust::expr_to_str(e, in),
-            _ => {
-                ~"an interpolated " +
-                    match (*nt) {
-                      nt_item(*) => ~"item",
-                      nt_block(*) => ~"block",
-                      nt_stmt(*) => ~"statement",
-                      nt_pat(*) => ~"pattern",
-                      nt_expr(*) => die!(~"should have been handled above"),
-                      nt_ty(*) => ~"type",
-                      nt_ident(*) => ~"identifier",
-                      nt_path(*) => ~"path",
-                      nt_tt(*) => ~"tt",
-                      nt_matchers(*) => ~"matcher sequence"
-                    }
-            }
+        // Print the fragment itself, so that stringify! and exported
+        // macros see what was matched rather than a placeholder
+        match *nt {
+          nt_item(i) => ::print::pprust::item_to_str(i, in),
+          nt_block(ref b) => ::print::pprust::block_to_str(copy *b, in),
+          nt_stmt(s) => ::print::pprust::stmt_to_str(copy *s, in),
+          nt_pat(p) => ::print::pprust::pat_to_str(p, in),
+          nt_expr(e) => ::print::pprust::expr_to_str(e, in),
+          nt_ty(t) => ::print::pprust::ty_to_str(t, in),
+          nt_ident(id, _) => *in.get(id),
+          nt_path(p) => ::print::pprust::path_to_str(p, in),
+          nt_tt(tt) => ::print::pprust::tt_to_str(copy *tt, in),
+          nt_matchers(*) => ~"an interpolated matcher sequence"
         }
       }
     }
@@ -229,6 +228,7 @@ pub pure fn can_begin_expr(t: Token) -> bool {
       LBRACKET => true,
       IDENT(_, _) => true,
       UNDERSCORE => true,
+      LIFETIME(_) => true,
       TILDE => true,
       LIT_INT(_, _) => true,
       LIT_UINT(_, _) => true,
@@ -284,6 +284,10 @@ pub pure fn is_ident(t: Token) -> bool {
     match t { IDENT(_, _) => true, _ => false }
 }
 
+pub pure fn is_lifetime(t: Token) -> bool {
+    match t { LIFETIME(_) => true, _ => false }
+}
+
 pub pure fn is_ident_or_path(t: Token) -> bool {
     match t {
       IDENT(_, _) | INTERPOLATED(nt_path(*)) => true,
@@ -766,6 +770,12 @@ impl Token : cmp::Eq {
                     _ => false
                 }
             }
+            LIFETIME(e0a) => {
+                match (*other) {
+                    LIFETIME(e0b) => e0a == e0b,
+                    _ => false
+                }
+            }
             INTERPOLATED(_) => {
                 match (*other) {
                     INTERPOLATED(_) => true,
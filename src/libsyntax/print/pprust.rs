@@ -415,6 +415,10 @@ pub fn print_type_ex(s: ps, &&ty: @ast::Ty, print_colons: bool) {
                       None, None);
       }
       ast::ty_path(path, _) => print_path(s, path, print_colons),
+      ast::ty_bounded(ty, bounds) => {
+          print_type(s, ty);
+          print_bounds(s, bounds);
+      }
       ast::ty_fixed_length_vec(mt, v) => {
         word(s.s, ~"[");
         match mt.mutbl {
@@ -750,7 +754,7 @@ pub fn print_tt(s: ps, tt: ast::token_tree) {
       }
       ast::tt_seq(_, ref tts, ref sep, zerok) => {
         word(s.s, ~"$(");
-        for (*tts).each() |tt_elt| { print_tt(s, *tt_elt); }
+        print_tts(s, *tts);
         word(s.s, ~")");
         match (*sep) {
           Some(ref tk) => word(s.s, parse::token::to_str(s.intr, (*tk))),
@@ -954,6 +958,7 @@ pub fn print_possibly_embedded_block_(s: ps,
     }
 
     print_inner_attributes(s, attrs);
+    print_inner_attributes(s, blk.node.attrs);
 
     for blk.node.view_items.each |vi| { print_view_item(s, *vi); }
     for blk.node.stmts.each |st| {
@@ -1061,6 +1066,12 @@ pub fn print_call_pre(s: ps,
             Some(base_args.pop())
         }
         ast::ForSugar => {
+            match base_args.last().node {
+                ast::expr_loop_body(_, opt_ident) => {
+                    print_loop_label(s, opt_ident);
+                }
+                _ => ()
+            }
             head(s, ~"for");
             Some(base_args.pop())
         }
@@ -1081,7 +1092,7 @@ pub fn print_call_post(s: ps,
         nbsp(s);
         match blk.get().node {
           // need to handle closures specifically
-          ast::expr_do_body(e) | ast::expr_loop_body(e) => {
+          ast::expr_do_body(e) | ast::expr_loop_body(e, _) => {
             end(s); // we close our head box; closure
                     // will create it's own.
             print_expr(s, e);
@@ -1231,19 +1242,16 @@ pub fn print_expr(s: ps, &&expr: @ast::expr) {
       ast::expr_if(test, ref blk, elseopt) => {
         print_if(s, test, (*blk), elseopt, false);
       }
-      ast::expr_while(test, ref blk) => {
+      ast::expr_while(test, ref blk, opt_ident) => {
+        print_loop_label(s, opt_ident);
         head(s, ~"while");
         print_expr(s, test);
         space(s.s);
         print_block(s, (*blk));
       }
       ast::expr_loop(ref blk, opt_ident) => {
+        print_loop_label(s, opt_ident);
         head(s, ~"loop");
-        space(s.s);
-        opt_ident.iter(|ident| {
-            print_ident(s, *ident);
-            word_space(s, ~":");
-        });
         print_block(s, (*blk));
       }
       ast::expr_match(expr, ref arms) => {
@@ -1349,7 +1357,7 @@ pub fn print_expr(s: ps, &&expr: @ast::expr) {
         // empty box to satisfy the close.
         ibox(s, 0);
       }
-      ast::expr_loop_body(body) => {
+      ast::expr_loop_body(body, _) => {
         print_expr(s, body);
       }
       ast::expr_do_body(body) => {
@@ -1402,12 +1410,12 @@ pub fn print_expr(s: ps, &&expr: @ast::expr) {
       ast::expr_break(opt_ident) => {
         word(s.s, ~"break");
         space(s.s);
-        opt_ident.iter(|ident| {print_ident(s, *ident); space(s.s)});
+        opt_ident.iter(|ident| {print_label(s, *ident); space(s.s)});
       }
       ast::expr_again(opt_ident) => {
         word(s.s, ~"loop");
         space(s.s);
-        opt_ident.iter(|ident| {print_ident(s, *ident); space(s.s)});
+        opt_ident.iter(|ident| {print_label(s, *ident); space(s.s)});
       }
       ast::expr_ret(result) => {
         word(s.s, ~"return");
@@ -1492,6 +1500,17 @@ pub fn print_ident(s: ps, ident: ast::ident) {
     word(s.s, *s.intr.get(ident));
 }
 
+pub fn print_label(s: ps, ident: ast::ident) {
+    word(s.s, ~"'" + *s.intr.get(ident));
+}
+
+pub fn print_loop_label(s: ps, opt_ident: Option<ast::ident>) {
+    opt_ident.iter(|ident| {
+        print_label(s, *ident);
+        word_space(s, ~":");
+    });
+}
+
 pub fn print_for_decl(s: ps, loc: @ast::local, coll: @ast::expr) {
     print_local_decl(s, loc);
     space(s.s);
@@ -226,6 +226,10 @@ pub fn visit_ty<E>(t: @Ty, e: E, v: vt<E>) {
         (v.visit_ty)(f.decl.output, e, v);
       }
       ty_path(p, _) => visit_path(p, e, v),
+      ty_bounded(ty, bounds) => {
+        (v.visit_ty)(ty, e, v);
+        visit_ty_param_bounds(bounds, e, v);
+      }
       ty_fixed_length_vec(mt, _) => (v.visit_ty)(mt.ty, e, v),
       ty_nil |
       ty_bot |
@@ -442,7 +446,7 @@ pub fn visit_expr<E>(ex: @expr, e: E, v: vt<E>) {
         (v.visit_expr)(a, e, v); (v.visit_expr)(b, e, v);
       }
       expr_addr_of(_, x) | expr_unary(_, x) |
-      expr_loop_body(x) | expr_do_body(x) |
+      expr_loop_body(x, _) | expr_do_body(x) |
       expr_assert(x) => (v.visit_expr)(x, e, v),
       expr_lit(_) => (),
       expr_cast(x, t) => { (v.visit_expr)(x, e, v); (v.visit_ty)(t, e, v); }
@@ -451,7 +455,7 @@ pub fn visit_expr<E>(ex: @expr, e: E, v: vt<E>) {
         (v.visit_block)((*b), e, v);
         visit_expr_opt(eo, e, v);
       }
-      expr_while(x, ref b) => {
+      expr_while(x, ref b, _) => {
         (v.visit_expr)(x, e, v);
         (v.visit_block)((*b), e, v);
       }
@@ -81,6 +81,7 @@ public:
     void *calloc(size_t size, const char *tag);
     void *realloc(void *mem, size_t size);
     void free(void *mem);
+    int live_allocations() { return _live_allocations; }
     ~memory_region();
  };
 
@@ -26,6 +26,10 @@
 
 #if !defined(__WIN32__)
 #include <sys/time.h>
+#include <termios.h>
+#include <unistd.h>
+#else
+#include <io.h>
 #endif
 
 #ifdef __FreeBSD__
@@ -115,6 +119,28 @@ rust_getcwd() {
     return make_str(task->kernel, cbuf, strlen(cbuf), "rust_str(getcwd)");
 }
 
+// Formats a double with printf's %f, %e or %E conversion, which rounds
+// correctly from the exact binary value.
+extern "C" CDECL rust_str *
+rust_format_double(double n, char conv, size_t precision) {
+    rust_task *task = rust_get_current_task();
+
+    char fmt[] = "%.*f";
+    fmt[3] = conv;
+
+    int len = snprintf(NULL, 0, fmt, (int)precision, n);
+    if (len < 0) {
+        task->fail();
+        return NULL;
+    }
+    char *buf = (char *)task->malloc(len + 1, "rust_format_double");
+    snprintf(buf, len + 1, fmt, (int)precision, n);
+    rust_str *st = make_str(task->kernel, buf, len,
+                            "rust_str(format_double)");
+    task->free(buf);
+    return st;
+}
+
 #if defined(__WIN32__)
 extern "C" CDECL rust_vec_box *
 rust_env_pairs() {
@@ -172,6 +198,12 @@ vec_reserve_shared(type_desc* ty, rust_vec_box** vp,
     reserve_vec_exact(task, vp, n_elts * ty->size);
 }
 
+extern "C" CDECL void
+vec_shrink_shared(rust_vec_box** vp) {
+    rust_task *task = rust_get_current_task();
+    shrink_vec_exact(task, vp);
+}
+
 extern "C" CDECL rust_vec*
 rand_seed() {
     size_t size = sizeof(ub4) * RANDSIZ;
@@ -433,6 +465,39 @@ extern "C" CDECL FILE* rust_get_stdin() {return stdin;}
 extern "C" CDECL FILE* rust_get_stdout() {return stdout;}
 extern "C" CDECL FILE* rust_get_stderr() {return stderr;}
 
+// Returns whether a terminal echoes its input, or -1 if fd isn't one
+extern "C" CDECL int
+rust_get_echo(int fd) {
+#if defined(__WIN32__)
+    HANDLE h = (HANDLE)_get_osfhandle(fd);
+    DWORD mode;
+    if (!GetConsoleMode(h, &mode)) return -1;
+    return (mode & ENABLE_ECHO_INPUT) != 0;
+#else
+    struct termios t;
+    if (!isatty(fd) || tcgetattr(fd, &t) != 0) return -1;
+    return (t.c_lflag & ECHO) != 0;
+#endif
+}
+
+extern "C" CDECL int
+rust_set_echo(int fd, int on) {
+#if defined(__WIN32__)
+    HANDLE h = (HANDLE)_get_osfhandle(fd);
+    DWORD mode;
+    if (!GetConsoleMode(h, &mode)) return -1;
+    if (on) mode |= ENABLE_ECHO_INPUT;
+    else mode &= ~ENABLE_ECHO_INPUT;
+    return SetConsoleMode(h, mode) ? 0 : -1;
+#else
+    struct termios t;
+    if (tcgetattr(fd, &t) != 0) return -1;
+    if (on) t.c_lflag |= ECHO;
+    else t.c_lflag &= ~ECHO;
+    return tcsetattr(fd, TCSANOW, &t);
+#endif
+}
+
 extern "C" CDECL int
 rust_ptr_eq(type_desc *t, rust_box *a, rust_box *b) {
     return a == b;
@@ -618,6 +683,12 @@ rust_num_threads() {
     return task->kernel->env->num_sched_threads;
 }
 
+extern "C" CDECL uintptr_t
+rust_num_cpus() {
+    rust_task *task = rust_get_current_task();
+    return task->kernel->env->num_cpus;
+}
+
 extern "C" CDECL int
 rust_get_argc() {
     rust_task *task = rust_get_current_task();
@@ -721,6 +792,16 @@ rust_log_console_off() {
     log_console_off(task->kernel->env);
 }
 
+extern "C" CDECL size_t
+rust_log_set_level(const char *prefix, uint32_t level) {
+    return set_log_level(global_crate_map, prefix, level);
+}
+
+extern "C" CDECL bool
+rust_log_get_level(const char *name, uint32_t *level) {
+    return get_log_level(global_crate_map, name, level);
+}
+
 extern "C" CDECL lock_and_signal *
 rust_dbg_lock_create() {
     return new lock_and_signal();
@@ -765,6 +846,12 @@ rust_dbg_call(dbg_callback cb, void *data) {
 
 extern "C" CDECL void rust_dbg_do_nothing() { }
 
+extern "C" CDECL intptr_t
+rust_dbg_exchange_live_allocs() {
+    rust_task *task = rust_get_current_task();
+    return task->kernel->region()->live_allocations();
+}
+
 extern "C" CDECL void
 rust_dbg_breakpoint() {
     BREAKPOINT_AWESOME;
@@ -16,6 +16,7 @@
 
 struct rust_env {
     size_t num_sched_threads;
+    size_t num_cpus;
     size_t min_stack_size;
     size_t max_stack_size;
     char* logspec;
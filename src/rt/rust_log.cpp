@@ -28,6 +28,11 @@ static lock_and_signal _log_lock;
  * Protected by _log_lock;
  */
 static bool _log_to_console = true;
+/**
+ * Serializes changes to the per-module log levels. Log statements read
+ * the level words without taking it.
+ */
+static lock_and_signal _log_level_lock;
 
 /*
  * Request that console logging be turned on.
@@ -310,6 +315,7 @@ void update_log_settings(void* crate_map, char* settings) {
     }
 
     size_t n_matches = 0;
+    scoped_lock with(_log_level_lock);
     update_module_map(_rt_module_map, &dirs[0], n_dirs, &n_matches);
     update_crate_map((const cratemap*)crate_map, &dirs[0],
                      n_dirs, &n_matches);
@@ -323,6 +329,57 @@ void update_log_settings(void* crate_map, char* settings) {
     free(buffer);
 }
 
+// Changing log levels while the program runs
+
+struct set_level_args {
+    const char *prefix;
+    size_t prefix_len;
+    uint32_t level;
+    size_t n_matches;
+};
+
+static void set_entry_level(const mod_entry* entry, void *cookie) {
+    set_level_args *args = (set_level_args *)cookie;
+    if (strncmp(entry->name, args->prefix, args->prefix_len) == 0) {
+        *entry->state = args->level;
+        args->n_matches++;
+    }
+}
+
+// Sets the level of every module whose name starts with `prefix`, matching
+// the way RUST_LOG directives apply. Returns the number of modules changed.
+size_t set_log_level(void* crate_map, const char* prefix, uint32_t level) {
+    set_level_args args = { prefix, strlen(prefix), level, 0 };
+    scoped_lock with(_log_level_lock);
+    iter_module_map(_rt_module_map, set_entry_level, &args);
+    iter_crate_map((const cratemap*)crate_map, set_entry_level, &args);
+    return args.n_matches;
+}
+
+struct get_level_args {
+    const char *name;
+    uint32_t *level;
+    bool found;
+};
+
+static void get_entry_level(const mod_entry* entry, void *cookie) {
+    get_level_args *args = (get_level_args *)cookie;
+    if (!args->found && strcmp(entry->name, args->name) == 0) {
+        *args->level = *entry->state;
+        args->found = true;
+    }
+}
+
+// Reads the level of the module called `name` into `level`. Returns false
+// if there is no such module in the log map.
+bool get_log_level(void* crate_map, const char* name, uint32_t *level) {
+    get_level_args args = { name, level, false };
+    scoped_lock with(_log_level_lock);
+    iter_module_map(_rt_module_map, get_entry_level, &args);
+    iter_crate_map((const cratemap*)crate_map, get_entry_level, &args);
+    return args.found;
+}
+
 
 //
 // Local Variables:
@@ -66,6 +66,8 @@ private:
 };
 
 void update_log_settings(void* crate_map, char* settings);
+size_t set_log_level(void* crate_map, const char* prefix, uint32_t level);
+bool get_log_level(void* crate_map, const char* name, uint32_t *level);
 
 extern uint32_t log_rt_mem;
 extern uint32_t log_rt_box;
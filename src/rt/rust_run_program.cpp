@@ -139,9 +139,20 @@ rust_process_wait(int proc) {
     }
 }
 
+extern "C" CDECL int
+rust_process_kill(int proc) {
+    return TerminateProcess((HANDLE)proc, 1) ? 0 : -1;
+}
+
+extern "C" CDECL void
+rust_block_sigpipe() {
+    // Writing to a closed pipe fails with an error here, not a signal
+}
+
 #elif defined(__GNUC__)
 
 #include <sys/file.h>
+#include <pthread.h>
 #include <signal.h>
 #include <sys/ioctl.h>
 #include <unistd.h>
@@ -192,6 +203,22 @@ rust_process_wait(int proc) {
     return 0;
 }
 
+extern "C" CDECL int
+rust_process_kill(int proc) {
+    return kill(proc, SIGKILL);
+}
+
+// Makes writes to a closed pipe from the calling thread fail with EPIPE
+// rather than raise SIGPIPE. A signal left pending is dropped when the
+// thread exits.
+extern "C" CDECL void
+rust_block_sigpipe() {
+    sigset_t sset;
+    sigemptyset(&sset);
+    sigaddset(&sset, SIGPIPE);
+    pthread_sigmask(SIG_BLOCK, &sset, NULL);
+}
+
 #else
 #error "Platform not supported."
 #endif
@@ -91,6 +91,15 @@ inline void reserve_vec_exact(rust_task* task, rust_vec_box** vpp,
     }
 }
 
+inline void shrink_vec_exact(rust_task* task, rust_vec_box** vpp) {
+    size_t size = (*vpp)->body.fill;
+    if (size < (*vpp)->body.alloc) {
+        *vpp = (rust_vec_box*)task->kernel
+            ->realloc(*vpp, size + sizeof(rust_vec_box));
+        (*vpp)->body.alloc = size;
+    }
+}
+
 inline void reserve_vec(rust_task* task, rust_vec_box** vpp, size_t size) {
     reserve_vec_exact(task, vpp, next_power_of_two(size));
 }
@@ -293,6 +293,73 @@ rust_uv_tcp_getpeername6
     return uv_tcp_getpeername(handle, (sockaddr*)name, &namelen);
 }
 
+extern "C" int
+rust_uv_tcp_nodelay
+(uv_tcp_t* handle, int enable) {
+    return uv_tcp_nodelay(handle, enable);
+}
+
+extern "C" int
+rust_uv_tcp_keepalive
+(uv_tcp_t* handle, int enable, unsigned int delay) {
+    return uv_tcp_keepalive(handle, enable, delay);
+}
+
+extern "C" int
+rust_uv_udp_init(uv_loop_t* loop, uv_udp_t* handle) {
+    return uv_udp_init(loop, handle);
+}
+
+extern "C" int
+rust_uv_udp_bind(uv_udp_t* handle, sockaddr_in* addr_ptr,
+        unsigned flags) {
+    // FIXME ref #2064
+    sockaddr_in addr = *addr_ptr;
+    return uv_udp_bind(handle, addr, flags);
+}
+
+extern "C" int
+rust_uv_udp_bind6(uv_udp_t* handle, sockaddr_in6* addr_ptr,
+        unsigned flags) {
+    // FIXME ref #2064
+    sockaddr_in6 addr = *addr_ptr;
+    return uv_udp_bind6(handle, addr, flags);
+}
+
+extern "C" int
+rust_uv_udp_send(uv_udp_send_t* req, uv_udp_t* handle,
+        uv_buf_t* bufs, int buf_cnt, sockaddr_in* addr_ptr,
+        uv_udp_send_cb cb) {
+    // FIXME ref #2064
+    sockaddr_in addr = *addr_ptr;
+    return uv_udp_send(req, handle, bufs, buf_cnt, addr, cb);
+}
+
+extern "C" int
+rust_uv_udp_send6(uv_udp_send_t* req, uv_udp_t* handle,
+        uv_buf_t* bufs, int buf_cnt, sockaddr_in6* addr_ptr,
+        uv_udp_send_cb cb) {
+    // FIXME ref #2064
+    sockaddr_in6 addr = *addr_ptr;
+    return uv_udp_send6(req, handle, bufs, buf_cnt, addr, cb);
+}
+
+extern "C" int
+rust_uv_udp_recv_start(uv_udp_t* handle, uv_alloc_cb on_alloc,
+        uv_udp_recv_cb on_recv) {
+    return uv_udp_recv_start(handle, on_alloc, on_recv);
+}
+
+extern "C" int
+rust_uv_udp_recv_stop(uv_udp_t* handle) {
+    return uv_udp_recv_stop(handle);
+}
+
+extern "C" bool
+rust_uv_is_ipv4_sockaddr(sockaddr* addr) {
+    return addr->sa_family == AF_INET;
+}
+
 extern "C" int
 rust_uv_listen(uv_stream_t* stream, int backlog,
         uv_connection_cb cb) {
@@ -309,6 +376,14 @@ rust_uv_helper_uv_tcp_t_size() {
     return sizeof(uv_tcp_t);
 }
 extern "C" size_t
+rust_uv_helper_uv_udp_t_size() {
+    return sizeof(uv_udp_t);
+}
+extern "C" size_t
+rust_uv_helper_uv_udp_send_t_size() {
+    return sizeof(uv_udp_send_t);
+}
+extern "C" size_t
 rust_uv_helper_uv_connect_t_size() {
     return sizeof(uv_connect_t);
 }
@@ -0,0 +1,32 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[link(name = "bounded_impl_xc_aux", vers = "0.1")];
+#[crate_type = "lib"];
+
+pub struct Pair<T> {
+    a: T,
+    b: T,
+}
+
+impl<T: Eq> Pair<T> : Eq {
+    pure fn eq(&self, other: &Pair<T>) -> bool {
+        self.a == other.a && self.b == other.b
+    }
+    pure fn ne(&self, other: &Pair<T>) -> bool { !(*self).eq(other) }
+}
+
+impl<T: ToStr> Pair<T> : ToStr {
+    pure fn to_str(&self) -> ~str {
+        fmt!("<%s, %s>", self.a.to_str(), self.b.to_str())
+    }
+}
+
+pub fn both_same<T: Eq>(p: &Pair<T>) -> bool { p.a == p.b }
@@ -0,0 +1,33 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[link(name="cci_inline_private_lib", vers="0.0")];
+
+// Private, but used by the bodies that are inlined into other crates
+const scale: uint = 3u;
+
+pub struct Counter {
+    priv count: uint
+}
+
+pub fn Counter(count: uint) -> Counter {
+    Counter { count: count }
+}
+
+pub impl Counter {
+    #[inline(always)]
+    fn get(&self) -> uint { self.count * scale }
+}
+
+pub fn scaled_sum<T>(v: &[T], f: fn(&T) -> uint) -> uint {
+    let mut sum = 0u;
+    for v.each |x| { sum += f(x) * scale; }
+    sum
+}
@@ -0,0 +1,52 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[link(name = "macro_export_lib",
+       vers = "0.1")];
+
+#[crate_type = "lib"];
+
+#[macro_export]
+macro_rules! square(
+    ($e:expr) => ({ let x = $e; x * x })
+)
+
+// Refers to a function of this crate, so users need it in scope
+#[macro_export]
+macro_rules! greet(
+    ($($name:expr),*) => (~[ $( macro_export_lib::greeting($name) ),* ])
+)
+
+#[macro_export]
+macro_rules! literals(
+    () => ((~"a \"quoted\"\n string", 'x', 0x10u8, 1.5f, true))
+)
+
+// Keywords and idents inside a repetition must stay separated when the
+// macro is written out
+#[macro_export]
+macro_rules! constants(
+    ($m:ident: $($name:ident = $value:expr);*) => (
+        mod $m {
+            $( pub fn $name() -> int { $value } )*
+        }
+    )
+)
+
+// Not exported
+macro_rules! private_twice(
+    ($e:expr) => ($e * 2)
+)
+
+pub fn greeting(name: &str) -> ~str {
+    fmt!("hello, %s", name)
+}
+
+pub fn four() -> int { private_twice!(2) }
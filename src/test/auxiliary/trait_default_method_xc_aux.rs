@@ -0,0 +1,25 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[link(name = "trait_default_method_xc_aux", vers = "0.1")];
+#[crate_type = "lib"];
+
+pub trait Describe {
+    fn name(&self) -> ~str;
+    fn describe(&self) -> ~str { ~"a " + self.name() }
+    fn count(&self) -> int { 1 }
+}
+
+pub struct Widget { n: int }
+
+impl Widget : Describe {
+    fn name(&self) -> ~str { ~"widget" }
+    fn count(&self) -> int { self.n }
+}
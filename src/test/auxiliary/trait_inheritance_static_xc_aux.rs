@@ -0,0 +1,20 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[link(name = "trait_inheritance_static_xc_aux", vers = "0.1")];
+#[crate_type = "lib"];
+
+pub trait Num {
+    static fn from_int(i: int) -> Self;
+    fn to_int(&self) -> int;
+}
+
+// Declares no static functions of its own
+pub trait NumExt : Num { }
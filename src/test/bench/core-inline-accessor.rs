@@ -0,0 +1,74 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Compares calling libcore's #[inline(always)] get_shared_immutable_state
+// in a loop against a local accessor that does the same work. If the
+// upstream accessor is inlined across crates the two should be close.
+
+extern mod std;
+use io::WriterUtil;
+use private::{SharedMutableState, get_shared_immutable_state};
+use private::shared_mutable_state;
+
+struct Local {
+    data: uint
+}
+
+#[inline(always)]
+fn get_local(x: &a/Local) -> &a/uint {
+    &x.data
+}
+
+fn sum_upstream(state: &SharedMutableState<uint>, n: uint) -> uint {
+    let mut sum = 0u;
+    for uint::range(0u, n) |i| {
+        sum += unsafe { *get_shared_immutable_state(state) } + i;
+    }
+    sum
+}
+
+fn sum_local(x: &Local, n: uint) -> uint {
+    let mut sum = 0u;
+    for uint::range(0u, n) |i| {
+        sum += *get_local(x) + i;
+    }
+    sum
+}
+
+fn main() {
+    let args = os::args();
+    let args = if os::getenv(~"RUST_BENCH").is_some() {
+        ~[~"", ~"100000000"]
+    } else if args.len() <= 1u {
+        ~[~"", ~"1000000"]
+    } else {
+        args
+    };
+    let n = uint::from_str(args[1]).get();
+
+    let state = unsafe { shared_mutable_state(3u) };
+    let local = Local { data: 3u };
+
+    let start = std::time::precise_time_s();
+    let upstream_sum = sum_upstream(&state, n);
+    let mid = std::time::precise_time_s();
+    let local_sum = sum_local(&local, n);
+    let end = std::time::precise_time_s();
+
+    assert upstream_sum == local_sum;
+
+    let upstream = mid - start;
+    let local = end - mid;
+
+    io::stdout().write_str(fmt!("Upstream : %? seconds\n", upstream));
+    io::stdout().write_str(fmt!("Local    : %? seconds\n", local));
+    io::stdout().write_str(fmt!("Upstream/local : %f\n",
+                                (upstream as float) / (local as float)));
+}
@@ -0,0 +1,71 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Microbenchmark for reading a file line by line, through the plain file
+// reader and through a BufferedReader.
+
+extern mod std;
+use io::{ReaderUtil, WriterUtil};
+
+fn write_file(path: &Path, nlines: uint) {
+    let out = io::file_writer(path, [io::Create, io::Truncate]).get();
+    for uint::range(0, nlines) |i| {
+        out.write_line(fmt!("%u: the quick brown fox jumps over the lazy dog",
+                            i));
+    }
+}
+
+fn count_unbuffered(path: &Path) -> (uint, uint) {
+    let rdr = io::file_reader(path).get();
+    let mut lines = 0u, bytes = 0u;
+    for rdr.each_line |line| {
+        lines += 1;
+        bytes += line.len();
+    }
+    (lines, bytes)
+}
+
+fn count_buffered(path: &Path) -> (uint, uint) {
+    let rdr = io::buffered_file_reader(path).get();
+    let mut lines = 0u, bytes = 0u;
+    for rdr.each_line |line| {
+        lines += 1;
+        bytes += line.len();
+    }
+    (lines, bytes)
+}
+
+fn main() {
+    let args = os::args();
+    let args = if os::getenv(~"RUST_BENCH").is_some() {
+        ~[~"", ~"1000000"]
+    } else if args.len() <= 1u {
+        ~[~"", ~"50000"]
+    } else {
+        args
+    };
+    let nlines = uint::from_str(args[1]).get();
+
+    let path = &Path("tmp/bench-core-io-lines.tmp");
+    write_file(path, nlines);
+
+    let start = std::time::precise_time_s();
+    let (_, unbuffered_bytes) = count_unbuffered(path);
+    let mid = std::time::precise_time_s();
+    let (lines, bytes) = count_buffered(path);
+    let end = std::time::precise_time_s();
+
+    assert lines == nlines;
+    assert bytes == unbuffered_bytes;
+
+    io::stdout().write_str(fmt!("%u lines, %u bytes\n", lines, bytes));
+    io::stdout().write_str(fmt!("unbuffered : %? seconds\n", mid - start));
+    io::stdout().write_str(fmt!("buffered   : %? seconds\n", end - mid));
+}
@@ -0,0 +1,55 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Microbenchmark for formatted output: writing fmt!'s result to a writer
+// against formatting straight into it with write_fmt!.
+
+extern mod std;
+use io::WriterUtil;
+
+fn with_fmt(nlines: uint) -> ~[u8] {
+    do io::with_bytes_writer |wr| {
+        for uint::range(0, nlines) |i| {
+            wr.write_str(fmt!("%u: %s %5d %x\n", i, "line", -(i as int), i));
+        }
+    }
+}
+
+fn with_write_fmt(nlines: uint) -> ~[u8] {
+    do io::with_bytes_writer |wr| {
+        for uint::range(0, nlines) |i| {
+            write_fmt!(wr, "%u: %s %5d %x\n", i, "line", -(i as int), i);
+        }
+    }
+}
+
+fn main() {
+    let args = os::args();
+    let args = if os::getenv(~"RUST_BENCH").is_some() {
+        ~[~"", ~"1000000"]
+    } else if args.len() <= 1u {
+        ~[~"", ~"50000"]
+    } else {
+        args
+    };
+    let nlines = uint::from_str(args[1]).get();
+
+    let start = std::time::precise_time_s();
+    let a = with_fmt(nlines);
+    let mid = std::time::precise_time_s();
+    let b = with_write_fmt(nlines);
+    let end = std::time::precise_time_s();
+
+    assert a == b;
+
+    io::stdout().write_str(fmt!("%u lines, %u bytes\n", nlines, a.len()));
+    io::stdout().write_str(fmt!("fmt!       : %? seconds\n", mid - start));
+    io::stdout().write_str(fmt!("write_fmt! : %? seconds\n", end - mid));
+}
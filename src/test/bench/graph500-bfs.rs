@@ -23,7 +23,6 @@ use std::time;
 use std::oldmap;
 use std::oldmap::Map;
 use std::oldmap::HashMap;
-use std::deque;
 use std::deque::Deque;
 use std::par;
 use core::io::WriterUtil;
@@ -124,12 +123,12 @@ fn bfs(graph: graph, key: node_id) -> bfs_result {
     let mut marks : ~[node_id]
         = vec::from_elem(vec::len(graph), -1i64);
 
-    let Q = deque::create();
+    let mut Q = Deque::new();
 
     Q.add_back(key);
     marks[key] = key;
 
-    while Q.size() > 0 {
+    while !Q.is_empty() {
         let t = Q.pop_front();
 
         do graph[t].each() |k| {
@@ -0,0 +1,69 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Microbenchmark for returning a large struct by value, both as a named
+// local and as a struct literal
+
+extern mod std;
+use io::WriterUtil;
+
+struct Big {
+    a: u64, b: u64, c: u64, d: u64,
+    e: u64, f: u64, g: u64, h: u64,
+    i: u64, j: u64, k: u64, l: u64,
+    m: u64, n: u64, o: u64, p: u64
+}
+
+fn make_named(x: u64) -> Big {
+    let mut big = Big {
+        a: x, b: x, c: x, d: x, e: x, f: x, g: x, h: x,
+        i: x, j: x, k: x, l: x, m: x, n: x, o: x, p: x
+    };
+    big.p = x + 1u64;
+    big
+}
+
+fn make_literal(x: u64) -> Big {
+    Big {
+        a: x, b: x, c: x, d: x, e: x, f: x, g: x, h: x,
+        i: x, j: x, k: x, l: x, m: x, n: x, o: x, p: x + 1u64
+    }
+}
+
+fn run(n: uint, f: fn(u64) -> Big) -> float {
+    let start = std::time::precise_time_s();
+    let mut sum = 0u64;
+    for uint::range(0u, n) |i| {
+        let big = f(i as u64);
+        sum += big.a + big.p;
+    }
+    let end = std::time::precise_time_s();
+    assert sum == (n as u64) * (n as u64);
+    (end - start) as float
+}
+
+fn main() {
+    let args = os::args();
+    let args = if os::getenv(~"RUST_BENCH").is_some() {
+        ~[~"", ~"10000000"]
+    } else if args.len() <= 1u {
+        ~[~"", ~"100000"]
+    } else {
+        args
+    };
+    let n = uint::from_str(args[1]).get();
+
+    let named = run(n, make_named);
+    let literal = run(n, make_literal);
+
+    io::stdout().write_str(fmt!("named local: %? seconds\n", named));
+    io::stdout().write_str(fmt!("literal    : %? seconds\n", literal));
+}
@@ -0,0 +1,113 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Microbenchmark for the bitv library: a backwards dataflow analysis
+// (liveness over a random control flow graph) solved to a fixed point,
+// once with the word-at-a-time set operations and once with the
+// equivalent bit-by-bit loops.
+
+extern mod std;
+use std::bitv::Bitv;
+use std::bitv;
+use io::WriterUtil;
+
+struct Block {
+    succs: ~[uint],
+    gen: Bitv,
+    kill: Bitv,
+}
+
+fn make_blocks(nblocks: uint, nvars: uint) -> ~[Block] {
+    let rng = rand::seeded_rng([1, 2, 3, 4]);
+    do vec::from_fn(nblocks) |i| {
+        let mut succs = ~[];
+        if i + 1 < nblocks { succs.push(i + 1); }
+        succs.push(rng.gen_uint_range(0, nblocks));
+        Block {
+            succs: succs,
+            gen: bitv::from_fn(nvars, |_| rng.gen_uint_range(0, 16) == 0),
+            kill: bitv::from_fn(nvars, |_| rng.gen_uint_range(0, 16) == 0),
+        }
+    }
+}
+
+// live_in = gen | (live_out - kill), live_out = union of successors' live_in
+fn solve_words(blocks: &[Block], nvars: uint) -> ~[Bitv] {
+    let live_in = vec::from_fn(blocks.len(), |_| Bitv(nvars, false));
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for uint::range_rev(blocks.len(), 0) |i| {
+            let b = &blocks[i - 1];
+            let out = Bitv(nvars, false);
+            for b.succs.each |s| { out.union(&live_in[*s]); }
+            out.difference(&b.kill);
+            out.union(&b.gen);
+            if live_in[i - 1].assign(&out) { changed = true; }
+        }
+    }
+    live_in
+}
+
+fn solve_bits(blocks: &[Block], nvars: uint) -> ~[Bitv] {
+    let live_in = vec::from_fn(blocks.len(), |_| Bitv(nvars, false));
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for uint::range_rev(blocks.len(), 0) |i| {
+            let b = &blocks[i - 1];
+            for uint::range(0, nvars) |v| {
+                let mut live = b.gen.get(v);
+                if !live && !b.kill.get(v) {
+                    for b.succs.each |s| {
+                        if live_in[*s].get(v) { live = true; break; }
+                    }
+                }
+                if live_in[i - 1].get(v) != live {
+                    live_in[i - 1].set(v, live);
+                    changed = true;
+                }
+            }
+        }
+    }
+    live_in
+}
+
+fn main() {
+    let args = os::args();
+    let args = if os::getenv(~"RUST_BENCH").is_some() {
+        ~[~"", ~"2000", ~"1000"]
+    } else if args.len() <= 1u {
+        ~[~"", ~"200", ~"500"]
+    } else {
+        args
+    };
+    let nblocks = uint::from_str(args[1]).get();
+    let nvars = uint::from_str(args[2]).get();
+
+    let blocks = make_blocks(nblocks, nvars);
+
+    let start = std::time::precise_time_s();
+    let words = solve_words(blocks, nvars);
+    let mid = std::time::precise_time_s();
+    let bits = solve_bits(blocks, nvars);
+    let end = std::time::precise_time_s();
+
+    let mut live = 0u;
+    for uint::range(0, nblocks) |i| {
+        assert words[i].equal(&bits[i]);
+        live += words[i].count_ones();
+    }
+
+    io::stdout().write_str(fmt!("%u live variables over %u blocks\n",
+                                live, nblocks));
+    io::stdout().write_str(fmt!("word ops   : %? seconds\n", mid - start));
+    io::stdout().write_str(fmt!("bit by bit : %? seconds\n", end - mid));
+}
@@ -1,7 +1,7 @@
 fn main() {
     let a = ~[];
     match a {
-        [1, ..tail, ..tail] => {}, //~ ERROR: expected `]` but found `,`
+        [1, ..tail, ..tail] => {}, //~ ERROR `..` pattern must come last
         _ => ()
     }
 }
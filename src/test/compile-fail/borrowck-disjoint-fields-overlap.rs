@@ -0,0 +1,53 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Borrows of different fields don't conflict, but a field still overlaps
+// the whole value it belongs to, and itself.
+
+struct Point {
+    x: int,
+    y: int
+}
+
+fn whole_after_field() {
+    let mut p = Point { x: 1, y: 2 };
+    let a = &mut p.x;
+    let b = &mut p; //~ ERROR conflicts with prior loan
+    *a += 1;
+    b.y += 1;
+}
+
+fn field_after_whole() {
+    let mut p = Point { x: 1, y: 2 };
+    let b = &p;
+    let a = &mut p.x; //~ ERROR conflicts with prior loan
+    *a += 1;
+    assert b.y == 2;
+}
+
+fn same_field_twice() {
+    let mut p = Point { x: 1, y: 2 };
+    let a = &mut p.x;
+    let c = &mut p.x; //~ ERROR conflicts with prior loan
+    //~^ ERROR conflicts with prior loan
+    *a += 1;
+    *c += 1;
+}
+
+fn assign_whole_after_field() {
+    let mut p = Point { x: 1, y: 2 };
+    let a = &mut p.x;
+    p = Point { x: 3, y: 4 }; //~ ERROR prohibited due to outstanding loan
+    *a += p.y;
+}
+
+fn main() {
+}
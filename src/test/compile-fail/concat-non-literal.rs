@@ -0,0 +1,21 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Arguments are not expanded before concat! sees them, even when they
+// come from a macro
+macro_rules! name(
+    ($x:expr) => (concat!("name: ", $x))
+)
+
+fn main() {
+    let n = 1;
+    let _ = name!(n);
+    //~^ ERROR concat! requires string, character or integer literals
+}
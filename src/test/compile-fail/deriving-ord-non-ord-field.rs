@@ -0,0 +1,26 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[deriving_eq]
+struct Unordered {
+    x: int
+}
+
+#[deriving_eq]
+#[deriving_ord]
+//~^ ERROR does not implement any method in scope named `lt`
+//~^^ ERROR does not implement any method in scope named `le`
+//~^^^ ERROR does not implement any method in scope named `ge`
+//~^^^^ ERROR does not implement any method in scope named `gt`
+struct Holder {
+    inner: Unordered
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn main() {
+    let width: int = -5;
+    let prec = 2u;
+    let s = fmt!("%*d", width, 10); //~ ERROR mismatched types
+    let t = fmt!("%.*f", prec, 1.5); // ok
+    let u = fmt!("%*.*f", prec, 'a', 1.5); //~ ERROR mismatched types
+}
@@ -0,0 +1,37 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// A label outside a closure can't be reached from inside it, unless the
+// closure is the body of the `for` loop the label names. That includes the
+// body of a nested `for` loop: leaving an outer loop from one isn't
+// supported yet, as trans has no way to carry the exit past the inner
+// loop's iterator call.
+
+fn call(f: fn()) { f() }
+
+fn main() {
+    let v = ~[1, 2, 3];
+    'a: loop {
+        for v.each |_x| {
+            break 'a; //~ ERROR cannot `break` to label `'a` from inside a
+        }
+    }
+    'b: for v.each |_x| {
+        for v.each |_y| {
+            loop 'b; //~ ERROR cannot `loop` to label `'b` from inside a
+        }
+    }
+    'c: while true {
+        do call {
+            break 'c; //~ ERROR cannot `break` to label `'c` from inside a
+        }
+    }
+}
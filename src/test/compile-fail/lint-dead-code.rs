@@ -0,0 +1,78 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[deny(dead_code)];
+
+pub use inner::reexported;
+
+pub fn exported() { used_by_exported(); }
+fn used_by_exported() { }
+
+fn dead() { only_used_by_dead(); } //~ ERROR function `dead` is never used
+fn only_used_by_dead() { } //~ ERROR function `only_used_by_dead` is never
+
+const used_const: int = 1;
+const dead_const: int = 2; //~ ERROR constant `dead_const` is never used
+
+struct Used { x: int }
+struct Dead { x: int } //~ ERROR struct `Dead` is never used
+
+enum Choice {
+    Constructed,
+    Matched,
+    NeverUsed, //~ ERROR variant `NeverUsed` is never used
+    #[allow(dead_code)]
+    AllowedVariant
+}
+
+// A trait impl method is live, and so is what it calls
+trait Answer { fn answer(&self) -> int; }
+impl Used : Answer {
+    fn answer(&self) -> int { used_by_impl() }
+}
+fn used_by_impl() -> int { used_const }
+
+impl Used {
+    priv fn called(&self) -> int { self.x }
+    priv fn not_called(&self) { } //~ ERROR method `not_called` is never
+    #[allow(dead_code)]
+    priv fn allowed_method(&self) { }
+}
+
+#[allow(dead_code)]
+impl Dead {
+    priv fn allowed_with_its_impl(&self) { }
+}
+
+mod inner {
+    pub fn reexported() { }
+    pub fn not_reexported() { } //~ ERROR function `not_reexported` is never
+}
+
+#[allow(dead_code)]
+fn allowed() { }
+
+fn _unused_on_purpose() { }
+
+#[test]
+fn a_test() { }
+
+#[start]
+fn start(_argc: int, _argv: **u8) -> int { 0 }
+
+fn main() {
+    let u = Used { x: 1 };
+    u.called();
+    match Constructed {
+        Matched => (),
+        _ => ()
+    }
+}
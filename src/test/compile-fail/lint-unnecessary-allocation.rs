@@ -0,0 +1,44 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[deny(unnecessary_allocation)];
+
+struct Holder { v: ~[int] }
+
+fn takes_int(x: &int) -> int { *x }
+fn takes_str(s: &str) -> uint { s.len() }
+fn takes_slice(v: &[int]) -> uint { v.len() }
+fn takes_owned(v: ~[int]) -> uint { v.len() }
+fn takes_managed(x: @int) -> int { *x }
+fn returns_box() -> ~int { ~3 }
+
+fn main() {
+    takes_int(@1); //~ ERROR only borrowed; use `&` instead of `@`
+    takes_int(~2); //~ ERROR only borrowed; use `&` instead of `~`
+    takes_int(&*~3); //~ ERROR use `&` instead of `~`
+    takes_str(~"foo"); //~ ERROR the value is only borrowed; use a string
+    takes_str(@"foo"); //~ ERROR use a string literal
+    takes_slice(~[1, 2]); //~ ERROR the value is only borrowed; use `&[...]`
+    takes_slice(@[1, 2]); //~ ERROR use `&[...]`
+    takes_slice(~[]); //~ ERROR use `&[...]`
+
+    // The box is kept
+    takes_owned(~[1, 2]);
+    takes_managed(@3);
+    let h = Holder { v: ~[4] };
+    let b = returns_box();
+
+    // Nothing is allocated here
+    takes_slice(h.v);
+    takes_int(b);
+    takes_int(&5);
+    takes_str("bar");
+    takes_slice([6, 7]);
+}
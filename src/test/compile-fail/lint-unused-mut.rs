@@ -0,0 +1,104 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[deny(unused_mut)];
+
+struct Point { x: int, y: int }
+struct Cell { mut value: int }
+
+fn unused_let() -> int {
+    let mut x = 3; //~ ERROR variable `x` does not need to be mutable
+    x
+}
+
+fn unused_arg(mut x: int) -> int { //~ ERROR variable `x` does not need
+    x
+}
+
+fn assigned(mut y: int) -> int {
+    let mut x = 3;
+    x += 1;
+    y = y * 2;
+    x + y
+}
+
+// Only the bindings that are never mutated are reported
+fn partly_mutated() -> int {
+    let mut (a, b) = (1, 2); //~ ERROR variable `b` does not need to be
+    a += 1;
+    a + b
+}
+
+fn borrowed_mutably() -> uint {
+    let mut v = ~[1];
+    vec::push(&mut v, 2);
+    let mut w = ~[3];
+    w.push(4);
+    v.len() + w.len()
+}
+
+fn components() -> int {
+    let mut p = Point { x: 1, y: 2 };
+    p.x = 3;
+    let mut b = ~Point { x: 0, y: 0 };
+    b.y = 4;
+    let mut v = ~[1, 2];
+    v[0] = 5;
+    p.x + p.y + b.x + b.y + v[0]
+}
+
+// A `mut` field can be assigned to through an immutable variable
+fn mut_field() -> int {
+    let mut c = Cell { value: 1 }; //~ ERROR variable `c` does not need
+    c.value = 2;
+    c.value
+}
+
+fn mutated_in_closure() -> int {
+    let mut total = 0;
+    for [1, 2, 3].each |i| { total += *i; }
+    total
+}
+
+#[allow(unused_mut)]
+fn allowed() -> int {
+    let mut x = 1;
+    x
+}
+
+fn allowed_in_block() -> int {
+    let y = {
+        #[allow(unused_mut)];
+        let mut x = 1;
+        x
+    };
+    let mut z = 2; //~ ERROR variable `z` does not need to be mutable
+    y + z
+}
+
+// `acc` only needs to be mutable when there is something to add up
+macro_rules! sum(
+    ($($e:expr),*) => ({ let mut acc = 0; $(acc += $e;)* acc })
+)
+
+fn main() {
+    unused_let();
+    unused_arg(1);
+    assigned(1);
+    partly_mutated();
+    borrowed_mutably();
+    components();
+    mut_field();
+    mutated_in_closure();
+    allowed();
+    allowed_in_block();
+    sum!();
+    sum!(1, 2);
+}
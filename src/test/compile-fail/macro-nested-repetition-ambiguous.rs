@@ -0,0 +1,20 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Without a separator after the inner repetition, nothing says where one
+// group of idents ends and the next begins
+macro_rules! groups(
+    ($( $( $x:ident )+ )+) => (0)
+)
+
+fn main() {
+    groups!(a b c);
+    //~^ ERROR ambiguous repetition: this token could be matched by 'x'
+}
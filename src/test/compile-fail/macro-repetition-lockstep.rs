@@ -0,0 +1,19 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+macro_rules! pairs(
+    ($( $k:expr ),* ; $( $v:expr ),*) => (
+        ~[ $( ($k, $v) ),* ] //~ ERROR 'k' has 3 items, but 'v' has 2
+    )
+)
+
+fn main() {
+    let _ = pairs!(1, 2, 3; 4, 5);
+}
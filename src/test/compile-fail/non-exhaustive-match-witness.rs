@@ -0,0 +1,67 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+enum Color { Red, Green, Blue, Other(int) }
+enum Direction { North, East, South, West, Up, Down }
+struct Point { x: int, visible: bool }
+
+fn nested(c: Option<(Color, int)>) {
+    match c {
+    //~^ ERROR `Some((Green, _))`, `Some((Blue, _))` and `Some((Other(_),
+        None => (),
+        Some((Red, _)) => ()
+    }
+    match c { //~ ERROR non-exhaustive patterns: `Some((Other(0), _))` not
+        None => (),
+        Some((Red, _)) | Some((Green, _)) | Some((Blue, _)) => (),
+        Some((Other(1..10), _)) => ()
+    }
+}
+
+fn capped(d: Direction) {
+    match d { //~ ERROR `East`, `South`, `West` and more not covered
+        North => ()
+    }
+}
+
+fn bool_pairs(a: bool, b: bool) {
+    match (a, b) { //~ ERROR non-exhaustive patterns: `(true, false)` not
+        (true, true) => (),
+        (false, false) => ()
+    }
+    match (a, b) { //~ ERROR non-exhaustive patterns: `(false, _)` not
+        (true, _) => ()
+    }
+}
+
+fn ranges(i: int, u: u8) {
+    match i { //~ ERROR non-exhaustive patterns: `-1` not covered
+        0..9 => (),
+        10 => ()
+    }
+    match u { //~ ERROR non-exhaustive patterns: `128` not covered
+        0..127 => (),
+        129..255 => ()
+    }
+}
+
+fn structs(p: Point) {
+    match p { //~ ERROR non-exhaustive patterns: `Point{visible: false, _}`
+        Point { visible: true, _ } => ()
+    }
+}
+
+fn main() {
+    nested(None);
+    capped(North);
+    bool_pairs(true, false);
+    ranges(0, 0u8);
+    structs(Point { x: 0, visible: true });
+}
@@ -0,0 +1,44 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+fn main() {
+    let v: &[int] = [1, 2];
+    match v { //~ ERROR `[]` not covered
+        [_, ..rest] => ()
+    }
+    match v { //~ ERROR `[_, _]` not covered
+        [] => (),
+        [_] => ()
+    }
+    match v {
+        [] => (),
+        [_] => (),
+        [_, _, .._] => ()
+    }
+
+    let fixed: [int * 2] = [1, 2];
+    match fixed {
+        [a, b] => ()
+    }
+    match fixed {
+        [1, ..rest] => (),
+        [_, _] => ()
+    }
+    match fixed { //~ ERROR `[_, _]` not covered
+        [] => (),
+        [_] => (),
+        [_, _, _] => ()
+    }
+    match fixed { //~ ERROR non-exhaustive patterns: `[0, 0]` not covered
+        [1, _] => (),
+        [_, 2] => ()
+    }
+}
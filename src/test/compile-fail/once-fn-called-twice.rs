@@ -0,0 +1,24 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+fn call_in_loop(f: ~once fn()) {
+    loop {
+        f(); //~ ERROR use of moved value: `f`
+    }
+}
+
+fn main() {
+    let s = ~"hello";
+    let f: ~once fn() -> ~str = || s;
+    f();
+    f(); //~ ERROR use of moved value: `f`
+    call_in_loop(|| ());
+}
@@ -0,0 +1,26 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Copying a once closure would let it be called, and move out of its
+// environment, once per copy.
+
+fn borrowed(f: &once fn() -> ~str) {
+    let g = copy f; //~ ERROR copying a noncopyable value
+    f();
+    g();
+}
+
+fn main() {
+    let f: @once fn() -> ~str = || ~"hello";
+    let g = copy f; //~ ERROR copying a noncopyable value
+    f();
+    g();
+    borrowed(|| ~"hi");
+}
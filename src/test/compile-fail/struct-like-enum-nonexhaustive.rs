@@ -15,7 +15,7 @@ enum A {
 
 fn main() {
     let x = B { x: Some(3) };
-    match x {   //~ ERROR non-exhaustive patterns
+    match x {   //~ ERROR non-exhaustive patterns: `B{x: Some(_)}` not
         C => {}
         B { x: None } => {}
     }
@@ -0,0 +1,24 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+struct Guard {
+    name: ~str,
+}
+
+impl Guard : Drop {
+    fn finalize(&self) {} //~ NOTE the destructor needs the struct whole
+}
+
+fn main() {
+    let g = Guard { name: ~"g" };
+    let Guard { name: name } = move g;
+    //~^ ERROR deconstructing struct not allowed in pattern
+    log(debug, name);
+}
@@ -0,0 +1,25 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A default method can't stand in for the required method it calls
+
+trait Foo {
+    fn f(&self) -> int { self.g() + 1 }
+    fn g(&self) -> int;
+}
+
+struct A;
+
+impl A : Foo { //~ ERROR missing method `g`
+}
+
+fn main() {
+    assert A.f() == 1;
+}
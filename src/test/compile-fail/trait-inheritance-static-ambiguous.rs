@@ -0,0 +1,21 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Two unrelated supertraits that both declare `make`
+
+trait Foo { static fn make() -> Self; }
+trait Bar { static fn make() -> Self; }
+trait Baz : Foo Bar { }
+
+fn f<T:Baz>() -> T {
+    Baz::make() //~ ERROR `make` is declared in more than one supertrait
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+trait Foo {
+    fn f(&self) -> int;
+}
+
+struct Managed {
+    x: @int,
+}
+
+impl Managed : Foo {
+    fn f(&self) -> int { *self.x }
+}
+
+struct Plain {
+    x: int,
+}
+
+impl Plain : Foo {
+    fn f(&self) -> int { self.x }
+}
+
+fn main() {
+    let _a = ~Plain { x: 1 } as ~Foo:Owned;
+    let _b = ~Managed { x: @1 } as ~Foo:Owned; //~ ERROR which is not owned
+    let _c = @Plain { x: 1 } as @Foo:Owned; //~ ERROR may be `Owned`
+
+    // Without the bound, the object can't be sent
+    let (_p, c) = pipes::stream();
+    c.send(~Plain { x: 1 } as ~Foo); //~ ERROR missing `owned`
+}
@@ -0,0 +1,26 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+trait Clone2 {
+    fn clone2(&self) -> Self;
+    fn name(&self) -> ~str;
+}
+
+impl int : Clone2 {
+    fn clone2(&self) -> int { *self }
+    fn name(&self) -> ~str { ~"int" }
+}
+
+fn main() {
+    let x = @3 as @Clone2;
+    // methods that don't mention Self are fine
+    assert x.name() == ~"int";
+    x.clone2(); //~ ERROR cannot call method `clone2` through a boxed trait
+}
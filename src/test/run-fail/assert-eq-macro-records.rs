@@ -0,0 +1,18 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// error-pattern:(left: `Point { x: 14, y: ~"abc" }`, right: `Point { x: 14, y: ~"abd" }`)
+
+#[deriving_eq]
+struct Point { x: int, y: ~str }
+
+fn main() {
+    assert_eq!(Point{x: 14, y: ~"abc"}, Point{x: 14, y: ~"abd"});
+}
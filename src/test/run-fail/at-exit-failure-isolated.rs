@@ -0,0 +1,28 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A failing exit function must not stop the ones after it from running,
+// but it should still make the process exit with an error status.
+
+// error-pattern:first handler failing
+// error-pattern:at_exit handler failed
+// error-pattern:second handler ran
+
+use core::io::WriterUtil;
+use core::private::at_exit::{at_exit, at_exit_prioritized};
+
+fn main() {
+    do at_exit {
+        io::stderr().write_line("second handler ran");
+    }
+    do at_exit_prioritized(1) {
+        die!(~"first handler failing");
+    }
+}
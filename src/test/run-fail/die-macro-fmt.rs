@@ -0,0 +1,19 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// error-pattern:bad index 7 of 3 in [1, 2, 3]
+
+fn main() {
+    let v = ~[1, 2, 3];
+    let i = 7u;
+    if i >= v.len() {
+        die!("bad index %u of %u in %?", i, v.len(), v);
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[deriving_eq]
+struct Point { x : int }
+
+pub fn main() {
+    assert_eq!(14, 14);
+    assert_eq!(~"abc", ~"abc");
+    assert_eq!(~Point{x: 34}, ~Point{x: 34});
+    assert_eq!(&Point{x: 34}, &Point{x: 34});
+    assert_eq!(@Point{x: 34}, @Point{x: 34});
+    assert_eq!(~[1, 2], ~[1, 2]);
+
+    // Each side is evaluated exactly once
+    let mut n = 0;
+    assert_eq!({ n += 1; n }, 1);
+    assert_eq!(n, 1);
+    assert_eq!(n + 1, { n += 1; n });
+    assert_eq!(n, 2);
+
+    fail_unless!(n == 2, "n was %d", n);
+}
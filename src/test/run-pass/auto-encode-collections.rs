@@ -0,0 +1,110 @@
+// xfail-fast
+
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern mod std;
+
+use core::hashmap::linear::{LinearMap, LinearSet};
+use EBReader = std::ebml::reader;
+use EBWriter = std::ebml::writer;
+use std::json;
+use std::serialize::{Encodable, Decodable};
+use std::treemap::TreeMap;
+
+#[auto_encode]
+#[auto_decode]
+#[deriving_eq]
+struct Inventory {
+    owner: Option<~str>,
+    note: Option<~str>,
+    counts: LinearMap<~str, uint>,
+    tags: LinearSet<int>,
+    shelves: TreeMap<int, ~[~str]>,
+    boxed: ~Option<int>,
+    shared: @LinearMap<uint, bool>
+}
+
+fn test_ebml<A: Eq
+                Encodable<EBWriter::Encoder>
+                Decodable<EBReader::Decoder>>(a1: &A) {
+    let bytes = do io::with_bytes_writer |wr| {
+        let ebml_w = &EBWriter::Encoder(wr);
+        a1.encode(ebml_w)
+    };
+    let d = EBReader::Doc(@move bytes);
+    let a2: A = Decodable::decode(&EBReader::Decoder(d));
+    assert *a1 == a2;
+}
+
+fn test_json<A: Eq
+                Encodable<json::Encoder>
+                Decodable<json::Decoder>>(a1: &A) {
+    let s = do io::with_str_writer |wr| {
+        a1.encode(&json::Encoder(wr))
+    };
+    let j = result::unwrap(json::from_str(s));
+    let a2: A = Decodable::decode(&json::Decoder(j));
+    assert *a1 == a2;
+}
+
+fn inventory(owner: Option<~str>, n: uint) -> Inventory {
+    let mut counts = LinearMap::new();
+    let mut tags = LinearSet::new();
+    let mut shelves = TreeMap::new();
+    let mut shared = LinearMap::new();
+    for uint::range(0, n) |i| {
+        counts.insert(fmt!("item%u", i), i * 10);
+        tags.insert(i as int - 2);
+        shelves.insert(i as int, vec::from_elem(i, uint::to_str(i)));
+        shared.insert(i, i % 2 == 0);
+    }
+    Inventory {
+        owner: owner,
+        note: None,
+        counts: counts,
+        tags: tags,
+        shelves: shelves,
+        boxed: ~if n > 0 { Some(n as int) } else { None },
+        shared: @shared
+    }
+}
+
+pub fn main() {
+    for [0u, 1, 5].each |n| {
+        let a = inventory(Some(~"me"), *n);
+        test_ebml(&a);
+        test_json(&a);
+        let b = inventory(None, *n);
+        test_ebml(&b);
+        test_json(&b);
+    }
+
+    let mut m = LinearMap::new();
+    m.insert(~"x", Some(1));
+    m.insert(~"y", None);
+    test_ebml(&m);
+    test_json(&m);
+
+    // Duplicate keys decode deterministically: the last pair wins
+    let pairs = ~[(1, ~"a"), (2, ~"b"), (1, ~"c")];
+    let bytes = do io::with_bytes_writer |wr| {
+        pairs.encode(&EBWriter::Encoder(wr))
+    };
+    let d = EBReader::Doc(@move bytes);
+    let t: TreeMap<int, ~str> = Decodable::decode(&EBReader::Decoder(d));
+    assert t.len() == 2;
+    assert t.find(&1) == Some(&~"c");
+    let s = do io::with_str_writer |wr| { pairs.encode(&json::Encoder(wr)) };
+    let j = result::unwrap(json::from_str(s));
+    let l: LinearMap<int, ~str> = Decodable::decode(&json::Decoder(j));
+    assert l.len() == 2;
+    assert l.find(&1) == Some(&~"c");
+}
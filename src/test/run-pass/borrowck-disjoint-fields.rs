@@ -0,0 +1,93 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Mutable borrows of different fields of the same value can be held at
+// the same time.
+
+struct Point {
+    x: int,
+    y: int
+}
+
+struct Line {
+    from: Point,
+    to: Point
+}
+
+struct Pair(int, int);
+
+fn swap_ints(a: &mut int, b: &mut int) {
+    let t = *a;
+    *a = *b;
+    *b = t;
+}
+
+pub fn main() {
+    let mut p = Point { x: 1, y: 2 };
+    {
+        let a = &mut p.x;
+        let b = &mut p.y;
+        *a += 10;
+        *b += 20;
+        swap_ints(a, b);
+    }
+    assert p.x == 22;
+    assert p.y == 11;
+
+    // assigning to one field while the other is borrowed
+    {
+        let a = &mut p.x;
+        p.y = 5;
+        *a = 6;
+    }
+    assert p.x == 6;
+    assert p.y == 5;
+
+    // fields at different depths
+    let mut l = Line { from: Point { x: 0, y: 0 }, to: Point { x: 1, y: 1 } };
+    {
+        let from = &mut l.from;
+        let to_y = &mut l.to.y;
+        from.x = 3;
+        *to_y = 4;
+    }
+    assert l.from.x == 3;
+    assert l.to.y == 4;
+
+    // through an owned box
+    let mut b = ~Point { x: 1, y: 2 };
+    {
+        let a = &mut b.x;
+        let c = &mut b.y;
+        *a = *c;
+        *c = 7;
+    }
+    assert b.x == 2;
+    assert b.y == 7;
+
+    // tuple elements and anonymous fields
+    let mut t = (1, ~"a");
+    {
+        let (ref mut n, ref mut s) = t;
+        *n += 1;
+        *s += ~"b";
+    }
+    assert t == (2, ~"ab");
+
+    let mut q = Pair(1, 2);
+    {
+        let Pair(ref mut a, ref mut c) = q;
+        swap_ints(a, c);
+    }
+    let Pair(a, c) = q;
+    assert a == 2;
+    assert c == 1;
+}
@@ -0,0 +1,53 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Impls whose type parameters are bounded by the trait they implement
+// let comparison and printing reach through nested containers
+
+struct MyVec<T> {
+    elts: ~[T],
+}
+
+impl<T: ToStr> MyVec<T> : ToStr {
+    pure fn to_str(&self) -> ~str {
+        ~"MyVec" + self.elts.to_str()
+    }
+}
+
+impl<T: Eq> MyVec<T> : Eq {
+    pure fn eq(&self, other: &MyVec<T>) -> bool { self.elts == other.elts }
+    pure fn ne(&self, other: &MyVec<T>) -> bool { !(*self).eq(other) }
+}
+
+fn same<T: Eq>(a: &T, b: &T) -> bool { *a == *b }
+
+pub fn main() {
+    let a = ~[~[1, 2], ~[3]];
+    assert a == ~[~[1, 2], ~[3]];
+    assert a != ~[~[1, 2], ~[4]];
+    assert a < ~[~[1, 3]];
+    assert same(&a, &copy a);
+
+    let o = Some(~[Some(1), None]);
+    assert o == Some(~[Some(1), None]);
+    assert o > Some(~[None, Some(1)]);
+    assert o > None;
+    assert o.to_str() == ~"Some([Some(1), None])";
+
+    let t = (1, ~"two", Some(3), ~[4], (5, 6), 7u);
+    assert t == (1, ~"two", Some(3), ~[4], (5, 6), 7u);
+    assert t < (1, ~"two", Some(3), ~[4], (5, 7), 0u);
+    assert (Some((1, 2, 3, 4)), 5) > (Some((1, 2, 3, 3)), 9);
+
+    let v = MyVec { elts: ~[Some(1), None] };
+    assert v.to_str() == ~"MyVec[Some(1), None]";
+    assert v == MyVec { elts: ~[Some(1), None] };
+    assert ~[copy v] != ~[MyVec { elts: ~[] }];
+}
@@ -0,0 +1,45 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:bounded_impl_xc_aux.rs
+// xfail-fast
+
+// Bounded impls defined in another crate, used at types that crate never
+// instantiated them with
+
+extern mod bounded_impl_xc_aux;
+use bounded_impl_xc_aux::{Pair, both_same};
+
+struct Meters(uint);
+
+impl Meters : Eq {
+    pure fn eq(&self, other: &Meters) -> bool { **self == **other }
+    pure fn ne(&self, other: &Meters) -> bool { **self != **other }
+}
+
+impl Meters : ToStr {
+    pure fn to_str(&self) -> ~str { fmt!("%um", **self) }
+}
+
+pub fn main() {
+    let p = Pair { a: ~[Some(1)], b: ~[Some(1)] };
+    assert p == Pair { a: ~[Some(1)], b: ~[Some(1)] };
+    assert both_same(&p);
+    assert p.to_str() == ~"<[Some(1)], [Some(1)]>";
+
+    let q = Pair { a: Meters(2), b: Meters(3) };
+    assert q != Pair { a: Meters(2), b: Meters(2) };
+    assert !both_same(&q);
+    assert q.to_str() == ~"<2m, 3m>";
+
+    let nested = Pair { a: Pair { a: 1, b: 2 }, b: Pair { a: 1, b: 2 } };
+    assert both_same(&nested);
+    assert ~[copy nested] == ~[nested];
+}
@@ -0,0 +1,29 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// xfail-fast - check-fast doesn't understand aux-build
+// aux-build:cci_inline_private_lib.rs
+
+// An inline method and a generic function from another crate, both of
+// which use a constant private to that crate
+
+extern mod cci_inline_private_lib;
+
+use cci_inline_private_lib::{Counter, scaled_sum};
+
+pub fn main() {
+    let c = Counter(2u);
+    let mut total = 0u;
+    for uint::range(0u, 1000u) |_| { total += c.get(); }
+    assert total == 6000u;
+
+    assert scaled_sum([1u, 2u, 3u], |x| *x) == 18u;
+    assert scaled_sum([~"a", ~"bc"], |s| s.len()) == 9u;
+}
@@ -0,0 +1,32 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --test --cfg cfg_macro_flag
+// xfail-fast
+
+#[test]
+fn test_cfg_test() {
+    assert cfg!(test);
+    assert !cfg!(notest);
+    assert cfg!(not(notest));
+}
+
+#[test]
+fn test_cfg_flag() {
+    assert cfg!(cfg_macro_flag);
+    assert cfg!(no_such_flag, cfg_macro_flag);
+    assert !cfg!(not(cfg_macro_flag));
+}
+
+#[test]
+#[cfg(not(windows))]
+fn test_cfg_attr_not() {
+    assert !cfg!(windows);
+}
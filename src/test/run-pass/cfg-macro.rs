@@ -0,0 +1,45 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::os::consts::{FAMILY, SYSNAME};
+
+fn family() -> &static/str {
+    if cfg!(windows) {
+        "windows"
+    } else if cfg!(unix) {
+        "unix"
+    } else {
+        die!()
+    }
+}
+
+pub fn main() {
+    assert cfg!(notest);
+    assert !cfg!(test);
+
+    assert family() == FAMILY;
+    assert cfg!(unix) != cfg!(windows);
+    assert cfg!(unix, windows);
+    assert cfg!(not(windows)) == !cfg!(windows);
+    assert !cfg!(not(unix, windows));
+
+    assert cfg!(target_os = "linux") == (SYSNAME == "linux");
+    assert cfg!(target_family = "unix") == cfg!(unix);
+    assert cfg!(not(target_os = "no_such_os"));
+
+    // A constant condition, so the dead arm is never run
+    if cfg!(no_such_cfg) {
+        die!(~"cfg!(no_such_cfg) was true");
+    }
+
+    let ran_windows = cfg!(windows) && { assert FAMILY == "windows"; true };
+    let ran_unix = cfg!(not(windows)) && { assert FAMILY == "unix"; true };
+    assert ran_windows != ran_unix;
+}
@@ -0,0 +1,33 @@
+// xfail-fast
+
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[deriving_clone]
+#[deriving_eq]
+enum Shape {
+    Empty,
+    Circle(float),
+    Rect(~str, int, int),
+    Polygon { name: ~str, points: ~[(int, int)] }
+}
+
+pub fn main() {
+    let shapes = ~[
+        Empty,
+        Circle(1.5),
+        Rect(~"r", 2, 3),
+        Polygon { name: ~"tri", points: ~[(0, 0), (1, 0), (0, 1)] }
+    ];
+    for shapes.each |s| {
+        assert s.clone() == *s;
+    }
+    assert shapes.clone() == shapes;
+}
@@ -0,0 +1,71 @@
+// xfail-fast
+
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Derived impls require each type parameter to be Clone, and nest through
+// generic fields, owned boxes and recursive enums
+
+#[deriving(Clone, Eq)]
+struct Wrapper<T> {
+    inner: T,
+    count: uint
+}
+
+#[deriving(Clone, Eq)]
+enum Tree<K, V> {
+    Leaf,
+    Node(~Tree<K, V>, K, V, ~Tree<K, V>)
+}
+
+fn insert<K: Ord, V>(t: Tree<K, V>, k: K, v: V) -> Tree<K, V> {
+    match move t {
+        Leaf => Node(~Leaf, move k, move v, ~Leaf),
+        Node(move l, move nk, move nv, move r) => {
+            if k < nk {
+                Node(~insert(move *l, move k, move v), move nk, move nv,
+                     move r)
+            } else {
+                Node(move l, move nk, move nv,
+                     ~insert(move *r, move k, move v))
+            }
+        }
+    }
+}
+
+#[deriving(Clone)]
+enum List<T> {
+    Nil,
+    Cons(T, ~List<T>)
+}
+
+fn sum(l: &List<int>) -> int {
+    match *l {
+        Nil => 0,
+        Cons(x, ref rest) => x + sum(&**rest)
+    }
+}
+
+pub fn main() {
+    let w = Wrapper { inner: Wrapper { inner: ~[Some(~"a"), None], count: 1 },
+                      count: 2 };
+    assert w.clone() == w;
+
+    let mut t = Leaf;
+    for [5, 2, 8, 1].each |k| {
+        t = insert(move t, *k, int::to_str(*k));
+    }
+    let t2 = t.clone();
+    assert t2 == t;
+    assert insert(move t2, 3, ~"3") != t;
+
+    let l = Cons(1, ~Cons(2, ~Cons(3, ~Nil)));
+    assert sum(&l.clone()) == 6;
+}
@@ -0,0 +1,55 @@
+// xfail-fast
+
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[deriving_clone]
+#[deriving_eq]
+struct Point {
+    x: int,
+    y: int
+}
+
+#[deriving_clone]
+#[deriving_eq]
+struct Named {
+    name: ~str,
+    tags: ~[~str],
+    origin: ~Point
+}
+
+#[deriving_clone]
+#[deriving_eq]
+struct Pair(~str, Option<int>);
+
+#[deriving_clone]
+#[deriving_eq]
+struct Unit;
+
+pub fn main() {
+    let p = Point { x: 1, y: 2 };
+    assert p.clone() == p;
+
+    let a = Named {
+        name: ~"a",
+        tags: ~[~"x", ~"y"],
+        origin: ~Point { x: 3, y: 4 }
+    };
+    let b = a.clone();
+    assert a == b;
+    // The owned fields are deep copies
+    assert ptr::addr_of(&(*a.origin)) != ptr::addr_of(&(*b.origin));
+    assert vec::raw::to_ptr(a.tags) != vec::raw::to_ptr(b.tags);
+
+    let t = Pair(~"pair", Some(5));
+    assert t.clone() == t;
+
+    assert Unit.clone() == Unit;
+}
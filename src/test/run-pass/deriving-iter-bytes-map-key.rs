@@ -0,0 +1,90 @@
+// xfail-fast
+
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::hash::HashUtil;
+use core::hashmap::linear::LinearMap;
+use core::hashmap::linear::LinearSet;
+use core::to_bytes::IterBytes;
+
+#[deriving_eq]
+#[deriving_iter_bytes]
+struct Point {
+    x: int,
+    y: int
+}
+
+#[deriving_eq]
+#[deriving_iter_bytes]
+struct Tagged<T>(~str, T);
+
+#[deriving_eq]
+#[deriving_iter_bytes]
+enum Key {
+    Name(~str),
+    Id(uint),
+    Pos { x: int, y: int },
+    Unknown
+}
+
+fn count_callbacks<T: IterBytes>(value: &T, stop_after: uint) -> uint {
+    let mut calls = 0u;
+    do value.iter_bytes(true) |_bytes| {
+        calls += 1;
+        calls < stop_after
+    }
+    calls
+}
+
+pub fn main() {
+    let mut points = LinearMap::new();
+    for int::range(0, 10) |x| {
+        for int::range(0, 10) |y| {
+            points.insert(Point { x: x, y: y }, x * 10 + y);
+        }
+    }
+    assert points.len() == 100;
+    assert points.find(&Point { x: 3, y: 7 }) == Some(&37);
+    assert points.find(&Point { x: 7, y: 3 }) == Some(&73);
+    assert points.find(&Point { x: 10, y: 0 }).is_none();
+
+    // Structurally different points hash differently
+    let mut hashes = LinearSet::new();
+    for points.each_key |p| {
+        assert hashes.insert(p.hash());
+    }
+
+    let mut tagged = LinearMap::new();
+    tagged.insert(Tagged(~"a", 1u), ~"first");
+    tagged.insert(Tagged(~"b", 1u), ~"second");
+    assert tagged.find(&Tagged(~"a", 1u)) == Some(&~"first");
+    assert tagged.find(&Tagged(~"b", 1u)) == Some(&~"second");
+    assert tagged.find(&Tagged(~"a", 2u)).is_none();
+
+    // The variant is hashed along with its fields
+    let keys = ~[Name(~"x"), Id(1), Id(2), Pos { x: 1, y: 2 },
+                 Pos { x: 2, y: 1 }, Unknown];
+    let mut key_map = LinearMap::new();
+    for keys.eachi |i, k| {
+        key_map.insert(copy *k, i);
+    }
+    for keys.eachi |i, k| {
+        assert key_map.find(k) == Some(&i);
+    }
+    assert Id(1).hash() != Id(2).hash();
+    assert Pos { x: 1, y: 2 }.hash() != Pos { x: 2, y: 1 }.hash();
+
+    // The callback is not called again once it has asked to stop
+    let p = Point { x: 1, y: 2 };
+    assert count_callbacks(&p, uint::max_value) == 2;
+    assert count_callbacks(&p, 1) == 1;
+    assert count_callbacks(&Pos { x: 1, y: 2 }, 2) == 2;
+}
@@ -0,0 +1,66 @@
+// xfail-fast
+
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[deriving_eq]
+#[deriving_ord]
+enum Card {
+    Number(uint),
+    Jack,
+    Queen,
+    King,
+    Joker { color: ~str }
+}
+
+#[deriving(Eq, Ord)]
+enum Tree<T> {
+    Leaf,
+    Node(~Tree<T>, T, ~Tree<T>)
+}
+
+#[deriving(Eq, Ord)]
+struct Pair<A, B> {
+    a: A,
+    b: B
+}
+
+fn node<T>(l: Tree<T>, v: T, r: Tree<T>) -> Tree<T> {
+    Node(~l, v, ~r)
+}
+
+pub fn main() {
+    // Variants are ordered by position, then by their fields
+    let cards = ~[Number(2), Number(10), Jack, Queen, King,
+                  Joker { color: ~"black" }, Joker { color: ~"red" }];
+    for cards.eachi |i, a| {
+        for cards.eachi |j, b| {
+            assert (*a < *b) == (i < j);
+            assert (*a <= *b) == (i <= j);
+            assert (*a > *b) == (i > j);
+            assert (*a >= *b) == (i >= j);
+        }
+    }
+
+    // Recursive types compare through owned boxes
+    let small = node(Leaf, 1, Leaf);
+    let big = node(Leaf, 1, node(Leaf, 2, Leaf));
+    assert Leaf < small;
+    assert small < big;
+    assert node(Leaf, 0, Leaf) < small;
+    assert node(small, 0, Leaf) > node(Leaf, 5, Leaf);
+
+    // Type parameters get Ord bounds
+    let p = Pair { a: ~"x", b: 3 };
+    let q = Pair { a: ~"x", b: 4 };
+    assert p < q && q > p && p <= p && q >= q;
+    assert Pair { a: 1, b: Pair { a: 2, b: 3 } } <
+           Pair { a: 1, b: Pair { a: 2, b: 4 } };
+}
@@ -0,0 +1,66 @@
+// xfail-fast
+
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern mod std;
+
+#[deriving_eq]
+#[deriving_ord]
+struct Person {
+    last: ~str,
+    first: ~str,
+    age: uint
+}
+
+#[deriving_eq]
+#[deriving_ord]
+struct Version(uint, uint, uint);
+
+#[deriving_eq]
+#[deriving_ord]
+struct Nothing;
+
+fn person(last: &str, first: &str, age: uint) -> Person {
+    Person { last: last.to_owned(), first: first.to_owned(), age: age }
+}
+
+pure fn manual_le(a: &Person, b: &Person) -> bool {
+    if a.last != b.last { return a.last < b.last; }
+    if a.first != b.first { return a.first < b.first; }
+    a.age <= b.age
+}
+
+pub fn main() {
+    let people = ~[
+        person("Smith", "Ann", 40), person("Jones", "Bob", 30),
+        person("Smith", "Ann", 25), person("Brown", "Cat", 50),
+        person("Smith", "Al", 60), person("Jones", "Abe", 30)
+    ];
+    let derived = std::sort::merge_sort(people, |a, b| a <= b);
+    let manual = std::sort::merge_sort(people, manual_le);
+    assert derived == manual;
+    assert derived[0] == person("Brown", "Cat", 50);
+    assert derived[5] == person("Smith", "Ann", 40);
+
+    let a = person("Smith", "Ann", 25);
+    let b = person("Smith", "Ann", 40);
+    assert a < b && a <= b && !(a > b) && !(a >= b);
+    assert b > a && b >= a;
+    assert a <= a && a >= a && !(a < a) && !(a > a);
+
+    assert Version(1, 2, 3) < Version(1, 3, 0);
+    assert Version(2, 0, 0) > Version(1, 9, 9);
+    assert Version(1, 2, 3) <= Version(1, 2, 3);
+    assert !(Version(1, 2, 3) < Version(1, 2, 3));
+
+    assert Nothing <= Nothing && Nothing >= Nothing;
+    assert !(Nothing < Nothing) && !(Nothing > Nothing);
+}
@@ -0,0 +1,80 @@
+// xfail-fast
+
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[deriving_to_str]
+struct Point {
+    x: int,
+    y: int
+}
+
+#[deriving_to_str]
+struct Labeled<T> {
+    label: ~str,
+    value: T
+}
+
+#[deriving_to_str]
+struct Meters(uint);
+
+#[deriving_to_str]
+struct Origin;
+
+#[deriving_to_str]
+enum Shape {
+    Empty,
+    Circle(Point, uint),
+    Polygon { name: ~str, points: ~[Point] }
+}
+
+#[deriving(ToStr)]
+enum List<T> {
+    Nil,
+    Cons(T, ~List<T>)
+}
+
+pub fn main() {
+    let p = Point { x: 1, y: -2 };
+    assert p.to_str() == ~"Point { x: 1, y: -2 }";
+
+    let l = Labeled { label: ~"say \"hi\"", value: p };
+    assert l.to_str() ==
+        ~"Labeled { label: \"say \\\"hi\\\"\", \
+          value: Point { x: 1, y: -2 } }";
+    let nested = Labeled { label: ~"outer", value: Labeled { label: ~"in",
+                                                           value: true } };
+    assert nested.to_str() ==
+        ~"Labeled { label: \"outer\", value: Labeled { label: \"in\", \
+          value: true } }";
+
+    assert Meters(5).to_str() == ~"Meters(5)";
+    assert Origin.to_str() == ~"Origin";
+
+    assert Empty.to_str() == ~"Empty";
+    assert Circle(Point { x: 0, y: 0 }, 3).to_str() ==
+        ~"Circle(Point { x: 0, y: 0 }, 3)";
+    let triangle = Polygon {
+        name: ~"tri",
+        points: ~[Point { x: 0, y: 0 }, Point { x: 1, y: 0 }]
+    };
+    assert triangle.to_str() ==
+        ~"Polygon { name: \"tri\", points: \
+          [Point { x: 0, y: 0 }, Point { x: 1, y: 0 }] }";
+
+    let list = Cons(1, ~Cons(2, ~Nil));
+    assert list.to_str() == ~"Cons(1, ~Cons(2, ~Nil))";
+    assert fmt!("%s", list.to_str()) == ~"Cons(1, ~Cons(2, ~Nil))";
+
+    // `%?` writes structs the same way
+    assert fmt!("%?", p) == p.to_str();
+    let circle = Circle(Point { x: 0, y: 0 }, 3);
+    assert fmt!("%?", circle) == circle.to_str();
+}
@@ -1,4 +1,5 @@
-// Just testing that die!() type checks in statement or expr
+// Just testing that die!() type checks in statement or expr, with no
+// arguments, a message, or a format string and its arguments
 
 fn f() {
     die!();
@@ -6,6 +7,19 @@ fn f() {
     let x: int = die!();
 }
 
+fn g() {
+    die!(~"message");
+
+    let x: int = die!(~"message");
+}
+
+fn h(i: uint, len: uint) {
+    die!("bad index %u of %u", i, len);
+
+    let x: int = die!("bad index %u", i);
+    let y: int = fail!("bad index %u of %u (%s)", i, len, "oops");
+}
+
 pub fn main() {
 
 }
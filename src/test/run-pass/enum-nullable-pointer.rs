@@ -0,0 +1,83 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// An enum with a nullary variant and a variant holding one pointer that
+// can't be null uses null as its discriminant, so it is no bigger than the
+// pointer.
+
+enum Either {
+    Ptr(~int),
+    Nothing
+}
+
+struct Counter {
+    count: @mut int
+}
+
+impl Counter : Drop {
+    fn finalize(&self) { *self.count += 1; }
+}
+
+fn get_or<T: Copy>(o: Option<T>, default: T) -> T {
+    match o {
+        Some(x) => x,
+        None => default
+    }
+}
+
+fn describe(e: &Either) -> int {
+    match *e {
+        Ptr(ref p) => **p,
+        Nothing => -1
+    }
+}
+
+pub fn main() {
+    assert sys::size_of::<Option<~int>>() == sys::size_of::<~int>();
+    assert sys::size_of::<Option<@int>>() == sys::size_of::<@int>();
+    assert sys::size_of::<Option<&int>>() == sys::size_of::<&int>();
+    assert sys::size_of::<Option<~str>>() == sys::size_of::<~str>();
+    assert sys::size_of::<Option<~[int]>>() == sys::size_of::<~[int]>();
+    assert sys::size_of::<Either>() == sys::size_of::<~int>();
+    assert sys::size_of::<Option<int>>() > sys::size_of::<int>();
+    assert sys::size_of::<Option<Option<~int>>>() >
+        sys::size_of::<Option<~int>>();
+
+    let x: Option<~int> = Some(~5);
+    let y: Option<~int> = None;
+    assert x.is_some();
+    assert y.is_none();
+    match x {
+        Some(ref p) => assert **p == 5,
+        None => die!()
+    }
+    assert describe(&Ptr(~3)) == 3;
+    assert describe(&Nothing) == -1;
+
+    // A pointer and an int of the same size get different instances
+    let five = 5;
+    assert *get_or(Some(&five), &0) == 5;
+    assert *get_or(None, &0) == 0;
+    assert get_or(Some(5u), 0u) == 5u;
+    assert get_or(None, 7u) == 7u;
+
+    // Drop glue only drops what is there
+    let count = @mut 0;
+    {
+        let _a = Some(~Counter { count: count });
+        let _b: Option<~Counter> = None;
+    }
+    assert *count == 1;
+
+    let mut v = Some(@10);
+    assert fmt!("%?", v) == ~"Some(@10)";
+    v = None;
+    assert fmt!("%?", v) == ~"None";
+}
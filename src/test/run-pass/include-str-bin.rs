@@ -0,0 +1,34 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// xfail-pretty - the pretty tests lose path information, breaking include!
+
+// Paths are relative to this file, not to the directory rustc runs in
+const text: &static/str = include_str!("include-str-bin-files/text.txt");
+const bytes: &static/[u8] = include_bin!("include-str-bin-files/bytes.bin");
+
+mod sub {
+    pub fn text() -> &static/str {
+        include_str!("include-str-bin-files/text.txt")
+    }
+}
+
+pub fn main() {
+    assert text == "Line one\nΛίνε τwo – ünïcödé\n";
+    assert str::char_len(text) == 28;
+    assert sub::text() == text;
+
+    // Not UTF-8, which include_bin! doesn't mind
+    assert bytes == &[0, 1, 2, 0x7f, 0x80, 0xfe, 0xff, 10, 13];
+    assert !str::is_utf8(bytes);
+
+    let bin_text = include_bin!("include-str-bin-files/text.txt");
+    assert str::from_bytes(bin_text) == text.to_owned();
+}
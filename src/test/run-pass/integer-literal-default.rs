@@ -0,0 +1,44 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Numeric literals that nothing constrains default to `int` and `float`;
+// ones that something constrains later take that type
+
+fn takes_uint(x: uint) -> uint { x }
+
+fn show<T: ToStr>(x: T, f: fn(&T) -> ~str) -> ~str { x.to_str() + f(&x) }
+
+pub fn main() {
+    let x = 5;
+    assert takes_uint(x as uint) == 5u;
+
+    let v = ~[1, 2, 3];
+    assert v.len() == 3u;
+    assert v[2] == 3;
+    let a = [0, ..10];
+    assert a[9] == 0;
+
+    assert 1 << 4 == 16;
+    let s = 3;
+    assert 1u8 << s == 8u8;
+    assert -16 >> 2 == -4;
+
+    assert 3 > 2;
+    assert 0x10 == 16;
+    let f = 2.5;
+    assert f * 2.0 == 5.0;
+
+    // The call is resolved early, because of the block argument, but `n`
+    // must not be made an `int` before the `u8` below pins it down
+    let n = 7;
+    assert show(n, |_n| ~"!") == ~"7!";
+    let m: u8 = n;
+    assert m == 7u8;
+}
@@ -0,0 +1,102 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// `break` and `loop` naming an outer `loop`, `while` or `for` leave every
+// scope in between, running the destructors of what was in them.
+
+struct Counter {
+    count: @mut int
+}
+
+impl Counter : Drop {
+    fn finalize(&self) { *self.count += 1; }
+}
+
+fn counter(count: @mut int) -> Counter {
+    Counter { count: count }
+}
+
+fn break_out_of_loops() {
+    let count = @mut 0;
+    let mut reached = 0;
+    'outer: loop {
+        let _a = counter(count);
+        'inner: loop {
+            let _b = counter(count);
+            loop {
+                let _c = counter(count);
+                reached += 1;
+                break 'outer;
+            }
+        }
+    }
+    assert reached == 1;
+    assert *count == 3;
+}
+
+fn continue_outer_while() {
+    let count = @mut 0;
+    let mut i = 0;
+    let mut inner_runs = 0;
+    'rows: while i < 3 {
+        let _a = counter(count);
+        i += 1;
+        let mut j = 0;
+        while j < 10 {
+            let _b = counter(count);
+            j += 1;
+            inner_runs += 1;
+            if j == 2 {
+                loop 'rows;
+            }
+        }
+        die!(~"skipped by `loop 'rows`");
+    }
+    assert i == 3;
+    assert inner_runs == 6;
+    assert *count == 9;
+}
+
+fn break_out_of_for() {
+    let count = @mut 0;
+    let mut last = 0u;
+    'each: for uint::range(0u, 10u) |i| {
+        let _a = counter(count);
+        last = i;
+        loop {
+            let _b = counter(count);
+            if i == 4u {
+                break 'each;
+            }
+            loop 'each;
+        }
+    }
+    assert last == 4u;
+    assert *count == 10;
+}
+
+fn old_syntax() {
+    let mut x = 0;
+    loop foo: {
+        loop {
+            x += 1;
+            break foo;
+        }
+    }
+    assert x == 1;
+}
+
+pub fn main() {
+    break_out_of_loops();
+    continue_outer_while();
+    break_out_of_for();
+    old_syntax();
+}
@@ -0,0 +1,40 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// xfail-fast
+// aux-build:macro_export_lib.rs
+
+extern mod macro_export_lib;
+
+constants!(consts: one = 1; two = 2)
+
+fn sum_of_squares(a: int, b: int) -> int {
+    square!(a) + square!(b)
+}
+
+pub fn main() {
+    assert square!(3) == 9;
+    assert square!(1 + 1) == 4;
+    assert sum_of_squares(3, 4) == 25;
+
+    assert greet!("world", "rust") ==
+        ~[~"hello, world", ~"hello, rust"];
+
+    let (s, c, b, f, t) = literals!();
+    assert s == ~"a \"quoted\"\n string";
+    assert c == 'x';
+    assert b == 16u8;
+    assert f == 1.5f;
+    assert t;
+
+    assert consts::one() + consts::two() == 3;
+
+    assert macro_export_lib::four() == 4;
+}
@@ -0,0 +1,66 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// xfail-pretty - token trees can't pretty print
+
+// Each record's fields are bound inside two repetitions, and are expanded
+// into the struct, its constructor and the list of its field names
+macro_rules! records(
+    ($m:ident: $( $name:ident { $( $field:ident : $ty:ty ),+ } );*) => (
+        mod $m {
+            $(
+                pub struct $name { $( $field: $ty ),+ }
+
+                pub impl $name {
+                    static fn new($( $field: $ty ),+) -> $name {
+                        $name { $( $field: $field ),+ }
+                    }
+
+                    static fn fields() -> ~[&static/str] {
+                        ~[ $( stringify!($field) ),+ ]
+                    }
+                }
+            )*
+        }
+    )
+)
+
+records!(shapes:
+    Point { x: int, y: int };
+    Named { name: ~str, id: uint, tags: ~[~str] }
+)
+
+// Separators on both levels of the expansion
+macro_rules! sums(
+    ($( [ $( $e:expr ),* ] ),+) => (
+        ~[ $( 0 $( + $e )* ),+ ]
+    )
+)
+
+macro_rules! nested_vecs(
+    ($( $( $e:expr ),+ );+) => (
+        ~[ $( ~[ $( $e ),+ ] ),+ ]
+    )
+)
+
+pub fn main() {
+    let p = shapes::Point::new(1, 2);
+    assert p.x == 1 && p.y == 2;
+    assert shapes::Point::fields() == ~["x", "y"];
+
+    let n = shapes::Named::new(~"n", 3u, ~[~"a", ~"b"]);
+    assert n.name == ~"n";
+    assert n.id == 3u;
+    assert n.tags == ~[~"a", ~"b"];
+    assert shapes::Named::fields() == ~["name", "id", "tags"];
+
+    assert sums!([1, 2, 3], [], [10]) == ~[6, 0, 10];
+    assert nested_vecs!(1, 2; 3; 4, 5, 6) == ~[~[1, 2], ~[3], ~[4, 5, 6]];
+}
@@ -0,0 +1,74 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Destructuring an owned box moves the fields out of it without copying
+// them, so each field is dropped exactly once, wherever it ends up.
+
+struct Counted {
+    count: @mut int
+}
+
+impl Counted : Drop {
+    fn finalize(&self) { *self.count += 1; }
+}
+
+struct Pair {
+    a: Counted,
+    b: ~Counted
+}
+
+fn make(a_drops: @mut int, b_drops: @mut int) -> ~Pair {
+    ~Pair { a: Counted { count: a_drops }, b: ~Counted { count: b_drops } }
+}
+
+fn unwrap(p: ~Pair) -> (Counted, ~Counted) {
+    let ~Pair { a: a, b: b } = p;
+    (a, b)
+}
+
+pub fn main() {
+    let a_drops = @mut 0;
+    let b_drops = @mut 0;
+
+    // every field moved on out of the function
+    {
+        let (_a, _b) = unwrap(make(a_drops, b_drops));
+        assert *a_drops == 0;
+        assert *b_drops == 0;
+    }
+    assert *a_drops == 1;
+    assert *b_drops == 1;
+
+    // one field moved on, the other dropped with the bindings
+    {
+        let p = make(a_drops, b_drops);
+        let ~Pair { a: a, b: _b } = p;
+        let moved = ~[a];
+        assert *a_drops == 1;
+        assert moved.len() == 1;
+    }
+    assert *a_drops == 2;
+    assert *b_drops == 2;
+
+    // the same through a match
+    match make(a_drops, b_drops) {
+        ~Pair { a: a, b: b } => {
+            let _b = b;
+            {
+                let _a = a;
+            }
+            assert *a_drops == 3;
+            assert *b_drops == 2;
+        }
+    }
+    assert *a_drops == 3;
+    assert *b_drops == 3;
+}
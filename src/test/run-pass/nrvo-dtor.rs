@@ -0,0 +1,130 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// A function whose value is one of its locals builds that local in the
+// caller's slot. It must still be dropped exactly once, and only by the
+// caller.
+
+struct Counter {
+    count: @mut int,
+    val: int
+}
+
+impl Counter : Drop {
+    fn finalize(&self) { *self.count += 1; }
+}
+
+fn make(count: @mut int, val: int) -> Counter {
+    let c = Counter { count: count, val: val };
+    c
+}
+
+fn make_mut(count: @mut int) -> Counter {
+    let mut c = Counter { count: count, val: 1 };
+    c.val += 1;
+    c = Counter { count: count, val: c.val * 10 };
+    c
+}
+
+// Built in place: the local is where the caller asked for the result
+fn make_at(count: @mut int, addr: @mut uint) -> Counter {
+    let c = Counter { count: count, val: 3 };
+    *addr = ptr::to_unsafe_ptr(&c) as uint;
+    c
+}
+
+// With an early return, which local is returned isn't known until run time
+fn pick(count: @mut int, first: bool) -> Counter {
+    let a = Counter { count: count, val: 1 };
+    let b = Counter { count: count, val: 2 };
+    if first { return a; }
+    b
+}
+
+fn make_literal(count: @mut int) -> Counter {
+    Counter { count: count, val: 4 }
+}
+
+// Failing after the local is built in the caller's slot must drop it once:
+// the caller never got a value, so only the callee's cleanup runs
+struct SharedCounter {
+    count: private::Exclusive<int>,
+    val: int
+}
+
+impl SharedCounter : Drop {
+    fn finalize(&self) {
+        unsafe { do self.count.with |count| { *count += 1; } }
+    }
+}
+
+fn make_then_fail(count: private::Exclusive<int>) -> SharedCounter {
+    let c = SharedCounter { count: count, val: 5 };
+    if c.val == 5 { die!(~"after building the result"); }
+    c
+}
+
+pub fn main() {
+    let count = @mut 0;
+    {
+        let c = make(count, 7);
+        assert *count == 0;
+        assert c.val == 7;
+    }
+    assert *count == 1;
+
+    *count = 0;
+    {
+        let c = make_mut(count);
+        // only the value that was overwritten has gone
+        assert *count == 1;
+        assert c.val == 20;
+    }
+    assert *count == 2;
+
+    *count = 0;
+    let addr = @mut 0u;
+    {
+        let c = make_at(count, addr);
+        assert *addr == ptr::to_unsafe_ptr(&c) as uint;
+        assert c.val == 3;
+        assert *count == 0;
+    }
+    assert *count == 1;
+
+    *count = 0;
+    {
+        let a = pick(count, true);
+        assert *count == 1;
+        assert a.val == 1;
+        let b = pick(count, false);
+        assert *count == 2;
+        assert b.val == 2;
+    }
+    assert *count == 4;
+
+    *count = 0;
+    {
+        let c = make_literal(count);
+        assert *count == 0;
+        assert c.val == 4;
+    }
+    assert *count == 1;
+
+    let shared = private::exclusive(0);
+    let shared2 = shared.clone();
+    let result = do task::try |move shared2| {
+        let c = make_then_fail(shared2.clone());
+        c.val
+    };
+    assert result.is_err();
+    unsafe { do shared.with |count| { assert *count == 1; } }
+}
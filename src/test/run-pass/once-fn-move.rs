@@ -0,0 +1,43 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// A once closure may move out of the variables it closes over.
+
+fn apply(f: &once fn() -> ~str) -> ~str { f() }
+
+fn apply_owned(f: ~once fn() -> ~str) -> ~str { f() }
+
+pub fn main() {
+    let s = ~"hello";
+    assert apply(|| s) == ~"hello";
+
+    let t = ~"world";
+    assert apply_owned(|| t) == ~"world";
+
+    let pair = (~"a", ~"b");
+    let joined = do apply {
+        let (a, b) = pair;
+        a + b
+    };
+    assert joined == ~"ab";
+
+    let u = ~"once";
+    let f: ~once fn() -> ~str = || u;
+    assert f() == ~"once";
+
+    // Moving into a spawned task without wrapping in an Option
+    let (port, chan) = pipes::oneshot();
+    let msg = ~"sent";
+    do task::spawn_once {
+        pipes::send_one(chan, msg);
+    }
+    assert pipes::recv_one(port) == ~"sent";
+}
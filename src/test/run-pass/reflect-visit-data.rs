@@ -310,6 +310,11 @@ impl<V: TyVisitor movable_ptr> ptr_visit_adaptor<V>: TyVisitor {
         true
     }
 
+    fn visit_class_name(&self, name: &str) -> bool {
+        if ! self.inner.visit_class_name(name) { return false; }
+        true
+    }
+
     fn visit_enter_class(&self, n_fields: uint, sz: uint, align: uint)
                       -> bool {
         self.align(align);
@@ -384,6 +389,11 @@ impl<V: TyVisitor movable_ptr> ptr_visit_adaptor<V>: TyVisitor {
         true
     }
 
+    fn visit_enum_null_discrim(&self, disr_val: int) -> bool {
+        if ! self.inner.visit_enum_null_discrim(disr_val) { return false; }
+        true
+    }
+
     fn visit_enter_enum_variant(&self, variant: uint,
                                 disr_val: int,
                                 n_fields: uint,
@@ -568,6 +578,7 @@ impl my_visitor: TyVisitor {
     fn visit_leave_rec(&self, _n_fields: uint,
                        _sz: uint, _align: uint) -> bool { true }
 
+    fn visit_class_name(&self, _name: &str) -> bool { true }
     fn visit_enter_class(&self, _n_fields: uint,
                          _sz: uint, _align: uint) -> bool { true }
     fn visit_class_field(&self, _i: uint, _name: &str,
@@ -591,6 +602,7 @@ impl my_visitor: TyVisitor {
         // FIXME (#3732): this needs to rewind between enum variants, or something.
         true
     }
+    fn visit_enum_null_discrim(&self, _disr_val: int) -> bool { true }
     fn visit_enter_enum_variant(&self, _variant: uint,
                                 _disr_val: int,
                                 _n_fields: uint,
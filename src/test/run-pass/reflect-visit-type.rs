@@ -90,6 +90,7 @@ impl my_visitor: TyVisitor {
     fn visit_leave_rec(_n_fields: uint,
                        _sz: uint, _align: uint) -> bool { true }
 
+    fn visit_class_name(_name: &str) -> bool { true }
     fn visit_enter_class(_n_fields: uint,
                          _sz: uint, _align: uint) -> bool { true }
     fn visit_class_field(_i: uint, _name: &str,
@@ -105,6 +106,7 @@ impl my_visitor: TyVisitor {
 
     fn visit_enter_enum(_n_variants: uint,
                         _sz: uint, _align: uint) -> bool { true }
+    fn visit_enum_null_discrim(_disr_val: int) -> bool { true }
     fn visit_enter_enum_variant(_variant: uint,
                                 _disr_val: int,
                                 _n_fields: uint,
@@ -0,0 +1,47 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// `Self` as an argument type, including in a default method
+
+trait Add2 {
+    fn add2(&self, other: &Self) -> Self;
+    fn double(&self) -> Self { self.add2(self) }
+}
+
+struct V { x: int, y: int }
+
+impl V : Add2 {
+    fn add2(&self, other: &V) -> V {
+        V { x: self.x + other.x, y: self.y + other.y }
+    }
+}
+
+impl ~str : Add2 {
+    fn add2(&self, other: &~str) -> ~str { *self + *other }
+}
+
+fn sum<T: Add2 Copy>(xs: &[T], zero: T) -> T {
+    let mut acc = zero;
+    for xs.each |x| { acc = acc.add2(x); }
+    acc
+}
+
+pub fn main() {
+    let v = V { x: 1, y: 2 }.add2(&V { x: 10, y: 20 });
+    assert v.x == 11 && v.y == 22;
+    let d = v.double();
+    assert d.x == 22 && d.y == 44;
+
+    assert (~"ab").add2(&~"cd") == ~"abcd";
+    assert (~"ab").double() == ~"abab";
+
+    let total = sum([V { x: 1, y: 1 }, V { x: 2, y: 3 }], V { x: 0, y: 0 });
+    assert total.x == 3 && total.y == 4;
+}
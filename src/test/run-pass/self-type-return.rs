@@ -0,0 +1,58 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// `Self` as a return type
+
+trait Clone2 {
+    fn clone2(&self) -> Self;
+}
+
+trait Builder {
+    static fn empty() -> Self;
+    fn with(&self, x: int) -> Self;
+}
+
+struct Point { x: int, y: int }
+
+impl Point : Clone2 {
+    fn clone2(&self) -> Point { Point { x: self.x, y: self.y } }
+}
+
+impl int : Clone2 {
+    fn clone2(&self) -> int { *self }
+}
+
+struct Stack { items: ~[int] }
+
+impl Stack : Builder {
+    static fn empty() -> Stack { Stack { items: ~[] } }
+    fn with(&self, x: int) -> Stack { Stack { items: self.items + ~[x] } }
+}
+
+fn twice<T: Clone2>(t: &T) -> (T, T) { (t.clone2(), t.clone2()) }
+
+fn build<T: Builder>() -> T {
+    let b: T = Builder::empty();
+    b.with(1).with(2)
+}
+
+pub fn main() {
+    let p = Point { x: 1, y: 2 };
+    let q = p.clone2();
+    assert q.x == 1 && q.y == 2;
+
+    let (a, b) = twice(&7);
+    assert a == 7 && b == 7;
+    let (c, _) = twice(&p);
+    assert c.y == 2;
+
+    let s: Stack = build();
+    assert s.items == ~[1, 2];
+}
@@ -15,8 +15,8 @@ use io::WriterUtil;
 use std::tempfile;
 
 pub fn main() {
-    let dir = option::unwrap(tempfile::mkdtemp(&Path("."), ""));
-    let path = dir.with_filename("file");
+    let dir = option::unwrap(tempfile::mkdtemp(&Path("stat")));
+    let path = dir.push("file");
 
     {
         match io::file_writer(&path, [io::Create, io::Truncate]) {
@@ -32,5 +32,5 @@ pub fn main() {
     assert path.exists();
     assert path.get_size() == Some(1000);
 
-    os::remove_dir(&dir);
+    assert os::remove_dir_recursive(&dir);
 }
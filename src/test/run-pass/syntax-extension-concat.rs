@@ -0,0 +1,65 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// xfail-pretty - token trees can't pretty print
+
+// stringify!, concat! and concat_idents!, both directly and on fragments
+// that a macro_rules! macro matched
+
+macro_rules! describe(
+    ($e:expr) => ((stringify!($e), $e))
+)
+
+macro_rules! show_ty(
+    ($t:ty) => (stringify!($t))
+)
+
+macro_rules! getter(
+    ($field:ident) => (concat_idents!(get_, $field))
+)
+
+macro_rules! version(
+    ($major:expr, $minor:expr) => (concat!("v", $major, '.', $minor))
+)
+
+macro_rules! labels(
+    ($( $name:expr => $n:expr ),*) => (
+        ~[ $( concat!($name, ':', $n) ),* ]
+    )
+)
+
+fn get_width() -> uint { 10 }
+
+pub fn main() {
+    let s: &static/str = stringify!(a + b);
+    assert s == "a + b";
+    assert stringify!() == "";
+    assert stringify!("quoted") == "\"quoted\"";
+
+    assert concat!() == "";
+    assert concat!("a", "b", 1) == "ab1";
+    assert concat!('x', 'y', 42u, 7i8, "z") == "xy427z";
+    let c: &static/str = concat!("a", 'é');
+    assert c == "aé";
+
+    // Interpolated fragments are stringified as what was matched
+    assert describe!(1 + 2) == ("1 + 2", 3);
+    assert describe!("s") == ("\"s\"", "s");
+    assert show_ty!(~[int]) == "~[int]";
+    assert show_ty!(Option<uint>) == "Option<uint>";
+
+    let x_y = 5;
+    assert concat_idents!(x, _y) == 5;
+    assert concat_idents!(x_, y) + 1 == 6;
+    assert getter!(width)() == 10;
+
+    assert version!(0, 6) == "v0.6";
+    assert labels!("a" => 1, "bc" => 23) == ~["a:1", "bc:23"];
+}
@@ -32,6 +32,7 @@ pub fn main() {
     part6();
     percent();
     more_floats();
+    star_counts();
 }
 
 fn part1() {
@@ -256,7 +257,45 @@ fn percent() {
 fn more_floats() {
     assert ~"3.1416"      == fmt!("%.4f", 3.14159);
     assert ~"3"           == fmt!("%.0f", 3.14159);
-    assert ~"99"          == fmt!("%.0f", 98.5);
+    assert ~"99"          == fmt!("%.0f", 98.51);
     assert ~"7.0000"      == fmt!("%.4f", 6.999999999);
     assert ~"3.141590000" == fmt!("%.9f", 3.14159);
+
+    // Values exactly halfway between two results round to even
+    assert ~"98"          == fmt!("%.0f", 98.5);
+    assert ~"100"         == fmt!("%.0f", 99.5);
+    assert ~"0.12"        == fmt!("%.2f", 0.125);
+    assert ~"0.38"        == fmt!("%.2f", 0.375);
+}
+
+fn star_counts() {
+    // A '*' width or precision comes from a uint argument placed before
+    // the value, the width first when both are given
+    test(fmt!("%*d", 5u, 42), ~"   42");
+    test(fmt!("%-*d|", 5u, 42), ~"42   |");
+    test(fmt!("%0*d", 5u, -42), ~"-0042");
+    test(fmt!("%*s|%*s", 4u, "ab", 1u, "cd"), ~"  ab|cd");
+    test(fmt!("%.*f", 2u, 3.14159), ~"3.14");
+    test(fmt!("%.*s", 2u, "abc"), ~"ab");
+    test(fmt!("%*.*f", 8u, 3u, 3.14159), ~"   3.142");
+    test(fmt!("%-*.*x|", 6u, 4u, 255u), ~"00ff  |");
+
+    // Zero widths and precisions
+    test(fmt!("%*d", 0u, 42), ~"42");
+    test(fmt!("%*s", 0u, ""), ~"");
+    test(fmt!("%.*f", 0u, 3.14159), ~"3");
+    test(fmt!("%.*s", 0u, "abc"), ~"");
+    test(fmt!("%*.*s|", 0u, 0u, "abc"), ~"|");
+
+    // A width narrower than the value does not truncate it
+    test(fmt!("%*d", 2u, -12345), ~"-12345");
+
+    // Counts computed at runtime, mixed with other conversions. A
+    // negative count can't be given since it must be a uint; left
+    // justification is the '-' flag.
+    let width = str::len("width") * 2;
+    let prec = width / 5;
+    test(fmt!("%s %*.*f %s", "[", width, prec, 1.5, "]"),
+         ~"[       1.50 ]");
+    test(fmt!("%-*s%d", width, "x", 1), ~"x         1");
 }
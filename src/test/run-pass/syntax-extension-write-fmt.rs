@@ -0,0 +1,52 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// write_fmt! must write exactly what fmt! would have returned
+
+macro_rules! check(
+    ($fmt:expr $(, $arg:expr)*) => ({
+        let written = do io::with_str_writer |wr| {
+            write_fmt!(wr, $fmt $(, $arg)*)
+        };
+        let expected = fmt!($fmt $(, $arg)*);
+        log(debug, copy written);
+        assert written == expected;
+    })
+)
+
+pub fn main() {
+    check!("");
+    check!("test");
+    check!("hello %d friends and %s things", 10, ~"formatted");
+    check!("%s%s%s", "a", ~"b", @"c");
+    check!("[%5s] [%-5s] [%.2s]", "abc", "abc", "abc");
+    check!("%d %i %u", -12, 7, 10u);
+    check!("%+d % d %05d %-5d|", 3, 3, -3, 3);
+    check!("%x %X %o %t", 0xff_u, 0x12ab_u, 10u, 0b1101_u);
+    check!("%#x %#o %08.3x", 255u, 8u, 15u);
+    check!("%f %.3f %10.2f", 5.82, 1.0, -2.5);
+    check!("%b %c %c", true, 'A', 'ü');
+    check!("%? %?", ~[1, 2], (1, ~"two"));
+    check!("%%d %d%%", 5);
+
+    // The writer expression is evaluated once
+    let mut count = 0;
+    let out = io::with_str_writer(|wr| {
+        write_fmt!({ count += 1; wr }, "%d %d %d", 1, 2, 3)
+    });
+    assert out == ~"1 2 3";
+    assert count == 1;
+
+    // Pieces reach the writer in order across several calls
+    let out = do io::with_str_writer |wr| {
+        for uint::range(0, 3) |i| { write_fmt!(wr, "%u,", i); }
+    };
+    assert out == ~"0,1,2,";
+}
@@ -0,0 +1,48 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// An impl may take a trait's default method or supply its own
+
+trait Equal {
+    fn eq(&self, other: &Self) -> bool;
+    fn ne(&self, other: &Self) -> bool { !self.eq(other) }
+}
+
+trait Ordered : Equal {
+    fn lt(&self, other: &Self) -> bool;
+    fn ge(&self, other: &Self) -> bool { !self.lt(other) }
+}
+
+struct A { x: int }
+
+impl A : Equal {
+    fn eq(&self, other: &A) -> bool { self.x == other.x }
+}
+
+impl A : Ordered {
+    fn lt(&self, other: &A) -> bool { self.x < other.x }
+    // overrides the default, which would give the opposite answer
+    fn ge(&self, other: &A) -> bool { self.x <= other.x }
+}
+
+fn not_equal<T: Equal>(a: &T, b: &T) -> bool { a.ne(b) }
+
+fn at_least<T: Ordered>(a: &T, b: &T) -> bool { a.ge(b) }
+
+pub fn main() {
+    let (one, two) = (A { x: 1 }, A { x: 2 });
+    assert one.ne(&two);
+    assert !one.ne(&one);
+    assert not_equal(&one, &two);
+
+    assert one.ge(&two);
+    assert !two.ge(&one);
+    assert at_least(&one, &two);
+}
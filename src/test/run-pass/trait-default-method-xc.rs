@@ -0,0 +1,46 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:trait_default_method_xc_aux.rs
+// xfail-fast
+
+extern mod trait_default_method_xc_aux;
+use trait_default_method_xc_aux::{Describe, Widget};
+
+struct Gadget;
+
+// takes `describe` from the upstream trait and overrides `count`
+impl Gadget : Describe {
+    fn name(&self) -> ~str { ~"gadget" }
+    fn count(&self) -> int { 2 }
+}
+
+struct Gizmo;
+
+// takes both defaults
+impl Gizmo : Describe {
+    fn name(&self) -> ~str { ~"gizmo" }
+}
+
+fn summary<T: Describe>(t: &T) -> ~str {
+    fmt!("%s x%d", t.describe(), t.count())
+}
+
+pub fn main() {
+    let w = Widget { n: 3 };
+    assert w.describe() == ~"a widget";
+    assert summary(&w) == ~"a widget x3";
+
+    assert Gadget.describe() == ~"a gadget";
+    assert summary(&Gadget) == ~"a gadget x2";
+
+    assert Gizmo.count() == 1;
+    assert summary(&Gizmo) == ~"a gizmo x1";
+}
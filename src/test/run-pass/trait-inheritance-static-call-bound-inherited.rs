@@ -0,0 +1,36 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+trait Foo { static fn f() -> Self; }
+trait Bar : Foo { fn g(&self) -> int; }
+trait Baz : Bar { }
+
+struct A { x: int }
+
+impl A : Foo { static fn f() -> A { A { x: 10 } } }
+impl A : Bar { fn g(&self) -> int { self.x * 2 } }
+impl A : Baz { }
+
+// Call a static function on Foo, given a T: Bar
+fn gg<T:Bar>() -> T {
+    Bar::f()
+}
+
+// And through two levels of inheritance
+fn hh<T:Baz>() -> int {
+    let t: T = Baz::f();
+    t.g()
+}
+
+pub fn main() {
+    let a: A = gg();
+    assert a.x == 10;
+    assert hh::<A>() == 20;
+}
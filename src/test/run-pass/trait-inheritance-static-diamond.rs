@@ -0,0 +1,35 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// B and C both inherit Base, so D reaches it twice. That is not ambiguous.
+
+trait Base { static fn make(x: int) -> Self; fn get(&self) -> int; }
+trait B : Base { }
+trait C : Base { }
+trait D : B C { }
+
+struct S { x: int }
+
+impl S : Base {
+    static fn make(x: int) -> S { S { x: x } }
+    fn get(&self) -> int { self.x }
+}
+impl S : B { }
+impl S : C { }
+impl S : D { }
+
+fn make_d<T:D>(x: int) -> T { D::make(x) }
+
+pub fn main() {
+    let s: S = make_d(3);
+    assert s.get() == 3;
+    let t: S = B::make(4);
+    assert t.get() == 4;
+}
@@ -0,0 +1,40 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:trait_inheritance_static_xc_aux.rs
+// xfail-fast
+
+extern mod trait_inheritance_static_xc_aux;
+use trait_inheritance_static_xc_aux::{Num, NumExt};
+
+// A local subtrait of an upstream subtrait
+trait LocalExt : NumExt { }
+
+struct S { v: int }
+
+impl S : Num {
+    static fn from_int(i: int) -> S { S { v: i } }
+    fn to_int(&self) -> int { self.v }
+}
+impl S : NumExt { }
+impl S : LocalExt { }
+
+fn one<T:NumExt>() -> T { NumExt::from_int(1) }
+
+fn two<T:LocalExt>() -> T { LocalExt::from_int(2) }
+
+pub fn main() {
+    let a: S = one();
+    assert a.to_int() == 1;
+    let b: S = two();
+    assert b.to_int() == 2;
+    let c: S = trait_inheritance_static_xc_aux::NumExt::from_int(3);
+    assert c.to_int() == 3;
+}
@@ -0,0 +1,54 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A ~Trait:Owned object can be sent to another task, which calls its
+// methods and drops it there
+
+// xfail-fast
+
+trait Shape {
+    fn area(&self) -> float;
+}
+
+struct Square {
+    side: float,
+}
+
+impl Square : Shape {
+    fn area(&self) -> float { self.side * self.side }
+}
+
+struct Rect {
+    sides: ~[float],
+}
+
+impl Rect : Shape {
+    fn area(&self) -> float { self.sides[0] * self.sides[1] }
+}
+
+pub fn main() {
+    let (p, c) = pipes::stream();
+    let (p2, c2) = pipes::stream();
+    do task::spawn |move p, move c2| {
+        let mut total = 0.0;
+        loop {
+            let shape: Option<~Shape:Owned> = p.recv();
+            match move shape {
+                Some(move shape) => total += shape.area(),
+                None => break
+            }
+        }
+        c2.send(total);
+    }
+    c.send(Some(~Square { side: 2.0 } as ~Shape:Owned));
+    c.send(Some(~Rect { sides: ~[3.0, 5.0] } as ~Shape:Owned));
+    c.send(None);
+    assert p2.recv() == 19.0;
+}
@@ -0,0 +1,92 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Vector patterns with a `..rest` binding, over owned, borrowed and
+// fixed-length vectors
+
+enum Token {
+    Num(int),
+    Plus,
+    Minus
+}
+
+// Sums an expression like `1 + 2 - 3`, stopping at the first malformed
+// token
+fn eval(tokens: &[Token]) -> Option<int> {
+    match tokens {
+        [] => None,
+        [Num(n)] => Some(n),
+        [Num(n), Plus, ..rest] => eval(rest).map(|m| n + *m),
+        [Num(n), Minus, ..rest] => match rest {
+            [Num(m), ..more] => {
+                let mut v = ~[Num(n - m)];
+                v.push_all(more);
+                eval(v)
+            }
+            _ => None
+        },
+        [_, .._] => None
+    }
+}
+
+fn count_owned(v: ~[~str]) -> uint {
+    match v {
+        [] => 0u,
+        [ref first, ..rest] => {
+            assert first.len() == 1u;
+            1u + rest.len()
+        }
+    }
+}
+
+fn last_of(v: &[int]) -> Option<int> {
+    match v {
+        [] => None,
+        [x] => Some(x),
+        [_, ..rest] => last_of(rest)
+    }
+}
+
+pub fn main() {
+    assert eval([Num(1), Plus, Num(2)]) == Some(3);
+    assert eval([Num(10), Minus, Num(3), Plus, Num(4)]) == Some(11);
+    assert eval([Num(1), Plus]) == None;
+    assert eval([Plus, Num(1)]) == None;
+    assert eval([]) == None;
+
+    assert count_owned(~[~"a", ~"b", ~"c"]) == 3u;
+    assert count_owned(~[]) == 0u;
+
+    assert last_of([1, 2, 3]) == Some(3);
+    assert last_of(~[4]) == Some(4);
+    assert last_of(@[]) == None;
+
+    // The length of a fixed-length vector is known, so this is exhaustive
+    let fixed: [int * 3] = [1, 2, 3];
+    let sum = match fixed {
+        [a, b, c] => a + b + c
+    };
+    assert sum == 6;
+    match fixed {
+        [1, ..rest] => assert rest == [2, 3],
+        [_, _, _] => die!()
+    }
+
+    // `rest` borrows from the vector it was matched against
+    let owned = ~[5, 6, 7];
+    match owned {
+        [_, ..rest] => {
+            assert ptr::to_unsafe_ptr(&rest[0]) ==
+                ptr::to_unsafe_ptr(&owned[1]);
+        }
+        [] => die!()
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Every weak task must be told about shutdown. Each of these waits for the
+// other to acknowledge the notification, so if either one isn't notified
+// the runtime hangs instead of exiting. Afterwards each weakens itself a
+// second time, which must be notified straight away.
+
+use core::pipes::{stream, Chan, Port, SharedChan};
+use core::private::weak_task::weaken_task;
+
+fn spawn_service(ready: SharedChan<()>, ack: Chan<()>, other_ack: Port<()>) {
+    do task::spawn_unlinked |move ready, move ack, move other_ack| {
+        unsafe {
+            do weaken_task |signal| {
+                ready.send(());
+                signal.recv();
+                ack.send(());
+                other_ack.recv();
+            }
+            do weaken_task |signal| {
+                signal.recv();
+            }
+        }
+    }
+}
+
+pub fn main() {
+    let (ready_po, ready_ch) = stream();
+    let ready_ch = SharedChan(ready_ch);
+    let (a_po, a_ch) = stream();
+    let (b_po, b_ch) = stream();
+    spawn_service(ready_ch.clone(), move a_ch, move b_po);
+    spawn_service(ready_ch.clone(), move b_ch, move a_po);
+    ready_po.recv();
+    ready_po.recv();
+}